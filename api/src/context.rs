@@ -102,6 +102,14 @@ impl Context {
         self.node_config.api.max_account_modules_page_size
     }
 
+    pub fn max_state_changes_page_size(&self) -> u16 {
+        self.node_config.api.max_state_changes_page_size
+    }
+
+    pub fn max_view_function_gas(&self) -> u64 {
+        self.node_config.api.max_view_function_gas
+    }
+
     pub fn move_resolver(&self) -> Result<StorageAdapterOwned<DbStateView>> {
         self.db
             .latest_state_checkpoint_view()
@@ -8,7 +8,8 @@ use crate::failpoint::fail_point_poem;
 use crate::page::Page;
 use crate::response::BadRequestError;
 use crate::response::{
-    BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404, InternalError,
+    version_pruned, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
+    InternalError,
 };
 use crate::ApiTags;
 use anyhow::Context as AnyhowContext;
@@ -16,9 +17,12 @@ use aptos_api_types::{
     verify_field_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
     MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
 };
+use aptos_types::contract_event::EventWithVersion;
 use aptos_types::event::EventKey;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::param::Query;
 use poem_openapi::{param::Path, OpenApi};
+use std::convert::TryInto;
 use std::sync::Arc;
 
 pub struct EventsApi {
@@ -135,6 +139,54 @@ impl EventsApi {
         let key = account.find_event_key(event_handle.0, field_name.0.into())?;
         self.list(account.latest_ledger_info, accept_type, page, key)
     }
+
+    /// Get events by event type
+    ///
+    /// Returns events of the given type emitted by any account, in the order
+    /// they were committed, starting from the transaction at version `start`.
+    /// All events emitted by the last transaction in a page are returned, so a
+    /// page may hold more than `limit` events. To get the next page, use the
+    /// version after the one of the last returned event as `start`.
+    #[oai(
+        path = "/events/by_type/:event_type",
+        method = "get",
+        operation_id = "get_events_by_type",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_type(
+        &self,
+        accept_type: AcceptType,
+        /// Fully qualified type of the events e.g. `0x1::coin::DepositEvent`
+        event_type: Path<MoveStructTag>,
+        /// Ledger version to start looking for events from.
+        ///
+        /// If unspecified, defaults to the oldest version available on the node
+        start: Query<Option<U64>>,
+        /// Max number of events to retrieve.
+        ///
+        /// If unspecified, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let event_type = event_type
+            .0
+            .verify(0)
+            .and_then(|_| event_type.0.try_into())
+            .map(|struct_tag: StructTag| TypeTag::Struct(Box::new(struct_tag)))
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_events_by_type")?;
+        self.context
+            .check_api_output_enabled("Get events by event type", &accept_type)?;
+        let page = Page::new(
+            start.0.map(|v| v.0),
+            limit.0,
+            self.context.max_events_page_size(),
+        );
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        self.list_by_type(latest_ledger_info, accept_type, page, event_type)
+    }
 }
 
 impl EventsApi {
@@ -164,6 +216,62 @@ impl EventsApi {
                 )
             })?;
 
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    /// List events of the given type, starting from the version in `page`
+    fn list_by_type(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        page: Page,
+        event_type: TypeTag,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let ledger_version = latest_ledger_info.version();
+        let oldest_version = latest_ledger_info.oldest_ledger_version.0;
+        let start_version = page.start_option().unwrap_or(oldest_version);
+        if start_version < oldest_version {
+            return Err(version_pruned(start_version, &latest_ledger_info));
+        }
+        if start_version > ledger_version {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                &format!(
+                    "Given start value ({}) is higher than the current ledger version, it must be <= {}",
+                    start_version, ledger_version
+                ),
+                AptosErrorCode::InvalidInput,
+                &latest_ledger_info,
+            ));
+        }
+
+        let events = self
+            .context
+            .db
+            .get_events_by_type(
+                &event_type,
+                start_version,
+                page.limit(&latest_ledger_info)? as u64,
+                ledger_version,
+            )
+            .context(format!("Failed to find events by type {}", event_type))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    /// Render events in the requested format
+    fn render(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        events: Vec<EventWithVersion>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
         match accept_type {
             AcceptType::Json => {
                 let resolver = self.context.move_resolver_poem(&latest_ledger_info)?;
@@ -17,13 +17,16 @@ mod index;
 mod log;
 pub mod metrics;
 mod page;
+mod proof;
 mod response;
 mod runtime;
 mod set_failpoints;
 mod state;
+mod stream;
 #[cfg(test)]
 pub mod tests;
 mod transactions;
+mod view_function;
 
 /// API categories for the OpenAPI spec
 #[derive(Tags)]
@@ -36,14 +39,23 @@ pub enum ApiTags {
     /// Access to events
     Events,
 
+    /// Access to data with proofs, for verifying clients
+    Proofs,
+
     /// General information
     General,
 
+    /// Access to changes of the global state
+    State,
+
     /// Access to tables
     Tables,
 
     /// Access to transactions
     Transactions,
+
+    /// View functions
+    View,
 }
 
 // Note: Many of these exports are just for the test-context crate, which is
@@ -0,0 +1,251 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    accept_type::AcceptType,
+    context::Context,
+    failpoint::fail_point_poem,
+    response::{
+        transaction_not_found_by_version, version_not_found, BadRequestError, BasicErrorWith404,
+        BasicResponse, BasicResponseStatus, BasicResultWith404, InternalError,
+    },
+    ApiTags,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    Address, AptosErrorCode, HexEncodedBytes, LedgerInfo, MoveStructTag, VerifyInputWithRecursion,
+    U64,
+};
+use aptos_types::{
+    access_path::AccessPath,
+    state_store::{state_key::StateKey, state_value::StateValueWithProof},
+};
+use move_core_types::language_storage::{ResourceKey, StructTag};
+use poem_openapi::{
+    param::{Path, Query},
+    OpenApi,
+};
+use serde::Serialize;
+use std::{convert::TryInto, sync::Arc};
+
+/// API for retrieving data along with proofs, for clients that verify what they read
+///
+/// Every endpoint returns a BCS encoded Rust type from `aptos-types`. With BCS
+/// output (`Accept: application/x-bcs`), the body is the encoded value itself,
+/// otherwise it is the encoded value as a hex string.
+pub struct ProofApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl ProofApi {
+    /// Get state proof
+    ///
+    /// Returns a `StateProof`, holding the latest ledger info with signatures and
+    /// the epoch change proof from the epoch of `known_version` onwards. Clients
+    /// use it to ratchet their trusted state forward. The epoch change proof may
+    /// be incomplete if many epochs have passed, in which case clients should ask
+    /// again from the version of the last epoch change they verified.
+    #[oai(
+        path = "/proofs/state_proof",
+        method = "get",
+        operation_id = "get_state_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_state_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Latest ledger version the client trusts
+        known_version: Query<U64>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        fail_point_poem("endpoint_get_state_proof")?;
+        self.context
+            .check_api_output_enabled("Get state proof", &accept_type)?;
+        // Epoch ending ledger infos are never pruned, so clients can catch up from
+        // any version, even one older than the oldest version in the DB
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let known_version = known_version.0 .0;
+        if known_version > ledger_info.version() {
+            return Err(version_not_found(known_version, &ledger_info));
+        }
+        let state_proof = self
+            .context
+            .db
+            .get_state_proof(known_version)
+            .context("Failed to get state proof")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+        Self::respond(&accept_type, &ledger_info, state_proof)
+    }
+
+    /// Get account resource with proof
+    ///
+    /// Returns a `StateValueWithProof` for the resource, read from the latest state
+    /// snapshot at or before `ledger_version`, and proven against the ledger info at
+    /// `ledger_version`. State snapshots are not taken at every version, so check the
+    /// `version` of the response to see which version the value was read at.
+    #[oai(
+        path = "/proofs/accounts/:address/resource/:resource_type",
+        method = "get",
+        operation_id = "get_account_resource_with_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_account_resource_with_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Name of struct to retrieve e.g. `0x1::account::Account`
+        resource_type: Path<MoveStructTag>,
+        /// Ledger version of the ledger info to prove the resource against
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        resource_type
+            .0
+            .verify(0)
+            .context("'resource_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_account_resource_with_proof")?;
+        self.context
+            .check_api_output_enabled("Get account resource with proof", &accept_type)?;
+        let resource_type: StructTag = resource_type
+            .0
+            .try_into()
+            .context("Failed to parse given resource type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        let resource_key = ResourceKey::new(address.0.into(), resource_type);
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(resource_key));
+        self.state_value_with_proof(
+            &accept_type,
+            state_key,
+            ledger_version.0.map(|inner| inner.0),
+        )
+    }
+
+    /// Get transaction by version with proof
+    ///
+    /// Returns a `TransactionWithProof`, holding the transaction at `txn_version`,
+    /// its events, and the proof of its transaction info against the ledger info at
+    /// `ledger_version`.
+    #[oai(
+        path = "/proofs/transactions/by_version/:txn_version",
+        method = "get",
+        operation_id = "get_transaction_by_version_with_proof",
+        tag = "ApiTags::Proofs"
+    )]
+    async fn get_transaction_by_version_with_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Version of the transaction
+        txn_version: Path<U64>,
+        /// Ledger version of the ledger info to prove the transaction against
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        fail_point_poem("endpoint_get_transaction_by_version_with_proof")?;
+        self.context
+            .check_api_output_enabled("Get transaction by version with proof", &accept_type)?;
+        let (ledger_info, ledger_version) = self
+            .context
+            .get_latest_ledger_info_and_verify_lookup_version(
+                ledger_version.0.map(|inner| inner.0),
+            )?;
+        let txn_version = txn_version.0 .0;
+        if txn_version > ledger_version {
+            return Err(transaction_not_found_by_version(txn_version, &ledger_info));
+        }
+        let txn_with_proof = self
+            .context
+            .db
+            .get_transaction_by_version(txn_version, ledger_version, true)
+            .context("Failed to get transaction with proof")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+        Self::respond(&accept_type, &ledger_info, txn_with_proof)
+    }
+}
+
+impl ProofApi {
+    /// Read a state value with proof from the latest state snapshot at or before the ledger version
+    fn state_value_with_proof(
+        &self,
+        accept_type: &AcceptType,
+        state_key: StateKey,
+        ledger_version: Option<u64>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        let (ledger_info, ledger_version) = self
+            .context
+            .get_latest_ledger_info_and_verify_lookup_version(ledger_version)?;
+        let internal_error = |err: anyhow::Error| {
+            BasicErrorWith404::internal_with_code(err, AptosErrorCode::InternalError, &ledger_info)
+        };
+
+        let db = &self.context.db;
+        let (snapshot_version, _root_hash) = db
+            .get_state_snapshot_before(ledger_version + 1)
+            .context("Failed to find state snapshot")
+            .map_err(internal_error)?
+            .ok_or_else(|| version_not_found(ledger_version, &ledger_info))?;
+        let (value, proof) = db
+            .get_state_value_with_proof_by_version(&state_key, snapshot_version)
+            .context(format!("Failed to query DB for {:?}", state_key))
+            .map_err(internal_error)?;
+        let transaction_info_with_proof = db
+            .get_transaction_by_version(snapshot_version, ledger_version, false)
+            .context("Failed to get transaction info with proof")
+            .map_err(internal_error)?
+            .proof;
+
+        Self::respond(
+            accept_type,
+            &ledger_info,
+            StateValueWithProof::new(snapshot_version, value, proof, transaction_info_with_proof),
+        )
+    }
+
+    /// Respond with the BCS encoded value, hex encoded for JSON
+    fn respond<T: Serialize>(
+        accept_type: &AcceptType,
+        ledger_info: &LedgerInfo,
+        value: T,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        match accept_type {
+            AcceptType::Json => {
+                let bytes = bcs::to_bytes(&value)
+                    .context("Failed to serialize proof")
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AptosErrorCode::InternalError,
+                            ledger_info,
+                        )
+                    })?;
+                BasicResponse::try_from_json((
+                    HexEncodedBytes::from(bytes),
+                    ledger_info,
+                    BasicResponseStatus::Ok,
+                ))
+            }
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((value, ledger_info, BasicResponseStatus::Ok))
+            }
+        }
+    }
+}
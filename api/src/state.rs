@@ -3,11 +3,12 @@
 
 use crate::response::{
     api_disabled, build_not_found, module_not_found, resource_not_found, table_item_not_found,
-    StdApiError,
+    version_pruned, StdApiError,
 };
 use crate::{
     accept_type::AcceptType,
     failpoint::fail_point_poem,
+    page::determine_limit,
     response::{
         BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
         InternalError,
@@ -18,7 +19,8 @@ use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_module_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
     MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, RawTableItemRequest,
-    TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
+    StateKeyWrapper, StateValueChange, TableItemRequest, VerifyInput, VerifyInputWithRecursion,
+    U64,
 };
 use aptos_state_view::StateView;
 use aptos_types::{
@@ -32,8 +34,11 @@ use poem_openapi::{
     payload::Json,
     OpenApi,
 };
-use std::{convert::TryInto, sync::Arc};
-use storage_interface::state_view::DbStateView;
+use std::{
+    convert::{TryFrom, TryInto},
+    sync::Arc,
+};
+use storage_interface::{state_view::DbStateView, Error as StorageError};
 
 /// API for retrieving individual state
 pub struct StateApi {
@@ -207,6 +212,56 @@ impl StateApi {
             ledger_version.0,
         )
     }
+
+    /// Get state changes
+    ///
+    /// Retrieves the state items whose values differ between two ledger versions, along with
+    /// their values at both versions. Items which changed and then changed back in between are
+    /// not included. Changes are ordered by the hash of the state key.
+    ///
+    /// Changes are returned in pages. A page may be shorter than `limit` even if more changes
+    /// follow, so keep requesting pages until no X-Aptos-Cursor header is returned.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If `from_version` has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/state/changes",
+        method = "get",
+        operation_id = "get_state_changes",
+        tag = "ApiTags::State"
+    )]
+    async fn get_state_changes(
+        &self,
+        accept_type: AcceptType,
+        /// Ledger version to compare the state from
+        from_version: Query<U64>,
+        /// Ledger version to compare the state to
+        ///
+        /// If not provided, it will be the latest version
+        to_version: Query<Option<U64>>,
+        /// Cursor specifying where to start for pagination
+        ///
+        /// This cursor cannot be derived manually client-side. Instead, you must
+        /// call this endpoint once without this query parameter specified, and
+        /// then use the cursor returned in the X-Aptos-Cursor header in the
+        /// response.
+        start: Query<Option<StateKeyWrapper>>,
+        /// Max number of state changes to retrieve
+        ///
+        /// If not provided, defaults to default page size.
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<StateValueChange>> {
+        fail_point_poem("endpoint_get_state_changes")?;
+        self.context
+            .check_api_output_enabled("Get state changes", &accept_type)?;
+        self.state_changes(
+            &accept_type,
+            from_version.0 .0,
+            to_version.0.map(|inner| inner.0),
+            start.0.map(StateKey::from),
+            limit.0,
+        )
+    }
 }
 
 impl StateApi {
@@ -287,6 +342,88 @@ impl StateApi {
         }
     }
 
+    /// Compare the state between two ledger versions
+    ///
+    /// JSON: Hex encode the state keys and values
+    /// BCS: Return the changes as [`Vec<aptos_types::state_store::state_value::StateValueChange>`]
+    fn state_changes(
+        &self,
+        accept_type: &AcceptType,
+        from_version: u64,
+        to_version: Option<u64>,
+        start: Option<StateKey>,
+        limit: Option<u16>,
+    ) -> BasicResultWith404<Vec<StateValueChange>> {
+        let (ledger_info, to_version) = self
+            .context
+            .get_latest_ledger_info_and_verify_lookup_version(to_version)?;
+        if from_version < ledger_info.oldest_ledger_version.0 {
+            return Err(version_pruned(from_version, &ledger_info));
+        }
+        if from_version > to_version {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                &format!(
+                    "Given from_version ({}) is higher than to_version, it must be <= {}",
+                    from_version, to_version
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+        let max_state_changes_page_size = self.context.max_state_changes_page_size();
+        let limit = determine_limit(
+            limit,
+            max_state_changes_page_size,
+            max_state_changes_page_size,
+            &ledger_info,
+        )?;
+
+        let (changes, next_state_key) = self
+            .context
+            .db
+            .get_state_value_changes(from_version, to_version, start.as_ref(), limit as u64)
+            .context("Failed to get state changes from storage")
+            .map_err(|err| {
+                // The state snapshots can't be diffed, e.g., because they're pruned, and the
+                // range is too long to read every write set
+                if let Some(error @ StorageError::VersionRangeTooLong(..)) = err.downcast_ref() {
+                    BasicErrorWith404::bad_request_with_code(
+                        error,
+                        AptosErrorCode::InvalidInput,
+                        &ledger_info,
+                    )
+                } else {
+                    BasicErrorWith404::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        &ledger_info,
+                    )
+                }
+            })?;
+
+        match accept_type {
+            AcceptType::Json => {
+                let changes = changes
+                    .into_iter()
+                    .map(StateValueChange::try_from)
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AptosErrorCode::InternalError,
+                            &ledger_info,
+                        )
+                    })?;
+                BasicResponse::try_from_json((changes, &ledger_info, BasicResponseStatus::Ok))
+                    .map(|v| v.with_cursor(next_state_key))
+            }
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((changes, &ledger_info, BasicResponseStatus::Ok))
+                    .map(|v| v.with_cursor(next_state_key))
+            }
+        }
+    }
+
     /// Retrieve the module
     ///
     /// JSON: Parse ABI and bytecode
@@ -0,0 +1,369 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point_poem,
+    response::{api_disabled, version_pruned, BadRequestError, BasicError, BasicErrorWith404},
+    ApiTags,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    Address, AptosErrorCode, AsConverter, LedgerInfo, MoveStructTag, Transaction,
+    TransactionOnChainData, VerifyInputWithRecursion, VersionedEvent, U64,
+};
+use aptos_logger::warn;
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    event::EventKey,
+    transaction::Transaction as CoreTransaction,
+};
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use poem_openapi::{param::Query, payload::EventStream, OpenApi};
+use std::{convert::TryInto, sync::Arc, time::Duration};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+type StreamResult<T> = poem::Result<EventStream<BoxStream<'static, T>>, BasicErrorWith404>;
+
+/// API for subscribing to newly committed transactions and events
+///
+/// Subscriptions are served as server-sent events (SSE). Each message holds one
+/// JSON encoded transaction or event, in ledger order. Clients can resume a
+/// broken subscription by passing the version after the last one they received.
+pub struct StreamApi {
+    pub context: Arc<Context>,
+    /// Bounds the number of streams open at the same time
+    stream_permits: Arc<Semaphore>,
+}
+
+#[OpenApi]
+impl StreamApi {
+    /// Stream transactions
+    ///
+    /// Streams committed transactions as server-sent events, starting from
+    /// `start_version`. The stream stays open and new transactions are sent as
+    /// they are committed.
+    #[oai(
+        path = "/transactions/stream",
+        method = "get",
+        operation_id = "stream_transactions",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn stream_transactions(
+        &self,
+        /// Ledger version to start streaming from
+        ///
+        /// If not provided, only transactions committed after the request are streamed
+        start_version: Query<Option<U64>>,
+        /// Only stream user transactions sent by this account
+        sender: Query<Option<Address>>,
+    ) -> StreamResult<Transaction> {
+        fail_point_poem("endpoint_stream_transactions")?;
+        if !self.context.node_config.api.stream_enabled {
+            return Err(api_disabled("Stream transactions"));
+        }
+        let start_version = self.start_version(start_version.0.map(|inner| inner.0))?;
+        let filter = StreamFilter {
+            sender: sender.0.map(|inner| inner.into()),
+            ..StreamFilter::default()
+        };
+
+        Ok(EventStream::new(poll_stream(
+            self.context.clone(),
+            self.acquire_stream_permit()?,
+            start_version,
+            move |context, ledger_info, data| {
+                filter.render_transactions(context, ledger_info, data)
+            },
+        )))
+    }
+
+    /// Stream events
+    ///
+    /// Streams events from committed transactions as server-sent events, starting
+    /// from `start_version`. Events can be filtered by event stream (`address` and
+    /// `creation_number`), by event type, and by the sender of the transaction
+    /// that emitted them. All given filters must match.
+    #[oai(
+        path = "/events/stream",
+        method = "get",
+        operation_id = "stream_events",
+        tag = "ApiTags::Events"
+    )]
+    async fn stream_events(
+        &self,
+        /// Ledger version to start streaming from
+        ///
+        /// If not provided, only events committed after the request are streamed
+        start_version: Query<Option<U64>>,
+        /// Account the event stream was created under, must be given together
+        /// with `creation_number`
+        address: Query<Option<Address>>,
+        /// Creation number of the event stream, must be given together with `address`
+        creation_number: Query<Option<U64>>,
+        /// Skip events of the event stream with a lower sequence number
+        ///
+        /// Only valid together with `address` and `creation_number`
+        start_sequence_number: Query<Option<U64>>,
+        /// Only stream events of this type e.g. `0x1::coin::DepositEvent`
+        event_type: Query<Option<MoveStructTag>>,
+        /// Only stream events emitted by user transactions sent by this account
+        sender: Query<Option<Address>>,
+    ) -> StreamResult<VersionedEvent> {
+        fail_point_poem("endpoint_stream_events")?;
+        if !self.context.node_config.api.stream_enabled {
+            return Err(api_disabled("Stream events"));
+        }
+
+        let event_key = match (address.0, creation_number.0) {
+            (Some(address), Some(creation_number)) => {
+                Some(EventKey::new(creation_number.0, address.into()))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(BasicErrorWith404::bad_request_with_code_no_info(
+                    "'address' and 'creation_number' must be given together",
+                    AptosErrorCode::InvalidInput,
+                ))
+            }
+        };
+        if event_key.is_none() && start_sequence_number.0.is_some() {
+            return Err(BasicErrorWith404::bad_request_with_code_no_info(
+                "'start_sequence_number' requires 'address' and 'creation_number'",
+                AptosErrorCode::InvalidInput,
+            ));
+        }
+        let event_type = event_type
+            .0
+            .map(|event_type| {
+                event_type.verify(0)?;
+                let struct_tag: StructTag = event_type.try_into()?;
+                Ok::<_, anyhow::Error>(TypeTag::Struct(Box::new(struct_tag)))
+            })
+            .transpose()
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+
+        let start_version = self.start_version(start_version.0.map(|inner| inner.0))?;
+        let filter = StreamFilter {
+            event_key,
+            start_sequence_number: start_sequence_number.0.map(|inner| inner.0),
+            event_type,
+            sender: sender.0.map(|inner| inner.into()),
+        };
+
+        Ok(EventStream::new(poll_stream(
+            self.context.clone(),
+            self.acquire_stream_permit()?,
+            start_version,
+            move |context, ledger_info, data| filter.render_events(context, ledger_info, data),
+        )))
+    }
+}
+
+impl StreamApi {
+    pub fn new(context: Arc<Context>) -> Self {
+        let max_concurrent_streams = context.node_config.api.max_concurrent_streams;
+        Self {
+            context,
+            stream_permits: Arc::new(Semaphore::new(max_concurrent_streams)),
+        }
+    }
+
+    /// Reserve one of the streams that can be open at the same time, released when the
+    /// stream is dropped
+    fn acquire_stream_permit(&self) -> Result<OwnedSemaphorePermit, BasicErrorWith404> {
+        self.stream_permits
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| {
+                BasicErrorWith404::service_unavailable_with_code_no_info(
+                    "Too many streams are open, try again later",
+                    AptosErrorCode::InternalError,
+                )
+            })
+    }
+
+    /// Resolve the version a stream starts at, rejecting pruned versions
+    fn start_version(&self, requested: Option<u64>) -> Result<u64, BasicErrorWith404> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        match requested {
+            Some(version) if version < ledger_info.oldest_ledger_version.0 => {
+                Err(version_pruned(version, &ledger_info))
+            }
+            Some(version) => Ok(version),
+            None => Ok(ledger_info.version() + 1),
+        }
+    }
+}
+
+/// Filters applied to a stream, all of which must match
+#[derive(Clone, Debug, Default)]
+struct StreamFilter {
+    event_key: Option<EventKey>,
+    start_sequence_number: Option<u64>,
+    event_type: Option<TypeTag>,
+    sender: Option<AccountAddress>,
+}
+
+impl StreamFilter {
+    fn matches_sender(&self, txn: &CoreTransaction) -> bool {
+        match (&self.sender, txn) {
+            (None, _) => true,
+            (Some(sender), CoreTransaction::UserTransaction(txn)) => txn.sender() == *sender,
+            (Some(_), _) => false,
+        }
+    }
+
+    fn matches_event(&self, event: &ContractEvent) -> bool {
+        if let Some(event_key) = &self.event_key {
+            if event.key() != event_key {
+                return false;
+            }
+            if let Some(start) = self.start_sequence_number {
+                if event.sequence_number() < start {
+                    return false;
+                }
+            }
+        }
+        self.event_type
+            .as_ref()
+            .map_or(true, |event_type| event.type_tag() == event_type)
+    }
+
+    fn render_transactions(
+        &self,
+        context: &Context,
+        ledger_info: &LedgerInfo,
+        data: Vec<TransactionOnChainData>,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        let data = data
+            .into_iter()
+            .filter(|txn| self.matches_sender(&txn.transaction))
+            .collect();
+        context
+            .render_transactions_non_sequential::<BasicError>(ledger_info, data)
+            .map_err(|err| err.into())
+    }
+
+    fn render_events(
+        &self,
+        context: &Context,
+        ledger_info: &LedgerInfo,
+        data: Vec<TransactionOnChainData>,
+    ) -> anyhow::Result<Vec<VersionedEvent>> {
+        let events: Vec<_> = data
+            .into_iter()
+            .filter(|txn| self.matches_sender(&txn.transaction))
+            .flat_map(|txn| {
+                let version = txn.version;
+                txn.events
+                    .into_iter()
+                    .filter(|event| self.matches_event(event))
+                    .map(move |event| EventWithVersion::new(version, event))
+            })
+            .collect();
+        if events.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let resolver = context.move_resolver_poem::<BasicError>(ledger_info)?;
+        resolver
+            .as_converter(context.db.clone())
+            .try_into_versioned_events(&events)
+            .context("Failed to convert events from storage into response")
+    }
+}
+
+/// Builds a stream that polls the DB for transactions from `start_version`
+/// onwards, and renders each batch with `render`
+///
+/// Batches are read on the blocking thread pool, as reading from the DB is
+/// synchronous. The stream holds `permit` until it is dropped. It ends when
+/// reading from the DB or rendering fails, e.g. because the node pruned past the
+/// stream's position while the client was slow.
+fn poll_stream<T, F>(
+    context: Arc<Context>,
+    permit: OwnedSemaphorePermit,
+    start_version: u64,
+    render: F,
+) -> BoxStream<'static, T>
+where
+    T: Send + 'static,
+    F: Fn(&Context, &LedgerInfo, Vec<TransactionOnChainData>) -> anyhow::Result<Vec<T>>
+        + Send
+        + Sync
+        + 'static,
+{
+    let poll_interval = Duration::from_millis(context.node_config.api.stream_poll_interval_ms);
+    stream::unfold(
+        (context, permit, start_version, Arc::new(render)),
+        move |(context, permit, mut next_version, render)| async move {
+            loop {
+                let batch = {
+                    let (context, render) = (context.clone(), render.clone());
+                    tokio::task::spawn_blocking(move || {
+                        next_batch(&context, next_version, render.as_ref())
+                    })
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|result| result)
+                };
+                match batch {
+                    Ok(Some((version, items))) => {
+                        next_version = version;
+                        if !items.is_empty() {
+                            return Some((items, (context, permit, next_version, render)));
+                        }
+                        // Everything in this batch was filtered out, move on to the next one
+                    }
+                    // Caught up with the ledger, wait for new transactions
+                    Ok(None) => tokio::time::sleep(poll_interval).await,
+                    Err(err) => {
+                        warn!(
+                            "Closing stream at version {} due to error: {:#}",
+                            next_version, err
+                        );
+                        return None;
+                    }
+                }
+            }
+        },
+    )
+    .flat_map(stream::iter)
+    .boxed()
+}
+
+/// Reads and renders the batch of transactions from `next_version`, returning the
+/// version after the batch along with the rendered items
+///
+/// Returns `None` if there are no transactions from `next_version` yet.
+fn next_batch<T, F>(
+    context: &Context,
+    next_version: u64,
+    render: &F,
+) -> anyhow::Result<Option<(u64, Vec<T>)>>
+where
+    F: Fn(&Context, &LedgerInfo, Vec<TransactionOnChainData>) -> anyhow::Result<Vec<T>>,
+{
+    let ledger_info = context.get_latest_ledger_info_wrapped()?;
+    let ledger_version = ledger_info.version();
+    if next_version > ledger_version {
+        return Ok(None);
+    }
+
+    let data = context.get_transactions(
+        next_version,
+        context.max_transactions_page_size(),
+        ledger_version,
+    )?;
+    let next_version = next_version + data.len() as u64;
+    render(context, &ledger_info, data).map(|items| Some((next_version, items)))
+}
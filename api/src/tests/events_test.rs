@@ -154,3 +154,71 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .get("/events/by_type/0x1::reconfiguration::NewEpochEvent")
+        .await;
+    let events = resp.as_array().unwrap();
+    assert!(!events.is_empty());
+    assert_eq!(events[0]["version"], "0");
+    for event in events {
+        assert_eq!(event["type"], "0x1::reconfiguration::NewEpochEvent");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type_paginated() {
+    let mut context = new_test_context(current_function_name!());
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account(&account);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    let event_type = "0x1::block::NewBlockEvent";
+    let all = context
+        .get(format!("/events/by_type/{}?limit=100", event_type).as_str())
+        .await;
+    let all = all.as_array().unwrap();
+    assert!(all.len() >= 3);
+
+    let mut paged = vec![];
+    let mut start = 0;
+    loop {
+        let resp = context
+            .get(format!("/events/by_type/{}?start={}&limit=1", event_type, start).as_str())
+            .await;
+        let page = resp.as_array().unwrap().clone();
+        let last = match page.last() {
+            Some(last) => last["version"].as_str().unwrap().parse::<u64>().unwrap(),
+            None => break,
+        };
+        paged.extend(page);
+        start = last + 1;
+    }
+    assert_eq!(&paged, all);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_invalid_type() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::reconfiguration")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type_start_after_ledger_version() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::reconfiguration::NewEpochEvent?start=1000000")
+        .await;
+}
@@ -0,0 +1,115 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::current_function_name;
+use aptos_api_types::HexEncodedBytes;
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::AccountResource,
+    state_proof::StateProof,
+    state_store::{state_key::StateKey, state_value::StateValueWithProof},
+    transaction::TransactionWithProof,
+    trusted_state::TrustedState,
+    waypoint::Waypoint,
+};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::ResourceKey, move_resource::MoveStructType,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::str::FromStr;
+use storage_interface::DbReader;
+
+fn decode<T: DeserializeOwned>(resp: Value) -> T {
+    let bytes = HexEncodedBytes::from_str(resp.as_str().unwrap()).unwrap();
+    bcs::from_bytes(bytes.inner()).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_proof() {
+    let context = new_test_context(current_function_name!());
+    let genesis_li = context.db.get_epoch_ending_ledger_info(0).unwrap();
+    let trusted_state = TrustedState::from_epoch_waypoint(
+        Waypoint::new_epoch_boundary(genesis_li.ledger_info()).unwrap(),
+    );
+
+    let resp = context.get("/proofs/state_proof?known_version=0").await;
+    let state_proof: StateProof = decode(resp);
+
+    assert_eq!(
+        state_proof.latest_ledger_info_w_sigs(),
+        &context.db.get_latest_ledger_info().unwrap()
+    );
+    trusted_state.verify_and_ratchet(&state_proof).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_proof_from_future_version() {
+    let mut context = new_test_context(current_function_name!());
+    let latest_version = context.db.get_latest_version().unwrap();
+
+    context
+        .expect_status_code(404)
+        .get(&format!(
+            "/proofs/state_proof?known_version={}",
+            latest_version + 1
+        ))
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resource_with_proof() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .get("/proofs/accounts/0x1/resource/0x1::account::Account")
+        .await;
+    let state_value_with_proof: StateValueWithProof = decode(resp);
+
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        AccountAddress::ONE,
+        AccountResource::struct_tag(),
+    )));
+    let latest_li = context.db.get_latest_ledger_info().unwrap();
+    state_value_with_proof
+        .verify(latest_li.ledger_info(), &state_key)
+        .unwrap();
+    let value = state_value_with_proof.value.unwrap();
+    bcs::from_bytes::<AccountResource>(value.bytes()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_by_version_with_proof() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .get("/proofs/transactions/by_version/0?ledger_version=0")
+        .await;
+    let txn_with_proof: TransactionWithProof = decode(resp);
+
+    let genesis_li = context.db.get_epoch_ending_ledger_info(0).unwrap();
+    assert_eq!(txn_with_proof.version, 0);
+    assert!(txn_with_proof.events.is_some());
+    txn_with_proof.verify(genesis_li.ledger_info()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resource_with_proof_invalid_type() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(400)
+        .get("/proofs/accounts/0x1/resource/0x1::account")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_by_version_with_proof_not_found() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(404)
+        .get("/proofs/transactions/by_version/1000000")
+        .await;
+}
@@ -3,12 +3,18 @@
 
 use super::new_test_context;
 use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_api_types::{StateKeyWrapper, StateValueChange};
 use aptos_sdk::{transaction_builder::aptos_stdlib::aptos_token_stdlib, types::LocalAccount};
-use move_core_types::account_address::AccountAddress;
+use aptos_types::{
+    access_path::AccessPath, account_config::AccountResource, state_store::state_key::StateKey,
+};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::ResourceKey, move_resource::MoveStructType,
+};
 use move_package::BuildConfig;
 use serde::Serialize;
 use serde_json::{json, Value};
-use std::{convert::TryInto, path::PathBuf};
+use std::{convert::TryInto, path::PathBuf, str::FromStr};
 use storage_interface::DbReader;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -298,6 +304,70 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_changes() {
+    let mut context = new_test_context(current_function_name!());
+    let from_version = context.get_latest_ledger_info().version();
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let to_version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .get(&get_state_changes(from_version, to_version))
+        .await;
+    let changes: Vec<StateValueChange> = serde_json::from_value(resp).unwrap();
+    let account_state_key = StateKey::AccessPath(AccessPath::resource_access_path(
+        ResourceKey::new(account.address(), AccountResource::struct_tag()),
+    ));
+    let account_change = changes
+        .iter()
+        .find(|change| change.state_key.inner() == account_state_key.encode().unwrap())
+        .unwrap();
+    assert!(account_change.old_value.is_none());
+    assert!(account_change.new_value.is_some());
+
+    // Paging through the changes one at a time returns the same changes.
+    let mut paged_changes = vec![];
+    let mut start = None;
+    loop {
+        let mut path = format!("/v1{}&limit=1", get_state_changes(from_version, to_version));
+        if let Some(start) = &start {
+            path = format!("{}&start={}", path, start);
+        }
+        let resp = context
+            .reply(warp::test::request().method("GET").path(&path))
+            .await;
+        assert_eq!(resp.status(), 200);
+        let page: Vec<StateValueChange> = serde_json::from_slice(resp.body()).unwrap();
+        assert!(page.len() <= 1);
+        paged_changes.extend(page);
+        match resp.headers().get("X-Aptos-Cursor") {
+            Some(cursor) => {
+                start = Some(StateKeyWrapper::from_str(cursor.to_str().unwrap()).unwrap())
+            }
+            None => break,
+        }
+    }
+    assert_eq!(paged_changes, changes);
+
+    // Nothing changes between a version and itself.
+    let resp = context
+        .get(&get_state_changes(to_version, to_version))
+        .await;
+    assert_eq!(resp, json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_changes_from_version_after_to_version() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    context
+        .expect_status_code(400)
+        .get(&get_state_changes(ledger_version + 1, ledger_version))
+        .await;
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -309,6 +379,13 @@ fn get_account_resource_with_version(address: &str, struct_tag: &str, version: u
     )
 }
 
+fn get_state_changes(from_version: u64, to_version: u64) -> String {
+    format!(
+        "/state/changes?from_version={}&to_version={}",
+        from_version, to_version
+    )
+}
+
 fn get_account_module(address: &str, name: &str) -> String {
     format!("/accounts/{}/module/{}", address, name)
 }
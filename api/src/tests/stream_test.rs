@@ -0,0 +1,220 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::{current_function_name, ApiSpecificConfig, TestContext};
+use aptos_api_types::Transaction;
+use aptos_sdk::{rest_client::Client, types::LocalAccount};
+use aptos_types::{account_address::AccountAddress, event::EventKey};
+use futures::{Stream, StreamExt};
+use reqwest::Url;
+use serde_json::json;
+use std::{fmt::Debug, time::Duration};
+
+static DEPOSIT_EVENT: &str = "0x1::coin::DepositEvent";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events_with_partial_event_key() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/events/stream?address=0x1")
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/events/stream?creation_number=0")
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events_with_start_sequence_number_only() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/events/stream?start_sequence_number=10")
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events_with_invalid_event_type() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/events/stream?event_type=0x1::coin")
+        .await;
+    assert!(resp["message"].is_string());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let client = rest_client(&context);
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    // The whole ledger is replayed from version 0
+    let mut all_txns = client.stream_transactions(Some(0), None).await.unwrap();
+    for version in 0..=ledger_version {
+        assert_eq!(next(&mut all_txns).await.version(), Some(version));
+    }
+
+    // Without a start version, only transactions committed after subscribing are streamed
+    let mut new_txns = client.stream_transactions(None, None).await.unwrap();
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&[txn]).await;
+    let new_ledger_version = context.get_latest_ledger_info().version();
+    assert!(new_ledger_version > ledger_version);
+    for version in ledger_version + 1..=new_ledger_version {
+        assert_eq!(next(&mut all_txns).await.version(), Some(version));
+        assert_eq!(next(&mut new_txns).await.version(), Some(version));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_resumes_from_start_version() {
+    let mut context = new_test_context(current_function_name!());
+    let client = rest_client(&context);
+    create_funded_account(&mut context).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    for start_version in [1, ledger_version / 2, ledger_version] {
+        let mut txns = client
+            .stream_transactions(Some(start_version), None)
+            .await
+            .unwrap();
+        for version in start_version..=ledger_version {
+            assert_eq!(next(&mut txns).await.version(), Some(version));
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_by_sender() {
+    let mut context = new_test_context(current_function_name!());
+    let client = rest_client(&context);
+    let (mut root_account, mut account) = create_funded_account(&mut context).await;
+    let txn = context.account_transfer(&mut account, &root_account, 1);
+    context.commit_block(&[txn]).await;
+
+    // The root account created and funded the account, which then sent a transfer back
+    let mut root_txns = client
+        .stream_transactions(Some(0), Some(root_account.address()))
+        .await
+        .unwrap();
+    for sequence_number in 0..2 {
+        assert_user_transaction(
+            next(&mut root_txns).await,
+            root_account.address(),
+            sequence_number,
+        );
+    }
+    let mut account_txns = client
+        .stream_transactions(Some(0), Some(account.address()))
+        .await
+        .unwrap();
+    assert_user_transaction(next(&mut account_txns).await, account.address(), 0);
+
+    // Later transactions of the sender are pushed as they are committed
+    let txn = context.account_transfer(&mut root_account, &account, 1);
+    context.commit_block(&[txn]).await;
+    assert_user_transaction(next(&mut root_txns).await, root_account.address(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events() {
+    let mut context = new_test_context(current_function_name!());
+    let client = rest_client(&context);
+    let (mut root_account, account) = create_funded_account(&mut context).await;
+    let txn = context.account_transfer(&mut root_account, &account, 1);
+    context.commit_block(&[txn]).await;
+
+    // Both transfers of the root account deposited into the new account
+    let mut deposits = client
+        .stream_events(
+            Some(0),
+            None,
+            None,
+            Some(DEPOSIT_EVENT),
+            Some(root_account.address()),
+        )
+        .await
+        .unwrap();
+    let first_deposit = next(&mut deposits).await;
+    let second_deposit = next(&mut deposits).await;
+    for deposit in [&first_deposit, &second_deposit] {
+        assert_eq!(deposit.typ.to_string(), DEPOSIT_EVENT);
+        assert_eq!(deposit.guid, first_deposit.guid);
+    }
+    assert_eq!(
+        AccountAddress::from(first_deposit.guid.account_address),
+        account.address()
+    );
+    assert!(first_deposit.version.0 < second_deposit.version.0);
+
+    // The event stream of the deposits holds the same events, and can be resumed by sequence
+    // number
+    let event_key = EventKey::new(first_deposit.guid.creation_number.0, account.address());
+    let mut account_deposits = client
+        .stream_events(Some(0), Some(event_key), None, None, None)
+        .await
+        .unwrap();
+    for (sequence_number, deposit) in [&first_deposit, &second_deposit].iter().enumerate() {
+        let event = next(&mut account_deposits).await;
+        assert_eq!(event.sequence_number.0, sequence_number as u64);
+        assert_eq!(event.version, deposit.version);
+    }
+    let mut later_deposits = client
+        .stream_events(Some(0), Some(event_key), Some(1), None, None)
+        .await
+        .unwrap();
+    let event = next(&mut later_deposits).await;
+    assert_eq!(event.sequence_number.0, 1);
+    assert_eq!(event.version, second_deposit.version);
+
+    // New events are pushed as they are committed
+    let txn = context.account_transfer(&mut root_account, &account, 1);
+    context.commit_block(&[txn]).await;
+    for stream in [&mut deposits, &mut account_deposits, &mut later_deposits] {
+        assert_eq!(next(stream).await.sequence_number.0, 2);
+    }
+}
+
+fn rest_client(context: &TestContext) -> Client {
+    let ApiSpecificConfig::V1(address) = context.api_specific_config;
+    Client::new(Url::parse(&format!("http://{}", address)).unwrap())
+}
+
+/// Creates an account and funds it from the root account, in separate blocks
+async fn create_funded_account(context: &mut TestContext) -> (LocalAccount, LocalAccount) {
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&[txn]).await;
+    let txn = context.account_transfer(&mut root_account, &account, 10_000_000);
+    context.commit_block(&[txn]).await;
+    (root_account, account)
+}
+
+/// The next item of a stream, failing the test if it doesn't come in time
+async fn next<T, E: Debug>(stream: &mut (impl Stream<Item = Result<T, E>> + Unpin)) -> T {
+    tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("Timed out waiting for the stream")
+        .expect("Stream ended")
+        .unwrap()
+}
+
+fn assert_user_transaction(txn: Transaction, sender: AccountAddress, sequence_number: u64) {
+    match txn {
+        Transaction::UserTransaction(txn) => {
+            assert_eq!(AccountAddress::from(txn.request.sender), sender);
+            assert_eq!(txn.request.sequence_number.0, sequence_number);
+        }
+        txn => panic!("Expected a user transaction, got {:?}", txn),
+    }
+}
@@ -18,7 +18,7 @@ use aptos_types::{
     utility_coin::APTOS_COIN_TYPE,
 };
 
-use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519Signature};
 use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
@@ -569,6 +569,120 @@ async fn test_signing_message_with_payload(
     assert_eq!(ledger["ledger_version"].as_str().unwrap(), "3"); // metadata + user txn + state checkpoint
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_signing_message_with_multi_agent_request() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let secondary = context.gen_account();
+    let mut root_account = context.root_account();
+
+    // Create secondary signer account
+    context
+        .commit_block(&[context.create_user_account_by(&mut root_account, &secondary)])
+        .await;
+
+    let mut request = context
+        .transaction_factory()
+        .create_user_account(account.public_key())
+        .sender(root_account.address())
+        .sequence_number(root_account.sequence_number())
+        .build_multi_agent(vec![secondary.address()])
+        .unwrap();
+    let raw_txn = request.raw_transaction().clone();
+    let resp = context
+        .post(
+            "/transactions/encode_submission",
+            json!({
+                "sender": root_account.address().to_hex_literal(),
+                "sequence_number": raw_txn.sequence_number().to_string(),
+                "gas_unit_price": raw_txn.gas_unit_price().to_string(),
+                "max_gas_amount": raw_txn.max_gas_amount().to_string(),
+                "expiration_timestamp_secs": raw_txn.expiration_timestamp_secs().to_string(),
+                "payload": {
+                    "type": "entry_function_payload",
+                    "function": "0x1::aptos_account::create_account",
+                    "type_arguments": [],
+                    "arguments": [
+                        account.address().to_hex_literal(),
+                    ]
+                },
+                "secondary_signers": [
+                    secondary.address().to_hex_literal(),
+                ],
+            }),
+        )
+        .await;
+    let signing_msg = context
+        .api_specific_config
+        .unwrap_signing_message_response(resp);
+    assert_eq!(signing_msg.inner(), &request.signing_message().unwrap()[..]);
+
+    // Each party signs separately, the request can be passed around serialized
+    secondary.sign_multi_agent_request(&mut request).unwrap();
+    assert_eq!(request.missing_signers(), vec![root_account.address()]);
+    let mut request = bcs::from_bytes(&bcs::to_bytes(&request).unwrap()).unwrap();
+    root_account.sign_multi_agent_request(&mut request).unwrap();
+    let txn = request.into_signed_transaction().unwrap();
+
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_gas_profile() {
+    let context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let root_account = context.root_account();
+
+    let raw_txn = context
+        .transaction_factory()
+        .create_user_account(account.public_key())
+        .sender(root_account.address())
+        .sequence_number(root_account.sequence_number())
+        .build();
+    // Simulated transactions must not have a valid signature
+    let txn = SignedTransaction::new(
+        raw_txn,
+        root_account.public_key().clone(),
+        Ed25519Signature::try_from(&[0u8; 64][..]).unwrap(),
+    );
+
+    let resp = context
+        .expect_status_code(200)
+        .post_bcs_txn(
+            "/transactions/simulate/gas_profile",
+            bcs::to_bytes(&txn).unwrap(),
+        )
+        .await;
+    assert_eq!(resp["success"], json!(true), "{}", pretty(&resp));
+
+    let parse_gas = |value: &serde_json::Value| value.as_str().unwrap().parse::<u64>().unwrap();
+    let call_stacks = resp["call_stacks"].as_array().unwrap();
+    assert!(call_stacks
+        .iter()
+        .any(|entry| entry["name"] == "0x1::aptos_account::create_account;intrinsic"));
+    assert!(call_stacks.iter().any(|entry| entry["name"]
+        .as_str()
+        .unwrap()
+        .starts_with("0x1::aptos_account::create_account;0x1::account::create_account")));
+    assert!(!resp["instructions"].as_array().unwrap().is_empty());
+    assert!(!resp["natives"].as_array().unwrap().is_empty());
+    assert!(!resp["storage_writes"].as_array().unwrap().is_empty());
+
+    // The call stacks account for all the gas used
+    let total: u64 = call_stacks
+        .iter()
+        .map(|entry| parse_gas(&entry["gas"]))
+        .sum();
+    let scaling_factor = parse_gas(&resp["gas_unit_scaling_factor"]);
+    assert_eq!(
+        (total + scaling_factor - 1) / scaling_factor,
+        parse_gas(&resp["gas_used"])
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_transactions() {
     let mut context = new_test_context(current_function_name!());
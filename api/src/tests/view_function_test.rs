@@ -0,0 +1,110 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::current_function_name;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_function() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let root = context.root_account().address().to_hex_literal();
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::account::get_sequence_number",
+                "type_arguments": [],
+                "arguments": [root],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!(["1"]));
+
+    let resp = context
+        .post(
+            "/view?ledger_version=0",
+            json!({
+                "function": "0x1::account::get_sequence_number",
+                "type_arguments": [],
+                "arguments": [root],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!(["0"]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_generic_function() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::coin::balance",
+                "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+                "arguments": [account.address().to_hex_literal()],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!(["0"]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_function_not_public() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::account::create_account_unchecked",
+                "type_arguments": [],
+                "arguments": ["0x1"],
+            }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_function_with_signer() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::account::rotate_authentication_key",
+                "type_arguments": [],
+                "arguments": [],
+            }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_function_aborts() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::account::get_sequence_number",
+                "type_arguments": [],
+                "arguments": ["0xA550C19"],
+            }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
@@ -22,11 +22,11 @@ use crate::{
 use anyhow::{anyhow, Context as AnyhowContext};
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
-    TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
-    MAX_RECURSIVE_TYPES_ALLOWED, U64,
+    AsConverter, EncodeSubmissionRequest, ExplainVMStatus, GasEstimation, GasEstimationBcs,
+    GasProfile, HashValue, HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction,
+    SubmitTransactionRequest, Transaction, TransactionData, TransactionOnChainData,
+    TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult, UserTransaction,
+    VerifyInput, VerifyInputWithRecursion, MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
 use aptos_types::{
@@ -526,6 +526,45 @@ impl TransactionsApi {
             .await
     }
 
+    /// Profile the gas of a simulated transaction
+    ///
+    /// Simulates a transaction like `/transactions/simulate`, but returns where its gas went
+    /// rather than its outputs: the gas charged by each call stack, type of instruction, native
+    /// function and storage write.  The call stacks are in the folded format of flamegraph tools.
+    ///
+    /// The same rules as `/transactions/simulate` apply to the submitted transaction, in
+    /// particular it must not have a valid signature.
+    #[oai(
+        path = "/transactions/simulate/gas_profile",
+        method = "post",
+        operation_id = "simulate_transaction_gas_profile",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn simulate_transaction_gas_profile(
+        &self,
+        accept_type: AcceptType,
+        data: SubmitTransactionPost,
+    ) -> SimulateTransactionResult<GasProfile> {
+        data.verify()
+            .context("Simulated transaction invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+        fail_point_poem("endpoint_simulate_transaction_gas_profile")?;
+        self.context
+            .check_api_output_enabled("Simulate transaction gas profile", &accept_type)?;
+        if !self.context.node_config.api.transaction_simulation_enabled {
+            return Err(api_disabled("Simulate transaction gas profile"));
+        }
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let signed_transaction = self.get_signed_transaction(&ledger_info, data)?;
+
+        self.simulate_gas_profile(&accept_type, ledger_info, signed_transaction)
+    }
+
     /// Encode submission
     ///
     /// This endpoint accepts an EncodeSubmissionRequest, which internally is a
@@ -1152,13 +1191,7 @@ impl TransactionsApi {
         // to apply deltas, we should propagate errors properly. Fix this when
         // VM error handling is fixed.
         let output = output_ext.into_transaction_output(&move_resolver);
-
-        // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
-        let exe_status = match output.status().clone() {
-            TransactionStatus::Keep(exec_status) => exec_status,
-            TransactionStatus::Discard(status) => ExecutionStatus::MiscellaneousError(Some(status)),
-            _ => ExecutionStatus::MiscellaneousError(None),
-        };
+        let exe_status = simulated_execution_status(output.status());
 
         // Build up a transaction from the outputs
         // All state hashes are invalid, and will be filled with 0s
@@ -1214,6 +1247,47 @@ impl TransactionsApi {
         }
     }
 
+    /// Simulate a transaction in the VM, recording where its gas goes
+    pub fn simulate_gas_profile(
+        &self,
+        accept_type: &AcceptType,
+        ledger_info: LedgerInfo,
+        txn: SignedTransaction,
+    ) -> SimulateTransactionResult<GasProfile> {
+        // Transactions shouldn't have a valid signature or this could be used to attack
+        if txn.signature_is_valid() {
+            return Err(SubmitTransactionError::bad_request_with_code(
+                "Simulated transactions must have a non-valid signature",
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let move_resolver = self.context.move_resolver_poem(&ledger_info)?;
+        let (_, output_ext, gas_profile) =
+            AptosVM::simulate_signed_transaction_with_gas_profile(&txn, &move_resolver);
+        let output = output_ext.into_transaction_output(&move_resolver);
+        let exe_status = simulated_execution_status(output.status());
+        let vm_status = move_resolver
+            .as_converter(self.context.db.clone())
+            .explain_vm_status(&exe_status);
+        let gas_profile = GasProfile::new(
+            gas_profile,
+            exe_status.is_success(),
+            vm_status,
+            output.gas_used(),
+        );
+
+        match accept_type {
+            AcceptType::Json => {
+                BasicResponse::try_from_json((gas_profile, &ledger_info, BasicResponseStatus::Ok))
+            }
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((gas_profile, &ledger_info, BasicResponseStatus::Ok))
+            }
+        }
+    }
+
     /// Encode message as BCS
     pub fn get_signing_message(
         &self,
@@ -1292,3 +1366,13 @@ fn override_gas_parameters(
     // TODO: Check that signature is null, this would just be helpful for downstream use
     SignedTransaction::new_with_authenticator(raw_txn, signed_txn.authenticator())
 }
+
+/// Ensure that all known statuses return their values in the output of a simulation (even if
+/// they aren't supposed to)
+fn simulated_execution_status(status: &TransactionStatus) -> ExecutionStatus {
+    match status.clone() {
+        TransactionStatus::Keep(exec_status) => exec_status,
+        TransactionStatus::Discard(status) => ExecutionStatus::MiscellaneousError(Some(status)),
+        _ => ExecutionStatus::MiscellaneousError(None),
+    }
+}
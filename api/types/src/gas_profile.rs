@@ -0,0 +1,88 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+use aptos_gas::{render_flamegraph, InternalGas};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Where the gas of a simulated transaction went
+///
+/// Gas amounts of the breakdowns are in internal gas units, `gas_unit_scaling_factor` of which
+/// make one gas unit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct GasProfile {
+    /// Whether the transaction succeeded
+    pub success: bool,
+    /// The VM status of the transaction
+    pub vm_status: String,
+    /// Gas used by the transaction, in gas units
+    pub gas_used: U64,
+    pub gas_unit_scaling_factor: U64,
+    /// Gas charged by each call stack itself, excluding the calls it made
+    ///
+    /// The frames of a stack are separated by `;`, from the bottom of the stack, as in the folded
+    /// format of flamegraph tools.
+    pub call_stacks: Vec<GasProfileEntry>,
+    /// Gas charged by each type of instruction
+    pub instructions: Vec<GasProfileEntry>,
+    /// Gas charged by each native function
+    pub natives: Vec<GasProfileEntry>,
+    /// Gas charged for writing each state item
+    pub storage_writes: Vec<GasProfileEntry>,
+}
+
+/// Gas charged by one item of a [`GasProfile`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct GasProfileEntry {
+    pub name: String,
+    pub gas: U64,
+}
+
+impl GasProfile {
+    pub fn new(
+        profile: aptos_gas::GasProfile,
+        success: bool,
+        vm_status: String,
+        gas_used: u64,
+    ) -> Self {
+        fn entries(gas: BTreeMap<String, InternalGas>) -> Vec<GasProfileEntry> {
+            gas.into_iter()
+                .map(|(name, gas)| GasProfileEntry {
+                    name,
+                    gas: u64::from(gas).into(),
+                })
+                .collect()
+        }
+
+        Self {
+            success,
+            vm_status,
+            gas_used: gas_used.into(),
+            gas_unit_scaling_factor: profile.gas_unit_scaling_factor.into(),
+            call_stacks: entries(profile.call_stacks),
+            instructions: entries(profile.instructions),
+            natives: entries(profile.natives),
+            storage_writes: entries(profile.storage_writes),
+        }
+    }
+
+    /// The call stacks in the folded format, one stack per line followed by its gas
+    pub fn to_folded_stacks(&self) -> String {
+        self.call_stacks
+            .iter()
+            .map(|entry| format!("{} {}\n", entry.name, entry.gas))
+            .collect()
+    }
+
+    /// The call stacks rendered as an SVG flamegraph
+    pub fn to_flamegraph(&self, title: &str) -> String {
+        render_flamegraph(
+            title,
+            self.call_stacks
+                .iter()
+                .map(|entry| (entry.name.as_str(), entry.gas.0)),
+        )
+    }
+}
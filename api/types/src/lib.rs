@@ -8,14 +8,17 @@ mod bytecode;
 mod convert;
 mod derives;
 mod error;
+mod gas_profile;
 mod hash;
 mod headers;
 mod index;
 mod ledger_info;
 pub mod mime_types;
 mod move_types;
+mod state_change;
 mod table;
 mod transaction;
+mod view;
 mod wrappers;
 
 pub use account::AccountData;
@@ -24,6 +27,7 @@ pub use block::{BcsBlock, Block};
 pub use bytecode::Bytecode;
 pub use convert::{new_vm_utf8_string, AsConverter, ExplainVMStatus, MoveConverter};
 pub use error::{AptosError, AptosErrorCode};
+pub use gas_profile::{GasProfile, GasProfileEntry};
 pub use hash::HashValue;
 pub use headers::*;
 pub use index::{IndexResponse, IndexResponseBcs};
@@ -37,6 +41,7 @@ pub use move_types::{
 };
 use serde::{Deserialize, Deserializer};
 use std::str::FromStr;
+pub use state_change::StateValueChange;
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
     AccountSignature, BlockMetadataTransaction, DeleteModule, DeleteResource, DeleteTableItem,
@@ -49,6 +54,7 @@ pub use transaction::{
     UserCreateSigningMessageRequest, UserTransaction, UserTransactionRequest, VersionedEvent,
     WriteModule, WriteResource, WriteSet, WriteSetChange, WriteSetPayload, WriteTableItem,
 };
+pub use view::{ViewFunction, ViewRequest};
 pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
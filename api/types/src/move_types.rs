@@ -589,6 +589,32 @@ impl MoveType {
             MoveType::Unparsable(string) => string.to_string(),
         }
     }
+
+    /// Replaces generic type params with the matching entry of `type_args`
+    pub fn instantiate(&self, type_args: &[MoveType]) -> anyhow::Result<MoveType> {
+        Ok(match self {
+            MoveType::GenericTypeParam { index } => type_args
+                .get(*index as usize)
+                .cloned()
+                .ok_or_else(|| format_err!("missing type argument for generic T{}", index))?,
+            MoveType::Vector { items } => MoveType::Vector {
+                items: Box::new(items.instantiate(type_args)?),
+            },
+            MoveType::Struct(struct_tag) => MoveType::Struct(MoveStructTag {
+                generic_type_params: struct_tag
+                    .generic_type_params
+                    .iter()
+                    .map(|param| param.instantiate(type_args))
+                    .collect::<anyhow::Result<_>>()?,
+                ..struct_tag.clone()
+            }),
+            MoveType::Reference { mutable, to } => MoveType::Reference {
+                mutable: *mutable,
+                to: Box::new(to.instantiate(type_args)?),
+            },
+            other => other.clone(),
+        })
+    }
 }
 
 impl fmt::Display for MoveType {
@@ -1251,6 +1277,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_instantiate_move_type() {
+        let generic = MoveType::Vector {
+            items: Box::new(MoveType::Struct(MoveStructTag::new(
+                address("0x1").into(),
+                identifier("coin").into(),
+                identifier("Coin").into(),
+                vec![MoveType::GenericTypeParam { index: 0 }],
+            ))),
+        };
+        let instantiated = generic
+            .instantiate(&["0x1::aptos_coin::AptosCoin".parse().unwrap()])
+            .unwrap();
+        assert_eq!(
+            instantiated.to_string(),
+            "vector<0x1::coin::Coin<0x1::aptos_coin::AptosCoin>>"
+        );
+        assert!(generic.instantiate(&[]).is_err());
+    }
+
     #[test]
     fn test_serialize_move_resource() {
         use AnnotatedMoveValue::*;
@@ -0,0 +1,41 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{HashValue, HexEncodedBytes};
+use anyhow::Context;
+use aptos_crypto::hash::CryptoHash;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// A state item whose value differs between two ledger versions
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateValueChange {
+    /// The BCS encoded state key
+    pub state_key: HexEncodedBytes,
+    /// The hash of the state key, which changes are ordered by
+    pub state_key_hash: HashValue,
+    /// The value at the older version, if the state item existed then
+    pub old_value: Option<HexEncodedBytes>,
+    /// The value at the newer version, if the state item still exists
+    pub new_value: Option<HexEncodedBytes>,
+}
+
+impl TryFrom<aptos_types::state_store::state_value::StateValueChange> for StateValueChange {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        change: aptos_types::state_store::state_value::StateValueChange,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            state_key: change
+                .state_key
+                .encode()
+                .context("Failed to encode StateKey")?
+                .into(),
+            state_key_hash: change.state_key.hash().into(),
+            old_value: change.old_value.map(|value| value.into_bytes().into()),
+            new_value: change.new_value.map(|value| value.into_bytes().into()),
+        })
+    }
+}
@@ -1,13 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{format_err, Result};
+use anyhow::{bail, format_err, Result};
 use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters, LATEST_GAS_FEATURE_VERSION};
 use aptos_resource_viewer::{AnnotatedAccountStateBlob, AptosValueAnnotator};
 use aptos_rest_client::Client;
 use aptos_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
     chain_id::ChainId,
+    state_store::{state_key::StateKey, state_value::StateValueChange},
     transaction::{ChangeSet, Transaction, TransactionOutput, Version},
 };
 use aptos_validator_interface::{
@@ -18,16 +20,24 @@ use aptos_vm::{
     move_vm_ext::{MoveVmExt, SessionExt, SessionId},
     AptosVM, VMExecutor,
 };
-use move_binary_format::errors::VMResult;
-use std::{path::Path, sync::Arc};
+use move_binary_format::{errors::VMResult, CompiledModule};
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+mod replay;
+
+pub use replay::{ModuleOverrideStateView, ReplayedTransaction};
 
 pub struct AptosDebugger {
     debugger: Arc<dyn AptosValidatorInterface + Send>,
+    module_overrides: HashMap<StateKey, Vec<u8>>,
 }
 
 impl AptosDebugger {
     pub fn new(debugger: Arc<dyn AptosValidatorInterface + Send>) -> Self {
-        Self { debugger }
+        Self {
+            debugger,
+            module_overrides: HashMap::new(),
+        }
     }
 
     pub fn rest_client(rest_client: Client) -> Result<Self> {
@@ -40,12 +50,30 @@ impl AptosDebugger {
         )?)))
     }
 
+    /// Executes transactions with the given modules in place of the ones on chain, e.g. to test
+    /// a locally built framework against past transactions
+    pub fn with_module_overrides(
+        mut self,
+        modules: impl IntoIterator<Item = CompiledModule>,
+    ) -> Result<Self> {
+        for module in modules {
+            let mut blob = vec![];
+            module.serialize(&mut blob)?;
+            self.module_overrides.insert(
+                StateKey::AccessPath(AccessPath::code_access_path(module.self_id())),
+                blob,
+            );
+        }
+        Ok(self)
+    }
+
     pub fn execute_transactions_at_version(
         &self,
         version: Version,
         txns: Vec<Transaction>,
     ) -> Result<Vec<TransactionOutput>> {
         let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let state_view = ModuleOverrideStateView::new(&state_view, &self.module_overrides);
         AptosVM::execute_block(txns, &state_view)
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
@@ -76,6 +104,42 @@ impl AptosDebugger {
         Ok(ret)
     }
 
+    /// Executes the transactions in `[begin, begin + limit)` again, pairing each output with the
+    /// one committed on chain
+    pub async fn replay_past_transactions(
+        &self,
+        mut begin: Version,
+        limit: u64,
+    ) -> Result<Vec<ReplayedTransaction>> {
+        let end = begin
+            .checked_add(limit)
+            .ok_or_else(|| format_err!("Version range overflows"))?;
+        let mut ret = vec![];
+        while begin < end {
+            let (txns, expected_outputs): (Vec<_>, Vec<_>) = self
+                .debugger
+                .get_committed_transactions_with_outputs(begin, end - begin)
+                .await?
+                .into_iter()
+                .unzip();
+            if txns.is_empty() {
+                bail!("No committed transaction at version {}", begin);
+            }
+            // Execution stops at the end of an epoch, the rest is fetched again with the state of
+            // the new epoch.
+            let outputs = self.execute_transactions_by_epoch(begin, txns).await?;
+            for (actual, expected) in outputs.into_iter().zip(expected_outputs) {
+                ret.push(ReplayedTransaction {
+                    version: begin,
+                    expected,
+                    actual,
+                });
+                begin += 1;
+            }
+        }
+        Ok(ret)
+    }
+
     pub async fn execute_transactions_by_epoch(
         &self,
         begin: Version,
@@ -137,6 +201,29 @@ impl AptosDebugger {
         self.debugger.get_latest_version().await
     }
 
+    /// All state items whose values differ between `from_version` and `to_version`, fetched a
+    /// page of `page_size` changes at a time
+    pub async fn get_state_value_changes(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        page_size: u64,
+    ) -> Result<Vec<StateValueChange>> {
+        let mut ret = vec![];
+        let mut start = None;
+        loop {
+            let (mut changes, next) = self
+                .debugger
+                .get_state_value_changes(from_version, to_version, start.as_ref(), page_size)
+                .await?;
+            ret.append(&mut changes);
+            match next {
+                Some(key) => start = Some(key),
+                None => return Ok(ret),
+            }
+        }
+    }
+
     pub async fn get_version_by_account_sequence(
         &self,
         account: AccountAddress,
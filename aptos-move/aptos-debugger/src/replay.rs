@@ -0,0 +1,268 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_state_view::StateView;
+use aptos_types::{
+    state_store::{state_key::StateKey, state_storage_usage::StateStorageUsage},
+    transaction::{TransactionOutput, Version},
+    write_set::WriteOp,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A past transaction which was executed again, along with its output on chain
+pub struct ReplayedTransaction {
+    pub version: Version,
+    pub expected: TransactionOutput,
+    pub actual: TransactionOutput,
+}
+
+impl ReplayedTransaction {
+    /// Human readable differences between the replayed output and the one on chain, empty if the
+    /// replay reproduced the transaction exactly
+    pub fn diff(&self) -> Vec<String> {
+        let mut differences = vec![];
+        if self.expected.status() != self.actual.status() {
+            differences.push(format!(
+                "status: expected {:?}, got {:?}",
+                self.expected.status(),
+                self.actual.status()
+            ));
+        }
+        if self.expected.gas_used() != self.actual.gas_used() {
+            differences.push(format!(
+                "gas used: expected {}, got {}",
+                self.expected.gas_used(),
+                self.actual.gas_used()
+            ));
+        }
+
+        let expected_writes: BTreeMap<_, _> = self.expected.write_set().iter().collect();
+        let actual_writes: BTreeMap<_, _> = self.actual.write_set().iter().collect();
+        let keys: BTreeSet<_> = expected_writes
+            .keys()
+            .chain(actual_writes.keys())
+            .copied()
+            .collect();
+        for key in keys {
+            let expected = expected_writes.get(key).copied();
+            let actual = actual_writes.get(key).copied();
+            if expected != actual {
+                differences.push(format!(
+                    "write to {:?}: expected {}, got {}",
+                    key,
+                    describe_write(expected),
+                    describe_write(actual)
+                ));
+            }
+        }
+
+        let expected_events = self.expected.events();
+        let actual_events = self.actual.events();
+        if expected_events.len() != actual_events.len() {
+            differences.push(format!(
+                "events: expected {}, got {}",
+                expected_events.len(),
+                actual_events.len()
+            ));
+        }
+        for (index, (expected, actual)) in expected_events.iter().zip(actual_events).enumerate() {
+            if expected != actual {
+                differences.push(format!(
+                    "event {}: expected {:?}, got {:?}",
+                    index, expected, actual
+                ));
+            }
+        }
+        differences
+    }
+}
+
+fn describe_write(write: Option<&WriteOp>) -> String {
+    match write {
+        None => "no write".to_string(),
+        Some(WriteOp::Creation(blob)) => format!("creation of 0x{}", hex::encode(blob)),
+        Some(WriteOp::Modification(blob)) => format!("modification to 0x{}", hex::encode(blob)),
+        Some(WriteOp::Deletion) => "deletion".to_string(),
+    }
+}
+
+/// A state view which replaces some of the modules of the underlying state, so that past
+/// transactions can be replayed against locally built code
+pub struct ModuleOverrideStateView<'a, S> {
+    base: &'a S,
+    overrides: &'a HashMap<StateKey, Vec<u8>>,
+}
+
+impl<'a, S: StateView> ModuleOverrideStateView<'a, S> {
+    pub fn new(base: &'a S, overrides: &'a HashMap<StateKey, Vec<u8>>) -> Self {
+        Self { base, overrides }
+    }
+}
+
+impl<'a, S: StateView> StateView for ModuleOverrideStateView<'a, S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        match self.overrides.get(state_key) {
+            Some(blob) => Ok(Some(blob.clone())),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        account_address::AccountAddress,
+        contract_event::ContractEvent,
+        event::EventKey,
+        transaction::{ExecutionStatus, TransactionStatus},
+        write_set::{WriteSet, WriteSetMut},
+    };
+    use move_core_types::language_storage::TypeTag;
+
+    fn output(
+        writes: Vec<(StateKey, WriteOp)>,
+        events: Vec<ContractEvent>,
+        gas_used: u64,
+        status: ExecutionStatus,
+    ) -> TransactionOutput {
+        TransactionOutput::new(
+            WriteSetMut::new(writes).freeze().unwrap(),
+            events,
+            gas_used,
+            TransactionStatus::Keep(status),
+        )
+    }
+
+    fn event(sequence_number: u64, data: Vec<u8>) -> ContractEvent {
+        ContractEvent::new(
+            EventKey::new(0, AccountAddress::ONE),
+            sequence_number,
+            TypeTag::U64,
+            data,
+        )
+    }
+
+    fn key(name: &str) -> StateKey {
+        StateKey::Raw(name.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_diff_of_identical_outputs() {
+        let make_output = || {
+            output(
+                vec![(key("a"), WriteOp::Modification(vec![1]))],
+                vec![event(0, vec![2])],
+                10,
+                ExecutionStatus::Success,
+            )
+        };
+        let replayed = ReplayedTransaction {
+            version: 5,
+            expected: make_output(),
+            actual: make_output(),
+        };
+        assert!(replayed.diff().is_empty());
+    }
+
+    #[test]
+    fn test_diff_of_different_outputs() {
+        let replayed = ReplayedTransaction {
+            version: 5,
+            expected: output(
+                vec![
+                    (key("a"), WriteOp::Modification(vec![1])),
+                    (key("b"), WriteOp::Deletion),
+                    (key("c"), WriteOp::Creation(vec![3])),
+                ],
+                vec![event(0, vec![1]), event(1, vec![2])],
+                10,
+                ExecutionStatus::Success,
+            ),
+            actual: output(
+                vec![
+                    (key("a"), WriteOp::Modification(vec![1])),
+                    (key("c"), WriteOp::Creation(vec![4])),
+                    (key("d"), WriteOp::Deletion),
+                ],
+                vec![event(0, vec![3])],
+                12,
+                ExecutionStatus::OutOfGas,
+            ),
+        };
+        assert_eq!(
+            replayed.diff(),
+            vec![
+                "status: expected Keep(Success), got Keep(OutOfGas)".to_string(),
+                "gas used: expected 10, got 12".to_string(),
+                format!("write to {:?}: expected deletion, got no write", key("b")),
+                format!(
+                    "write to {:?}: expected creation of 0x03, got creation of 0x04",
+                    key("c")
+                ),
+                format!("write to {:?}: expected no write, got deletion", key("d")),
+                "events: expected 2, got 1".to_string(),
+                format!(
+                    "event 0: expected {:?}, got {:?}",
+                    event(0, vec![1]),
+                    event(0, vec![3])
+                ),
+            ]
+        );
+    }
+
+    struct MockStateView(HashMap<StateKey, Vec<u8>>);
+
+    impl StateView for MockStateView {
+        fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+            Ok(self.0.get(state_key).cloned())
+        }
+
+        fn is_genesis(&self) -> bool {
+            false
+        }
+
+        fn get_usage(&self) -> Result<StateStorageUsage> {
+            Ok(StateStorageUsage::new(self.0.len(), 3))
+        }
+    }
+
+    #[test]
+    fn test_module_override_state_view() {
+        let base = MockStateView(HashMap::from([
+            (key("module"), vec![1]),
+            (key("resource"), vec![2]),
+        ]));
+        let overrides = HashMap::from([(key("module"), vec![3]), (key("new_module"), vec![4])]);
+        let state_view = ModuleOverrideStateView::new(&base, &overrides);
+
+        // Overrides shadow the base state, everything else is read through
+        assert_eq!(
+            state_view.get_state_value(&key("module")).unwrap(),
+            Some(vec![3])
+        );
+        assert_eq!(
+            state_view.get_state_value(&key("new_module")).unwrap(),
+            Some(vec![4])
+        );
+        assert_eq!(
+            state_view.get_state_value(&key("resource")).unwrap(),
+            Some(vec![2])
+        );
+        assert_eq!(state_view.get_state_value(&key("missing")).unwrap(), None);
+        assert_eq!(
+            state_view.get_usage().unwrap(),
+            StateStorageUsage::new(2, 3)
+        );
+        assert!(!state_view.is_genesis());
+    }
+}
@@ -165,6 +165,23 @@ impl InitialGasSchedule for AptosGasParameters {
     }
 }
 
+/// The charges of a transaction which happen outside of the Move VM, on top of the ones of
+/// [`GasMeter`].  The Aptos VM is generic over this trait, so the official gas meter can be
+/// wrapped, e.g. by the [`GasProfiler`](crate::GasProfiler).
+pub trait GasMeterExt: GasMeter {
+    /// Remaining gas, in external gas units.
+    fn balance(&self) -> Gas;
+
+    fn feature_version(&self) -> u64;
+
+    fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
+
+    fn charge_write_set_gas<'a>(
+        &mut self,
+        ops: impl IntoIterator<Item = (&'a StateKey, &'a WriteOp)>,
+    ) -> VMResult<()>;
+}
+
 /// The official gas meter used inside the Aptos VM.
 /// It maintains an internal gas counter, measured in internal gas units, and carries an environment
 /// consisting all the gas parameters, which it can lookup when performing gas calculations.
@@ -204,9 +221,12 @@ impl AptosGasMeter {
         }
     }
 
-    pub fn balance(&self) -> Gas {
+    pub fn balance_internal(&self) -> InternalGas {
         self.balance
-            .to_unit_round_down_with_params(&self.gas_params.txn)
+    }
+
+    pub fn gas_params(&self) -> &AptosGasParameters {
+        &self.gas_params
     }
 
     #[inline]
@@ -247,10 +267,6 @@ impl AptosGasMeter {
             self.memory_quota += amount;
         }
     }
-
-    pub fn feature_version(&self) -> u64 {
-        self.feature_version
-    }
 }
 
 impl GasMeter for AptosGasMeter {
@@ -747,13 +763,22 @@ impl GasMeter for AptosGasMeter {
     }
 }
 
-impl AptosGasMeter {
-    pub fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
+impl GasMeterExt for AptosGasMeter {
+    fn balance(&self) -> Gas {
+        self.balance
+            .to_unit_round_down_with_params(&self.gas_params.txn)
+    }
+
+    fn feature_version(&self) -> u64 {
+        self.feature_version
+    }
+
+    fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
         let cost = self.gas_params.txn.calculate_intrinsic_gas(txn_size);
         self.charge(cost).map_err(|e| e.finish(Location::Undefined))
     }
 
-    pub fn charge_write_set_gas<'a>(
+    fn charge_write_set_gas<'a>(
         &mut self,
         ops: impl IntoIterator<Item = (&'a StateKey, &'a WriteOp)>,
     ) -> VMResult<()> {
@@ -0,0 +1,712 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module contains a gas meter which records where the gas of a transaction goes, by wrapping
+//! the official gas meter, along with a renderer for the resulting call stacks.
+//!
+//! The profiler charges exactly what the official gas meter charges, so a profiled execution uses
+//! the same amount of gas as a regular one, it only costs more time and memory to run.
+
+use crate::{
+    algebra::Gas,
+    gas_meter::{AptosGasMeter, GasMeterExt},
+};
+use aptos_types::{access_path::Path, state_store::state_key::StateKey, write_set::WriteOp};
+use move_binary_format::errors::{PartialVMResult, VMResult};
+use move_core_types::{
+    gas_algebra::{InternalGas, NumArgs, NumBytes},
+    language_storage::ModuleId,
+};
+use move_vm_types::{
+    gas::{GasMeter, SimpleInstruction},
+    views::{TypeView, ValueView},
+};
+use std::collections::BTreeMap;
+
+/// Name of the frame under which the intrinsic gas of the transaction is recorded
+const INTRINSIC_FRAME: &str = "intrinsic";
+/// Name of the frame under which the storage writes of the transaction are recorded
+const STORAGE_WRITES_FRAME: &str = "storage_writes";
+
+/// Gas charged by a transaction, broken down by call stack, instruction, native function and
+/// storage write.
+///
+/// All amounts are in internal gas units, `gas_unit_scaling_factor` of which make one gas unit.
+#[derive(Clone, Debug, Default)]
+pub struct GasProfile {
+    pub gas_unit_scaling_factor: u64,
+    /// Gas charged by each call stack itself, excluding the calls it made.  Stacks are keyed by
+    /// their frames separated by `;`, from the bottom of the stack.
+    pub call_stacks: BTreeMap<String, InternalGas>,
+    /// Gas charged by each type of instruction, across all frames
+    pub instructions: BTreeMap<String, InternalGas>,
+    /// Gas charged by each native function, across all frames
+    pub natives: BTreeMap<String, InternalGas>,
+    /// Gas charged for writing each state item, once execution is done
+    pub storage_writes: BTreeMap<String, InternalGas>,
+}
+
+impl GasProfile {
+    /// Total gas charged by the transaction
+    pub fn total(&self) -> InternalGas {
+        self.call_stacks
+            .values()
+            .fold(InternalGas::zero(), |acc, gas| acc + *gas)
+    }
+
+    /// The call stacks in the folded format, one stack per line followed by its gas, which is
+    /// the input of most flamegraph tools.
+    pub fn to_folded_stacks(&self) -> String {
+        self.call_stacks
+            .iter()
+            .map(|(stack, gas)| format!("{} {}\n", stack, u64::from(*gas)))
+            .collect()
+    }
+
+    /// The call stacks rendered as an SVG flamegraph
+    pub fn to_flamegraph(&self, title: &str) -> String {
+        render_flamegraph(
+            title,
+            self.call_stacks
+                .iter()
+                .map(|(stack, gas)| (stack.as_str(), u64::from(*gas))),
+        )
+    }
+}
+
+/// A frame of the call tree, with the gas charged by the frame itself
+struct Frame {
+    name: String,
+    gas: InternalGas,
+    children: BTreeMap<String, usize>,
+}
+
+/// A gas meter which records the gas charged by the [`AptosGasMeter`] it wraps.
+///
+/// Function frames are tracked from the calls charged by the Move VM, so the frame at the bottom
+/// of the stack, which the VM enters without a call, is named by the caller.
+pub struct GasProfiler {
+    base: AptosGasMeter,
+
+    frames: Vec<Frame>,
+    stack: Vec<usize>,
+
+    instructions: BTreeMap<String, InternalGas>,
+    natives: BTreeMap<String, InternalGas>,
+    storage_writes: BTreeMap<String, InternalGas>,
+}
+
+impl GasProfiler {
+    /// Wraps `base`, `root` being the name of the bottom frame, e.g. the entry function of the
+    /// transaction.
+    pub fn new(base: AptosGasMeter, root: String) -> Self {
+        Self {
+            base,
+            frames: vec![Frame {
+                name: root,
+                gas: InternalGas::zero(),
+                children: BTreeMap::new(),
+            }],
+            stack: vec![0],
+            instructions: BTreeMap::new(),
+            natives: BTreeMap::new(),
+            storage_writes: BTreeMap::new(),
+        }
+    }
+
+    pub fn into_profile(self) -> GasProfile {
+        let mut call_stacks = BTreeMap::new();
+        self.fold_call_stacks(0, "", &mut call_stacks);
+
+        GasProfile {
+            gas_unit_scaling_factor: u64::from(self.base.gas_params().txn.gas_unit_scaling_factor),
+            call_stacks,
+            instructions: self.instructions,
+            natives: self.natives,
+            storage_writes: self.storage_writes,
+        }
+    }
+
+    fn fold_call_stacks(
+        &self,
+        index: usize,
+        prefix: &str,
+        call_stacks: &mut BTreeMap<String, InternalGas>,
+    ) {
+        let frame = &self.frames[index];
+        let stack = if prefix.is_empty() {
+            frame.name.clone()
+        } else {
+            format!("{};{}", prefix, frame.name)
+        };
+        for child in frame.children.values() {
+            self.fold_call_stacks(*child, &stack, call_stacks);
+        }
+        if frame.gas > InternalGas::zero() {
+            call_stacks.insert(stack, frame.gas);
+        }
+    }
+
+    fn current_frame(&self) -> usize {
+        *self.stack.last().expect("The root frame is never exited")
+    }
+
+    /// Returns the child of `parent` named `name`, creating it if needed
+    fn child_frame(&mut self, parent: usize, name: &str) -> usize {
+        if let Some(index) = self.frames[parent].children.get(name) {
+            return *index;
+        }
+        let index = self.frames.len();
+        self.frames.push(Frame {
+            name: name.to_string(),
+            gas: InternalGas::zero(),
+            children: BTreeMap::new(),
+        });
+        self.frames[parent].children.insert(name.to_string(), index);
+        index
+    }
+
+    fn enter_frame(&mut self, name: &str) {
+        let index = self.child_frame(self.current_frame(), name);
+        self.stack.push(index);
+    }
+
+    fn exit_frame(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    /// Runs `charge` against the wrapped gas meter, returning the gas it charged
+    fn metered<T>(&mut self, charge: impl FnOnce(&mut AptosGasMeter) -> T) -> (InternalGas, T) {
+        let balance = self.base.balance_internal();
+        let res = charge(&mut self.base);
+        let amount = balance
+            .checked_sub(self.base.balance_internal())
+            .unwrap_or_else(InternalGas::zero);
+        (amount, res)
+    }
+
+    /// Charges an instruction, recording its gas against the current frame
+    fn charge_instr(
+        &mut self,
+        instr: &str,
+        charge: impl FnOnce(&mut AptosGasMeter) -> PartialVMResult<()>,
+    ) -> PartialVMResult<()> {
+        let (amount, res) = self.metered(charge);
+        if amount > InternalGas::zero() {
+            let frame = self.current_frame();
+            self.frames[frame].gas += amount;
+            add_gas(&mut self.instructions, instr, amount);
+        }
+        res
+    }
+}
+
+fn add_gas(entries: &mut BTreeMap<String, InternalGas>, name: &str, amount: InternalGas) {
+    match entries.get_mut(name) {
+        Some(gas) => *gas += amount,
+        None => {
+            entries.insert(name.to_string(), amount);
+        }
+    }
+}
+
+/// A human readable name for a state item
+fn state_key_name(key: &StateKey) -> String {
+    match key {
+        StateKey::AccessPath(access_path) => match bcs::from_bytes(&access_path.path) {
+            Ok(Path::Resource(struct_tag)) => {
+                format!("{}/{}", access_path.address.to_hex_literal(), struct_tag)
+            }
+            Ok(Path::Code(module_id)) => format!("code/{}", module_id.short_str_lossless()),
+            Err(_) => access_path.to_string(),
+        },
+        StateKey::TableItem { handle, key } => {
+            format!("table/{}/{}", handle.0.to_hex_literal(), hex::encode(key))
+        }
+        StateKey::Raw(bytes) => format!("raw/{}", hex::encode(bytes)),
+    }
+}
+
+impl GasMeter for GasProfiler {
+    #[inline]
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        self.charge_instr(&format!("{:?}", instr), |base| {
+            base.charge_simple_instr(instr)
+        })
+    }
+
+    #[inline]
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.base
+            .charge_native_function_before_execution(ty_args, args)
+    }
+
+    /// Natives are entered through a call like other functions, but the VM doesn't drop their
+    /// frame, so it is exited here.
+    #[inline]
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        let (amount, res) = self.metered(|base| base.charge_native_function(amount, ret_vals));
+        let frame = self.current_frame();
+        self.frames[frame].gas += amount;
+        let name = self.frames[frame].name.clone();
+        add_gas(&mut self.natives, &name, amount);
+        self.exit_frame();
+        res
+    }
+
+    #[inline]
+    fn charge_load_resource(
+        &mut self,
+        loaded: Option<(NumBytes, impl ValueView)>,
+    ) -> PartialVMResult<()> {
+        self.charge_instr("LoadResource", |base| base.charge_load_resource(loaded))
+    }
+
+    #[inline]
+    fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.charge_instr("Pop", |base| base.charge_pop(popped_val))
+    }
+
+    #[inline]
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        let res = self.charge_instr("Call", |base| {
+            base.charge_call(module_id, func_name, args, num_locals)
+        });
+        self.enter_frame(&format!(
+            "{}::{}",
+            module_id.short_str_lossless(),
+            func_name
+        ));
+        res
+    }
+
+    #[inline]
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        let res = self.charge_instr("CallGeneric", |base| {
+            base.charge_call_generic(module_id, func_name, ty_args, args, num_locals)
+        });
+        self.enter_frame(&format!(
+            "{}::{}",
+            module_id.short_str_lossless(),
+            func_name
+        ));
+        res
+    }
+
+    #[inline]
+    fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
+        self.charge_instr("LdConst", |base| base.charge_ld_const(size))
+    }
+
+    #[inline]
+    fn charge_ld_const_after_deserialization(
+        &mut self,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge_instr("LdConst", |base| {
+            base.charge_ld_const_after_deserialization(val)
+        })
+    }
+
+    #[inline]
+    fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge_instr("CopyLoc", |base| base.charge_copy_loc(val))
+    }
+
+    #[inline]
+    fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge_instr("MoveLoc", |base| base.charge_move_loc(val))
+    }
+
+    #[inline]
+    fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge_instr("StLoc", |base| base.charge_store_loc(val))
+    }
+
+    #[inline]
+    fn charge_pack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let instr = if is_generic { "PackGeneric" } else { "Pack" };
+        self.charge_instr(instr, |base| base.charge_pack(is_generic, args))
+    }
+
+    #[inline]
+    fn charge_unpack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let instr = if is_generic {
+            "UnpackGeneric"
+        } else {
+            "Unpack"
+        };
+        self.charge_instr(instr, |base| base.charge_unpack(is_generic, args))
+    }
+
+    #[inline]
+    fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge_instr("ReadRef", |base| base.charge_read_ref(val))
+    }
+
+    #[inline]
+    fn charge_write_ref(
+        &mut self,
+        new_val: impl ValueView,
+        old_val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge_instr("WriteRef", |base| base.charge_write_ref(new_val, old_val))
+    }
+
+    #[inline]
+    fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge_instr("Eq", |base| base.charge_eq(lhs, rhs))
+    }
+
+    #[inline]
+    fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge_instr("Neq", |base| base.charge_neq(lhs, rhs))
+    }
+
+    #[inline]
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let instr = match (is_mut, is_generic) {
+            (false, false) => "ImmBorrowGlobal",
+            (false, true) => "ImmBorrowGlobalGeneric",
+            (true, false) => "MutBorrowGlobal",
+            (true, true) => "MutBorrowGlobalGeneric",
+        };
+        self.charge_instr(instr, |base| {
+            base.charge_borrow_global(is_mut, is_generic, ty, is_success)
+        })
+    }
+
+    #[inline]
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        let instr = if is_generic {
+            "ExistsGeneric"
+        } else {
+            "Exists"
+        };
+        self.charge_instr(instr, |base| base.charge_exists(is_generic, ty, exists))
+    }
+
+    #[inline]
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let instr = if is_generic {
+            "MoveFromGeneric"
+        } else {
+            "MoveFrom"
+        };
+        self.charge_instr(instr, |base| base.charge_move_from(is_generic, ty, val))
+    }
+
+    #[inline]
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let instr = if is_generic {
+            "MoveToGeneric"
+        } else {
+            "MoveTo"
+        };
+        self.charge_instr(instr, |base| {
+            base.charge_move_to(is_generic, ty, val, is_success)
+        })
+    }
+
+    #[inline]
+    fn charge_vec_pack<'a>(
+        &mut self,
+        ty: impl TypeView + 'a,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge_instr("VecPack", |base| base.charge_vec_pack(ty, args))
+    }
+
+    #[inline]
+    fn charge_vec_unpack(
+        &mut self,
+        ty: impl TypeView,
+        expect_num_elements: NumArgs,
+        elems: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge_instr("VecUnpack", |base| {
+            base.charge_vec_unpack(ty, expect_num_elements, elems)
+        })
+    }
+
+    #[inline]
+    fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge_instr("VecLen", |base| base.charge_vec_len(ty))
+    }
+
+    #[inline]
+    fn charge_vec_borrow(
+        &mut self,
+        is_mut: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let instr = if is_mut {
+            "VecMutBorrow"
+        } else {
+            "VecImmBorrow"
+        };
+        self.charge_instr(instr, |base| base.charge_vec_borrow(is_mut, ty, is_success))
+    }
+
+    #[inline]
+    fn charge_vec_push_back(
+        &mut self,
+        ty: impl TypeView,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge_instr("VecPushBack", |base| base.charge_vec_push_back(ty, val))
+    }
+
+    #[inline]
+    fn charge_vec_pop_back(
+        &mut self,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge_instr("VecPopBack", |base| base.charge_vec_pop_back(ty, val))
+    }
+
+    #[inline]
+    fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge_instr("VecSwap", |base| base.charge_vec_swap(ty))
+    }
+
+    #[inline]
+    fn charge_drop_frame(
+        &mut self,
+        locals: impl Iterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let res = self.base.charge_drop_frame(locals);
+        self.exit_frame();
+        res
+    }
+}
+
+impl GasMeterExt for GasProfiler {
+    fn balance(&self) -> Gas {
+        self.base.balance()
+    }
+
+    fn feature_version(&self) -> u64 {
+        self.base.feature_version()
+    }
+
+    fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
+        let (amount, res) =
+            self.metered(|base| base.charge_intrinsic_gas_for_transaction(txn_size));
+        let frame = self.child_frame(0, INTRINSIC_FRAME);
+        self.frames[frame].gas += amount;
+        res
+    }
+
+    /// Write set gas is linear in the number of writes, so each write is charged on its own to
+    /// know what it costs.
+    fn charge_write_set_gas<'a>(
+        &mut self,
+        ops: impl IntoIterator<Item = (&'a StateKey, &'a WriteOp)>,
+    ) -> VMResult<()> {
+        let storage_writes = self.child_frame(0, STORAGE_WRITES_FRAME);
+        for (key, op) in ops {
+            let (amount, res) =
+                self.metered(|base| base.charge_write_set_gas(std::iter::once((key, op))));
+            let name = state_key_name(key);
+            let frame = self.child_frame(storage_writes, &name);
+            self.frames[frame].gas += amount;
+            add_gas(&mut self.storage_writes, &name, amount);
+            res?;
+        }
+        Ok(())
+    }
+}
+
+const FLAMEGRAPH_WIDTH: f64 = 1200.0;
+const FLAMEGRAPH_MARGIN: f64 = 10.0;
+const FLAMEGRAPH_TITLE_HEIGHT: f64 = 30.0;
+const FRAME_HEIGHT: f64 = 16.0;
+/// Approximate width of a character in the 12px monospace font of the frames
+const CHAR_WIDTH: f64 = 7.2;
+
+/// Renders call stacks in the folded format as an SVG flamegraph.
+///
+/// Each stack is its frames separated by `;`, from the bottom of the stack, along with the gas it
+/// charged itself.  Frames are as wide as the gas charged by them and their callees, and hovering
+/// over a frame shows its gas.
+pub fn render_flamegraph<'a>(
+    title: &str,
+    stacks: impl IntoIterator<Item = (&'a str, u64)>,
+) -> String {
+    let mut root = FlameNode::default();
+    for (stack, gas) in stacks {
+        root.gas += gas;
+        let mut node = &mut root;
+        for frame in stack.split(';') {
+            node = node.children.entry(frame.to_string()).or_default();
+            node.gas += gas;
+        }
+    }
+
+    let height = FLAMEGRAPH_TITLE_HEIGHT
+        + (root.depth() + 1) as f64 * FRAME_HEIGHT
+        + 2.0 * FLAMEGRAPH_MARGIN;
+    let mut svg = format!(
+        "<?xml version=\"1.0\" standalone=\"no\"?>\n\
+         <svg version=\"1.1\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" \
+         xmlns=\"http://www.w3.org/2000/svg\" font-family=\"monospace\" font-size=\"12\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#f8f8f8\"/>\n\
+         <text x=\"{center}\" y=\"24\" text-anchor=\"middle\" font-size=\"16\">{title}</text>\n",
+        width = FLAMEGRAPH_WIDTH,
+        height = height,
+        center = FLAMEGRAPH_WIDTH / 2.0,
+        title = escape_xml(title),
+    );
+    if root.gas > 0 {
+        let layout = FlameLayout {
+            scale: (FLAMEGRAPH_WIDTH - 2.0 * FLAMEGRAPH_MARGIN) / root.gas as f64,
+            bottom: height - FLAMEGRAPH_MARGIN,
+            total: root.gas,
+        };
+        layout.draw(&mut svg, "all", &root, FLAMEGRAPH_MARGIN, 0);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[derive(Default)]
+struct FlameNode {
+    gas: u64,
+    children: BTreeMap<String, FlameNode>,
+}
+
+impl FlameNode {
+    fn depth(&self) -> usize {
+        self.children
+            .values()
+            .map(|child| child.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+struct FlameLayout {
+    scale: f64,
+    bottom: f64,
+    total: u64,
+}
+
+impl FlameLayout {
+    fn draw(&self, svg: &mut String, name: &str, node: &FlameNode, x: f64, depth: usize) {
+        let width = node.gas as f64 * self.scale;
+        // Frames narrower than this aren't visible anyway
+        if width < 0.1 {
+            return;
+        }
+        let y = self.bottom - (depth + 1) as f64 * FRAME_HEIGHT;
+        svg.push_str(&format!(
+            "<g><title>{} ({} gas, {:.2}%)</title>\
+             <rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\" rx=\"2\"/>",
+            escape_xml(name),
+            node.gas,
+            node.gas as f64 * 100.0 / self.total as f64,
+            x,
+            y,
+            width,
+            FRAME_HEIGHT - 1.0,
+            frame_color(name),
+        ));
+        let max_chars = ((width - 6.0) / CHAR_WIDTH) as usize;
+        if max_chars >= 3 {
+            let label = if name.chars().count() > max_chars {
+                format!("{}..", name.chars().take(max_chars - 2).collect::<String>())
+            } else {
+                name.to_string()
+            };
+            svg.push_str(&format!(
+                "<text x=\"{:.2}\" y=\"{:.2}\">{}</text>",
+                x + 3.0,
+                y + FRAME_HEIGHT - 4.0,
+                escape_xml(&label)
+            ));
+        }
+        svg.push_str("</g>\n");
+
+        let mut child_x = x;
+        for (child_name, child) in &node.children {
+            self.draw(svg, child_name, child, child_x, depth + 1);
+            child_x += child.gas as f64 * self.scale;
+        }
+    }
+}
+
+/// A warm color derived from the name, so that a frame has the same color in every flamegraph
+fn frame_color(name: &str) -> String {
+    let hash = name.bytes().fold(2_166_136_261u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(16_777_619)
+    });
+    format!(
+        "rgb({},{},{})",
+        205 + hash % 50,
+        100 + (hash >> 8) % 130,
+        (hash >> 16) % 55
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -4,7 +4,7 @@
 //! This crate is the core of the gas metering system of the Aptos blockchain.
 //!
 //! More specifically, it
-//!   - Is home to the gas meter implementation
+//!   - Is home to the gas meter implementation, and a profiler which wraps it
 //!   - Defines the gas parameters and formulae for instructions
 //!   - Defines the gas parameters for transactions
 //!   - Sets the initial values for all gas parameters, including the instruction, transaction
@@ -27,6 +27,7 @@ mod params;
 mod algebra;
 mod aptos_framework;
 mod gas_meter;
+mod gas_profiler;
 pub mod gen;
 mod instr;
 mod misc;
@@ -36,9 +37,10 @@ mod transaction;
 
 pub use algebra::*;
 pub use gas_meter::{
-    AptosGasMeter, AptosGasParameters, FromOnChainGasSchedule, InitialGasSchedule,
+    AptosGasMeter, AptosGasParameters, FromOnChainGasSchedule, GasMeterExt, InitialGasSchedule,
     NativeGasParameters, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
 };
+pub use gas_profiler::{render_flamegraph, GasProfile, GasProfiler};
 pub use instr::InstructionGasParameters;
 pub use misc::{AbstractValueSizeGasParameters, MiscGasParameters};
 pub use move_core_types::gas_algebra::{
@@ -0,0 +1,570 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{AptosValidatorInterface, RestDebuggerInterface};
+use anyhow::{anyhow, Result};
+use aptos_config::config::ForkConfig;
+use aptos_crypto::HashValue;
+use aptos_rest_client::Client;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    contract_event::EventWithVersion,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{
+        AccumulatorConsistencyProof, SparseMerkleBatchProof, SparseMerkleProof,
+        SparseMerkleProofExt, TransactionAccumulatorSummary,
+    },
+    state_proof::StateProof,
+    state_store::{
+        state_key::StateKey,
+        state_key_prefix::StateKeyPrefix,
+        state_storage_usage::StateStorageUsage,
+        state_value::{StateValue, StateValueChange, StateValueChunkWithProof},
+        table::{TableHandle, TableInfo},
+    },
+    transaction::{
+        AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
+        TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
+    },
+};
+use move_core_types::language_storage::TypeTag;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{mpsc, RwLock},
+    thread,
+};
+use storage_interface::{
+    state_delta::StateDelta, DbReader, DbReaderWriter, DbWriter, ExecutedTrees, Order,
+    StateSnapshotReceiver,
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+const DELETED_KEYS_FILE: &str = "fork_deleted_keys.bcs";
+
+/// A local DB which forks the state of another chain at a fixed version
+///
+/// Everything is read from and written to the local DB, except for state keys which were never
+/// written locally: their values are read lazily from a node of the forked chain, and cached.
+/// Keys which are deleted locally are remembered, so that their values on the forked chain don't
+/// come back.
+///
+/// State on the forked chain isn't part of the local state tree, so it isn't returned by the
+/// methods with proofs, nor by state iterators.
+pub struct ForkedDb {
+    db: DbReaderWriter,
+    remote: RemoteState,
+    deleted_keys: RwLock<BTreeSet<StateKey>>,
+    deleted_keys_path: PathBuf,
+}
+
+impl ForkedDb {
+    /// Forks `config.url` on top of `db`, keeping track of local deletions in `db_root_path`
+    pub fn new(db: DbReaderWriter, config: &ForkConfig, db_root_path: &Path) -> Result<Self> {
+        let remote = RemoteState::new(
+            RestDebuggerInterface::new(Client::new(config.url.clone())),
+            config.version,
+        )?;
+        let deleted_keys_path = db_root_path.join(DELETED_KEYS_FILE);
+        let deleted_keys = if deleted_keys_path.exists() {
+            bcs::from_bytes(&std::fs::read(&deleted_keys_path)?)?
+        } else {
+            BTreeSet::new()
+        };
+        Ok(Self {
+            db,
+            remote,
+            deleted_keys: RwLock::new(deleted_keys),
+            deleted_keys_path,
+        })
+    }
+
+    fn record_deleted_keys(&self, txns_to_commit: &[TransactionToCommit]) -> Result<()> {
+        let mut deleted_keys = self.deleted_keys.write().unwrap();
+        let mut changed = false;
+        for txn in txns_to_commit {
+            for (state_key, value) in txn.state_updates() {
+                if value.is_none() {
+                    changed |= deleted_keys.insert(state_key.clone());
+                }
+            }
+        }
+        if changed {
+            // Write to a temporary file first, so that a crash can't leave a truncated file
+            let tmp_path = self.deleted_keys_path.with_extension("tmp");
+            std::fs::write(&tmp_path, bcs::to_bytes(&*deleted_keys)?)?;
+            std::fs::rename(&tmp_path, &self.deleted_keys_path)?;
+        }
+        Ok(())
+    }
+}
+
+type RemoteQuery = (StateKey, mpsc::Sender<Result<Option<StateValue>>>);
+
+/// Blocking reads of the state of the forked chain
+///
+/// The REST client is async, and state is read from threads which may or may not be in a tokio
+/// runtime, so the requests are made on a dedicated thread with its own runtime.
+struct RemoteState {
+    query_sender: UnboundedSender<RemoteQuery>,
+    cache: RwLock<HashMap<StateKey, Option<StateValue>>>,
+}
+
+impl RemoteState {
+    fn new(
+        interface: impl AptosValidatorInterface + Send + 'static,
+        version: Version,
+    ) -> Result<Self> {
+        let (query_sender, mut query_receiver) = unbounded_channel::<RemoteQuery>();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        thread::Builder::new()
+            .name("fork-state".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    while let Some((state_key, reply_sender)) = query_receiver.recv().await {
+                        let value = interface
+                            .get_state_value_by_version(&state_key, version)
+                            .await;
+                        // The reader may have given up waiting, which is fine
+                        let _ = reply_sender.send(value);
+                    }
+                })
+            })?;
+        Ok(Self {
+            query_sender,
+            cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn get(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        if let Some(value) = self.cache.read().unwrap().get(state_key) {
+            return Ok(value.clone());
+        }
+        let (reply_sender, reply_receiver) = mpsc::channel();
+        self.query_sender
+            .send((state_key.clone(), reply_sender))
+            .map_err(|_| anyhow!("Fork state reader stopped"))?;
+        let value = reply_receiver
+            .recv()
+            .map_err(|_| anyhow!("Fork state reader stopped"))??;
+        // The forked chain is read at a fixed version, so its values never change
+        self.cache
+            .write()
+            .unwrap()
+            .insert(state_key.clone(), value.clone());
+        Ok(value)
+    }
+}
+
+impl DbReader for ForkedDb {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        self.db
+            .reader
+            .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+    }
+
+    fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        self.db
+            .reader
+            .get_transactions(start_version, batch_size, ledger_version, fetch_events)
+    }
+
+    fn get_gas_prices(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<u64>> {
+        self.db
+            .reader
+            .get_gas_prices(start_version, limit, ledger_version)
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.db
+            .reader
+            .get_transaction_by_hash(hash, ledger_version, fetch_events)
+    }
+
+    fn get_transaction_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        self.db
+            .reader
+            .get_transaction_by_version(version, ledger_version, fetch_events)
+    }
+
+    fn get_first_txn_version(&self) -> Result<Option<Version>> {
+        self.db.reader.get_first_txn_version()
+    }
+
+    fn get_first_viable_txn_version(&self) -> Result<Version> {
+        self.db.reader.get_first_viable_txn_version()
+    }
+
+    fn get_first_write_set_version(&self) -> Result<Option<Version>> {
+        self.db.reader.get_first_write_set_version()
+    }
+
+    fn get_transaction_outputs(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        self.db
+            .reader
+            .get_transaction_outputs(start_version, limit, ledger_version)
+    }
+
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.db
+            .reader
+            .get_events(event_key, start, order, limit, ledger_version)
+    }
+
+    fn get_events_by_type(
+        &self,
+        event_type: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.db
+            .reader
+            .get_events_by_type(event_type, start_version, limit, ledger_version)
+    }
+
+    fn get_block_timestamp(&self, version: Version) -> Result<u64> {
+        self.db.reader.get_block_timestamp(version)
+    }
+
+    fn get_next_block_event(&self, version: Version) -> Result<(Version, NewBlockEvent)> {
+        self.db.reader.get_next_block_event(version)
+    }
+
+    fn get_block_info_by_version(
+        &self,
+        version: Version,
+    ) -> Result<(Version, Version, NewBlockEvent)> {
+        self.db.reader.get_block_info_by_version(version)
+    }
+
+    fn get_block_info_by_height(&self, height: u64) -> Result<(Version, Version, NewBlockEvent)> {
+        self.db.reader.get_block_info_by_height(height)
+    }
+
+    fn get_last_version_before_timestamp(
+        &self,
+        timestamp: u64,
+        ledger_version: Version,
+    ) -> Result<Version> {
+        self.db
+            .reader
+            .get_last_version_before_timestamp(timestamp, ledger_version)
+    }
+
+    fn get_latest_epoch_state(&self) -> Result<EpochState> {
+        self.db.reader.get_latest_epoch_state()
+    }
+
+    fn get_prefixed_state_value_iterator(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        cursor: Option<&StateKey>,
+        version: Version,
+    ) -> Result<Box<dyn Iterator<Item = Result<(StateKey, StateValue)>> + '_>> {
+        self.db
+            .reader
+            .get_prefixed_state_value_iterator(key_prefix, cursor, version)
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        self.db.reader.get_latest_ledger_info_option()
+    }
+
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
+        self.db.reader.get_latest_ledger_info()
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        self.db.reader.get_latest_version()
+    }
+
+    fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>> {
+        self.db.reader.get_latest_state_checkpoint_version()
+    }
+
+    fn get_state_snapshot_before(
+        &self,
+        next_version: Version,
+    ) -> Result<Option<(Version, HashValue)>> {
+        self.db.reader.get_state_snapshot_before(next_version)
+    }
+
+    fn get_latest_commit_metadata(&self) -> Result<(Version, u64)> {
+        self.db.reader.get_latest_commit_metadata()
+    }
+
+    fn get_account_transaction(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.db
+            .reader
+            .get_account_transaction(address, seq_num, include_events, ledger_version)
+    }
+
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof> {
+        self.db.reader.get_account_transactions(
+            address,
+            seq_num,
+            limit,
+            include_events,
+            ledger_version,
+        )
+    }
+
+    fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<StateProof> {
+        self.db
+            .reader
+            .get_state_proof_with_ledger_info(known_version, ledger_info)
+    }
+
+    fn get_state_proof(&self, known_version: u64) -> Result<StateProof> {
+        self.db.reader.get_state_proof(known_version)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        self.db
+            .reader
+            .get_state_value_by_version(state_key, version)
+    }
+
+    fn get_state_proof_by_version_ext(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<SparseMerkleProofExt> {
+        self.db
+            .reader
+            .get_state_proof_by_version_ext(state_key, version)
+    }
+
+    fn get_state_value_with_proof_by_version_ext(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<(Option<StateValue>, SparseMerkleProofExt)> {
+        self.db
+            .reader
+            .get_state_value_with_proof_by_version_ext(state_key, version)
+    }
+
+    fn get_state_values_with_batch_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<(StateKey, Option<StateValue>)>, SparseMerkleBatchProof)> {
+        self.db
+            .reader
+            .get_state_values_with_batch_proof_by_version(state_keys, version)
+    }
+
+    fn get_state_value_with_proof_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<(Option<StateValue>, SparseMerkleProof)> {
+        self.db
+            .reader
+            .get_state_value_with_proof_by_version(state_key, version)
+    }
+
+    fn get_state_value_changes(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        start: Option<&StateKey>,
+        limit: u64,
+    ) -> Result<(Vec<StateValueChange>, Option<StateKey>)> {
+        self.db
+            .reader
+            .get_state_value_changes(from_version, to_version, start, limit)
+    }
+
+    fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
+        self.db.reader.get_latest_executed_trees()
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        self.db.reader.get_epoch_ending_ledger_info(known_version)
+    }
+
+    fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
+        self.db.reader.get_latest_transaction_info_option()
+    }
+
+    fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
+        self.db.reader.get_accumulator_root_hash(version)
+    }
+
+    fn get_accumulator_consistency_proof(
+        &self,
+        client_known_version: Option<Version>,
+        ledger_version: Version,
+    ) -> Result<AccumulatorConsistencyProof> {
+        self.db
+            .reader
+            .get_accumulator_consistency_proof(client_known_version, ledger_version)
+    }
+
+    fn get_accumulator_summary(
+        &self,
+        ledger_version: Version,
+    ) -> Result<TransactionAccumulatorSummary> {
+        self.db.reader.get_accumulator_summary(ledger_version)
+    }
+
+    fn get_state_leaf_count(&self, version: Version) -> Result<usize> {
+        self.db.reader.get_state_leaf_count(version)
+    }
+
+    fn get_state_value_chunk_with_proof(
+        &self,
+        version: Version,
+        start_idx: usize,
+        chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        self.db
+            .reader
+            .get_state_value_chunk_with_proof(version, start_idx, chunk_size)
+    }
+
+    fn is_state_pruner_enabled(&self) -> Result<bool> {
+        self.db.reader.is_state_pruner_enabled()
+    }
+
+    fn get_epoch_snapshot_prune_window(&self) -> Result<usize> {
+        self.db.reader.get_epoch_snapshot_prune_window()
+    }
+
+    fn is_ledger_pruner_enabled(&self) -> Result<bool> {
+        self.db.reader.is_ledger_pruner_enabled()
+    }
+
+    fn get_ledger_prune_window(&self) -> Result<usize> {
+        self.db.reader.get_ledger_prune_window()
+    }
+
+    fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo> {
+        self.db.reader.get_table_info(handle)
+    }
+
+    fn indexer_enabled(&self) -> bool {
+        self.db.reader.indexer_enabled()
+    }
+
+    fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
+        self.db.reader.get_state_storage_usage(version)
+    }
+
+    fn get_forked_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        if self.deleted_keys.read().unwrap().contains(state_key) {
+            return Ok(None);
+        }
+        self.remote.get(state_key)
+    }
+}
+
+impl DbWriter for ForkedDb {
+    fn get_state_snapshot_receiver(
+        &self,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<Box<dyn StateSnapshotReceiver<StateKey, StateValue>>> {
+        self.db
+            .writer
+            .get_state_snapshot_receiver(version, expected_root_hash)
+    }
+
+    fn finalize_state_snapshot(
+        &self,
+        version: Version,
+        output_with_proof: TransactionOutputListWithProof,
+        ledger_infos: &[LedgerInfoWithSignatures],
+    ) -> Result<()> {
+        self.db
+            .writer
+            .finalize_state_snapshot(version, output_with_proof, ledger_infos)
+    }
+
+    fn save_transactions(
+        &self,
+        txns_to_commit: &[TransactionToCommit],
+        first_version: Version,
+        base_state_version: Option<Version>,
+        ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+        sync_commit: bool,
+        latest_in_memory_state: StateDelta,
+    ) -> Result<()> {
+        self.db.writer.save_transactions(
+            txns_to_commit,
+            first_version,
+            base_state_version,
+            ledger_info_with_sigs,
+            sync_commit,
+            latest_in_memory_state,
+        )?;
+        self.record_deleted_keys(txns_to_commit)
+    }
+}
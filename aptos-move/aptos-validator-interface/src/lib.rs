@@ -1,9 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod fork;
 mod rest_interface;
 mod storage_interface;
 
+pub use crate::fork::ForkedDb;
 pub use crate::rest_interface::RestDebuggerInterface;
 pub use crate::storage_interface::DBDebuggerInterface;
 
@@ -16,8 +18,11 @@ use aptos_types::{
     account_state::AccountState,
     account_view::AccountView,
     on_chain_config::ValidatorSet,
-    state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{Transaction, Version},
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChange},
+    },
+    transaction::{Transaction, TransactionOutput, Version},
 };
 use move_binary_format::file_format::CompiledModule;
 use std::sync::{
@@ -48,6 +53,25 @@ pub trait AptosValidatorInterface: Sync {
         limit: u64,
     ) -> Result<Vec<Transaction>>;
 
+    /// Like `get_committed_transactions`, along with the output each transaction had on chain.
+    /// May return fewer than `limit` transactions.
+    async fn get_committed_transactions_with_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<(Transaction, TransactionOutput)>>;
+
+    /// State items whose values differ between `from_version` and `to_version`, ordered by key
+    /// hash and starting at `start`, along with the key to continue from if there are more.
+    /// May return fewer than `limit` changes even if there are more.
+    async fn get_state_value_changes(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        start: Option<&StateKey>,
+        limit: u64,
+    ) -> Result<(Vec<StateValueChange>, Option<StateKey>)>;
+
     async fn get_latest_version(&self) -> Result<Version>;
 
     async fn get_version_by_account_sequence(
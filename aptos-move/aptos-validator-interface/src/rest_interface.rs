@@ -3,14 +3,17 @@
 
 use crate::AptosValidatorInterface;
 use anyhow::{anyhow, bail, Result};
-use aptos_api_types::MoveStructTag;
-use aptos_rest_client::Client;
+use aptos_api_types::{AptosErrorCode, MoveStructTag, StateKeyWrapper};
+use aptos_rest_client::{error::RestError, Client, Response};
 use aptos_types::{
     access_path::Path,
     account_address::AccountAddress,
     account_state::AccountState,
-    state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{Transaction, Version},
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChange},
+    },
+    transaction::{Transaction, TransactionOutput, TransactionStatus, Version},
 };
 use std::collections::BTreeMap;
 
@@ -49,32 +52,31 @@ impl AptosValidatorInterface for RestDebuggerInterface {
     ) -> Result<Option<StateValue>> {
         match state_key {
             StateKey::AccessPath(path) => match path.get_path() {
-                Path::Code(module_id) => Ok(Some(StateValue::new(
+                Path::Code(module_id) => not_found_as_none(
                     self.0
-                        .get_account_module_bcs(*module_id.address(), module_id.name().as_str())
-                        .await
-                        .map_err(|err| anyhow!("Failed to get account states: {:?}", err))?
-                        .into_inner()
-                        .to_vec(),
-                ))),
-                Path::Resource(tag) => Ok(self
-                    .0
-                    .get_account_resource_at_version_bytes(
-                        path.address,
-                        MoveStructTag::from(tag).to_string().as_str(),
-                        version,
-                    )
-                    .await
-                    .ok()
-                    .map(|inner| StateValue::new(inner.into_inner()))),
+                        .get_account_module_bcs_at_version(
+                            *module_id.address(),
+                            module_id.name().as_str(),
+                            version,
+                        )
+                        .await,
+                )
+                .map(|module| module.map(|inner| StateValue::new(inner.to_vec()))),
+                Path::Resource(tag) => not_found_as_none(
+                    self.0
+                        .get_account_resource_at_version_bytes(
+                            path.address,
+                            MoveStructTag::from(tag).to_string().as_str(),
+                            version,
+                        )
+                        .await,
+                )
+                .map(|resource| resource.map(StateValue::new)),
             },
-            StateKey::TableItem { handle, key } => Ok(Some(StateValue::new(
-                self.0
-                    .get_raw_table_item(handle.0, key, version)
-                    .await
-                    .map_err(|err| anyhow!("Failed to get account states: {:?}", err))?
-                    .into_inner(),
-            ))),
+            StateKey::TableItem { handle, key } => {
+                not_found_as_none(self.0.get_raw_table_item(handle.0, key, version).await)
+                    .map(|item| item.map(StateValue::new))
+            }
             StateKey::Raw(_) => bail!("Unexpected key type"),
         }
     }
@@ -94,6 +96,53 @@ impl AptosValidatorInterface for RestDebuggerInterface {
             .collect())
     }
 
+    async fn get_committed_transactions_with_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<(Transaction, TransactionOutput)>> {
+        Ok(self
+            .0
+            .get_transactions_bcs(Some(start), Some(limit.min(u16::MAX as u64) as u16))
+            .await?
+            .into_inner()
+            .into_iter()
+            .map(|txn| {
+                let output = TransactionOutput::new(
+                    txn.changes,
+                    txn.events,
+                    txn.info.gas_used(),
+                    TransactionStatus::Keep(txn.info.status().clone()),
+                );
+                (txn.transaction, output)
+            })
+            .collect())
+    }
+
+    async fn get_state_value_changes(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        start: Option<&StateKey>,
+        limit: u64,
+    ) -> Result<(Vec<StateValueChange>, Option<StateKey>)> {
+        let start = start.map(|key| StateKeyWrapper::from(key.clone()).to_string());
+        let response = self
+            .0
+            .get_state_changes_bcs(
+                from_version,
+                Some(to_version),
+                start.as_deref(),
+                Some(limit.min(u16::MAX as u64) as u16),
+            )
+            .await?;
+        let cursor = match &response.state().cursor {
+            Some(cursor) => Some(cursor.parse::<StateKeyWrapper>()?.0),
+            None => None,
+        };
+        Ok((response.into_inner(), cursor))
+    }
+
     async fn get_latest_version(&self) -> Result<Version> {
         Ok(self.0.get_ledger_information().await?.into_inner().version)
     }
@@ -112,3 +161,22 @@ impl AptosValidatorInterface for RestDebuggerInterface {
         ))
     }
 }
+
+/// A state key which doesn't exist at the version isn't an error, it has no value
+fn not_found_as_none<T>(result: Result<Response<T>, RestError>) -> Result<Option<T>> {
+    match result {
+        Ok(response) => Ok(Some(response.into_inner())),
+        Err(RestError::Api(err))
+            if matches!(
+                err.error.error_code,
+                AptosErrorCode::AccountNotFound
+                    | AptosErrorCode::ResourceNotFound
+                    | AptosErrorCode::ModuleNotFound
+                    | AptosErrorCode::TableItemNotFound
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(anyhow!("Failed to get state value: {:?}", err)),
+    }
+}
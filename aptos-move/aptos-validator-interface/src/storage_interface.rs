@@ -10,8 +10,12 @@ use aptos_config::config::{
 use aptos_types::{
     account_address::AccountAddress,
     account_state::AccountState,
-    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
-    transaction::{Transaction, Version},
+    state_store::{
+        state_key::StateKey,
+        state_key_prefix::StateKeyPrefix,
+        state_value::{StateValue, StateValueChange},
+    },
+    transaction::{Transaction, TransactionOutput, Version},
 };
 use aptosdb::AptosDB;
 use std::{path::Path, sync::Arc};
@@ -79,6 +83,36 @@ impl AptosValidatorInterface for DBDebuggerInterface {
             .transactions)
     }
 
+    async fn get_committed_transactions_with_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<(Transaction, TransactionOutput)>> {
+        Ok(self
+            .0
+            .get_transaction_outputs(
+                start,
+                limit.min(MAX_REQUEST_LIMIT),
+                self.get_latest_version().await?,
+            )?
+            .transactions_and_outputs)
+    }
+
+    async fn get_state_value_changes(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        start: Option<&StateKey>,
+        limit: u64,
+    ) -> Result<(Vec<StateValueChange>, Option<StateKey>)> {
+        self.0.get_state_value_changes(
+            from_version,
+            to_version,
+            start,
+            limit.min(MAX_REQUEST_LIMIT),
+        )
+    }
+
     async fn get_latest_version(&self) -> Result<Version> {
         let (version, _) = self
             .0
@@ -19,13 +19,13 @@ use crate::{
     transaction_metadata::TransactionMetadata,
     VMExecutor, VMValidator,
 };
-use anyhow::Result;
+use anyhow::{anyhow, ensure, Result};
 use aptos_aggregator::{
     delta_change_set::DeltaChangeSet,
     transaction::{ChangeSetExt, TransactionOutputExt},
 };
 use aptos_crypto::HashValue;
-use aptos_gas::AptosGasMeter;
+use aptos_gas::{AptosGasMeter, Gas, GasMeterExt, GasProfile, GasProfiler};
 use aptos_logger::prelude::*;
 use aptos_module_verifier::module_init::verify_module_init_function;
 use aptos_state_view::StateView;
@@ -47,12 +47,14 @@ use framework::natives::code::PublishRequest;
 use move_binary_format::{
     access::ModuleAccess,
     errors::{verification_error, Location, PartialVMError, VMError, VMResult},
+    file_format::Visibility,
     CompiledModule, IndexKind,
 };
 use move_core_types::{
     account_address::AccountAddress,
     ident_str,
-    language_storage::ModuleId,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
     transaction_argument::convert_txn_args,
     value::{serialize_values, MoveValue},
 };
@@ -187,7 +189,7 @@ impl AptosVM {
     pub fn failed_transaction_cleanup<S: MoveResolverExt>(
         &self,
         error_code: VMStatus,
-        gas_meter: &mut AptosGasMeter,
+        gas_meter: &mut impl GasMeterExt,
         txn_data: &TransactionMetadata,
         storage: &S,
         log_context: &AdapterLogSchema,
@@ -205,7 +207,7 @@ impl AptosVM {
     fn failed_transaction_cleanup_and_keep_vm_status<S: MoveResolverExt>(
         &self,
         error_code: VMStatus,
-        gas_meter: &mut AptosGasMeter,
+        gas_meter: &mut impl GasMeterExt,
         txn_data: &TransactionMetadata,
         storage: &S,
         log_context: &AdapterLogSchema,
@@ -266,7 +268,7 @@ impl AptosVM {
         &self,
         storage: &S,
         user_txn_change_set_ext: ChangeSetExt,
-        gas_meter: &mut AptosGasMeter,
+        gas_meter: &mut impl GasMeterExt,
         txn_data: &TransactionMetadata,
         log_context: &AdapterLogSchema,
     ) -> Result<(VMStatus, TransactionOutputExt), VMStatus> {
@@ -331,7 +333,7 @@ impl AptosVM {
         &self,
         storage: &S,
         mut session: SessionExt<S>,
-        gas_meter: &mut AptosGasMeter,
+        gas_meter: &mut impl GasMeterExt,
         txn_data: &TransactionMetadata,
         payload: &TransactionPayload,
         log_context: &AdapterLogSchema,
@@ -448,7 +450,7 @@ impl AptosVM {
     fn execute_module_initialization<S: MoveResolverExt>(
         &self,
         session: &mut SessionExt<S>,
-        gas_meter: &mut AptosGasMeter,
+        gas_meter: &mut impl GasMeterExt,
         modules: &[CompiledModule],
         exists: BTreeSet<ModuleId>,
         senders: &[AccountAddress],
@@ -508,7 +510,7 @@ impl AptosVM {
         &self,
         storage: &S,
         mut session: SessionExt<S>,
-        gas_meter: &mut AptosGasMeter,
+        gas_meter: &mut impl GasMeterExt,
         txn_data: &TransactionMetadata,
         modules: &ModuleBundle,
         log_context: &AdapterLogSchema,
@@ -555,7 +557,7 @@ impl AptosVM {
     fn resolve_pending_code_publish<S: MoveResolverExt>(
         &self,
         session: &mut SessionExt<S>,
-        gas_meter: &mut AptosGasMeter,
+        gas_meter: &mut impl GasMeterExt,
     ) -> VMResult<()> {
         if let Some(PublishRequest {
             destination,
@@ -934,7 +936,106 @@ impl AptosVM {
         let vm = AptosVM::new(state_view);
         let simulation_vm = AptosSimulationVM(vm);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
-        simulation_vm.simulate_signed_transaction(&state_view.as_move_resolver(), txn, &log_context)
+        let (vm_status, output, _) = simulation_vm.simulate_signed_transaction(
+            &state_view.as_move_resolver(),
+            txn,
+            &log_context,
+            |gas_meter| gas_meter,
+        );
+        (vm_status, output)
+    }
+
+    /// Simulates a transaction like [`AptosVM::simulate_signed_transaction`], recording where its
+    /// gas goes.  The profile is empty if the transaction is discarded before execution.
+    pub fn simulate_signed_transaction_with_gas_profile(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutputExt, GasProfile) {
+        let vm = AptosVM::new(state_view);
+        let simulation_vm = AptosSimulationVM(vm);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let root = match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => format!(
+                "{}::{}",
+                entry_function.module().short_str_lossless(),
+                entry_function.function()
+            ),
+            TransactionPayload::Script(_) => "script".to_string(),
+            TransactionPayload::ModuleBundle(_) => "module_bundle".to_string(),
+        };
+        let (vm_status, output, gas_profiler) = simulation_vm.simulate_signed_transaction(
+            &state_view.as_move_resolver(),
+            txn,
+            &log_context,
+            |gas_meter| GasProfiler::new(gas_meter, root),
+        );
+        let gas_profile = gas_profiler
+            .map(GasProfiler::into_profile)
+            .unwrap_or_default();
+        (vm_status, output, gas_profile)
+    }
+
+    /// Executes a public Move function against `state_view` without a transaction, returning the
+    /// BCS-serialized return values. Any writes made by the function are discarded, and the
+    /// execution is bounded by `gas_budget` gas units.
+    pub fn execute_view_function(
+        state_view: &impl StateView,
+        module_id: ModuleId,
+        func_name: Identifier,
+        type_args: Vec<TypeTag>,
+        arguments: Vec<Vec<u8>>,
+        gas_budget: u64,
+    ) -> Result<Vec<Vec<u8>>> {
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let gas_params =
+            vm.0.get_gas_parameters(&log_context)
+                .map_err(|status| anyhow!("Failed to get gas parameters: {:?}", status))?;
+        let storage_gas_params =
+            vm.0.get_storage_gas_parameters(&log_context)
+                .map_err(|status| anyhow!("Failed to get storage gas parameters: {:?}", status))?;
+        let mut gas_meter = AptosGasMeter::new(
+            vm.0.get_gas_feature_version(),
+            gas_params.clone(),
+            storage_gas_params.cloned(),
+            Gas::new(gas_budget),
+        );
+
+        let resolver = state_view.as_move_resolver();
+        let module = vm
+            .load_module(&module_id, &resolver)
+            .map_err(|err| anyhow!("Failed to load module {}: {:?}", module_id, err))?;
+        let func_def = module
+            .function_defs()
+            .iter()
+            .find(|def| {
+                let handle = module.function_handle_at(def.function);
+                module.identifier_at(handle.name) == func_name.as_ident_str()
+            })
+            .ok_or_else(|| anyhow!("Function {}::{} not found", module_id, func_name))?;
+        ensure!(
+            func_def.visibility == Visibility::Public,
+            "Function {}::{} is not public",
+            module_id,
+            func_name
+        );
+
+        let mut session = vm.0.new_session(&resolver, SessionId::void());
+        let return_values = session
+            .execute_function_bypass_visibility(
+                &module_id,
+                &func_name,
+                type_args,
+                arguments,
+                &mut gas_meter,
+            )
+            .map_err(|err| anyhow!("Failed to execute function: {:?}", err.into_vm_status()))?
+            .return_values;
+
+        Ok(return_values
+            .into_iter()
+            .map(|(bytes, _layout)| bytes)
+            .collect())
     }
 
     fn run_prologue_with_payload<S: MoveResolverExt>(
@@ -1174,18 +1275,28 @@ impl AptosSimulationVM {
     }
 
     /*
-    Executes a SignedTransaction without performing signature verification
+    Executes a SignedTransaction without performing signature verification, metering it with the
+    gas meter built by `new_gas_meter` from the official one. The gas meter is returned unless the
+    transaction was discarded before execution.
      */
-    fn simulate_signed_transaction<S: MoveResolverExt + StateView>(
+    fn simulate_signed_transaction<S: MoveResolverExt + StateView, G: GasMeterExt>(
         &self,
         storage: &S,
         txn: &SignedTransaction,
         log_context: &AdapterLogSchema,
-    ) -> (VMStatus, TransactionOutputExt) {
+        new_gas_meter: impl FnOnce(AptosGasMeter) -> G,
+    ) -> (VMStatus, TransactionOutputExt, Option<G>) {
+        macro_rules! discard {
+            ($err: expr) => {{
+                let (vm_status, output) = discard_error_vm_status($err);
+                return (vm_status, output, None);
+            }};
+        }
+
         // simulation transactions should not carry valid signatures, otherwise malicious fullnodes
         // may execute them without user's explicit permission.
         if txn.signature_is_valid() {
-            return discard_error_vm_status(VMStatus::Error(StatusCode::INVALID_SIGNATURE));
+            discard!(VMStatus::Error(StatusCode::INVALID_SIGNATURE));
         }
 
         // Revalidate the transaction.
@@ -1198,24 +1309,24 @@ impl AptosSimulationVM {
             &txn_data,
             log_context,
         ) {
-            return discard_error_vm_status(err);
+            discard!(err);
         };
 
         let gas_params = match self.0 .0.get_gas_parameters(log_context) {
-            Err(err) => return discard_error_vm_status(err),
+            Err(err) => discard!(err),
             Ok(s) => s,
         };
         let storage_gas_params = match self.0 .0.get_storage_gas_parameters(log_context) {
-            Err(err) => return discard_error_vm_status(err),
+            Err(err) => discard!(err),
             Ok(s) => s,
         };
 
-        let mut gas_meter = AptosGasMeter::new(
+        let mut gas_meter = new_gas_meter(AptosGasMeter::new(
             self.0 .0.get_gas_feature_version(),
             gas_params.clone(),
             storage_gas_params.cloned(),
             txn_data.max_gas_amount(),
-        );
+        ));
 
         let result = match txn.payload() {
             payload @ TransactionPayload::Script(_)
@@ -1235,23 +1346,23 @@ impl AptosSimulationVM {
             }
         };
 
-        match result {
+        let (vm_status, output) = match result {
             Ok(output) => output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
                 if txn_status.is_discarded() {
                     discard_error_vm_status(err)
                 } else {
-                    let (vm_status, output) = self.0.failed_transaction_cleanup_and_keep_vm_status(
+                    self.0.failed_transaction_cleanup_and_keep_vm_status(
                         err,
                         &mut gas_meter,
                         &txn_data,
                         storage,
                         log_context,
-                    );
-                    (vm_status, output)
+                    )
                 }
             }
-        }
+        };
+        (vm_status, output, Some(gas_meter))
     }
 }
@@ -10,27 +10,29 @@ use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
 use aptos_config::{
     config::{
-        AptosDataClientConfig, BaseConfig, DataStreamingServiceConfig, NetworkConfig, NodeConfig,
-        PersistableConfig, StorageServiceConfig,
+        AptosDataClientConfig, BaseConfig, DataStreamingServiceConfig, ForkConfig, NetworkConfig,
+        NodeConfig, PersistableConfig, StorageServiceConfig,
     },
     network_id::NetworkId,
     utils::get_genesis_txn,
 };
-use aptos_data_client::aptosnet::AptosNetDataClient;
+use aptos_data_client::aptosnet::{AptosNetDataClient, PeerMonitoringPoller};
 use aptos_fh_stream::runtime::bootstrap as bootstrap_fh_stream;
 use aptos_infallible::RwLock;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
+use aptos_rest_client::Client;
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_time_service::TimeService;
 use aptos_types::{
     account_config::CORE_CODE_ADDRESS, account_view::AccountView, chain_id::ChainId,
-    on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint,
+    on_chain_config::ON_CHAIN_CONFIG_REGISTRY, transaction::Version, waypoint::Waypoint,
 };
+use aptos_validator_interface::ForkedDb;
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
 use backup_service::start_backup_service;
 use clap::Parser;
-use consensus::consensus_provider::start_consensus;
+use consensus::consensus_provider::{start_consensus, start_consensus_observer};
 use consensus_notifications::ConsensusNotificationListener;
 use data_streaming_service::{
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
@@ -43,8 +45,18 @@ use futures::channel::mpsc;
 use hex::FromHex;
 use log_build_information::log_build_information;
 use mempool_notifications::MempoolNotificationSender;
-use network::application::storage::PeerMetadataStorage;
+use network::{
+    application::storage::PeerMetadataStorage,
+    connectivity_manager::reputation::PeerReputationRegistry,
+};
 use network_builder::builder::NetworkBuilder;
+use peer_monitoring_service_client::{
+    PeerMonitoringServiceClient, PeerMonitoringServiceMultiSender,
+    PeerMonitoringServiceNetworkSender,
+};
+use peer_monitoring_service_server::{
+    network::PeerMonitoringServiceNetworkEvents, PeerMonitoringServiceServer,
+};
 use rand::{rngs::StdRng, SeedableRng};
 use state_sync_driver::{
     driver_factory::{DriverFactory, StateSyncRuntimes},
@@ -68,6 +80,7 @@ use storage_service_server::{
     network::StorageServiceNetworkEvents, StorageReader, StorageServiceServer,
 };
 use tokio::runtime::{Builder, Runtime};
+use url::Url;
 
 use aptos_mempool::MempoolClientSender;
 
@@ -112,6 +125,73 @@ pub struct AptosNodeArgs {
     /// only commit a block when there is user transaction in mempool.
     #[clap(long, requires("test"))]
     lazy: bool,
+
+    #[clap(flatten)]
+    fork: ForkArgs,
+}
+
+/// Options to fork the state of another chain in test mode
+#[derive(Clone, Debug, Default, Parser)]
+pub struct ForkArgs {
+    /// REST endpoint of a node of a chain to fork
+    ///
+    /// State which was never written locally is read lazily from that chain, and everything
+    /// written stays local.  This allows testing against e.g. mainnet state without syncing it.
+    #[clap(long)]
+    pub fork_url: Option<Url>,
+
+    /// Version of the forked chain to read state at
+    ///
+    /// Defaults to the latest version of the forked chain
+    #[clap(long, requires = "fork_url")]
+    pub fork_version: Option<Version>,
+}
+
+impl ForkArgs {
+    /// The fork config of a new test chain, pinning the latest version if none was given
+    fn fork_config(&self) -> anyhow::Result<Option<ForkConfig>> {
+        let url = match &self.fork_url {
+            Some(url) => url.clone(),
+            None => return Ok(None),
+        };
+        let version = match self.fork_version {
+            Some(version) => version,
+            None => {
+                Runtime::new()?
+                    .block_on(Client::new(url.clone()).get_ledger_information())
+                    .map_err(|err| anyhow!("Failed to get the latest version of {}: {}", url, err))?
+                    .into_inner()
+                    .version
+            }
+        };
+        Ok(Some(ForkConfig { url, version }))
+    }
+
+    /// Checks that an existing test chain forks the requested chain, if any
+    fn check(&self, existing: Option<&ForkConfig>) -> anyhow::Result<()> {
+        let url = match &self.fork_url {
+            Some(url) => url,
+            None => return Ok(()),
+        };
+        match existing {
+            Some(existing)
+                if existing.url == *url
+                    && self
+                        .fork_version
+                        .map_or(true, |version| version == existing.version) =>
+            {
+                Ok(())
+            }
+            Some(existing) => Err(anyhow!(
+                "The test chain already forks {} at version {}, restart it to fork another",
+                existing.url,
+                existing.version
+            )),
+            None => Err(anyhow!(
+                "The test chain isn't a fork, restart it to fork another chain"
+            )),
+        }
+    }
 }
 
 impl AptosNodeArgs {
@@ -132,6 +212,7 @@ impl AptosNodeArgs {
                 self.test_dir,
                 self.random_ports,
                 self.lazy,
+                &self.fork,
                 &genesis_framework,
                 rng,
             )
@@ -167,6 +248,7 @@ pub struct AptosHandle {
     _api: Option<Runtime>,
     _backup: Runtime,
     _consensus_runtime: Option<Runtime>,
+    _consensus_observer_runtime: Option<Runtime>,
     _mempool: Runtime,
     _network_runtimes: Vec<Runtime>,
     _fh_stream: Option<Runtime>,
@@ -248,6 +330,7 @@ pub fn load_test_environment<R>(
     test_dir: Option<PathBuf>,
     random_ports: bool,
     lazy: bool,
+    fork: &ForkArgs,
     framework: &ReleaseBundle,
     rng: R,
 ) -> anyhow::Result<()>
@@ -273,8 +356,10 @@ where
 
     // If there's already a config, use it
     let config = if validator_config_path.exists() {
-        NodeConfig::load(&validator_config_path)
-            .map_err(|err| anyhow!("Unable to load config: {}", err))?
+        let config = NodeConfig::load(&validator_config_path)
+            .map_err(|err| anyhow!("Unable to load config: {}", err))?;
+        fork.check(config.storage.fork.as_ref())?;
+        config
     } else {
         // Build a single validator network with a generated config
         let mut template = NodeConfig::default_for_validator();
@@ -311,6 +396,7 @@ where
         if lazy {
             template.consensus.quorum_store_poll_count = u64::MAX;
         }
+        template.storage.fork = fork.fork_config()?;
 
         // Build genesis and validator node
         let builder = aptos_genesis::builder::Builder::new(&test_dir, framework.clone())?
@@ -362,6 +448,9 @@ where
     if lazy {
         println!("\tLazy mode is enabled");
     }
+    if let Some(fork) = &config.storage.fork {
+        println!("\tForked from {} at version {}", fork.url, fork.version);
+    }
 
     println!("\nAptos is running, press ctrl-c to exit\n");
 
@@ -389,6 +478,7 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
         NetworkId,
         storage_service_client::StorageServiceNetworkSender,
     >,
+    peer_monitoring_client_network_handles: HashMap<NetworkId, PeerMonitoringServiceNetworkSender>,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
     mempool_notifier: M,
     consensus_listener: ConsensusNotificationListener,
@@ -409,6 +499,7 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
         node_config.state_sync.aptos_data_client,
         node_config.base.clone(),
         storage_service_client_network_handles,
+        peer_monitoring_client_network_handles,
         peer_metadata_storage,
     )?;
 
@@ -477,11 +568,18 @@ fn setup_aptos_data_client(
     aptos_data_client_config: AptosDataClientConfig,
     base_config: BaseConfig,
     network_handles: HashMap<NetworkId, storage_service_client::StorageServiceNetworkSender>,
+    peer_monitoring_network_handles: HashMap<NetworkId, PeerMonitoringServiceNetworkSender>,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
 ) -> anyhow::Result<(AptosNetDataClient, Runtime)> {
     // Combine all storage service client handles
     let network_client = StorageServiceClient::new(
         StorageServiceMultiSender::new(network_handles),
+        peer_metadata_storage.clone(),
+    );
+
+    // Combine all peer monitoring service client handles
+    let peer_monitoring_client = PeerMonitoringServiceClient::new(
+        PeerMonitoringServiceMultiSender::new(peer_monitoring_network_handles),
         peer_metadata_storage,
     );
 
@@ -508,6 +606,14 @@ fn setup_aptos_data_client(
     );
     aptos_data_client_runtime.spawn(data_summary_poller.start_poller());
 
+    // Spawn the peer monitoring poller to measure the peers for the data client
+    let peer_monitoring_poller = PeerMonitoringPoller::new(
+        aptos_data_client.clone(),
+        peer_monitoring_client,
+        TimeService::real(),
+    );
+    aptos_data_client_runtime.spawn(peer_monitoring_poller.start_poller());
+
     Ok((aptos_data_client, aptos_data_client_runtime))
 }
 
@@ -574,10 +680,16 @@ pub fn setup_environment(
     remote_log_rx: Option<mpsc::Receiver<TelemetryLog>>,
     logger_filter_update_job: Option<LoggerFilterUpdater>,
 ) -> anyhow::Result<AptosHandle> {
-    // Start the node inspection service
+    // Start the node inspection service. The peer reputations of the networks are
+    // registered once the networks are created below.
     let node_config_clone = node_config.clone();
+    let peer_reputation_registry = PeerReputationRegistry::new();
+    let peer_reputation_registry_clone = peer_reputation_registry.clone();
     thread::spawn(move || {
-        inspection_service::inspection_service::start_inspection_service(node_config_clone)
+        inspection_service::inspection_service::start_inspection_service(
+            node_config_clone,
+            peer_reputation_registry_clone,
+        )
     });
 
     // Open the database
@@ -607,6 +719,18 @@ pub fn setup_environment(
     } else {
         info!("Genesis txn not provided, it's fine if you don't expect to apply it otherwise please double check config");
     }
+    // Everything but the backup service reads through the fork, so that state which was never
+    // written locally comes from the forked chain
+    let (aptos_db, db_rw): (Arc<dyn DbReader>, _) = match &node_config.storage.fork {
+        Some(fork_config) => {
+            let (forked_db, db_rw) = DbReaderWriter::wrap(
+                ForkedDb::new(db_rw, fork_config, &node_config.storage.dir())
+                    .map_err(|err| anyhow!("Failed to fork {}: {}", fork_config.url, err))?,
+            );
+            (forked_db, db_rw)
+        }
+        None => (aptos_db, db_rw),
+    };
     AptosVM::set_runtime_config(
         node_config.execution.paranoid_type_verification,
         node_config.execution.paranoid_hot_potato_verification,
@@ -630,8 +754,10 @@ pub fn setup_environment(
     let mut network_runtimes = vec![];
     let mut mempool_network_handles = vec![];
     let mut consensus_network_handles = None;
+    let mut consensus_observer_network_handles = None;
     let mut storage_service_server_network_handles = vec![];
     let mut storage_service_client_network_handles = HashMap::new();
+    let mut peer_monitoring_client_network_handles = HashMap::new();
 
     // Create an event subscription service so that components can be notified of events and reconfigs
     let mut event_subscription_service = EventSubscriptionService::new(
@@ -726,6 +852,7 @@ pub fn setup_environment(
             peer_metadata_storage.clone(),
         );
         let network_id = network_config.network_id;
+        peer_reputation_registry.register(network_id, network_builder.peer_reputations());
 
         // TODO(philiphayes): configure which networks we serve the storage service
         // on? for example, if we're a light node we wouldn't want to provide the
@@ -743,6 +870,25 @@ pub fn setup_environment(
             network_builder.add_client(&storage_service_client::network_endpoint_config());
         storage_service_client_network_handles.insert(network_id, storage_service_sender);
 
+        // Register the peer monitoring service (server and client) with Network
+        let peer_monitoring_service_events: PeerMonitoringServiceNetworkEvents = network_builder
+            .add_service(
+                &peer_monitoring_service_server::network::network_endpoint_config(
+                    node_config.peer_monitoring_service.clone(),
+                ),
+            );
+        let peer_monitoring_server = PeerMonitoringServiceServer::new(
+            node_config.base.clone(),
+            node_config.peer_monitoring_service.clone(),
+            runtime.handle().clone(),
+            peer_monitoring_service_events,
+            peer_metadata_storage.clone(),
+        );
+        runtime.spawn(peer_monitoring_server.start());
+        let peer_monitoring_sender =
+            network_builder.add_client(&peer_monitoring_service_client::network_endpoint_config());
+        peer_monitoring_client_network_handles.insert(network_id, peer_monitoring_sender);
+
         // Create the endpoints to connect the Network to mempool.
         let (mempool_sender, mempool_events) = network_builder.add_p2p_service(
             &aptos_mempool::network::network_endpoint_config(MEMPOOL_NETWORK_CHANNEL_BUFFER_SIZE),
@@ -763,6 +909,22 @@ pub fn setup_environment(
             );
         }
 
+        // Register the consensus observer (or publisher) on the network between
+        // the validator and its VFNs.
+        let consensus_observer_config = node_config.consensus_observer;
+        if network_id.is_vfn_network()
+            && ((node_config.base.role.is_validator()
+                && consensus_observer_config.publisher_enabled)
+                || (!node_config.base.role.is_validator()
+                    && consensus_observer_config.observer_enabled))
+        {
+            consensus_observer_network_handles = Some(network_builder.add_p2p_service(
+                &consensus::consensus_observer::network_endpoint_config(
+                    consensus_observer_config.max_network_channel_size,
+                ),
+            ));
+        }
+
         let network_context = network_builder.network_context();
         network_builder.build(runtime.handle().clone());
         network_builder.start();
@@ -789,6 +951,7 @@ pub fn setup_environment(
         &node_config,
         storage_service_server_network_handles,
         storage_service_client_network_handles,
+        peer_monitoring_client_network_handles,
         peer_metadata_storage.clone(),
         mempool_notifier,
         consensus_listener,
@@ -822,6 +985,7 @@ pub fn setup_environment(
     let index_runtime = bootstrap_indexer(&node_config, chain_id, aptos_db, mp_client_sender)?;
 
     let mut consensus_runtime = None;
+    let mut consensus_observer_runtime = None;
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
         mpsc::channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
 
@@ -847,6 +1011,11 @@ pub fn setup_environment(
         node_config.mempool.shared_mempool_validator_broadcast,
         "Shared mempool validator broadcast must be turned off when QuorumStore is on, and vice versa"
     );
+    assert!(
+        !(node_config.consensus.use_quorum_store
+            && node_config.consensus_observer.publisher_enabled),
+        "The consensus publisher must be turned off when QuorumStore is on, as consensus observers can't fetch the batches"
+    );
 
     // StateSync should be instantiated and started before Consensus to avoid a cyclic dependency:
     // network provider -> consensus -> state synchronizer -> network provider.  This has resulted
@@ -872,14 +1041,39 @@ pub fn setup_environment(
             consensus_reconfig_subscription
                 .expect("Consensus requires a reconfiguration subscription!"),
             peer_metadata_storage,
+            consensus_observer_network_handles,
         ));
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
+    } else if let Some((consensus_observer_network_sender, consensus_observer_network_events)) =
+        consensus_observer_network_handles
+    {
+        // The consensus observer takes over execution from state sync, so it
+        // can only start once state sync is initialized.
+        debug!("Wait until state sync is initialized");
+        state_sync_runtimes.block_until_initialized();
+        debug!("State sync initialization complete.");
+
+        // Initialize and start the consensus observer.
+        instant = Instant::now();
+        consensus_observer_runtime = Some(start_consensus_observer(
+            &node_config,
+            consensus_observer_network_sender,
+            consensus_observer_network_events,
+            Arc::new(consensus_notifier),
+            consensus_to_mempool_sender,
+            db_rw,
+        ));
+        debug!(
+            "Consensus observer started in {} ms",
+            instant.elapsed().as_millis()
+        );
     }
 
     Ok(AptosHandle {
         _api: api_runtime,
         _backup: backup_service,
         _consensus_runtime: consensus_runtime,
+        _consensus_observer_runtime: consensus_observer_runtime,
         _mempool: mempool,
         _network_runtimes: network_runtimes,
         _index_runtime: index_runtime,
@@ -30,6 +30,16 @@ pub struct ApiConfig {
     pub transaction_submission_enabled: bool,
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    #[serde(default = "default_enabled")]
+    pub view_function_enabled: bool,
+    /// Maximum gas units a single view function call may consume
+    pub max_view_function_gas: u64,
+    #[serde(default = "default_enabled")]
+    pub stream_enabled: bool,
+    /// How often open transaction and event streams check for new transactions
+    pub stream_poll_interval_ms: u64,
+    /// Maximum number of transaction and event streams open at the same time
+    pub max_concurrent_streams: usize,
 
     pub max_submit_transaction_batch_size: usize,
 
@@ -38,6 +48,7 @@ pub struct ApiConfig {
     pub max_events_page_size: u16,
     pub max_account_resources_page_size: u16,
     pub max_account_modules_page_size: u16,
+    pub max_state_changes_page_size: u16,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
@@ -47,6 +58,9 @@ pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 pub const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 pub const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
+pub const DEFAULT_MAX_VIEW_FUNCTION_GAS: u64 = 2_000_000;
+pub const DEFAULT_STREAM_POLL_INTERVAL_MS: u64 = 500;
+pub const DEFAULT_MAX_CONCURRENT_STREAMS: usize = 100;
 
 fn default_enabled() -> bool {
     true
@@ -72,11 +86,17 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            view_function_enabled: default_enabled(),
+            max_view_function_gas: DEFAULT_MAX_VIEW_FUNCTION_GAS,
+            stream_enabled: default_enabled(),
+            stream_poll_interval_ms: DEFAULT_STREAM_POLL_INTERVAL_MS,
+            max_concurrent_streams: DEFAULT_MAX_CONCURRENT_STREAMS,
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
+            max_state_changes_page_size: DEFAULT_MAX_PAGE_SIZE,
         }
     }
 }
@@ -32,6 +32,19 @@ pub struct ConsensusConfig {
     // the period = (poll_count - 1) * 30ms
     pub quorum_store_poll_count: u64,
     pub intra_consensus_channel_buffer_size: usize,
+    // How often a validator pulls a new batch from mempool when using the quorum store
+    pub quorum_store_batch_interval_ms: u64,
+    pub quorum_store_max_batch_txns: u64,
+    pub quorum_store_max_batch_bytes: u64,
+    // Batches (and their proofs) expire this many rounds after the latest committed round
+    pub quorum_store_batch_expiry_rounds: u64,
+    // Proofs that expire within this many rounds are not proposed anymore
+    pub quorum_store_expiration_margin_rounds: u64,
+    // Back pressure: stop creating batches while this many of our batches are not committed
+    pub quorum_store_max_batches_in_progress: usize,
+    // How many bytes of batches are stored for each validator
+    pub quorum_store_memory_quota_per_peer_bytes: u64,
+    pub quorum_store_batch_request_timeout_ms: u64,
 }
 
 impl Default for ConsensusConfig {
@@ -59,6 +72,14 @@ impl Default for ConsensusConfig {
             quorum_store_pull_timeout_ms: 1000,
             quorum_store_poll_count: 10,
             intra_consensus_channel_buffer_size: 10,
+            quorum_store_batch_interval_ms: 50,
+            quorum_store_max_batch_txns: 500,
+            quorum_store_max_batch_bytes: 500 * 1024, // 500 KB
+            quorum_store_batch_expiry_rounds: 100,
+            quorum_store_expiration_margin_rounds: 20,
+            quorum_store_max_batches_in_progress: 20,
+            quorum_store_memory_quota_per_peer_bytes: 100 * 1024 * 1024, // 100 MB
+            quorum_store_batch_request_timeout_ms: 1000,
         }
     }
 }
@@ -0,0 +1,24 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusObserverConfig {
+    pub observer_enabled: bool, // Whether the (validator full) node observes its validator's consensus
+    pub publisher_enabled: bool, // Whether the validator publishes consensus updates to its VFNs
+    pub max_network_channel_size: u64, // Max num of pending network messages
+    pub observer_fallback_duration_ms: u64, // Time (ms) without observer commits before state sync takes over
+}
+
+impl Default for ConsensusObserverConfig {
+    fn default() -> Self {
+        Self {
+            observer_enabled: false,
+            publisher_enabled: false,
+            max_network_channel_size: 1000,
+            observer_fallback_duration_ms: 10_000, // 10 seconds
+        }
+    }
+}
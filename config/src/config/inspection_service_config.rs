@@ -11,6 +11,11 @@ pub struct InspectionServiceConfig {
     pub port: u16,
     pub expose_configuration: bool,
     pub expose_system_information: bool,
+    // Allows viewing the peer ban lists of the networks
+    pub expose_peer_bans: bool,
+    // Allows banning and unbanning peers. The endpoints aren't authenticated, so only enable
+    // them if the inspection service can't be reached from untrusted hosts.
+    pub enable_peer_ban_updates: bool,
 }
 
 impl Default for InspectionServiceConfig {
@@ -20,6 +25,8 @@ impl Default for InspectionServiceConfig {
             port: 9101,
             expose_configuration: false,
             expose_system_information: true,
+            expose_peer_bans: false,
+            enable_peer_ban_updates: false,
         }
     }
 }
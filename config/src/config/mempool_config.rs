@@ -3,6 +3,7 @@
 
 use crate::config::MAX_APPLICATION_MESSAGE_SIZE;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 pub const DEFAULT_BROADCAST_BUCKETS: &[u64] =
     &[0, 150, 300, 500, 1000, 3000, 5000, 10000, 100000, 1000000];
@@ -27,6 +28,12 @@ pub struct MempoolConfig {
     pub system_transaction_gc_interval_ms: u64,
     pub shared_mempool_validator_broadcast: bool,
     pub broadcast_buckets: Vec<u64>,
+    // journal the transactions in mempool to disk, so that they survive restarts
+    pub enable_journal: bool,
+    // relative to the data directory, unless absolute
+    pub journal_path: PathBuf,
+    #[serde(skip)]
+    data_dir: PathBuf,
 }
 
 impl Default for MempoolConfig {
@@ -48,6 +55,23 @@ impl Default for MempoolConfig {
             system_transaction_gc_interval_ms: 60_000,
             shared_mempool_validator_broadcast: true,
             broadcast_buckets: DEFAULT_BROADCAST_BUCKETS.to_vec(),
+            enable_journal: false,
+            journal_path: PathBuf::from("mempool/journal"),
+            data_dir: PathBuf::from("/opt/aptos/data"),
         }
     }
 }
+
+impl MempoolConfig {
+    pub fn journal_path(&self) -> PathBuf {
+        if self.journal_path.is_relative() {
+            self.data_dir.join(&self.journal_path)
+        } else {
+            self.journal_path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
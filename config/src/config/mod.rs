@@ -18,6 +18,8 @@ use thiserror::Error;
 
 mod consensus_config;
 pub use consensus_config::*;
+mod consensus_observer_config;
+pub use consensus_observer_config::*;
 mod error;
 pub use error::*;
 mod execution_config;
@@ -66,6 +68,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub consensus: ConsensusConfig,
     #[serde(default)]
+    pub consensus_observer: ConsensusObserverConfig,
+    #[serde(default)]
     pub execution: ExecutionConfig,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub full_node_networks: Vec<NetworkConfig>,
@@ -269,6 +273,13 @@ impl NodeConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.base.data_dir = data_dir.clone();
         self.consensus.set_data_dir(data_dir.clone());
+        self.mempool.set_data_dir(data_dir.clone());
+        if let Some(network) = self.validator_network.as_mut() {
+            network.peer_reputation.set_data_dir(data_dir.clone());
+        }
+        for network in self.full_node_networks.iter_mut() {
+            network.peer_reputation.set_data_dir(data_dir.clone());
+        }
         self.storage.set_data_dir(data_dir);
     }
 
@@ -320,12 +331,31 @@ impl NodeConfig {
             return Ok(self);
         }
 
-        self.indexer.postgres_uri = env_or_default(
-            "INDEXER_DATABASE_URL",
-            self.indexer.postgres_uri,
-            must_be_set("postgres_uri", "INDEXER_DATABASE_URL"),
+        self.indexer.database_backend = env_or_default(
+            "INDEXER_DATABASE_BACKEND",
+            self.indexer
+                .database_backend
+                .or(Some(IndexerDatabaseBackend::Postgres)),
+            None,
         );
 
+        match self.indexer.database_backend {
+            Some(IndexerDatabaseBackend::Sqlite) => {
+                self.indexer.sqlite_path = env_or_default(
+                    "INDEXER_SQLITE_PATH",
+                    self.indexer.sqlite_path,
+                    must_be_set("sqlite_path", "INDEXER_SQLITE_PATH"),
+                );
+            }
+            _ => {
+                self.indexer.postgres_uri = env_or_default(
+                    "INDEXER_DATABASE_URL",
+                    self.indexer.postgres_uri,
+                    must_be_set("postgres_uri", "INDEXER_DATABASE_URL"),
+                );
+            }
+        }
+
         self.indexer.processor = env_or_default(
             "PROCESSOR_NAME",
             self.indexer
@@ -27,6 +27,7 @@ use std::{
     string::ToString,
     time::Duration,
 };
+use url::Url;
 
 // TODO: We could possibly move these constants somewhere else, but since they are defaults for the
 //   configurations of the system, we'll leave it here for now.
@@ -113,6 +114,8 @@ pub struct NetworkConfig {
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
     // The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
+    // Tracks misbehaving peers and bans them from connecting for a while
+    pub peer_reputation: PeerReputationConfig,
 }
 
 impl Default for NetworkConfig {
@@ -153,6 +156,7 @@ impl NetworkConfig {
             inbound_tx_buffer_size_bytes: Some(INBOUND_TCP_TX_BUFFER_SIZE),
             outbound_rx_buffer_size_bytes: Some(OUTBOUND_TCP_RX_BUFFER_SIZE),
             outbound_tx_buffer_size_bytes: Some(OUTBOUND_TCP_TX_BUFFER_SIZE),
+            peer_reputation: PeerReputationConfig::default(),
         };
         config.prepare_identity();
         config
@@ -328,6 +332,60 @@ impl NetworkConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerReputationConfig {
+    // Ban peers that repeatedly misbehave, e.g., send invalid messages or fail handshakes
+    pub enable_automatic_bans: bool,
+    // Number of failures within the failure window that gets a peer banned
+    pub failure_threshold: u64,
+    pub failure_window_secs: u64,
+    // The first ban of a peer lasts this long, every following ban twice as long as the last
+    pub initial_ban_secs: u64,
+    pub max_ban_secs: u64,
+    // Peers that are never allowed to connect, unless they're in the on-chain validator set
+    pub banned_peers: HashSet<PeerId>,
+    // Persist the reputations of the peers to disk, so that bans survive restarts
+    pub enable_persistence: bool,
+    // Directory of the reputation files, one per network. Relative to the data directory,
+    // unless absolute
+    pub persistence_dir: PathBuf,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl Default for PeerReputationConfig {
+    fn default() -> Self {
+        Self {
+            enable_automatic_bans: false,
+            failure_threshold: 10,
+            failure_window_secs: 60,
+            initial_ban_secs: 60,
+            max_ban_secs: 24 * 60 * 60, /* 1 day */
+            banned_peers: HashSet::new(),
+            enable_persistence: true,
+            persistence_dir: PathBuf::from("network/peer_reputation"),
+            data_dir: PathBuf::from("/opt/aptos/data"),
+        }
+    }
+}
+
+impl PeerReputationConfig {
+    /// The file the reputations of the peers of the given network are persisted to
+    pub fn persistence_path(&self, network_id: NetworkId) -> PathBuf {
+        let dir = if self.persistence_dir.is_relative() {
+            self.data_dir.join(&self.persistence_dir)
+        } else {
+            self.persistence_dir.clone()
+        };
+        dir.join(format!("{}.json", network_id.as_str().to_lowercase()))
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct PeerMonitoringServiceConfig {
@@ -349,9 +407,37 @@ impl Default for PeerMonitoringServiceConfig {
 pub enum DiscoveryMethod {
     Onchain,
     File(PathBuf, Duration),
+    Rest(RestDiscovery),
     None,
 }
 
+/// Polls a URL for peers, so that networks can follow validator set changes without a local
+/// reconfiguration stream.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestDiscovery {
+    pub url: Url,
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub source: RestDiscoverySource,
+}
+
+/// What the URL of a [`RestDiscovery`] serves
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestDiscoverySource {
+    /// The REST API of a node, to read the on-chain validator set from
+    ValidatorSet,
+    /// A static seed list, a `PeerSet` in the same format as the one of `DiscoveryMethod::File`
+    SeedList,
+}
+
+impl Default for RestDiscoverySource {
+    fn default() -> Self {
+        RestDiscoverySource::ValidatorSet
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Identity {
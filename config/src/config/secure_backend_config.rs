@@ -3,8 +3,8 @@
 
 use crate::config::Error;
 use aptos_secure_storage::{
-    GitHubStorage, InMemoryStorage, Namespaced, OnDiskStorage, RocksDbStorage, Storage,
-    VaultStorage, SECURE_STORAGE_DB_NAME,
+    EncryptedOnDiskStorage, GitHubStorage, InMemoryStorage, Namespaced, OnDiskStorage,
+    RocksDbStorage, Storage, VaultStorage, SECURE_STORAGE_DB_NAME,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -20,6 +20,7 @@ pub enum SecureBackend {
     InMemoryStorage,
     Vault(VaultConfig),
     OnDiskStorage(OnDiskStorageConfig),
+    EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig),
     RocksDbStorage(RocksDbStorageConfig),
 }
 
@@ -29,6 +30,10 @@ impl SecureBackend {
             SecureBackend::GitHub(GitHubConfig { namespace, .. })
             | SecureBackend::Vault(VaultConfig { namespace, .. })
             | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig {
+                namespace,
+                ..
+            })
             | SecureBackend::RocksDbStorage(RocksDbStorageConfig { namespace, .. }) => {
                 namespace.as_deref()
             }
@@ -41,6 +46,10 @@ impl SecureBackend {
             SecureBackend::GitHub(GitHubConfig { namespace, .. })
             | SecureBackend::Vault(VaultConfig { namespace, .. })
             | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig {
+                namespace,
+                ..
+            })
             | SecureBackend::RocksDbStorage(RocksDbStorageConfig { namespace, .. }) => {
                 *namespace = None;
             }
@@ -144,6 +153,45 @@ pub struct TokenFromDisk {
     path: PathBuf,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptedOnDiskStorageConfig {
+    // Required path for encrypted on disk storage
+    pub path: PathBuf,
+    /// A namespace is an optional portion of the path to a key stored within
+    /// EncryptedOnDiskStorage. For example, a key, S, without a namespace would be available in S,
+    /// with a namespace, N, it would be in N/S.
+    pub namespace: Option<String>,
+    /// The passphrase or key file the encryption key is derived from. Prefer `from_disk` with a
+    /// file readable only by the node, so the secret does not end up in the node config.
+    pub encryption_key: Token,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl EncryptedOnDiskStorageConfig {
+    pub fn new(path: PathBuf, encryption_key: Token) -> Self {
+        Self {
+            path,
+            namespace: None,
+            encryption_key,
+            data_dir: PathBuf::from("/opt/aptos/data"),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        if self.path.is_relative() {
+            self.data_dir.join(&self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
+
 impl Default for OnDiskStorageConfig {
     fn default() -> Self {
         Self {
@@ -243,6 +291,21 @@ impl From<&SecureBackend> for Storage {
                     storage
                 }
             }
+            SecureBackend::EncryptedOnDiskStorage(config) => {
+                let storage = Storage::from(EncryptedOnDiskStorage::new(
+                    config.path(),
+                    config
+                        .encryption_key
+                        .read_token()
+                        .expect("Unable to read encryption key")
+                        .as_bytes(),
+                ));
+                if let Some(namespace) = &config.namespace {
+                    Storage::from(Namespaced::new(namespace, Box::new(storage)))
+                } else {
+                    storage
+                }
+            }
             SecureBackend::RocksDbStorage(config) => {
                 let storage = Storage::from(RocksDbStorage::new(config.path()));
                 if let Some(namespace) = &config.namespace {
@@ -369,6 +432,40 @@ vault:
         serde_yaml::to_string(&from_disk).unwrap();
     }
 
+    #[test]
+    fn test_encrypted_on_disk_parsing() {
+        #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+        struct Config {
+            backend: SecureBackend,
+        }
+
+        let from_config = Config {
+            backend: SecureBackend::EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig::new(
+                PathBuf::from("secure_storage.enc"),
+                Token::FromDisk(PathBuf::from("/secure_storage.key")),
+            )),
+        };
+
+        let text_from_config = r#"
+backend:
+    type: "encrypted_on_disk_storage"
+    path: "secure_storage.enc"
+    namespace: ~
+    encryption_key:
+        from_disk: "/secure_storage.key"
+        "#;
+
+        let de_from_config: Config = serde_yaml::from_str(text_from_config).unwrap();
+        assert_eq!(de_from_config.backend.namespace(), None);
+        if let SecureBackend::EncryptedOnDiskStorage(config) = &de_from_config.backend {
+            assert_eq!(config.path, PathBuf::from("secure_storage.enc"));
+        } else {
+            panic!("Unexpected backend: {:?}", de_from_config.backend);
+        }
+        // Just assert that it can be serialized, not about to do string comparison
+        serde_yaml::to_string(&from_config).unwrap();
+    }
+
     #[test]
     fn test_token_reading() {
         let temppath = aptos_temppath::TempPath::new();
@@ -170,6 +170,7 @@ impl Default for DataStreamingServiceConfig {
 pub struct AptosDataClientConfig {
     pub max_num_in_flight_priority_polls: u64, // Max num of in-flight polls for priority peers
     pub max_num_in_flight_regular_polls: u64,  // Max num of in-flight polls for regular peers
+    pub peer_monitoring_poll_interval_ms: u64, // Interval (in milliseconds) between peer monitoring polls
     pub response_timeout_ms: u64, // Timeout (in milliseconds) when waiting for a response
     pub summary_poll_interval_ms: u64, // Interval (in milliseconds) between data summary polls
     pub use_compression: bool,    // Whether or not to request compression for incoming data
@@ -180,6 +181,7 @@ impl Default for AptosDataClientConfig {
         Self {
             max_num_in_flight_priority_polls: 10,
             max_num_in_flight_regular_polls: 10,
+            peer_monitoring_poll_interval_ms: 5000,
             response_timeout_ms: 20000, // 20 seconds
             summary_poll_interval_ms: 200,
             use_compression: true,
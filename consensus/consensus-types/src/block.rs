@@ -15,13 +15,14 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     epoch_state::EpochState,
     ledger_info::LedgerInfo,
-    transaction::{Transaction, Version},
+    transaction::{SignedTransaction, Transaction, Version},
     validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
 use mirai_annotations::debug_checked_verify_eq;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
+    collections::HashSet,
     convert::TryFrom,
     fmt::{self, Display, Formatter},
     iter::once,
@@ -245,6 +246,11 @@ impl Block {
                     .as_ref()
                     .ok_or_else(|| format_err!("Missing signature in Proposal"))?;
                 validator.verify(*author, &self.block_data, signature)?;
+                if let Some(Payload::InQuorumStore(proofs)) = self.payload() {
+                    for proof in proofs {
+                        proof.verify(validator)?;
+                    }
+                }
                 self.quorum_cert().verify(validator)
             }
         }
@@ -323,6 +329,24 @@ impl Block {
             !self.quorum_cert().ends_epoch(),
             "Block cannot be proposed in an epoch that has ended"
         );
+        if let Some(Payload::InQuorumStore(proofs)) = self.payload() {
+            // The batches have to be available for execution, so their proofs must outlive the
+            // block, and a batch must not be included twice.
+            let mut digests = HashSet::new();
+            for proof in proofs {
+                ensure!(
+                    proof.epoch() == self.epoch() && proof.expiration().round() > self.round(),
+                    "ProofOfStore {} expires at {}, before the block",
+                    proof.digest(),
+                    proof.expiration()
+                );
+                ensure!(
+                    digests.insert(*proof.digest()),
+                    "Duplicate ProofOfStore {} in block",
+                    proof.digest()
+                );
+            }
+        }
         debug_checked_verify_eq!(
             self.id(),
             self.block_data.hash(),
@@ -331,17 +355,17 @@ impl Block {
         Ok(())
     }
 
-    pub fn transactions_to_execute(&self, validators: &[AccountAddress]) -> Vec<Transaction> {
+    /// The transactions to execute for this block, `txns` are the user transactions of its
+    /// payload, which may have to be fetched from quorum store first.
+    pub fn transactions_to_execute(
+        &self,
+        validators: &[AccountAddress],
+        txns: Vec<SignedTransaction>,
+    ) -> Vec<Transaction> {
         once(Transaction::BlockMetadata(
             self.new_block_metadata(validators),
         ))
-        .chain(
-            self.payload()
-                .unwrap_or(&Payload::empty())
-                .clone()
-                .into_iter()
-                .map(Transaction::UserTransaction),
-        )
+        .chain(txns.into_iter().map(Transaction::UserTransaction))
         .chain(once(Transaction::StateCheckpoint(self.id)))
         .collect()
     }
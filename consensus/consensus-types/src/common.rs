@@ -1,11 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::proof_of_store::ProofOfStore;
 use aptos_crypto::HashValue;
 use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::{fmt, fmt::Write};
+use std::{collections::HashSet, fmt, fmt::Write};
 
 /// The round of a block is a consensus-internal counter, which starts with 0 and increases
 /// monotonically. It is used for the protocol safety and liveness (please see the detailed
@@ -37,6 +38,9 @@ pub struct RejectedTransactionSummary {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum Payload {
     DirectMempool(Vec<SignedTransaction>),
+    /// Proofs of batches stored by a quorum of validators, the transactions are fetched from
+    /// quorum store for execution.
+    InQuorumStore(Vec<ProofOfStore>),
 }
 
 impl Payload {
@@ -47,12 +51,16 @@ impl Payload {
     pub fn len(&self) -> usize {
         match self {
             Payload::DirectMempool(txns) => txns.len(),
+            Payload::InQuorumStore(proofs) => {
+                proofs.iter().map(|proof| proof.num_txns() as usize).sum()
+            }
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
             Payload::DirectMempool(txns) => txns.is_empty(),
+            Payload::InQuorumStore(proofs) => proofs.is_empty(),
         }
     }
 
@@ -64,18 +72,9 @@ impl Payload {
                 .with_min_len(100)
                 .map(|txn| txn.raw_txn_bytes_len())
                 .sum(),
-        }
-    }
-}
-
-// TODO: What I really want is an iterator that isn't necessarily a vector (e.g., read lazily from RocksDB). This doesn't seem like the way.
-impl IntoIterator for Payload {
-    type Item = SignedTransaction;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        match self {
-            Payload::DirectMempool(txns) => txns.into_iter(),
+            Payload::InQuorumStore(proofs) => {
+                proofs.iter().map(|proof| proof.num_bytes() as usize).sum()
+            }
         }
     }
 }
@@ -86,6 +85,9 @@ impl fmt::Display for Payload {
             Payload::DirectMempool(txns) => {
                 write!(f, "InMemory txns: {}", txns.len())
             }
+            Payload::InQuorumStore(proofs) => {
+                write!(f, "InQuorumStore proofs: {}", proofs.len())
+            }
         }
     }
 }
@@ -94,18 +96,28 @@ impl fmt::Display for Payload {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum PayloadFilter {
     DirectMempool(Vec<TransactionSummary>),
+    InQuorumStore(HashSet<HashValue>),
 }
 
 impl From<&Vec<&Payload>> for PayloadFilter {
     fn from(exclude_payloads: &Vec<&Payload>) -> Self {
-        if exclude_payloads.is_empty() {
-            return PayloadFilter::DirectMempool(vec![]);
-        }
-        match exclude_payloads.first().unwrap() {
-            Payload::DirectMempool(_) => {
-                let mut exclude_txns = vec![];
-                for payload in exclude_payloads {
-                    let Payload::DirectMempool(txns) = payload;
+        // Empty payloads of nil blocks are always DirectMempool, so look at all of them to
+        // decide which kind of filter is needed.
+        let in_quorum_store = exclude_payloads
+            .iter()
+            .any(|payload| matches!(payload, Payload::InQuorumStore(_)));
+        if in_quorum_store {
+            let mut exclude_digests = HashSet::new();
+            for payload in exclude_payloads {
+                if let Payload::InQuorumStore(proofs) = payload {
+                    exclude_digests.extend(proofs.iter().map(|proof| *proof.digest()));
+                }
+            }
+            PayloadFilter::InQuorumStore(exclude_digests)
+        } else {
+            let mut exclude_txns = vec![];
+            for payload in exclude_payloads {
+                if let Payload::DirectMempool(txns) = payload {
                     for txn in txns {
                         exclude_txns.push(TransactionSummary {
                             sender: txn.sender(),
@@ -113,8 +125,8 @@ impl From<&Vec<&Payload>> for PayloadFilter {
                         });
                     }
                 }
-                PayloadFilter::DirectMempool(exclude_txns)
             }
+            PayloadFilter::DirectMempool(exclude_txns)
         }
     }
 }
@@ -129,6 +141,13 @@ impl fmt::Display for PayloadFilter {
                 }
                 write!(f, "{}", txns_str)
             }
+            PayloadFilter::InQuorumStore(excluded_digests) => {
+                let mut digests_str = "".to_string();
+                for digest in excluded_digests.iter() {
+                    write!(digests_str, "{} ", digest)?;
+                }
+                write!(f, "{}", digests_str)
+            }
         }
     }
 }
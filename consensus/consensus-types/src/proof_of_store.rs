@@ -0,0 +1,299 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::Round;
+use anyhow::Context;
+use aptos_crypto::{bls12381, hash::CryptoHash, CryptoMaterialError, HashValue};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_types::{
+    aggregate_signature::AggregateSignature, transaction::SignedTransaction,
+    validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier, PeerId,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// A point in consensus time, used to expire batches and their proofs.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LogicalTime {
+    epoch: u64,
+    round: Round,
+}
+
+impl LogicalTime {
+    pub fn new(epoch: u64, round: Round) -> Self {
+        Self { epoch, round }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn round(&self) -> Round {
+        self.round
+    }
+}
+
+impl Display for LogicalTime {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "[epoch: {}, round: {}]", self.epoch, self.round)
+    }
+}
+
+/// The transactions of a batch, the digest of a batch is the hash of its payload.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, CryptoHasher, BCSCryptoHash)]
+pub struct BatchPayload {
+    txns: Vec<SignedTransaction>,
+}
+
+/// A batch of transactions a validator broadcasts for the others to store, so that proposals
+/// only need to carry its digest.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Batch {
+    epoch: u64,
+    source: PeerId,
+    expiration: LogicalTime,
+    payload: BatchPayload,
+}
+
+impl Batch {
+    pub fn new(
+        epoch: u64,
+        source: PeerId,
+        expiration: LogicalTime,
+        txns: Vec<SignedTransaction>,
+    ) -> Self {
+        Self {
+            epoch,
+            source,
+            expiration,
+            payload: BatchPayload { txns },
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn source(&self) -> PeerId {
+        self.source
+    }
+
+    pub fn expiration(&self) -> LogicalTime {
+        self.expiration
+    }
+
+    /// This is computationally expensive, callers should compute it once
+    pub fn digest(&self) -> HashValue {
+        self.payload.hash()
+    }
+
+    pub fn num_txns(&self) -> u64 {
+        self.payload.txns.len() as u64
+    }
+
+    /// This is computationally expensive, callers should compute it once
+    pub fn num_bytes(&self) -> u64 {
+        self.payload
+            .txns
+            .iter()
+            .map(|txn| txn.raw_txn_bytes_len() as u64)
+            .sum()
+    }
+
+    pub fn txns(&self) -> &[SignedTransaction] {
+        &self.payload.txns
+    }
+
+    pub fn into_transactions(self) -> Vec<SignedTransaction> {
+        self.payload.txns
+    }
+}
+
+impl Display for Batch {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Batch: [epoch: {}, source: {}, expiration: {}, txns: {}]",
+            self.epoch,
+            self.source,
+            self.expiration,
+            self.payload.txns.len()
+        )
+    }
+}
+
+/// Request for a batch the requester is missing, answered with the batch.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BatchRequest {
+    epoch: u64,
+    source: PeerId,
+    digest: HashValue,
+}
+
+impl BatchRequest {
+    pub fn new(epoch: u64, source: PeerId, digest: HashValue) -> Self {
+        Self {
+            epoch,
+            source,
+            digest,
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn source(&self) -> PeerId {
+        self.source
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.digest
+    }
+}
+
+impl Display for BatchRequest {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "BatchRequest: [epoch: {}, source: {}, digest: {}]",
+            self.epoch, self.source, self.digest
+        )
+    }
+}
+
+/// What a validator signs when it has stored a batch: it promises to serve the batch until
+/// the expiration.
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, CryptoHasher, BCSCryptoHash,
+)]
+pub struct SignedDigestInfo {
+    pub digest: HashValue,
+    pub expiration: LogicalTime,
+    pub num_txns: u64,
+    pub num_bytes: u64,
+}
+
+impl SignedDigestInfo {
+    pub fn new(digest: HashValue, expiration: LogicalTime, num_txns: u64, num_bytes: u64) -> Self {
+        Self {
+            digest,
+            expiration,
+            num_txns,
+            num_bytes,
+        }
+    }
+}
+
+/// A signature of a single validator on a stored batch, sent back to the author of the batch.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SignedDigest {
+    epoch: u64,
+    peer_id: PeerId,
+    info: SignedDigestInfo,
+    signature: bls12381::Signature,
+}
+
+impl SignedDigest {
+    pub fn new(
+        epoch: u64,
+        info: SignedDigestInfo,
+        validator_signer: &ValidatorSigner,
+    ) -> Result<Self, CryptoMaterialError> {
+        let signature = validator_signer.sign(&info)?;
+        Ok(Self {
+            epoch,
+            peer_id: validator_signer.author(),
+            info,
+            signature,
+        })
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn peer_id(&self) -> PeerId {
+        self.peer_id
+    }
+
+    pub fn info(&self) -> &SignedDigestInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> HashValue {
+        self.info.digest
+    }
+
+    pub fn signature(&self) -> &bls12381::Signature {
+        &self.signature
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify(self.peer_id, &self.info, &self.signature)
+            .context("Failed to verify SignedDigest")
+    }
+}
+
+/// Certifies that a quorum of validators stored a batch, so it can be fetched from them until
+/// it expires. Proposals in quorum store mode carry these instead of transactions.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ProofOfStore {
+    info: SignedDigestInfo,
+    multi_signature: AggregateSignature,
+}
+
+impl ProofOfStore {
+    pub fn new(info: SignedDigestInfo, multi_signature: AggregateSignature) -> Self {
+        Self {
+            info,
+            multi_signature,
+        }
+    }
+
+    pub fn info(&self) -> &SignedDigestInfo {
+        &self.info
+    }
+
+    pub fn digest(&self) -> &HashValue {
+        &self.info.digest
+    }
+
+    pub fn expiration(&self) -> LogicalTime {
+        self.info.expiration
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.info.expiration.epoch()
+    }
+
+    pub fn num_txns(&self) -> u64 {
+        self.info.num_txns
+    }
+
+    pub fn num_bytes(&self) -> u64 {
+        self.info.num_bytes
+    }
+
+    /// The validators that signed the proof, i.e. that promised to serve the batch
+    pub fn signers(&self, ordered_validators: &[PeerId]) -> Vec<PeerId> {
+        self.multi_signature.get_voter_addresses(ordered_validators)
+    }
+
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        validator
+            .verify_multi_signatures(&self.info, &self.multi_signature)
+            .context("Failed to verify ProofOfStore")
+    }
+}
+
+impl Display for ProofOfStore {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "ProofOfStore: [digest: {}, expiration: {}, txns: {}]",
+            self.info.digest, self.info.expiration, self.info.num_txns
+        )
+    }
+}
@@ -25,6 +25,8 @@ pub enum ConsensusRequest {
         u64,
         // round
         Round,
+        // payloads of the committed blocks
+        Vec<Payload>,
         // callback to respond to
         oneshot::Sender<Result<ConsensusResponse>>,
     ),
@@ -40,7 +42,7 @@ impl fmt::Display for ConsensusRequest {
                     max_txns, max_bytes, excluded
                 )
             }
-            ConsensusRequest::CleanRequest(epoch, round, _) => {
+            ConsensusRequest::CleanRequest(epoch, round, _, _) => {
                 write!(f, "CleanRequest [epoch: {}, round: {}]", epoch, round)
             }
         }
@@ -55,6 +55,10 @@ pub enum Error {
     WaypointOutOfDate(u64, u64, u64, u64),
     #[error("Invalid Timeout: {0}")]
     InvalidTimeout(String),
+    #[error("Provided author, {0}, does not match expected author, {1}")]
+    IncorrectAuthor(String, String),
+    #[error("Unsupported safety data interchange version {0}, expected version {1}")]
+    UnsupportedSafetyDataVersion(u64, u64),
 }
 
 impl From<serde_json::Error> for Error {
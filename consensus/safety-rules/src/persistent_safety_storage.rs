@@ -4,7 +4,7 @@
 use crate::{
     counters,
     logging::{self, LogEntry, LogEvent},
-    Error,
+    Error, SafetyDataInterchange,
 };
 use aptos_crypto::{bls12381, PrivateKey};
 use aptos_global_constants::{CONSENSUS_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT};
@@ -144,6 +144,32 @@ impl PersistentSafetyStorage {
         }
     }
 
+    /// Exports the safety data in the portable interchange format
+    pub fn export_safety_data(&mut self) -> Result<SafetyDataInterchange, Error> {
+        Ok(SafetyDataInterchange::new(
+            self.author()?,
+            self.safety_data()?,
+        ))
+    }
+
+    /// Merges the exported safety data into the stored one. The exported data must belong to
+    /// the same author, and to the same or a later epoch.
+    pub fn import_safety_data(
+        &mut self,
+        interchange: &SafetyDataInterchange,
+    ) -> Result<SafetyData, Error> {
+        let merged = self.export_safety_data()?.merge(interchange)?;
+        self.set_safety_data(merged.safety_data.clone())?;
+        info!(
+            logging::SafetyLogSchema::new(LogEntry::State, LogEvent::Update)
+                .author(merged.author)
+                .epoch(merged.safety_data.epoch)
+                .last_voted_round(merged.safety_data.last_voted_round)
+                .preferred_round(merged.safety_data.preferred_round)
+        );
+        Ok(merged.safety_data)
+    }
+
     pub fn waypoint(&self) -> Result<Waypoint, Error> {
         let _timer = counters::start_timer("get", WAYPOINT);
         Ok(self.internal_store.get(WAYPOINT).map(|v| v.value)?)
@@ -168,13 +194,14 @@ impl PersistentSafetyStorage {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::counters;
+    use crate::{counters, SAFETY_DATA_INTERCHANGE_VERSION};
     use aptos_crypto::hash::HashValue;
     use aptos_secure_storage::InMemoryStorage;
     use aptos_types::{
         block_info::BlockInfo, epoch_state::EpochState, ledger_info::LedgerInfo,
         transaction::Version, validator_signer::ValidatorSigner, waypoint::Waypoint,
     };
+    use consensus_types::{vote::Vote, vote_data::VoteData};
     use rusty_fork::rusty_fork_test;
 
     // Metrics are globally instantiated. We use rusty_fork to prevent concurrent tests
@@ -251,4 +278,167 @@ mod tests {
             );
         }
     }
+
+    fn make_vote(signer: &ValidatorSigner, round: u64) -> Vote {
+        let block_info =
+            |round| BlockInfo::new(1, round, HashValue::zero(), HashValue::zero(), 0, 0, None);
+        Vote::new(
+            VoteData::new(block_info(round), block_info(round - 1)),
+            signer.author(),
+            LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+            signer,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_export_and_import_safety_data() {
+        let signer = ValidatorSigner::from_int(0);
+        let mut safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(InMemoryStorage::new()),
+            signer.author(),
+            signer.private_key().clone(),
+            Waypoint::default(),
+            true,
+        );
+        let vote = make_vote(&signer, 10);
+        safety_storage
+            .set_safety_data(SafetyData::new(1, 10, 8, 9, Some(vote.clone())))
+            .unwrap();
+
+        // The exported data survives a round trip through the interchange format
+        let exported = safety_storage.export_safety_data().unwrap();
+        assert_eq!(exported.version, SAFETY_DATA_INTERCHANGE_VERSION);
+        assert_eq!(exported.author, signer.author());
+        let bytes = serde_json::to_vec(&exported).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<SafetyDataInterchange>(&bytes).unwrap(),
+            exported
+        );
+
+        // Importing keeps the highest of each watermark, and the latest vote
+        let newer_vote = make_vote(&signer, 12);
+        let imported = SafetyDataInterchange::new(
+            signer.author(),
+            SafetyData::new(1, 12, 7, 11, Some(newer_vote.clone())),
+        );
+        let merged = safety_storage.import_safety_data(&imported).unwrap();
+        assert_eq!(merged, SafetyData::new(1, 12, 8, 11, Some(newer_vote)));
+        assert_eq!(safety_storage.safety_data().unwrap(), merged);
+
+        // Importing older data doesn't lower any watermark
+        let older =
+            SafetyDataInterchange::new(signer.author(), SafetyData::new(1, 3, 2, 1, Some(vote)));
+        assert_eq!(safety_storage.import_safety_data(&older).unwrap(), merged);
+    }
+
+    #[test]
+    fn test_import_incompatible_safety_data() {
+        let signer = ValidatorSigner::from_int(0);
+        let mut safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(InMemoryStorage::new()),
+            signer.author(),
+            signer.private_key().clone(),
+            Waypoint::default(),
+            true,
+        );
+        safety_storage
+            .set_safety_data(SafetyData::new(2, 5, 4, 4, None))
+            .unwrap();
+        let safety_data = safety_storage.safety_data().unwrap();
+
+        // Another author
+        let other_author =
+            SafetyDataInterchange::new(Author::random(), SafetyData::new(2, 10, 8, 9, None));
+        assert!(matches!(
+            safety_storage.import_safety_data(&other_author),
+            Err(Error::IncorrectAuthor(_, _))
+        ));
+
+        // An earlier epoch
+        let earlier_epoch =
+            SafetyDataInterchange::new(signer.author(), SafetyData::new(1, 10, 8, 9, None));
+        assert_eq!(
+            safety_storage.import_safety_data(&earlier_epoch),
+            Err(Error::IncorrectEpoch(1, 2))
+        );
+
+        // An unknown version of the format
+        let mut other_version =
+            SafetyDataInterchange::new(signer.author(), SafetyData::new(2, 10, 8, 9, None));
+        other_version.version = SAFETY_DATA_INTERCHANGE_VERSION + 1;
+        assert_eq!(
+            safety_storage.import_safety_data(&other_version),
+            Err(Error::UnsupportedSafetyDataVersion(
+                SAFETY_DATA_INTERCHANGE_VERSION + 1,
+                SAFETY_DATA_INTERCHANGE_VERSION
+            ))
+        );
+
+        // Nothing was imported
+        assert_eq!(safety_storage.safety_data().unwrap(), safety_data);
+    }
+
+    #[test]
+    fn test_import_safety_data_into_fresh_storage() {
+        let signer = ValidatorSigner::from_int(0);
+        let mut safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(InMemoryStorage::new()),
+            signer.author(),
+            signer.private_key().clone(),
+            Waypoint::default(),
+            true,
+        );
+
+        // The data of a later epoch is imported as is
+        let vote = make_vote(&signer, 10);
+        let imported =
+            SafetyDataInterchange::new(signer.author(), SafetyData::new(5, 10, 8, 9, Some(vote)));
+        let merged = safety_storage.import_safety_data(&imported).unwrap();
+        assert_eq!(merged, imported.safety_data);
+        assert_eq!(safety_storage.safety_data().unwrap(), merged);
+
+        // Pristine data of an earlier epoch doesn't replace the imported data
+        let pristine =
+            SafetyDataInterchange::new(signer.author(), SafetyData::new(1, 0, 0, 0, None));
+        assert_eq!(
+            safety_storage.import_safety_data(&pristine),
+            Err(Error::IncorrectEpoch(1, 5))
+        );
+        assert_eq!(safety_storage.safety_data().unwrap(), merged);
+
+        // Another author is still rejected
+        let other_author =
+            SafetyDataInterchange::new(Author::random(), SafetyData::new(6, 10, 8, 9, None));
+        assert!(matches!(
+            safety_storage.import_safety_data(&other_author),
+            Err(Error::IncorrectAuthor(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_import_safety_data_of_earlier_epoch_after_epoch_change() {
+        let signer = ValidatorSigner::from_int(0);
+        let mut safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(InMemoryStorage::new()),
+            signer.author(),
+            signer.private_key().clone(),
+            Waypoint::default(),
+            true,
+        );
+
+        // Safety rules reset the watermarks when a new epoch starts, so the data is pristine
+        let new_epoch = SafetyData::new(6, 0, 0, 0, None);
+        safety_storage.set_safety_data(new_epoch.clone()).unwrap();
+
+        // Importing the data exported during the previous epoch must not roll the epoch back
+        let vote = make_vote(&signer, 10);
+        let previous_epoch =
+            SafetyDataInterchange::new(signer.author(), SafetyData::new(5, 10, 8, 9, Some(vote)));
+        assert_eq!(
+            safety_storage.import_safety_data(&previous_epoch),
+            Err(Error::IncorrectEpoch(5, 6))
+        );
+        assert_eq!(safety_storage.safety_data().unwrap(), new_epoch);
+    }
 }
@@ -0,0 +1,83 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Error;
+use consensus_types::{common::Author, safety_data::SafetyData, vote::Vote};
+use serde::{Deserialize, Serialize};
+
+/// The version of the interchange format written by this release
+pub const SAFETY_DATA_INTERCHANGE_VERSION: u64 = 1;
+
+/// A portable copy of the safety data of a validator, used to carry the slashing protection
+/// data over when moving a validator between hosts or secure storage backends.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SafetyDataInterchange {
+    pub version: u64,
+    pub author: Author,
+    pub safety_data: SafetyData,
+}
+
+impl SafetyDataInterchange {
+    pub fn new(author: Author, safety_data: SafetyData) -> Self {
+        Self {
+            version: SAFETY_DATA_INTERCHANGE_VERSION,
+            author,
+            safety_data,
+        }
+    }
+
+    /// Merges the other safety data of the same author into this one. Safety data of the same
+    /// epoch keeps the highest of each watermark, so that the merged data never allows voting
+    /// twice in a round. Safety data of a later epoch replaces this one, as the watermarks of
+    /// an epoch don't apply to the next ones. Safety data of an earlier epoch is rejected, as
+    /// it would roll the epoch back, even if this one was never used to vote: safety rules
+    /// reset the watermarks at the start of every epoch.
+    pub fn merge(&self, other: &SafetyDataInterchange) -> Result<SafetyDataInterchange, Error> {
+        for interchange in [self, other] {
+            if interchange.version != SAFETY_DATA_INTERCHANGE_VERSION {
+                return Err(Error::UnsupportedSafetyDataVersion(
+                    interchange.version,
+                    SAFETY_DATA_INTERCHANGE_VERSION,
+                ));
+            }
+        }
+        if other.author != self.author {
+            return Err(Error::IncorrectAuthor(
+                other.author.to_string(),
+                self.author.to_string(),
+            ));
+        }
+        if other.safety_data.epoch > self.safety_data.epoch {
+            return Ok(other.clone());
+        } else if other.safety_data.epoch < self.safety_data.epoch {
+            return Err(Error::IncorrectEpoch(
+                other.safety_data.epoch,
+                self.safety_data.epoch,
+            ));
+        }
+
+        let (ours, theirs) = (&self.safety_data, &other.safety_data);
+        let safety_data = SafetyData::new(
+            ours.epoch,
+            ours.last_voted_round.max(theirs.last_voted_round),
+            ours.preferred_round.max(theirs.preferred_round),
+            ours.one_chain_round.max(theirs.one_chain_round),
+            latest_vote(&ours.last_vote, &theirs.last_vote),
+        );
+        Ok(SafetyDataInterchange::new(self.author, safety_data))
+    }
+}
+
+/// Returns the vote of the highest round, preferring ours on a tie
+fn latest_vote(ours: &Option<Vote>, theirs: &Option<Vote>) -> Option<Vote> {
+    match (ours, theirs) {
+        (Some(our_vote), Some(their_vote))
+            if their_vote.vote_data().proposed().round()
+                > our_vote.vote_data().proposed().round() =>
+        {
+            Some(their_vote.clone())
+        }
+        (Some(our_vote), _) => Some(our_vote.clone()),
+        (None, their_vote) => their_vote.clone(),
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The consensus observer lets validator full nodes follow the consensus of their validator.
+//!
+//! A validator running the decoupled execution pipeline publishes every batch of ordered blocks
+//! (together with the ordering proof) and every commit decision to the VFNs that subscribed.
+//! The VFN executes the ordered blocks itself, and commits them once the commit ledger info,
+//! verified against the current `EpochState`, matches its own execution result. Whenever the
+//! observer falls behind, it hands over to state sync.
+
+mod network;
+mod observer;
+mod publisher;
+#[cfg(test)]
+mod tests;
+
+pub use network::{
+    network_endpoint_config, ConsensusObserverMessage, ConsensusObserverNetworkEvents,
+    ConsensusObserverNetworkSender, OrderedBlock,
+};
+pub use observer::ConsensusObserver;
+pub use publisher::ConsensusPublisher;
@@ -0,0 +1,140 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters;
+use anyhow::{ensure, Context};
+use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_types::{block::Block, experimental::commit_decision::CommitDecision};
+use network::{
+    protocols::network::{AppConfig, NetworkEvents, NetworkSender},
+    ProtocolId,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Network type for consensus observer messages
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ConsensusObserverMessage {
+    /// Sent by an observer to start receiving updates from the publisher.
+    Subscribe,
+    /// Sent by an observer to stop receiving updates from the publisher.
+    Unsubscribe,
+    /// A chain of blocks ordered by consensus, but not yet executed.
+    OrderedBlock(OrderedBlock),
+    /// The commit ledger info of a previously ordered block.
+    CommitDecision(CommitDecision),
+}
+
+impl ConsensusObserverMessage {
+    /// Returns the name of the message type (used for metrics and logging)
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConsensusObserverMessage::Subscribe => "Subscribe",
+            ConsensusObserverMessage::Unsubscribe => "Unsubscribe",
+            ConsensusObserverMessage::OrderedBlock(_) => "OrderedBlock",
+            ConsensusObserverMessage::CommitDecision(_) => "CommitDecision",
+        }
+    }
+}
+
+/// A chain of ordered blocks and the ordering proof of its last block.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct OrderedBlock {
+    blocks: Vec<Block>,
+    ordered_proof: LedgerInfoWithSignatures,
+}
+
+impl Display for OrderedBlock {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "OrderedBlock: [{} blocks, {}]",
+            self.blocks.len(),
+            self.ordered_proof
+        )
+    }
+}
+
+impl OrderedBlock {
+    /// Creates a new ordered block message
+    pub fn new(blocks: Vec<Block>, ordered_proof: LedgerInfoWithSignatures) -> Self {
+        Self {
+            blocks,
+            ordered_proof,
+        }
+    }
+
+    /// Returns the ordered blocks, from the oldest to the newest
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Returns the ordering proof of the last block
+    pub fn ordered_proof(&self) -> &LedgerInfoWithSignatures {
+        &self.ordered_proof
+    }
+
+    /// Consumes the message and returns the ordered blocks
+    pub fn into_blocks(self) -> Vec<Block> {
+        self.blocks
+    }
+
+    /// Verifies that the ordering proof is signed by a quorum of the given epoch, and that
+    /// it certifies the whole chain of blocks. As the id of each block is the hash of its
+    /// data, this authenticates every block of the chain.
+    pub fn verify(&self, epoch_state: &EpochState) -> anyhow::Result<()> {
+        let commit_info = self.ordered_proof.commit_info();
+        ensure!(
+            commit_info.epoch() == epoch_state.epoch,
+            "Ordered block is in epoch {}, but the current epoch is {}",
+            commit_info.epoch(),
+            epoch_state.epoch
+        );
+        self.ordered_proof
+            .verify_signatures(&epoch_state.verifier)
+            .context("Failed to verify the ordering proof")?;
+
+        let last_block = self.blocks.last().context("Ordered block has no blocks")?;
+        ensure!(
+            last_block.id() == commit_info.id(),
+            "The last block {} is not the one ordered by the proof {}",
+            last_block.id(),
+            commit_info.id()
+        );
+        for (parent, child) in self.blocks.iter().zip(self.blocks.iter().skip(1)) {
+            ensure!(
+                child.parent_id() == parent.id(),
+                "Block {} does not extend block {}",
+                child.id(),
+                parent.id()
+            );
+        }
+        for block in &self.blocks {
+            ensure!(
+                block.epoch() == epoch_state.epoch,
+                "Block {} is not in the current epoch {}",
+                block.id(),
+                epoch_state.epoch
+            );
+            block.verify_well_formed()?;
+        }
+        Ok(())
+    }
+}
+
+/// The interface from the consensus observer to the network layer.
+pub type ConsensusObserverNetworkSender = NetworkSender<ConsensusObserverMessage>;
+
+/// The interface from the network layer to the consensus observer.
+pub type ConsensusObserverNetworkEvents = NetworkEvents<ConsensusObserverMessage>;
+
+/// Configuration for the network endpoints of the consensus observer and publisher.
+pub fn network_endpoint_config(max_network_channel_size: u64) -> AppConfig {
+    AppConfig::p2p(
+        [ProtocolId::ConsensusObserver],
+        aptos_channel::Config::new(max_network_channel_size as usize)
+            .queue_style(QueueStyle::FIFO)
+            .counters(&counters::PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS),
+    )
+}
@@ -0,0 +1,400 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::network::{
+        ConsensusObserverMessage, ConsensusObserverNetworkEvents, ConsensusObserverNetworkSender,
+        OrderedBlock,
+    },
+    counters,
+    payload_manager::PayloadManager,
+    state_replication::StateComputer,
+};
+use anyhow::{bail, ensure, Context};
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, PeerId};
+use consensus_types::{
+    block::Block, common::Payload, executed_block::ExecutedBlock,
+    experimental::commit_decision::CommitDecision,
+};
+use futures::StreamExt;
+use network::{protocols::network::Event, transport::ConnectionMetadata, ProtocolId};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage_interface::DbReader;
+use tokio::time::{interval, MissedTickBehavior};
+
+#[cfg(test)]
+#[path = "observer_test.rs"]
+mod observer_test;
+
+/// Follows the consensus of the validator this node is connected to: executes the ordered
+/// blocks it publishes, and commits them once the commit decision matches the local
+/// execution result.
+///
+/// The observer only takes over execution from state sync with a sync request to a verified
+/// commit decision, and stops executing once it hasn't made progress for half of the state
+/// sync fallback duration, so that the two never write to storage at the same time.
+///
+/// Note: observers can't fetch quorum store batches, so validators using quorum store don't
+/// publish, and blocks with quorum store payloads are rejected rather than executed.
+pub struct ConsensusObserver {
+    config: ConsensusObserverConfig,
+    network_sender: ConsensusObserverNetworkSender,
+    // The block executor, committing to storage and notifying state sync
+    execution_proxy: Arc<dyn StateComputer>,
+    db_reader: Arc<dyn DbReader>,
+    // The validator we're subscribed to
+    publisher: Option<PeerId>,
+    epoch_state: EpochState,
+    // The latest committed ledger info
+    root: LedgerInfoWithSignatures,
+    // The verified chain of ordered blocks extending the root
+    pending_blocks: Vec<Block>,
+    // The executed prefix of the pending blocks
+    executed_blocks: Vec<Arc<ExecutedBlock>>,
+    // The last time a block was committed (or synced to) by the observer, if it is in charge
+    // of execution
+    last_progress_time: Option<Instant>,
+}
+
+impl ConsensusObserver {
+    /// Creates a new observer starting from the latest ledger info in storage
+    pub fn new(
+        config: ConsensusObserverConfig,
+        network_sender: ConsensusObserverNetworkSender,
+        execution_proxy: Arc<dyn StateComputer>,
+        db_reader: Arc<dyn DbReader>,
+    ) -> anyhow::Result<Self> {
+        let root = db_reader.get_latest_ledger_info()?;
+        let epoch_state = db_reader.get_latest_epoch_state()?;
+        execution_proxy.new_epoch(&epoch_state, Arc::new(PayloadManager::DirectMempool));
+        Ok(Self {
+            config,
+            network_sender,
+            execution_proxy,
+            db_reader,
+            publisher: None,
+            epoch_state,
+            root,
+            pending_blocks: vec![],
+            executed_blocks: vec![],
+            last_progress_time: None,
+        })
+    }
+
+    /// Processes the network events until the network shuts down
+    pub async fn start(mut self, mut network_events: ConsensusObserverNetworkEvents) {
+        let mut progress_check_interval = interval(self.max_time_without_progress());
+        progress_check_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        info!("Consensus observer starts");
+        loop {
+            tokio::select! {
+                _ = progress_check_interval.tick() => {
+                    self.check_progress();
+                },
+                event = network_events.next() => match event {
+                    Some(event) => self.process_network_event(event).await,
+                    None => break,
+                },
+            }
+        }
+        info!("Consensus observer stops");
+    }
+
+    /// The observer hands execution back to state sync well before state sync takes over
+    /// on its own.
+    fn max_time_without_progress(&self) -> Duration {
+        Duration::from_millis(self.config.observer_fallback_duration_ms / 2)
+    }
+
+    fn is_executing(&self) -> bool {
+        self.last_progress_time.is_some()
+    }
+
+    fn check_progress(&mut self) {
+        if let Some(last_progress_time) = self.last_progress_time {
+            if last_progress_time.elapsed() > self.max_time_without_progress() {
+                warn!(
+                    round = self.root.commit_info().round(),
+                    "Consensus observer isn't making progress, falling back to state sync"
+                );
+                counters::CONSENSUS_OBSERVER_STATE_SYNC_FALLBACKS.inc();
+                self.last_progress_time = None;
+                self.executed_blocks.clear();
+            }
+        }
+    }
+
+    async fn process_network_event(&mut self, event: Event<ConsensusObserverMessage>) {
+        match event {
+            Event::NewPeer(metadata) => self.subscribe(metadata),
+            Event::LostPeer(metadata) => {
+                if self.publisher == Some(metadata.remote_peer_id) {
+                    info!(
+                        peer_id = metadata.remote_peer_id,
+                        "Lost the consensus publisher"
+                    );
+                    self.publisher = None;
+                }
+            }
+            Event::Message(peer_id, message) => {
+                if self.publisher != Some(peer_id) {
+                    return;
+                }
+                counters::CONSENSUS_OBSERVER_MESSAGES
+                    .with_label_values(&[message.name()])
+                    .inc();
+                let result = match message {
+                    ConsensusObserverMessage::OrderedBlock(ordered_block) => {
+                        self.process_ordered_block(ordered_block).await
+                    }
+                    ConsensusObserverMessage::CommitDecision(commit_decision) => {
+                        self.process_commit_decision(commit_decision).await
+                    }
+                    message => Err(anyhow::anyhow!(
+                        "Unexpected consensus observer message: {}",
+                        message.name()
+                    )),
+                };
+                if let Err(error) = result {
+                    warn!(
+                        peer_id = peer_id,
+                        error = ?error,
+                        "Failed to process the consensus observer message"
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn subscribe(&mut self, metadata: ConnectionMetadata) {
+        if self.publisher.is_some()
+            || !metadata
+                .application_protocols
+                .contains(ProtocolId::ConsensusObserver)
+        {
+            return;
+        }
+        let peer_id = metadata.remote_peer_id;
+        match self.network_sender.send_to(
+            peer_id,
+            ProtocolId::ConsensusObserver,
+            ConsensusObserverMessage::Subscribe,
+        ) {
+            Ok(()) => {
+                info!(peer_id = peer_id, "Subscribed to the consensus publisher");
+                self.publisher = Some(peer_id);
+            }
+            Err(error) => {
+                warn!(
+                    peer_id = peer_id,
+                    error = ?error,
+                    "Failed to subscribe to the consensus publisher"
+                );
+            }
+        }
+    }
+
+    async fn process_ordered_block(&mut self, ordered_block: OrderedBlock) -> anyhow::Result<()> {
+        ordered_block.verify(&self.epoch_state)?;
+        if let Some(block) = ordered_block
+            .blocks()
+            .iter()
+            .find(|block| matches!(block.payload(), Some(Payload::InQuorumStore(_))))
+        {
+            bail!(
+                "Block {} has a quorum store payload, which the observer can't execute",
+                block.id()
+            );
+        }
+
+        let last_round = match self.pending_blocks.last() {
+            Some(block) => block.round(),
+            // Rounds start over in a new epoch
+            None if self.root.ledger_info().ends_epoch() => 0,
+            None => self.root.commit_info().round(),
+        };
+        let new_blocks: Vec<_> = ordered_block
+            .into_blocks()
+            .into_iter()
+            .filter(|block| block.round() > last_round)
+            .collect();
+        let first_block = match new_blocks.first() {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+        if let Some(last_block) = self.pending_blocks.last() {
+            if first_block.parent_id() != last_block.id() {
+                // The chain forked, which may only happen after we missed some messages. Start
+                // over from the new chain, the next commit decision will sync to it.
+                self.pending_blocks.clear();
+                self.executed_blocks.clear();
+            }
+        }
+        self.pending_blocks.extend(new_blocks);
+
+        self.execute_pending_blocks().await
+    }
+
+    async fn process_commit_decision(
+        &mut self,
+        commit_decision: CommitDecision,
+    ) -> anyhow::Result<()> {
+        if !self.is_executing() {
+            // State sync may have moved storage ahead in the meantime
+            self.reload_from_storage()?;
+        }
+        let commit_ledger_info = commit_decision.ledger_info();
+        let commit_info = commit_ledger_info.commit_info();
+        let root_info = self.root.commit_info();
+        if (commit_info.epoch(), commit_info.round()) <= (root_info.epoch(), root_info.round()) {
+            return Ok(());
+        }
+        ensure!(
+            commit_decision.epoch() == self.epoch_state.epoch,
+            "Commit decision is in epoch {}, but the current epoch is {}",
+            commit_decision.epoch(),
+            self.epoch_state.epoch
+        );
+        commit_decision.verify(&self.epoch_state.verifier)?;
+
+        if self.is_executing() {
+            if let Some(index) = self
+                .executed_blocks
+                .iter()
+                .position(|block| block.id() == commit_info.id())
+            {
+                let executed_info = self.executed_blocks[index].block_info();
+                if executed_info == *commit_info {
+                    return self.commit(index, commit_ledger_info.clone()).await;
+                }
+                error!(
+                    executed = %executed_info,
+                    committed = %commit_info,
+                    "Consensus observer execution result doesn't match the commit decision"
+                );
+            }
+        }
+        self.sync_to(commit_ledger_info.clone()).await
+    }
+
+    /// Commits the executed blocks up to (and including) the given index
+    async fn commit(
+        &mut self,
+        index: usize,
+        commit_ledger_info: LedgerInfoWithSignatures,
+    ) -> anyhow::Result<()> {
+        let blocks: Vec<_> = self.executed_blocks.drain(..=index).collect();
+        self.pending_blocks.drain(..=index);
+        self.execution_proxy
+            .commit(&blocks, commit_ledger_info.clone(), Box::new(|_, _| {}))
+            .await?;
+        self.update_root(commit_ledger_info);
+        self.execute_pending_blocks().await
+    }
+
+    /// Takes over execution by syncing storage to the given commit ledger info
+    async fn sync_to(
+        &mut self,
+        commit_ledger_info: LedgerInfoWithSignatures,
+    ) -> anyhow::Result<()> {
+        let latest_version = self.db_reader.get_latest_version()?;
+        if commit_ledger_info.ledger_info().version() < latest_version {
+            // Wait for a commit decision ahead of storage
+            return Ok(());
+        }
+        info!(
+            target = %commit_ledger_info,
+            "Consensus observer syncs to the commit decision"
+        );
+        self.executed_blocks.clear();
+        self.last_progress_time = None;
+        self.execution_proxy
+            .sync_to(commit_ledger_info.clone())
+            .await
+            .context("Failed to sync to the commit decision")?;
+        let round = commit_ledger_info.commit_info().round();
+        self.pending_blocks.retain(|block| block.round() > round);
+        self.update_root(commit_ledger_info);
+        self.execute_pending_blocks().await
+    }
+
+    fn update_root(&mut self, root: LedgerInfoWithSignatures) {
+        if let Some(next_epoch_state) = root.ledger_info().next_epoch_state() {
+            info!(
+                epoch = next_epoch_state.epoch,
+                "Consensus observer enters a new epoch"
+            );
+            self.epoch_state = next_epoch_state.clone();
+            self.execution_proxy
+                .new_epoch(&self.epoch_state, Arc::new(PayloadManager::DirectMempool));
+            self.pending_blocks.clear();
+            self.executed_blocks.clear();
+        }
+        self.root = root;
+        self.last_progress_time = Some(Instant::now());
+    }
+
+    fn reload_from_storage(&mut self) -> anyhow::Result<()> {
+        let root = self.db_reader.get_latest_ledger_info()?;
+        if root.ledger_info().version() == self.root.ledger_info().version() {
+            return Ok(());
+        }
+        let epoch_state = self.db_reader.get_latest_epoch_state()?;
+        if epoch_state.epoch != self.epoch_state.epoch {
+            self.pending_blocks.clear();
+            self.execution_proxy
+                .new_epoch(&epoch_state, Arc::new(PayloadManager::DirectMempool));
+            self.epoch_state = epoch_state;
+        }
+        let round = root.commit_info().round();
+        self.pending_blocks.retain(|block| block.round() > round);
+        self.executed_blocks.clear();
+        self.root = root;
+        Ok(())
+    }
+
+    /// The id of the block the next ordered block extends
+    fn root_block_id(&self) -> HashValue {
+        let ledger_info = self.root.ledger_info();
+        if ledger_info.ends_epoch() {
+            Block::make_genesis_block_from_ledger_info(ledger_info).id()
+        } else {
+            ledger_info.consensus_block_id()
+        }
+    }
+
+    async fn execute_pending_blocks(&mut self) -> anyhow::Result<()> {
+        if !self.is_executing() {
+            return Ok(());
+        }
+        let mut parent_id = self
+            .executed_blocks
+            .last()
+            .map_or_else(|| self.root_block_id(), |block| block.id());
+        for block in self.pending_blocks.iter().skip(self.executed_blocks.len()) {
+            if block.parent_id() != parent_id {
+                bail!(
+                    "Block {} doesn't extend the executed block {}",
+                    block.id(),
+                    parent_id
+                );
+            }
+            let result = self
+                .execution_proxy
+                .compute(block, parent_id)
+                .await
+                .with_context(|| format!("Failed to execute block {}", block.id()))?;
+            parent_id = block.id();
+            self.executed_blocks
+                .push(Arc::new(ExecutedBlock::new(block.clone(), result)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,434 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        observer::ConsensusObserver,
+        tests::{block_info, make_chain, make_ordered_proof},
+        OrderedBlock,
+    },
+    error::StateSyncError,
+    payload_manager::PayloadManager,
+    state_replication::{StateComputer, StateComputerCommitCallBackType},
+};
+use anyhow::Result;
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_types::{
+    block::{block_test_utils::gen_test_certificate, Block},
+    common::Payload,
+    executed_block::ExecutedBlock,
+    experimental::commit_decision::CommitDecision,
+};
+use executor_types::{Error as ExecutionError, StateComputeResult};
+use network::{
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::NewNetworkSender,
+};
+use std::{sync::Arc, time::Duration};
+use storage_interface::DbReader;
+
+/// The storage of the observer, updated by the mock execution proxy
+struct MockStorage {
+    ledger_info: Mutex<LedgerInfoWithSignatures>,
+    epoch_state: Mutex<EpochState>,
+}
+
+impl MockStorage {
+    fn update(&self, ledger_info: &LedgerInfoWithSignatures) {
+        if let Some(epoch_state) = ledger_info.ledger_info().next_epoch_state() {
+            *self.epoch_state.lock() = epoch_state.clone();
+        }
+        *self.ledger_info.lock() = ledger_info.clone();
+    }
+}
+
+impl DbReader for MockStorage {
+    fn get_latest_epoch_state(&self) -> Result<EpochState> {
+        Ok(self.epoch_state.lock().clone())
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        Ok(Some(self.ledger_info.lock().clone()))
+    }
+}
+
+/// Records the calls of the observer, and executes every block to the state id returned by
+/// `executed_state_id`.
+struct MockExecutionProxy {
+    storage: Arc<MockStorage>,
+    computed_blocks: Mutex<Vec<HashValue>>,
+    committed_blocks: Mutex<Vec<HashValue>>,
+    sync_targets: Mutex<Vec<LedgerInfoWithSignatures>>,
+    new_epochs: Mutex<Vec<u64>>,
+}
+
+impl MockExecutionProxy {
+    fn new(storage: Arc<MockStorage>) -> Self {
+        Self {
+            storage,
+            computed_blocks: Mutex::new(vec![]),
+            committed_blocks: Mutex::new(vec![]),
+            sync_targets: Mutex::new(vec![]),
+            new_epochs: Mutex::new(vec![]),
+        }
+    }
+
+    fn computed_blocks(&self) -> Vec<HashValue> {
+        self.computed_blocks.lock().clone()
+    }
+
+    fn committed_blocks(&self) -> Vec<HashValue> {
+        self.committed_blocks.lock().clone()
+    }
+
+    fn sync_targets(&self) -> Vec<LedgerInfoWithSignatures> {
+        self.sync_targets.lock().clone()
+    }
+
+    fn new_epochs(&self) -> Vec<u64> {
+        self.new_epochs.lock().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl StateComputer for MockExecutionProxy {
+    async fn compute(
+        &self,
+        block: &Block,
+        _parent_block_id: HashValue,
+    ) -> Result<StateComputeResult, ExecutionError> {
+        self.computed_blocks.lock().push(block.id());
+        Ok(StateComputeResult::new_dummy_with_root_hash(
+            executed_state_id(block),
+        ))
+    }
+
+    async fn commit(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        finality_proof: LedgerInfoWithSignatures,
+        _callback: StateComputerCommitCallBackType,
+    ) -> Result<(), ExecutionError> {
+        self.committed_blocks
+            .lock()
+            .extend(blocks.iter().map(|block| block.id()));
+        self.storage.update(&finality_proof);
+        Ok(())
+    }
+
+    async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.storage.update(&target);
+        self.sync_targets.lock().push(target);
+        Ok(())
+    }
+
+    fn new_epoch(&self, epoch_state: &EpochState, _payload_manager: Arc<PayloadManager>) {
+        self.new_epochs.lock().push(epoch_state.epoch);
+    }
+}
+
+/// The state id the mock execution proxy executes the given block to
+fn executed_state_id(block: &Block) -> HashValue {
+    block.id()
+}
+
+/// The commit decision matching the mock execution of the given block
+fn make_commit_decision(
+    signers: &[ValidatorSigner],
+    block: &Block,
+    executed_state_id: HashValue,
+    next_epoch_state: Option<EpochState>,
+) -> CommitDecision {
+    CommitDecision::new(generate_ledger_info_with_sig(
+        signers,
+        LedgerInfo::new(
+            block.gen_block_info(executed_state_id, 0, next_epoch_state),
+            HashValue::zero(),
+        ),
+    ))
+}
+
+/// Creates an observer starting from genesis, with a validator set of 4 in epoch 1
+fn create_observer(
+    config: ConsensusObserverConfig,
+) -> (
+    ConsensusObserver,
+    Arc<MockExecutionProxy>,
+    Vec<ValidatorSigner>,
+) {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let storage = Arc::new(MockStorage {
+        ledger_info: Mutex::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::mock_genesis(None),
+            AggregateSignature::empty(),
+        )),
+        epoch_state: Mutex::new(EpochState { epoch: 1, verifier }),
+    });
+    let execution_proxy = Arc::new(MockExecutionProxy::new(storage.clone()));
+
+    let (network_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    let network_sender = NewNetworkSender::new(
+        PeerManagerRequestSender::new(network_reqs_tx),
+        ConnectionRequestSender::new(connection_reqs_tx),
+    );
+
+    let observer =
+        ConsensusObserver::new(config, network_sender, execution_proxy.clone(), storage).unwrap();
+    (observer, execution_proxy, signers)
+}
+
+/// Makes the observer take over execution by syncing to the first of the given blocks
+async fn take_over_execution(
+    observer: &mut ConsensusObserver,
+    signers: &[ValidatorSigner],
+    blocks: &[Block],
+) {
+    let ordered_proof = make_ordered_proof(signers, blocks.last().unwrap());
+    observer
+        .process_ordered_block(OrderedBlock::new(blocks.to_vec(), ordered_proof))
+        .await
+        .unwrap();
+    observer
+        .process_commit_decision(make_commit_decision(
+            signers,
+            &blocks[0],
+            executed_state_id(&blocks[0]),
+            None,
+        ))
+        .await
+        .unwrap();
+}
+
+fn block_ids(blocks: &[Block]) -> Vec<HashValue> {
+    blocks.iter().map(|block| block.id()).collect()
+}
+
+#[tokio::test]
+async fn test_sync_to_takeover() {
+    let (mut observer, execution_proxy, signers) =
+        create_observer(ConsensusObserverConfig::default());
+    let blocks = make_chain(&signers, 3);
+
+    // The observer doesn't execute the ordered blocks until it has taken over from state sync
+    let ordered_proof = make_ordered_proof(&signers, blocks.last().unwrap());
+    observer
+        .process_ordered_block(OrderedBlock::new(blocks.clone(), ordered_proof))
+        .await
+        .unwrap();
+    assert!(!observer.is_executing());
+    assert!(execution_proxy.computed_blocks().is_empty());
+    assert_eq!(observer.pending_blocks.len(), 3);
+
+    // The first commit decision makes it sync to the committed block, and execute the others
+    let commit_decision =
+        make_commit_decision(&signers, &blocks[0], executed_state_id(&blocks[0]), None);
+    observer
+        .process_commit_decision(commit_decision.clone())
+        .await
+        .unwrap();
+    assert!(observer.is_executing());
+    assert_eq!(
+        execution_proxy.sync_targets(),
+        vec![commit_decision.ledger_info().clone()]
+    );
+    assert_eq!(observer.root, *commit_decision.ledger_info());
+    assert_eq!(execution_proxy.computed_blocks(), block_ids(&blocks[1..]));
+    assert_eq!(observer.executed_blocks.len(), 2);
+
+    // A commit decision for an older block is ignored
+    observer
+        .process_commit_decision(commit_decision)
+        .await
+        .unwrap();
+    assert_eq!(execution_proxy.sync_targets().len(), 1);
+}
+
+#[tokio::test]
+async fn test_commit_matching_execution() {
+    let (mut observer, execution_proxy, signers) =
+        create_observer(ConsensusObserverConfig::default());
+    let blocks = make_chain(&signers, 4);
+    take_over_execution(&mut observer, &signers, &blocks).await;
+
+    // A commit decision matching the execution result commits up to the committed block
+    let commit_decision =
+        make_commit_decision(&signers, &blocks[2], executed_state_id(&blocks[2]), None);
+    observer
+        .process_commit_decision(commit_decision.clone())
+        .await
+        .unwrap();
+    assert_eq!(execution_proxy.committed_blocks(), block_ids(&blocks[1..3]));
+    assert_eq!(execution_proxy.sync_targets().len(), 1);
+    assert_eq!(observer.root, *commit_decision.ledger_info());
+    assert_eq!(observer.pending_blocks, blocks[3..].to_vec());
+    assert_eq!(observer.executed_blocks.len(), 1);
+    assert!(observer.is_executing());
+}
+
+#[tokio::test]
+async fn test_commit_mismatching_execution() {
+    let (mut observer, execution_proxy, signers) =
+        create_observer(ConsensusObserverConfig::default());
+    let blocks = make_chain(&signers, 4);
+    take_over_execution(&mut observer, &signers, &blocks).await;
+
+    // A commit decision that doesn't match the execution result syncs to it instead
+    let commit_decision = make_commit_decision(&signers, &blocks[2], HashValue::random(), None);
+    observer
+        .process_commit_decision(commit_decision.clone())
+        .await
+        .unwrap();
+    assert!(execution_proxy.committed_blocks().is_empty());
+    assert_eq!(
+        execution_proxy.sync_targets().last(),
+        Some(commit_decision.ledger_info())
+    );
+    assert_eq!(observer.root, *commit_decision.ledger_info());
+
+    // The remaining block is executed again on top of the synced state
+    assert_eq!(observer.pending_blocks, blocks[3..].to_vec());
+    assert_eq!(observer.executed_blocks.len(), 1);
+    assert_eq!(
+        execution_proxy.computed_blocks().last(),
+        Some(&blocks[3].id())
+    );
+}
+
+#[tokio::test]
+async fn test_fork_reset() {
+    let (mut observer, execution_proxy, signers) =
+        create_observer(ConsensusObserverConfig::default());
+    let blocks = make_chain(&signers, 3);
+    take_over_execution(&mut observer, &signers, &blocks).await;
+
+    // A block extending the second block, rather than the last one, forks the chain
+    let fork_block = Block::new_proposal(
+        Payload::empty(),
+        4,
+        4,
+        gen_test_certificate(
+            &signers,
+            block_info(&blocks[1]),
+            block_info(&blocks[0]),
+            None,
+        ),
+        &signers[0],
+        vec![],
+    )
+    .unwrap();
+    let ordered_proof = make_ordered_proof(&signers, &fork_block);
+    let result = observer
+        .process_ordered_block(OrderedBlock::new(vec![fork_block.clone()], ordered_proof))
+        .await;
+
+    // The observer starts over from the new chain, which it can't execute yet
+    assert!(result.is_err());
+    assert_eq!(observer.pending_blocks, vec![fork_block.clone()]);
+    assert!(observer.executed_blocks.is_empty());
+
+    // The next commit decision syncs to the new chain
+    let commit_decision =
+        make_commit_decision(&signers, &fork_block, executed_state_id(&fork_block), None);
+    observer
+        .process_commit_decision(commit_decision.clone())
+        .await
+        .unwrap();
+    assert!(execution_proxy.committed_blocks().is_empty());
+    assert_eq!(
+        execution_proxy.sync_targets().last(),
+        Some(commit_decision.ledger_info())
+    );
+    assert_eq!(observer.root, *commit_decision.ledger_info());
+    assert!(observer.pending_blocks.is_empty());
+}
+
+#[tokio::test]
+async fn test_check_progress_fallback() {
+    let config = ConsensusObserverConfig {
+        observer_fallback_duration_ms: 100,
+        ..ConsensusObserverConfig::default()
+    };
+    let (mut observer, execution_proxy, signers) = create_observer(config);
+    let blocks = make_chain(&signers, 4);
+    take_over_execution(&mut observer, &signers, &blocks).await;
+
+    // The observer keeps executing while it makes progress
+    observer.check_progress();
+    assert!(observer.is_executing());
+
+    // The observer hands execution back to state sync once it stops making progress
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    observer.check_progress();
+    assert!(!observer.is_executing());
+    assert!(observer.executed_blocks.is_empty());
+
+    // The next commit decision is synced to, even though it matches the previous execution
+    let commit_decision =
+        make_commit_decision(&signers, &blocks[2], executed_state_id(&blocks[2]), None);
+    observer
+        .process_commit_decision(commit_decision.clone())
+        .await
+        .unwrap();
+    assert!(execution_proxy.committed_blocks().is_empty());
+    assert_eq!(
+        execution_proxy.sync_targets().last(),
+        Some(commit_decision.ledger_info())
+    );
+    assert!(observer.is_executing());
+}
+
+#[tokio::test]
+async fn test_update_root_new_epoch() {
+    let (mut observer, execution_proxy, signers) =
+        create_observer(ConsensusObserverConfig::default());
+    let blocks = make_chain(&signers, 3);
+    assert_eq!(execution_proxy.new_epochs(), vec![1]);
+
+    // Order the blocks, and sync to a commit decision ending the epoch
+    let ordered_proof = make_ordered_proof(&signers, blocks.last().unwrap());
+    observer
+        .process_ordered_block(OrderedBlock::new(blocks.clone(), ordered_proof))
+        .await
+        .unwrap();
+    let (_, next_verifier) = random_validator_verifier(4, None, false);
+    let next_epoch_state = EpochState {
+        epoch: 2,
+        verifier: next_verifier,
+    };
+    let commit_decision = make_commit_decision(
+        &signers,
+        &blocks[0],
+        executed_state_id(&blocks[0]),
+        Some(next_epoch_state.clone()),
+    );
+    observer
+        .process_commit_decision(commit_decision.clone())
+        .await
+        .unwrap();
+
+    // The observer enters the new epoch, and drops the blocks of the previous one
+    assert_eq!(observer.epoch_state, next_epoch_state);
+    assert_eq!(execution_proxy.new_epochs(), vec![1, 2]);
+    assert_eq!(observer.root, *commit_decision.ledger_info());
+    assert!(observer.pending_blocks.is_empty());
+    assert!(observer.executed_blocks.is_empty());
+    assert!(execution_proxy.computed_blocks().is_empty());
+
+    // The next blocks of the previous epoch are rejected
+    let ordered_proof = make_ordered_proof(&signers, blocks.last().unwrap());
+    assert!(observer
+        .process_ordered_block(OrderedBlock::new(blocks, ordered_proof))
+        .await
+        .is_err());
+}
@@ -0,0 +1,114 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::network::{
+        ConsensusObserverMessage, ConsensusObserverNetworkEvents, ConsensusObserverNetworkSender,
+        OrderedBlock,
+    },
+    counters,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, PeerId};
+use consensus_types::{
+    executed_block::ExecutedBlock, experimental::commit_decision::CommitDecision,
+};
+use futures::StreamExt;
+use network::{protocols::network::Event, ProtocolId};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+/// Publishes the ordered blocks and commit decisions of the decoupled execution pipeline
+/// to the subscribed consensus observers.
+pub struct ConsensusPublisher {
+    network_sender: ConsensusObserverNetworkSender,
+    subscribers: Mutex<HashSet<PeerId>>,
+}
+
+impl ConsensusPublisher {
+    /// Creates a new publisher without any subscribers
+    pub fn new(network_sender: ConsensusObserverNetworkSender) -> Self {
+        Self {
+            network_sender,
+            subscribers: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Returns the peers currently subscribed to the publisher
+    pub fn subscribers(&self) -> HashSet<PeerId> {
+        self.subscribers.lock().clone()
+    }
+
+    /// Publishes a chain of ordered blocks together with its ordering proof
+    pub fn publish_ordered_block(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        ordered_proof: &LedgerInfoWithSignatures,
+    ) {
+        let blocks = blocks.iter().map(|block| block.block().clone()).collect();
+        self.publish(ConsensusObserverMessage::OrderedBlock(OrderedBlock::new(
+            blocks,
+            ordered_proof.clone(),
+        )));
+    }
+
+    /// Publishes the commit ledger info of previously ordered blocks
+    pub fn publish_commit_decision(&self, commit_ledger_info: &LedgerInfoWithSignatures) {
+        self.publish(ConsensusObserverMessage::CommitDecision(
+            CommitDecision::new(commit_ledger_info.clone()),
+        ));
+    }
+
+    fn publish(&self, message: ConsensusObserverMessage) {
+        let subscribers = self.subscribers();
+        if subscribers.is_empty() {
+            return;
+        }
+        if let Err(error) = self.network_sender.send_to_many(
+            subscribers.into_iter(),
+            ProtocolId::ConsensusObserver,
+            message,
+        ) {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                warn!(error = ?error, "Failed to publish to the consensus observers")
+            );
+        }
+    }
+
+    /// Keeps track of the subscribed observers until the network shuts down
+    pub async fn start(self: Arc<Self>, mut network_events: ConsensusObserverNetworkEvents) {
+        info!("Consensus publisher starts");
+        while let Some(event) = network_events.next().await {
+            match event {
+                Event::Message(peer_id, message) => {
+                    counters::CONSENSUS_OBSERVER_MESSAGES
+                        .with_label_values(&[message.name()])
+                        .inc();
+                    match message {
+                        ConsensusObserverMessage::Subscribe => {
+                            info!(peer_id = peer_id, "Consensus observer subscribed");
+                            self.subscribers.lock().insert(peer_id);
+                        }
+                        ConsensusObserverMessage::Unsubscribe => {
+                            info!(peer_id = peer_id, "Consensus observer unsubscribed");
+                            self.subscribers.lock().remove(&peer_id);
+                        }
+                        message => {
+                            warn!(
+                                peer_id = peer_id,
+                                "Unexpected consensus observer message: {}",
+                                message.name()
+                            );
+                        }
+                    }
+                }
+                Event::LostPeer(metadata) => {
+                    self.subscribers.lock().remove(&metadata.remote_peer_id);
+                }
+                _ => {}
+            }
+        }
+        info!("Consensus publisher stops");
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::OrderedBlock;
+use aptos_crypto::HashValue;
+use aptos_types::{
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use consensus_types::{
+    block::{
+        block_test_utils::{certificate_for_genesis, gen_test_certificate},
+        Block,
+    },
+    common::Payload,
+};
+
+pub(super) fn block_info(block: &Block) -> BlockInfo {
+    block.gen_block_info(HashValue::zero(), 0, None)
+}
+
+pub(super) fn make_chain(signers: &[ValidatorSigner], num_blocks: u64) -> Vec<Block> {
+    let genesis_qc = certificate_for_genesis();
+    let mut parent_info = genesis_qc.certified_block().clone();
+    let mut quorum_cert = genesis_qc;
+    let mut blocks: Vec<Block> = vec![];
+    for round in 1..=num_blocks {
+        let block = Block::new_proposal(
+            Payload::empty(),
+            round,
+            round,
+            quorum_cert,
+            &signers[0],
+            vec![],
+        )
+        .unwrap();
+        quorum_cert = gen_test_certificate(signers, block_info(&block), parent_info, None);
+        parent_info = block_info(&block);
+        blocks.push(block);
+    }
+    blocks
+}
+
+pub(super) fn make_ordered_proof(
+    signers: &[ValidatorSigner],
+    block: &Block,
+) -> LedgerInfoWithSignatures {
+    generate_ledger_info_with_sig(
+        signers,
+        LedgerInfo::new(block_info(block), HashValue::zero()),
+    )
+}
+
+#[test]
+fn test_verify_ordered_block() {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let blocks = make_chain(&signers, 3);
+    let epoch_state = EpochState {
+        epoch: blocks[0].epoch(),
+        verifier,
+    };
+
+    let ordered_proof = make_ordered_proof(&signers, blocks.last().unwrap());
+    OrderedBlock::new(blocks.clone(), ordered_proof.clone())
+        .verify(&epoch_state)
+        .unwrap();
+
+    // A suffix of the chain is still certified by the proof
+    OrderedBlock::new(blocks[1..].to_vec(), ordered_proof.clone())
+        .verify(&epoch_state)
+        .unwrap();
+
+    // The proof doesn't certify the last block
+    let wrong_proof = make_ordered_proof(&signers, &blocks[1]);
+    assert!(OrderedBlock::new(blocks.clone(), wrong_proof)
+        .verify(&epoch_state)
+        .is_err());
+
+    // The chain is broken
+    let broken_chain = vec![blocks[0].clone(), blocks[2].clone()];
+    assert!(OrderedBlock::new(broken_chain, ordered_proof.clone())
+        .verify(&epoch_state)
+        .is_err());
+
+    // There are no blocks
+    assert!(OrderedBlock::new(vec![], ordered_proof)
+        .verify(&epoch_state)
+        .is_err());
+}
+
+#[test]
+fn test_verify_ordered_block_signatures() {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let blocks = make_chain(&signers, 2);
+    let epoch = blocks[0].epoch();
+
+    // The proof isn't signed by a quorum
+    let ordered_proof = make_ordered_proof(&signers[..2], blocks.last().unwrap());
+    let epoch_state = EpochState {
+        epoch,
+        verifier: verifier.clone(),
+    };
+    assert!(OrderedBlock::new(blocks.clone(), ordered_proof)
+        .verify(&epoch_state)
+        .is_err());
+
+    // The proof is signed by the validators of another epoch
+    let ordered_proof = make_ordered_proof(&signers, blocks.last().unwrap());
+    let (_, other_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = EpochState {
+        epoch,
+        verifier: other_verifier,
+    };
+    assert!(OrderedBlock::new(blocks.clone(), ordered_proof.clone())
+        .verify(&epoch_state)
+        .is_err());
+
+    // The proof is for another epoch
+    let epoch_state = EpochState {
+        epoch: epoch + 1,
+        verifier,
+    };
+    assert!(OrderedBlock::new(blocks, ordered_proof)
+        .verify(&epoch_state)
+        .is_err());
+}
@@ -2,7 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    commit_notifier::QuorumStoreCommitNotifier,
+    commit_notifier::{NoopCommitNotifier, QuorumStoreCommitNotifier},
+    consensus_observer::{
+        ConsensusObserver, ConsensusObserverNetworkEvents, ConsensusObserverNetworkSender,
+        ConsensusPublisher,
+    },
     counters,
     epoch_manager::EpochManager,
     network::NetworkTask,
@@ -38,6 +42,10 @@ pub fn start_consensus(
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    consensus_publisher_network: Option<(
+        ConsensusObserverNetworkSender,
+        ConsensusObserverNetworkEvents,
+    )>,
 ) -> Runtime {
     let runtime = runtime::Builder::new_multi_thread()
         .thread_name_fn(|| {
@@ -72,6 +80,12 @@ pub fn start_consensus(
     let (self_sender, self_receiver) = channel::new(1_024, &counters::PENDING_SELF_MESSAGES);
     network_sender.initialize(peer_metadata_storage);
 
+    let consensus_publisher = consensus_publisher_network.map(|(sender, events)| {
+        let consensus_publisher = Arc::new(ConsensusPublisher::new(sender));
+        runtime.spawn(consensus_publisher.clone().start(events));
+        consensus_publisher
+    });
+
     let epoch_mgr = EpochManager::new(
         node_config,
         time_service,
@@ -83,6 +97,7 @@ pub fn start_consensus(
         storage,
         reconfig_events,
         commit_notifier,
+        consensus_publisher,
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
@@ -93,3 +108,47 @@ pub fn start_consensus(
     debug!("Consensus started.");
     runtime
 }
+
+/// Helper function to start the consensus observer of a validator full node and return the runtime
+pub fn start_consensus_observer(
+    node_config: &NodeConfig,
+    network_sender: ConsensusObserverNetworkSender,
+    network_events: ConsensusObserverNetworkEvents,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
+    aptos_db: DbReaderWriter,
+) -> Runtime {
+    let runtime = runtime::Builder::new_multi_thread()
+        .thread_name_fn(|| {
+            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
+            let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
+            format!("consensus-observer-{}", id)
+        })
+        .disable_lifo_slot()
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime!");
+    let txn_notifier = Arc::new(MempoolNotifier::new(
+        consensus_to_mempool_sender,
+        node_config.consensus.mempool_executed_txn_timeout_ms,
+    ));
+    let execution_proxy = Arc::new(ExecutionProxy::new(
+        Arc::new(BlockExecutor::<AptosVM>::new(aptos_db.clone())),
+        txn_notifier,
+        state_sync_notifier,
+        Arc::new(NoopCommitNotifier),
+        runtime.handle(),
+    ));
+
+    let consensus_observer = ConsensusObserver::new(
+        node_config.consensus_observer,
+        network_sender,
+        execution_proxy,
+        aptos_db.reader,
+    )
+    .expect("Failed to create the consensus observer!");
+    runtime.spawn(consensus_observer.start(network_events));
+
+    debug!("Consensus observer started.");
+    runtime
+}
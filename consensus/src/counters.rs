@@ -479,6 +479,26 @@ pub static BLOCK_RETRIEVAL_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counters(queued,dequeued,dropped) related to quorum store channel
+pub static QUORUM_STORE_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_quorum_store_channel_msgs_count",
+        "Counters(queued,dequeued,dropped) related to quorum store channel",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Counters(queued,dequeued,dropped) related to quorum store task
+pub static QUORUM_STORE_TASK_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_quorum_store_task_msgs_count",
+        "Counters(queued,dequeued,dropped) related to quorum store task",
+        &["state"]
+    )
+    .unwrap()
+});
+
 /// Counters(queued,dequeued,dropped) related to block retrieval task
 pub static BLOCK_RETRIEVAL_TASK_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -506,3 +526,32 @@ pub static PROPOSER_ELECTION_DURATION: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Counters(queued,dequeued,dropped) related to pending network notifications to the consensus observer
+pub static PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_pending_network_events",
+        "Counters(queued,dequeued,dropped) related to pending network notifications to the consensus observer",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Count of the consensus observer messages processed, by message type
+pub static CONSENSUS_OBSERVER_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_messages_count",
+        "Count of the consensus observer messages processed, by message type",
+        &["type"]
+    )
+    .unwrap()
+});
+
+/// Count of the times the consensus observer fell back to state sync to catch up
+pub static CONSENSUS_OBSERVER_STATE_SYNC_FALLBACKS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_state_sync_fallbacks_count",
+        "Count of the times the consensus observer fell back to state sync to catch up"
+    )
+    .unwrap()
+});
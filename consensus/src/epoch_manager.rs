@@ -7,6 +7,7 @@ use crate::{
         BlockStore,
     },
     commit_notifier::CommitNotifier,
+    consensus_observer::ConsensusPublisher,
     counters,
     error::{error_kind, DbError},
     experimental::{
@@ -29,21 +30,26 @@ use crate::{
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
     monitor,
-    network::{IncomingBlockRetrievalRequest, NetworkReceivers, NetworkSender},
+    network::{IncomingBlockRetrievalRequest, NetworkReceivers, NetworkSender, QuorumStoreMsg},
     network_interface::{ConsensusMsg, ConsensusNetworkSender},
-    payload_manager::QuorumStoreClient,
+    payload_manager::{PayloadManager, QuorumStoreClient},
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
-    quorum_store::direct_mempool_quorum_store::DirectMempoolQuorumStore,
+    quorum_store::{
+        batch_store::{BatchReader, BatchStore},
+        direct_mempool_quorum_store::DirectMempoolQuorumStore,
+        in_quorum_store::InQuorumStore,
+    },
     recovery_manager::RecoveryManager,
     round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
     state_replication::StateComputer,
     util::time_service::TimeService,
 };
-use anyhow::{bail, ensure, Context};
+use anyhow::{anyhow, bail, ensure, Context};
 use aptos_config::config::{ConsensusConfig, NodeConfig};
 use aptos_infallible::{duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
+use aptos_secure_storage::Storage;
 use aptos_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
@@ -52,12 +58,14 @@ use aptos_types::{
         LeaderReputationType, OnChainConfigPayload, OnChainConsensusConfig, ProposerElectionType,
         ValidatorSet,
     },
+    validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
 use channel::{aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
     common::{Author, Round},
     epoch_retrieval::EpochRetrievalRequest,
+    proof_of_store::LogicalTime,
     request_response::ConsensusRequest,
 };
 use event_notifications::ReconfigNotificationListener;
@@ -72,7 +80,7 @@ use futures::{
 };
 use itertools::Itertools;
 use network::protocols::network::{ApplicationNetworkSender, Event};
-use safety_rules::SafetyRulesManager;
+use safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
 use std::{
     cmp::Ordering,
     collections::HashMap,
@@ -120,6 +128,9 @@ pub struct EpochManager {
     epoch_state: Option<EpochState>,
     block_retrieval_tx:
         Option<aptos_channel::Sender<AccountAddress, IncomingBlockRetrievalRequest>>,
+    quorum_store_msg_tx:
+        Option<aptos_channel::Sender<AccountAddress, (AccountAddress, QuorumStoreMsg)>>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl EpochManager {
@@ -134,6 +145,7 @@ impl EpochManager {
         storage: Arc<dyn PersistentLivenessStorage>,
         reconfig_events: ReconfigNotificationListener,
         commit_notifier: Arc<dyn CommitNotifier>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -158,6 +170,8 @@ impl EpochManager {
             round_manager_close_tx: None,
             epoch_state: None,
             block_retrieval_tx: None,
+            quorum_store_msg_tx: None,
+            consensus_publisher,
         }
     }
 
@@ -424,16 +438,104 @@ impl EpochManager {
         Ok(())
     }
 
+    /// Quorum store signs batches with the consensus key, which is kept by safety rules.
+    fn load_signer(&self, epoch_state: &EpochState) -> anyhow::Result<Arc<ValidatorSigner>> {
+        let public_key = epoch_state
+            .verifier
+            .get_public_key(&self.author)
+            .ok_or_else(|| anyhow!("Not a validator in epoch {}", epoch_state.epoch))?;
+        let sr_config = &self.config.safety_rules;
+        let test_key = sr_config
+            .test
+            .as_ref()
+            .and_then(|test_config| test_config.consensus_key.as_ref());
+        let private_key = match test_key {
+            Some(consensus_key) => consensus_key.private_key(),
+            None => {
+                let storage: Storage = (&sr_config.backend).into();
+                PersistentSafetyStorage::new(storage, false)
+                    .consensus_key_for_version(public_key.clone())?
+            }
+        };
+        ensure!(
+            private_key.public_key() == public_key,
+            "Consensus key doesn't match the validator set"
+        );
+        Ok(Arc::new(ValidatorSigner::new(self.author, private_key)))
+    }
+
+    /// Spawns the quorum store for the epoch, and returns the payload manager that resolves the
+    /// payloads it produces into transactions.
     fn spawn_quorum_store(
         &mut self,
+        epoch_state: &EpochState,
+        latest_round: Round,
+        network_sender: NetworkSender,
         consensus_to_quorum_store_receiver: Receiver<ConsensusRequest>,
-    ) {
-        let quorum_store = DirectMempoolQuorumStore::new(
-            consensus_to_quorum_store_receiver,
-            self.quorum_store_to_mempool_sender.clone(),
-            self.config.mempool_txn_pull_timeout_ms,
+    ) -> Arc<PayloadManager> {
+        if !self.config.use_quorum_store {
+            let quorum_store = DirectMempoolQuorumStore::new(
+                consensus_to_quorum_store_receiver,
+                self.quorum_store_to_mempool_sender.clone(),
+                self.config.mempool_txn_pull_timeout_ms,
+            );
+            spawn_named!("Quorum Store", quorum_store.start());
+            return Arc::new(PayloadManager::DirectMempool);
+        }
+
+        let epoch = epoch_state.epoch;
+        let batch_store = Arc::new(BatchStore::new(
+            epoch,
+            self.config.quorum_store_memory_quota_per_peer_bytes,
+        ));
+        let batch_reader = BatchReader::new(
+            self.author,
+            batch_store.clone(),
+            network_sender.clone(),
+            epoch_state
+                .verifier
+                .get_ordered_account_addresses_iter()
+                .collect(),
+            Duration::from_millis(self.config.quorum_store_batch_request_timeout_ms),
         );
-        spawn_named!("Quorum Store", quorum_store.start());
+        match self.load_signer(epoch_state) {
+            Ok(signer) => {
+                let (quorum_store_msg_tx, quorum_store_msg_rx) = aptos_channel::new(
+                    QueueStyle::FIFO,
+                    self.config.channel_size,
+                    Some(&counters::QUORUM_STORE_TASK_MSGS),
+                );
+                self.quorum_store_msg_tx = Some(quorum_store_msg_tx);
+                let quorum_store = InQuorumStore::new(
+                    LogicalTime::new(epoch, latest_round),
+                    signer,
+                    epoch_state.verifier.clone(),
+                    network_sender,
+                    consensus_to_quorum_store_receiver,
+                    quorum_store_msg_rx,
+                    self.quorum_store_to_mempool_sender.clone(),
+                    batch_store,
+                    &self.config,
+                );
+                spawn_named!("Quorum Store", quorum_store.start());
+            }
+            Err(e) => {
+                // Without the consensus key there is nothing to sign or propose, but the batches
+                // of the blocks can still be fetched for execution.
+                warn!(
+                    epoch = epoch,
+                    error = ?e,
+                    "Unable to load the consensus key, not disseminating batches"
+                );
+                let quorum_store = DirectMempoolQuorumStore::new(
+                    consensus_to_quorum_store_receiver,
+                    self.quorum_store_to_mempool_sender.clone(),
+                    self.config.mempool_txn_pull_timeout_ms,
+                );
+                spawn_named!("Quorum Store", quorum_store.start());
+            }
+        }
+        Arc::new(PayloadManager::InQuorumStore(Arc::new(batch_reader)))
     }
 
     fn spawn_block_retrieval_task(&mut self, epoch: u64, block_store: Arc<BlockStore>) {
@@ -495,6 +597,7 @@ impl EpochManager {
                 block_rx,
                 reset_rx,
                 verifier,
+                self.consensus_publisher.clone(),
             );
 
         tokio::spawn(execution_phase.start());
@@ -502,7 +605,12 @@ impl EpochManager {
         tokio::spawn(persisting_phase.start());
         tokio::spawn(buffer_manager.start());
 
-        OrderingStateComputer::new(block_tx, self.commit_state_computer.clone(), reset_tx)
+        OrderingStateComputer::new(
+            block_tx,
+            self.commit_state_computer.clone(),
+            reset_tx,
+            self.consensus_publisher.clone(),
+        )
     }
 
     async fn shutdown_current_processor(&mut self) {
@@ -535,6 +643,9 @@ impl EpochManager {
 
         // Shutdown the block retrieval task by dropping the sender
         self.block_retrieval_tx = None;
+
+        // Shutdown the quorum store by dropping the sender, consensus drops its own
+        self.quorum_store_msg_tx = None;
     }
 
     async fn start_recovery_manager(
@@ -612,8 +723,13 @@ impl EpochManager {
 
         let (consensus_to_quorum_store_sender, consensus_to_quorum_store_receiver) =
             mpsc::channel(self.config.intra_consensus_channel_buffer_size);
-        self.spawn_quorum_store(consensus_to_quorum_store_receiver);
-        let payload_manager = QuorumStoreClient::new(
+        let payload_manager = self.spawn_quorum_store(
+            &epoch_state,
+            recovery_data.root_block().round(),
+            network_sender.clone(),
+            consensus_to_quorum_store_receiver,
+        );
+        let payload_client = QuorumStoreClient::new(
             consensus_to_quorum_store_sender.clone(),
             self.config.quorum_store_poll_count,
             self.config.quorum_store_pull_timeout_ms,
@@ -621,7 +737,8 @@ impl EpochManager {
         self.commit_notifier
             .new_epoch(consensus_to_quorum_store_sender);
 
-        self.commit_state_computer.new_epoch(&epoch_state);
+        self.commit_state_computer
+            .new_epoch(&epoch_state, payload_manager);
         let state_computer = if onchain_config.decoupled_execution() {
             Arc::new(self.spawn_decoupled_execution(
                 safety_rules_container.clone(),
@@ -647,7 +764,7 @@ impl EpochManager {
         let proposal_generator = ProposalGenerator::new(
             self.author,
             block_store.clone(),
-            Arc::new(payload_manager),
+            Arc::new(payload_client),
             self.time_service.clone(),
             self.config.max_sending_block_txns,
             self.config.max_sending_block_bytes,
@@ -875,6 +992,18 @@ impl EpochManager {
         }
     }
 
+    fn process_quorum_store_message(
+        &self,
+        peer_id: Author,
+        msg: QuorumStoreMsg,
+    ) -> anyhow::Result<()> {
+        // Dropped when quorum store is disabled, or this node is not a validator in the epoch
+        if let Some(tx) = &self.quorum_store_msg_tx {
+            tx.push(peer_id, (peer_id, msg))?;
+        }
+        Ok(())
+    }
+
     fn process_local_timeout(&mut self, round: u64) {
         self.forward_to_round_manager(self.author, VerifiedEvent::LocalTimeout(round));
     }
@@ -908,6 +1037,11 @@ impl EpochManager {
                         error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
                     }
                 },
+                (peer, msg) = network_receivers.quorum_store_messages.select_next_some() => {
+                    if let Err(e) = self.process_quorum_store_message(peer, msg) {
+                        error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
+                    }
+                },
                 round = round_timeout_sender_rx.select_next_some() => {
                     self.process_local_timeout(round);
                 },
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::ConsensusPublisher,
     experimental::{
         buffer_manager::{create_channel, BufferManager, OrderedBlocks, ResetRequest},
         execution_phase::{ExecutionPhase, ExecutionRequest, ExecutionResponse},
@@ -32,6 +33,7 @@ pub fn prepare_phases_and_buffer_manager(
     block_rx: UnboundedReceiver<OrderedBlocks>,
     sync_rx: UnboundedReceiver<ResetRequest>,
     verifier: ValidatorVerifier,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 ) -> (
     PipelinePhase<ExecutionPhase>,
     PipelinePhase<SigningPhase>,
@@ -70,7 +72,7 @@ pub fn prepare_phases_and_buffer_manager(
     let (persisting_phase_request_tx, persisting_phase_request_rx) =
         create_channel::<CountedRequest<PersistingRequest>>();
 
-    let persisting_phase_processor = PersistingPhase::new(persisting_proxy);
+    let persisting_phase_processor = PersistingPhase::new(persisting_proxy, consensus_publisher);
     let persisting_phase = PipelinePhase::new(
         persisting_phase_request_rx,
         None,
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::ConsensusPublisher,
     error::StateSyncError,
     experimental::{
         buffer_manager::{OrderedBlocks, ResetAck, ResetRequest},
         errors::Error,
     },
+    payload_manager::PayloadManager,
     state_replication::{StateComputer, StateComputerCommitCallBackType},
 };
 use anyhow::Result;
@@ -31,6 +33,8 @@ pub struct OrderingStateComputer {
     executor_channel: UnboundedSender<OrderedBlocks>,
     state_computer_for_sync: Arc<dyn StateComputer>,
     reset_event_channel_tx: UnboundedSender<ResetRequest>,
+    // publishes the ordered blocks to the consensus observers (if any)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl OrderingStateComputer {
@@ -38,11 +42,13 @@ impl OrderingStateComputer {
         executor_channel: UnboundedSender<OrderedBlocks>,
         state_computer_for_sync: Arc<dyn StateComputer>,
         reset_event_channel_tx: UnboundedSender<ResetRequest>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         Self {
             executor_channel,
             state_computer_for_sync,
             reset_event_channel_tx,
+            consensus_publisher,
         }
     }
 }
@@ -73,6 +79,10 @@ impl StateComputer for OrderingStateComputer {
     ) -> Result<(), ExecutionError> {
         assert!(!blocks.is_empty());
 
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.publish_ordered_block(blocks, &finality_proof);
+        }
+
         if self
             .executor_channel
             .clone()
@@ -117,5 +127,5 @@ impl StateComputer for OrderingStateComputer {
         Ok(())
     }
 
-    fn new_epoch(&self, _: &EpochState) {}
+    fn new_epoch(&self, _: &EpochState, _: Arc<PayloadManager>) {}
 }
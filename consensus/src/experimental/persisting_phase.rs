@@ -7,6 +7,7 @@ use std::{
 };
 
 use crate::{
+    consensus_observer::ConsensusPublisher,
     experimental::pipeline_phase::StatelessPipeline,
     state_replication::{StateComputer, StateComputerCommitCallBackType},
 };
@@ -46,11 +47,18 @@ pub type PersistingResponse = Result<(), Error>;
 
 pub struct PersistingPhase {
     persisting_handle: Arc<dyn StateComputer>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl PersistingPhase {
-    pub fn new(persisting_handle: Arc<dyn StateComputer>) -> Self {
-        Self { persisting_handle }
+    pub fn new(
+        persisting_handle: Arc<dyn StateComputer>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    ) -> Self {
+        Self {
+            persisting_handle,
+            consensus_publisher,
+        }
     }
 }
 
@@ -65,6 +73,10 @@ impl StatelessPipeline for PersistingPhase {
             callback,
         } = req;
 
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.publish_commit_decision(&commit_ledger_info);
+        }
+
         self.persisting_handle
             .commit(&blocks, commit_ledger_info, callback)
             .await
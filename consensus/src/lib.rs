@@ -41,6 +41,8 @@ mod twins;
 mod txn_notifier;
 mod util;
 
+/// Lets validator full nodes follow the consensus of their validator
+pub mod consensus_observer;
 /// AptosBFT implementation
 pub mod consensus_provider;
 /// Required by the telemetry service
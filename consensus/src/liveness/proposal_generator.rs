@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::BlockReader, state_replication::PayloadManager, util::time_service::TimeService,
+    block_storage::BlockReader, state_replication::PayloadClient, util::time_service::TimeService,
 };
 use anyhow::{bail, ensure, format_err, Context};
 use consensus_types::{
@@ -40,7 +40,7 @@ pub struct ProposalGenerator {
     // proposed block.
     block_store: Arc<dyn BlockReader + Send + Sync>,
     // ProofOfStore manager is delivering the ProofOfStores.
-    payload_manager: Arc<dyn PayloadManager>,
+    payload_client: Arc<dyn PayloadClient>,
     // Transaction manager is delivering the transactions.
     // Time service to generate block timestamps
     time_service: Arc<dyn TimeService>,
@@ -58,7 +58,7 @@ impl ProposalGenerator {
     pub fn new(
         author: Author,
         block_store: Arc<dyn BlockReader + Send + Sync>,
-        payload_manager: Arc<dyn PayloadManager>,
+        payload_client: Arc<dyn PayloadClient>,
         time_service: Arc<dyn TimeService>,
         max_block_txns: u64,
         max_block_bytes: u64,
@@ -67,7 +67,7 @@ impl ProposalGenerator {
         Self {
             author,
             block_store,
-            payload_manager,
+            payload_client,
             time_service,
             max_block_txns,
             max_block_bytes,
@@ -157,7 +157,7 @@ impl ProposalGenerator {
             let timestamp = self.time_service.get_current_timestamp();
 
             let payload = self
-                .payload_manager
+                .payload_client
                 .pull_payload(
                     self.max_block_txns,
                     self.max_block_bytes,
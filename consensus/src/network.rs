@@ -20,6 +20,7 @@ use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse, MAX_BLOCKS_PER_REQUEST},
     common::Author,
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    proof_of_store::{Batch, BatchRequest, ProofOfStore, SignedDigest},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
@@ -47,6 +48,28 @@ pub struct IncomingBlockRetrievalRequest {
     pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
 }
 
+/// The batch request is used internally for implementing RPC: the callback is executed for
+/// carrying the response
+#[derive(Debug)]
+pub struct IncomingBatchRequest {
+    pub req: BatchRequest,
+    pub protocol: ProtocolId,
+    pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
+}
+
+/// Messages handled by quorum store rather than the round manager.
+#[derive(Debug)]
+pub enum QuorumStoreMsg {
+    Batch(Box<Batch>),
+    SignedDigest(Box<SignedDigest>),
+    ProofOfStore(Box<ProofOfStore>),
+    BatchRequest(IncomingBatchRequest),
+}
+
+/// Quorum store messages are all needed, so they are queued FIFO per peer instead of
+/// keeping only the latest one.
+const QUORUM_STORE_CHANNEL_SIZE_PER_PEER: usize = 100;
+
 /// Just a convenience struct to keep all the network proxy receiving queues in one place.
 /// Will be returned by the NetworkTask upon startup.
 pub struct NetworkReceivers {
@@ -57,6 +80,8 @@ pub struct NetworkReceivers {
     >,
     pub block_retrieval:
         aptos_channel::Receiver<AccountAddress, (AccountAddress, IncomingBlockRetrievalRequest)>,
+    pub quorum_store_messages:
+        aptos_channel::Receiver<AccountAddress, (AccountAddress, QuorumStoreMsg)>,
 }
 
 /// Implements the actual networking support for all consensus messaging.
@@ -128,6 +153,33 @@ impl NetworkSender {
         Ok(response)
     }
 
+    /// Tries to fetch the batch with the given digest from the given peer, which is expected to
+    /// be one of the signers of its proof of store. The caller checks the digest of the batch.
+    pub async fn request_batch(
+        &self,
+        request: BatchRequest,
+        from: Author,
+        timeout: Duration,
+    ) -> anyhow::Result<Batch> {
+        fail_point!("consensus::send::any", |_| {
+            Err(anyhow::anyhow!("Injected error in request_batch"))
+        });
+
+        ensure!(from != self.author, "Request batch from self");
+        let msg = ConsensusMsg::BatchRequestMsg(Box::new(request));
+        counters::CONSENSUS_SENT_MSGS
+            .with_label_values(&[msg.name()])
+            .inc();
+        let response_msg = monitor!(
+            "batch_request",
+            self.network_sender.send_rpc(from, msg, timeout).await
+        )?;
+        match response_msg {
+            ConsensusMsg::BatchMsg(batch) => Ok(*batch),
+            _ => Err(anyhow!("Invalid response to request")),
+        }
+    }
+
     /// Tries to send the given msg to all the participants.
     ///
     /// The future is fulfilled as soon as the message put into the mpsc channel to network
@@ -264,6 +316,24 @@ impl NetworkSender {
         self.author
     }
 
+    pub async fn broadcast_batch(&mut self, batch: Batch) {
+        fail_point!("consensus::send::broadcast_batch", |_| ());
+        let msg = ConsensusMsg::BatchMsg(Box::new(batch));
+        self.broadcast(msg).await
+    }
+
+    pub async fn send_signed_digest(&self, signed_digest: SignedDigest, recipient: Author) {
+        fail_point!("consensus::send::signed_digest", |_| ());
+        let msg = ConsensusMsg::SignedDigestMsg(Box::new(signed_digest));
+        self.send(msg, vec![recipient]).await
+    }
+
+    pub async fn broadcast_proof_of_store(&mut self, proof: ProofOfStore) {
+        fail_point!("consensus::send::broadcast_proof_of_store", |_| ());
+        let msg = ConsensusMsg::ProofOfStoreMsg(Box::new(proof));
+        self.broadcast(msg).await
+    }
+
     pub async fn broadcast_commit_proof(&mut self, ledger_info: LedgerInfoWithSignatures) {
         fail_point!("consensus::send::broadcast_commit_proof", |_| ());
         let msg = ConsensusMsg::CommitDecisionMsg(Box::new(CommitDecision::new(ledger_info)));
@@ -278,6 +348,8 @@ pub struct NetworkTask {
     >,
     block_retrieval_tx:
         aptos_channel::Sender<AccountAddress, (AccountAddress, IncomingBlockRetrievalRequest)>,
+    quorum_store_messages_tx:
+        aptos_channel::Sender<AccountAddress, (AccountAddress, QuorumStoreMsg)>,
     all_events: Box<dyn Stream<Item = Event<ConsensusMsg>> + Send + Unpin>,
 }
 
@@ -294,16 +366,23 @@ impl NetworkTask {
             1,
             Some(&counters::BLOCK_RETRIEVAL_CHANNEL_MSGS),
         );
+        let (quorum_store_messages_tx, quorum_store_messages) = aptos_channel::new(
+            QueueStyle::FIFO,
+            QUORUM_STORE_CHANNEL_SIZE_PER_PEER,
+            Some(&counters::QUORUM_STORE_CHANNEL_MSGS),
+        );
         let all_events = Box::new(select(network_events, self_receiver));
         (
             NetworkTask {
                 consensus_messages_tx,
                 block_retrieval_tx,
+                quorum_store_messages_tx,
                 all_events,
             },
             NetworkReceivers {
                 consensus_messages,
                 block_retrieval,
+                quorum_store_messages,
             },
         )
     }
@@ -321,13 +400,32 @@ impl NetworkTask {
                             BlockStage::NETWORK_RECEIVED,
                         );
                     }
+                    let quorum_store_msg = match msg {
+                        ConsensusMsg::BatchMsg(batch) => QuorumStoreMsg::Batch(batch),
+                        ConsensusMsg::SignedDigestMsg(signed_digest) => {
+                            QuorumStoreMsg::SignedDigest(signed_digest)
+                        }
+                        ConsensusMsg::ProofOfStoreMsg(proof) => QuorumStoreMsg::ProofOfStore(proof),
+                        msg => {
+                            if let Err(e) = self
+                                .consensus_messages_tx
+                                .push((peer_id, discriminant(&msg)), (peer_id, msg))
+                            {
+                                warn!(
+                                    remote_peer = peer_id,
+                                    error = ?e, "Error pushing consensus msg",
+                                );
+                            }
+                            continue;
+                        }
+                    };
                     if let Err(e) = self
-                        .consensus_messages_tx
-                        .push((peer_id, discriminant(&msg)), (peer_id, msg))
+                        .quorum_store_messages_tx
+                        .push(peer_id, (peer_id, quorum_store_msg))
                     {
                         warn!(
                             remote_peer = peer_id,
-                            error = ?e, "Error pushing consensus msg",
+                            error = ?e, "Error pushing quorum store msg",
                         );
                     }
                 }
@@ -362,6 +460,22 @@ impl NetworkTask {
                             warn!(error = ?e, "aptos channel closed");
                         }
                     }
+                    ConsensusMsg::BatchRequestMsg(request) => {
+                        counters::CONSENSUS_RECEIVED_MSGS
+                            .with_label_values(&["BatchRequestMsg"])
+                            .inc();
+                        let req_with_callback = IncomingBatchRequest {
+                            req: *request,
+                            protocol,
+                            response_sender: callback,
+                        };
+                        if let Err(e) = self.quorum_store_messages_tx.push(
+                            peer_id,
+                            (peer_id, QuorumStoreMsg::BatchRequest(req_with_callback)),
+                        ) {
+                            warn!(error = ?e, "aptos channel closed");
+                        }
+                    }
                     _ => {
                         warn!(remote_peer = peer_id, "Unexpected msg: {:?}", msg);
                         continue;
@@ -1,23 +1,70 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::QuorumStoreError, monitor, state_replication::PayloadManager};
+use crate::{
+    error::QuorumStoreError, monitor, quorum_store::batch_store::BatchReader,
+    state_replication::PayloadClient,
+};
 use anyhow::Result;
 use aptos_logger::prelude::*;
+use aptos_types::transaction::SignedTransaction;
 use consensus_types::{
+    block::Block,
     common::{Payload, PayloadFilter},
     request_response::{ConsensusRequest, ConsensusResponse},
 };
+use executor_types::Error as ExecutorError;
 use fail::fail_point;
 use futures::{
     channel::{mpsc, oneshot},
-    future::BoxFuture,
+    future::{join_all, BoxFuture},
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tokio::time::{sleep, timeout};
 
 const NO_TXN_DELAY: u64 = 30;
 
+/// Resolves the payload of a block into the transactions to execute.
+pub enum PayloadManager {
+    DirectMempool,
+    /// The batches are read from the local quorum store, or fetched from the validators that
+    /// signed their proofs of store.
+    InQuorumStore(Arc<BatchReader>),
+}
+
+impl PayloadManager {
+    pub async fn get_transactions(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<SignedTransaction>, ExecutorError> {
+        match (self, block.payload()) {
+            (_, None) => Ok(vec![]),
+            (_, Some(Payload::DirectMempool(txns))) => Ok(txns.clone()),
+            (PayloadManager::InQuorumStore(batch_reader), Some(Payload::InQuorumStore(proofs))) => {
+                let batches = monitor!(
+                    "get_batches",
+                    join_all(proofs.iter().map(|proof| batch_reader.get_batch(proof))).await
+                );
+                let mut txns = vec![];
+                for batch in batches {
+                    txns.extend(batch.map_err(|e| ExecutorError::InternalError {
+                        error: format!("Failed to get the batches of block {}: {}", block.id(), e),
+                    })?);
+                }
+                Ok(txns)
+            }
+            (PayloadManager::DirectMempool, Some(Payload::InQuorumStore(_))) => {
+                Err(ExecutorError::InternalError {
+                    error: format!(
+                        "Block {} has a quorum store payload, but quorum store is disabled",
+                        block.id()
+                    ),
+                })
+            }
+        }
+    }
+}
+
 /// Client that pulls blocks from Quorum Store
 #[derive(Clone)]
 pub struct QuorumStoreClient {
@@ -81,7 +128,7 @@ impl QuorumStoreClient {
 }
 
 #[async_trait::async_trait]
-impl PayloadManager for QuorumStoreClient {
+impl PayloadClient for QuorumStoreClient {
     async fn pull_payload(
         &self,
         max_items: u64,
@@ -0,0 +1,107 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::{counters, utils::pull_from_mempool};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_mempool::QuorumStoreRequest;
+use aptos_types::PeerId;
+use consensus_types::{
+    common::TransactionSummary,
+    proof_of_store::{Batch, LogicalTime, SignedDigestInfo},
+};
+use futures::channel::mpsc::Sender;
+use std::collections::{HashMap, HashSet};
+
+/// Pulls transactions from mempool into batches of this validator.
+pub struct BatchGenerator {
+    epoch: u64,
+    author: PeerId,
+    mempool_sender: Sender<QuorumStoreRequest>,
+    mempool_txn_pull_timeout_ms: u64,
+    max_batch_txns: u64,
+    max_batch_bytes: u64,
+    batch_expiry_rounds: u64,
+    /// The transactions of the batches that are neither committed nor expired, which must not
+    /// be pulled into another batch.
+    batches_in_progress: HashMap<HashValue, (Vec<TransactionSummary>, LogicalTime)>,
+}
+
+impl BatchGenerator {
+    pub fn new(
+        epoch: u64,
+        author: PeerId,
+        mempool_sender: Sender<QuorumStoreRequest>,
+        mempool_txn_pull_timeout_ms: u64,
+        max_batch_txns: u64,
+        max_batch_bytes: u64,
+        batch_expiry_rounds: u64,
+    ) -> Self {
+        Self {
+            epoch,
+            author,
+            mempool_sender,
+            mempool_txn_pull_timeout_ms,
+            max_batch_txns,
+            max_batch_bytes,
+            batch_expiry_rounds,
+            batches_in_progress: HashMap::new(),
+        }
+    }
+
+    pub fn num_batches_in_progress(&self) -> usize {
+        self.batches_in_progress.len()
+    }
+
+    /// Pulls a new batch from mempool, expiring `batch_expiry_rounds` after `latest_time`, and
+    /// returns it along with the info to be signed. Returns None if mempool has no transactions
+    /// that are not in a batch already.
+    pub async fn generate(
+        &mut self,
+        latest_time: LogicalTime,
+    ) -> Result<Option<(SignedDigestInfo, Batch)>> {
+        let exclude_txns: Vec<_> = self
+            .batches_in_progress
+            .values()
+            .flat_map(|(txns, _)| txns.iter().cloned())
+            .collect();
+        let txns = pull_from_mempool(
+            &self.mempool_sender,
+            self.mempool_txn_pull_timeout_ms,
+            self.max_batch_txns,
+            self.max_batch_bytes,
+            exclude_txns,
+        )
+        .await?;
+        if txns.is_empty() {
+            return Ok(None);
+        }
+
+        let expiration =
+            LogicalTime::new(self.epoch, latest_time.round() + self.batch_expiry_rounds);
+        let summaries = txns
+            .iter()
+            .map(|txn| TransactionSummary {
+                sender: txn.sender(),
+                sequence_number: txn.sequence_number(),
+            })
+            .collect();
+        let batch = Batch::new(self.epoch, self.author, expiration, txns);
+        let info = SignedDigestInfo::new(
+            batch.digest(),
+            expiration,
+            batch.num_txns(),
+            batch.num_bytes(),
+        );
+        self.batches_in_progress
+            .insert(info.digest, (summaries, expiration));
+        counters::CREATED_BATCHES.inc();
+        Ok(Some((info, batch)))
+    }
+
+    /// Releases the transactions of the batches that are committed or expired by `time`.
+    pub fn clean(&mut self, time: LogicalTime, committed: &HashSet<HashValue>) {
+        self.batches_in_progress
+            .retain(|digest, (_, expiration)| *expiration > time && !committed.contains(digest));
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{network::NetworkSender, quorum_store::counters};
+use anyhow::bail;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{transaction::SignedTransaction, PeerId};
+use consensus_types::proof_of_store::{Batch, BatchRequest, LogicalTime, ProofOfStore};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+struct StoredBatch {
+    batch: Batch,
+    num_bytes: u64,
+}
+
+#[derive(Default)]
+struct BatchStoreInner {
+    batches: HashMap<HashValue, StoredBatch>,
+    bytes_per_peer: HashMap<PeerId, u64>,
+}
+
+/// Keeps the batches this validator promised to serve, i.e. signed, until they expire.
+///
+/// Batches are only kept in memory: after a restart, the validator can't serve the batches it
+/// signed before, and relies on the other signers of their proofs.
+pub struct BatchStore {
+    epoch: u64,
+    memory_quota_per_peer: u64,
+    inner: Mutex<BatchStoreInner>,
+}
+
+impl BatchStore {
+    pub fn new(epoch: u64, memory_quota_per_peer: u64) -> Self {
+        Self {
+            epoch,
+            memory_quota_per_peer,
+            inner: Mutex::new(BatchStoreInner::default()),
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Stores the batch, returns false if it doesn't fit in the quota of its source.
+    pub fn insert(&self, digest: HashValue, batch: Batch, num_bytes: u64) -> bool {
+        let mut inner = self.inner.lock();
+        if inner.batches.contains_key(&digest) {
+            return true;
+        }
+        let source = batch.source();
+        let used = inner.bytes_per_peer.get(&source).copied().unwrap_or(0);
+        if used + num_bytes > self.memory_quota_per_peer {
+            return false;
+        }
+        inner.bytes_per_peer.insert(source, used + num_bytes);
+        inner
+            .batches
+            .insert(digest, StoredBatch { batch, num_bytes });
+        counters::STORED_BATCHES.set(inner.batches.len() as i64);
+        true
+    }
+
+    pub fn get(&self, digest: &HashValue) -> Option<Batch> {
+        self.inner
+            .lock()
+            .batches
+            .get(digest)
+            .map(|stored| stored.batch.clone())
+    }
+
+    /// Removes the batches that expire at or before `time`, returns how many were removed.
+    pub fn clear_expired(&self, time: LogicalTime) -> usize {
+        let mut inner = self.inner.lock();
+        let BatchStoreInner {
+            batches,
+            bytes_per_peer,
+        } = &mut *inner;
+        let num_batches = batches.len();
+        batches.retain(|_, stored| {
+            if stored.batch.expiration() > time {
+                return true;
+            }
+            if let Some(used) = bytes_per_peer.get_mut(&stored.batch.source()) {
+                *used = used.saturating_sub(stored.num_bytes);
+            }
+            false
+        });
+        bytes_per_peer.retain(|_, used| *used > 0);
+        counters::STORED_BATCHES.set(batches.len() as i64);
+        num_batches - batches.len()
+    }
+}
+
+/// Resolves the proofs of store of a block into transactions, reading the local batch store and
+/// fetching the batches it is missing from the signers of their proofs.
+pub struct BatchReader {
+    author: PeerId,
+    batch_store: Arc<BatchStore>,
+    network_sender: NetworkSender,
+    ordered_validators: Vec<PeerId>,
+    request_timeout: Duration,
+}
+
+impl BatchReader {
+    pub fn new(
+        author: PeerId,
+        batch_store: Arc<BatchStore>,
+        network_sender: NetworkSender,
+        ordered_validators: Vec<PeerId>,
+        request_timeout: Duration,
+    ) -> Self {
+        Self {
+            author,
+            batch_store,
+            network_sender,
+            ordered_validators,
+            request_timeout,
+        }
+    }
+
+    pub async fn get_batch(&self, proof: &ProofOfStore) -> anyhow::Result<Vec<SignedTransaction>> {
+        let digest = *proof.digest();
+        if let Some(batch) = self.batch_store.get(&digest) {
+            return Ok(batch.into_transactions());
+        }
+
+        let signers: Vec<_> = proof
+            .signers(&self.ordered_validators)
+            .into_iter()
+            .filter(|signer| *signer != self.author)
+            .collect();
+        if signers.is_empty() {
+            bail!("No signer to fetch batch {} from", digest);
+        }
+        // Spread the requests for different batches over the signers
+        let start = digest[0] as usize % signers.len();
+        for peer in signers[start..].iter().chain(signers[..start].iter()) {
+            let request = BatchRequest::new(self.batch_store.epoch(), self.author, digest);
+            match self
+                .network_sender
+                .request_batch(request, *peer, self.request_timeout)
+                .await
+            {
+                Ok(batch) => {
+                    if batch.digest() != digest {
+                        counters::FETCHED_BATCHES
+                            .with_label_values(&["mismatch"])
+                            .inc();
+                        warn!(
+                            remote_peer = *peer,
+                            "Received batch that doesn't match digest {}", digest
+                        );
+                        continue;
+                    }
+                    counters::FETCHED_BATCHES
+                        .with_label_values(&["success"])
+                        .inc();
+                    // Keep it around, in case the block is executed again before it expires. Only
+                    // the payload is covered by the digest, so take the expiration from the proof.
+                    let batch = Batch::new(
+                        proof.epoch(),
+                        batch.source(),
+                        proof.expiration(),
+                        batch.into_transactions(),
+                    );
+                    let num_bytes = batch.num_bytes();
+                    self.batch_store.insert(digest, batch.clone(), num_bytes);
+                    return Ok(batch.into_transactions());
+                }
+                Err(e) => {
+                    counters::FETCHED_BATCHES.with_label_values(&["fail"]).inc();
+                    warn!(
+                        remote_peer = *peer,
+                        error = ?e,
+                        "Failed to fetch batch {}", digest
+                    );
+                }
+            }
+        }
+        bail!("Unable to fetch batch {} from any signer", digest)
+    }
+}
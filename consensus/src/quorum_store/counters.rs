@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use aptos_metrics_core::{
-    op_counters::DurationHistogram, register_histogram, register_histogram_vec, HistogramVec,
+    op_counters::DurationHistogram, register_histogram, register_histogram_vec,
+    register_int_counter, register_int_counter_vec, register_int_gauge, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 use std::time::Duration;
@@ -42,3 +44,59 @@ pub static MAIN_LOOP: Lazy<DurationHistogram> = Lazy::new(|| {
         .unwrap(),
     )
 });
+
+/// Number of batches created by this validator.
+pub static CREATED_BATCHES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_created_batches_count",
+        "Number of batches created by this validator"
+    )
+    .unwrap()
+});
+
+/// Number of batches received from validators, by whether they were stored and signed.
+pub static RECEIVED_BATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "quorum_store_received_batches_count",
+        "Number of batches received from validators",
+        &["result"]
+    )
+    .unwrap()
+});
+
+/// Number of proofs of store aggregated for batches of this validator.
+pub static CREATED_PROOFS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_created_proofs_count",
+        "Number of proofs of store aggregated for batches of this validator"
+    )
+    .unwrap()
+});
+
+/// Number of proofs of store waiting to be proposed.
+pub static PROOFS_IN_POOL: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_proofs_in_pool",
+        "Number of proofs of store waiting to be proposed"
+    )
+    .unwrap()
+});
+
+/// Number of batches stored for other validators and for this one.
+pub static STORED_BATCHES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_stored_batches",
+        "Number of batches stored by this validator"
+    )
+    .unwrap()
+});
+
+/// Number of batches fetched from other validators to execute a block, by result.
+pub static FETCHED_BATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "quorum_store_fetched_batches_count",
+        "Number of batches fetched from other validators",
+        &["result"]
+    )
+    .unwrap()
+});
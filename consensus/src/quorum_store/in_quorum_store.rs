@@ -0,0 +1,374 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    network::{IncomingBatchRequest, NetworkSender, QuorumStoreMsg},
+    network_interface::ConsensusMsg,
+    quorum_store::{
+        batch_generator::BatchGenerator, batch_store::BatchStore, counters,
+        proof_coordinator::ProofCoordinator, proof_manager::ProofManager,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_config::config::ConsensusConfig;
+use aptos_logger::prelude::*;
+use aptos_mempool::QuorumStoreRequest;
+use aptos_types::{
+    account_address::AccountAddress, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier, PeerId,
+};
+use channel::aptos_channel;
+use consensus_types::{
+    common::{Payload, PayloadFilter},
+    proof_of_store::{Batch, LogicalTime, ProofOfStore, SignedDigest, SignedDigestInfo},
+    request_response::{ConsensusRequest, ConsensusResponse},
+};
+use futures::{
+    channel::{
+        mpsc::{Receiver, Sender},
+        oneshot,
+    },
+    StreamExt,
+};
+use network::protocols::rpc::error::RpcError;
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::{interval, MissedTickBehavior};
+
+/// Disseminates transactions in batches instead of in proposals: every validator broadcasts
+/// batches pulled from its mempool, the others store and sign them, and once a quorum signed a
+/// batch, its proof of store can be proposed in place of the transactions.
+pub struct InQuorumStore {
+    epoch: u64,
+    author: PeerId,
+    signer: Arc<ValidatorSigner>,
+    verifier: ValidatorVerifier,
+    network_sender: NetworkSender,
+    consensus_receiver: Receiver<ConsensusRequest>,
+    message_receiver: aptos_channel::Receiver<AccountAddress, (AccountAddress, QuorumStoreMsg)>,
+    batch_store: Arc<BatchStore>,
+    batch_generator: BatchGenerator,
+    proof_coordinator: ProofCoordinator,
+    proof_manager: ProofManager,
+    latest_time: LogicalTime,
+    batch_interval: Duration,
+    batch_expiry_rounds: u64,
+    max_batches_in_progress: usize,
+    max_receiving_batch_txns: u64,
+    max_receiving_batch_bytes: u64,
+}
+
+impl InQuorumStore {
+    /// `latest_time` is the latest committed block when quorum store starts.
+    pub fn new(
+        latest_time: LogicalTime,
+        signer: Arc<ValidatorSigner>,
+        verifier: ValidatorVerifier,
+        network_sender: NetworkSender,
+        consensus_receiver: Receiver<ConsensusRequest>,
+        message_receiver: aptos_channel::Receiver<AccountAddress, (AccountAddress, QuorumStoreMsg)>,
+        mempool_sender: Sender<QuorumStoreRequest>,
+        batch_store: Arc<BatchStore>,
+        config: &ConsensusConfig,
+    ) -> Self {
+        let epoch = latest_time.epoch();
+        let author = signer.author();
+        Self {
+            epoch,
+            author,
+            signer,
+            verifier,
+            network_sender,
+            consensus_receiver,
+            message_receiver,
+            batch_store,
+            batch_generator: BatchGenerator::new(
+                epoch,
+                author,
+                mempool_sender,
+                config.mempool_txn_pull_timeout_ms,
+                config.quorum_store_max_batch_txns,
+                config.quorum_store_max_batch_bytes,
+                config.quorum_store_batch_expiry_rounds,
+            ),
+            proof_coordinator: ProofCoordinator::default(),
+            proof_manager: ProofManager::new(
+                latest_time,
+                config.quorum_store_expiration_margin_rounds,
+                config.quorum_store_batch_expiry_rounds,
+            ),
+            latest_time,
+            batch_interval: Duration::from_millis(config.quorum_store_batch_interval_ms),
+            batch_expiry_rounds: config.quorum_store_batch_expiry_rounds,
+            max_batches_in_progress: config.quorum_store_max_batches_in_progress,
+            // A batch can't be proposed if it doesn't fit in a block
+            max_receiving_batch_txns: config.max_receiving_block_txns,
+            max_receiving_batch_bytes: config.max_receiving_block_bytes,
+        }
+    }
+
+    async fn generate_batch(&mut self) {
+        if self.batch_generator.num_batches_in_progress() >= self.max_batches_in_progress {
+            return;
+        }
+        match self.batch_generator.generate(self.latest_time).await {
+            Ok(Some((info, batch))) => {
+                debug!("Broadcasting {}", batch);
+                self.proof_coordinator.start_proof(info);
+                // The batch is delivered to ourself too, and stored and signed like any other
+                self.network_sender.broadcast_batch(batch).await;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(error = ?e, "Failed to pull a batch from mempool");
+            }
+        }
+    }
+
+    fn check_batch(&self, peer_id: PeerId, batch: &Batch) -> Result<()> {
+        ensure!(
+            batch.epoch() == self.epoch && batch.expiration().epoch() == self.epoch,
+            "Batch from a different epoch"
+        );
+        ensure!(batch.source() == peer_id, "Batch not sent by its source");
+        ensure!(
+            batch.expiration() > self.latest_time,
+            "Batch already expired"
+        );
+        // The source may have committed further than us, allow for it
+        ensure!(
+            batch.expiration().round() <= self.latest_time.round() + 2 * self.batch_expiry_rounds,
+            "Batch expires too late"
+        );
+        ensure!(
+            batch.num_txns() <= self.max_receiving_batch_txns,
+            "Batch has too many transactions"
+        );
+        Ok(())
+    }
+
+    async fn handle_batch(&mut self, peer_id: PeerId, batch: Batch) {
+        if let Err(e) = self.check_batch(peer_id, &batch) {
+            counters::RECEIVED_BATCHES
+                .with_label_values(&["invalid"])
+                .inc();
+            warn!(remote_peer = peer_id, error = ?e, "Invalid {}", batch);
+            return;
+        }
+        let num_bytes = batch.num_bytes();
+        if num_bytes > self.max_receiving_batch_bytes {
+            counters::RECEIVED_BATCHES
+                .with_label_values(&["invalid"])
+                .inc();
+            warn!(
+                remote_peer = peer_id,
+                "Batch too large: {} bytes", num_bytes
+            );
+            return;
+        }
+
+        let info = SignedDigestInfo::new(
+            batch.digest(),
+            batch.expiration(),
+            batch.num_txns(),
+            num_bytes,
+        );
+        if !self.batch_store.insert(info.digest, batch, num_bytes) {
+            counters::RECEIVED_BATCHES
+                .with_label_values(&["over_quota"])
+                .inc();
+            warn!(
+                remote_peer = peer_id,
+                "Batch {} exceeds the memory quota of its source", info.digest
+            );
+            return;
+        }
+        counters::RECEIVED_BATCHES
+            .with_label_values(&["stored"])
+            .inc();
+
+        match SignedDigest::new(self.epoch, info, &self.signer) {
+            Ok(signed_digest) => {
+                self.network_sender
+                    .send_signed_digest(signed_digest, peer_id)
+                    .await
+            }
+            Err(e) => error!(error = ?e, "Failed to sign batch"),
+        }
+    }
+
+    async fn handle_signed_digest(&mut self, peer_id: PeerId, signed_digest: SignedDigest) {
+        if signed_digest.epoch() != self.epoch || signed_digest.peer_id() != peer_id {
+            warn!(remote_peer = peer_id, "Unexpected signed digest");
+            return;
+        }
+        if let Err(e) = signed_digest.verify(&self.verifier) {
+            error!(
+                SecurityEvent::ConsensusInvalidMessage,
+                remote_peer = peer_id,
+                error = ?e,
+            );
+            return;
+        }
+        match self
+            .proof_coordinator
+            .add_signature(signed_digest, &self.verifier)
+        {
+            Ok(Some(proof)) => {
+                debug!("Broadcasting {}", proof);
+                self.network_sender.broadcast_proof_of_store(proof).await;
+            }
+            Ok(None) => {}
+            Err(e) => warn!(remote_peer = peer_id, error = ?e, "Failed to add signature"),
+        }
+    }
+
+    fn handle_proof_of_store(&mut self, peer_id: PeerId, proof: ProofOfStore) {
+        if proof.epoch() != self.epoch {
+            debug!(
+                remote_peer = peer_id,
+                "Proof of store from a different epoch"
+            );
+            return;
+        }
+        if let Err(e) = proof.verify(&self.verifier) {
+            error!(
+                SecurityEvent::ConsensusInvalidMessage,
+                remote_peer = peer_id,
+                error = ?e,
+            );
+            return;
+        }
+        self.proof_manager.insert(proof);
+    }
+
+    fn handle_batch_request(&self, peer_id: PeerId, request: IncomingBatchRequest) {
+        let digest = request.req.digest();
+        let response = match self.batch_store.get(&digest) {
+            Some(batch) => request
+                .protocol
+                .to_bytes(&ConsensusMsg::BatchMsg(Box::new(batch)))
+                .map(Into::into)
+                .map_err(RpcError::Error),
+            None => Err(RpcError::Error(anyhow!("Batch {} not found", digest))),
+        };
+        if request.response_sender.send(response).is_err() {
+            warn!(remote_peer = peer_id, "Failed to respond to batch request");
+        }
+    }
+
+    async fn handle_message(&mut self, peer_id: PeerId, msg: QuorumStoreMsg) {
+        match msg {
+            QuorumStoreMsg::Batch(batch) => self.handle_batch(peer_id, *batch).await,
+            QuorumStoreMsg::SignedDigest(signed_digest) => {
+                self.handle_signed_digest(peer_id, *signed_digest).await
+            }
+            QuorumStoreMsg::ProofOfStore(proof) => self.handle_proof_of_store(peer_id, *proof),
+            QuorumStoreMsg::BatchRequest(request) => self.handle_batch_request(peer_id, request),
+        }
+    }
+
+    fn handle_block_request(
+        &self,
+        max_txns: u64,
+        max_bytes: u64,
+        payload_filter: PayloadFilter,
+        callback: oneshot::Sender<Result<ConsensusResponse>>,
+    ) {
+        let get_batch_start_time = Instant::now();
+        let excluded = match payload_filter {
+            PayloadFilter::InQuorumStore(digests) => digests,
+            PayloadFilter::DirectMempool(_) => HashSet::new(),
+        };
+        let proofs = self.proof_manager.pull(max_txns, max_bytes, &excluded);
+        counters::quorum_store_service_latency(
+            counters::GET_BATCH_LABEL,
+            counters::REQUEST_SUCCESS_LABEL,
+            get_batch_start_time.elapsed(),
+        );
+
+        let payload = Payload::InQuorumStore(proofs);
+        if callback
+            .send(Ok(ConsensusResponse::GetBlockResponse(payload)))
+            .is_err()
+        {
+            error!("Callback failed");
+        }
+    }
+
+    fn handle_clean_request(
+        &mut self,
+        epoch: u64,
+        round: u64,
+        payloads: Vec<Payload>,
+        callback: oneshot::Sender<Result<ConsensusResponse>>,
+    ) {
+        if epoch == self.epoch {
+            let time = LogicalTime::new(epoch, round);
+            if time > self.latest_time {
+                self.latest_time = time;
+            }
+            let committed: HashSet<_> = payloads
+                .iter()
+                .flat_map(|payload| match payload {
+                    Payload::InQuorumStore(proofs) => {
+                        proofs.iter().map(|proof| *proof.digest()).collect()
+                    }
+                    Payload::DirectMempool(_) => vec![],
+                })
+                .collect();
+            self.proof_manager.commit(self.latest_time, &committed);
+            self.batch_generator.clean(self.latest_time, &committed);
+            self.proof_coordinator.expire(self.latest_time);
+            self.batch_store.clear_expired(self.latest_time);
+        }
+        if callback
+            .send(Ok(ConsensusResponse::CleanResponse()))
+            .is_err()
+        {
+            error!("Callback failed");
+        }
+    }
+
+    fn handle_consensus_request(&mut self, req: ConsensusRequest) {
+        match req {
+            ConsensusRequest::GetBlockRequest(max_txns, max_bytes, payload_filter, callback) => {
+                self.handle_block_request(max_txns, max_bytes, payload_filter, callback)
+            }
+            ConsensusRequest::CleanRequest(epoch, round, payloads, callback) => {
+                self.handle_clean_request(epoch, round, payloads, callback)
+            }
+        }
+    }
+
+    /// Runs until consensus or the epoch manager drop their sender, i.e. the epoch ends.
+    pub async fn start(mut self) {
+        info!(
+            epoch = self.epoch,
+            author = self.author,
+            "Quorum store starts"
+        );
+        let mut batch_interval = interval(self.batch_interval);
+        batch_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            let _timer = counters::MAIN_LOOP.start_timer();
+            tokio::select! {
+                _ = batch_interval.tick() => {
+                    self.generate_batch().await;
+                },
+                req = self.consensus_receiver.next() => match req {
+                    Some(req) => self.handle_consensus_request(req),
+                    None => break,
+                },
+                msg = self.message_receiver.next() => match msg {
+                    Some((peer_id, msg)) => self.handle_message(peer_id, msg).await,
+                    None => break,
+                },
+            }
+        }
+        info!(epoch = self.epoch, "Quorum store stops");
+    }
+}
@@ -1,9 +1,16 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod batch_generator;
+pub mod batch_store;
 /// Equivalent to directly fetching blocks from mempool without a quorum store.
 pub mod direct_mempool_quorum_store;
+/// Disseminates batches of transactions ahead of consensus, proposals carry proofs of store.
+pub mod in_quorum_store;
+pub mod proof_coordinator;
+pub mod proof_manager;
 
 mod counters;
 #[cfg(test)]
 mod tests;
+mod utils;
@@ -0,0 +1,74 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::counters;
+use anyhow::ensure;
+use aptos_crypto::HashValue;
+use aptos_types::{aggregate_signature::PartialSignatures, validator_verifier::ValidatorVerifier};
+use consensus_types::proof_of_store::{LogicalTime, ProofOfStore, SignedDigest, SignedDigestInfo};
+use std::collections::HashMap;
+
+struct IncrementalProof {
+    info: SignedDigestInfo,
+    signatures: PartialSignatures,
+}
+
+/// Collects the signatures on the batches of this validator into proofs of store.
+#[derive(Default)]
+pub struct ProofCoordinator {
+    proofs_in_progress: HashMap<HashValue, IncrementalProof>,
+}
+
+impl ProofCoordinator {
+    /// Starts collecting signatures on a batch of this validator.
+    pub fn start_proof(&mut self, info: SignedDigestInfo) {
+        self.proofs_in_progress
+            .entry(info.digest)
+            .or_insert_with(|| IncrementalProof {
+                info,
+                signatures: PartialSignatures::empty(),
+            });
+    }
+
+    /// Adds a verified signature, and returns the proof of store once the signers reach a quorum.
+    /// Signatures on batches that already have a proof are ignored.
+    pub fn add_signature(
+        &mut self,
+        signed_digest: SignedDigest,
+        verifier: &ValidatorVerifier,
+    ) -> anyhow::Result<Option<ProofOfStore>> {
+        let digest = signed_digest.digest();
+        let proof = match self.proofs_in_progress.get_mut(&digest) {
+            Some(proof) => proof,
+            None => return Ok(None),
+        };
+        ensure!(
+            proof.info == *signed_digest.info(),
+            "Signed digest info doesn't match the batch {}",
+            digest
+        );
+        proof
+            .signatures
+            .add_signature(signed_digest.peer_id(), signed_digest.signature().clone());
+        if verifier
+            .check_voting_power(proof.signatures.signatures().keys())
+            .is_err()
+        {
+            return Ok(None);
+        }
+
+        let multi_signature = verifier.aggregate_signatures(&proof.signatures)?;
+        let proof = self
+            .proofs_in_progress
+            .remove(&digest)
+            .expect("Proof must exist");
+        counters::CREATED_PROOFS.inc();
+        Ok(Some(ProofOfStore::new(proof.info, multi_signature)))
+    }
+
+    /// Gives up on the batches that expire at or before `time`.
+    pub fn expire(&mut self, time: LogicalTime) {
+        self.proofs_in_progress
+            .retain(|_, proof| proof.info.expiration > time);
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::counters;
+use aptos_crypto::HashValue;
+use consensus_types::proof_of_store::{LogicalTime, ProofOfStore};
+use std::collections::{HashMap, HashSet};
+
+/// The pool of proofs of store this validator can propose, in the order they arrived.
+pub struct ProofManager {
+    epoch: u64,
+    proofs: Vec<ProofOfStore>,
+    digests: HashSet<HashValue>,
+    /// Committed digests and their expiration, so that proofs arriving late aren't proposed.
+    committed: HashMap<HashValue, LogicalTime>,
+    latest_time: LogicalTime,
+    /// Proofs that expire within this many rounds are not proposed anymore, as the block might
+    /// only be executed after their batches are gone.
+    expiration_margin_rounds: u64,
+    /// Upper bound of the expiration of any valid batch, relative to the latest round.
+    batch_expiry_rounds: u64,
+}
+
+impl ProofManager {
+    /// `latest_time` is the latest committed block when the pool is created.
+    pub fn new(
+        latest_time: LogicalTime,
+        expiration_margin_rounds: u64,
+        batch_expiry_rounds: u64,
+    ) -> Self {
+        Self {
+            epoch: latest_time.epoch(),
+            proofs: Vec::new(),
+            digests: HashSet::new(),
+            committed: HashMap::new(),
+            latest_time,
+            expiration_margin_rounds,
+            batch_expiry_rounds,
+        }
+    }
+
+    pub fn num_proofs(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Adds a verified proof to the pool, unless it is known, committed or expired.
+    pub fn insert(&mut self, proof: ProofOfStore) {
+        if proof.epoch() != self.epoch
+            || proof.expiration() <= self.latest_time
+            || self.digests.contains(proof.digest())
+            || self.committed.contains_key(proof.digest())
+        {
+            return;
+        }
+        self.digests.insert(*proof.digest());
+        self.proofs.push(proof);
+        counters::PROOFS_IN_POOL.set(self.proofs.len() as i64);
+    }
+
+    /// Returns the oldest proofs that fit in the limits, skipping the excluded digests, i.e. the
+    /// ones in pending blocks, and the ones close to their expiration.
+    pub fn pull(
+        &self,
+        max_txns: u64,
+        max_bytes: u64,
+        excluded: &HashSet<HashValue>,
+    ) -> Vec<ProofOfStore> {
+        let min_expiration_round = self.latest_time.round() + self.expiration_margin_rounds;
+        let mut num_txns = 0;
+        let mut num_bytes = 0;
+        let mut result = vec![];
+        for proof in &self.proofs {
+            if excluded.contains(proof.digest())
+                || proof.expiration().round() <= min_expiration_round
+            {
+                continue;
+            }
+            if num_txns + proof.num_txns() > max_txns || num_bytes + proof.num_bytes() > max_bytes {
+                break;
+            }
+            num_txns += proof.num_txns();
+            num_bytes += proof.num_bytes();
+            result.push(proof.clone());
+        }
+        result
+    }
+
+    /// Removes the committed and the expired proofs, `time` being the latest committed block.
+    pub fn commit(&mut self, time: LogicalTime, committed_digests: &HashSet<HashValue>) {
+        if time > self.latest_time {
+            self.latest_time = time;
+        }
+        // Proofs that are not in the pool can't expire later than the maximum allowed expiry
+        let max_expiration = LogicalTime::new(self.epoch, time.round() + self.batch_expiry_rounds);
+        for digest in committed_digests {
+            self.committed.insert(*digest, max_expiration);
+        }
+
+        let latest_time = self.latest_time;
+        let committed = &mut self.committed;
+        let digests = &mut self.digests;
+        self.proofs.retain(|proof| {
+            let digest = proof.digest();
+            if let Some(expiration) = committed.get_mut(digest) {
+                *expiration = proof.expiration();
+            } else if proof.expiration() > latest_time {
+                return true;
+            }
+            digests.remove(digest);
+            false
+        });
+        self.committed
+            .retain(|_, expiration| *expiration > latest_time);
+        counters::PROOFS_IN_POOL.set(self.proofs.len() as i64);
+    }
+}
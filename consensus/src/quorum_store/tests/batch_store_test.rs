@@ -0,0 +1,49 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::batch_store::BatchStore;
+use aptos_crypto::HashValue;
+use aptos_types::account_address::AccountAddress;
+use consensus_types::proof_of_store::{Batch, LogicalTime};
+
+#[test]
+fn test_memory_quota() {
+    let batch_store = BatchStore::new(1, 1000);
+    let source = AccountAddress::random();
+    let other_source = AccountAddress::random();
+    let batch = |source| Batch::new(1, source, LogicalTime::new(1, 10), vec![]);
+
+    assert!(batch_store.insert(HashValue::random(), batch(source), 600));
+    assert!(!batch_store.insert(HashValue::random(), batch(source), 600));
+    assert!(batch_store.insert(HashValue::random(), batch(source), 400));
+    // The quota is per source
+    assert!(batch_store.insert(HashValue::random(), batch(other_source), 600));
+}
+
+#[test]
+fn test_clear_expired() {
+    let batch_store = BatchStore::new(1, 1000);
+    let source = AccountAddress::random();
+    let expiring = HashValue::random();
+    let remaining = HashValue::random();
+    batch_store.insert(
+        expiring,
+        Batch::new(1, source, LogicalTime::new(1, 10), vec![]),
+        600,
+    );
+    batch_store.insert(
+        remaining,
+        Batch::new(1, source, LogicalTime::new(1, 20), vec![]),
+        400,
+    );
+
+    assert_eq!(batch_store.clear_expired(LogicalTime::new(1, 10)), 1);
+    assert!(batch_store.get(&expiring).is_none());
+    assert!(batch_store.get(&remaining).is_some());
+    // The quota of the expired batch is released
+    assert!(batch_store.insert(
+        HashValue::random(),
+        Batch::new(1, source, LogicalTime::new(1, 20), vec![]),
+        600
+    ));
+}
@@ -1,5 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(test)]
+mod batch_store_test;
 #[cfg(test)]
 mod direct_mempool_quorum_store_test;
+#[cfg(test)]
+mod proof_coordinator_test;
+#[cfg(test)]
+mod proof_manager_test;
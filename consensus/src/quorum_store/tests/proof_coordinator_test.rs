@@ -0,0 +1,69 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::proof_coordinator::ProofCoordinator;
+use aptos_crypto::HashValue;
+use aptos_types::validator_verifier::random_validator_verifier;
+use consensus_types::proof_of_store::{LogicalTime, SignedDigest, SignedDigestInfo};
+
+#[test]
+fn test_proof_at_quorum() {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let mut proof_coordinator = ProofCoordinator::default();
+    let info = SignedDigestInfo::new(HashValue::random(), LogicalTime::new(1, 20), 10, 1000);
+    proof_coordinator.start_proof(info.clone());
+
+    for signer in &signers[..2] {
+        let signed_digest = SignedDigest::new(1, info.clone(), signer).unwrap();
+        assert!(proof_coordinator
+            .add_signature(signed_digest, &verifier)
+            .unwrap()
+            .is_none());
+    }
+    let signed_digest = SignedDigest::new(1, info.clone(), &signers[2]).unwrap();
+    let proof = proof_coordinator
+        .add_signature(signed_digest, &verifier)
+        .unwrap()
+        .unwrap();
+    assert_eq!(proof.info(), &info);
+    proof.verify(&verifier).unwrap();
+    let ordered_validators: Vec<_> = verifier.get_ordered_account_addresses_iter().collect();
+    assert_eq!(proof.signers(&ordered_validators).len(), 3);
+
+    // Late signatures don't produce another proof
+    let signed_digest = SignedDigest::new(1, info, &signers[3]).unwrap();
+    assert!(proof_coordinator
+        .add_signature(signed_digest, &verifier)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_mismatched_info() {
+    let (signers, verifier) = random_validator_verifier(4, None, false);
+    let mut proof_coordinator = ProofCoordinator::default();
+    let info = SignedDigestInfo::new(HashValue::random(), LogicalTime::new(1, 20), 10, 1000);
+    proof_coordinator.start_proof(info.clone());
+
+    let mut wrong_info = info;
+    wrong_info.num_txns = 20;
+    let signed_digest = SignedDigest::new(1, wrong_info, &signers[0]).unwrap();
+    assert!(proof_coordinator
+        .add_signature(signed_digest, &verifier)
+        .is_err());
+}
+
+#[test]
+fn test_expired_proof() {
+    let (signers, verifier) = random_validator_verifier(1, None, false);
+    let mut proof_coordinator = ProofCoordinator::default();
+    let info = SignedDigestInfo::new(HashValue::random(), LogicalTime::new(1, 20), 10, 1000);
+    proof_coordinator.start_proof(info.clone());
+    proof_coordinator.expire(LogicalTime::new(1, 20));
+
+    let signed_digest = SignedDigest::new(1, info, &signers[0]).unwrap();
+    assert!(proof_coordinator
+        .add_signature(signed_digest, &verifier)
+        .unwrap()
+        .is_none());
+}
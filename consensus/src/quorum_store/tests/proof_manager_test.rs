@@ -0,0 +1,78 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::proof_manager::ProofManager;
+use aptos_crypto::HashValue;
+use aptos_types::aggregate_signature::AggregateSignature;
+use consensus_types::proof_of_store::{LogicalTime, ProofOfStore, SignedDigestInfo};
+use std::collections::HashSet;
+
+fn create_proof(expiration_round: u64, num_txns: u64) -> ProofOfStore {
+    ProofOfStore::new(
+        SignedDigestInfo::new(
+            HashValue::random(),
+            LogicalTime::new(1, expiration_round),
+            num_txns,
+            num_txns * 100,
+        ),
+        AggregateSignature::empty(),
+    )
+}
+
+#[test]
+fn test_pull_in_order_within_limits() {
+    let mut proof_manager = ProofManager::new(LogicalTime::new(1, 0), 5, 100);
+    let proofs: Vec<_> = (0..4).map(|_| create_proof(50, 10)).collect();
+    for proof in &proofs {
+        proof_manager.insert(proof.clone());
+    }
+    // Duplicates are ignored
+    proof_manager.insert(proofs[0].clone());
+    assert_eq!(proof_manager.num_proofs(), 4);
+
+    assert_eq!(proof_manager.pull(25, 10_000, &HashSet::new()), proofs[..2]);
+    assert_eq!(proof_manager.pull(100, 2_500, &HashSet::new()), proofs[..2]);
+
+    let excluded: HashSet<_> = proofs[..3].iter().map(|proof| *proof.digest()).collect();
+    assert_eq!(proof_manager.pull(100, 10_000, &excluded), proofs[3..]);
+}
+
+#[test]
+fn test_skip_close_to_expiration() {
+    let mut proof_manager = ProofManager::new(LogicalTime::new(1, 10), 5, 100);
+    let expiring = create_proof(15, 10);
+    let fresh = create_proof(16, 10);
+    proof_manager.insert(expiring);
+    proof_manager.insert(fresh.clone());
+    // Already expired proofs are not even kept
+    proof_manager.insert(create_proof(10, 10));
+    assert_eq!(proof_manager.num_proofs(), 2);
+
+    assert_eq!(
+        proof_manager.pull(100, 10_000, &HashSet::new()),
+        vec![fresh]
+    );
+}
+
+#[test]
+fn test_commit() {
+    let mut proof_manager = ProofManager::new(LogicalTime::new(1, 0), 0, 100);
+    let committed = create_proof(50, 10);
+    let expired = create_proof(20, 10);
+    let pending = create_proof(50, 10);
+    let late = create_proof(50, 10);
+    proof_manager.insert(committed.clone());
+    proof_manager.insert(expired);
+    proof_manager.insert(pending.clone());
+
+    let committed_digests: HashSet<_> = [*committed.digest(), *late.digest()].into_iter().collect();
+    proof_manager.commit(LogicalTime::new(1, 20), &committed_digests);
+    assert_eq!(proof_manager.num_proofs(), 1);
+
+    // A proof that arrives after its batch was committed isn't proposed again
+    proof_manager.insert(late);
+    assert_eq!(
+        proof_manager.pull(100, 10_000, &HashSet::new()),
+        vec![pending]
+    );
+}
@@ -0,0 +1,46 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::monitor;
+use anyhow::Result;
+use aptos_mempool::{QuorumStoreRequest, QuorumStoreResponse};
+use aptos_types::transaction::SignedTransaction;
+use consensus_types::common::TransactionSummary;
+use futures::channel::{mpsc::Sender, oneshot};
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// Pulls up to `max_items` transactions and `max_bytes` from mempool, skipping `exclude_txns`.
+pub(crate) async fn pull_from_mempool(
+    mempool_sender: &Sender<QuorumStoreRequest>,
+    mempool_txn_pull_timeout_ms: u64,
+    max_items: u64,
+    max_bytes: u64,
+    exclude_txns: Vec<TransactionSummary>,
+) -> Result<Vec<SignedTransaction>> {
+    let (callback, callback_rcv) = oneshot::channel();
+    let msg = QuorumStoreRequest::GetBatchRequest(max_items, max_bytes, exclude_txns, callback);
+    mempool_sender
+        .clone()
+        .try_send(msg)
+        .map_err(anyhow::Error::from)?;
+    // wait for response
+    match monitor!(
+        "pull_txn",
+        timeout(
+            Duration::from_millis(mempool_txn_pull_timeout_ms),
+            callback_rcv
+        )
+        .await
+    ) {
+        Err(_) => Err(anyhow::anyhow!(
+            "[quorum_store] did not receive GetBatchResponse on time"
+        )),
+        Ok(resp) => match resp.map_err(anyhow::Error::from)?? {
+            QuorumStoreResponse::GetBatchResponse(txns) => Ok(txns),
+            _ => Err(anyhow::anyhow!(
+                "[quorum_store] did not receive expected GetBatchResponse"
+            )),
+        },
+    }
+}
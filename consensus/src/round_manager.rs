@@ -33,7 +33,7 @@ use aptos_types::{
 use channel::aptos_channel;
 use consensus_types::{
     block::Block,
-    common::{Author, Round},
+    common::{Author, Payload, Round},
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
     proposal_msg::ProposalMsg,
     quorum_cert::QuorumCert,
@@ -601,6 +601,16 @@ impl RoundManager {
             self.local_config.max_receiving_block_bytes,
         );
 
+        let is_quorum_store_payload = matches!(proposal.payload(), Some(Payload::InQuorumStore(_)));
+        ensure!(
+            proposal
+                .payload()
+                .map_or(true, |payload| payload.is_empty())
+                || is_quorum_store_payload == self.local_config.use_quorum_store,
+            "Payload type doesn't match use_quorum_store {}",
+            self.local_config.use_quorum_store,
+        );
+
         ensure!(
             self.proposer_election.is_valid_proposal(&proposal),
             "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
@@ -7,6 +7,7 @@ use crate::{
     commit_notifier::CommitNotifier,
     counters,
     error::StateSyncError,
+    payload_manager::PayloadManager,
     state_replication::{StateComputer, StateComputerCommitCallBackType},
     txn_notifier::TxnNotifier,
 };
@@ -19,7 +20,11 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures, transaction::Transaction,
 };
 use consensus_notifications::ConsensusNotificationSender;
-use consensus_types::{block::Block, common::Round, executed_block::ExecutedBlock};
+use consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+    executed_block::ExecutedBlock,
+};
 use executor_types::{BlockExecutorTrait, Error as ExecutionError, StateComputeResult};
 use fail::fail_point;
 use futures::{SinkExt, StreamExt};
@@ -32,7 +37,7 @@ type NotificationType = (
     Vec<ContractEvent>,
 );
 
-type CommitType = (u64, Round);
+type CommitType = (u64, Round, Vec<Payload>);
 
 /// Basic communication with the Execution module;
 /// implements StateComputer traits.
@@ -43,6 +48,7 @@ pub struct ExecutionProxy {
     async_state_sync_notifier: channel::Sender<NotificationType>,
     async_commit_notifier: channel::Sender<CommitType>,
     validators: Mutex<Vec<AccountAddress>>,
+    payload_manager: Mutex<Arc<PayloadManager>>,
     write_mutex: AsyncMutex<()>,
 }
 
@@ -73,10 +79,11 @@ impl ExecutionProxy {
             channel::new::<CommitType>(10, &counters::PENDING_QUORUM_STORE_COMMIT_NOTIFICATION);
         let notifier = commit_notifier.clone();
         handle.spawn(async move {
-            while let Some((epoch, round)) = commit_rx.next().await {
-                if let Err(e) =
-                    monitor!("notify_commit", notifier.notify_commit(epoch, round).await)
-                {
+            while let Some((epoch, round, payloads)) = commit_rx.next().await {
+                if let Err(e) = monitor!(
+                    "notify_commit",
+                    notifier.notify_commit(epoch, round, payloads).await
+                ) {
                     error!(error = ?e, "Failed to notify commit notifier");
                 }
             }
@@ -88,6 +95,7 @@ impl ExecutionProxy {
             async_state_sync_notifier: tx,
             async_commit_notifier: commit_tx,
             validators: Mutex::new(vec![]),
+            payload_manager: Mutex::new(Arc::new(PayloadManager::DirectMempool)),
             write_mutex: AsyncMutex::new(()),
         }
     }
@@ -116,7 +124,10 @@ impl StateComputer for ExecutionProxy {
 
         // TODO: figure out error handling for the prologue txn
         let executor = self.executor.clone();
-        let transactions_to_execute = block.transactions_to_execute(&self.validators.lock());
+        let payload_manager = self.payload_manager.lock().clone();
+        let txns = payload_manager.get_transactions(block).await?;
+        let transactions_to_execute =
+            block.transactions_to_execute(&self.validators.lock(), txns.clone());
         let compute_result = monitor!(
             "execute_block",
             tokio::task::spawn_blocking(move || {
@@ -130,7 +141,7 @@ impl StateComputer for ExecutionProxy {
         // notify mempool about failed transaction
         if let Err(e) = self
             .txn_notifier
-            .notify_failed_txn(block, &compute_result)
+            .notify_failed_txn(txns, &compute_result)
             .await
         {
             error!(
@@ -152,14 +163,20 @@ impl StateComputer for ExecutionProxy {
         let mut block_ids = Vec::new();
         let mut txns = Vec::new();
         let mut reconfig_events = Vec::new();
+        let mut payloads = Vec::new();
         let skip_clean = blocks.is_empty();
         let mut latest_epoch: u64 = 0;
         let mut latest_round: u64 = 0;
 
+        let payload_manager = self.payload_manager.lock().clone();
         for block in blocks {
             block_ids.push(block.id());
-            txns.extend(block.transactions_to_commit(&self.validators.lock()));
+            let block_txns = payload_manager.get_transactions(block.block()).await?;
+            txns.extend(block.transactions_to_commit(&self.validators.lock(), block_txns));
             reconfig_events.extend(block.reconfig_event());
+            if let Some(payload) = block.payload() {
+                payloads.push(payload.clone());
+            }
 
             if block.epoch() > latest_epoch {
                 latest_epoch = block.epoch();
@@ -199,7 +216,7 @@ impl StateComputer for ExecutionProxy {
         }
         self.async_commit_notifier
             .clone()
-            .send((latest_epoch, latest_round))
+            .send((latest_epoch, latest_round, payloads))
             .await
             .expect("Failed to send async commit notification");
         Ok(())
@@ -236,10 +253,11 @@ impl StateComputer for ExecutionProxy {
         })
     }
 
-    fn new_epoch(&self, epoch_state: &EpochState) {
+    fn new_epoch(&self, epoch_state: &EpochState, payload_manager: Arc<PayloadManager>) {
         *self.validators.lock() = epoch_state
             .verifier
             .get_ordered_account_addresses_iter()
             .collect();
+        *self.payload_manager.lock() = payload_manager;
     }
 }
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::error::{QuorumStoreError, StateSyncError};
+use crate::{
+    error::{QuorumStoreError, StateSyncError},
+    payload_manager::PayloadManager,
+};
 use anyhow::Result;
 use aptos_crypto::HashValue;
 use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
@@ -18,7 +21,7 @@ pub type StateComputerCommitCallBackType =
     Box<dyn FnOnce(&[Arc<ExecutedBlock>], LedgerInfoWithSignatures) + Send + Sync>;
 
 #[async_trait::async_trait]
-pub trait PayloadManager: Send + Sync {
+pub trait PayloadClient: Send + Sync {
     async fn pull_payload(
         &self,
         max_items: u64,
@@ -62,5 +65,5 @@ pub trait StateComputer: Send + Sync {
     async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError>;
 
     // Reconfigure to execute transactions for a new epoch.
-    fn new_epoch(&self, epoch_state: &EpochState);
+    fn new_epoch(&self, epoch_state: &EpochState, payload_manager: Arc<PayloadManager>);
 }
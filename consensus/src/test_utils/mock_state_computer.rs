@@ -4,6 +4,7 @@
 use crate::{
     error::StateSyncError,
     experimental::buffer_manager::OrderedBlocks,
+    payload_manager::PayloadManager,
     state_replication::{StateComputer, StateComputerCommitCallBackType},
     test_utils::mock_storage::MockStorage,
 };
@@ -14,7 +15,7 @@ use aptos_logger::prelude::*;
 use aptos_types::{
     epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
 };
-use consensus_types::{block::Block, common::Payload, executed_block::ExecutedBlock};
+use consensus_types::{block::Block, executed_block::ExecutedBlock};
 use executor_types::{Error, StateComputeResult};
 use futures::{channel::mpsc, SinkExt};
 use futures_channel::mpsc::UnboundedSender;
@@ -24,7 +25,7 @@ pub struct MockStateComputer {
     state_sync_client: mpsc::UnboundedSender<Vec<SignedTransaction>>,
     executor_channel: UnboundedSender<OrderedBlocks>,
     consensus_db: Arc<MockStorage>,
-    block_cache: Mutex<HashMap<HashValue, Payload>>,
+    block_cache: Mutex<HashMap<HashValue, Vec<SignedTransaction>>>,
 }
 
 impl MockStateComputer {
@@ -58,9 +59,7 @@ impl MockStateComputer {
                 .block_cache
                 .lock()
                 .remove(&block.id())
-                .ok_or_else(|| format_err!("Cannot find block"))?
-                .into_iter()
-                .collect();
+                .ok_or_else(|| format_err!("Cannot find block"))?;
             txns.append(&mut payload);
         }
         // they may fail during shutdown
@@ -82,10 +81,10 @@ impl StateComputer for MockStateComputer {
         block: &Block,
         _parent_block_id: HashValue,
     ) -> Result<StateComputeResult, Error> {
-        self.block_cache.lock().insert(
-            block.id(),
-            block.payload().unwrap_or(&Payload::empty()).clone(),
-        );
+        let txns = PayloadManager::DirectMempool
+            .get_transactions(block)
+            .await?;
+        self.block_cache.lock().insert(block.id(), txns);
         let result = StateComputeResult::new_dummy();
         Ok(result)
     }
@@ -131,7 +130,7 @@ impl StateComputer for MockStateComputer {
         Ok(())
     }
 
-    fn new_epoch(&self, _: &EpochState) {}
+    fn new_epoch(&self, _: &EpochState, _: Arc<PayloadManager>) {}
 }
 
 pub struct EmptyStateComputer;
@@ -159,7 +158,7 @@ impl StateComputer for EmptyStateComputer {
         Ok(())
     }
 
-    fn new_epoch(&self, _: &EpochState) {}
+    fn new_epoch(&self, _: &EpochState, _: Arc<PayloadManager>) {}
 }
 
 /// Random Compute Result State Computer
@@ -210,5 +209,5 @@ impl StateComputer for RandomComputeResultStateComputer {
         Ok(())
     }
 
-    fn new_epoch(&self, _: &EpochState) {}
+    fn new_epoch(&self, _: &EpochState, _: Arc<PayloadManager>) {}
 }
@@ -146,6 +146,7 @@ impl SMRNode {
             storage.clone(),
             reconfig_listener,
             commit_notifier,
+            None,
         );
         let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
 
@@ -4,8 +4,8 @@
 use crate::{error::MempoolError, monitor};
 use anyhow::{format_err, Result};
 use aptos_mempool::QuorumStoreRequest;
-use aptos_types::transaction::TransactionStatus;
-use consensus_types::{block::Block, common::RejectedTransactionSummary};
+use aptos_types::transaction::{SignedTransaction, TransactionStatus};
+use consensus_types::common::RejectedTransactionSummary;
 use executor_types::StateComputeResult;
 use futures::channel::{mpsc, oneshot};
 use itertools::Itertools;
@@ -15,11 +15,11 @@ use tokio::time::timeout;
 /// Notification of failed transactions.
 #[async_trait::async_trait]
 pub trait TxnNotifier: Send + Sync {
-    /// Notification of txns which failed execution. (Committed txns is notified by
-    /// state sync.)
+    /// Notification of txns which failed execution, `txns` being the user transactions of the
+    /// executed block. (Committed txns is notified by state sync.)
     async fn notify_failed_txn(
         &self,
-        block: &Block,
+        txns: Vec<SignedTransaction>,
         compute_results: &StateComputeResult,
     ) -> Result<(), MempoolError>;
 }
@@ -48,18 +48,10 @@ impl MempoolNotifier {
 impl TxnNotifier for MempoolNotifier {
     async fn notify_failed_txn(
         &self,
-        block: &Block,
+        txns: Vec<SignedTransaction>,
         compute_results: &StateComputeResult,
     ) -> Result<(), MempoolError> {
         let mut rejected_txns = vec![];
-        let txns: Vec<_> = match block.payload() {
-            Some(payload) => payload,
-            None => return Ok(()),
-        }
-        .clone()
-        .into_iter()
-        .collect();
-
         if txns.is_empty() {
             return Ok(());
         }
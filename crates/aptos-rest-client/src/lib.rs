@@ -12,12 +12,14 @@ pub mod response;
 pub use response::Response;
 pub mod state;
 pub mod types;
+pub mod verifying_client;
 
 pub use aptos_api_types::{
     self, IndexResponseBcs, MoveModuleBytecode, PendingTransaction, Transaction,
 };
 pub use state::State;
 pub use types::{deserialize_from_prefixed_hex_string, Account, Resource};
+pub use verifying_client::VerifyingClient;
 
 use crate::aptos::{AptosVersion, Balance};
 use crate::error::RestError;
@@ -25,9 +27,10 @@ use anyhow::{anyhow, Result};
 use aptos_api_types::{
     deserialize_from_string,
     mime_types::{BCS, BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE},
-    AptosError, BcsBlock, Block, Bytecode, ExplainVMStatus, GasEstimation, HexEncodedBytes,
-    IndexResponse, MoveModuleId, TransactionData, TransactionOnChainData,
-    TransactionsBatchSubmissionResult, UserTransaction, VersionedEvent,
+    AptosError, BcsBlock, Block, Bytecode, ExplainVMStatus, GasEstimation, GasProfile,
+    HexEncodedBytes, IndexResponse, MoveModuleId, StateValueChange, TransactionData,
+    TransactionOnChainData, TransactionsBatchSubmissionResult, UserTransaction, VersionedEvent,
+    ViewRequest,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{debug, info, sample, sample::SampleRate};
@@ -35,9 +38,16 @@ use aptos_types::{
     account_address::AccountAddress,
     account_config::{AccountResource, CoinStoreResource, NewBlockEvent, CORE_CODE_ADDRESS},
     contract_event::EventWithVersion,
-    transaction::SignedTransaction,
+    event::EventKey,
+    state_proof::StateProof,
+    state_store::state_value::StateValueWithProof,
+    transaction::{SignedTransaction, TransactionWithProof},
+};
+use futures::{
+    executor::block_on,
+    stream::{self, BoxStream},
+    Stream, StreamExt,
 };
-use futures::executor::block_on;
 use move_binary_format::CompiledModule;
 use move_core_types::language_storage::{ModuleId, StructTag};
 use reqwest::header::ACCEPT;
@@ -296,6 +306,7 @@ impl Client {
             oldest_ledger_version: r.oldest_ledger_version.into(),
             oldest_block_height: r.oldest_block_height.into(),
             block_height: r.block_height.into(),
+            cursor: None,
         });
         assert_eq!(response.inner().chain_id, response.state().chain_id);
         assert_eq!(response.inner().epoch, response.state().epoch);
@@ -368,6 +379,25 @@ impl Client {
         Ok(response.and_then(|bytes| bcs::from_bytes(&bytes))?)
     }
 
+    /// Simulates a transaction, returning where its gas went rather than its outputs
+    pub async fn simulate_gas_profile(
+        &self,
+        txn: &SignedTransaction,
+    ) -> AptosResult<Response<GasProfile>> {
+        let txn_payload = bcs::to_bytes(txn)?;
+        let url = self.build_path("transactions/simulate/gas_profile")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+            .body(txn_payload)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     pub async fn submit(
         &self,
         txn: &SignedTransaction,
@@ -985,6 +1015,66 @@ impl Client {
         Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
     }
 
+    /// Get the proof of the latest ledger info and of the epoch changes since `known_version`.
+    ///
+    /// The response is not verified, see [`VerifyingClient`] for that.
+    pub async fn get_state_proof_bcs(
+        &self,
+        known_version: u64,
+    ) -> AptosResult<Response<StateProof>> {
+        let url = self.build_path(&format!(
+            "proofs/state_proof?known_version={}",
+            known_version
+        ))?;
+        let response = self.get_bcs(url).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    /// Get a resource along with its proof against the ledger info at `ledger_version`.
+    ///
+    /// The response is not verified, see [`VerifyingClient`] for that.
+    pub async fn get_account_resource_with_proof_bcs(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+        ledger_version: Option<u64>,
+    ) -> AptosResult<Response<StateValueWithProof>> {
+        let url = self.build_path(&format!(
+            "proofs/accounts/{}/resource/{}",
+            address, resource_type
+        ))?;
+        let mut request = self.inner.get(url).header(ACCEPT, BCS);
+        if let Some(ledger_version) = ledger_version {
+            request = request.query(&[("ledger_version", ledger_version)])
+        }
+
+        let response = self
+            .check_and_parse_bcs_response(request.send().await?)
+            .await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    /// Get a transaction and its events along with their proof against the ledger info at
+    /// `ledger_version`.
+    ///
+    /// The response is not verified, see [`VerifyingClient`] for that.
+    pub async fn get_transaction_by_version_with_proof_bcs(
+        &self,
+        version: u64,
+        ledger_version: Option<u64>,
+    ) -> AptosResult<Response<TransactionWithProof>> {
+        let url = self.build_path(&format!("proofs/transactions/by_version/{}", version))?;
+        let mut request = self.inner.get(url).header(ACCEPT, BCS);
+        if let Some(ledger_version) = ledger_version {
+            request = request.query(&[("ledger_version", ledger_version)])
+        }
+
+        let response = self
+            .check_and_parse_bcs_response(request.send().await?)
+            .await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
     pub async fn get_account_resource_at_version_bytes(
         &self,
         address: AccountAddress,
@@ -1052,6 +1142,72 @@ impl Client {
         self.get_bcs(url).await
     }
 
+    pub async fn get_account_module_bcs_at_version(
+        &self,
+        address: AccountAddress,
+        module_name: &str,
+        version: u64,
+    ) -> AptosResult<Response<bytes::Bytes>> {
+        let url = self.build_path(&format!(
+            "accounts/{}/module/{}?ledger_version={}",
+            address, module_name, version
+        ))?;
+        self.get_bcs(url).await
+    }
+
+    /// Returns a page of the state items whose values differ between `from_version` and
+    /// `to_version`, from the cursor `start` onwards
+    ///
+    /// The cursor of the next page is in the `cursor` of the response state, and is `None` once
+    /// all changes have been returned.
+    pub async fn get_state_changes(
+        &self,
+        from_version: u64,
+        to_version: Option<u64>,
+        start: Option<&str>,
+        limit: Option<u16>,
+    ) -> AptosResult<Response<Vec<StateValueChange>>> {
+        let url = self.build_state_changes_path(from_version, to_version, start, limit)?;
+        let response = self.inner.get(url).send().await?;
+        self.json(response).await
+    }
+
+    pub async fn get_state_changes_bcs(
+        &self,
+        from_version: u64,
+        to_version: Option<u64>,
+        start: Option<&str>,
+        limit: Option<u16>,
+    ) -> AptosResult<Response<Vec<aptos_types::state_store::state_value::StateValueChange>>> {
+        let url = self.build_state_changes_path(from_version, to_version, start, limit)?;
+        let response = self.get_bcs(url).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    fn build_state_changes_path(
+        &self,
+        from_version: u64,
+        to_version: Option<u64>,
+        start: Option<&str>,
+        limit: Option<u16>,
+    ) -> AptosResult<Url> {
+        let mut url = self.build_path("state/changes")?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("from_version", &from_version.to_string());
+            if let Some(to_version) = to_version {
+                query.append_pair("to_version", &to_version.to_string());
+            }
+            if let Some(start) = start {
+                query.append_pair("start", start);
+            }
+            if let Some(limit) = limit {
+                query.append_pair("limit", &limit.to_string());
+            }
+        }
+        Ok(url)
+    }
+
     pub async fn get_account_events(
         &self,
         address: AccountAddress,
@@ -1098,6 +1254,41 @@ impl Client {
         Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
     }
 
+    /// Get events of the given type emitted by any account, from version `start` onwards.
+    ///
+    /// To get the next page, pass the version after the last returned event as `start`.
+    pub async fn get_events_by_type(
+        &self,
+        event_type: &str,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> AptosResult<Response<Vec<VersionedEvent>>> {
+        let url = self.build_path(&format!("events/by_type/{}", event_type))?;
+        let mut request = self.inner.get(url);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+        self.json(response).await
+    }
+
+    pub async fn get_events_by_type_bcs(
+        &self,
+        event_type: &str,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> AptosResult<Response<Vec<EventWithVersion>>> {
+        let url = self.build_path(&format!("events/by_type/{}", event_type))?;
+
+        let response = self.get_bcs_with_page(url, start, limit).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
     pub async fn get_new_block_events_bcs(
         &self,
         start: Option<u64>,
@@ -1202,6 +1393,94 @@ impl Client {
         Ok(response.map(|inner| inner.to_vec()))
     }
 
+    pub async fn view(
+        &self,
+        request: &ViewRequest,
+        version: Option<u64>,
+    ) -> AptosResult<Response<Vec<Value>>> {
+        let url = match version {
+            Some(version) => self.build_path(&format!("view?ledger_version={}", version))?,
+            None => self.build_path("view")?,
+        };
+        let response = self.inner.post(url).json(request).send().await?;
+        self.json(response).await
+    }
+
+    pub async fn view_bcs(
+        &self,
+        request: &ViewRequest,
+        version: Option<u64>,
+    ) -> AptosResult<Response<Vec<Vec<u8>>>> {
+        let url = match version {
+            Some(version) => self.build_path(&format!("view?ledger_version={}", version))?,
+            None => self.build_path("view")?,
+        };
+        let response = self.post_bcs(url, json!(request)).await?;
+        Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
+    }
+
+    /// Streams committed transactions from `start_version`, or from the next committed
+    /// transaction if no version is given
+    ///
+    /// The stream never ends on its own; drop it to unsubscribe.
+    pub async fn stream_transactions(
+        &self,
+        start_version: Option<u64>,
+        sender: Option<AccountAddress>,
+    ) -> AptosResult<BoxStream<'static, AptosResult<Transaction>>> {
+        let mut url = self.build_path("transactions/stream")?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(start_version) = start_version {
+                query.append_pair("start_version", &start_version.to_string());
+            }
+            if let Some(sender) = sender {
+                query.append_pair("sender", &sender.to_hex_literal());
+            }
+        }
+        self.get_event_stream(url).await
+    }
+
+    /// Streams events from `start_version`, or from the next committed transaction if
+    /// no version is given, that match all of the given filters
+    ///
+    /// `start_sequence_number` skips the earlier events of the `event_key` stream, e.g. to
+    /// resume after the last event received. The stream never ends on its own; drop it to
+    /// unsubscribe.
+    pub async fn stream_events(
+        &self,
+        start_version: Option<u64>,
+        event_key: Option<EventKey>,
+        start_sequence_number: Option<u64>,
+        event_type: Option<&str>,
+        sender: Option<AccountAddress>,
+    ) -> AptosResult<BoxStream<'static, AptosResult<VersionedEvent>>> {
+        let mut url = self.build_path("events/stream")?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(start_version) = start_version {
+                query.append_pair("start_version", &start_version.to_string());
+            }
+            if let Some(event_key) = event_key {
+                query.append_pair("address", &event_key.get_creator_address().to_hex_literal());
+                query.append_pair(
+                    "creation_number",
+                    &event_key.get_creation_number().to_string(),
+                );
+            }
+            if let Some(start_sequence_number) = start_sequence_number {
+                query.append_pair("start_sequence_number", &start_sequence_number.to_string());
+            }
+            if let Some(event_type) = event_type {
+                query.append_pair("event_type", event_type);
+            }
+            if let Some(sender) = sender {
+                query.append_pair("sender", &sender.to_hex_literal());
+            }
+        }
+        self.get_event_stream(url).await
+    }
+
     pub async fn get_account(&self, address: AccountAddress) -> AptosResult<Response<Account>> {
         let url = self.build_path(&format!("accounts/{}", address))?;
         let response = self.inner.get(url).send().await?;
@@ -1304,6 +1583,27 @@ impl Client {
         self.check_and_parse_bcs_response(response).await
     }
 
+    async fn get_event_stream<T: DeserializeOwned + Send + 'static>(
+        &self,
+        url: Url,
+    ) -> AptosResult<BoxStream<'static, AptosResult<T>>> {
+        // Streams stay open indefinitely, so they can't share the client's request timeout
+        let client = ReqwestClient::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(anyhow::Error::from)?;
+        let response = client
+            .get(url)
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(parse_error(response).await);
+        }
+
+        Ok(parse_event_stream(response.bytes_stream()).boxed())
+    }
+
     async fn get_bcs_with_page(
         &self,
         url: Url,
@@ -1439,6 +1739,43 @@ async fn parse_error(response: reqwest::Response) -> RestError {
     }
 }
 
+/// Decodes a server-sent event stream in which the data of every message is a JSON encoded `T`
+fn parse_event_stream<T: DeserializeOwned + Send + 'static>(
+    bytes: impl Stream<Item = reqwest::Result<bytes::Bytes>> + Send + 'static,
+) -> impl Stream<Item = AptosResult<T>> + Send + 'static {
+    stream::unfold(
+        (bytes.boxed(), Vec::new()),
+        |(mut bytes, mut buffer)| async move {
+            loop {
+                if let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+                    let message: Vec<u8> = buffer.drain(..end + 2).collect();
+                    let message = String::from_utf8_lossy(&message);
+                    let data = message
+                        .lines()
+                        .filter_map(|line| line.strip_prefix("data:"))
+                        .map(|line| line.trim_start())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    // Messages without data, e.g. keep-alive comments, carry no item
+                    if data.is_empty() {
+                        continue;
+                    }
+                    let item = serde_json::from_str(&data).map_err(RestError::from);
+                    return Some((item, (bytes, buffer)));
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(err)) => {
+                        return Some((Err(anyhow::Error::from(err).into()), (bytes, buffer)))
+                    }
+                    None => return None,
+                }
+            }
+        },
+    )
+}
+
 pub struct GasEstimationParams {
     pub estimated_gas_used: u64,
     pub estimated_gas_price: u64,
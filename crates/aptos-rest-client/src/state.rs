@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_api_types::{
-    X_APTOS_BLOCK_HEIGHT, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_OLDEST_VERSION,
-    X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION, X_APTOS_OLDEST_BLOCK_HEIGHT,
+    X_APTOS_BLOCK_HEIGHT, X_APTOS_CHAIN_ID, X_APTOS_CURSOR, X_APTOS_EPOCH,
+    X_APTOS_LEDGER_OLDEST_VERSION, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    X_APTOS_OLDEST_BLOCK_HEIGHT,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -15,6 +16,8 @@ pub struct State {
     pub oldest_ledger_version: u64,
     pub oldest_block_height: u64,
     pub block_height: u64,
+    /// Where the next page of a paginated response starts, if there is one
+    pub cursor: Option<String>,
 }
 
 impl State {
@@ -47,6 +50,10 @@ impl State {
             .get(X_APTOS_OLDEST_BLOCK_HEIGHT)
             .and_then(|h| h.to_str().ok())
             .and_then(|s| s.parse().ok());
+        let cursor = headers
+            .get(X_APTOS_CURSOR)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string());
 
         let state = if let (
             Some(chain_id),
@@ -73,6 +80,7 @@ impl State {
                 oldest_ledger_version,
                 block_height,
                 oldest_block_height,
+                cursor,
             }
         } else {
             anyhow::bail!(
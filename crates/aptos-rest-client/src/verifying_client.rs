@@ -0,0 +1,228 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client that verifies what it reads from a fullnode.
+//!
+//! [`VerifyingClient`] keeps a [`TrustedState`], seeded from a waypoint, and only
+//! returns data that is proven against a ledger info it verified. It ratchets its
+//! trusted state forward through epoch change proofs on [`VerifyingClient::sync`].
+
+use crate::{error::RestError, Client};
+use anyhow::format_err;
+use aptos_infallible::RwLock;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::state_key::StateKey,
+    transaction::{TransactionWithProof, Version},
+    trusted_state::{TrustedState, TrustedStateChange},
+    waypoint::Waypoint,
+};
+use move_core_types::language_storage::{ResourceKey, StructTag};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+type AptosResult<T> = Result<T, RestError>;
+
+/// How far behind the verified ledger info a state snapshot may be, by default. Snapshots are
+/// taken about every 20k versions, this leaves room for the node to lag behind on them.
+pub const DEFAULT_MAX_STATE_STALENESS: Version = 100_000;
+
+/// A wrapper of [`Client`] that verifies every response against its trusted state.
+///
+/// Reads are proven against the ledger info of the last [`VerifyingClient::sync`],
+/// which happens automatically before the first read. Call `sync` again to see
+/// newer data.
+#[derive(Clone, Debug)]
+pub struct VerifyingClient {
+    inner: Client,
+    state: Arc<RwLock<VerifiedState>>,
+    /// The maximum number of versions a state snapshot may be behind the verified ledger info.
+    max_state_staleness: Version,
+}
+
+#[derive(Clone, Debug)]
+struct VerifiedState {
+    trusted_state: TrustedState,
+    /// The ledger info the trusted state was last ratcheted to, `None` before the first sync.
+    ledger_info: Option<LedgerInfoWithSignatures>,
+}
+
+impl VerifyingClient {
+    /// Creates a client that trusts the epoch change ledger info committed to by `waypoint`.
+    pub fn new(inner: Client, waypoint: Waypoint) -> Self {
+        Self::new_with_trusted_state(inner, TrustedState::from_epoch_waypoint(waypoint))
+    }
+
+    /// Creates a client resuming from a trusted state, e.g. one persisted by a previous client.
+    pub fn new_with_trusted_state(inner: Client, trusted_state: TrustedState) -> Self {
+        Self {
+            inner,
+            state: Arc::new(RwLock::new(VerifiedState {
+                trusted_state,
+                ledger_info: None,
+            })),
+            max_state_staleness: DEFAULT_MAX_STATE_STALENESS,
+        }
+    }
+
+    /// Sets how many versions behind the verified ledger info the state snapshot resources are
+    /// read from may be. Older snapshots are rejected, as a node could serve an outdated value
+    /// with a valid proof.
+    pub fn with_max_state_staleness(mut self, max_state_staleness: Version) -> Self {
+        self.max_state_staleness = max_state_staleness;
+        self
+    }
+
+    /// The underlying client, which doesn't verify anything.
+    pub fn inner(&self) -> &Client {
+        &self.inner
+    }
+
+    pub fn trusted_state(&self) -> TrustedState {
+        self.state.read().trusted_state.clone()
+    }
+
+    /// The latest verified ledger info, `None` before the first sync.
+    pub fn latest_ledger_info(&self) -> Option<LedgerInfoWithSignatures> {
+        self.state.read().ledger_info.clone()
+    }
+
+    /// Ratchets the trusted state forward to the latest ledger info of the node, verifying
+    /// every epoch change on the way, and returns that ledger info.
+    pub async fn sync(&self) -> AptosResult<LedgerInfoWithSignatures> {
+        loop {
+            let trusted_state = self.trusted_state();
+            let state_proof = self
+                .inner
+                .get_state_proof_bcs(trusted_state.version())
+                .await?
+                .into_inner();
+            let latest_li = state_proof.latest_ledger_info_w_sigs();
+
+            let (new_state, verified_li) = match trusted_state.verify_and_ratchet(&state_proof)? {
+                TrustedStateChange::Epoch {
+                    new_state,
+                    latest_epoch_change_li,
+                } => {
+                    if new_state.version() == latest_li.ledger_info().version() {
+                        (new_state, latest_li)
+                    } else {
+                        (new_state, latest_epoch_change_li)
+                    }
+                }
+                TrustedStateChange::Version { new_state } => (new_state, latest_li),
+                TrustedStateChange::NoChange => (trusted_state, latest_li),
+            };
+            self.update(new_state, verified_li.clone());
+
+            // The epoch change proof stops early if there were too many epoch changes, in
+            // which case we only got as far as the last epoch change and have to ask again.
+            if verified_li == latest_li {
+                return Ok(verified_li.clone());
+            }
+        }
+    }
+
+    /// Gets a resource, proven against the latest verified ledger info.
+    ///
+    /// Returns the version the resource was read at along with the resource, `None` if it
+    /// doesn't exist. State is only proven at state snapshots, so the version may be lower
+    /// than the one of the latest verified ledger info, but not by more than the maximum state
+    /// staleness, see [`VerifyingClient::with_max_state_staleness`].
+    pub async fn get_account_resource_bcs<T: DeserializeOwned>(
+        &self,
+        address: AccountAddress,
+        resource_type: &StructTag,
+    ) -> AptosResult<(Version, Option<T>)> {
+        let ledger_info = self.ledger_info().await?;
+        let state_value_with_proof = self
+            .inner
+            .get_account_resource_with_proof_bcs(
+                address,
+                &resource_type.to_string(),
+                Some(ledger_info.ledger_info().version()),
+            )
+            .await?
+            .into_inner();
+
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            resource_type.clone(),
+        )));
+        let ledger_version = ledger_info.ledger_info().version();
+        let snapshot_version = state_value_with_proof.version;
+        if snapshot_version > ledger_version
+            || ledger_version - snapshot_version > self.max_state_staleness
+        {
+            return Err(format_err!(
+                "State snapshot version {} is not within {} versions before verified version {}.",
+                snapshot_version,
+                self.max_state_staleness,
+                ledger_version
+            )
+            .into());
+        }
+        state_value_with_proof.verify(ledger_info.ledger_info(), &state_key)?;
+
+        let resource = state_value_with_proof
+            .value
+            .map(|value| bcs::from_bytes(value.bytes()))
+            .transpose()?;
+        Ok((snapshot_version, resource))
+    }
+
+    /// Gets a transaction and its events, proven against the latest verified ledger info.
+    pub async fn get_transaction_by_version(
+        &self,
+        version: Version,
+    ) -> AptosResult<TransactionWithProof> {
+        let ledger_info = self.ledger_info().await?;
+        let ledger_version = ledger_info.ledger_info().version();
+        if version > ledger_version {
+            return Err(format_err!(
+                "Transaction {} is after the latest verified version {}, sync first.",
+                version,
+                ledger_version
+            )
+            .into());
+        }
+
+        let txn_with_proof = self
+            .inner
+            .get_transaction_by_version_with_proof_bcs(version, Some(ledger_version))
+            .await?
+            .into_inner();
+        if txn_with_proof.version != version {
+            return Err(format_err!(
+                "Version ({}) is not expected ({}).",
+                txn_with_proof.version,
+                version
+            )
+            .into());
+        }
+        txn_with_proof.verify(ledger_info.ledger_info())?;
+
+        Ok(txn_with_proof)
+    }
+
+    /// The latest verified ledger info, syncing first if there is none yet.
+    async fn ledger_info(&self) -> AptosResult<LedgerInfoWithSignatures> {
+        match self.latest_ledger_info() {
+            Some(ledger_info) => Ok(ledger_info),
+            None => self.sync().await,
+        }
+    }
+
+    fn update(&self, trusted_state: TrustedState, ledger_info: LedgerInfoWithSignatures) {
+        let mut state = self.state.write();
+        // Another sync may have ratcheted further in the meantime, never go backwards.
+        if trusted_state.version() >= state.trusted_state.version() {
+            *state = VerifiedState {
+                trusted_state,
+                ledger_info: Some(ledger_info),
+            };
+        }
+    }
+}
@@ -6,13 +6,17 @@ use crate::{
     common::{
         check_network, get_block_index_from_request, get_timestamp, handle_request, with_context,
     },
-    error::ApiResult,
-    types::{Block, BlockIdentifier, BlockRequest, BlockResponse, Transaction},
+    error::{ApiError, ApiResult},
+    types::{
+        Block, BlockIdentifier, BlockRequest, BlockResponse, BlockTransactionRequest,
+        BlockTransactionResponse, Transaction,
+    },
     RosettaContext,
 };
 use aptos_logger::{debug, trace};
+use aptos_rest_client::aptos_api_types::TransactionData;
 use aptos_types::chain_id::ChainId;
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 use warp::Filter;
 
 pub fn block_route(
@@ -25,6 +29,16 @@ pub fn block_route(
         .and_then(handle_request(block))
 }
 
+pub fn block_transaction_route(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("block" / "transaction")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_context(server_context))
+        .and_then(handle_request(block_transaction))
+}
+
 /// Retrieves a block (in this case a single transaction) given it's identifier.
 ///
 /// Our implementation allows for by `index`, which is the ledger `version` or by
@@ -69,6 +83,59 @@ async fn block(request: BlockRequest, server_context: RosettaContext) -> ApiResu
     Ok(BlockResponse { block })
 }
 
+/// Retrieves a transaction of a block by its hash
+///
+/// [API Spec](https://www.rosetta-api.org/docs/BlockApi.html#blocktransaction)
+async fn block_transaction(
+    request: BlockTransactionRequest,
+    server_context: RosettaContext,
+) -> ApiResult<BlockTransactionResponse> {
+    debug!("/block/transaction");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "/block/transaction",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    // The block identifier must be consistent, the hash encodes the block height
+    let block_height = BlockHash::from_str(&request.block_identifier.hash)?
+        .block_height(server_context.chain_id)?;
+    if block_height != request.block_identifier.index {
+        return Err(ApiError::InvalidInput(Some(format!(
+            "Block hash {} doesn't match block index {}",
+            request.block_identifier.hash, request.block_identifier.index
+        ))));
+    }
+
+    let hash = request.transaction_identifier.hash_value()?;
+    let txn = match server_context
+        .rest_client()?
+        .get_transaction_by_hash_bcs(hash)
+        .await?
+        .into_inner()
+    {
+        TransactionData::OnChain(txn) => txn,
+        TransactionData::Pending(_) => return Err(ApiError::TransactionIsPending),
+    };
+
+    // The transaction must be in the requested block
+    let block = server_context
+        .block_cache()?
+        .get_block_by_version(txn.version)
+        .await?;
+    if block.block_height != request.block_identifier.index {
+        return Err(ApiError::TransactionNotFound(Some(format!(
+            "Transaction {} is in block {} not in block {}",
+            request.transaction_identifier.hash, block.block_height, request.block_identifier.index
+        ))));
+    }
+
+    let transaction = Transaction::from_transaction(&server_context, txn).await?;
+    Ok(BlockTransactionResponse { transaction })
+}
+
 /// Build up the transaction, which should contain the `operations` as the change set
 async fn build_block(
     server_context: &RosettaContext,
@@ -94,7 +161,7 @@ async fn build_block(
     }
 
     // Ensure the transactions are sorted in order
-    transactions.sort_by(|first, second| first.metadata.version.0.cmp(&second.metadata.version.0));
+    transactions.sort_by_key(|txn| txn.metadata.as_ref().map(|metadata| metadata.version.0));
 
     Ok(Block {
         block_identifier,
@@ -191,6 +258,18 @@ impl BlockRetriever {
         Ok(BlockInfo::from_block(&block, chain_id))
     }
 
+    /// Retrieves the block containing the version, without its transactions
+    pub async fn get_block_by_version(
+        &self,
+        version: u64,
+    ) -> ApiResult<aptos_rest_client::aptos_api_types::BcsBlock> {
+        Ok(self
+            .rest_client
+            .get_block_by_version_bcs(version, false)
+            .await?
+            .into_inner())
+    }
+
     pub async fn get_block_by_height(
         &self,
         height: u64,
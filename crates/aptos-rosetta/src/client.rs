@@ -4,14 +4,17 @@
 use crate::common::native_coin;
 use crate::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, BlockRequest, BlockResponse,
-    ConstructionCombineRequest, ConstructionCombineResponse, ConstructionDeriveRequest,
-    ConstructionDeriveResponse, ConstructionHashRequest, ConstructionMetadata,
-    ConstructionMetadataRequest, ConstructionMetadataResponse, ConstructionParseRequest,
-    ConstructionParseResponse, ConstructionPayloadsRequest, ConstructionPayloadsResponse,
-    ConstructionPreprocessRequest, ConstructionPreprocessResponse, ConstructionSubmitRequest,
-    ConstructionSubmitResponse, Error, MetadataRequest, NetworkIdentifier, NetworkListResponse,
-    NetworkOptionsResponse, NetworkRequest, NetworkStatusResponse, Operation, PreprocessMetadata,
-    PublicKey, Signature, SignatureType, TransactionIdentifier, TransactionIdentifierResponse,
+    BlockTransactionRequest, BlockTransactionResponse, ConstructionCombineRequest,
+    ConstructionCombineResponse, ConstructionDeriveRequest, ConstructionDeriveResponse,
+    ConstructionHashRequest, ConstructionMetadata, ConstructionMetadataRequest,
+    ConstructionMetadataResponse, ConstructionParseRequest, ConstructionParseResponse,
+    ConstructionPayloadsRequest, ConstructionPayloadsResponse, ConstructionPreprocessRequest,
+    ConstructionPreprocessResponse, ConstructionSubmitRequest, ConstructionSubmitResponse, Error,
+    EventsBlocksRequest, EventsBlocksResponse, MempoolRequest, MempoolResponse,
+    MempoolTransactionRequest, MempoolTransactionResponse, MetadataRequest, NetworkIdentifier,
+    NetworkListResponse, NetworkOptionsResponse, NetworkRequest, NetworkStatusResponse, Operation,
+    PreprocessMetadata, PublicKey, SearchTransactionsRequest, SearchTransactionsResponse,
+    Signature, SignatureType, TransactionIdentifier, TransactionIdentifierResponse,
 };
 use anyhow::anyhow;
 use aptos_crypto::ed25519::Ed25519PrivateKey;
@@ -54,6 +57,13 @@ impl RosettaClient {
         self.make_call("block", request).await
     }
 
+    pub async fn block_transaction(
+        &self,
+        request: &BlockTransactionRequest,
+    ) -> anyhow::Result<BlockTransactionResponse> {
+        self.make_call("block/transaction", request).await
+    }
+
     pub async fn combine(
         &self,
         request: &ConstructionCombineRequest,
@@ -110,6 +120,24 @@ impl RosettaClient {
         self.make_call("construction/submit", request).await
     }
 
+    pub async fn events_blocks(
+        &self,
+        request: &EventsBlocksRequest,
+    ) -> anyhow::Result<EventsBlocksResponse> {
+        self.make_call("events/blocks", request).await
+    }
+
+    pub async fn mempool(&self, request: &MempoolRequest) -> anyhow::Result<MempoolResponse> {
+        self.make_call("mempool", request).await
+    }
+
+    pub async fn mempool_transaction(
+        &self,
+        request: &MempoolTransactionRequest,
+    ) -> anyhow::Result<MempoolTransactionResponse> {
+        self.make_call("mempool/transaction", request).await
+    }
+
     pub async fn network_list(&self) -> anyhow::Result<NetworkListResponse> {
         self.make_call("network/list", &MetadataRequest {}).await
     }
@@ -128,6 +156,13 @@ impl RosettaClient {
         self.make_call("network/status", request).await
     }
 
+    pub async fn search_transactions(
+        &self,
+        request: &SearchTransactionsRequest,
+    ) -> anyhow::Result<SearchTransactionsResponse> {
+        self.make_call("search/transactions", request).await
+    }
+
     async fn make_call<'a, I: Serialize + Debug, O: DeserializeOwned>(
         &'a self,
         path: &'static str,
@@ -1046,7 +1046,11 @@ async fn construction_submit(
 
     let txn: SignedTransaction = decode_bcs(&request.signed_transaction, "SignedTransaction")?;
     let hash = txn.clone().committed_hash();
+    let expiration_timestamp_secs = txn.expiration_timestamp_secs();
     rest_client.submit_bcs(&txn).await?;
+    server_context
+        .submitted_transactions
+        .insert(hash, expiration_timestamp_secs);
     Ok(ConstructionSubmitResponse {
         transaction_identifier: hash.into(),
     })
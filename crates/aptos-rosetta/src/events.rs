@@ -0,0 +1,79 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Events API
+//!
+//! See: [Events API Spec](https://www.rosetta-api.org/docs/EventsApi.html)
+
+use crate::{
+    common::{check_network, handle_request, with_context, BlockHash},
+    error::ApiResult,
+    types::{
+        BlockEvent, BlockEventType, BlockIdentifier, EventsBlocksRequest, EventsBlocksResponse,
+    },
+    RosettaContext,
+};
+use aptos_logger::{debug, trace};
+use warp::Filter;
+
+/// Number of events returned when the request has no limit
+const DEFAULT_EVENTS_LIMIT: u64 = 100;
+
+pub fn events_blocks_route(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("events" / "blocks")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_context(server_context))
+        .and_then(handle_request(events_blocks))
+}
+
+/// Lists the blocks added to the chain, starting at `offset`
+///
+/// Aptos has no reorgs, so there is one `block_added` event per block, and its sequence is the
+/// block height.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/EventsApi.html#eventsblocks)
+async fn events_blocks(
+    request: EventsBlocksRequest,
+    server_context: RosettaContext,
+) -> ApiResult<EventsBlocksResponse> {
+    debug!("/events/blocks");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "/events/blocks",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let response = server_context
+        .rest_client()?
+        .get_ledger_information()
+        .await?;
+    let state = response.state();
+    let max_sequence = state.block_height;
+
+    // Blocks before the oldest block are pruned, and can't be retrieved anymore
+    let start = request.offset.unwrap_or(0).max(state.oldest_block_height);
+    let limit = request.limit.unwrap_or(DEFAULT_EVENTS_LIMIT);
+    let end = start
+        .saturating_add(limit)
+        .min(max_sequence.saturating_add(1));
+    let events = (start..end)
+        .map(|block_height| BlockEvent {
+            sequence: block_height,
+            block_identifier: BlockIdentifier {
+                index: block_height,
+                hash: BlockHash::new(server_context.chain_id, block_height).to_string(),
+            },
+            event_type: BlockEventType::BlockAdded,
+        })
+        .collect();
+
+    Ok(EventsBlocksResponse {
+        max_sequence,
+        events,
+    })
+}
@@ -10,6 +10,7 @@ use crate::{
     block::BlockRetriever,
     common::{handle_request, with_context},
     error::{ApiError, ApiResult},
+    mempool::SubmittedTransactions,
 };
 use aptos_config::config::ApiConfig;
 use aptos_logger::{debug, warn};
@@ -32,7 +33,10 @@ use warp::{
 mod account;
 mod block;
 mod construction;
+mod events;
+mod mempool;
 mod network;
+mod search;
 
 pub mod client;
 pub mod common;
@@ -53,6 +57,8 @@ pub struct RosettaContext {
     pub block_cache: Option<Arc<BlockRetriever>>,
     pub owner_addresses: Vec<AccountAddress>,
     pub pool_address_to_owner: BTreeMap<AccountAddress, AccountAddress>,
+    /// Transactions submitted through this server, for `/mempool`
+    submitted_transactions: Arc<SubmittedTransactions>,
 }
 
 impl RosettaContext {
@@ -94,6 +100,7 @@ impl RosettaContext {
             block_cache,
             owner_addresses,
             pool_address_to_owner,
+            submitted_transactions: Arc::new(SubmittedTransactions::default()),
         }
     }
 
@@ -189,6 +196,7 @@ pub fn routes(
 ) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
     account::routes(context.clone())
         .or(block::block_route(context.clone()))
+        .or(block::block_transaction_route(context.clone()))
         .or(construction::combine_route(context.clone()))
         .or(construction::derive_route(context.clone()))
         .or(construction::hash_route(context.clone()))
@@ -197,9 +205,13 @@ pub fn routes(
         .or(construction::payloads_route(context.clone()))
         .or(construction::preprocess_route(context.clone()))
         .or(construction::submit_route(context.clone()))
+        .or(events::events_blocks_route(context.clone()))
+        .or(mempool::mempool_route(context.clone()))
+        .or(mempool::mempool_transaction_route(context.clone()))
         .or(network::list_route(context.clone()))
         .or(network::options_route(context.clone()))
         .or(network::status_route(context.clone()))
+        .or(search::search_transactions_route(context.clone()))
         .or(health_check_route(context))
         .with(
             warp::cors()
@@ -0,0 +1,189 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Mempool API
+//!
+//! See: [Mempool API Spec](https://www.rosetta-api.org/docs/MempoolApi.html)
+
+use crate::{
+    common::{check_network, handle_request, with_context},
+    error::{ApiError, ApiResult},
+    types::{
+        MempoolRequest, MempoolResponse, MempoolTransactionRequest, MempoolTransactionResponse,
+        Transaction,
+    },
+    RosettaContext,
+};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::{debug, trace};
+use aptos_rest_client::aptos_api_types::TransactionData;
+use futures::{stream, StreamExt};
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use warp::Filter;
+
+/// Maximum number of submitted transactions tracked for `/mempool`
+const MAX_SUBMITTED_TRANSACTIONS: usize = 1_000;
+
+/// Maximum number of concurrent transaction lookups for a single `/mempool` request
+const MAX_CONCURRENT_LOOKUPS: usize = 10;
+
+/// Transactions submitted through `/construction/submit` that may still be in mempool
+///
+/// The REST API doesn't expose the contents of mempool, so `/mempool` can only list the
+/// transactions that went through this server.  Entries are keyed by hash, and hold the
+/// expiration timestamp (in seconds) after which the transaction can't be in mempool anymore.
+#[derive(Debug, Default)]
+pub struct SubmittedTransactions {
+    transactions: Mutex<BTreeMap<HashValue, u64>>,
+}
+
+impl SubmittedTransactions {
+    /// Tracks a submitted transaction, evicting the one expiring first if full
+    pub fn insert(&self, hash: HashValue, expiration_timestamp_secs: u64) {
+        let mut transactions = self.transactions.lock();
+        Self::prune_expired(&mut transactions, now_secs());
+        if transactions.len() >= MAX_SUBMITTED_TRANSACTIONS {
+            if let Some(first_expiring) = transactions
+                .iter()
+                .min_by_key(|(_, expiration)| **expiration)
+                .map(|(hash, _)| *hash)
+            {
+                transactions.remove(&first_expiring);
+            }
+        }
+        transactions.insert(hash, expiration_timestamp_secs);
+    }
+
+    /// Removes a transaction that's not in mempool anymore
+    pub fn remove(&self, hash: &HashValue) {
+        self.transactions.lock().remove(hash);
+    }
+
+    /// Returns the hashes of all transactions that haven't expired yet
+    pub fn hashes(&self) -> Vec<HashValue> {
+        let mut transactions = self.transactions.lock();
+        Self::prune_expired(&mut transactions, now_secs());
+        transactions.keys().copied().collect()
+    }
+
+    fn prune_expired(transactions: &mut BTreeMap<HashValue, u64>, now_secs: u64) {
+        transactions.retain(|_, expiration| *expiration > now_secs);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+pub fn mempool_route(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("mempool")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_context(server_context))
+        .and_then(handle_request(mempool))
+}
+
+pub fn mempool_transaction_route(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("mempool" / "transaction")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_context(server_context))
+        .and_then(handle_request(mempool_transaction))
+}
+
+/// Lists the transactions in mempool
+///
+/// The REST API doesn't expose the contents of mempool, so this only lists the transactions
+/// submitted through this server that are still pending.  Other pending transactions can still
+/// be looked up by hash with `/mempool/transaction`.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempool)
+async fn mempool(
+    request: MempoolRequest,
+    server_context: RosettaContext,
+) -> ApiResult<MempoolResponse> {
+    debug!("/mempool");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "/mempool",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+    let rest_client = server_context.rest_client()?;
+
+    let mut lookups = stream::iter(server_context.submitted_transactions.hashes())
+        .map(|hash| {
+            let rest_client = rest_client.clone();
+            async move { (hash, rest_client.get_transaction_by_hash_bcs(hash).await) }
+        })
+        .buffer_unordered(MAX_CONCURRENT_LOOKUPS);
+
+    let mut transaction_identifiers = vec![];
+    while let Some((hash, result)) = lookups.next().await {
+        match result {
+            Ok(response) => match response.into_inner() {
+                TransactionData::Pending(_) => transaction_identifiers.push(hash.into()),
+                TransactionData::OnChain(_) => server_context.submitted_transactions.remove(&hash),
+            },
+            Err(err) => match ApiError::from(err) {
+                // Dropped from mempool e.g. it was rejected, it's not coming back
+                ApiError::TransactionNotFound(_) => {
+                    server_context.submitted_transactions.remove(&hash)
+                }
+                err => return Err(err),
+            },
+        }
+    }
+
+    Ok(MempoolResponse {
+        transaction_identifiers,
+    })
+}
+
+/// Retrieves a transaction in mempool by hash
+///
+/// Committed transactions are not in mempool anymore, and must be retrieved with
+/// `/block/transaction` or `/search/transactions`.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempooltransaction)
+async fn mempool_transaction(
+    request: MempoolTransactionRequest,
+    server_context: RosettaContext,
+) -> ApiResult<MempoolTransactionResponse> {
+    debug!("/mempool/transaction");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "/mempool/transaction",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let hash = request.transaction_identifier.hash_value()?;
+    match server_context
+        .rest_client()?
+        .get_transaction_by_hash_bcs(hash)
+        .await?
+        .into_inner()
+    {
+        TransactionData::Pending(txn) => Ok(MempoolTransactionResponse {
+            transaction: Transaction::from_pending_transaction(*txn),
+        }),
+        TransactionData::OnChain(_) => Err(ApiError::TransactionNotFound(Some(format!(
+            "Transaction {} is already committed",
+            request.transaction_identifier.hash
+        )))),
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Search API
+//!
+//! See: [Search API Spec](https://www.rosetta-api.org/docs/SearchApi.html)
+
+use crate::{
+    common::{check_network, handle_request, with_context},
+    error::{ApiError, ApiResult},
+    types::{
+        BlockIdentifier, BlockTransaction, SearchTransactionsRequest, SearchTransactionsResponse,
+        Transaction,
+    },
+    RosettaContext,
+};
+use aptos_logger::{debug, trace};
+use aptos_rest_client::aptos_api_types::{TransactionData, TransactionOnChainData};
+use aptos_types::{account_address::AccountAddress, transaction::Transaction as AptosTransaction};
+use warp::Filter;
+
+/// Number of transactions returned when the request has no limit
+const DEFAULT_SEARCH_LIMIT: u64 = 25;
+
+pub fn search_transactions_route(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("search" / "transactions")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_context(server_context))
+        .and_then(handle_request(search_transactions))
+}
+
+/// Searches for committed transactions by hash or by sender
+///
+/// Lookups by account are backed by the account transactions of the REST API, so only the
+/// transactions sent by the account are returned, and offsets are sequence numbers.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/SearchApi.html#searchtransactions)
+async fn search_transactions(
+    request: SearchTransactionsRequest,
+    server_context: RosettaContext,
+) -> ApiResult<SearchTransactionsResponse> {
+    debug!("/search/transactions");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "/search/transactions",
+    );
+
+    check_network(request.network_identifier.clone(), &server_context)?;
+    if let Some(ref operator) = request.operator {
+        if operator != "and" {
+            return Err(ApiError::InvalidInput(Some(format!(
+                "Unsupported search operator {}",
+                operator
+            ))));
+        }
+    }
+    let sender = request
+        .account_identifier
+        .as_ref()
+        .map(|account| {
+            if account.is_base_account() {
+                account.account_address()
+            } else {
+                Err(ApiError::InvalidInput(Some(
+                    "Search by sub account is not supported".to_string(),
+                )))
+            }
+        })
+        .transpose()?;
+
+    let rest_client = server_context.rest_client()?;
+    let (txns, total_count, next_offset) =
+        if let Some(ref transaction_identifier) = request.transaction_identifier {
+            let hash = transaction_identifier.hash_value()?;
+            let txns = match rest_client
+                .get_transaction_by_hash_bcs(hash)
+                .await?
+                .into_inner()
+            {
+                TransactionData::OnChain(txn)
+                    if sender.map_or(true, |sender| txn_sender(&txn) == Some(sender)) =>
+                {
+                    vec![txn]
+                }
+                _ => vec![],
+            };
+            let total_count = txns.len() as u64;
+            (txns, total_count, None)
+        } else if let Some(sender) = sender {
+            let offset = request.offset.unwrap_or(0);
+            let limit = request
+                .limit
+                .unwrap_or(DEFAULT_SEARCH_LIMIT)
+                .min(u16::MAX as u64) as u16;
+            let total_count = rest_client
+                .get_account_bcs(sender)
+                .await?
+                .into_inner()
+                .sequence_number();
+            let txns = if offset < total_count {
+                rest_client
+                    .get_account_transactions_bcs(sender, Some(offset), Some(limit))
+                    .await?
+                    .into_inner()
+            } else {
+                vec![]
+            };
+            let next_offset = offset + txns.len() as u64;
+            let next_offset = if !txns.is_empty() && next_offset < total_count {
+                Some(next_offset)
+            } else {
+                None
+            };
+            (txns, total_count, next_offset)
+        } else {
+            return Err(ApiError::InvalidInput(Some(
+                "Search requires a transaction_identifier or an account_identifier".to_string(),
+            )));
+        };
+
+    let block_cache = server_context.block_cache()?;
+    let mut transactions = vec![];
+    for txn in txns {
+        if let Some(success) = request.success {
+            if txn.info.status().is_success() != success {
+                continue;
+            }
+        }
+        let block = block_cache.get_block_by_version(txn.version).await?;
+        if let Some(max_block) = request.max_block {
+            if block.block_height > max_block {
+                continue;
+            }
+        }
+        transactions.push(BlockTransaction {
+            block_identifier: BlockIdentifier::from_block(&block, server_context.chain_id),
+            transaction: Transaction::from_transaction(&server_context, txn).await?,
+        });
+    }
+
+    Ok(SearchTransactionsResponse {
+        transactions,
+        total_count,
+        next_offset,
+    })
+}
+
+fn txn_sender(txn: &TransactionOnChainData) -> Option<AccountAddress> {
+    match &txn.transaction {
+        AptosTransaction::UserTransaction(user_txn) => Some(user_txn.sender()),
+        _ => None,
+    }
+}
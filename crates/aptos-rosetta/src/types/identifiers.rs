@@ -7,7 +7,7 @@
 
 use crate::common::BlockHash;
 use crate::{
-    common::{to_hex_lower, BLOCKCHAIN},
+    common::{strip_hex_prefix, to_hex_lower, BLOCKCHAIN},
     error::{ApiError, ApiResult},
 };
 use aptos_types::transaction::TransactionInfo;
@@ -258,6 +258,14 @@ pub struct TransactionIdentifier {
     pub hash: String,
 }
 
+impl TransactionIdentifier {
+    /// Convert [`TransactionIdentifier`] to a [`aptos_crypto::HashValue`]
+    pub fn hash_value(&self) -> ApiResult<aptos_crypto::HashValue> {
+        aptos_crypto::HashValue::from_hex(strip_hex_prefix(&self.hash))
+            .map_err(|_| ApiError::InvalidInput(Some("Invalid transaction hash".to_string())))
+    }
+}
+
 impl From<&TransactionInfo> for TransactionIdentifier {
     fn from(txn: &TransactionInfo) -> Self {
         TransactionIdentifier {
@@ -26,7 +26,7 @@ use aptos_types::account_config::{AccountResource, CoinStoreResource, WithdrawEv
 use aptos_types::contract_event::ContractEvent;
 use aptos_types::stake_pool::{SetOperatorEvent, StakePool};
 use aptos_types::state_store::state_key::StateKey;
-use aptos_types::transaction::{EntryFunction, TransactionPayload};
+use aptos_types::transaction::{EntryFunction, SignedTransaction, TransactionPayload};
 use aptos_types::write_set::{WriteOp, WriteSet};
 use aptos_types::{account_address::AccountAddress, event::EventKey};
 use cached_packages::aptos_stdlib;
@@ -116,9 +116,31 @@ pub struct Block {
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct BlockTransaction {
     /// Block associated with transaction
-    block_identifier: BlockIdentifier,
+    pub block_identifier: BlockIdentifier,
     /// Transaction associated with block
-    transaction: Transaction,
+    pub transaction: Transaction,
+}
+
+/// An event of the canonical chain, for following blocks without polling them one by one.
+/// Aptos has no reorgs, so blocks are only ever added
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/BlockEvent.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockEvent {
+    /// Sequence of the event, which is the block height
+    pub sequence: u64,
+    /// Block added or removed
+    pub block_identifier: BlockIdentifier,
+    #[serde(rename = "type")]
+    pub event_type: BlockEventType,
+}
+
+/// [API Spec](https://www.rosetta-api.org/docs/models/BlockEventType.html)
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockEventType {
+    BlockAdded,
+    BlockRemoved,
 }
 
 /// Currency represented as atomic units including decimals
@@ -526,7 +548,9 @@ pub struct Transaction {
     pub transaction_identifier: TransactionIdentifier,
     /// Individual operations (write set changes) in a transaction
     pub operations: Vec<Operation>,
-    pub metadata: TransactionMetadata,
+    /// Only populated for committed transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<TransactionMetadata>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -628,14 +652,30 @@ impl Transaction {
         Ok(Transaction {
             transaction_identifier: (&txn_info).into(),
             operations,
-            metadata: TransactionMetadata {
+            metadata: Some(TransactionMetadata {
                 transaction_type: txn_type,
                 version: txn.version.into(),
                 failed: !successful,
                 vm_status: format!("{:?}", txn_info.status()),
-            },
+            }),
         })
     }
+
+    /// Builds a transaction that is still in mempool.  There are no state changes yet, so the
+    /// operations are parsed from the payload and have no status
+    pub fn from_pending_transaction(txn: SignedTransaction) -> Transaction {
+        let mut operations =
+            parse_failed_operations_from_txn_payload(0, txn.sender(), txn.payload());
+        for operation in operations.iter_mut() {
+            operation.status = None;
+        }
+
+        Transaction {
+            transaction_identifier: txn.committed_hash().into(),
+            operations,
+            metadata: None,
+        }
+    }
 }
 
 /// Parses operations from the transaction payload
@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::types::{
-    AccountIdentifier, Allow, Amount, Block, BlockIdentifier, Currency, InternalOperation,
-    NetworkIdentifier, Operation, PartialBlockIdentifier, Peer, PublicKey, Signature,
-    SigningPayload, SyncStatus, Transaction, TransactionIdentifier, Version,
+    AccountIdentifier, Allow, Amount, Block, BlockEvent, BlockIdentifier, BlockTransaction,
+    Currency, InternalOperation, NetworkIdentifier, Operation, PartialBlockIdentifier, Peer,
+    PublicKey, Signature, SigningPayload, SyncStatus, Transaction, TransactionIdentifier, Version,
 };
 use crate::{AccountAddress, ApiError};
 use aptos_rest_client::aptos_api_types::U64;
@@ -112,6 +112,27 @@ pub struct BlockResponse {
     pub block: Block,
 }
 
+/// Request for a transaction in a block, for transactions left out of the [`BlockResponse`]
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/BlockTransactionRequest.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockTransactionRequest {
+    /// Network identifier describing the blockchain and the chain id
+    pub network_identifier: NetworkIdentifier,
+    /// Block containing the transaction
+    pub block_identifier: BlockIdentifier,
+    /// Hash of the transaction
+    pub transaction_identifier: TransactionIdentifier,
+}
+
+/// Response with the transaction in the block
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/BlockTransactionResponse.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockTransactionResponse {
+    pub transaction: Transaction,
+}
+
 /// Request to combine signatures and an unsigned transaction for submission as a
 /// [`aptos_types::transaction::SignedTransaction`]
 ///
@@ -466,6 +487,32 @@ pub struct ConstructionSubmitResponse {
     pub transaction_identifier: TransactionIdentifier,
 }
 
+/// Request for the block events of the chain, starting at `offset`
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/EventsBlocksRequest.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventsBlocksRequest {
+    /// Network identifier describing the blockchain and the chain id
+    pub network_identifier: NetworkIdentifier,
+    /// Sequence of the first event to return, from the first event if not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Maximum number of events to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+/// Response of block events in sequence order
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/EventsBlocksResponse.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventsBlocksResponse {
+    /// Sequence of the latest event, which may not be in the returned events
+    pub max_sequence: u64,
+    /// Events in sequence order
+    pub events: Vec<BlockEvent>,
+}
+
 /// Request for all transactions in mempool
 ///
 /// [API Spec](https://www.rosetta-api.org/docs/models/MempoolRequest.html)
@@ -558,6 +605,89 @@ pub struct NetworkStatusResponse {
     pub peers: Vec<Peer>,
 }
 
+/// Request to search for committed transactions
+///
+/// Only lookups by `transaction_identifier` and `account_identifier` are supported, and
+/// conditions are always combined with `and`
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/SearchTransactionsRequest.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SearchTransactionsRequest {
+    /// Network identifier describing the blockchain and the chain id
+    pub network_identifier: NetworkIdentifier,
+    /// How conditions are combined, `and` or `or`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    /// Only transactions in this block or before are returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<u64>,
+    /// Offset into the results, from the `next_offset` of a previous response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Maximum number of transactions to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    /// Lookup a transaction by hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_identifier: Option<TransactionIdentifier>,
+    /// Lookup transactions sent by an account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_identifier: Option<AccountIdentifier>,
+    /// Only return successful, or failed transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+}
+
+impl SearchTransactionsRequest {
+    fn new(chain_id: ChainId) -> Self {
+        Self {
+            network_identifier: chain_id.into(),
+            operator: None,
+            max_block: None,
+            offset: None,
+            limit: None,
+            transaction_identifier: None,
+            account_identifier: None,
+            success: None,
+        }
+    }
+
+    pub fn by_hash(chain_id: ChainId, transaction_identifier: TransactionIdentifier) -> Self {
+        Self {
+            transaction_identifier: Some(transaction_identifier),
+            ..Self::new(chain_id)
+        }
+    }
+
+    pub fn by_account(
+        chain_id: ChainId,
+        account_identifier: AccountIdentifier,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Self {
+        Self {
+            account_identifier: Some(account_identifier),
+            offset,
+            limit,
+            ..Self::new(chain_id)
+        }
+    }
+}
+
+/// Response with the matching transactions and the blocks they are in
+///
+/// [API Spec](https://www.rosetta-api.org/docs/models/SearchTransactionsResponse.html)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SearchTransactionsResponse {
+    /// Matching transactions, in order
+    pub transactions: Vec<BlockTransaction>,
+    /// Number of transactions the search could return, over all pages
+    pub total_count: u64,
+    /// Offset of the next page, not present on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u64>,
+}
+
 /// Response with a transaction that was hashed or submitted
 ///
 /// [API Spec](https://www.rosetta-api.org/docs/models/TransactionIdentifierResponse.html)
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliTypedResult, TransactionOptions, TransactionSummary};
+use crate::common::types::{
+    CliCommand, CliTypedResult, OutputUnsignedOptions, SubmitResult, TransactionOptions,
+    TransactionSummary,
+};
 use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
 use cached_packages::aptos_stdlib;
@@ -24,19 +27,24 @@ pub struct CreateAccount {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateAccount {
+impl CliCommand<SubmitResult<TransactionSummary>> for CreateAccount {
     fn command_name(&self) -> &'static str {
         "CreateAccount"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let address = self.account;
-        self.txn_options
-            .submit_transaction(aptos_stdlib::aptos_account_create_account(address))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::aptos_account_create_account(address),
+            )
             .await
-            .map(TransactionSummary::from)
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliTypedResult, TransactionOptions, TransactionSummary};
+use crate::common::types::{
+    CliCommand, CliTypedResult, OutputUnsignedOptions, SubmitResult, TransactionOptions,
+    TransactionSummary,
+};
 use aptos_rest_client::{
     aptos_api_types::{WriteResource, WriteSetChange},
     Transaction,
@@ -32,6 +35,8 @@ pub struct CreateResourceAccount {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 /// A shortened create resource account output
@@ -71,23 +76,26 @@ impl From<Transaction> for CreateResourceAccountSummary {
 }
 
 #[async_trait]
-impl CliCommand<CreateResourceAccountSummary> for CreateResourceAccount {
+impl CliCommand<SubmitResult<CreateResourceAccountSummary>> for CreateResourceAccount {
     fn command_name(&self) -> &'static str {
         "CreateResourceAccount"
     }
 
-    async fn execute(self) -> CliTypedResult<CreateResourceAccountSummary> {
+    async fn execute(self) -> CliTypedResult<SubmitResult<CreateResourceAccountSummary>> {
         let authentication_key: Vec<u8> = if let Some(key) = self.authentication_key {
             bcs::to_bytes(&key)?
         } else {
             vec![]
         };
-        self.txn_options
-            .submit_transaction(resource_account_create_resource_account(
-                bcs::to_bytes(&self.seed)?,
-                authentication_key,
-            ))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                resource_account_create_resource_account(
+                    bcs::to_bytes(&self.seed)?,
+                    authentication_key,
+                ),
+            )
             .await
-            .map(CreateResourceAccountSummary::from)
+            .map(|result| result.map(CreateResourceAccountSummary::from))
     }
 }
@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliTypedResult, TransactionOptions};
+use crate::common::types::{
+    CliCommand, CliTypedResult, OutputUnsignedOptions, SubmitResult, TransactionOptions,
+};
 use aptos_rest_client::aptos_api_types::HashValue;
 use aptos_rest_client::{
     aptos_api_types::{WriteResource, WriteSetChange},
@@ -30,22 +32,25 @@ pub struct TransferCoins {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransferSummary> for TransferCoins {
+impl CliCommand<SubmitResult<TransferSummary>> for TransferCoins {
     fn command_name(&self) -> &'static str {
         "TransferCoins"
     }
 
-    async fn execute(self) -> CliTypedResult<TransferSummary> {
-        self.txn_options
-            .submit_transaction(aptos_stdlib::aptos_account_transfer(
-                self.account,
-                self.amount,
-            ))
+    async fn execute(self) -> CliTypedResult<SubmitResult<TransferSummary>> {
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::aptos_account_transfer(self.account, self.amount),
+            )
             .await
-            .map(TransferSummary::from)
+            .map(|result| result.map(TransferSummary::from))
     }
 }
 
@@ -19,12 +19,14 @@ use aptos_crypto::{
 };
 use aptos_global_constants::adjust_gas_headroom;
 use aptos_keygen::KeyGen;
-use aptos_rest_client::aptos_api_types::{ExplainVMStatus, HashValue, UserTransaction};
+use aptos_rest_client::aptos_api_types::{
+    ExplainVMStatus, GasProfile, GasProfileEntry, HashValue, UserTransaction,
+};
 use aptos_rest_client::error::RestError;
 use aptos_rest_client::{Client, Transaction};
 use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_types::transaction::{
-    authenticator::AuthenticationKey, SignedTransaction, TransactionPayload,
+    authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionPayload,
 };
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
@@ -46,6 +48,9 @@ use std::{
 use thiserror::Error;
 
 const MAX_POSSIBLE_GAS_UNITS: u64 = 1_000_000;
+const DEFAULT_UNSIGNED_EXPIRATION_SECS: u64 = 3600;
+const GAS_PROFILE_FOLDED_STACKS_FILE: &str = "gas-profile.folded";
+const GAS_PROFILE_FLAMEGRAPH_FILE: &str = "gas-profile.svg";
 pub const DEFAULT_PROFILE: &str = "default";
 
 /// A common result to be returned to users
@@ -1383,6 +1388,55 @@ impl TransactionOptions {
         Ok(response.into_inner())
     }
 
+    /// Builds a transaction to be signed offline, without requiring the private key
+    ///
+    /// Unless `--max-gas` is given, the transaction can't be simulated, so it uses the default
+    /// maximum gas amount.
+    pub async fn build_unsigned_transaction(
+        &self,
+        payload: TransactionPayload,
+        unsigned_options: &UnsignedTransactionOptions,
+    ) -> CliTypedResult<RawTransaction> {
+        let client = self.rest_client()?;
+        let sender_address = if let Some(sender_address) = self.sender_account {
+            sender_address
+        } else {
+            self.profile_options.account_address().map_err(|_| {
+                CliError::CommandArgumentError(
+                    "--sender-account must be set if the profile has no account".to_string(),
+                )
+            })?
+        };
+
+        let sequence_number = if let Some(sequence_number) = unsigned_options.sequence_number {
+            sequence_number
+        } else {
+            get_sequence_number(&client, sender_address).await?
+        };
+        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            gas_unit_price
+        } else {
+            self.estimate_gas_price().await?
+        };
+
+        let mut transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+            .with_gas_unit_price(gas_unit_price)
+            .with_transaction_expiration_time(
+                unsigned_options
+                    .expiration_secs
+                    .unwrap_or(DEFAULT_UNSIGNED_EXPIRATION_SECS),
+            );
+        if let Some(max_gas) = self.gas_options.max_gas {
+            transaction_factory = transaction_factory.with_max_gas_amount(max_gas);
+        }
+
+        Ok(transaction_factory
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .build())
+    }
+
     pub async fn simulate_transaction(
         &self,
         payload: TransactionPayload,
@@ -1390,10 +1444,72 @@ impl TransactionOptions {
         amount_transfer: Option<u64>,
     ) -> CliTypedResult<UserTransaction> {
         let client = self.rest_client()?;
+        let signed_transaction = self
+            .build_simulated_transaction(&client, payload, gas_price, amount_transfer)
+            .await?;
+        let txns = client.simulate(&signed_transaction).await?.into_inner();
+        Ok(txns.first().unwrap().clone())
+    }
+
+    /// Simulates the transaction, and writes the gas profile of its call stacks to files
+    ///
+    /// The call stacks are written in the folded format, for flamegraph tools, and as a
+    /// flamegraph.
+    pub async fn profile_gas(
+        &self,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<GasProfileSummary> {
+        let title = match &payload {
+            TransactionPayload::EntryFunction(entry_function) => format!(
+                "Gas profile of {}::{}",
+                entry_function.module().short_str_lossless(),
+                entry_function.function()
+            ),
+            _ => "Gas profile".to_string(),
+        };
+        let client = self.rest_client()?;
+        let signed_transaction = self
+            .build_simulated_transaction(&client, payload, self.gas_options.gas_unit_price, None)
+            .await?;
+        let gas_profile = client
+            .simulate_gas_profile(&signed_transaction)
+            .await?
+            .into_inner();
+
+        let folded_stacks_file = PathBuf::from(GAS_PROFILE_FOLDED_STACKS_FILE);
+        let flamegraph_file = PathBuf::from(GAS_PROFILE_FLAMEGRAPH_FILE);
+        check_if_file_exists(&folded_stacks_file, self.prompt_options)?;
+        check_if_file_exists(&flamegraph_file, self.prompt_options)?;
+        write_to_file(
+            &folded_stacks_file,
+            "Gas profile call stacks",
+            gas_profile.to_folded_stacks().as_bytes(),
+        )?;
+        write_to_file(
+            &flamegraph_file,
+            "Gas profile flamegraph",
+            gas_profile.to_flamegraph(&title).as_bytes(),
+        )?;
+
+        Ok(GasProfileSummary::new(
+            gas_profile,
+            folded_stacks_file,
+            flamegraph_file,
+        ))
+    }
+
+    /// Builds a transaction to simulate, with as much gas as the sender can afford
+    async fn build_simulated_transaction(
+        &self,
+        client: &Client,
+        payload: TransactionPayload,
+        gas_price: Option<u64>,
+        amount_transfer: Option<u64>,
+    ) -> CliTypedResult<SignedTransaction> {
         let (sender_key, sender_address) = self.get_key_and_address()?;
 
         // Get sequence number for account
-        let sequence_number = get_sequence_number(&client, sender_address).await?;
+        let sequence_number = get_sequence_number(client, sender_address).await?;
 
         // Estimate gas price if necessary
         let gas_price = if let Some(gas_price) = gas_price {
@@ -1427,7 +1543,7 @@ impl TransactionOptions {
             )
         };
 
-        let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+        let transaction_factory = TransactionFactory::new(chain_id(client).await?)
             .with_gas_unit_price(gas_price)
             .with_max_gas_amount(max_possible_gas);
 
@@ -1437,13 +1553,11 @@ impl TransactionOptions {
             .sequence_number(sequence_number)
             .build();
 
-        let signed_transaction = SignedTransaction::new(
+        Ok(SignedTransaction::new(
             unsigned_transaction,
             sender_key.public_key(),
             Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
-        );
-        let txns = client.simulate(&signed_transaction).await?.into_inner();
-        Ok(txns.first().unwrap().clone())
+        ))
     }
 
     pub async fn estimate_gas_price(&self) -> CliTypedResult<u64> {
@@ -1461,6 +1575,201 @@ impl TransactionOptions {
     }
 }
 
+/// Options for building a transaction to be signed offline
+#[derive(Debug, Default, Parser)]
+pub struct UnsignedTransactionOptions {
+    /// Sequence number of the transaction
+    ///
+    /// Defaults to the current sequence number of the sender on chain
+    #[clap(long)]
+    pub(crate) sequence_number: Option<u64>,
+
+    /// Number of seconds from now after which the transaction expires
+    ///
+    /// Defaults to an hour, to leave time to sign the transaction offline
+    #[clap(long)]
+    pub(crate) expiration_secs: Option<u64>,
+}
+
+/// Writes the transaction to a file instead of submitting it
+#[derive(Debug, Default, Parser)]
+pub struct OutputUnsignedOptions {
+    /// Write the unsigned transaction to this file instead of signing and submitting it
+    ///
+    /// The transaction can then be signed with `aptos transaction sign`, and submitted with
+    /// `aptos transaction submit`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_unsigned: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) unsigned_options: UnsignedTransactionOptions,
+}
+
+impl OutputUnsignedOptions {
+    /// Submits the transaction, or writes it unsigned to a file if `--output-unsigned` is set
+    pub async fn submit_transaction(
+        &self,
+        txn_options: &TransactionOptions,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<SubmitResult<Transaction>> {
+        if let Some(ref output_file) = self.output_unsigned {
+            let raw_txn = txn_options
+                .build_unsigned_transaction(payload, &self.unsigned_options)
+                .await?;
+            check_if_file_exists(output_file, txn_options.prompt_options)?;
+            write_to_file(
+                output_file,
+                "Unsigned transaction",
+                &bcs::to_bytes(&raw_txn).map_err(|err| CliError::BCS("RawTransaction", err))?,
+            )?;
+            Ok(SubmitResult::Unsigned(UnsignedTransactionSummary::from(
+                &raw_txn,
+            )))
+        } else {
+            txn_options
+                .submit_transaction(payload)
+                .await
+                .map(SubmitResult::Submitted)
+        }
+    }
+
+    /// Submits the transactions one after the other, or writes the transaction unsigned to a
+    /// file if `--output-unsigned` is set, which only supports a single transaction
+    pub async fn submit_transactions(
+        &self,
+        txn_options: &TransactionOptions,
+        payloads: Vec<TransactionPayload>,
+    ) -> CliTypedResult<Vec<SubmitResult<TransactionSummary>>> {
+        if self.output_unsigned.is_some() && payloads.len() > 1 {
+            return Err(CliError::CommandArgumentError(format!(
+                "--output-unsigned writes a single transaction, but {} are needed",
+                payloads.len()
+            )));
+        }
+
+        let mut results = vec![];
+        for payload in payloads {
+            results.push(
+                self.submit_transaction(txn_options, payload)
+                    .await?
+                    .map(TransactionSummary::from),
+            );
+        }
+        Ok(results)
+    }
+}
+
+/// Output of a command which either submits a transaction, writes it unsigned to a file, or
+/// profiles its gas
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum SubmitResult<T> {
+    Submitted(T),
+    Unsigned(UnsignedTransactionSummary),
+    Profiled(GasProfileSummary),
+}
+
+impl<T> SubmitResult<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> SubmitResult<U> {
+        match self {
+            SubmitResult::Submitted(inner) => SubmitResult::Submitted(f(inner)),
+            SubmitResult::Unsigned(summary) => SubmitResult::Unsigned(summary),
+            SubmitResult::Profiled(summary) => SubmitResult::Profiled(summary),
+        }
+    }
+
+    /// Like [`SubmitResult::map`], for a fallible `f`
+    pub fn try_map<U, F: FnOnce(T) -> CliTypedResult<U>>(
+        self,
+        f: F,
+    ) -> CliTypedResult<SubmitResult<U>> {
+        Ok(match self {
+            SubmitResult::Submitted(inner) => SubmitResult::Submitted(f(inner)?),
+            SubmitResult::Unsigned(summary) => SubmitResult::Unsigned(summary),
+            SubmitResult::Profiled(summary) => SubmitResult::Profiled(summary),
+        })
+    }
+
+    /// Returns the output of the submitted transaction, failing if it wasn't submitted
+    pub fn into_submitted(self) -> CliTypedResult<T> {
+        match self {
+            SubmitResult::Submitted(inner) => Ok(inner),
+            SubmitResult::Unsigned(_) => Err(CliError::UnexpectedError(
+                "Transaction was written unsigned instead of being submitted".to_string(),
+            )),
+            SubmitResult::Profiled(_) => Err(CliError::UnexpectedError(
+                "Transaction was profiled instead of being submitted".to_string(),
+            )),
+        }
+    }
+}
+
+/// A summary of the gas profile of a simulated transaction
+///
+/// Gas of the breakdowns is in internal gas units, `gas_unit_scaling_factor` of which make one
+/// gas unit.
+#[derive(Clone, Debug, Serialize)]
+pub struct GasProfileSummary {
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    /// Gas of each call stack, in the folded format
+    pub folded_stacks_file: PathBuf,
+    /// Gas of each call stack, as a flamegraph
+    pub flamegraph_file: PathBuf,
+    pub gas_unit_scaling_factor: u64,
+    pub instructions: BTreeMap<String, u64>,
+    pub natives: BTreeMap<String, u64>,
+    pub storage_writes: BTreeMap<String, u64>,
+}
+
+impl GasProfileSummary {
+    fn new(gas_profile: GasProfile, folded_stacks_file: PathBuf, flamegraph_file: PathBuf) -> Self {
+        fn gas_map(entries: Vec<GasProfileEntry>) -> BTreeMap<String, u64> {
+            entries
+                .into_iter()
+                .map(|entry| (entry.name, entry.gas.0))
+                .collect()
+        }
+
+        GasProfileSummary {
+            success: gas_profile.success,
+            vm_status: gas_profile.vm_status,
+            gas_used: gas_profile.gas_used.0,
+            folded_stacks_file,
+            flamegraph_file,
+            gas_unit_scaling_factor: gas_profile.gas_unit_scaling_factor.0,
+            instructions: gas_map(gas_profile.instructions),
+            natives: gas_map(gas_profile.natives),
+            storage_writes: gas_map(gas_profile.storage_writes),
+        }
+    }
+}
+
+/// A summary of an unsigned transaction
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UnsignedTransactionSummary {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+    pub chain_id: u8,
+}
+
+impl From<&RawTransaction> for UnsignedTransactionSummary {
+    fn from(raw_txn: &RawTransaction) -> Self {
+        UnsignedTransactionSummary {
+            sender: raw_txn.sender(),
+            sequence_number: raw_txn.sequence_number(),
+            max_gas_amount: raw_txn.max_gas_amount(),
+            gas_unit_price: raw_txn.gas_unit_price(),
+            expiration_timestamp_secs: raw_txn.expiration_timestamp_secs(),
+            chain_id: raw_txn.chain_id().id(),
+        }
+    }
+}
+
 #[derive(Parser)]
 pub struct OptionalPoolAddressArgs {
     /// Address of the Staking pool
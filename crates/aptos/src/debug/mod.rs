@@ -0,0 +1,206 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions,
+};
+use aptos_debugger::AptosDebugger;
+use aptos_types::transaction::Version;
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use framework::{BuildOptions, BuiltPackage};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Tool for debugging past transactions
+#[derive(Subcommand)]
+pub enum DebugTool {
+    Replay(ReplayTransactions),
+    StateDiff(StateDiff),
+}
+
+impl DebugTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            DebugTool::Replay(tool) => tool.execute_serialized().await,
+            DebugTool::StateDiff(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Replay a range of committed transactions and compare the outputs with the ones on chain
+///
+/// The state is read from a local database with `--db-path`, or from a fullnode's REST API
+/// otherwise.  Packages given with `--override-package` are compiled and used in place of the
+/// modules on chain, e.g. to check a framework change against real traffic.
+#[derive(Parser)]
+pub struct ReplayTransactions {
+    /// First version to replay
+    #[clap(long)]
+    pub(crate) begin: Version,
+
+    /// Number of transactions to replay
+    #[clap(long, default_value = "1")]
+    pub(crate) limit: u64,
+
+    /// Path to the database of a node, instead of a REST endpoint
+    #[clap(long, parse(from_os_str), conflicts_with = "url")]
+    pub(crate) db_path: Option<PathBuf>,
+
+    /// Move packages to replace the on chain modules with, separated by spaces
+    #[clap(long, multiple_values = true, parse(from_os_str))]
+    pub(crate) override_package: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<ReplaySummary> for ReplayTransactions {
+    fn command_name(&self) -> &'static str {
+        "ReplayTransactions"
+    }
+
+    async fn execute(self) -> CliTypedResult<ReplaySummary> {
+        if self.limit == 0 {
+            return Err(CliError::CommandArgumentError(
+                "--limit must be at least 1".to_string(),
+            ));
+        }
+
+        let debugger = if let Some(ref db_path) = self.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            AptosDebugger::rest_client(self.rest_options.client(&self.profile_options)?)?
+        };
+
+        let mut modules = vec![];
+        for package_dir in self.override_package {
+            let package = BuiltPackage::build(package_dir, BuildOptions::default())
+                .map_err(|err| CliError::MoveCompilationError(err.to_string()))?;
+            modules.extend(package.modules().cloned());
+        }
+        let debugger = debugger.with_module_overrides(modules)?;
+
+        let replayed = debugger
+            .replay_past_transactions(self.begin, self.limit)
+            .await?;
+        let mismatches: Vec<_> = replayed
+            .iter()
+            .filter_map(|txn| {
+                let differences = txn.diff();
+                if differences.is_empty() {
+                    None
+                } else {
+                    Some(ReplayMismatch {
+                        version: txn.version,
+                        differences,
+                    })
+                }
+            })
+            .collect();
+        Ok(ReplaySummary {
+            replayed: replayed.len() as u64,
+            matched: (replayed.len() - mismatches.len()) as u64,
+            mismatches,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplaySummary {
+    pub replayed: u64,
+    pub matched: u64,
+    pub mismatches: Vec<ReplayMismatch>,
+}
+
+/// A replayed transaction whose output differs from the one on chain
+#[derive(Debug, Serialize)]
+pub struct ReplayMismatch {
+    pub version: Version,
+    pub differences: Vec<String>,
+}
+
+/// Show the state items whose values differ between two ledger versions
+///
+/// Changes are ordered by the hash of their state key.  The state is read from a local database
+/// with `--db-path`, or from a fullnode's REST API otherwise.
+#[derive(Parser)]
+pub struct StateDiff {
+    /// Older version to compare
+    #[clap(long)]
+    pub(crate) from_version: Version,
+
+    /// Newer version to compare, defaults to the latest version
+    #[clap(long)]
+    pub(crate) to_version: Option<Version>,
+
+    /// Number of changes to fetch per request
+    #[clap(long, default_value = "1000")]
+    pub(crate) page_size: u64,
+
+    /// Path to the database of a node, instead of a REST endpoint
+    #[clap(long, parse(from_os_str), conflicts_with = "url")]
+    pub(crate) db_path: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<StateChange>> for StateDiff {
+    fn command_name(&self) -> &'static str {
+        "StateDiff"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<StateChange>> {
+        if self.page_size == 0 {
+            return Err(CliError::CommandArgumentError(
+                "--page-size must be at least 1".to_string(),
+            ));
+        }
+
+        let debugger = if let Some(ref db_path) = self.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            AptosDebugger::rest_client(self.rest_options.client(&self.profile_options)?)?
+        };
+        let to_version = match self.to_version {
+            Some(version) => version,
+            None => debugger.get_latest_version().await?,
+        };
+        if self.from_version > to_version {
+            return Err(CliError::CommandArgumentError(format!(
+                "--from-version {} is after --to-version {}",
+                self.from_version, to_version
+            )));
+        }
+
+        Ok(debugger
+            .get_state_value_changes(self.from_version, to_version, self.page_size)
+            .await?
+            .into_iter()
+            .map(|change| StateChange {
+                state_key: format!("{:?}", change.state_key),
+                old_value: change
+                    .old_value
+                    .map(|value| format!("0x{}", hex::encode(value.bytes()))),
+                new_value: change
+                    .new_value
+                    .map(|value| format!("0x{}", hex::encode(value.bytes()))),
+            })
+            .collect())
+    }
+}
+
+/// A state item whose value differs between the two versions, absent values didn't exist
+#[derive(Debug, Serialize)]
+pub struct StateChange {
+    pub state_key: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::{
-    CliError, CliTypedResult, MovePackageDir, PoolAddressArgs, ProfileOptions, PromptOptions,
-    RestOptions, TransactionOptions, TransactionSummary,
+    CliError, CliTypedResult, MovePackageDir, OutputUnsignedOptions, PoolAddressArgs,
+    ProfileOptions, PromptOptions, RestOptions, SubmitResult, TransactionOptions,
+    TransactionSummary,
 };
 use crate::common::utils::prompt_yes_with_override;
 #[cfg(feature = "no-upload-proposal")]
@@ -273,18 +274,20 @@ pub struct SubmitProposal {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+    #[clap(flatten)]
     pub(crate) pool_address_args: PoolAddressArgs,
     #[clap(flatten)]
     pub(crate) compile_proposal_args: CompileScriptFunction,
 }
 
 #[async_trait]
-impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
+impl CliCommand<SubmitResult<ProposalSubmissionSummary>> for SubmitProposal {
     fn command_name(&self) -> &'static str {
         "SubmitProposal"
     }
 
-    async fn execute(mut self) -> CliTypedResult<ProposalSubmissionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<ProposalSubmissionSummary>> {
         let (_bytecode, script_hash) = self
             .compile_proposal_args
             .compile("SubmitProposal", self.txn_options.prompt_options)?;
@@ -301,42 +304,48 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
             self.txn_options.prompt_options,
         )?;
 
-        let txn = self
-            .txn_options
-            .submit_transaction(aptos_stdlib::aptos_governance_create_proposal(
-                self.pool_address_args.pool_address,
-                script_hash.to_vec(),
-                self.metadata_url.to_string().as_bytes().to_vec(),
-                metadata_hash.to_hex().as_bytes().to_vec(),
-            ))
-            .await?;
-        let txn_summary = TransactionSummary::from(&txn);
-        if let Transaction::UserTransaction(inner) = txn {
-            // Find event with proposal id
-            let proposal_id = if let Some(event) = inner.events.into_iter().find(|event| {
-                event.typ.to_string().as_str() == "0x1::aptos_governance::CreateProposalEvent"
-            }) {
-                let data: CreateProposalEvent =
-                    serde_json::from_value(event.data).map_err(|_| {
-                        CliError::UnexpectedError(
-                            "Failed to parse Proposal event to get ProposalId".to_string(),
-                        )
-                    })?;
-                Some(data.proposal_id.0)
-            } else {
-                warn!("No proposal event found to find proposal id");
-                None
-            };
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::aptos_governance_create_proposal(
+                    self.pool_address_args.pool_address,
+                    script_hash.to_vec(),
+                    self.metadata_url.to_string().as_bytes().to_vec(),
+                    metadata_hash.to_hex().as_bytes().to_vec(),
+                ),
+            )
+            .await?
+            .try_map(proposal_submission_summary)
+    }
+}
 
-            return Ok(ProposalSubmissionSummary {
-                proposal_id,
-                transaction: txn_summary,
-            });
-        }
-        Err(CliError::UnexpectedError(
-            "Unable to find parse proposal transaction output".to_string(),
-        ))
+/// Finds the id of the proposal created by `txn`
+fn proposal_submission_summary(txn: Transaction) -> CliTypedResult<ProposalSubmissionSummary> {
+    let txn_summary = TransactionSummary::from(&txn);
+    if let Transaction::UserTransaction(inner) = txn {
+        // Find event with proposal id
+        let proposal_id = if let Some(event) = inner.events.into_iter().find(|event| {
+            event.typ.to_string().as_str() == "0x1::aptos_governance::CreateProposalEvent"
+        }) {
+            let data: CreateProposalEvent = serde_json::from_value(event.data).map_err(|_| {
+                CliError::UnexpectedError(
+                    "Failed to parse Proposal event to get ProposalId".to_string(),
+                )
+            })?;
+            Some(data.proposal_id.0)
+        } else {
+            warn!("No proposal event found to find proposal id");
+            None
+        };
+
+        return Ok(ProposalSubmissionSummary {
+            proposal_id,
+            transaction: txn_summary,
+        });
     }
+    Err(CliError::UnexpectedError(
+        "Unable to find parse proposal transaction output".to_string(),
+    ))
 }
 
 impl SubmitProposal {
@@ -437,15 +446,17 @@ pub struct SubmitVote {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
+impl CliCommand<Vec<SubmitResult<TransactionSummary>>> for SubmitVote {
     fn command_name(&self) -> &'static str {
         "SubmitVote"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Vec<SubmitResult<TransactionSummary>>> {
         let (vote_str, vote) = match (self.yes, self.no) {
             (true, false) => ("Yes", true),
             (false, true) => ("No", false),
@@ -471,7 +482,7 @@ impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
             .into_inner()
             .votes;
 
-        let mut summaries: Vec<TransactionSummary> = vec![];
+        let mut payloads = vec![];
         for pool_address in self.pool_addresses {
             let voting_record = client
                 .get_table_item(
@@ -508,18 +519,15 @@ impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
                 self.txn_options.prompt_options,
             )?;
 
-            summaries.push(
-                self.txn_options
-                    .submit_transaction(aptos_stdlib::aptos_governance_vote(
-                        pool_address,
-                        proposal_id,
-                        vote,
-                    ))
-                    .await
-                    .map(TransactionSummary::from)?,
-            );
+            payloads.push(aptos_stdlib::aptos_governance_vote(
+                pool_address,
+                proposal_id,
+                vote,
+            ));
         }
-        Ok(summaries)
+        self.output_options
+            .submit_transactions(&self.txn_options, payloads)
+            .await
     }
 }
 
@@ -626,16 +634,18 @@ pub struct ExecuteProposal {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+    #[clap(flatten)]
     pub(crate) compile_proposal_args: CompileScriptFunction,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for ExecuteProposal {
+impl CliCommand<SubmitResult<TransactionSummary>> for ExecuteProposal {
     fn command_name(&self) -> &'static str {
         "ExecuteProposal"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let (bytecode, _script_hash) = self
             .compile_proposal_args
             .compile("ExecuteProposal", self.txn_options.prompt_options)?;
@@ -644,10 +654,10 @@ impl CliCommand<TransactionSummary> for ExecuteProposal {
         let args = vec![TransactionArgument::U64(self.proposal_id)];
         let txn = TransactionPayload::Script(Script::new(bytecode, vec![], args));
 
-        self.txn_options
-            .submit_transaction(txn)
+        self.output_options
+            .submit_transaction(&self.txn_options, txn)
             .await
-            .map(TransactionSummary::from)
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
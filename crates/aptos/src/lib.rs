@@ -6,14 +6,17 @@
 pub mod account;
 pub mod common;
 pub mod config;
+pub mod debug;
 pub mod genesis;
 pub mod governance;
 pub mod move_tool;
+pub mod multisig;
 pub mod node;
 pub mod op;
 pub mod stake;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod test;
+pub mod transaction;
 
 use crate::common::types::{CliCommand, CliResult, CliTypedResult};
 use crate::common::utils::cli_build_information;
@@ -30,6 +33,8 @@ pub enum Tool {
     #[clap(subcommand)]
     Config(config::ConfigTool),
     #[clap(subcommand)]
+    Debug(debug::DebugTool),
+    #[clap(subcommand)]
     Genesis(genesis::GenesisTool),
     #[clap(subcommand)]
     Governance(governance::GovernanceTool),
@@ -40,9 +45,13 @@ pub enum Tool {
     #[clap(subcommand)]
     Move(move_tool::MoveTool),
     #[clap(subcommand)]
+    Multisig(multisig::MultisigTool),
+    #[clap(subcommand)]
     Node(node::NodeTool),
     #[clap(subcommand)]
     Stake(stake::StakeTool),
+    #[clap(subcommand)]
+    Transaction(transaction::TransactionTool),
 }
 
 impl Tool {
@@ -51,6 +60,7 @@ impl Tool {
         match self {
             Account(tool) => tool.execute().await,
             Config(tool) => tool.execute().await,
+            Debug(tool) => tool.execute().await,
             Genesis(tool) => tool.execute().await,
             Governance(tool) => tool.execute().await,
             Info(tool) => tool.execute_serialized().await,
@@ -58,8 +68,10 @@ impl Tool {
             Init(tool) => tool.execute_serialized_success().await,
             Key(tool) => tool.execute().await,
             Move(tool) => tool.execute().await,
+            Multisig(tool) => tool.execute().await,
             Node(tool) => tool.execute().await,
             Stake(tool) => tool.execute().await,
+            Transaction(tool) => tool.execute().await,
         }
     }
 }
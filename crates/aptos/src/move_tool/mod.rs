@@ -22,8 +22,8 @@ use crate::move_tool::manifest::{
 use crate::{
     common::{
         types::{
-            load_account_arg, CliError, CliTypedResult, MovePackageDir, PromptOptions,
-            TransactionOptions, TransactionSummary,
+            load_account_arg, CliError, CliTypedResult, MovePackageDir, OutputUnsignedOptions,
+            PromptOptions, SubmitResult, TransactionOptions, TransactionSummary,
         },
         utils::check_if_file_exists,
     },
@@ -917,9 +917,9 @@ impl CliCommand<&'static str> for CleanPackage {
     }
 }
 
-/// Run a Move function
+/// Arguments of an entry function call
 #[derive(Parser)]
-pub struct RunFunction {
+pub struct EntryFunctionArguments {
     /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
     ///
     /// Example: `0x842ed41fad9640a2ad08fdd7d3e4f7f505319aac7d67e1c0dd6a7cce8732c7e3::message::set_message`
@@ -939,18 +939,11 @@ pub struct RunFunction {
     /// Example: `u8 u64 u128 bool address vector signer`
     #[clap(long, multiple_values = true)]
     pub(crate) type_args: Vec<MoveType>,
-
-    #[clap(flatten)]
-    pub(crate) txn_options: TransactionOptions,
 }
 
-#[async_trait]
-impl CliCommand<TransactionSummary> for RunFunction {
-    fn command_name(&self) -> &'static str {
-        "RunFunction"
-    }
-
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+impl EntryFunctionArguments {
+    /// Builds the entry function payload
+    pub fn create_payload(self) -> CliTypedResult<TransactionPayload> {
         let args: Vec<Vec<u8>> = self
             .args
             .into_iter()
@@ -965,15 +958,55 @@ impl CliCommand<TransactionSummary> for RunFunction {
             type_args.push(type_tag)
         }
 
-        self.txn_options
-            .submit_transaction(TransactionPayload::EntryFunction(EntryFunction::new(
-                self.function_id.module_id,
-                self.function_id.member_id,
-                type_args,
-                args,
-            )))
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            self.function_id.module_id,
+            self.function_id.member_id,
+            type_args,
+            args,
+        )))
+    }
+}
+
+/// Run a Move function
+#[derive(Parser)]
+pub struct RunFunction {
+    #[clap(flatten)]
+    pub(crate) entry_function_args: EntryFunctionArguments,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+
+    /// Simulate the transaction and profile its gas instead of submitting it
+    ///
+    /// The gas of each call stack is written to `gas-profile.folded` in the folded format of
+    /// flamegraph tools, and to `gas-profile.svg` as a flamegraph.  The gas of each type of
+    /// instruction, native function and storage write is in the output.
+    #[clap(long, conflicts_with = "output_unsigned")]
+    pub(crate) profile_gas: bool,
+}
+
+#[async_trait]
+impl CliCommand<SubmitResult<TransactionSummary>> for RunFunction {
+    fn command_name(&self) -> &'static str {
+        "RunFunction"
+    }
+
+    async fn execute(self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
+        let payload = self.entry_function_args.create_payload()?;
+        if self.profile_gas {
+            return self
+                .txn_options
+                .profile_gas(payload)
+                .await
+                .map(SubmitResult::Profiled);
+        }
+        self.output_options
+            .submit_transaction(&self.txn_options, payload)
             .await
-            .map(TransactionSummary::from)
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
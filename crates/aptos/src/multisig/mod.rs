@@ -0,0 +1,249 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            CliCommand, CliError, CliResult, CliTypedResult, EncodingOptions,
+            PrivateKeyInputOptions, ProfileOptions, SaveFile, TransactionSummary,
+        },
+        utils::read_from_file,
+    },
+    genesis::git::{from_yaml, to_yaml},
+    transaction::{prompt_sign_transaction, read_unsigned_transaction, save_signed_transaction},
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    PrivateKey, Signature, SigningKey, ValidCryptoMaterialStringExt,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{authenticator::AuthenticationKey, SignedTransaction},
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Tool for managing K-of-N multisig (MultiEd25519) accounts
+///
+/// A multisig account is defined by an ordered list of public keys, and the number of them that
+/// must sign each transaction.  Transactions are built with `aptos transaction build` or
+/// `--output-unsigned`, signed separately by each key holder, then combined and submitted with
+/// `aptos transaction submit`.
+#[derive(Subcommand)]
+pub enum MultisigTool {
+    Combine(CombineSignatures),
+    DeriveAddress(DeriveMultisigAddress),
+    Sign(SignMultisigTransaction),
+}
+
+impl MultisigTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            MultisigTool::Combine(tool) => tool.execute_serialized().await,
+            MultisigTool::DeriveAddress(tool) => tool.execute_serialized().await,
+            MultisigTool::Sign(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// The keys of a multisig account
+#[derive(Debug, Parser)]
+pub struct MultisigKeyArgs {
+    /// Hex encoded public keys of the multisig account, separated by spaces
+    ///
+    /// The order of the keys is part of the account, so it must always be the same
+    #[clap(long, required = true, multiple_values = true, parse(try_from_str = Ed25519PublicKey::from_encoded_string))]
+    pub(crate) public_keys: Vec<Ed25519PublicKey>,
+
+    /// Number of keys which must sign each transaction
+    #[clap(long)]
+    pub(crate) threshold: u8,
+}
+
+impl MultisigKeyArgs {
+    pub fn multi_public_key(&self) -> CliTypedResult<MultiEd25519PublicKey> {
+        MultiEd25519PublicKey::new(self.public_keys.clone(), self.threshold).map_err(|err| {
+            CliError::CommandArgumentError(format!(
+                "Invalid multisig keys, the threshold must be between 1 and the number of keys: {}",
+                err
+            ))
+        })
+    }
+}
+
+/// Derive the address of a multisig account from its public keys
+///
+/// This doesn't create the account, it only needs to be funded to be created.
+#[derive(Parser)]
+pub struct DeriveMultisigAddress {
+    #[clap(flatten)]
+    pub(crate) key_args: MultisigKeyArgs,
+}
+
+#[async_trait]
+impl CliCommand<MultisigAccount> for DeriveMultisigAddress {
+    fn command_name(&self) -> &'static str {
+        "DeriveMultisigAddress"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigAccount> {
+        let multi_public_key = self.key_args.multi_public_key()?;
+        let authentication_key = AuthenticationKey::multi_ed25519(&multi_public_key);
+        Ok(MultisigAccount {
+            account_address: authentication_key.derived_address(),
+            authentication_key: authentication_key.to_string(),
+            threshold: self.key_args.threshold,
+            public_keys: self.key_args.public_keys,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MultisigAccount {
+    pub account_address: AccountAddress,
+    pub authentication_key: String,
+    pub threshold: u8,
+    pub public_keys: Vec<Ed25519PublicKey>,
+}
+
+/// Sign a transaction with one of the keys of a multisig account
+///
+/// This doesn't connect to the network, so it can run on an air-gapped machine.  The signature is
+/// written to the output file, to be combined with the others with `aptos multisig combine`.
+#[derive(Parser)]
+pub struct SignMultisigTransaction {
+    /// Unsigned transaction file, from `aptos transaction build` or `--output-unsigned`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) unsigned_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<PartialSignature> for SignMultisigTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignMultisigTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<PartialSignature> {
+        self.save_file.check_file()?;
+        let raw_txn = read_unsigned_transaction(&self.unsigned_file)?;
+        let private_key = self
+            .private_key_options
+            .extract_private_key(self.encoding_options.encoding, &self.profile_options)?;
+
+        prompt_sign_transaction(&raw_txn, self.save_file.prompt_options)?;
+        let signature = PartialSignature {
+            public_key: private_key.public_key(),
+            signature: private_key
+                .sign(&raw_txn)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+        };
+        self.save_file
+            .save_to_file("Partial signature", to_yaml(&signature)?.as_bytes())?;
+        Ok(signature)
+    }
+}
+
+/// A signature of a transaction by one of the keys of a multisig account
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartialSignature {
+    pub public_key: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+/// Combine the signatures of a multisig transaction into a signed transaction
+///
+/// At least `--threshold` signatures are needed, and the keys must be the ones the sender's
+/// address is derived from.  The signed transaction is written to the output file, to be submitted
+/// with `aptos transaction submit`.
+#[derive(Parser)]
+pub struct CombineSignatures {
+    /// Unsigned transaction file, from `aptos transaction build` or `--output-unsigned`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) unsigned_file: PathBuf,
+
+    /// Signature files from `aptos multisig sign`, separated by spaces
+    #[clap(long, required = true, multiple_values = true, parse(from_os_str))]
+    pub(crate) signature_files: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) key_args: MultisigKeyArgs,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for CombineSignatures {
+    fn command_name(&self) -> &'static str {
+        "CombineSignatures"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        self.save_file.check_file()?;
+        let raw_txn = read_unsigned_transaction(&self.unsigned_file)?;
+        let multi_public_key = self.key_args.multi_public_key()?;
+        let account_address = AuthenticationKey::multi_ed25519(&multi_public_key).derived_address();
+        if account_address != raw_txn.sender() {
+            return Err(CliError::CommandArgumentError(format!(
+                "The transaction is sent by {}, but the multisig keys are the ones of {}, check \
+                 the order of the keys and the threshold",
+                raw_txn.sender(),
+                account_address
+            )));
+        }
+
+        let mut signatures = Vec::new();
+        for signature_file in &self.signature_files {
+            let partial: PartialSignature = from_yaml(
+                &String::from_utf8(read_from_file(signature_file)?)
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+            )?;
+            let index = multi_public_key
+                .public_keys()
+                .iter()
+                .position(|public_key| public_key == &partial.public_key)
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(format!(
+                        "Signature {} is from {}, which isn't one of the multisig keys",
+                        signature_file.display(),
+                        partial.public_key
+                    ))
+                })?;
+            partial
+                .signature
+                .verify(&raw_txn, &partial.public_key)
+                .map_err(|_| {
+                    CliError::CommandArgumentError(format!(
+                        "Signature {} isn't a valid signature of the transaction",
+                        signature_file.display()
+                    ))
+                })?;
+            signatures.push((partial.signature, index as u8));
+        }
+
+        if signatures.len() < *multi_public_key.threshold() as usize {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} signatures are required, but only {} were given",
+                multi_public_key.threshold(),
+                signatures.len()
+            )));
+        }
+        let multi_signature = MultiEd25519Signature::new(signatures)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+
+        let signed_txn =
+            SignedTransaction::new_multisig(raw_txn, multi_public_key, multi_signature);
+        save_signed_transaction(&self.save_file, signed_txn)
+    }
+}
@@ -6,27 +6,31 @@ pub mod analyze;
 use crate::common::types::{
     ConfigSearchMode, OptionalPoolAddressArgs, PoolAddressArgs, PromptOptions, TransactionSummary,
 };
-use crate::common::utils::prompt_yes_with_override;
+use crate::common::utils::{
+    check_if_file_exists, prompt_yes_with_override, write_to_user_only_file,
+};
 use crate::config::GlobalConfig;
 use crate::node::analyze::analyze_validators::{AnalyzeValidators, ValidatorStats};
 use crate::node::analyze::fetch_metadata::FetchMetadata;
 use crate::{
     common::{
         types::{
-            CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions,
-            TransactionOptions,
+            CliCommand, CliError, CliResult, CliTypedResult, OutputUnsignedOptions, ProfileOptions,
+            RestOptions, SubmitResult, TransactionOptions,
         },
         utils::read_from_file,
     },
     genesis::git::from_yaml,
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{NodeConfig, Token};
 use aptos_crypto::bls12381::PublicKey;
 use aptos_crypto::{bls12381, x25519, ValidCryptoMaterialStringExt};
 use aptos_faucet::FaucetArgs;
 use aptos_genesis::config::{HostAndPort, OperatorConfiguration};
+use aptos_node::ForkArgs;
 use aptos_rest_client::aptos_api_types::VersionedEvent;
 use aptos_rest_client::{Client, State};
+use aptos_secure_storage::{EncryptedOnDiskStorage, Storage};
 use aptos_types::account_config::BlockResource;
 use aptos_types::chain_id::ChainId;
 use aptos_types::network_address::NetworkAddress;
@@ -52,12 +56,17 @@ use hex::FromHex;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use reqwest::Url;
+use safety_rules::{PersistentSafetyStorage, SafetyDataInterchange};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::{path::PathBuf, thread, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
 use tokio::time::Instant;
 
 const SECS_TO_MICROSECS: u64 = 1_000_000;
@@ -82,6 +91,10 @@ pub enum NodeTool {
     UpdateValidatorNetworkAddresses(UpdateValidatorNetworkAddresses),
     AnalyzeValidatorPerformance(AnalyzeValidatorPerformance),
     BootstrapDbFromBackup(BootstrapDbFromBackup),
+    EncryptSecureStorage(EncryptSecureStorage),
+    ExportSafetyData(ExportSafetyData),
+    ImportSafetyData(ImportSafetyData),
+    MergeSafetyData(MergeSafetyData),
 }
 
 impl NodeTool {
@@ -102,6 +115,10 @@ impl NodeTool {
             UpdateValidatorNetworkAddresses(tool) => tool.execute_serialized().await,
             AnalyzeValidatorPerformance(tool) => tool.execute_serialized().await,
             BootstrapDbFromBackup(tool) => tool.execute_serialized().await,
+            EncryptSecureStorage(tool) => tool.execute_serialized().await,
+            ExportSafetyData(tool) => tool.execute_serialized().await,
+            ImportSafetyData(tool) => tool.execute_serialized().await,
+            MergeSafetyData(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -584,6 +601,8 @@ pub struct InitializeValidator {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+    #[clap(flatten)]
     pub(crate) operator_config_file_args: OperatorConfigFileArgs,
     #[clap(flatten)]
     pub(crate) validator_consensus_key_args: ValidatorConsensusKeyArgs,
@@ -592,12 +611,12 @@ pub struct InitializeValidator {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for InitializeValidator {
+impl CliCommand<SubmitResult<TransactionSummary>> for InitializeValidator {
     fn command_name(&self) -> &'static str {
         "InitializeValidator"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let operator_config = self.operator_config_file_args.load()?;
         let consensus_public_key = self
             .validator_consensus_key_args
@@ -627,16 +646,19 @@ impl CliCommand<TransactionSummary> for InitializeValidator {
                 }
             };
 
-        self.txn_options
-            .submit_transaction(aptos_stdlib::stake_initialize_validator(
-                consensus_public_key.to_bytes().to_vec(),
-                consensus_proof_of_possession.to_bytes().to_vec(),
-                // BCS encode, so that we can hide the original type
-                bcs::to_bytes(&validator_network_addresses)?,
-                bcs::to_bytes(&full_node_network_addresses)?,
-            ))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::stake_initialize_validator(
+                    consensus_public_key.to_bytes().to_vec(),
+                    consensus_proof_of_possession.to_bytes().to_vec(),
+                    // BCS encode, so that we can hide the original type
+                    bcs::to_bytes(&validator_network_addresses)?,
+                    bcs::to_bytes(&full_node_network_addresses)?,
+                ),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -680,24 +702,29 @@ pub struct JoinValidatorSet {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+    #[clap(flatten)]
     pub(crate) operator_args: OperatorArgs,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for JoinValidatorSet {
+impl CliCommand<SubmitResult<TransactionSummary>> for JoinValidatorSet {
     fn command_name(&self) -> &'static str {
         "JoinValidatorSet"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
 
-        self.txn_options
-            .submit_transaction(aptos_stdlib::stake_join_validator_set(address))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::stake_join_validator_set(address),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -710,24 +737,29 @@ pub struct LeaveValidatorSet {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+    #[clap(flatten)]
     pub(crate) operator_args: OperatorArgs,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for LeaveValidatorSet {
+impl CliCommand<SubmitResult<TransactionSummary>> for LeaveValidatorSet {
     fn command_name(&self) -> &'static str {
         "LeaveValidatorSet"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
 
-        self.txn_options
-            .submit_transaction(aptos_stdlib::stake_leave_validator_set(address))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::stake_leave_validator_set(address),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -1026,6 +1058,8 @@ const TESTNET_FOLDER: &str = "testnet";
 ///
 /// This local testnet will run it's own Genesis and run as a single node
 /// network locally.  Optionally, a faucet can be added for minting APT coins.
+/// With `--fork-url`, state which isn't written locally is read from another
+/// network at `--fork-version`, so transactions can be tested against real state.
 #[derive(Parser)]
 pub struct RunLocalTestnet {
     /// An overridable config template for the test node
@@ -1072,6 +1106,9 @@ pub struct RunLocalTestnet {
     #[clap(long)]
     do_not_delegate: bool,
 
+    #[clap(flatten)]
+    fork: ForkArgs,
+
     #[clap(flatten)]
     prompt_options: PromptOptions,
 }
@@ -1107,12 +1144,14 @@ impl CliCommand<()> for RunLocalTestnet {
         // Spawn the node in a separate thread
         let config_path = self.config_path.clone();
         let test_dir_copy = test_dir.clone();
+        let fork = self.fork.clone();
         let _node = thread::spawn(move || {
             aptos_node::load_test_environment(
                 config_path,
                 Some(test_dir_copy),
                 false,
                 false,
+                &fork,
                 cached_packages::head_release_bundle(),
                 rng,
             )
@@ -1203,6 +1242,8 @@ pub struct UpdateConsensusKey {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+    #[clap(flatten)]
     pub(crate) operator_args: OperatorArgs,
     #[clap(flatten)]
     pub(crate) operator_config_file_args: OperatorConfigFileArgs,
@@ -1211,12 +1252,12 @@ pub struct UpdateConsensusKey {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for UpdateConsensusKey {
+impl CliCommand<SubmitResult<TransactionSummary>> for UpdateConsensusKey {
     fn command_name(&self) -> &'static str {
         "UpdateConsensusKey"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
@@ -1228,14 +1269,17 @@ impl CliCommand<TransactionSummary> for UpdateConsensusKey {
         let consensus_proof_of_possession = self
             .validator_consensus_key_args
             .get_consensus_proof_of_possession(&operator_config)?;
-        self.txn_options
-            .submit_transaction(aptos_stdlib::stake_rotate_consensus_key(
-                address,
-                consensus_public_key.to_bytes().to_vec(),
-                consensus_proof_of_possession.to_bytes().to_vec(),
-            ))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::stake_rotate_consensus_key(
+                    address,
+                    consensus_public_key.to_bytes().to_vec(),
+                    consensus_proof_of_possession.to_bytes().to_vec(),
+                ),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -1245,6 +1289,8 @@ pub struct UpdateValidatorNetworkAddresses {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
+    #[clap(flatten)]
     pub(crate) operator_args: OperatorArgs,
     #[clap(flatten)]
     pub(crate) operator_config_file_args: OperatorConfigFileArgs,
@@ -1253,12 +1299,12 @@ pub struct UpdateValidatorNetworkAddresses {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for UpdateValidatorNetworkAddresses {
+impl CliCommand<SubmitResult<TransactionSummary>> for UpdateValidatorNetworkAddresses {
     fn command_name(&self) -> &'static str {
         "UpdateValidatorNetworkAddresses"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
@@ -1286,15 +1332,18 @@ impl CliCommand<TransactionSummary> for UpdateValidatorNetworkAddresses {
                 }
             };
 
-        self.txn_options
-            .submit_transaction(aptos_stdlib::stake_update_network_and_fullnode_addresses(
-                address,
-                // BCS encode, so that we can hide the original type
-                bcs::to_bytes(&validator_network_addresses)?,
-                bcs::to_bytes(&full_node_network_addresses)?,
-            ))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::stake_update_network_and_fullnode_addresses(
+                    address,
+                    // BCS encode, so that we can hide the original type
+                    bcs::to_bytes(&validator_network_addresses)?,
+                    bcs::to_bytes(&full_node_network_addresses)?,
+                ),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -1591,3 +1640,211 @@ impl Time {
         Self::new(Duration::from_secs(seconds))
     }
 }
+
+/// Encrypt the on-disk secure storage of a node
+///
+/// Copies every key of a plaintext `on_disk_storage` backend into a new
+/// `encrypted_on_disk_storage` backend, encrypted with a key derived from the contents of
+/// the encryption key file. Afterwards, point the node config at the new backend with
+/// `encryption_key: from_disk: <encryption key file>`, and delete the plaintext file once
+/// the node runs with it.
+#[derive(Parser)]
+pub struct EncryptSecureStorage {
+    /// Plaintext on-disk secure storage e.g. /opt/aptos/data/secure_storage.json
+    #[clap(long, parse(from_os_str))]
+    input_file: PathBuf,
+
+    /// File to write the encrypted secure storage to, it must not exist yet
+    #[clap(long, parse(from_os_str))]
+    output_file: PathBuf,
+
+    /// File holding the passphrase or key to derive the encryption key from
+    #[clap(long, parse(from_os_str))]
+    encryption_key_file: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncryptSecureStorageSummary {
+    pub output_file: PathBuf,
+    pub keys_migrated: usize,
+}
+
+#[async_trait]
+impl CliCommand<EncryptSecureStorageSummary> for EncryptSecureStorage {
+    fn command_name(&self) -> &'static str {
+        "EncryptSecureStorage"
+    }
+
+    async fn execute(self) -> CliTypedResult<EncryptSecureStorageSummary> {
+        // Read the key the same way the node does, so both derive the same encryption key
+        let secret = Token::FromDisk(self.encryption_key_file).read_token()?;
+        let keys_migrated = EncryptedOnDiskStorage::migrate_from_on_disk(
+            &self.input_file,
+            self.output_file.clone(),
+            secret.as_bytes(),
+        )
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        Ok(EncryptSecureStorageSummary {
+            output_file: self.output_file,
+            keys_migrated,
+        })
+    }
+}
+
+/// Node config file used to locate the safety rules secure storage
+#[derive(Parser)]
+pub struct SafetyRulesStorageArgs {
+    /// Node config file of the validator e.g. /opt/aptos/etc/validator.yaml
+    #[clap(long, parse(from_os_str))]
+    node_config_file: PathBuf,
+}
+
+impl SafetyRulesStorageArgs {
+    fn open(&self) -> CliTypedResult<PersistentSafetyStorage> {
+        let node_config = NodeConfig::load(&self.node_config_file)
+            .map_err(|err| CliError::UnableToParse("node config", err.to_string()))?;
+        let storage = Storage::from(&node_config.consensus.safety_rules.backend);
+        Ok(PersistentSafetyStorage::new(storage, false))
+    }
+}
+
+fn read_safety_data_interchange(file: &Path) -> CliTypedResult<SafetyDataInterchange> {
+    serde_json::from_slice(&read_from_file(file)?)
+        .map_err(|err| CliError::UnableToParse("safety data", err.to_string()))
+}
+
+fn write_safety_data_interchange(
+    file: &Path,
+    interchange: &SafetyDataInterchange,
+) -> CliTypedResult<()> {
+    let bytes = serde_json::to_vec_pretty(interchange)
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+    write_to_user_only_file(file, "safety data", &bytes)
+}
+
+/// Export the safety rules data of a validator
+///
+/// Writes the safety data (the last voted round, preferred round and last vote) held in the
+/// secure storage of the validator to a file, in a versioned interchange format. Stop the
+/// validator first, so that it doesn't vote after the export.
+#[derive(Parser)]
+pub struct ExportSafetyData {
+    #[clap(flatten)]
+    storage_args: SafetyRulesStorageArgs,
+
+    /// File to write the safety data to
+    #[clap(long, parse(from_os_str))]
+    output_file: PathBuf,
+
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<SafetyDataInterchange> for ExportSafetyData {
+    fn command_name(&self) -> &'static str {
+        "ExportSafetyData"
+    }
+
+    async fn execute(self) -> CliTypedResult<SafetyDataInterchange> {
+        check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
+        let interchange = self
+            .storage_args
+            .open()?
+            .export_safety_data()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        write_safety_data_interchange(self.output_file.as_path(), &interchange)?;
+        Ok(interchange)
+    }
+}
+
+/// Import safety rules data into the secure storage of a validator
+///
+/// Merges the exported safety data into the safety data of the validator, keeping the highest
+/// of each round. The exported data must be for the same validator, and for the same or a
+/// later epoch, which then replaces the safety data of the validator. Stop the validator
+/// first, so that it doesn't overwrite the imported data.
+#[derive(Parser)]
+pub struct ImportSafetyData {
+    #[clap(flatten)]
+    storage_args: SafetyRulesStorageArgs,
+
+    /// File holding the exported safety data
+    #[clap(long, parse(from_os_str))]
+    input_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<SafetyDataInterchange> for ImportSafetyData {
+    fn command_name(&self) -> &'static str {
+        "ImportSafetyData"
+    }
+
+    async fn execute(self) -> CliTypedResult<SafetyDataInterchange> {
+        let imported = read_safety_data_interchange(self.input_file.as_path())?;
+        let mut storage = self.storage_args.open()?;
+        let safety_data = storage
+            .import_safety_data(&imported)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        Ok(SafetyDataInterchange::new(imported.author, safety_data))
+    }
+}
+
+/// Merge exported safety rules data
+///
+/// Merges safety data exported from several secure storages of the same validator into a single
+/// file. Only the data of the latest epoch is kept, and the highest of each of its rounds.
+#[derive(Parser)]
+pub struct MergeSafetyData {
+    /// Files holding the exported safety data
+    #[clap(long, parse(from_os_str), multiple_values = true, min_values = 2)]
+    input_files: Vec<PathBuf>,
+
+    /// File to write the merged safety data to
+    #[clap(long, parse(from_os_str))]
+    output_file: PathBuf,
+
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<SafetyDataInterchange> for MergeSafetyData {
+    fn command_name(&self) -> &'static str {
+        "MergeSafetyData"
+    }
+
+    async fn execute(self) -> CliTypedResult<SafetyDataInterchange> {
+        check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
+        let mut interchanges = self
+            .input_files
+            .iter()
+            .map(|input_file| {
+                read_safety_data_interchange(input_file.as_path())
+                    .map(|interchange| (input_file, interchange))
+            })
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        // Merge in epoch order, as the data of an earlier epoch can't be merged into a later one
+        interchanges.sort_by_key(|(_, interchange)| interchange.safety_data.epoch);
+
+        let mut merged: Option<SafetyDataInterchange> = None;
+        for (input_file, interchange) in interchanges {
+            merged = Some(match merged {
+                Some(merged) => merged.merge(&interchange).map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "Unable to merge {}: {}",
+                        input_file.display(),
+                        err
+                    ))
+                })?,
+                None => interchange,
+            });
+        }
+        let merged = merged.ok_or_else(|| {
+            CliError::CommandArgumentError("Must provide --input-files".to_string())
+        })?;
+        write_safety_data_interchange(self.output_file.as_path(), &merged)?;
+        Ok(merged)
+    }
+}
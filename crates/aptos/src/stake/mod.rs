@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::{
-    CliCommand, CliError, CliResult, CliTypedResult, TransactionOptions, TransactionSummary,
+    CliCommand, CliError, CliResult, CliTypedResult, OutputUnsignedOptions, SubmitResult,
+    TransactionOptions, TransactionSummary,
 };
 use crate::common::utils::prompt_yes_with_override;
 use crate::node::{get_stake_pools, StakePoolType};
@@ -61,44 +62,37 @@ pub struct AddStake {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for AddStake {
+impl CliCommand<Vec<SubmitResult<TransactionSummary>>> for AddStake {
     fn command_name(&self) -> &'static str {
         "AddStake"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Vec<SubmitResult<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
             .client(&self.txn_options.profile_options)?;
         let amount = self.amount;
         let owner_address = self.txn_options.sender_address()?;
-        let mut transaction_summaries: Vec<TransactionSummary> = vec![];
+        let mut payloads = vec![];
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
             match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_add_stake(amount))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::stake_add_stake(amount));
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_add_stake(
-                                stake_pool.operator_address,
-                                amount,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::staking_contract_add_stake(
+                        stake_pool.operator_address,
+                        amount,
+                    ));
                 }
                 StakePoolType::Vesting => {
                     return Err(CliError::UnexpectedError(
@@ -107,7 +101,9 @@ impl CliCommand<Vec<TransactionSummary>> for AddStake {
                 }
             }
         }
-        Ok(transaction_summaries)
+        self.output_options
+            .submit_transactions(&self.txn_options, payloads)
+            .await
     }
 }
 
@@ -122,44 +118,37 @@ pub struct UnlockStake {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for UnlockStake {
+impl CliCommand<Vec<SubmitResult<TransactionSummary>>> for UnlockStake {
     fn command_name(&self) -> &'static str {
         "UnlockStake"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Vec<SubmitResult<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
             .client(&self.txn_options.profile_options)?;
         let amount = self.amount;
         let owner_address = self.txn_options.sender_address()?;
-        let mut transaction_summaries: Vec<TransactionSummary> = vec![];
+        let mut payloads = vec![];
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
             match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_unlock(amount))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::stake_unlock(amount));
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_unlock_stake(
-                                stake_pool.operator_address,
-                                amount,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::staking_contract_unlock_stake(
+                        stake_pool.operator_address,
+                        amount,
+                    ));
                 }
                 StakePoolType::Vesting => {
                     return Err(CliError::UnexpectedError(
@@ -168,7 +157,9 @@ impl CliCommand<Vec<TransactionSummary>> for UnlockStake {
                 }
             }
         }
-        Ok(transaction_summaries)
+        self.output_options
+            .submit_transactions(&self.txn_options, payloads)
+            .await
     }
 }
 
@@ -184,19 +175,25 @@ pub struct WithdrawStake {
 
     #[clap(flatten)]
     pub(crate) node_op_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for WithdrawStake {
+impl CliCommand<SubmitResult<TransactionSummary>> for WithdrawStake {
     fn command_name(&self) -> &'static str {
         "WithdrawStake"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
-        self.node_op_options
-            .submit_transaction(aptos_stdlib::stake_withdraw(self.amount))
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
+        self.output_options
+            .submit_transaction(
+                &self.node_op_options,
+                aptos_stdlib::stake_withdraw(self.amount),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -207,56 +204,46 @@ impl CliCommand<TransactionSummary> for WithdrawStake {
 pub struct IncreaseLockup {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for IncreaseLockup {
+impl CliCommand<Vec<SubmitResult<TransactionSummary>>> for IncreaseLockup {
     fn command_name(&self) -> &'static str {
         "IncreaseLockup"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Vec<SubmitResult<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
             .client(&self.txn_options.profile_options)?;
         let owner_address = self.txn_options.sender_address()?;
-        let mut transaction_summaries: Vec<TransactionSummary> = vec![];
+        let mut payloads = vec![];
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
             match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_increase_lockup())
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::stake_increase_lockup());
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_reset_lockup(
-                                stake_pool.operator_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::staking_contract_reset_lockup(
+                        stake_pool.operator_address,
+                    ));
                 }
                 StakePoolType::Vesting => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::vesting_reset_lockup(
-                                stake_pool.vesting_contract.unwrap(),
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::vesting_reset_lockup(
+                        stake_pool.vesting_contract.unwrap(),
+                    ));
                 }
             }
         }
-        Ok(transaction_summaries)
+        self.output_options
+            .submit_transactions(&self.txn_options, payloads)
+            .await
     }
 }
 
@@ -280,24 +267,30 @@ pub struct InitializeStakeOwner {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for InitializeStakeOwner {
+impl CliCommand<SubmitResult<TransactionSummary>> for InitializeStakeOwner {
     fn command_name(&self) -> &'static str {
         "InitializeStakeOwner"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let owner_address = self.txn_options.sender_address()?;
-        self.txn_options
-            .submit_transaction(aptos_stdlib::stake_initialize_stake_owner(
-                self.initial_stake_amount,
-                self.operator_address.unwrap_or(owner_address),
-                self.voter_address.unwrap_or(owner_address),
-            ))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::stake_initialize_stake_owner(
+                    self.initial_stake_amount,
+                    self.operator_address.unwrap_or(owner_address),
+                    self.voter_address.unwrap_or(owner_address),
+                ),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -314,65 +307,51 @@ pub struct SetOperator {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for SetOperator {
+impl CliCommand<Vec<SubmitResult<TransactionSummary>>> for SetOperator {
     fn command_name(&self) -> &'static str {
         "SetOperator"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Vec<SubmitResult<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
             .client(&self.txn_options.profile_options)?;
         let owner_address = self.txn_options.sender_address()?;
         let new_operator_address = self.operator_address;
-        let mut transaction_summaries: Vec<TransactionSummary> = vec![];
+        let mut payloads = vec![];
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
             match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_set_operator(
-                                new_operator_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::stake_set_operator(new_operator_address));
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(
-                                aptos_stdlib::staking_contract_switch_operator_with_same_commission(
-                                    stake_pool.operator_address,
-                                    new_operator_address,
-                                ),
-                            )
-                            .await
-                            .map(|inner| inner.into())?,
+                    payloads.push(
+                        aptos_stdlib::staking_contract_switch_operator_with_same_commission(
+                            stake_pool.operator_address,
+                            new_operator_address,
+                        ),
                     );
                 }
                 StakePoolType::Vesting => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(
-                                aptos_stdlib::vesting_update_operator_with_same_commission(
-                                    stake_pool.vesting_contract.unwrap(),
-                                    new_operator_address,
-                                ),
-                            )
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::vesting_update_operator_with_same_commission(
+                        stake_pool.vesting_contract.unwrap(),
+                        new_operator_address,
+                    ));
                 }
             }
         }
-        Ok(transaction_summaries)
+        self.output_options
+            .submit_transactions(&self.txn_options, payloads)
+            .await
     }
 }
 
@@ -389,61 +368,49 @@ pub struct SetDelegatedVoter {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<Vec<TransactionSummary>> for SetDelegatedVoter {
+impl CliCommand<Vec<SubmitResult<TransactionSummary>>> for SetDelegatedVoter {
     fn command_name(&self) -> &'static str {
         "SetDelegatedVoter"
     }
 
-    async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
+    async fn execute(mut self) -> CliTypedResult<Vec<SubmitResult<TransactionSummary>>> {
         let client = self
             .txn_options
             .rest_options
             .client(&self.txn_options.profile_options)?;
         let owner_address = self.txn_options.sender_address()?;
         let new_voter_address = self.voter_address;
-        let mut transaction_summaries: Vec<TransactionSummary> = vec![];
+        let mut payloads = vec![];
 
         let stake_pool_results = get_stake_pools(&client, owner_address).await?;
         for stake_pool in stake_pool_results {
             match stake_pool.pool_type {
                 StakePoolType::Direct => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_set_delegated_voter(
-                                new_voter_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::stake_set_delegated_voter(new_voter_address));
                 }
                 StakePoolType::StakingContract => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_update_voter(
-                                stake_pool.operator_address,
-                                new_voter_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::staking_contract_update_voter(
+                        stake_pool.operator_address,
+                        new_voter_address,
+                    ));
                 }
                 StakePoolType::Vesting => {
-                    transaction_summaries.push(
-                        self.txn_options
-                            .submit_transaction(aptos_stdlib::vesting_update_voter(
-                                stake_pool.vesting_contract.unwrap(),
-                                new_voter_address,
-                            ))
-                            .await
-                            .map(|inner| inner.into())?,
-                    );
+                    payloads.push(aptos_stdlib::vesting_update_voter(
+                        stake_pool.vesting_contract.unwrap(),
+                        new_voter_address,
+                    ));
                 }
             }
         }
-        Ok(transaction_summaries)
+        self.output_options
+            .submit_transactions(&self.txn_options, payloads)
+            .await
     }
 }
 
@@ -470,15 +437,18 @@ pub struct CreateStakingContract {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateStakingContract {
+impl CliCommand<SubmitResult<TransactionSummary>> for CreateStakingContract {
     fn command_name(&self) -> &'static str {
         "CreateStakingContract"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let pool_address = default_stake_pool_address(
             self.txn_options.profile_options.account_address()?,
             self.operator,
@@ -491,16 +461,19 @@ impl CliCommand<TransactionSummary> for CreateStakingContract {
             self.txn_options.prompt_options,
         )?;
 
-        self.txn_options
-            .submit_transaction(aptos_stdlib::staking_contract_create_staking_contract(
-                self.operator,
-                self.voter,
-                self.amount,
-                self.commission_percentage,
-                vec![],
-            ))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::staking_contract_create_staking_contract(
+                    self.operator,
+                    self.voter,
+                    self.amount,
+                    self.commission_percentage,
+                    vec![],
+                ),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -514,20 +487,26 @@ pub struct DistributeVestedCoins {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for DistributeVestedCoins {
+impl CliCommand<SubmitResult<TransactionSummary>> for DistributeVestedCoins {
     fn command_name(&self) -> &'static str {
         "DistributeVestedCoins"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let vesting_contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
-        self.txn_options
-            .submit_transaction(aptos_stdlib::vesting_distribute(vesting_contract_address))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::vesting_distribute(vesting_contract_address),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -545,20 +524,26 @@ pub struct UnlockVestedCoins {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for UnlockVestedCoins {
+impl CliCommand<SubmitResult<TransactionSummary>> for UnlockVestedCoins {
     fn command_name(&self) -> &'static str {
         "UnlockVestedCoins"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let vesting_contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
-        self.txn_options
-            .submit_transaction(aptos_stdlib::vesting_vest(vesting_contract_address))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::vesting_vest(vesting_contract_address),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
 
@@ -579,15 +564,18 @@ pub struct RequestCommission {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputUnsignedOptions,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RequestCommission {
+impl CliCommand<SubmitResult<TransactionSummary>> for RequestCommission {
     fn command_name(&self) -> &'static str {
         "RequestCommission"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<SubmitResult<TransactionSummary>> {
         let client = self
             .txn_options
             .rest_options
@@ -608,12 +596,15 @@ impl CliCommand<TransactionSummary> for RequestCommission {
         } else {
             self.owner_address
         };
-        self.txn_options
-            .submit_transaction(aptos_stdlib::staking_contract_request_commission(
-                staker_address,
-                self.operator_address,
-            ))
+        self.output_options
+            .submit_transaction(
+                &self.txn_options,
+                aptos_stdlib::staking_contract_request_commission(
+                    staker_address,
+                    self.operator_address,
+                ),
+            )
             .await
-            .map(|inner| inner.into())
+            .map(|result| result.map(TransactionSummary::from))
     }
 }
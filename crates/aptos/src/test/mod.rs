@@ -13,16 +13,18 @@ use crate::common::init::{InitTool, Network};
 use crate::common::types::{
     account_address_from_public_key, AccountAddressWrapper, CliError, CliTypedResult,
     EncodingOptions, FaucetOptions, GasOptions, KeyType, MoveManifestAccountWrapper,
-    MovePackageDir, OptionalPoolAddressArgs, PrivateKeyInputOptions, PromptOptions,
-    PublicKeyInputOptions, RestOptions, RngArgs, SaveFile, TransactionOptions, TransactionSummary,
+    MovePackageDir, OptionalPoolAddressArgs, OutputUnsignedOptions, PrivateKeyInputOptions,
+    PromptOptions, PublicKeyInputOptions, RestOptions, RngArgs, SaveFile, SubmitResult,
+    TransactionOptions, TransactionSummary,
 };
 
 #[cfg(feature = "cli-framework-test-move")]
 use crate::common::utils::write_to_file;
 
 use crate::move_tool::{
-    ArgWithType, CompilePackage, DownloadPackage, FrameworkPackageArgs, IncludedArtifacts,
-    IncludedArtifactsArgs, InitPackage, MemberId, PublishPackage, RunFunction, TestPackage,
+    ArgWithType, CompilePackage, DownloadPackage, EntryFunctionArguments, FrameworkPackageArgs,
+    IncludedArtifacts, IncludedArtifactsArgs, InitPackage, MemberId, PublishPackage, RunFunction,
+    TestPackage,
 };
 use crate::node::{
     AnalyzeMode, AnalyzeValidatorPerformance, GetStakePool, InitializeValidator, JoinValidatorSet,
@@ -35,6 +37,7 @@ use crate::stake::{
     AddStake, IncreaseLockup, InitializeStakeOwner, SetDelegatedVoter, SetOperator, UnlockStake,
     WithdrawStake,
 };
+use crate::transaction::{SignTransaction, SubmitTransaction};
 use crate::CliCommand;
 use aptos_config::config::Peer;
 use aptos_crypto::ed25519::Ed25519PublicKey;
@@ -161,9 +164,11 @@ impl CliTestFramework {
         CreateAccount {
             txn_options: self.transaction_options(sender_index, None),
             account: self.account_id(index),
+            output_options: Default::default(),
         }
         .execute()
-        .await?;
+        .await?
+        .into_submitted()?;
 
         Ok(index)
     }
@@ -262,9 +267,11 @@ impl CliTestFramework {
             txn_options: self.transaction_options(sender_index, gas_options),
             account: self.account_id(receiver_index),
             amount,
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn transfer_invalid_addr(
@@ -274,24 +281,87 @@ impl CliTestFramework {
         gas_options: Option<GasOptions>,
     ) -> CliTypedResult<TransactionSummary> {
         RunFunction {
-            function_id: MemberId {
-                module_id: ModuleId::new(
-                    AccountAddress::ONE,
-                    Identifier::from_str("coin").unwrap(),
-                ),
-                member_id: Identifier::from_str("transfer").unwrap(),
+            entry_function_args: EntryFunctionArguments {
+                function_id: MemberId {
+                    module_id: ModuleId::new(
+                        AccountAddress::ONE,
+                        Identifier::from_str("coin").unwrap(),
+                    ),
+                    member_id: Identifier::from_str("transfer").unwrap(),
+                },
+                args: vec![
+                    ArgWithType::from_str("address:0xdeadbeefcafebabe").unwrap(),
+                    ArgWithType::from_str(&format!("u64:{}", amount)).unwrap(),
+                ],
+                type_args: vec![MoveType::Struct(MoveStructTag::new(
+                    AccountAddress::ONE.into(),
+                    IdentifierWrapper::from_str("aptos_coin").unwrap(),
+                    IdentifierWrapper::from_str("AptosCoin").unwrap(),
+                    vec![],
+                ))],
             },
-            args: vec![
-                ArgWithType::from_str("address:0xdeadbeefcafebabe").unwrap(),
-                ArgWithType::from_str(&format!("u64:{}", amount)).unwrap(),
-            ],
-            type_args: vec![MoveType::Struct(MoveStructTag::new(
-                AccountAddress::ONE.into(),
-                IdentifierWrapper::from_str("aptos_coin").unwrap(),
-                IdentifierWrapper::from_str("AptosCoin").unwrap(),
-                vec![],
-            ))],
             txn_options: self.transaction_options(sender_index, gas_options),
+            output_options: Default::default(),
+            profile_gas: false,
+        }
+        .execute()
+        .await?
+        .into_submitted()
+    }
+
+    pub async fn transfer_coins_unsigned(
+        &self,
+        sender_index: usize,
+        receiver_index: usize,
+        amount: u64,
+        output_file: PathBuf,
+    ) -> CliTypedResult<SubmitResult<TransferSummary>> {
+        TransferCoins {
+            txn_options: TransactionOptions {
+                private_key_options: Default::default(),
+                ..self.transaction_options(sender_index, None)
+            },
+            account: self.account_id(receiver_index),
+            amount,
+            output_options: OutputUnsignedOptions {
+                output_unsigned: Some(output_file),
+                unsigned_options: Default::default(),
+            },
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn sign_transaction(
+        &self,
+        index: usize,
+        unsigned_file: PathBuf,
+        output_file: PathBuf,
+    ) -> CliTypedResult<TransactionSummary> {
+        SignTransaction {
+            unsigned_file,
+            private_key_options: PrivateKeyInputOptions::from_private_key(self.private_key(index))
+                .unwrap(),
+            encoding_options: Default::default(),
+            profile_options: Default::default(),
+            save_file: SaveFile {
+                output_file,
+                prompt_options: PromptOptions::yes(),
+            },
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn submit_transaction(
+        &self,
+        signed_file: PathBuf,
+    ) -> CliTypedResult<TransactionSummary> {
+        SubmitTransaction {
+            signed_file,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+            prompt_options: PromptOptions::yes(),
         }
         .execute()
         .await
@@ -354,9 +424,11 @@ impl CliTestFramework {
                 full_node_host: None,
                 full_node_network_public_key: None,
             },
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn add_stake(
@@ -374,9 +446,13 @@ impl CliTestFramework {
                 }),
             ),
             amount,
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_iter()
+        .map(SubmitResult::into_submitted)
+        .collect()
     }
 
     pub async fn unlock_stake(
@@ -387,9 +463,13 @@ impl CliTestFramework {
         UnlockStake {
             txn_options: self.transaction_options(index, None),
             amount,
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_iter()
+        .map(SubmitResult::into_submitted)
+        .collect()
     }
 
     pub async fn withdraw_stake(
@@ -400,17 +480,23 @@ impl CliTestFramework {
         WithdrawStake {
             node_op_options: self.transaction_options(index, None),
             amount,
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn increase_lockup(&self, index: usize) -> CliTypedResult<Vec<TransactionSummary>> {
         IncreaseLockup {
             txn_options: self.transaction_options(index, None),
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_iter()
+        .map(SubmitResult::into_submitted)
+        .collect()
     }
 
     pub async fn join_validator_set(
@@ -421,9 +507,11 @@ impl CliTestFramework {
         JoinValidatorSet {
             txn_options: self.transaction_options(operator_index, None),
             operator_args: self.operator_args(pool_index),
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn leave_validator_set(
@@ -434,9 +522,11 @@ impl CliTestFramework {
         LeaveValidatorSet {
             txn_options: self.transaction_options(operator_index, None),
             operator_args: self.operator_args(pool_index),
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn update_validator_network_addresses(
@@ -458,9 +548,11 @@ impl CliTestFramework {
                 full_node_host: None,
                 full_node_network_public_key: None,
             },
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn analyze_validator_performance(
@@ -497,9 +589,11 @@ impl CliTestFramework {
                 consensus_public_key: Some(consensus_public_key),
                 proof_of_possession: Some(proof_of_possession),
             },
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn init(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<()> {
@@ -550,9 +644,11 @@ impl CliTestFramework {
             initial_stake_amount,
             operator_address: operator_index.map(|idx| self.account_id(idx)),
             voter_address: voter_index.map(|idx| self.account_id(idx)),
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn create_stake_pool(
@@ -564,20 +660,25 @@ impl CliTestFramework {
         commission_percentage: u64,
     ) -> CliTypedResult<TransactionSummary> {
         RunFunction {
-            function_id: MemberId::from_str("0x1::staking_contract::create_staking_contract")
-                .unwrap(),
-            args: vec![
-                ArgWithType::address(self.account_id(operator_index)),
-                ArgWithType::address(self.account_id(voter_index)),
-                ArgWithType::u64(amount),
-                ArgWithType::u64(commission_percentage),
-                ArgWithType::bytes(vec![]),
-            ],
-            type_args: vec![],
+            entry_function_args: EntryFunctionArguments {
+                function_id: MemberId::from_str("0x1::staking_contract::create_staking_contract")
+                    .unwrap(),
+                args: vec![
+                    ArgWithType::address(self.account_id(operator_index)),
+                    ArgWithType::address(self.account_id(voter_index)),
+                    ArgWithType::u64(amount),
+                    ArgWithType::u64(commission_percentage),
+                    ArgWithType::bytes(vec![]),
+                ],
+                type_args: vec![],
+            },
             txn_options: self.transaction_options(owner_index, None),
+            output_options: Default::default(),
+            profile_gas: false,
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub async fn set_operator(
@@ -588,9 +689,13 @@ impl CliTestFramework {
         SetOperator {
             txn_options: self.transaction_options(owner_index, None),
             operator_address: self.account_id(operator_index),
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_iter()
+        .map(SubmitResult::into_submitted)
+        .collect()
     }
 
     pub async fn set_delegated_voter(
@@ -601,9 +706,13 @@ impl CliTestFramework {
         SetDelegatedVoter {
             txn_options: self.transaction_options(owner_index, None),
             voter_address: self.account_id(voter_index),
+            output_options: Default::default(),
         }
         .execute()
-        .await
+        .await?
+        .into_iter()
+        .map(SubmitResult::into_submitted)
+        .collect()
     }
 
     /// Wait for an account to exist
@@ -877,13 +986,18 @@ impl CliTestFramework {
         }
 
         RunFunction {
+            entry_function_args: EntryFunctionArguments {
+                function_id,
+                args: parsed_args,
+                type_args: parsed_type_args,
+            },
             txn_options: self.transaction_options(index, gas_options),
-            function_id,
-            args: parsed_args,
-            type_args: parsed_type_args,
+            output_options: Default::default(),
+            profile_gas: false,
         }
         .execute()
-        .await
+        .await?
+        .into_submitted()
     }
 
     pub fn move_options(&self, account_strs: BTreeMap<&str, &str>) -> MovePackageDir {
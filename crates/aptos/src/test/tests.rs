@@ -2,9 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    common::types::{CliCommand, PrivateKeyInputOptions, PromptOptions, SaveFile},
     move_tool::{ArgWithType, FunctionArgType},
+    multisig::{
+        CombineSignatures, DeriveMultisigAddress, MultisigKeyArgs, SignMultisigTransaction,
+    },
     CliResult, Tool,
 };
+use aptos_crypto::PrivateKey;
+use aptos_keygen::KeyGen;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    chain_id::ChainId,
+    transaction::{RawTransaction, SignedTransaction},
+};
+use cached_packages::aptos_stdlib;
 use clap::Parser;
 use std::str::FromStr;
 
@@ -30,6 +42,10 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "config", "show-global-config"]).await;
     assert_cmd_not_panic(&["aptos", "config", "show-profiles"]).await;
 
+    assert_cmd_not_panic(&["aptos", "debug"]).await;
+    assert_cmd_not_panic(&["aptos", "debug", "replay", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "debug", "state-diff", "--help"]).await;
+
     assert_cmd_not_panic(&["aptos", "genesis"]).await;
     assert_cmd_not_panic(&["aptos", "genesis", "generate-genesis", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "genesis", "generate-keys", "--help"]).await;
@@ -65,13 +81,21 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "move", "test", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "transactional-test", "--help"]).await;
 
+    assert_cmd_not_panic(&["aptos", "multisig"]).await;
+    assert_cmd_not_panic(&["aptos", "multisig", "combine", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "multisig", "derive-address", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "multisig", "sign", "--help"]).await;
+
     assert_cmd_not_panic(&["aptos", "node"]).await;
     assert_cmd_not_panic(&["aptos", "node", "get-stake-pool", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "analyze-validator-performance", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "bootstrap-db-from-backup", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "node", "export-safety-data", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "node", "import-safety-data", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "initialize-validator", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "join-validator-set", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "leave-validator-set", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "node", "merge-safety-data", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "run-local-testnet", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "show-validator-config", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "node", "show-validator-set", "--help"]).await;
@@ -93,6 +117,14 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "stake", "set-operator", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "unlock-stake", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "withdraw-stake", "--help"]).await;
+
+    assert_cmd_not_panic(&["aptos", "transaction"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "add-signature", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "build", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "build-multi-agent", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "sign", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "submit", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "submit-multi-agent", "--help"]).await;
 }
 
 /// Ensure we can parse URLs for args
@@ -106,6 +138,96 @@ async fn ensure_can_parse_args_with_urls() {
     );
 }
 
+/// Signs a transaction with 2 of 3 keys of a multisig account, without any network
+#[tokio::test]
+async fn test_multisig_signing() {
+    let mut keygen = KeyGen::from_seed([7u8; 32]);
+    let private_keys: Vec<_> = (0..3)
+        .map(|_| keygen.generate_ed25519_private_key())
+        .collect();
+    let key_args = || MultisigKeyArgs {
+        public_keys: private_keys.iter().map(|key| key.public_key()).collect(),
+        threshold: 2,
+    };
+    let account = DeriveMultisigAddress {
+        key_args: key_args(),
+    }
+    .execute()
+    .await
+    .unwrap();
+
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let unsigned_file = dir.path().join("txn.unsigned");
+    let raw_txn = RawTransaction::new(
+        account.account_address,
+        0,
+        aptos_stdlib::aptos_account_transfer(account.account_address, 1),
+        1000,
+        100,
+        u64::MAX,
+        ChainId::test(),
+    );
+    std::fs::write(&unsigned_file, bcs::to_bytes(&raw_txn).unwrap()).unwrap();
+
+    // Sign with the last and the first key, the order of the signatures shouldn't matter
+    let mut signature_files = vec![];
+    for index in [2, 0] {
+        let signature_file = dir.path().join(format!("txn.signature.{}", index));
+        SignMultisigTransaction {
+            unsigned_file: unsigned_file.clone(),
+            private_key_options: PrivateKeyInputOptions::from_private_key(&private_keys[index])
+                .unwrap(),
+            encoding_options: Default::default(),
+            profile_options: Default::default(),
+            save_file: SaveFile {
+                output_file: signature_file.clone(),
+                prompt_options: PromptOptions::yes(),
+            },
+        }
+        .execute()
+        .await
+        .unwrap();
+        signature_files.push(signature_file);
+    }
+
+    let combine = |signature_files: Vec<_>, output_file| CombineSignatures {
+        unsigned_file: unsigned_file.clone(),
+        signature_files,
+        key_args: key_args(),
+        save_file: SaveFile {
+            output_file,
+            prompt_options: PromptOptions::yes(),
+        },
+    };
+    // A single signature is below the threshold
+    combine(
+        signature_files[..1].to_vec(),
+        dir.path().join("txn.partially_signed"),
+    )
+    .execute()
+    .await
+    .unwrap_err();
+
+    // The keys in another order are the ones of another account
+    let mut reordered = combine(
+        signature_files.clone(),
+        dir.path().join("txn.wrong_account"),
+    );
+    reordered.key_args.public_keys.reverse();
+    reordered.execute().await.unwrap_err();
+
+    let signed_file = dir.path().join("txn.signed");
+    let summary = combine(signature_files, signed_file.clone())
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(Some(account.account_address), summary.sender);
+    let signed_txn: SignedTransaction =
+        bcs::from_bytes(&std::fs::read(signed_file).unwrap()).unwrap();
+    signed_txn.check_signature().unwrap();
+}
+
 async fn assert_cmd_not_panic(args: &[&str]) {
     // When a command fails, it will have a panic in it due to an improperly setup command
     // thread 'main' panicked at 'Command propose: Argument names must be unique, but 'assume-yes' is
@@ -0,0 +1,424 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            account_address_from_public_key, CliCommand, CliError, CliResult, CliTypedResult,
+            EncodingOptions, PrivateKeyInputOptions, ProfileOptions, PromptOptions, RestOptions,
+            SaveFile, TransactionOptions, TransactionSummary, UnsignedTransactionOptions,
+            UnsignedTransactionSummary,
+        },
+        utils::{check_if_file_exists, prompt_yes_with_override, read_from_file, write_to_file},
+    },
+    genesis::git::{from_yaml, to_yaml},
+    move_tool::EntryFunctionArguments,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    PrivateKey, SigningKey, ValidCryptoMaterialStringExt,
+};
+use aptos_rest_client::aptos_api_types::HexEncodedBytes;
+use aptos_sdk::transaction_builder::MultiAgentSigningRequest;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{authenticator::AccountAuthenticator, RawTransaction, SignedTransaction},
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// Tool for signing transactions offline
+///
+/// Transactions are built online with the sender's address only, then signed on a machine
+/// holding the private key, which doesn't need a connection to the network, and finally
+/// submitted from any machine.
+#[derive(Subcommand)]
+pub enum TransactionTool {
+    AddSignature(AddMultiAgentSignature),
+    Build(BuildTransaction),
+    BuildMultiAgent(BuildMultiAgentTransaction),
+    Sign(SignTransaction),
+    Submit(SubmitTransaction),
+    SubmitMultiAgent(SubmitMultiAgentTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::AddSignature(tool) => tool.execute_serialized().await,
+            TransactionTool::Build(tool) => tool.execute_serialized().await,
+            TransactionTool::BuildMultiAgent(tool) => tool.execute_serialized().await,
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+            TransactionTool::SubmitMultiAgent(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Build an unsigned transaction running a Move function
+///
+/// The sequence number, gas unit price and chain id are looked up from the network, but the
+/// private key of the sender isn't needed.  The transaction is written as BCS to the output file.
+#[derive(Parser)]
+pub struct BuildTransaction {
+    #[clap(flatten)]
+    pub(crate) entry_function_args: EntryFunctionArguments,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) unsigned_options: UnsignedTransactionOptions,
+
+    /// Output file for the unsigned transaction
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<UnsignedTransactionSummary> for BuildTransaction {
+    fn command_name(&self) -> &'static str {
+        "BuildTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<UnsignedTransactionSummary> {
+        check_if_file_exists(&self.output_file, self.txn_options.prompt_options)?;
+        let payload = self.entry_function_args.create_payload()?;
+        let raw_txn = self
+            .txn_options
+            .build_unsigned_transaction(payload, &self.unsigned_options)
+            .await?;
+        write_to_file(
+            &self.output_file,
+            "Unsigned transaction",
+            &bcs::to_bytes(&raw_txn).map_err(|err| CliError::BCS("RawTransaction", err))?,
+        )?;
+        Ok(UnsignedTransactionSummary::from(&raw_txn))
+    }
+}
+
+/// Sign an unsigned transaction
+///
+/// This doesn't connect to the network, so it can run on an air-gapped machine.  The signed
+/// transaction is written as BCS to the output file.
+#[derive(Parser)]
+pub struct SignTransaction {
+    /// Unsigned transaction file, from `aptos transaction build` or `--output-unsigned`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) unsigned_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        self.save_file.check_file()?;
+        let raw_txn = read_unsigned_transaction(&self.unsigned_file)?;
+        let private_key = self
+            .private_key_options
+            .extract_private_key(self.encoding_options.encoding, &self.profile_options)?;
+
+        prompt_sign_transaction(&raw_txn, self.save_file.prompt_options)?;
+        let signed_txn = raw_txn
+            .sign(&private_key, private_key.public_key())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .into_inner();
+        save_signed_transaction(&self.save_file, signed_txn)
+    }
+}
+
+/// Reads an unsigned transaction, from `aptos transaction build` or `--output-unsigned`
+pub(crate) fn read_unsigned_transaction(path: &Path) -> CliTypedResult<RawTransaction> {
+    bcs::from_bytes(&read_from_file(path)?).map_err(|err| CliError::BCS("RawTransaction", err))
+}
+
+/// The signer can't check the transaction against the chain, so show what is being signed
+pub(crate) fn prompt_sign_transaction(
+    raw_txn: &RawTransaction,
+    prompt_options: PromptOptions,
+) -> CliTypedResult<()> {
+    prompt_yes_with_override(
+        &format!("Do you want to sign this transaction?\n{:#?}\n", raw_txn),
+        prompt_options,
+    )
+}
+
+/// Writes a signed transaction, to be submitted with `aptos transaction submit`
+pub(crate) fn save_signed_transaction(
+    save_file: &SaveFile,
+    signed_txn: SignedTransaction,
+) -> CliTypedResult<TransactionSummary> {
+    save_file.save_to_file(
+        "Signed transaction",
+        &bcs::to_bytes(&signed_txn).map_err(|err| CliError::BCS("SignedTransaction", err))?,
+    )?;
+
+    Ok(TransactionSummary {
+        transaction_hash: signed_txn.clone().committed_hash().into(),
+        gas_used: None,
+        gas_unit_price: Some(signed_txn.gas_unit_price()),
+        pending: None,
+        sender: Some(signed_txn.sender()),
+        sequence_number: Some(signed_txn.sequence_number()),
+        success: None,
+        timestamp_us: None,
+        version: None,
+        vm_status: None,
+    })
+}
+
+/// Submit a signed transaction, and wait for it to be committed
+#[derive(Parser)]
+pub struct SubmitTransaction {
+    /// Signed transaction file, from `aptos transaction sign`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) signed_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let signed_txn: SignedTransaction = bcs::from_bytes(&read_from_file(&self.signed_file)?)
+            .map_err(|err| CliError::BCS("SignedTransaction", err))?;
+        prompt_yes_with_override(
+            &format!(
+                "Do you want to submit transaction {} from {} with a maximum of {} Octas?",
+                signed_txn.clone().committed_hash(),
+                signed_txn.sender(),
+                signed_txn
+                    .max_gas_amount()
+                    .saturating_mul(signed_txn.gas_unit_price())
+            ),
+            self.prompt_options,
+        )?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        client
+            .submit_and_wait(&signed_txn)
+            .await
+            .map(|response| TransactionSummary::from(response.into_inner()))
+            .map_err(|err| CliError::ApiError(err.to_string()))
+    }
+}
+
+/// Build a multi-agent transaction running a Move function
+///
+/// The transaction is signed by the sender and by each of the secondary signers, who may be on
+/// separate machines.  The signing request is written to the output file, each signer adds its
+/// signature with `aptos transaction add-signature`, then the transaction is submitted with
+/// `aptos transaction submit-multi-agent`.
+#[derive(Parser)]
+pub struct BuildMultiAgentTransaction {
+    #[clap(flatten)]
+    pub(crate) entry_function_args: EntryFunctionArguments,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) unsigned_options: UnsignedTransactionOptions,
+
+    /// Addresses of the secondary signers, separated by spaces
+    #[clap(long, required = true, multiple_values = true, parse(try_from_str = crate::common::types::load_account_arg))]
+    pub(crate) secondary_signers: Vec<AccountAddress>,
+
+    /// Output file for the signing request
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<MultiAgentRequestSummary> for BuildMultiAgentTransaction {
+    fn command_name(&self) -> &'static str {
+        "BuildMultiAgentTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultiAgentRequestSummary> {
+        check_if_file_exists(&self.output_file, self.txn_options.prompt_options)?;
+        let payload = self.entry_function_args.create_payload()?;
+        let raw_txn = self
+            .txn_options
+            .build_unsigned_transaction(payload, &self.unsigned_options)
+            .await?;
+        let request = MultiAgentSigningRequest::new(raw_txn, self.secondary_signers)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        write_to_file(
+            &self.output_file,
+            "Multi-agent signing request",
+            to_yaml(&request)?.as_bytes(),
+        )?;
+        MultiAgentRequestSummary::new(&request)
+    }
+}
+
+/// Add a signature to a multi-agent transaction
+///
+/// The signature is made with the private key, unless it's given with `--signature` and
+/// `--public-key`.  This allows signing the `signing_message` elsewhere, e.g. as returned by the
+/// `/transactions/encode_submission` API.  The request file is updated in place.
+#[derive(Parser)]
+pub struct AddMultiAgentSignature {
+    /// Signing request file, from `aptos transaction build-multi-agent`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) request_file: PathBuf,
+
+    /// Address of the signer
+    ///
+    /// Defaults to the profile's account, or the address derived from the key
+    #[clap(long, parse(try_from_str = crate::common::types::load_account_arg))]
+    pub(crate) signer_account: Option<AccountAddress>,
+
+    /// Hex encoded signature of the signing message, made without the CLI
+    #[clap(long, requires = "public_key", parse(try_from_str = Ed25519Signature::from_encoded_string))]
+    pub(crate) signature: Option<Ed25519Signature>,
+
+    /// Hex encoded public key of the `--signature`
+    #[clap(long, requires = "signature", parse(try_from_str = Ed25519PublicKey::from_encoded_string))]
+    pub(crate) public_key: Option<Ed25519PublicKey>,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<MultiAgentRequestSummary> for AddMultiAgentSignature {
+    fn command_name(&self) -> &'static str {
+        "AddMultiAgentSignature"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultiAgentRequestSummary> {
+        let mut request = read_multi_agent_request(&self.request_file)?;
+
+        let (signer, authenticator) = if let (Some(signature), Some(public_key)) =
+            (self.signature, self.public_key)
+        {
+            let signer = self
+                .signer_account
+                .unwrap_or_else(|| account_address_from_public_key(&public_key));
+            (signer, AccountAuthenticator::ed25519(public_key, signature))
+        } else {
+            let (private_key, signer) = self.private_key_options.extract_private_key_and_address(
+                self.encoding_options.encoding,
+                &self.profile_options,
+                self.signer_account,
+            )?;
+            prompt_sign_transaction(request.raw_transaction(), self.prompt_options)?;
+            let signature = private_key
+                .sign(&request.message())
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            (
+                signer,
+                AccountAuthenticator::ed25519(private_key.public_key(), signature),
+            )
+        };
+        request
+            .add_signature(signer, authenticator)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+
+        write_to_file(
+            &self.request_file,
+            "Multi-agent signing request",
+            to_yaml(&request)?.as_bytes(),
+        )?;
+        MultiAgentRequestSummary::new(&request)
+    }
+}
+
+/// Submit a multi-agent transaction, once every signer has added its signature
+#[derive(Parser)]
+pub struct SubmitMultiAgentTransaction {
+    /// Signing request file, from `aptos transaction build-multi-agent`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) request_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitMultiAgentTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitMultiAgentTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let signed_txn = read_multi_agent_request(&self.request_file)?
+            .into_signed_transaction()
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        client
+            .submit_and_wait(&signed_txn)
+            .await
+            .map(|response| TransactionSummary::from(response.into_inner()))
+            .map_err(|err| CliError::ApiError(err.to_string()))
+    }
+}
+
+fn read_multi_agent_request(path: &Path) -> CliTypedResult<MultiAgentSigningRequest> {
+    from_yaml(
+        &String::from_utf8(read_from_file(path)?).map_err(|err| {
+            CliError::UnableToReadFile(path.display().to_string(), err.to_string())
+        })?,
+    )
+}
+
+/// A summary of a multi-agent signing request
+#[derive(Clone, Debug, Serialize)]
+pub struct MultiAgentRequestSummary {
+    pub sender: AccountAddress,
+    pub secondary_signers: Vec<AccountAddress>,
+    pub missing_signers: Vec<AccountAddress>,
+    /// Message to sign, the same as returned by `/transactions/encode_submission`
+    pub signing_message: HexEncodedBytes,
+}
+
+impl MultiAgentRequestSummary {
+    fn new(request: &MultiAgentSigningRequest) -> CliTypedResult<Self> {
+        Ok(MultiAgentRequestSummary {
+            sender: request.raw_transaction().sender(),
+            secondary_signers: request.secondary_signer_addresses().to_vec(),
+            missing_signers: request.missing_signers(),
+            signing_message: request
+                .signing_message()
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                .into(),
+        })
+    }
+}
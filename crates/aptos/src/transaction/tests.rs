@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::utils::write_to_file, genesis::git::to_yaml, transaction::read_multi_agent_request,
+};
+use aptos_keygen::KeyGen;
+use aptos_sdk::{
+    transaction_builder::{aptos_stdlib, MultiAgentSigningRequest, TransactionFactory},
+    types::{chain_id::ChainId, AccountKey, LocalAccount},
+};
+use aptos_temppath::TempPath;
+
+fn generate_account(keygen: &mut KeyGen) -> LocalAccount {
+    let key = AccountKey::from_private_key(keygen.generate_ed25519_private_key());
+    let address = key.authentication_key().derived_address();
+    LocalAccount::new(address, key, 0)
+}
+
+#[test]
+fn test_multi_agent_request_yaml_round_trip() {
+    let mut keygen = KeyGen::from_os_rng();
+    let sender = generate_account(&mut keygen);
+    let secondary_signers = vec![generate_account(&mut keygen), generate_account(&mut keygen)];
+    let raw_txn = TransactionFactory::new(ChainId::test())
+        .payload(aptos_stdlib::aptos_coin_transfer(
+            secondary_signers[0].address(),
+            100,
+        ))
+        .sender(sender.address())
+        .sequence_number(0)
+        .build();
+    let mut request = MultiAgentSigningRequest::new(
+        raw_txn,
+        secondary_signers
+            .iter()
+            .map(|account| account.address())
+            .collect(),
+    )
+    .unwrap();
+    secondary_signers[1]
+        .sign_multi_agent_request(&mut request)
+        .unwrap();
+
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let request_file = dir.path().join("request.yaml");
+    let yaml = to_yaml(&request).unwrap();
+    write_to_file(&request_file, "Request", yaml.as_bytes()).unwrap();
+    assert_eq!(read_multi_agent_request(&request_file).unwrap(), request);
+
+    // A request whose signatures don't line up with its signers is rejected
+    let mut tampered: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    tampered["secondary_signatures"]
+        .as_sequence_mut()
+        .unwrap()
+        .push(serde_yaml::Value::Null);
+    let tampered_yaml = to_yaml(&tampered).unwrap();
+    write_to_file(&request_file, "Request", tampered_yaml.as_bytes()).unwrap();
+    read_multi_agent_request(&request_file).unwrap_err();
+}
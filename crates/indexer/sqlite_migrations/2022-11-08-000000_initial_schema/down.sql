@@ -0,0 +1,31 @@
+DROP TABLE IF EXISTS write_set_changes;
+DROP TABLE IF EXISTS user_transactions;
+DROP TABLE IF EXISTS transactions;
+DROP TABLE IF EXISTS tokens;
+DROP TABLE IF EXISTS token_ownerships;
+DROP TABLE IF EXISTS token_datas;
+DROP TABLE IF EXISTS token_activities;
+DROP TABLE IF EXISTS table_metadatas;
+DROP TABLE IF EXISTS table_items;
+DROP TABLE IF EXISTS signatures;
+DROP TABLE IF EXISTS proposal_votes;
+DROP TABLE IF EXISTS processor_statuses;
+DROP TABLE IF EXISTS processor_status;
+DROP TABLE IF EXISTS move_resources;
+DROP TABLE IF EXISTS move_modules;
+DROP TABLE IF EXISTS ledger_infos;
+DROP TABLE IF EXISTS indexer_status;
+DROP TABLE IF EXISTS events;
+DROP TABLE IF EXISTS current_token_pending_claims;
+DROP TABLE IF EXISTS current_token_ownerships;
+DROP TABLE IF EXISTS current_token_datas;
+DROP TABLE IF EXISTS current_staking_pool_voter;
+DROP TABLE IF EXISTS current_collection_datas;
+DROP TABLE IF EXISTS current_coin_balances;
+DROP TABLE IF EXISTS current_ans_lookup;
+DROP TABLE IF EXISTS collection_datas;
+DROP TABLE IF EXISTS coin_supply;
+DROP TABLE IF EXISTS coin_infos;
+DROP TABLE IF EXISTS coin_balances;
+DROP TABLE IF EXISTS coin_activities;
+DROP TABLE IF EXISTS block_metadata_transactions;
@@ -0,0 +1,418 @@
+-- SQLite counterpart of the Postgres migrations, for local development and tests. Tables match
+-- `src/schema.rs`, with `jsonb` and `numeric` columns stored as text (see `src/sql_types.rs`).
+-- Only the indexes needed by the processors are created.
+CREATE TABLE block_metadata_transactions (
+  version BIGINT NOT NULL,
+  block_height BIGINT NOT NULL,
+  id VARCHAR NOT NULL,
+  round BIGINT NOT NULL,
+  epoch BIGINT NOT NULL,
+  previous_block_votes_bitvec TEXT NOT NULL,
+  proposer VARCHAR NOT NULL,
+  failed_proposer_indices TEXT NOT NULL,
+  "timestamp" TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (version)
+);
+CREATE TABLE coin_activities (
+  transaction_version BIGINT NOT NULL,
+  event_account_address VARCHAR NOT NULL,
+  event_creation_number BIGINT NOT NULL,
+  event_sequence_number BIGINT NOT NULL,
+  owner_address VARCHAR NOT NULL,
+  coin_type VARCHAR NOT NULL,
+  amount TEXT NOT NULL,
+  activity_type VARCHAR NOT NULL,
+  is_gas_fee BOOLEAN NOT NULL,
+  is_transaction_success BOOLEAN NOT NULL,
+  entry_function_id_str VARCHAR,
+  block_height BIGINT NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, event_account_address, event_creation_number, event_sequence_number)
+);
+CREATE TABLE coin_balances (
+  transaction_version BIGINT NOT NULL,
+  owner_address VARCHAR NOT NULL,
+  coin_type_hash VARCHAR NOT NULL,
+  coin_type VARCHAR NOT NULL,
+  amount TEXT NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, owner_address, coin_type_hash)
+);
+CREATE TABLE coin_infos (
+  coin_type_hash VARCHAR NOT NULL,
+  coin_type VARCHAR NOT NULL,
+  transaction_version_created BIGINT NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  symbol VARCHAR NOT NULL,
+  decimals INT NOT NULL,
+  transaction_created_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  supply_aggregator_table_handle VARCHAR,
+  supply_aggregator_table_key TEXT,
+  PRIMARY KEY (coin_type_hash)
+);
+CREATE INDEX ci_ct_index ON coin_infos (coin_type);
+CREATE TABLE coin_supply (
+  transaction_version BIGINT NOT NULL,
+  coin_type_hash VARCHAR NOT NULL,
+  coin_type VARCHAR NOT NULL,
+  supply TEXT NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  transaction_epoch BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, coin_type_hash)
+);
+CREATE TABLE collection_datas (
+  collection_data_id_hash VARCHAR NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  description TEXT NOT NULL,
+  metadata_uri VARCHAR NOT NULL,
+  supply TEXT NOT NULL,
+  maximum TEXT NOT NULL,
+  maximum_mutable BOOLEAN NOT NULL,
+  uri_mutable BOOLEAN NOT NULL,
+  description_mutable BOOLEAN NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  table_handle VARCHAR NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  PRIMARY KEY (collection_data_id_hash, transaction_version)
+);
+CREATE TABLE current_ans_lookup (
+  domain VARCHAR NOT NULL,
+  subdomain VARCHAR NOT NULL,
+  registered_address VARCHAR,
+  expiration_timestamp TIMESTAMP NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  token_name VARCHAR NOT NULL DEFAULT '',
+  PRIMARY KEY (domain, subdomain)
+);
+CREATE TABLE current_coin_balances (
+  owner_address VARCHAR NOT NULL,
+  coin_type_hash VARCHAR NOT NULL,
+  coin_type VARCHAR NOT NULL,
+  amount TEXT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  last_transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (owner_address, coin_type_hash)
+);
+CREATE TABLE current_collection_datas (
+  collection_data_id_hash VARCHAR NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  description TEXT NOT NULL,
+  metadata_uri VARCHAR NOT NULL,
+  supply TEXT NOT NULL,
+  maximum TEXT NOT NULL,
+  maximum_mutable BOOLEAN NOT NULL,
+  uri_mutable BOOLEAN NOT NULL,
+  description_mutable BOOLEAN NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  table_handle VARCHAR NOT NULL,
+  last_transaction_timestamp TIMESTAMP NOT NULL,
+  PRIMARY KEY (collection_data_id_hash)
+);
+CREATE INDEX curr_cd_th_index ON current_collection_datas (table_handle);
+CREATE TABLE current_staking_pool_voter (
+  staking_pool_address VARCHAR NOT NULL,
+  voter_address VARCHAR NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (staking_pool_address)
+);
+CREATE TABLE current_token_datas (
+  token_data_id_hash VARCHAR NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  maximum TEXT NOT NULL,
+  supply TEXT NOT NULL,
+  largest_property_version TEXT NOT NULL,
+  metadata_uri VARCHAR NOT NULL,
+  payee_address VARCHAR NOT NULL,
+  royalty_points_numerator TEXT NOT NULL,
+  royalty_points_denominator TEXT NOT NULL,
+  maximum_mutable BOOLEAN NOT NULL,
+  uri_mutable BOOLEAN NOT NULL,
+  description_mutable BOOLEAN NOT NULL,
+  properties_mutable BOOLEAN NOT NULL,
+  royalty_mutable BOOLEAN NOT NULL,
+  default_properties TEXT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  collection_data_id_hash VARCHAR NOT NULL,
+  last_transaction_timestamp TIMESTAMP NOT NULL,
+  description TEXT NOT NULL,
+  PRIMARY KEY (token_data_id_hash)
+);
+CREATE TABLE current_token_ownerships (
+  token_data_id_hash VARCHAR NOT NULL,
+  property_version TEXT NOT NULL,
+  owner_address VARCHAR NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  amount TEXT NOT NULL,
+  token_properties TEXT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  collection_data_id_hash VARCHAR NOT NULL,
+  table_type TEXT NOT NULL,
+  last_transaction_timestamp TIMESTAMP NOT NULL,
+  PRIMARY KEY (token_data_id_hash, property_version, owner_address)
+);
+CREATE TABLE current_token_pending_claims (
+  token_data_id_hash VARCHAR NOT NULL,
+  property_version TEXT NOT NULL,
+  from_address VARCHAR NOT NULL,
+  to_address VARCHAR NOT NULL,
+  collection_data_id_hash VARCHAR NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  amount TEXT NOT NULL,
+  table_handle VARCHAR NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  last_transaction_timestamp TIMESTAMP NOT NULL,
+  PRIMARY KEY (token_data_id_hash, property_version, from_address, to_address)
+);
+CREATE TABLE events (
+  sequence_number BIGINT NOT NULL,
+  creation_number BIGINT NOT NULL,
+  account_address VARCHAR NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  transaction_block_height BIGINT NOT NULL,
+  "type" TEXT NOT NULL,
+  data TEXT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (account_address, creation_number, sequence_number)
+);
+CREATE INDEX ev_tv_index ON events (transaction_version);
+CREATE TABLE indexer_status (
+  db VARCHAR NOT NULL,
+  is_indexer_up BOOLEAN NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (db)
+);
+CREATE TABLE ledger_infos (
+  chain_id BIGINT NOT NULL,
+  PRIMARY KEY (chain_id)
+);
+CREATE TABLE move_modules (
+  transaction_version BIGINT NOT NULL,
+  write_set_change_index BIGINT NOT NULL,
+  transaction_block_height BIGINT NOT NULL,
+  name TEXT NOT NULL,
+  address VARCHAR NOT NULL,
+  bytecode BLOB,
+  friends TEXT,
+  exposed_functions TEXT,
+  structs TEXT,
+  is_deleted BOOLEAN NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, write_set_change_index)
+);
+CREATE TABLE move_resources (
+  transaction_version BIGINT NOT NULL,
+  write_set_change_index BIGINT NOT NULL,
+  transaction_block_height BIGINT NOT NULL,
+  name TEXT NOT NULL,
+  address VARCHAR NOT NULL,
+  "type" TEXT NOT NULL,
+  module TEXT NOT NULL,
+  generic_type_params TEXT,
+  data TEXT,
+  is_deleted BOOLEAN NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, write_set_change_index)
+);
+CREATE TABLE processor_status (
+  processor VARCHAR NOT NULL,
+  last_success_version BIGINT NOT NULL,
+  last_updated TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (processor)
+);
+CREATE TABLE processor_statuses (
+  name VARCHAR NOT NULL,
+  version BIGINT NOT NULL,
+  success BOOLEAN NOT NULL,
+  details TEXT,
+  last_updated TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (name, version)
+);
+CREATE INDEX ps_name_succ_ver_index ON processor_statuses (name, success, version);
+CREATE TABLE proposal_votes (
+  transaction_version BIGINT NOT NULL,
+  proposal_id BIGINT NOT NULL,
+  voter_address VARCHAR NOT NULL,
+  staking_pool_address VARCHAR NOT NULL,
+  num_votes TEXT NOT NULL,
+  should_pass BOOLEAN NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, proposal_id, voter_address)
+);
+CREATE TABLE signatures (
+  transaction_version BIGINT NOT NULL,
+  multi_agent_index BIGINT NOT NULL,
+  multi_sig_index BIGINT NOT NULL,
+  transaction_block_height BIGINT NOT NULL,
+  signer VARCHAR NOT NULL,
+  is_sender_primary BOOLEAN NOT NULL,
+  "type" VARCHAR NOT NULL,
+  public_key VARCHAR NOT NULL,
+  signature VARCHAR NOT NULL,
+  threshold BIGINT NOT NULL,
+  public_key_indices TEXT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, multi_agent_index, multi_sig_index, is_sender_primary)
+);
+CREATE TABLE table_items (
+  "key" TEXT NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  write_set_change_index BIGINT NOT NULL,
+  transaction_block_height BIGINT NOT NULL,
+  table_handle VARCHAR NOT NULL,
+  decoded_key TEXT NOT NULL,
+  decoded_value TEXT,
+  is_deleted BOOLEAN NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, write_set_change_index)
+);
+CREATE TABLE table_metadatas (
+  handle VARCHAR NOT NULL,
+  key_type TEXT NOT NULL,
+  value_type TEXT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (handle)
+);
+CREATE TABLE token_activities (
+  transaction_version BIGINT NOT NULL,
+  event_account_address VARCHAR NOT NULL,
+  event_creation_number BIGINT NOT NULL,
+  event_sequence_number BIGINT NOT NULL,
+  collection_data_id_hash VARCHAR NOT NULL,
+  token_data_id_hash VARCHAR NOT NULL,
+  property_version TEXT NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  transfer_type VARCHAR NOT NULL,
+  from_address VARCHAR,
+  to_address VARCHAR,
+  token_amount TEXT NOT NULL,
+  coin_type TEXT,
+  coin_amount TEXT,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  PRIMARY KEY (transaction_version, event_account_address, event_creation_number, event_sequence_number)
+);
+CREATE TABLE token_datas (
+  token_data_id_hash VARCHAR NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  maximum TEXT NOT NULL,
+  supply TEXT NOT NULL,
+  largest_property_version TEXT NOT NULL,
+  metadata_uri VARCHAR NOT NULL,
+  payee_address VARCHAR NOT NULL,
+  royalty_points_numerator TEXT NOT NULL,
+  royalty_points_denominator TEXT NOT NULL,
+  maximum_mutable BOOLEAN NOT NULL,
+  uri_mutable BOOLEAN NOT NULL,
+  description_mutable BOOLEAN NOT NULL,
+  properties_mutable BOOLEAN NOT NULL,
+  royalty_mutable BOOLEAN NOT NULL,
+  default_properties TEXT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  collection_data_id_hash VARCHAR NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  description TEXT NOT NULL,
+  PRIMARY KEY (token_data_id_hash, transaction_version)
+);
+CREATE TABLE token_ownerships (
+  token_data_id_hash VARCHAR NOT NULL,
+  property_version TEXT NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  table_handle VARCHAR NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  owner_address VARCHAR,
+  amount TEXT NOT NULL,
+  table_type TEXT,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  collection_data_id_hash VARCHAR NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  PRIMARY KEY (token_data_id_hash, property_version, transaction_version, table_handle)
+);
+CREATE TABLE tokens (
+  token_data_id_hash VARCHAR NOT NULL,
+  property_version TEXT NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  creator_address VARCHAR NOT NULL,
+  collection_name VARCHAR NOT NULL,
+  name VARCHAR NOT NULL,
+  token_properties TEXT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  collection_data_id_hash VARCHAR NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  PRIMARY KEY (token_data_id_hash, property_version, transaction_version)
+);
+CREATE TABLE transactions (
+  version BIGINT NOT NULL,
+  block_height BIGINT NOT NULL,
+  hash VARCHAR NOT NULL,
+  "type" VARCHAR NOT NULL,
+  payload TEXT,
+  state_change_hash VARCHAR NOT NULL,
+  event_root_hash VARCHAR NOT NULL,
+  state_checkpoint_hash VARCHAR,
+  gas_used TEXT NOT NULL,
+  success BOOLEAN NOT NULL,
+  vm_status TEXT NOT NULL,
+  accumulator_root_hash VARCHAR NOT NULL,
+  num_events BIGINT NOT NULL,
+  num_write_set_changes BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  epoch BIGINT NOT NULL,
+  PRIMARY KEY (version)
+);
+CREATE UNIQUE INDEX txn_hash_index ON transactions (hash);
+CREATE TABLE user_transactions (
+  version BIGINT NOT NULL,
+  block_height BIGINT NOT NULL,
+  parent_signature_type VARCHAR NOT NULL,
+  sender VARCHAR NOT NULL,
+  sequence_number BIGINT NOT NULL,
+  max_gas_amount TEXT NOT NULL,
+  expiration_timestamp_secs TIMESTAMP NOT NULL,
+  gas_unit_price TEXT NOT NULL,
+  "timestamp" TIMESTAMP NOT NULL,
+  entry_function_id_str TEXT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  epoch BIGINT NOT NULL,
+  PRIMARY KEY (version)
+);
+CREATE UNIQUE INDEX ut_sender_seq_index ON user_transactions (sender, sequence_number);
+CREATE TABLE write_set_changes (
+  transaction_version BIGINT NOT NULL,
+  "index" BIGINT NOT NULL,
+  hash VARCHAR NOT NULL,
+  transaction_block_height BIGINT NOT NULL,
+  "type" TEXT NOT NULL,
+  address VARCHAR NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (transaction_version, "index")
+);
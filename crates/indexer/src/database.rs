@@ -2,19 +2,146 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Database-related functions
+//!
+//! The indexer writes either to Postgres, or to a SQLite file for local development and tests.
+//! Both backends share the schema, models and processors: queries are written once against a
+//! `DbConnection`, and `with_connection!` compiles them for each backend.
 #![allow(clippy::extra_unused_lifetimes)]
 use crate::util::remove_null_bytes;
 use diesel::{
+    backend::Backend,
+    connection::{AnsiTransactionManager, SimpleConnection, TransactionManager},
     pg::{Pg, PgConnection},
     query_builder::{AstPass, Query, QueryFragment},
-    r2d2::{ConnectionManager, PoolError, PooledConnection},
-    QueryResult, RunQueryDsl,
+    r2d2::{self, ManageConnection, PoolError, PooledConnection, R2D2Connection},
+    sqlite::{Sqlite, SqliteConnection},
+    Connection, QueryResult, RunQueryDsl,
 };
 use std::{cmp::min, sync::Arc};
 
-pub type PgPool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
-pub type PgDbPool = Arc<PgPool>;
-pub type PgPoolConnection = PooledConnection<ConnectionManager<PgConnection>>;
+pub type DbConnectionPool = r2d2::Pool<DbConnectionManager>;
+pub type DbPool = Arc<DbConnectionPool>;
+pub type DbPoolConnection = PooledConnection<DbConnectionManager>;
+
+/// Waiting for the write lock is expected, as processor tasks write to SQLite concurrently
+const SQLITE_CONNECTION_SETUP: &str = "
+    PRAGMA busy_timeout = 30000;
+    PRAGMA journal_mode = WAL;
+    PRAGMA synchronous = NORMAL;
+";
+
+/// Runs `$body` with `$inner` bound to the backend specific connection of `$conn`, a
+/// `&mut DbConnection`. The body is compiled once per backend.
+macro_rules! with_connection {
+    ($conn:expr, |$inner:ident| $body:expr) => {
+        match $conn {
+            $crate::database::DbConnection::Postgres($inner) => $body,
+            $crate::database::DbConnection::Sqlite($inner) => $body,
+        }
+    };
+}
+pub(crate) use with_connection;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DbBackend {
+    Postgres,
+    Sqlite,
+}
+
+/// A connection to either of the supported backends
+pub enum DbConnection {
+    Postgres(PgConnection),
+    Sqlite(SqliteConnection),
+}
+
+impl DbConnection {
+    pub fn backend(&self) -> DbBackend {
+        match self {
+            DbConnection::Postgres(_) => DbBackend::Postgres,
+            DbConnection::Sqlite(_) => DbBackend::Sqlite,
+        }
+    }
+
+    /// Runs `f` in a read-write transaction, which is rolled back if `f` fails
+    pub fn transaction<T, F>(&mut self, f: F) -> QueryResult<T>
+    where
+        F: FnOnce(&mut Self) -> QueryResult<T>,
+    {
+        match self {
+            DbConnection::Postgres(conn) => AnsiTransactionManager::begin_transaction(conn)?,
+            // Take the write lock up front, a deferred transaction can't upgrade its read lock
+            // while another connection writes
+            DbConnection::Sqlite(conn) => {
+                AnsiTransactionManager::begin_transaction_sql(conn, "BEGIN IMMEDIATE")?
+            }
+        }
+        match f(self) {
+            Ok(value) => {
+                with_connection!(self, |conn| AnsiTransactionManager::commit_transaction(
+                    conn
+                ))?;
+                Ok(value)
+            }
+            Err(err) => {
+                with_connection!(self, |conn| AnsiTransactionManager::rollback_transaction(
+                    conn
+                ))?;
+                Err(err)
+            }
+        }
+    }
+}
+
+impl SimpleConnection for DbConnection {
+    fn batch_execute(&mut self, query: &str) -> QueryResult<()> {
+        with_connection!(self, |conn| conn.batch_execute(query))
+    }
+}
+
+/// Opens connections for the pool, to the backend it was created for
+#[derive(Clone, Debug)]
+pub struct DbConnectionManager {
+    backend: DbBackend,
+    database_url: String,
+}
+
+impl DbConnectionManager {
+    pub fn new(backend: DbBackend, database_url: &str) -> Self {
+        Self {
+            backend,
+            database_url: database_url.to_string(),
+        }
+    }
+}
+
+impl ManageConnection for DbConnectionManager {
+    type Connection = DbConnection;
+    type Error = r2d2::Error;
+
+    fn connect(&self) -> Result<DbConnection, r2d2::Error> {
+        match self.backend {
+            DbBackend::Postgres => PgConnection::establish(&self.database_url)
+                .map(DbConnection::Postgres)
+                .map_err(r2d2::Error::ConnectionError),
+            DbBackend::Sqlite => {
+                let mut conn = SqliteConnection::establish(&self.database_url)
+                    .map_err(r2d2::Error::ConnectionError)?;
+                conn.batch_execute(SQLITE_CONNECTION_SETUP)
+                    .map_err(r2d2::Error::QueryError)?;
+                Ok(DbConnection::Sqlite(conn))
+            }
+        }
+    }
+
+    fn is_valid(&self, conn: &mut DbConnection) -> Result<(), r2d2::Error> {
+        with_connection!(conn, |conn| conn.ping()).map_err(r2d2::Error::QueryError)
+    }
+
+    fn has_broken(&self, conn: &mut DbConnection) -> bool {
+        std::thread::panicking() || with_connection!(conn, |conn| conn.is_broken())
+    }
+}
+
 #[derive(QueryId)]
 /// Using this will append a where clause at the end of the string upsert function, e.g.
 /// INSERT INTO ... ON CONFLICT DO UPDATE SET ... WHERE "transaction_version" = excluded."transaction_version"
@@ -24,6 +151,8 @@ pub struct UpsertFilterLatestTransactionQuery<T> {
     where_clause: Option<&'static str>,
 }
 
+/// The bundled SQLite is built with a limit of 250,000 bound parameters, so this holds for both
+/// backends
 pub const MAX_DIESEL_PARAM_SIZE: u16 = u16::MAX;
 
 /// Given diesel has a limit of how many parameters can be inserted in a single operation (u16::MAX)
@@ -56,25 +185,38 @@ pub fn clean_data_for_db<T: serde::Serialize + for<'de> serde::Deserialize<'de>>
     }
 }
 
-pub fn new_db_pool(database_url: &str) -> Result<PgDbPool, PoolError> {
-    let manager = ConnectionManager::<PgConnection>::new(database_url);
-    PgPool::builder().build(manager).map(Arc::new)
+/// Creates a pool of connections to the Postgres database at `database_url`
+pub fn new_db_pool(database_url: &str) -> Result<DbPool, PoolError> {
+    let manager = DbConnectionManager::new(DbBackend::Postgres, database_url);
+    DbConnectionPool::builder().build(manager).map(Arc::new)
+}
+
+/// Creates a pool of connections to the SQLite database at `database_path`, which is created if
+/// it doesn't exist. In memory databases can't be used, as they aren't shared between connections.
+pub fn new_sqlite_db_pool(database_path: &str) -> Result<DbPool, PoolError> {
+    let manager = DbConnectionManager::new(DbBackend::Sqlite, database_path);
+    DbConnectionPool::builder().build(manager).map(Arc::new)
 }
 
 pub fn execute_with_better_error<
     T: diesel::Table + diesel::QuerySource + diesel::query_builder::QueryId + 'static,
-    U: diesel::query_builder::QueryFragment<diesel::pg::Pg>
+    U: QueryFragment<Pg>
+        + QueryFragment<Sqlite>
         + diesel::query_builder::QueryId
-        + diesel::insertable::CanInsertInSingleQuery<diesel::pg::Pg>,
+        + diesel::insertable::CanInsertInSingleQuery<Pg>
+        + diesel::insertable::CanInsertInSingleQuery<Sqlite>,
 >(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     query: diesel::query_builder::InsertStatement<T, U>,
     mut additional_where_clause: Option<&'static str>,
 ) -> diesel::QueryResult<usize>
 where
-    <T as diesel::QuerySource>::FromClause: diesel::query_builder::QueryFragment<diesel::pg::Pg>,
+    <T as diesel::QuerySource>::FromClause: QueryFragment<Pg> + QueryFragment<Sqlite>,
 {
-    let original_query = diesel::debug_query::<diesel::pg::Pg, _>(&query).to_string();
+    let original_query = match conn.backend() {
+        DbBackend::Postgres => diesel::debug_query::<Pg, _>(&query).to_string(),
+        DbBackend::Sqlite => diesel::debug_query::<Sqlite, _>(&query).to_string(),
+    };
     // This is needed because if we don't insert any row, then diesel makes a call like this
     // SELECT 1 FROM TABLE WHERE 1=0
     if original_query.to_lowercase().contains("where") {
@@ -84,9 +226,12 @@ where
         query,
         where_clause: additional_where_clause,
     };
-    let debug = diesel::debug_query::<diesel::pg::Pg, _>(&final_query).to_string();
+    let debug = match conn.backend() {
+        DbBackend::Postgres => diesel::debug_query::<Pg, _>(&final_query).to_string(),
+        DbBackend::Sqlite => diesel::debug_query::<Sqlite, _>(&final_query).to_string(),
+    };
     aptos_logger::debug!("Executing query: {:?}", debug);
-    let res = final_query.execute(conn);
+    let res = with_connection!(conn, |conn| final_query.execute(conn));
     if let Err(ref e) = res {
         aptos_logger::warn!("Error running query: {:?}\n{}", e, debug);
     }
@@ -98,13 +243,14 @@ impl<T: Query> Query for UpsertFilterLatestTransactionQuery<T> {
     type SqlType = T::SqlType;
 }
 
-impl<T> RunQueryDsl<PgConnection> for UpsertFilterLatestTransactionQuery<T> {}
+impl<T, Conn> RunQueryDsl<Conn> for UpsertFilterLatestTransactionQuery<T> {}
 
-impl<T> QueryFragment<Pg> for UpsertFilterLatestTransactionQuery<T>
+impl<T, DB> QueryFragment<DB> for UpsertFilterLatestTransactionQuery<T>
 where
-    T: QueryFragment<Pg>,
+    DB: Backend,
+    T: QueryFragment<DB>,
 {
-    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, DB>) -> QueryResult<()> {
         self.query.walk_ast(out.reborrow())?;
         if let Some(w) = self.where_clause {
             out.push_sql(w);
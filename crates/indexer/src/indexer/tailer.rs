@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    database::{execute_with_better_error, PgDbPool},
+    database::{execute_with_better_error, with_connection, DbBackend, DbConnection, DbPool},
     indexer::{
         errors::TransactionProcessingError,
         fetcher::{TransactionFetcher, TransactionFetcherOptions, TransactionFetcherTrait},
@@ -19,9 +19,9 @@ use aptos_api::context::Context as ApiContext;
 use aptos_logger::{debug, info};
 use chrono::ParseError;
 use diesel::{
-    pg::upsert::excluded,
     sql_query,
     sql_types::{BigInt, Text},
+    upsert::excluded,
     ExpressionMethods, RunQueryDsl,
 };
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness};
@@ -29,18 +29,19 @@ use std::{fmt::Debug, sync::Arc};
 use tokio::{sync::Mutex, task::JoinHandle};
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+pub const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("sqlite_migrations");
 
 #[derive(Clone)]
 pub struct Tailer {
     pub transaction_fetcher: Arc<Mutex<dyn TransactionFetcherTrait>>,
     processor: Arc<dyn TransactionProcessor>,
-    connection_pool: PgDbPool,
+    connection_pool: DbPool,
 }
 
 impl Tailer {
     pub fn new(
         context: Arc<ApiContext>,
-        connection_pool: PgDbPool,
+        connection_pool: DbPool,
         processor: Arc<dyn TransactionProcessor>,
         options: TransactionFetcherOptions,
     ) -> Result<Tailer, ParseError> {
@@ -55,12 +56,15 @@ impl Tailer {
     }
 
     pub fn run_migrations(&self) {
-        let _ = &self
+        let mut conn = self
             .connection_pool
             .get()
-            .expect("Could not get connection for migrations")
-            .run_pending_migrations(MIGRATIONS)
-            .expect("migrations failed!");
+            .expect("Could not get connection for migrations");
+        let _ = match &mut *conn {
+            DbConnection::Postgres(conn) => conn.run_pending_migrations(MIGRATIONS),
+            DbConnection::Sqlite(conn) => conn.run_pending_migrations(SQLITE_MIGRATIONS),
+        }
+        .expect("migrations failed!");
     }
 
     /// If chain id doesn't exist, save it. Otherwise, make sure that we're indexing the same chain
@@ -274,17 +278,22 @@ impl Tailer {
         FROM
             gap, boundaries
         ";
+        // SQLite has no GREATEST, but its MAX with several arguments does the same
+        let sql = match conn.backend() {
+            DbBackend::Postgres => sql.to_string(),
+            DbBackend::Sqlite => sql.replace("GREATEST", "MAX"),
+        };
         #[derive(Debug, QueryableByName)]
         pub struct Gap {
             #[diesel(sql_type = BigInt)]
             pub version: i64,
         }
-        let mut res: Vec<Option<Gap>> = sql_query(sql)
+        let mut res: Vec<Option<Gap>> = with_connection!(&mut *conn, |conn| sql_query(&sql)
             .bind::<Text, _>(processor_name)
             // This is the number used to determine how far we look back for gaps. Increasing it may result in slower startup
             .bind::<BigInt, _>(lookback_versions)
-            .get_results(&mut conn)
-            .unwrap();
+            .get_results(conn))
+        .unwrap();
         res.pop().unwrap().map(|g| g.version)
     }
 }
@@ -307,14 +316,16 @@ pub async fn await_tasks<T: Debug>(tasks: Vec<JoinHandle<T>>) -> Vec<T> {
 mod test {
     use super::*;
     use crate::{
-        database::{new_db_pool, PgPoolConnection},
+        database::{new_db_pool, new_sqlite_db_pool, DbConnection},
         models::transactions::TransactionQuery,
         processors::default_processor::DefaultTransactionProcessor,
     };
     use aptos_api_test_context::new_test_context;
     use aptos_api_types::{LedgerInfo as APILedgerInfo, Transaction, U64};
-    use diesel::RunQueryDsl;
+    use aptos_temppath::TempPath;
+    use diesel::connection::SimpleConnection;
     use serde_json::json;
+    use std::path::Path;
 
     struct FakeFetcher {
         version: u64,
@@ -359,21 +370,35 @@ mod test {
         }
     }
 
-    pub fn wipe_database(conn: &mut PgPoolConnection) {
-        for command in [
-            "DROP SCHEMA public CASCADE",
-            "CREATE SCHEMA public",
-            "GRANT ALL ON SCHEMA public TO postgres",
-            "GRANT ALL ON SCHEMA public TO public",
-        ] {
-            diesel::sql_query(command).execute(conn).unwrap();
+    pub fn wipe_database(conn: &mut DbConnection) {
+        match conn {
+            DbConnection::Postgres(conn) => {
+                for command in [
+                    "DROP SCHEMA public CASCADE",
+                    "CREATE SCHEMA public",
+                    "GRANT ALL ON SCHEMA public TO postgres",
+                    "GRANT ALL ON SCHEMA public TO public",
+                ] {
+                    conn.batch_execute(command).unwrap();
+                }
+            }
+            DbConnection::Sqlite(conn) => {
+                conn.revert_all_migrations(SQLITE_MIGRATIONS).unwrap();
+            }
         }
     }
 
-    pub fn setup_indexer() -> Result<(PgDbPool, Tailer)> {
+    pub fn setup_indexer() -> Result<(DbPool, Tailer)> {
         let database_url = std::env::var("INDEXER_DATABASE_URL")
             .expect("must set 'INDEXER_DATABASE_URL' to run tests!");
-        let conn_pool = new_db_pool(database_url.as_str())?;
+        setup_indexer_with_pool(new_db_pool(database_url.as_str())?)
+    }
+
+    pub fn setup_sqlite_indexer(database_path: &Path) -> Result<(DbPool, Tailer)> {
+        setup_indexer_with_pool(new_sqlite_db_pool(database_path.to_str().unwrap())?)
+    }
+
+    fn setup_indexer_with_pool(conn_pool: DbPool) -> Result<(DbPool, Tailer)> {
         wipe_database(&mut conn_pool.get()?);
 
         let test_context = new_test_context("doesnt_matter".to_string(), true);
@@ -396,6 +421,18 @@ mod test {
         if crate::should_skip_pg_tests() {
             return;
         }
+        check_parsing_and_writing(setup_indexer).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_parsing_and_writing_sqlite() {
+        let path = TempPath::new();
+        path.create_as_dir().unwrap();
+        let database_path = path.path().join("indexer.sqlite");
+        check_parsing_and_writing(|| setup_sqlite_indexer(&database_path)).await;
+    }
+
+    async fn check_parsing_and_writing(setup_indexer: impl Fn() -> Result<(DbPool, Tailer)>) {
         let (conn_pool, tailer) = setup_indexer().unwrap();
         // An abridged genesis transaction
         let genesis_txn: Transaction = serde_json::from_value(json!(
@@ -8,14 +8,14 @@ use crate::{
         GOT_CONNECTION, PROCESSOR_ERRORS, PROCESSOR_INVOCATIONS, PROCESSOR_SUCCESSES,
         UNABLE_TO_GET_CONNECTION,
     },
-    database::{execute_with_better_error, PgDbPool, PgPoolConnection},
+    database::{execute_with_better_error, DbPool, DbPoolConnection},
     indexer::{errors::TransactionProcessingError, processing_result::ProcessingResult},
     models::processor_statuses::ProcessorStatusModel,
     schema,
 };
 use aptos_api_types::Transaction;
 use async_trait::async_trait;
-use diesel::{pg::upsert::excluded, prelude::*};
+use diesel::{prelude::*, upsert::excluded};
 use field_count::FieldCount;
 use schema::processor_statuses::{self, dsl};
 use std::fmt::Debug;
@@ -38,13 +38,13 @@ pub trait TransactionProcessor: Send + Sync + Debug {
 
     /// Gets a reference to the connection pool
     /// This is used by the `get_conn()` helper below
-    fn connection_pool(&self) -> &PgDbPool;
+    fn connection_pool(&self) -> &DbPool;
 
     //* Below are helper methods that don't need to be implemented *//
 
     /// Gets the connection.
     /// If it was unable to do so (default timeout: 30s), it will keep retrying until it can.
-    fn get_conn(&self) -> PgPoolConnection {
+    fn get_conn(&self) -> DbPoolConnection {
         let pool = self.connection_pool();
         loop {
             match pool.get() {
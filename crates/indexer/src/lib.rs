@@ -18,6 +18,7 @@ pub mod models;
 pub mod processors;
 pub mod runtime;
 pub mod schema;
+pub mod sql_types;
 mod util;
 
 /// By default, skips test unless `INDEXER_DATABASE_URL` is set.
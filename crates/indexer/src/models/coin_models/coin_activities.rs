@@ -44,6 +44,7 @@ pub type EventToCoinType = HashMap<EventGuidResource, CoinType>;
     event_sequence_number
 ))]
 #[diesel(table_name = coin_activities)]
+#[diesel(treat_none_as_default_value = false)]
 pub struct CoinActivity {
     pub transaction_version: i64,
     pub event_account_address: String,
@@ -6,7 +6,10 @@
 #![allow(clippy::unused_unit)]
 
 use super::coin_utils::{CoinInfoType, CoinResource};
-use crate::{database::PgPoolConnection, schema::coin_infos};
+use crate::{
+    database::{with_connection, DbConnection},
+    schema::coin_infos,
+};
 use aptos_api_types::WriteResource as APIWriteResource;
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use field_count::FieldCount;
@@ -15,6 +18,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
 #[diesel(primary_key(coin_type_hash))]
 #[diesel(table_name = coin_infos)]
+#[diesel(treat_none_as_default_value = false)]
 pub struct CoinInfo {
     pub coin_type_hash: String,
     pub coin_type: String,
@@ -84,11 +88,11 @@ impl CoinInfo {
 impl CoinInfoQuery {
     pub fn get_by_coin_type(
         coin_type: String,
-        conn: &mut PgPoolConnection,
+        conn: &mut DbConnection,
     ) -> diesel::QueryResult<Option<Self>> {
-        coin_infos::table
+        with_connection!(conn, |conn| coin_infos::table
             .filter(coin_infos::coin_type.eq(coin_type))
             .first::<Self>(conn)
-            .optional()
+            .optional())
     }
 }
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 #[diesel(belongs_to(Transaction, foreign_key = transaction_version))]
 #[diesel(primary_key(transaction_version, write_set_change_index))]
 #[diesel(table_name = move_modules)]
+#[diesel(treat_none_as_default_value = false)]
 pub struct MoveModule {
     pub transaction_version: i64,
     pub write_set_change_index: i64,
@@ -16,6 +16,7 @@ use serde::{Deserialize, Serialize};
 #[diesel(belongs_to(Transaction, foreign_key = transaction_version))]
 #[diesel(primary_key(transaction_version, write_set_change_index))]
 #[diesel(table_name = table_items)]
+#[diesel(treat_none_as_default_value = false)]
 pub struct TableItem {
     pub transaction_version: i64,
     pub write_set_change_index: i64,
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 #![allow(clippy::extra_unused_lifetimes)]
-use crate::{database::PgPoolConnection, schema::processor_status};
+use crate::{
+    database::{with_connection, DbConnection},
+    schema::processor_status,
+};
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 
 #[derive(AsChangeset, Debug, Insertable)]
@@ -24,11 +27,11 @@ pub struct ProcessorStatusV2Query {
 impl ProcessorStatusV2Query {
     pub fn get_by_processor(
         processor_name: &String,
-        conn: &mut PgPoolConnection,
+        conn: &mut DbConnection,
     ) -> diesel::QueryResult<Option<Self>> {
-        processor_status::table
+        with_connection!(conn, |conn| processor_status::table
             .filter(processor_status::processor.eq(processor_name))
             .first::<Self>(conn)
-            .optional()
+            .optional())
     }
 }
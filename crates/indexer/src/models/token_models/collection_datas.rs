@@ -10,7 +10,7 @@ use super::{
     tokens::TableHandleToOwner,
 };
 use crate::{
-    database::PgPoolConnection,
+    database::{with_connection, DbConnection},
     schema::{collection_datas, current_collection_datas},
     util::standardize_address,
 };
@@ -88,7 +88,7 @@ impl CollectionData {
         txn_version: i64,
         txn_timestamp: chrono::NaiveDateTime,
         table_handle_to_owner: &TableHandleToOwner,
-        conn: &mut PgPoolConnection,
+        conn: &mut DbConnection,
     ) -> anyhow::Result<Option<(Self, CurrentCollectionData)>> {
         let table_item_data = table_item.data.as_ref().unwrap();
 
@@ -160,7 +160,7 @@ impl CollectionData {
     /// cannot change, we can just look in the current_collection_datas table.
     /// Retrying a few times since this collection could've been written in a separate thread.
     pub fn get_collection_creator(
-        conn: &mut PgPoolConnection,
+        conn: &mut DbConnection,
         table_handle: &str,
     ) -> anyhow::Result<String> {
         let mut retried = 0;
@@ -179,11 +179,11 @@ impl CollectionData {
 
 impl CurrentCollectionDataQuery {
     pub fn get_by_table_handle(
-        conn: &mut PgPoolConnection,
+        conn: &mut DbConnection,
         table_handle: &str,
     ) -> diesel::QueryResult<Self> {
-        current_collection_datas::table
+        with_connection!(conn, |conn| current_collection_datas::table
             .filter(current_collection_datas::table_handle.eq(table_handle))
-            .first::<Self>(conn)
+            .first::<Self>(conn))
     }
 }
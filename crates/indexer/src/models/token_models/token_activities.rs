@@ -23,6 +23,7 @@ use serde::{Deserialize, Serialize};
     event_sequence_number
 ))]
 #[diesel(table_name = token_activities)]
+#[diesel(treat_none_as_default_value = false)]
 pub struct TokenActivity {
     pub transaction_version: i64,
     pub event_account_address: String,
@@ -26,6 +26,7 @@ use serde_json::Value;
     table_handle
 ))]
 #[diesel(table_name = token_ownerships)]
+#[diesel(treat_none_as_default_value = false)]
 pub struct TokenOwnership {
     pub token_data_id_hash: String,
     pub property_version: BigDecimal,
@@ -13,7 +13,7 @@ use super::{
     token_utils::{TokenResource, TokenWriteSet},
 };
 use crate::{
-    database::PgPoolConnection,
+    database::DbConnection,
     models::move_resources::MoveResource,
     schema::tokens,
     util::{ensure_not_negative, parse_timestamp, standardize_address},
@@ -70,7 +70,7 @@ impl Token {
     pub fn from_transaction(
         transaction: &APITransaction,
         table_handle_to_owner: &TableHandleToOwner,
-        conn: &mut PgPoolConnection,
+        conn: &mut DbConnection,
     ) -> (
         Vec<Self>,
         Vec<TokenOwnership>,
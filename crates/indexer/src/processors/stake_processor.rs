@@ -2,9 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    database::{
-        clean_data_for_db, execute_with_better_error, get_chunks, PgDbPool, PgPoolConnection,
-    },
+    database::{clean_data_for_db, execute_with_better_error, get_chunks, DbConnection, DbPool},
     indexer::{
         errors::TransactionProcessingError, processing_result::ProcessingResult,
         transaction_processor::TransactionProcessor,
@@ -17,17 +15,17 @@ use crate::{
 };
 use aptos_api_types::Transaction as APITransaction;
 use async_trait::async_trait;
-use diesel::{pg::upsert::excluded, result::Error, ExpressionMethods, PgConnection};
+use diesel::{upsert::excluded, ExpressionMethods};
 use field_count::FieldCount;
 use std::{collections::HashMap, fmt::Debug};
 
 pub const NAME: &str = "stake_processor";
 pub struct StakeTransactionProcessor {
-    connection_pool: PgDbPool,
+    connection_pool: DbPool,
 }
 
 impl StakeTransactionProcessor {
-    pub fn new(connection_pool: PgDbPool) -> Self {
+    pub fn new(connection_pool: DbPool) -> Self {
         Self { connection_pool }
     }
 }
@@ -44,7 +42,7 @@ impl Debug for StakeTransactionProcessor {
 }
 
 fn insert_to_db_impl(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     current_stake_pool_voters: &[CurrentStakingPoolVoter],
     proposal_votes: &[ProposalVote],
 ) -> Result<(), diesel::result::Error> {
@@ -54,7 +52,7 @@ fn insert_to_db_impl(
 }
 
 fn insert_to_db(
-    conn: &mut PgPoolConnection,
+    conn: &mut DbConnection,
     name: &'static str,
     start_version: u64,
     end_version: u64,
@@ -68,26 +66,20 @@ fn insert_to_db(
         "Inserting to db",
     );
     match conn
-        .build_transaction()
-        .read_write()
-        .run::<_, Error, _>(|pg_conn| {
-            insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)
-        }) {
+        .transaction(|conn| insert_to_db_impl(conn, &current_stake_pool_voters, &proposal_votes))
+    {
         Ok(_) => Ok(()),
-        Err(_) => conn
-            .build_transaction()
-            .read_write()
-            .run::<_, Error, _>(|pg_conn| {
-                let current_stake_pool_voters = clean_data_for_db(current_stake_pool_voters, true);
-                let proposal_votes = clean_data_for_db(proposal_votes, true);
-
-                insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)
-            }),
+        Err(_) => conn.transaction(|conn| {
+            let current_stake_pool_voters = clean_data_for_db(current_stake_pool_voters, true);
+            let proposal_votes = clean_data_for_db(proposal_votes, true);
+
+            insert_to_db_impl(conn, &current_stake_pool_voters, &proposal_votes)
+        }),
     }
 }
 
 fn insert_current_stake_pool_voter(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     item_to_insert: &[CurrentStakingPoolVoter],
 ) -> Result<(), diesel::result::Error> {
     use schema::current_staking_pool_voter::dsl::*;
@@ -115,7 +107,7 @@ fn insert_current_stake_pool_voter(
 }
 
 fn insert_proposal_votes(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     item_to_insert: &[ProposalVote],
 ) -> Result<(), diesel::result::Error> {
     use schema::proposal_votes::dsl::*;
@@ -187,7 +179,7 @@ impl TransactionProcessor for StakeTransactionProcessor {
         }
     }
 
-    fn connection_pool(&self) -> &PgDbPool {
+    fn connection_pool(&self) -> &DbPool {
         &self.connection_pool
     }
 }
@@ -2,9 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    database::{
-        clean_data_for_db, execute_with_better_error, get_chunks, PgDbPool, PgPoolConnection,
-    },
+    database::{clean_data_for_db, execute_with_better_error, get_chunks, DbConnection, DbPool},
     indexer::{
         errors::TransactionProcessingError, processing_result::ProcessingResult,
         transaction_processor::TransactionProcessor,
@@ -25,18 +23,18 @@ use crate::{
 };
 use aptos_api_types::Transaction;
 use async_trait::async_trait;
-use diesel::{pg::upsert::excluded, result::Error, ExpressionMethods, PgConnection};
+use diesel::{upsert::excluded, ExpressionMethods};
 use field_count::FieldCount;
 use std::{collections::HashMap, fmt::Debug};
 
 pub const NAME: &str = "token_processor";
 pub struct TokenTransactionProcessor {
-    connection_pool: PgDbPool,
+    connection_pool: DbPool,
     ans_contract_address: Option<String>,
 }
 
 impl TokenTransactionProcessor {
-    pub fn new(connection_pool: PgDbPool, ans_contract_address: Option<String>) -> Self {
+    pub fn new(connection_pool: DbPool, ans_contract_address: Option<String>) -> Self {
         aptos_logger::info!(
             ans_contract_address = ans_contract_address,
             "init TokenTransactionProcessor"
@@ -60,7 +58,7 @@ impl Debug for TokenTransactionProcessor {
 }
 
 fn insert_to_db_impl(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     basic_token_transaction_lists: (&[Token], &[TokenOwnership], &[TokenData], &[CollectionData]),
     basic_token_current_lists: (
         &[CurrentTokenOwnership],
@@ -88,7 +86,7 @@ fn insert_to_db_impl(
 }
 
 fn insert_to_db(
-    conn: &mut PgPoolConnection,
+    conn: &mut DbConnection,
     name: &'static str,
     start_version: u64,
     end_version: u64,
@@ -116,12 +114,35 @@ fn insert_to_db(
     let (tokens, token_ownerships, token_datas, collection_datas) = basic_token_transaction_lists;
     let (current_token_ownerships, current_token_datas, current_collection_datas) =
         basic_token_current_lists;
-    match conn
-        .build_transaction()
-        .read_write()
-        .run::<_, Error, _>(|pg_conn| {
+    match conn.transaction(|conn| {
+        insert_to_db_impl(
+            conn,
+            (&tokens, &token_ownerships, &token_datas, &collection_datas),
+            (
+                &current_token_ownerships,
+                &current_token_datas,
+                &current_collection_datas,
+            ),
+            &token_activities,
+            &current_token_claims,
+            &current_ans_lookups,
+        )
+    }) {
+        Ok(_) => Ok(()),
+        Err(_) => conn.transaction(|conn| {
+            let tokens = clean_data_for_db(tokens, true);
+            let token_datas = clean_data_for_db(token_datas, true);
+            let token_ownerships = clean_data_for_db(token_ownerships, true);
+            let collection_datas = clean_data_for_db(collection_datas, true);
+            let current_token_ownerships = clean_data_for_db(current_token_ownerships, true);
+            let current_token_datas = clean_data_for_db(current_token_datas, true);
+            let current_collection_datas = clean_data_for_db(current_collection_datas, true);
+            let token_activities = clean_data_for_db(token_activities, true);
+            let current_token_claims = clean_data_for_db(current_token_claims, true);
+            let current_ans_lookups = clean_data_for_db(current_ans_lookups, true);
+
             insert_to_db_impl(
-                pg_conn,
+                conn,
                 (&tokens, &token_ownerships, &token_datas, &collection_datas),
                 (
                     &current_token_ownerships,
@@ -132,41 +153,12 @@ fn insert_to_db(
                 &current_token_claims,
                 &current_ans_lookups,
             )
-        }) {
-        Ok(_) => Ok(()),
-        Err(_) => conn
-            .build_transaction()
-            .read_write()
-            .run::<_, Error, _>(|pg_conn| {
-                let tokens = clean_data_for_db(tokens, true);
-                let token_datas = clean_data_for_db(token_datas, true);
-                let token_ownerships = clean_data_for_db(token_ownerships, true);
-                let collection_datas = clean_data_for_db(collection_datas, true);
-                let current_token_ownerships = clean_data_for_db(current_token_ownerships, true);
-                let current_token_datas = clean_data_for_db(current_token_datas, true);
-                let current_collection_datas = clean_data_for_db(current_collection_datas, true);
-                let token_activities = clean_data_for_db(token_activities, true);
-                let current_token_claims = clean_data_for_db(current_token_claims, true);
-                let current_ans_lookups = clean_data_for_db(current_ans_lookups, true);
-
-                insert_to_db_impl(
-                    pg_conn,
-                    (&tokens, &token_ownerships, &token_datas, &collection_datas),
-                    (
-                        &current_token_ownerships,
-                        &current_token_datas,
-                        &current_collection_datas,
-                    ),
-                    &token_activities,
-                    &current_token_claims,
-                    &current_ans_lookups,
-                )
-            }),
+        }),
     }
 }
 
 fn insert_tokens(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     tokens_to_insert: &[Token],
 ) -> Result<(), diesel::result::Error> {
     use schema::tokens::dsl::*;
@@ -190,7 +182,7 @@ fn insert_tokens(
 }
 
 fn insert_token_ownerships(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     token_ownerships_to_insert: &[TokenOwnership],
 ) -> Result<(), diesel::result::Error> {
     use schema::token_ownerships::dsl::*;
@@ -218,7 +210,7 @@ fn insert_token_ownerships(
 }
 
 fn insert_token_datas(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     token_datas_to_insert: &[TokenData],
 ) -> Result<(), diesel::result::Error> {
     use schema::token_datas::dsl::*;
@@ -242,7 +234,7 @@ fn insert_token_datas(
 }
 
 fn insert_collection_datas(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     collection_datas_to_insert: &[CollectionData],
 ) -> Result<(), diesel::result::Error> {
     use schema::collection_datas::dsl::*;
@@ -265,7 +257,7 @@ fn insert_collection_datas(
 }
 
 fn insert_current_token_ownerships(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     items_to_insert: &[CurrentTokenOwnership],
 ) -> Result<(), diesel::result::Error> {
     use schema::current_token_ownerships::dsl::*;
@@ -297,7 +289,7 @@ fn insert_current_token_ownerships(
 }
 
 fn insert_current_token_datas(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     items_to_insert: &[CurrentTokenData],
 ) -> Result<(), diesel::result::Error> {
     use schema::current_token_datas::dsl::*;
@@ -340,7 +332,7 @@ fn insert_current_token_datas(
 }
 
 fn insert_current_collection_datas(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     items_to_insert: &[CurrentCollectionData],
 ) -> Result<(), diesel::result::Error> {
     use schema::current_collection_datas::dsl::*;
@@ -375,7 +367,7 @@ fn insert_current_collection_datas(
 }
 
 fn insert_token_activities(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     items_to_insert: &[TokenActivity],
 ) -> Result<(), diesel::result::Error> {
     use schema::token_activities::dsl::*;
@@ -400,7 +392,7 @@ fn insert_token_activities(
     Ok(())
 }
 fn insert_current_token_claims(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     items_to_insert: &[CurrentTokenPendingClaim],
 ) -> Result<(), diesel::result::Error> {
     use schema::current_token_pending_claims::dsl::*;
@@ -436,7 +428,7 @@ fn insert_current_token_claims(
 }
 
 fn insert_current_ans_lookups(
-    conn: &mut PgConnection,
+    conn: &mut DbConnection,
     items_to_insert: &[CurrentAnsLookup],
 ) -> Result<(), diesel::result::Error> {
     use schema::current_ans_lookup::dsl::*;
@@ -618,7 +610,7 @@ impl TransactionProcessor for TokenTransactionProcessor {
         }
     }
 
-    fn connection_pool(&self) -> &PgDbPool {
+    fn connection_pool(&self) -> &DbPool {
         &self.connection_pool
     }
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    database::new_db_pool,
+    database::{new_db_pool, new_sqlite_db_pool},
     indexer::{
         fetcher::TransactionFetcherOptions, processing_result::ProcessingResult, tailer::Tailer,
         transaction_processor::TransactionProcessor,
@@ -15,7 +15,7 @@ use crate::{
 };
 
 use aptos_api::context::Context;
-use aptos_config::config::{IndexerConfig, NodeConfig};
+use aptos_config::config::{IndexerConfig, IndexerDatabaseBackend, NodeConfig};
 use aptos_logger::{error, info};
 use aptos_mempool::MempoolClientSender;
 use aptos_types::chain_id::ChainId;
@@ -75,6 +75,7 @@ impl MovingAverage {
 }
 
 /// Creates a runtime which creates a thread pool which reads from storage and writes to postgres
+/// (or sqlite)
 /// Returns corresponding Tokio runtime
 pub fn bootstrap(
     config: &NodeConfig,
@@ -117,12 +118,17 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
 
     info!(processor_name = processor_name, "Starting indexer...");
 
-    let db_uri = &config.postgres_uri.unwrap();
+    let database_backend = config.database_backend.unwrap();
     info!(
         processor_name = processor_name,
+        database_backend = ?database_backend,
         "Creating connection pool..."
     );
-    let conn_pool = new_db_pool(db_uri).expect("Failed to create connection pool");
+    let conn_pool = match database_backend {
+        IndexerDatabaseBackend::Postgres => new_db_pool(&config.postgres_uri.unwrap()),
+        IndexerDatabaseBackend::Sqlite => new_sqlite_db_pool(&config.sqlite_path.unwrap()),
+    }
+    .expect("Failed to create connection pool");
     info!(
         processor_name = processor_name,
         "Created the connection pool... "
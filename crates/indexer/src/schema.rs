@@ -4,6 +4,9 @@
 // @generated automatically by Diesel CLI.
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     block_metadata_transactions (version) {
         version -> Int8,
         block_height -> Int8,
@@ -19,6 +22,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     coin_activities (transaction_version, event_account_address, event_creation_number, event_sequence_number) {
         transaction_version -> Int8,
         event_account_address -> Varchar,
@@ -38,6 +44,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     coin_balances (transaction_version, owner_address, coin_type_hash) {
         transaction_version -> Int8,
         owner_address -> Varchar,
@@ -50,6 +59,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     coin_infos (coin_type_hash) {
         coin_type_hash -> Varchar,
         coin_type -> Varchar,
@@ -66,6 +78,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     coin_supply (transaction_version, coin_type_hash) {
         transaction_version -> Int8,
         coin_type_hash -> Varchar,
@@ -78,6 +93,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     collection_datas (collection_data_id_hash, transaction_version) {
         collection_data_id_hash -> Varchar,
         transaction_version -> Int8,
@@ -97,6 +115,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     current_ans_lookup (domain, subdomain) {
         domain -> Varchar,
         subdomain -> Varchar,
@@ -109,6 +130,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     current_coin_balances (owner_address, coin_type_hash) {
         owner_address -> Varchar,
         coin_type_hash -> Varchar,
@@ -121,6 +145,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     current_collection_datas (collection_data_id_hash) {
         collection_data_id_hash -> Varchar,
         creator_address -> Varchar,
@@ -140,6 +167,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     current_staking_pool_voter (staking_pool_address) {
         staking_pool_address -> Varchar,
         voter_address -> Varchar,
@@ -149,6 +179,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     current_token_datas (token_data_id_hash) {
         token_data_id_hash -> Varchar,
         creator_address -> Varchar,
@@ -176,6 +209,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     current_token_ownerships (token_data_id_hash, property_version, owner_address) {
         token_data_id_hash -> Varchar,
         property_version -> Numeric,
@@ -194,6 +230,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     current_token_pending_claims (token_data_id_hash, property_version, from_address, to_address) {
         token_data_id_hash -> Varchar,
         property_version -> Numeric,
@@ -212,6 +251,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     events (account_address, creation_number, sequence_number) {
         sequence_number -> Int8,
         creation_number -> Int8,
@@ -226,6 +268,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     indexer_status (db) {
         db -> Varchar,
         is_indexer_up -> Bool,
@@ -234,12 +279,18 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     ledger_infos (chain_id) {
         chain_id -> Int8,
     }
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     move_modules (transaction_version, write_set_change_index) {
         transaction_version -> Int8,
         write_set_change_index -> Int8,
@@ -256,6 +307,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     move_resources (transaction_version, write_set_change_index) {
         transaction_version -> Int8,
         write_set_change_index -> Int8,
@@ -273,6 +327,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     processor_status (processor) {
         processor -> Varchar,
         last_success_version -> Int8,
@@ -281,6 +338,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     processor_statuses (name, version) {
         name -> Varchar,
         version -> Int8,
@@ -291,6 +351,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     proposal_votes (transaction_version, proposal_id, voter_address) {
         transaction_version -> Int8,
         proposal_id -> Int8,
@@ -304,6 +367,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     signatures (transaction_version, multi_agent_index, multi_sig_index, is_sender_primary) {
         transaction_version -> Int8,
         multi_agent_index -> Int8,
@@ -322,6 +388,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     table_items (transaction_version, write_set_change_index) {
         key -> Text,
         transaction_version -> Int8,
@@ -336,6 +405,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     table_metadatas (handle) {
         handle -> Varchar,
         key_type -> Text,
@@ -345,6 +417,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     token_activities (transaction_version, event_account_address, event_creation_number, event_sequence_number) {
         transaction_version -> Int8,
         event_account_address -> Varchar,
@@ -368,6 +443,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     token_datas (token_data_id_hash, transaction_version) {
         token_data_id_hash -> Varchar,
         transaction_version -> Int8,
@@ -395,6 +473,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     token_ownerships (token_data_id_hash, property_version, transaction_version, table_handle) {
         token_data_id_hash -> Varchar,
         property_version -> Numeric,
@@ -413,6 +494,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     tokens (token_data_id_hash, property_version, transaction_version) {
         token_data_id_hash -> Varchar,
         property_version -> Numeric,
@@ -428,6 +512,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     transactions (version) {
         version -> Int8,
         block_height -> Int8,
@@ -450,6 +537,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     user_transactions (version) {
         version -> Int8,
         block_height -> Int8,
@@ -467,6 +557,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use crate::sql_types::{Jsonb, Numeric};
+
     write_set_changes (transaction_version, index) {
         transaction_version -> Int8,
         index -> Int8,
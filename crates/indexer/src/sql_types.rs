@@ -0,0 +1,117 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! SQL types whose representation depends on the database backend
+//!
+//! Postgres has native `jsonb` and `numeric` types, SQLite has neither, so the SQLite schema stores
+//! JSON documents and arbitrary precision numbers as text. The schema uses these types in place of
+//! `diesel::sql_types::{Jsonb, Numeric}`, so that the same models work with both backends.
+#![allow(clippy::extra_unused_lifetimes)]
+
+use bigdecimal::BigDecimal;
+use diesel::{
+    backend::RawValue,
+    deserialize::{self, FromSql},
+    expression::AsExpression,
+    internal::derives::as_expression::Bound,
+    pg::Pg,
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::{self, Text},
+    sqlite::Sqlite,
+};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, QueryId, SqlType)]
+#[diesel(postgres_type(oid = 3802, array_oid = 3807))]
+#[diesel(sqlite_type(name = "Text"))]
+pub struct Jsonb;
+
+#[derive(Clone, Copy, Debug, Default, QueryId, SqlType)]
+#[diesel(postgres_type(oid = 1700, array_oid = 1231))]
+#[diesel(sqlite_type(name = "Text"))]
+pub struct Numeric;
+
+impl AsExpression<Jsonb> for serde_json::Value {
+    type Expression = Bound<Jsonb, Self>;
+
+    fn as_expression(self) -> Self::Expression {
+        Bound::new(self)
+    }
+}
+
+impl<'a> AsExpression<Jsonb> for &'a serde_json::Value {
+    type Expression = Bound<Jsonb, Self>;
+
+    fn as_expression(self) -> Self::Expression {
+        Bound::new(self)
+    }
+}
+
+impl ToSql<Jsonb, Pg> for serde_json::Value {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <Self as ToSql<sql_types::Jsonb, Pg>>::to_sql(self, out)
+    }
+}
+
+impl FromSql<Jsonb, Pg> for serde_json::Value {
+    fn from_sql(value: RawValue<'_, Pg>) -> deserialize::Result<Self> {
+        <Self as FromSql<sql_types::Jsonb, Pg>>::from_sql(value)
+    }
+}
+
+impl ToSql<Jsonb, Sqlite> for serde_json::Value {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(serde_json::to_string(self)?);
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Jsonb, Sqlite> for serde_json::Value {
+    fn from_sql(value: RawValue<'_, Sqlite>) -> deserialize::Result<Self> {
+        let text = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+impl AsExpression<Numeric> for BigDecimal {
+    type Expression = Bound<Numeric, Self>;
+
+    fn as_expression(self) -> Self::Expression {
+        Bound::new(self)
+    }
+}
+
+impl<'a> AsExpression<Numeric> for &'a BigDecimal {
+    type Expression = Bound<Numeric, Self>;
+
+    fn as_expression(self) -> Self::Expression {
+        Bound::new(self)
+    }
+}
+
+impl ToSql<Numeric, Pg> for BigDecimal {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <Self as ToSql<sql_types::Numeric, Pg>>::to_sql(self, out)
+    }
+}
+
+impl FromSql<Numeric, Pg> for BigDecimal {
+    fn from_sql(value: RawValue<'_, Pg>) -> deserialize::Result<Self> {
+        <Self as FromSql<sql_types::Numeric, Pg>>::from_sql(value)
+    }
+}
+
+/// Stored as text rather than as a SQLite number, which would lose precision past 2^53
+impl ToSql<Numeric, Sqlite> for BigDecimal {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.to_string());
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Numeric, Sqlite> for BigDecimal {
+    fn from_sql(value: RawValue<'_, Sqlite>) -> deserialize::Result<Self> {
+        let text = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+        Ok(BigDecimal::from_str(&text)?)
+    }
+}
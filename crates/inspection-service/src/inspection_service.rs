@@ -3,11 +3,13 @@
 
 use crate::{gather_metrics, json_encoder::JsonEncoder, NUM_METRICS};
 use aptos_build_info::build_information;
-use aptos_config::config::NodeConfig;
+use aptos_config::{config::NodeConfig, network_id::NetworkId};
+use aptos_types::PeerId;
 use hyper::{
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
 };
+use network::connectivity_manager::reputation::PeerReputationRegistry;
 use prometheus::{
     proto::{MetricFamily, MetricType},
     Encoder, TextEncoder,
@@ -16,7 +18,10 @@ use std::{
     collections::HashMap,
     convert::Infallible,
     net::{SocketAddr, ToSocketAddrs},
+    str::FromStr,
+    sync::Arc,
     thread,
+    time::Duration,
 };
 use tokio::runtime;
 
@@ -82,9 +87,66 @@ pub fn get_all_metrics() -> HashMap<String, String> {
     get_metrics(all_metric_families)
 }
 
+/// Bans or unbans (depending on the path) the peer given in the query, e.g.,
+/// `network_id=public&peer_id=0x1&duration_secs=3600`, and returns the updated ban list of the
+/// network. The ban lasts until the peer is unbanned if no duration is given.
+pub(crate) fn update_peer_bans(
+    peer_reputations: &PeerReputationRegistry,
+    path: &str,
+    query: Option<&str>,
+) -> Result<String, (StatusCode, String)> {
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, message);
+    let params: HashMap<&str, &str> = query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .collect();
+
+    let network_id = params
+        .get("network_id")
+        .ok_or_else(|| bad_request("Missing the network_id parameter".into()))?;
+    let network_id = NetworkId::from_str(network_id)
+        .map_err(|error| bad_request(format!("Invalid network_id {}: {}", network_id, error)))?;
+    let peer_id = params
+        .get("peer_id")
+        .ok_or_else(|| bad_request("Missing the peer_id parameter".into()))?;
+    let peer_id = PeerId::from_str(peer_id)
+        .map_err(|error| bad_request(format!("Invalid peer_id {}: {}", peer_id, error)))?;
+    let reputations = peer_reputations.get(&network_id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("The {} network isn't running", network_id),
+        )
+    })?;
+
+    let result = if path == "/peer_bans/ban" {
+        let duration = params
+            .get("duration_secs")
+            .map(|duration_secs| {
+                duration_secs
+                    .parse()
+                    .map(Duration::from_secs)
+                    .map_err(|error| {
+                        bad_request(format!(
+                            "Invalid duration_secs {}: {}",
+                            duration_secs, error
+                        ))
+                    })
+            })
+            .transpose()?;
+        reputations.ban(peer_id, duration)
+    } else {
+        reputations.unban(peer_id)
+    };
+    result.map_err(|error| bad_request(error.to_string()))?;
+
+    Ok(serde_json::to_string(&reputations.banned_peers()).unwrap())
+}
+
 async fn serve_requests(
     req: Request<Body>,
     node_config: NodeConfig,
+    peer_reputations: Arc<PeerReputationRegistry>,
 ) -> Result<Response<Body>, hyper::Error> {
     let mut resp = Response::new(Body::empty());
     match (req.method(), req.uri().path()) {
@@ -131,6 +193,29 @@ async fn serve_requests(
                 *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
             }
         }
+        // Exposes the peers banned from each network
+        (&Method::GET, "/peer_bans") => {
+            if node_config.inspection_service.expose_peer_bans {
+                let banned_peers = peer_reputations.banned_peers();
+                *resp.body_mut() = Body::from(serde_json::to_string(&banned_peers).unwrap());
+            } else {
+                *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
+            }
+        }
+        // Bans and unbans peers at runtime
+        (&Method::POST, path @ ("/peer_bans/ban" | "/peer_bans/unban")) => {
+            if node_config.inspection_service.enable_peer_ban_updates {
+                match update_peer_bans(&peer_reputations, path, req.uri().query()) {
+                    Ok(banned_peers) => *resp.body_mut() = Body::from(banned_peers),
+                    Err((status, message)) => {
+                        *resp.status_mut() = status;
+                        *resp.body_mut() = Body::from(message);
+                    }
+                }
+            } else {
+                *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
+            }
+        }
         _ => {
             *resp.status_mut() = StatusCode::NOT_FOUND;
         }
@@ -139,7 +224,10 @@ async fn serve_requests(
     Ok(resp)
 }
 
-pub fn start_inspection_service(node_config: NodeConfig) {
+pub fn start_inspection_service(
+    node_config: NodeConfig,
+    peer_reputations: Arc<PeerReputationRegistry>,
+) {
     // Fetch the service port and address
     let service_port = node_config.inspection_service.port;
     let service_address = node_config.inspection_service.address.clone();
@@ -160,9 +248,10 @@ pub fn start_inspection_service(node_config: NodeConfig) {
     thread::spawn(move || {
         let make_service = make_service_fn(move |_conn| {
             let node_config = node_config.clone();
+            let peer_reputations = peer_reputations.clone();
             async move {
                 Ok::<_, Infallible>(service_fn(move |request| {
-                    serve_requests(request, node_config.clone())
+                    serve_requests(request, node_config.clone(), peer_reputations.clone())
                 }))
             }
         });
@@ -1,11 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::inspection_service::get_all_metrics;
+use crate::inspection_service::{get_all_metrics, update_peer_bans};
+use aptos_config::{
+    config::RoleType,
+    network_id::{NetworkContext, NetworkId},
+};
+use aptos_time_service::TimeService;
+use aptos_types::PeerId;
 use assert_approx_eq::assert_approx_eq;
+use hyper::StatusCode;
+use network::connectivity_manager::reputation::{PeerReputationRegistry, PeerReputations};
 use once_cell::sync::Lazy;
 use prometheus::{proto::MetricFamily, register_int_counter, Counter, IntCounter, Opts, Registry};
 use rusty_fork::rusty_fork_test;
+use std::sync::Arc;
 
 const INT_COUNTER_NAME: &str = "INT_COUNTER";
 pub static INT_COUNTER: Lazy<IntCounter> =
@@ -70,3 +79,34 @@ fn get_all_metrics_test() {
     }
 }
 }
+
+#[test]
+fn update_peer_bans_test() {
+    let registry = PeerReputationRegistry::new();
+    let network_context = NetworkContext::new(RoleType::FullNode, NetworkId::Public, PeerId::ONE);
+    let reputations = Arc::new(PeerReputations::in_memory(
+        network_context,
+        TimeService::mock(),
+    ));
+    registry.register(NetworkId::Public, reputations.clone());
+    let peer_id = PeerId::random();
+
+    // Ban and unban the peer
+    let query = format!("network_id=public&peer_id={}&duration_secs=60", peer_id);
+    update_peer_bans(&registry, "/peer_bans/ban", Some(&query)).unwrap();
+    assert!(reputations.is_banned(&peer_id));
+    let query = format!("network_id=public&peer_id={}", peer_id);
+    update_peer_bans(&registry, "/peer_bans/unban", Some(&query)).unwrap();
+    assert!(!reputations.is_banned(&peer_id));
+
+    // Invalid requests are rejected
+    let query = format!("network_id=vfn&peer_id={}", peer_id);
+    let (status, _) = update_peer_bans(&registry, "/peer_bans/ban", Some(&query)).unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) =
+        update_peer_bans(&registry, "/peer_bans/ban", Some("network_id=public")).unwrap_err();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let query = format!("network_id=public&peer_id={}&duration_secs=soon", peer_id);
+    let (status, _) = update_peer_bans(&registry, "/peer_bans/ban", Some(&query)).unwrap_err();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,224 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-disk journal of the transactions in mempool, so that pending transactions survive a
+//! restart of the node.
+//!
+//! The journal is an append-only log of insertions and removals. It is compacted, i.e. rewritten
+//! with the transactions still in mempool, once removals make up most of it. On startup, the
+//! journal is read back and the transactions it holds are resubmitted to mempool, which validates
+//! them against the latest state again. The resubmitted transactions are journaled to a new file,
+//! which only replaces the old journal once the restore is done, so that a crash in the middle
+//! of the restore loses nothing.
+
+use crate::{
+    core_mempool::transaction::TimelineState,
+    counters,
+    logging::{LogEntry, LogSchema},
+};
+use anyhow::{ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// The journal isn't compacted until it holds at least this many records.
+const MIN_RECORDS_TO_COMPACT: usize = 10_000;
+
+#[derive(Debug, Deserialize, Serialize)]
+enum JournalRecord {
+    Insert(SignedTransaction, TimelineState),
+    Remove(AccountAddress, u64),
+}
+
+pub struct MempoolJournal {
+    path: PathBuf,
+    // the file being written, which differs from `path` until the restore is done
+    file_path: PathBuf,
+    writer: BufWriter<File>,
+    // number of records in the file
+    num_records: usize,
+    // number of transactions the records add up to
+    num_txns: usize,
+}
+
+impl MempoolJournal {
+    /// Opens the journal at `path`, creating it if it doesn't exist, and returns the transactions
+    /// it holds, ordered by account and sequence number. The returned transactions are journaled
+    /// again to a new file as they are added back to mempool, which replaces the journal once
+    /// [`MempoolJournal::finish_restore`] is called.
+    pub(crate) fn open(path: &Path) -> Result<(Self, Vec<(SignedTransaction, TimelineState)>)> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let txns = match File::open(path) {
+            Ok(file) => Self::read_records(file)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e.into()),
+        };
+        let file_path = path.with_extension("restore");
+        let journal = Self {
+            path: path.to_path_buf(),
+            writer: Self::create_file(&file_path)?,
+            file_path,
+            num_records: 0,
+            num_txns: 0,
+        };
+        Ok((journal, txns))
+    }
+
+    /// Replaces the journal read on open with the one written since, once the transactions it
+    /// held have been added back to mempool.
+    pub(crate) fn finish_restore(&mut self) {
+        if self.file_path == self.path {
+            return;
+        }
+        let result: Result<()> = self
+            .writer
+            .flush()
+            .and_then(|_| fs::rename(&self.file_path, &self.path))
+            .map_err(Into::into);
+        match result {
+            Ok(()) => self.file_path = self.path.clone(),
+            Err(e) => {
+                error!(LogSchema::new(LogEntry::Journal).error(&e));
+                counters::CORE_MEMPOOL_JOURNAL_ERROR_COUNT.inc();
+            }
+        }
+    }
+
+    /// Journals a transaction added to mempool.
+    pub(crate) fn insert(&mut self, txn: &SignedTransaction, timeline_state: TimelineState) {
+        // Broadcast positions are not preserved across restarts.
+        let timeline_state = match timeline_state {
+            TimelineState::Ready(_) => TimelineState::NotReady,
+            state => state,
+        };
+        let record = JournalRecord::Insert(txn.clone(), timeline_state);
+        if self.append(&record) {
+            self.num_txns += 1;
+        }
+    }
+
+    /// Journals a transaction removed from mempool, either committed, rejected, evicted or
+    /// garbage collected.
+    pub(crate) fn remove(&mut self, sender: AccountAddress, sequence_number: u64) {
+        if self.append(&JournalRecord::Remove(sender, sequence_number)) {
+            self.num_txns = self.num_txns.saturating_sub(1);
+        }
+    }
+
+    /// Whether most of the journal is made of records that cancel each other.
+    pub(crate) fn needs_compaction(&self) -> bool {
+        self.num_records >= MIN_RECORDS_TO_COMPACT && self.num_records > 2 * self.num_txns
+    }
+
+    /// Rewrites the journal with `txns`, the transactions currently in mempool.
+    pub(crate) fn compact<'a>(
+        &mut self,
+        txns: impl Iterator<Item = (&'a SignedTransaction, TimelineState)>,
+    ) {
+        if let Err(e) = self.try_compact(txns) {
+            error!(LogSchema::new(LogEntry::Journal).error(&e));
+            counters::CORE_MEMPOOL_JOURNAL_ERROR_COUNT.inc();
+        }
+    }
+
+    fn try_compact<'a>(
+        &mut self,
+        txns: impl Iterator<Item = (&'a SignedTransaction, TimelineState)>,
+    ) -> Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        let mut writer = Self::create_file(&tmp_path)?;
+        let mut num_txns = 0;
+        for (txn, timeline_state) in txns {
+            let timeline_state = match timeline_state {
+                TimelineState::Ready(_) => TimelineState::NotReady,
+                state => state,
+            };
+            Self::write_record(
+                &mut writer,
+                &JournalRecord::Insert(txn.clone(), timeline_state),
+            )?;
+            num_txns += 1;
+        }
+        writer.flush()?;
+        fs::rename(&tmp_path, &self.file_path)?;
+
+        self.writer = writer;
+        self.num_records = num_txns;
+        self.num_txns = num_txns;
+        Ok(())
+    }
+
+    /// Appends a record, returns false if it couldn't be written.
+    fn append(&mut self, record: &JournalRecord) -> bool {
+        match Self::write_record(&mut self.writer, record).and_then(|_| {
+            self.writer.flush()?;
+            Ok(())
+        }) {
+            Ok(()) => {
+                self.num_records += 1;
+                true
+            }
+            Err(e) => {
+                error!(LogSchema::new(LogEntry::Journal).error(&e));
+                counters::CORE_MEMPOOL_JOURNAL_ERROR_COUNT.inc();
+                false
+            }
+        }
+    }
+
+    fn create_file(path: &Path) -> Result<BufWriter<File>> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(BufWriter::new(file))
+    }
+
+    /// Records are framed by their length, as a little endian u32.
+    fn write_record(writer: &mut impl Write, record: &JournalRecord) -> Result<()> {
+        let bytes = bcs::to_bytes(record)?;
+        ensure!(bytes.len() <= u32::MAX as usize, "Journal record too large");
+        writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Replays the records of the journal. A truncated record at the end, left by a crash in the
+    /// middle of a write, is ignored.
+    fn read_records(file: File) -> Result<Vec<(SignedTransaction, TimelineState)>> {
+        let mut reader = BufReader::new(file);
+        let mut txns = BTreeMap::new();
+        loop {
+            let mut len_bytes = [0u8; 4];
+            if reader.read_exact(&mut len_bytes).is_err() {
+                break;
+            }
+            let mut bytes = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+            if reader.read_exact(&mut bytes).is_err() {
+                warn!(
+                    LogSchema::new(LogEntry::Journal),
+                    "Ignoring truncated record at the end of the mempool journal"
+                );
+                break;
+            }
+            match bcs::from_bytes(&bytes)? {
+                JournalRecord::Insert(txn, timeline_state) => {
+                    txns.insert((txn.sender(), txn.sequence_number()), (txn, timeline_state));
+                }
+                JournalRecord::Remove(sender, sequence_number) => {
+                    txns.remove(&(sender, sequence_number));
+                }
+            }
+        }
+        Ok(txns.into_values().collect())
+    }
+}
@@ -8,6 +8,7 @@ use crate::shared_mempool::types::MultiBucketTimelineIndexIds;
 use crate::{
     core_mempool::{
         index::TxnPointer,
+        journal::MempoolJournal,
         transaction::{MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
     },
@@ -105,6 +106,16 @@ impl Mempool {
         }
     }
 
+    /// Starts journaling the transactions in mempool to disk.
+    pub(crate) fn set_journal(&mut self, journal: MempoolJournal) {
+        self.transactions.set_journal(journal);
+    }
+
+    /// Replaces the journal read on startup, once its transactions have been added back.
+    pub(crate) fn finish_journal_restore(&mut self) {
+        self.transactions.finish_journal_restore();
+    }
+
     pub(crate) fn get_by_hash(&self, hash: HashValue) -> Option<SignedTransaction> {
         self.transactions.get_by_hash(hash)
     }
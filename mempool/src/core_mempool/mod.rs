@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod journal;
 mod mempool;
 mod transaction;
 mod transaction_store;
 
 pub use self::{
-    index::TxnPointer, mempool::Mempool as CoreMempool, transaction::MempoolTransaction,
-    transaction::TimelineState, transaction_store::TXN_INDEX_ESTIMATED_BYTES,
+    index::TxnPointer, journal::MempoolJournal, mempool::Mempool as CoreMempool,
+    transaction::MempoolTransaction, transaction::TimelineState,
+    transaction_store::TXN_INDEX_ESTIMATED_BYTES,
 };
@@ -11,6 +11,7 @@ use crate::{
             AccountTransactions, MultiBucketTimelineIndex, ParkingLotIndex, PriorityIndex,
             PriorityQueueIter, TTLIndex,
         },
+        journal::MempoolJournal,
         transaction::{MempoolTransaction, TimelineState},
     },
     counters,
@@ -74,6 +75,9 @@ pub struct TransactionStore {
     capacity_bytes: usize,
     capacity_per_user: usize,
     max_batch_bytes: u64,
+
+    // optional on-disk journal of the transactions in the store
+    journal: Option<MempoolJournal>,
 }
 
 impl TransactionStore {
@@ -102,6 +106,20 @@ impl TransactionStore {
             capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
+
+            journal: None,
+        }
+    }
+
+    /// Starts journaling the transactions inserted into and removed from the store.
+    pub(crate) fn set_journal(&mut self, journal: MempoolJournal) {
+        self.journal = Some(journal);
+    }
+
+    /// Replaces the journal read on startup, once its transactions have been added back.
+    pub(crate) fn finish_journal_restore(&mut self) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.finish_restore();
         }
     }
 
@@ -272,6 +290,9 @@ impl TransactionStore {
                 (sender, sequence_number.transaction_sequence_number),
             );
             let txn_size_bytes = txn.get_estimated_bytes();
+            if let Some(journal) = &mut self.journal {
+                journal.insert(&txn.txn, txn.timeline_state);
+            }
             txns.insert(sequence_number.transaction_sequence_number, txn);
             self.sequence_numbers.insert(
                 sender,
@@ -544,6 +565,12 @@ impl TransactionStore {
         self.parking_lot_index.remove(txn);
         self.hash_index.remove(&txn.get_committed_hash());
         self.size_bytes -= txn.get_estimated_bytes();
+        if let Some(journal) = &mut self.journal {
+            journal.remove(
+                txn.get_sender(),
+                txn.sequence_info.transaction_sequence_number,
+            );
+        }
 
         // Remove account datastructures if there are no more transactions for the account.
         let address = &txn.get_sender();
@@ -711,6 +738,22 @@ impl TransactionStore {
             trace!(LogSchema::event_log(LogEntry::GCRemoveTxns, log_event).txns(gc_txns_log));
         }
         self.track_indices();
+        self.compact_journal();
+    }
+
+    /// Rewrites the journal with the transactions in the store, once it is mostly made of
+    /// removed transactions.
+    fn compact_journal(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            if journal.needs_compaction() {
+                journal.compact(
+                    self.transactions
+                        .values()
+                        .flat_map(|txns| txns.values())
+                        .map(|txn| (&txn.txn, txn.timeline_state)),
+                );
+            }
+        }
     }
 
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
@@ -167,6 +167,25 @@ pub static CORE_MEMPOOL_IDEMPOTENT_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking number of failed writes to the mempool journal
+pub static CORE_MEMPOOL_JOURNAL_ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_core_mempool_journal_error_count",
+        "Number of failed writes to the mempool journal"
+    )
+    .unwrap()
+});
+
+/// Counter tracking number of txns restored from the mempool journal on startup
+pub static CORE_MEMPOOL_JOURNAL_RESTORED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_journal_restored_txns_count",
+        "Number of txns restored from the mempool journal on startup",
+        &["status"]
+    )
+    .unwrap()
+});
+
 pub fn core_mempool_txn_commit_latency(
     stage: &'static str,
     scope: &'static str,
@@ -172,6 +172,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    Journal,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -6,6 +6,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        tasks::process_journal_restore,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
@@ -70,6 +71,10 @@ pub(crate) fn start_shared_mempool<V>(
         peer_metadata_storage,
     );
 
+    if config.mempool.enable_journal {
+        process_journal_restore(&smp, &config.mempool.journal_path());
+    }
+
     executor.spawn(coordinator(
         smp,
         executor.clone(),
@@ -3,7 +3,7 @@
 
 //! Tasks that are executed by coordinators (short-lived compared to coordinators)
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    core_mempool::{CoreMempool, MempoolJournal, TimelineState, TxnPointer},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, MempoolSyncMsg},
@@ -33,6 +33,7 @@ use rayon::prelude::*;
 use std::{
     cmp,
     collections::HashSet,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -350,6 +351,60 @@ where
     statuses
 }
 
+/// Opens the mempool journal, and resubmits the transactions it holds from before the restart.
+/// They are validated against the latest state again, so the ones committed or expired in the
+/// meantime are dropped.
+pub(crate) fn process_journal_restore<V>(smp: &SharedMempool<V>, journal_path: &Path)
+where
+    V: TransactionValidation,
+{
+    let (journal, txns) = match MempoolJournal::open(journal_path) {
+        Ok(result) => result,
+        Err(e) => {
+            error!(
+                LogSchema::new(LogEntry::Journal).error(&e),
+                "Failed to open mempool journal, not journaling transactions"
+            );
+            counters::CORE_MEMPOOL_JOURNAL_ERROR_COUNT.inc();
+            return;
+        }
+    };
+    smp.mempool.lock().set_journal(journal);
+
+    let num_txns = txns.len();
+    let (non_qualified, qualified): (Vec<_>, Vec<_>) = txns
+        .into_iter()
+        .partition(|(_, timeline_state)| *timeline_state == TimelineState::NonQualified);
+    let mut num_accepted = 0;
+    for (txns, timeline_state) in [
+        (qualified, TimelineState::NotReady),
+        (non_qualified, TimelineState::NonQualified),
+    ] {
+        if txns.is_empty() {
+            continue;
+        }
+        let txns = txns.into_iter().map(|(txn, _)| txn).collect();
+        for (_, (mempool_status, _)) in process_incoming_transactions(smp, txns, timeline_state) {
+            let status = if mempool_status.code == MempoolStatusCode::Accepted {
+                num_accepted += 1;
+                counters::REQUEST_SUCCESS_LABEL
+            } else {
+                counters::REQUEST_FAIL_LABEL
+            };
+            counters::CORE_MEMPOOL_JOURNAL_RESTORED_TXNS
+                .with_label_values(&[status])
+                .inc();
+        }
+    }
+    smp.mempool.lock().finish_journal_restore();
+    info!(
+        LogSchema::new(LogEntry::Journal),
+        num_txns = num_txns,
+        num_accepted = num_accepted,
+        "Restored transactions from mempool journal"
+    );
+}
+
 fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<PeerNetworkId>) {
     let network = match sender {
         Some(peer) => peer.network_id().to_string(),
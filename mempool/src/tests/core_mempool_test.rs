@@ -3,11 +3,12 @@
 
 use crate::tests::common::setup_mempool_with_broadcast_buckets;
 use crate::{
-    core_mempool::{CoreMempool, MempoolTransaction, TimelineState},
+    core_mempool::{CoreMempool, MempoolJournal, MempoolTransaction, TimelineState},
     tests::common::{add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool, TestTransaction},
 };
 use aptos_config::config::NodeConfig;
 use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
 use aptos_types::mempool_status::MempoolStatusCode;
 use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction};
 use itertools::Itertools;
@@ -785,3 +786,43 @@ fn test_sequence_number_behavior_at_capacity() {
     let batch = pool.get_batch(10, 10240, HashSet::new());
     assert_eq!(batch.len(), 1);
 }
+
+#[test]
+fn test_journal_restore() {
+    let journal_path = TempPath::new();
+    let (mut pool, _) = setup_mempool();
+    let (journal, restored) = MempoolJournal::open(journal_path.path()).unwrap();
+    assert!(restored.is_empty());
+    pool.set_journal(journal);
+    pool.finish_journal_restore();
+
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 1),
+        ],
+    );
+    pool.commit_transaction(&TestTransaction::get_address(0), 0);
+
+    // Only the transactions still in mempool are restored.
+    let (_, restored) = MempoolJournal::open(journal_path.path()).unwrap();
+    let restored: Vec<_> = restored.into_iter().map(|(txn, _)| txn).collect();
+    assert_eq!(restored.len(), 2);
+    assert!(restored.contains(&txns[1]));
+    assert!(restored.contains(&txns[2]));
+
+    // The journal is kept until a restore finishes, so a crash during the restore loses nothing.
+    let (_, restored) = MempoolJournal::open(journal_path.path()).unwrap();
+    assert_eq!(restored.len(), 2);
+    let (mut pool, _) = setup_mempool();
+    let (journal, restored) = MempoolJournal::open(journal_path.path()).unwrap();
+    pool.set_journal(journal);
+    for (txn, _) in restored {
+        add_signed_txn(&mut pool, txn).unwrap();
+    }
+    pool.finish_journal_restore();
+    let (_, restored) = MempoolJournal::open(journal_path.path()).unwrap();
+    assert_eq!(restored.len(), 2);
+}
@@ -26,7 +26,9 @@ use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
 use event_notifications::{EventSubscriptionService, ReconfigNotificationListener};
 use network::{
     application::storage::PeerMetadataStorage,
-    connectivity_manager::{builder::ConnectivityManagerBuilder, ConnectivityRequest},
+    connectivity_manager::{
+        builder::ConnectivityManagerBuilder, reputation::PeerReputations, ConnectivityRequest,
+    },
     constants::MAX_MESSAGE_SIZE,
     logging::NetworkSchema,
     peer_manager::{
@@ -45,6 +47,7 @@ use std::{
     clone::Clone,
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Duration,
 };
 use tokio::runtime::Handle;
 
@@ -70,6 +73,7 @@ pub struct NetworkBuilder {
     health_checker_builder: Option<HealthCheckerBuilder>,
     peer_manager_builder: PeerManagerBuilder,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    peer_reputations: Arc<PeerReputations>,
 }
 
 impl NetworkBuilder {
@@ -79,6 +83,7 @@ impl NetworkBuilder {
     pub fn new(
         chain_id: ChainId,
         trusted_peers: Arc<RwLock<PeerSet>>,
+        peer_reputations: Arc<PeerReputations>,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
         network_context: NetworkContext,
         time_service: TimeService,
@@ -103,6 +108,7 @@ impl NetworkBuilder {
             listen_address,
             peer_metadata_storage.clone(),
             trusted_peers,
+            peer_reputations.clone(),
             authentication_mode,
             network_channel_size,
             max_concurrent_network_reqs,
@@ -125,6 +131,7 @@ impl NetworkBuilder {
             health_checker_builder: None,
             peer_manager_builder,
             peer_metadata_storage,
+            peer_reputations,
         }
     }
 
@@ -143,6 +150,10 @@ impl NetworkBuilder {
         let mut builder = NetworkBuilder::new(
             chain_id,
             trusted_peers.clone(),
+            Arc::new(PeerReputations::in_memory(
+                network_context,
+                time_service.clone(),
+            )),
             peer_metadata_storage,
             network_context,
             time_service,
@@ -195,10 +206,16 @@ impl NetworkBuilder {
         let network_context = NetworkContext::new(role, config.network_id, peer_id);
 
         let trusted_peers = Arc::new(RwLock::new(HashMap::new()));
+        let peer_reputations = Arc::new(PeerReputations::new(
+            network_context,
+            time_service.clone(),
+            config.peer_reputation.clone(),
+        ));
 
         let mut network_builder = NetworkBuilder::new(
             chain_id,
             trusted_peers.clone(),
+            peer_reputations,
             peer_metadata_storage,
             network_context,
             time_service,
@@ -337,6 +354,11 @@ impl NetworkBuilder {
         self.peer_manager_builder.listen_address()
     }
 
+    /// The reputations of the peers of the network, including its ban list
+    pub fn peer_reputations(&self) -> Arc<PeerReputations> {
+        self.peer_reputations.clone()
+    }
+
     /// Add a [`network::connectivity_manager::ConnectivityManager`] to the network.
     ///
     /// [`network::connectivity_manager::ConnectivityManager`] is responsible for ensuring that we are connected
@@ -378,6 +400,7 @@ impl NetworkBuilder {
             pm_conn_mgr_notifs_rx,
             outbound_connection_limit,
             mutual_authentication,
+            self.peer_reputations.clone(),
         ));
         self
     }
@@ -396,6 +419,9 @@ impl NetworkBuilder {
             DiscoveryMethod::Onchain => {
                 let reconfig_events =
                     reconfig_events.expect("Reconfiguration listener is expected!");
+                // Validators must never be banned, so don't enforce any ban before the
+                // validator set is discovered
+                self.peer_reputations.wait_for_validator_set();
                 DiscoveryChangeListener::validator_set(
                     self.network_context,
                     conn_mgr_reqs_tx,
@@ -410,6 +436,14 @@ impl NetworkBuilder {
                 *interval_duration,
                 self.time_service.clone(),
             ),
+            DiscoveryMethod::Rest(rest_discovery) => DiscoveryChangeListener::rest(
+                self.network_context,
+                conn_mgr_reqs_tx,
+                rest_discovery.url.clone(),
+                rest_discovery.source,
+                Duration::from_secs(rest_discovery.interval_secs),
+                self.time_service.clone(),
+            ),
             DiscoveryMethod::None => return,
         };
 
@@ -1,8 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::DISCOVERY_COUNTS, file::FileStream, validator_set::ValidatorSetStream};
-use aptos_config::{config::PeerSet, network_id::NetworkContext};
+use crate::{
+    counters::DISCOVERY_COUNTS, file::FileStream, rest::RestStream,
+    validator_set::ValidatorSetStream,
+};
+use aptos_config::{
+    config::{PeerSet, RestDiscoverySource},
+    network_id::NetworkContext,
+};
 use aptos_crypto::x25519;
 use aptos_logger::prelude::*;
 use aptos_time_service::TimeService;
@@ -20,15 +26,18 @@ use std::{
     time::Duration,
 };
 use tokio::runtime::Handle;
+use url::Url;
 
 mod counters;
 mod file;
+mod rest;
 mod validator_set;
 
 #[derive(Debug)]
 pub enum DiscoveryError {
     IO(std::io::Error),
     Parsing(String),
+    Rest(aptos_rest_client::error::RestError),
 }
 
 /// A union type for all implementations of `DiscoveryChangeListenerTrait`
@@ -42,6 +51,7 @@ pub struct DiscoveryChangeListener {
 enum DiscoveryChangeStream {
     ValidatorSet(ValidatorSetStream),
     File(FileStream),
+    Rest(RestStream),
 }
 
 impl Stream for DiscoveryChangeStream {
@@ -51,6 +61,7 @@ impl Stream for DiscoveryChangeStream {
         match self.get_mut() {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
+            Self::Rest(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -95,6 +106,29 @@ impl DiscoveryChangeListener {
         }
     }
 
+    pub fn rest(
+        network_context: NetworkContext,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        rest_url: Url,
+        rest_source: RestDiscoverySource,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::Rest(RestStream::new(
+            network_context,
+            rest_url,
+            rest_source,
+            interval_duration,
+            time_service,
+        ));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Rest,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         spawn_named!("DiscoveryChangeListener", executor, Box::pin(self).run());
     }
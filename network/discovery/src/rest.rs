@@ -0,0 +1,288 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::DISCOVERY_COUNTS, validator_set::extract_validator_set_updates, DiscoveryError,
+};
+use aptos_config::{
+    config::{PeerSet, RestDiscoverySource},
+    network_id::NetworkContext,
+};
+use aptos_rest_client::Client;
+use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
+use aptos_types::{account_config::CORE_CODE_ADDRESS, on_chain_config::ValidatorSet};
+use futures::{future::BoxFuture, Future, FutureExt, Stream};
+use network::counters::inc_by_with_context;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use url::Url;
+
+/// Timeout of a request for a static seed list
+const SEED_LIST_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Periodically fetches peers from a URL, either the on-chain `ValidatorSet` from the REST API
+/// of a node, or a static seed list.
+pub struct RestStream {
+    network_context: NetworkContext,
+    source: PeerSource,
+    interval: Pin<Box<Interval>>,
+    pending_request: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl RestStream {
+    pub(crate) fn new(
+        network_context: NetworkContext,
+        rest_url: Url,
+        rest_source: RestDiscoverySource,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        let source = match rest_source {
+            RestDiscoverySource::ValidatorSet => PeerSource::ValidatorSet(Client::new(rest_url)),
+            RestDiscoverySource::SeedList => PeerSource::SeedList(
+                reqwest::Client::builder()
+                    .timeout(SEED_LIST_REQUEST_TIMEOUT)
+                    .build()
+                    .expect("Failed to build the HTTP client"),
+                rest_url,
+            ),
+        };
+        RestStream {
+            network_context,
+            source,
+            interval: Box::pin(time_service.interval(interval_duration)),
+            pending_request: None,
+        }
+    }
+}
+
+impl Stream for RestStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Wait for delay, then start a request unless one is in flight
+        if self.pending_request.is_none() {
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            let request = self.source.clone().fetch(self.network_context);
+            self.pending_request = Some(request.boxed());
+        }
+
+        let result = futures::ready!(self
+            .pending_request
+            .as_mut()
+            .expect("Request must be in flight")
+            .as_mut()
+            .poll(cx));
+        self.pending_request = None;
+
+        if result.is_err() {
+            inc_by_with_context(&DISCOVERY_COUNTS, &self.network_context, "rest_failure", 1);
+        }
+        Poll::Ready(Some(result))
+    }
+}
+
+#[derive(Clone)]
+enum PeerSource {
+    /// The REST API of a node, serving the on-chain `ValidatorSet`
+    ValidatorSet(Client),
+    /// A URL serving a `PeerSet` as YAML (or JSON), in the format of `DiscoveryMethod::File`
+    SeedList(reqwest::Client, Url),
+}
+
+impl PeerSource {
+    async fn fetch(self, network_context: NetworkContext) -> Result<PeerSet, DiscoveryError> {
+        match self {
+            PeerSource::ValidatorSet(rest_client) => {
+                let validator_set = rest_client
+                    .get_account_resource_bcs::<ValidatorSet>(
+                        CORE_CODE_ADDRESS,
+                        "0x1::stake::ValidatorSet",
+                    )
+                    .await
+                    .map_err(DiscoveryError::Rest)?
+                    .into_inner();
+                Ok(extract_validator_set_updates(
+                    network_context,
+                    validator_set,
+                ))
+            }
+            PeerSource::SeedList(http_client, url) => {
+                let contents = http_client
+                    .get(url)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|err| DiscoveryError::Rest(err.into()))?
+                    .text()
+                    .await
+                    .map_err(|err| DiscoveryError::Rest(err.into()))?;
+                serde_yaml::from_str(&contents)
+                    .map_err(|err| DiscoveryError::Parsing(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscoveryChangeListener;
+    use aptos_config::config::{Peer, PeerRole, HANDSHAKE_VERSION};
+    use aptos_crypto::{bls12381, x25519, PrivateKey, Uniform};
+    use aptos_logger::spawn_named;
+    use aptos_rest_client::aptos_api_types::{
+        X_APTOS_BLOCK_HEIGHT, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_OLDEST_VERSION,
+        X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION, X_APTOS_OLDEST_BLOCK_HEIGHT,
+    };
+    use aptos_types::{
+        network_address::NetworkAddress, validator_config::ValidatorConfig,
+        validator_info::ValidatorInfo, PeerId,
+    };
+    use futures::StreamExt;
+    use network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{collections::HashSet, str::FromStr};
+    use warp::{http::Response, Filter};
+
+    /// Serves `body` with `status` and the ledger headers of the REST API on any path
+    fn serve(status: u16, body: Vec<u8>) -> Url {
+        let route = warp::any().map(move || {
+            Response::builder()
+                .status(status)
+                .header(X_APTOS_CHAIN_ID, "4")
+                .header(X_APTOS_EPOCH, "1")
+                .header(X_APTOS_LEDGER_VERSION, "10")
+                .header(X_APTOS_LEDGER_OLDEST_VERSION, "0")
+                .header(X_APTOS_LEDGER_TIMESTAMP, "1000")
+                .header(X_APTOS_BLOCK_HEIGHT, "5")
+                .header(X_APTOS_OLDEST_BLOCK_HEIGHT, "0")
+                .body(body.clone())
+                .unwrap()
+        });
+        let (address, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        spawn_named!("[Network] Server Task", server);
+        Url::parse(&format!("http://localhost:{}/", address.port())).unwrap()
+    }
+
+    fn create_stream(url: Url, source: RestDiscoverySource) -> RestStream {
+        RestStream::new(
+            NetworkContext::mock(),
+            url,
+            source,
+            Duration::from_millis(5),
+            TimeService::real(),
+        )
+    }
+
+    fn create_validator_set() -> ValidatorSet {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let consensus_pubkey = bls12381::PrivateKey::generate(&mut rng).public_key();
+        let network_pubkey = x25519::PrivateKey::generate(&mut rng).public_key();
+        let addresses =
+            vec![NetworkAddress::mock().append_prod_protos(network_pubkey, HANDSHAKE_VERSION)];
+        let encoded_addresses = bcs::to_bytes(&addresses).unwrap();
+        ValidatorSet::new(vec![ValidatorInfo::new(
+            PeerId::random(),
+            0,
+            ValidatorConfig::new(
+                consensus_pubkey,
+                encoded_addresses.clone(),
+                encoded_addresses,
+                0,
+            ),
+        )])
+    }
+
+    fn create_seed_list() -> PeerSet {
+        let addr = NetworkAddress::from_str("/ip4/1.2.3.4/tcp/6180/noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/handshake/0").unwrap();
+        let key = addr.find_noise_proto().unwrap();
+        let mut peers = PeerSet::new();
+        peers.insert(
+            PeerId::random(),
+            Peer::new(vec![addr], HashSet::from([key]), PeerRole::Upstream),
+        );
+        peers
+    }
+
+    #[tokio::test]
+    async fn test_rest_listener() {
+        let validator_set = create_validator_set();
+        let url = serve(200, bcs::to_bytes(&validator_set).unwrap());
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) =
+            channel::new(1, &network::counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS);
+        let listener = DiscoveryChangeListener::rest(
+            NetworkContext::mock(),
+            conn_mgr_reqs_tx,
+            url,
+            RestDiscoverySource::ValidatorSet,
+            Duration::from_millis(5),
+            TimeService::real(),
+        );
+        spawn_named!("[Network] Listener Task", Box::pin(listener).run());
+
+        let expected_peers = extract_validator_set_updates(NetworkContext::mock(), validator_set);
+        assert_eq!(expected_peers.len(), 1);
+        if let Some(ConnectivityRequest::UpdateDiscoveredPeers(
+            DiscoverySource::Rest,
+            actual_peers,
+        )) = conn_mgr_reqs_rx.next().await
+        {
+            assert_eq!(expected_peers, actual_peers)
+        } else {
+            panic!("No message sent by discovery")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seed_list() {
+        let peers = create_seed_list();
+        let url = serve(200, serde_yaml::to_vec(&peers).unwrap());
+        let mut stream = create_stream(url, RestDiscoverySource::SeedList);
+
+        // The seed list is fetched again on every interval
+        for _ in 0..2 {
+            assert_eq!(stream.next().await.unwrap().unwrap(), peers);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failures() {
+        // Server errors
+        let url = serve(500, vec![]);
+        for source in [
+            RestDiscoverySource::ValidatorSet,
+            RestDiscoverySource::SeedList,
+        ] {
+            let mut stream = create_stream(url.clone(), source);
+            assert!(matches!(
+                stream.next().await.unwrap(),
+                Err(DiscoveryError::Rest(_))
+            ));
+        }
+
+        // Garbage responses
+        let url = serve(200, b"not a peer set".to_vec());
+        let mut stream = create_stream(url.clone(), RestDiscoverySource::ValidatorSet);
+        assert!(matches!(
+            stream.next().await.unwrap(),
+            Err(DiscoveryError::Rest(_))
+        ));
+        let mut stream = create_stream(url, RestDiscoverySource::SeedList);
+        assert!(matches!(
+            stream.next().await.unwrap(),
+            Err(DiscoveryError::Parsing(_))
+        ));
+
+        // Nothing listening
+        let url = Url::parse("http://localhost:1/").unwrap();
+        let mut stream = create_stream(url, RestDiscoverySource::SeedList);
+        assert!(matches!(
+            stream.next().await.unwrap(),
+            Err(DiscoveryError::Rest(_))
+        ));
+    }
+}
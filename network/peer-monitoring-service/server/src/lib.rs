@@ -9,13 +9,17 @@ use crate::{
     network::PeerMonitoringServiceNetworkEvents,
 };
 use ::network::{application::storage::PeerMetadataStorage, ProtocolId};
-use aptos_config::config::PeerMonitoringServiceConfig;
+use aptos_config::{
+    config::{BaseConfig, PeerMonitoringServiceConfig},
+    network_id::NetworkId,
+};
 use aptos_logger::prelude::*;
 use bounded_executor::BoundedExecutor;
 use futures::stream::StreamExt;
 use peer_monitoring_service_types::{
-    ConnectedPeersResponse, PeerMonitoringServiceError, PeerMonitoringServiceRequest,
-    PeerMonitoringServiceResponse, Result, ServerProtocolVersionResponse,
+    ConnectedPeersResponse, DepthFromValidatorsResponse, PeerMonitoringServiceError,
+    PeerMonitoringServiceRequest, PeerMonitoringServiceResponse, PingResponse, Result,
+    ServerProtocolVersionResponse,
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
@@ -31,6 +35,7 @@ mod tests;
 
 /// Peer monitoring server constants
 pub const PEER_MONITORING_SERVER_VERSION: u64 = 1;
+pub const MAX_DEPTH_FROM_VALIDATORS: u64 = 100; // The depth reported when the server can't tell
 
 #[derive(Clone, Debug, Deserialize, Error, PartialEq, Eq, Serialize)]
 pub enum Error {
@@ -52,6 +57,7 @@ impl Error {
 
 /// The server-side actor for the peer monitoring service
 pub struct PeerMonitoringServiceServer {
+    base_config: BaseConfig,
     bounded_executor: BoundedExecutor,
     network_requests: PeerMonitoringServiceNetworkEvents,
     peer_metadata: Arc<PeerMetadataStorage>,
@@ -59,6 +65,7 @@ pub struct PeerMonitoringServiceServer {
 
 impl PeerMonitoringServiceServer {
     pub fn new(
+        base_config: BaseConfig,
         config: PeerMonitoringServiceConfig,
         executor: Handle,
         network_requests: PeerMonitoringServiceNetworkEvents,
@@ -68,6 +75,7 @@ impl PeerMonitoringServiceServer {
             BoundedExecutor::new(config.max_concurrent_requests as usize, executor);
 
         Self {
+            base_config,
             bounded_executor,
             network_requests,
             peer_metadata,
@@ -89,10 +97,11 @@ impl PeerMonitoringServiceServer {
 
             // All handler methods are currently CPU-bound so we want
             // to spawn on the blocking thread pool.
+            let base_config = self.base_config.clone();
             let peer_metadata = self.peer_metadata.clone();
             self.bounded_executor
                 .spawn_blocking(move || {
-                    let response = Handler::new(peer_metadata).call(protocol, request);
+                    let response = Handler::new(base_config, peer_metadata).call(protocol, request);
                     log_monitoring_service_response(&response);
                     response_sender.send(response);
                 })
@@ -106,12 +115,16 @@ impl PeerMonitoringServiceServer {
 /// request. We usually clone/create a new handler for every request.
 #[derive(Clone)]
 pub struct Handler {
+    base_config: BaseConfig,
     peer_metadata: Arc<PeerMetadataStorage>,
 }
 
 impl Handler {
-    pub fn new(peer_metadata: Arc<PeerMetadataStorage>) -> Self {
-        Self { peer_metadata }
+    pub fn new(base_config: BaseConfig, peer_metadata: Arc<PeerMetadataStorage>) -> Self {
+        Self {
+            base_config,
+            peer_metadata,
+        }
     }
 
     pub fn call(
@@ -198,7 +211,31 @@ impl Handler {
     }
 
     fn get_depth_from_validators(&self) -> Result<PeerMonitoringServiceResponse, Error> {
-        unimplemented!();
+        // Validators are at depth 0, and their fullnodes are directly connected to
+        // them over the VFN network. Public fullnodes only know their upstream peers,
+        // so they can't tell how far they are from the validators.
+        let depth = if self.base_config.role.is_validator() {
+            0
+        } else if self.is_connected_to_network(NetworkId::Vfn) {
+            1
+        } else {
+            MAX_DEPTH_FROM_VALIDATORS
+        };
+
+        Ok(PeerMonitoringServiceResponse::DepthFromValidators(
+            DepthFromValidatorsResponse { depth },
+        ))
+    }
+
+    /// Returns true iff we have a connected peer on the given network
+    fn is_connected_to_network(&self, network_id: NetworkId) -> bool {
+        self.peer_metadata
+            .networks()
+            .any(|network| network == network_id)
+            && !self
+                .peer_metadata
+                .read_filtered(network_id, |(_, peer_info)| peer_info.is_connected())
+                .is_empty()
     }
 
     fn get_known_peers(&self) -> Result<PeerMonitoringServiceResponse, Error> {
@@ -218,7 +255,7 @@ impl Handler {
     }
 
     fn handle_ping(&self) -> Result<PeerMonitoringServiceResponse, Error> {
-        unimplemented!();
+        Ok(PeerMonitoringServiceResponse::Ping(PingResponse {}))
     }
 }
 
@@ -4,10 +4,11 @@
 #![forbid(unsafe_code)]
 
 use crate::{
-    PeerMonitoringServiceNetworkEvents, PeerMonitoringServiceServer, PEER_MONITORING_SERVER_VERSION,
+    PeerMonitoringServiceNetworkEvents, PeerMonitoringServiceServer, MAX_DEPTH_FROM_VALIDATORS,
+    PEER_MONITORING_SERVER_VERSION,
 };
 use aptos_config::{
-    config::{PeerMonitoringServiceConfig, PeerRole},
+    config::{BaseConfig, PeerMonitoringServiceConfig, PeerRole, RoleType},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_logger::Level;
@@ -29,8 +30,9 @@ use network::{
     transport::{ConnectionId, ConnectionMetadata},
 };
 use peer_monitoring_service_types::{
-    ConnectedPeersResponse, PeerMonitoringServiceError, PeerMonitoringServiceMessage,
-    PeerMonitoringServiceRequest, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
+    ConnectedPeersResponse, DepthFromValidatorsResponse, PeerMonitoringServiceError,
+    PeerMonitoringServiceMessage, PeerMonitoringServiceRequest, PeerMonitoringServiceResponse,
+    PingResponse, ServerProtocolVersionResponse,
 };
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -41,7 +43,7 @@ use std::{
 #[tokio::test]
 async fn test_get_server_protocol_version() {
     // Create the peer monitoring client and server
-    let (mut mock_client, service, _) = MockClient::new();
+    let (mut mock_client, service, _) = MockClient::new(None);
     tokio::spawn(service.start());
 
     // Process a request to fetch the protocol version
@@ -59,7 +61,7 @@ async fn test_get_server_protocol_version() {
 #[tokio::test]
 async fn test_get_connected_peers() {
     // Create the peer monitoring client and server
-    let (mut mock_client, service, peer_metadata_storage) = MockClient::new();
+    let (mut mock_client, service, peer_metadata_storage) = MockClient::new(None);
     tokio::spawn(service.start());
 
     // Process a request to fetch the connected peers
@@ -123,6 +125,86 @@ async fn test_get_connected_peers() {
     assert_eq!(response, expected_response);
 }
 
+#[tokio::test]
+async fn test_ping() {
+    // Create the peer monitoring client and server
+    let (mut mock_client, service, _) = MockClient::new(None);
+    tokio::spawn(service.start());
+
+    // Process a ping request and verify the response
+    let request = PeerMonitoringServiceRequest::Ping;
+    let response = mock_client.send_request(request).await.unwrap();
+    assert_eq!(
+        response,
+        PeerMonitoringServiceResponse::Ping(PingResponse {})
+    );
+}
+
+#[tokio::test]
+async fn test_get_depth_from_validators() {
+    // Validators are at depth 0
+    let base_config = BaseConfig {
+        role: RoleType::Validator,
+        ..Default::default()
+    };
+    let (mut mock_client, service, _) = MockClient::new(Some(base_config));
+    tokio::spawn(service.start());
+    verify_depth_from_validators(&mut mock_client, 0).await;
+
+    // Fullnodes don't know their depth until they're connected to a validator
+    let base_config = BaseConfig {
+        role: RoleType::FullNode,
+        ..Default::default()
+    };
+    let (mut mock_client, service, peer_metadata_storage) = MockClient::new(Some(base_config));
+    tokio::spawn(service.start());
+    let public_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    peer_metadata_storage.insert(public_peer, create_peer_info(public_peer));
+    verify_depth_from_validators(&mut mock_client, MAX_DEPTH_FROM_VALIDATORS).await;
+
+    // Validator fullnodes are directly connected to their validator
+    let validator_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    peer_metadata_storage.insert(validator_peer, create_peer_info(validator_peer));
+    verify_depth_from_validators(&mut mock_client, 1).await;
+
+    // Disconnect the validator and verify the depth is unknown again
+    peer_metadata_storage
+        .write(validator_peer, |entry| match entry {
+            Entry::Vacant(..) => Err(PeerError::NotFound),
+            Entry::Occupied(inner) => {
+                inner.get_mut().status = PeerState::Disconnected;
+                Ok(())
+            }
+        })
+        .unwrap();
+    verify_depth_from_validators(&mut mock_client, MAX_DEPTH_FROM_VALIDATORS).await;
+}
+
+/// Creates the info of a newly connected peer
+fn create_peer_info(peer_network_id: PeerNetworkId) -> PeerInfo {
+    let connection_metadata = ConnectionMetadata::new(
+        peer_network_id.peer_id(),
+        ConnectionId::default(),
+        NetworkAddress::from_str("/ip4/127.0.0.1/tcp/8081").unwrap(),
+        ConnectionOrigin::Outbound,
+        MessagingProtocolVersion::V1,
+        ProtocolIdSet::empty(),
+        PeerRole::Unknown,
+    );
+    PeerInfo::new(connection_metadata)
+}
+
+/// Fetches the depth from the validators and verifies it matches the expected depth
+async fn verify_depth_from_validators(mock_client: &mut MockClient, expected_depth: u64) {
+    let request = PeerMonitoringServiceRequest::GetDepthFromValidators;
+    let response = mock_client.send_request(request).await.unwrap();
+    let expected_response =
+        PeerMonitoringServiceResponse::DepthFromValidators(DepthFromValidatorsResponse {
+            depth: expected_depth,
+        });
+    assert_eq!(response, expected_response);
+}
+
 /// A wrapper around the inbound network interface/channel for easily sending
 /// mock client requests to a [`PeerMonitoringServiceServer`].
 struct MockClient {
@@ -130,7 +212,9 @@ struct MockClient {
 }
 
 impl MockClient {
-    fn new() -> (Self, PeerMonitoringServiceServer, Arc<PeerMetadataStorage>) {
+    fn new(
+        base_config: Option<BaseConfig>,
+    ) -> (Self, PeerMonitoringServiceServer, Arc<PeerMetadataStorage>) {
         initialize_logger();
 
         // Create the peer monitoring service event stream
@@ -149,9 +233,11 @@ impl MockClient {
         );
 
         // Create the peer monitoring server
-        let peer_metadata_storage = PeerMetadataStorage::new(&[NetworkId::Validator]);
+        let peer_metadata_storage =
+            PeerMetadataStorage::new(&[NetworkId::Validator, NetworkId::Vfn, NetworkId::Public]);
         let executor = tokio::runtime::Handle::current();
         let peer_monitoring_server = PeerMonitoringServiceServer::new(
+            base_config.unwrap_or_default(),
             peer_monitoring_service_config,
             executor,
             network_request_stream,
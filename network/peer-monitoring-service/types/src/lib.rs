@@ -90,7 +90,7 @@ pub struct ConnectedPeersResponse {
 /// A response for the depth from validators request
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct DepthFromValidatorsResponse {
-    pub todo: bool,
+    pub depth: u64, // The number of hops between the server and the validators
 }
 
 /// A response for the known peers request
@@ -101,9 +101,7 @@ pub struct KnownPeersResponse {
 
 /// A response for the ping request
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-pub struct PingResponse {
-    pub todo: bool,
-}
+pub struct PingResponse {}
 
 /// A response for the server protocol version request
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    connectivity_manager::{ConnectivityManager, ConnectivityRequest},
+    connectivity_manager::{reputation::PeerReputations, ConnectivityManager, ConnectivityRequest},
     counters,
     peer_manager::{conn_notifs_channel, ConnectionRequestSender},
 };
@@ -34,6 +34,7 @@ impl ConnectivityManagerBuilder {
         connection_notifs_rx: conn_notifs_channel::Receiver,
         outbound_connection_limit: Option<usize>,
         mutual_authentication: bool,
+        peer_reputations: Arc<PeerReputations>,
     ) -> Self {
         let (conn_mgr_reqs_tx, conn_mgr_reqs_rx) = channel::new(
             channel_size,
@@ -55,6 +56,7 @@ impl ConnectivityManagerBuilder {
                 Duration::from_millis(max_connection_delay_ms),
                 outbound_connection_limit,
                 mutual_authentication,
+                peer_reputations,
             )),
         }
     }
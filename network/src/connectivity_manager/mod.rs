@@ -25,8 +25,12 @@
 //! absolutely important that we maintain connectivity with all peers and heal
 //! any partitions asap, as we aren't currently gossiping consensus messages or
 //! using a relay protocol.
+//!
+//! Peers that are banned for misbehaving (see [`reputation`]) are neither dialed
+//! nor kept connected to.
 
 use crate::{
+    connectivity_manager::reputation::PeerReputations,
     counters,
     logging::NetworkSchema,
     peer_manager::{self, conn_notifs_channel, ConnectionRequestSender, PeerManagerError},
@@ -64,6 +68,7 @@ use std::{
 use tokio_retry::strategy::jitter;
 
 pub mod builder;
+pub mod reputation;
 #[cfg(test)]
 mod test;
 
@@ -118,6 +123,8 @@ pub struct ConnectivityManager<TBackoff> {
     rng: SmallRng,
     /// Whether we are using mutual authentication or not
     mutual_authentication: bool,
+    /// Reputations of the peers, used to keep away from banned peers
+    peer_reputations: Arc<PeerReputations>,
 }
 
 /// Different sources for peer addresses, ordered by priority (Onchain=highest,
@@ -127,6 +134,7 @@ pub struct ConnectivityManager<TBackoff> {
 pub enum DiscoverySource {
     OnChainValidatorSet,
     File,
+    Rest,
     Config,
 }
 
@@ -144,6 +152,7 @@ impl fmt::Display for DiscoverySource {
             match self {
                 DiscoverySource::OnChainValidatorSet => "OnChainValidatorSet",
                 DiscoverySource::File => "File",
+                DiscoverySource::Rest => "Rest",
                 DiscoverySource::Config => "Config",
             }
         )
@@ -186,6 +195,16 @@ impl DiscoveredPeerSet {
         }
     }
 
+    /// The peers discovered from the on-chain validator set. Peers discovered through the REST
+    /// API of another node aren't included, as the endpoint isn't authenticated.
+    fn on_chain_peers(&self) -> HashSet<PeerId> {
+        self.0
+            .iter()
+            .filter(|(_, peer)| peer.keys.has_src(DiscoverySource::OnChainValidatorSet))
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+
     /// Converts `DiscoveredPeerSet` into a `PeerSet`, however disregards the source of discovery
     /// TODO: Provide smarter merging based on discovery source
     pub fn to_eligible_peers(&self) -> PeerSet {
@@ -313,6 +332,7 @@ where
         max_delay: Duration,
         outbound_connection_limit: Option<usize>,
         mutual_authentication: bool,
+        peer_reputations: Arc<PeerReputations>,
     ) -> Self {
         assert!(
             eligible.read().is_empty(),
@@ -343,6 +363,7 @@ where
             outbound_connection_limit,
             rng: SmallRng::from_entropy(),
             mutual_authentication,
+            peer_reputations,
         };
 
         // set the initial config addresses and pubkeys
@@ -406,7 +427,7 @@ where
         );
     }
 
-    /// Disconnect from all peers that are no longer eligible.
+    /// Disconnect from all peers that are no longer eligible or are banned.
     ///
     /// For instance, a validator might leave the validator set after a
     /// reconfiguration. If we are currently connected to this validator, calling
@@ -417,11 +438,15 @@ where
         for p in self
             .connected
             .iter()
-            .filter(|(peer_id, _)| !eligible.contains_key(peer_id))
+            .filter(|(peer_id, _)| {
+                !eligible.contains_key(peer_id) || self.peer_reputations.is_banned(peer_id)
+            })
             .filter_map(|(peer_id, metadata)| {
-                // If we're using server only auth, we need to not evict unknown peers
+                // If we're using server only auth, we need to not evict unknown peers, unless
+                // they're banned
                 // TODO: We should prevent `Unknown` from discovery sources
                 if !self.mutual_authentication
+                    && !self.peer_reputations.is_banned(peer_id)
                     && metadata.origin == ConnectionOrigin::Inbound
                     && metadata.role == PeerRole::Unknown
                 {
@@ -453,7 +478,7 @@ where
         }
     }
 
-    /// Cancel all pending dials to peers that are no longer eligible.
+    /// Cancel all pending dials to peers that are no longer eligible or are banned.
     ///
     /// For instance, a validator might leave the validator set after a
     /// reconfiguration. If there is a pending dial to this validator, calling
@@ -463,7 +488,9 @@ where
         let stale_dials: Vec<_> = self
             .dial_queue
             .keys()
-            .filter(|peer_id| !eligible.contains_key(peer_id))
+            .filter(|peer_id| {
+                !eligible.contains_key(peer_id) || self.peer_reputations.is_banned(peer_id)
+            })
             .cloned()
             .collect();
 
@@ -501,6 +528,7 @@ where
                     && !self.connected.contains_key(peer_id) // The node is not already connected.
                     && !self.dial_queue.contains_key(peer_id) // There is no pending dial to this node.
                     && roles_to_dial.contains(&peer.role) // We can dial this role
+                    && !self.peer_reputations.is_banned(peer_id) // The node is not banned
             })
             .collect();
 
@@ -761,11 +789,19 @@ where
             }
         }
 
+        // Peers of the on-chain validator set must never be banned
+        if src == DiscoverySource::OnChainValidatorSet {
+            self.peer_reputations
+                .set_protected_peers(self.discovered_peers.on_chain_peers());
+        }
+
         // update eligible peers accordingly
         if keys_updated {
             // For each peer, union all of the pubkeys from each discovery source
             // to generate the new eligible peers set.
             let new_eligible = self.discovered_peers.to_eligible_peers();
+            self.peer_reputations
+                .set_trusted_peers(new_eligible.keys().copied().collect());
 
             // Swap in the new eligible peers set. Drop the old set after releasing
             // the write lock.
@@ -973,6 +1009,10 @@ impl PublicKeys {
         self.update(src, HashSet::new())
     }
 
+    fn has_src(&self, src: DiscoverySource) -> bool {
+        !self.0[src.as_usize()].is_empty()
+    }
+
     fn union(&self) -> HashSet<x25519::PublicKey> {
         self.0.iter().flatten().copied().collect()
     }
@@ -0,0 +1,578 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reputation of the peers of a network, and the list of peers banned from it.
+//!
+//! Misbehaviors of a peer, e.g., invalid messages, RPC floods and failed handshakes, are
+//! recorded as they're detected by the [`Peer`](crate::peer::Peer) actors and the transport.
+//! Only identities authenticated by the Noise handshake are recorded against. If automatic bans
+//! are enabled, a peer that misbehaves `failure_threshold` times within the failure window is
+//! banned for a while, and every following ban of the peer lasts twice as long as the last one,
+//! up to `max_ban_secs`. Operators can also ban peers in the config, or ban and unban them at
+//! runtime through the inspection service.
+//!
+//! Banned peers aren't dialed by the [`ConnectivityManager`](super::ConnectivityManager) and
+//! their connections are closed. Peers of the on-chain validator set are never banned: on
+//! networks discovering the validator set on-chain, no ban is enforced until the validator set
+//! is known. The reputations are persisted to disk by a background thread, so that bans survive
+//! restarts.
+
+use crate::{counters, logging::NetworkSchema};
+use anyhow::Result;
+use aptos_config::{
+    config::PeerReputationConfig,
+    network_id::{NetworkContext, NetworkId},
+};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::PeerId;
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt, fs,
+    io::{ErrorKind, Write},
+    iter,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The maximum number of peers outside of the eligible peers whose reputation is tracked.
+/// Misbehaviors of new peers are ignored while the limit is reached and no stale reputation can
+/// be pruned. The eligible peers are always tracked, so that peers minting new identities can't
+/// crowd them out.
+pub const MAX_TRACKED_PEERS: usize = 10_000;
+
+/// A misbehavior of a peer that counts against its reputation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Misbehavior {
+    /// The peer exceeded its limit of concurrent inbound RPCs
+    RpcError,
+    /// The peer sent a message or stream we couldn't make sense of
+    InvalidMessage,
+    /// The peer failed the protocol negotiation, e.g., it's on another chain
+    HandshakeFailure,
+    /// The peer failed the Noise authentication
+    NoiseAuthFailure,
+}
+
+impl Misbehavior {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Misbehavior::RpcError => "rpc_error",
+            Misbehavior::InvalidMessage => "invalid_message",
+            Misbehavior::HandshakeFailure => "handshake_failure",
+            Misbehavior::NoiseAuthFailure => "noise_auth_failure",
+        }
+    }
+}
+
+impl fmt::Display for Misbehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BanReason {
+    /// The peer misbehaved repeatedly, the last misbehavior is kept
+    Misbehavior(Misbehavior),
+    /// The peer was banned by an operator at runtime
+    Manual,
+    /// The peer is banned in the node config
+    Config,
+}
+
+impl BanReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BanReason::Misbehavior(_) => "misbehavior",
+            BanReason::Manual => "manual",
+            BanReason::Config => "config",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Ban {
+    pub reason: BanReason,
+    /// When the ban expires, in seconds since the unix epoch. `None` if it never expires.
+    pub expires_at_secs: Option<u64>,
+}
+
+impl Ban {
+    fn is_active(&self, now_secs: u64) -> bool {
+        self.expires_at_secs
+            .map_or(true, |expires_at_secs| now_secs < expires_at_secs)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ReputationError {
+    #[error("Peer {0} is in the on-chain validator set and can't be banned")]
+    ProtectedPeer(PeerId),
+
+    #[error("Peer {0} is banned in the node config and can't be unbanned at runtime")]
+    ConfiguredBan(PeerId),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+struct PeerReputation {
+    /// When the misbehaviors within the failure window happened, in seconds since the unix epoch
+    recent_failures: VecDeque<u64>,
+    /// The number of automatic bans of the peer so far, which sets the length of the next one
+    num_bans: u32,
+    ban: Option<Ban>,
+}
+
+impl PeerReputation {
+    /// Whether the reputation can be forgotten: the peer has no recent failures, and its last
+    /// ban expired long enough ago that the backoff no longer matters.
+    fn is_stale(&self, now_secs: u64, config: &PeerReputationConfig) -> bool {
+        let window_start_secs = now_secs.saturating_sub(config.failure_window_secs);
+        let no_recent_failures = self
+            .recent_failures
+            .iter()
+            .all(|failure_secs| *failure_secs < window_start_secs);
+        let ban_forgotten = self.ban.as_ref().map_or(true, |ban| {
+            ban.expires_at_secs.map_or(false, |expires_at_secs| {
+                expires_at_secs.saturating_add(config.max_ban_secs) <= now_secs
+            })
+        });
+        no_recent_failures && ban_forgotten
+    }
+}
+
+struct ReputationState {
+    reputations: BTreeMap<PeerId, PeerReputation>,
+    /// The peers of the on-chain validator set, which are never banned
+    protected_peers: HashSet<PeerId>,
+    /// Whether the peers of the on-chain validator set are known. Until they are, no ban is
+    /// enforced, as validators can't be told apart from the other peers.
+    validator_set_known: bool,
+    /// The eligible peers of the network, whose reputation is always tracked
+    trusted_peers: HashSet<PeerId>,
+}
+
+impl ReputationState {
+    /// The number of tracked peers that aren't eligible
+    fn num_untrusted_peers(&self) -> usize {
+        let num_trusted_peers = self
+            .trusted_peers
+            .iter()
+            .filter(|peer_id| self.reputations.contains_key(peer_id))
+            .count();
+        self.reputations.len() - num_trusted_peers
+    }
+}
+
+/// A request to the thread persisting the reputations
+enum PersistRequest {
+    /// Replace the persisted reputations with these ones
+    Write(BTreeMap<PeerId, PeerReputation>),
+    /// Acknowledge once the reputations sent so far are written
+    Flush(mpsc::Sender<()>),
+}
+
+/// The reputations of the peers of a network, shared between the actors of the network.
+pub struct PeerReputations {
+    network_context: NetworkContext,
+    time_service: TimeService,
+    config: PeerReputationConfig,
+    /// Sends the reputations to the thread persisting them, if persistence is enabled
+    persist_tx: Option<Mutex<mpsc::Sender<PersistRequest>>>,
+    state: RwLock<ReputationState>,
+}
+
+impl PeerReputations {
+    /// Creates the reputations of the network, reading back the persisted ones if there are any.
+    pub fn new(
+        network_context: NetworkContext,
+        time_service: TimeService,
+        config: PeerReputationConfig,
+    ) -> Self {
+        let path = if config.enable_persistence {
+            Some(config.persistence_path(network_context.network_id()))
+        } else {
+            None
+        };
+        let reputations = match path.as_deref().map(read_reputations) {
+            Some(Ok(mut reputations)) => {
+                let now_secs = time_service.now_secs();
+                reputations.retain(|_, reputation| !reputation.is_stale(now_secs, &config));
+                reputations
+            }
+            Some(Err(error)) => {
+                warn!(
+                    NetworkSchema::new(&network_context),
+                    error = %error,
+                    "{} Failed to read the persisted peer reputations, starting afresh: {}",
+                    network_context,
+                    error
+                );
+                BTreeMap::new()
+            }
+            None => BTreeMap::new(),
+        };
+        let persist_tx = path.map(|path| Mutex::new(spawn_persister(network_context, path)));
+
+        Self {
+            network_context,
+            time_service,
+            config,
+            persist_tx,
+            state: RwLock::new(ReputationState {
+                reputations,
+                protected_peers: HashSet::new(),
+                validator_set_known: true,
+                trusted_peers: HashSet::new(),
+            }),
+        }
+    }
+
+    /// Creates reputations that are only kept in memory, with the default ban policy.
+    pub fn in_memory(network_context: NetworkContext, time_service: TimeService) -> Self {
+        let mut config = PeerReputationConfig::default();
+        config.enable_persistence = false;
+        Self::new(network_context, time_service, config)
+    }
+
+    /// Stops enforcing bans until the peers of the on-chain validator set are known, so that
+    /// validators aren't banned, e.g., by the persisted bans after a restart. Called before the
+    /// network starts on networks discovering the validator set on-chain.
+    pub fn wait_for_validator_set(&self) {
+        self.state.write().validator_set_known = false;
+    }
+
+    /// Whether the peer is currently banned from the network.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        let state = self.state.read();
+        if !state.validator_set_known || state.protected_peers.contains(peer_id) {
+            return false;
+        }
+        if self.config.banned_peers.contains(peer_id) {
+            return true;
+        }
+
+        let now_secs = self.time_service.now_secs();
+        state
+            .reputations
+            .get(peer_id)
+            .and_then(|reputation| reputation.ban.as_ref())
+            .map_or(false, |ban| ban.is_active(now_secs))
+    }
+
+    /// Records a misbehavior of the peer, banning it if it misbehaved too often recently.
+    pub fn record_misbehavior(&self, peer_id: PeerId, misbehavior: Misbehavior) {
+        counters::peer_misbehaviors(&self.network_context, misbehavior).inc();
+        if !self.config.enable_automatic_bans {
+            return;
+        }
+
+        let now_secs = self.time_service.now_secs();
+        let mut state = self.state.write();
+        if state.protected_peers.contains(&peer_id) {
+            return;
+        }
+
+        if !state.reputations.contains_key(&peer_id)
+            && !state.trusted_peers.contains(&peer_id)
+            && state.num_untrusted_peers() >= MAX_TRACKED_PEERS
+        {
+            state
+                .reputations
+                .retain(|_, reputation| !reputation.is_stale(now_secs, &self.config));
+            if state.num_untrusted_peers() >= MAX_TRACKED_PEERS {
+                return;
+            }
+        }
+
+        let reputation = state.reputations.entry(peer_id).or_default();
+        if reputation
+            .ban
+            .as_ref()
+            .map_or(false, |ban| ban.is_active(now_secs))
+        {
+            return;
+        }
+
+        let window_start_secs = now_secs.saturating_sub(self.config.failure_window_secs);
+        reputation
+            .recent_failures
+            .retain(|failure_secs| *failure_secs >= window_start_secs);
+        reputation.recent_failures.push_back(now_secs);
+        if (reputation.recent_failures.len() as u64) < self.config.failure_threshold {
+            return;
+        }
+
+        let ban_secs = self.ban_duration_secs(reputation.num_bans);
+        reputation.recent_failures.clear();
+        reputation.num_bans = reputation.num_bans.saturating_add(1);
+        reputation.ban = Some(Ban {
+            reason: BanReason::Misbehavior(misbehavior),
+            expires_at_secs: Some(now_secs.saturating_add(ban_secs)),
+        });
+        warn!(
+            NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+            "{} Banning peer {} for {} secs after repeated misbehavior: {}",
+            self.network_context,
+            peer_id.short_str(),
+            ban_secs,
+            misbehavior
+        );
+        counters::peer_bans(&self.network_context, BanReason::Misbehavior(misbehavior)).inc();
+        self.persist(&state);
+    }
+
+    /// Bans the peer for the given duration, or until it's unbanned if there's none.
+    pub fn ban(&self, peer_id: PeerId, duration: Option<Duration>) -> Result<(), ReputationError> {
+        let now_secs = self.time_service.now_secs();
+        let mut state = self.state.write();
+        if state.protected_peers.contains(&peer_id) {
+            return Err(ReputationError::ProtectedPeer(peer_id));
+        }
+
+        state.reputations.entry(peer_id).or_default().ban = Some(Ban {
+            reason: BanReason::Manual,
+            expires_at_secs: duration.map(|duration| now_secs.saturating_add(duration.as_secs())),
+        });
+        info!(
+            NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+            "{} Banned peer {} for {:?}",
+            self.network_context,
+            peer_id.short_str(),
+            duration
+        );
+        counters::peer_bans(&self.network_context, BanReason::Manual).inc();
+        self.persist(&state);
+        Ok(())
+    }
+
+    /// Lifts the ban of the peer and forgets its recent misbehaviors.
+    pub fn unban(&self, peer_id: PeerId) -> Result<(), ReputationError> {
+        if self.config.banned_peers.contains(&peer_id) {
+            return Err(ReputationError::ConfiguredBan(peer_id));
+        }
+
+        let mut state = self.state.write();
+        if let Some(reputation) = state.reputations.get_mut(&peer_id) {
+            reputation.ban = None;
+            reputation.recent_failures.clear();
+            info!(
+                NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                "{} Unbanned peer {}",
+                self.network_context,
+                peer_id.short_str()
+            );
+            self.persist(&state);
+        }
+        Ok(())
+    }
+
+    /// Sets the peers of the on-chain validator set, which are never banned. Their current bans
+    /// are lifted, and the bans of the other peers are enforced from now on.
+    pub fn set_protected_peers(&self, protected_peers: HashSet<PeerId>) {
+        let mut state = self.state.write();
+        let mut bans_lifted = false;
+        for peer_id in protected_peers.iter() {
+            if let Some(reputation) = state.reputations.remove(peer_id) {
+                if reputation.ban.is_some() {
+                    info!(
+                        NetworkSchema::new(&self.network_context).remote_peer(peer_id),
+                        "{} Lifting the ban of peer {} as it joined the validator set",
+                        self.network_context,
+                        peer_id.short_str()
+                    );
+                    bans_lifted = true;
+                }
+            }
+        }
+        state.protected_peers = protected_peers;
+        state.validator_set_known = true;
+        if bans_lifted {
+            self.persist(&state);
+        }
+    }
+
+    /// Sets the eligible peers of the network, whose misbehaviors are tracked even when the
+    /// reputations of too many other peers are.
+    pub fn set_trusted_peers(&self, trusted_peers: HashSet<PeerId>) {
+        self.state.write().trusted_peers = trusted_peers;
+    }
+
+    /// Blocks until the reputations recorded so far are written to disk, if persistence is
+    /// enabled.
+    pub fn flush(&self) {
+        if let Some(persist_tx) = self.persist_tx.as_ref() {
+            let (ack_tx, ack_rx) = mpsc::channel();
+            if persist_tx
+                .lock()
+                .send(PersistRequest::Flush(ack_tx))
+                .is_ok()
+            {
+                let _ = ack_rx.recv();
+            }
+        }
+    }
+
+    /// Returns the peers that are currently banned and their bans.
+    pub fn banned_peers(&self) -> BTreeMap<PeerId, Ban> {
+        let now_secs = self.time_service.now_secs();
+        let state = self.state.read();
+        let mut banned_peers: BTreeMap<_, _> = state
+            .reputations
+            .iter()
+            .filter_map(|(peer_id, reputation)| {
+                reputation
+                    .ban
+                    .as_ref()
+                    .filter(|ban| ban.is_active(now_secs))
+                    .map(|ban| (*peer_id, ban.clone()))
+            })
+            .collect();
+        for peer_id in self.config.banned_peers.iter() {
+            banned_peers.insert(
+                *peer_id,
+                Ban {
+                    reason: BanReason::Config,
+                    expires_at_secs: None,
+                },
+            );
+        }
+        banned_peers.retain(|peer_id, _| !state.protected_peers.contains(peer_id));
+        banned_peers
+    }
+
+    fn ban_duration_secs(&self, num_bans: u32) -> u64 {
+        self.config
+            .initial_ban_secs
+            .saturating_mul(2u64.saturating_pow(num_bans))
+            .min(self.config.max_ban_secs)
+    }
+
+    /// Hands a snapshot of the reputations of the banned peers to the persisting thread. The
+    /// snapshot is taken under the state lock, so that snapshots are written in order, but it's
+    /// written without holding the lock. The recent failures of the other peers aren't worth
+    /// keeping across restarts.
+    fn persist(&self, state: &ReputationState) {
+        if let Some(persist_tx) = self.persist_tx.as_ref() {
+            let reputations: BTreeMap<_, _> = state
+                .reputations
+                .iter()
+                .filter(|(_, reputation)| reputation.ban.is_some())
+                .map(|(peer_id, reputation)| (*peer_id, reputation.clone()))
+                .collect();
+            if persist_tx
+                .lock()
+                .send(PersistRequest::Write(reputations))
+                .is_err()
+            {
+                warn!(
+                    NetworkSchema::new(&self.network_context),
+                    "{} Failed to persist the peer reputations, the persisting thread is gone",
+                    self.network_context,
+                );
+            }
+        }
+    }
+}
+
+/// Spawns the thread writing the reputations to `path`. Only the latest of the pending
+/// snapshots is written, and write errors are logged rather than failing as the reputations
+/// are still enforced from memory. The thread exits once the reputations are dropped.
+fn spawn_persister(network_context: NetworkContext, path: PathBuf) -> mpsc::Sender<PersistRequest> {
+    let (persist_tx, persist_rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("peer-reputations".to_string())
+        .spawn(move || {
+            while let Ok(request) = persist_rx.recv() {
+                let mut latest_reputations = None;
+                let mut flush_acks = vec![];
+                for request in iter::once(request).chain(persist_rx.try_iter()) {
+                    match request {
+                        PersistRequest::Write(reputations) => {
+                            latest_reputations = Some(reputations)
+                        }
+                        PersistRequest::Flush(ack_tx) => flush_acks.push(ack_tx),
+                    }
+                }
+                if let Some(reputations) = latest_reputations {
+                    if let Err(error) = write_reputations(&path, &reputations) {
+                        warn!(
+                            NetworkSchema::new(&network_context),
+                            error = %error,
+                            "{} Failed to persist the peer reputations to {}: {}",
+                            network_context,
+                            path.display(),
+                            error
+                        );
+                    }
+                }
+                for ack_tx in flush_acks {
+                    let _ = ack_tx.send(());
+                }
+            }
+        })
+        .expect("Failed to spawn the peer reputations persisting thread");
+    persist_tx
+}
+
+fn read_reputations(path: &Path) -> Result<BTreeMap<PeerId, PeerReputation>> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Writes the reputations to a temporary file first, and syncs it to disk before renaming it
+/// over the persisted file, so that a crash can't leave a truncated file behind.
+fn write_reputations(path: &Path, reputations: &BTreeMap<PeerId, PeerReputation>) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(&serde_json::to_vec_pretty(reputations)?)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// The peer reputations of all the networks of a node, shared with the inspection service so
+/// that operators can view and edit the ban lists at runtime.
+#[derive(Default)]
+pub struct PeerReputationRegistry {
+    reputations: RwLock<HashMap<NetworkId, Arc<PeerReputations>>>,
+}
+
+impl PeerReputationRegistry {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn register(&self, network_id: NetworkId, reputations: Arc<PeerReputations>) {
+        self.reputations.write().insert(network_id, reputations);
+    }
+
+    pub fn get(&self, network_id: &NetworkId) -> Option<Arc<PeerReputations>> {
+        self.reputations.read().get(network_id).cloned()
+    }
+
+    /// Returns the peers currently banned from each network.
+    pub fn banned_peers(&self) -> BTreeMap<String, BTreeMap<PeerId, Ban>> {
+        self.reputations
+            .read()
+            .iter()
+            .map(|(network_id, reputations)| {
+                (network_id.as_str().to_string(), reputations.banned_peers())
+            })
+            .collect()
+    }
+}
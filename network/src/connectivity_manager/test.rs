@@ -3,13 +3,17 @@
 
 use super::*;
 use crate::{
+    connectivity_manager::reputation::{
+        BanReason, Misbehavior, ReputationError, MAX_TRACKED_PEERS,
+    },
     peer::DisconnectReason,
     peer_manager::{conn_notifs_channel, ConnectionRequest},
     transport::ConnectionMetadata,
 };
-use aptos_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
+use aptos_config::config::{Peer, PeerReputationConfig, PeerRole, PeerSet, HANDSHAKE_VERSION};
 use aptos_crypto::{test_utils::TEST_SEED, x25519, Uniform};
 use aptos_logger::info;
+use aptos_temppath::TempPath;
 use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::{account_address::AccountAddress, network_address::NetworkAddress};
 use channel::{aptos_channel, message_queues::QueueStyle};
@@ -70,6 +74,7 @@ fn update_peer_with_address(mut peer: Peer, addr_str: &'static str) -> (Peer, Ne
 
 struct TestHarness {
     trusted_peers: Arc<RwLock<PeerSet>>,
+    peer_reputations: Arc<PeerReputations>,
     mock_time: MockTimeService,
     connection_reqs_rx: aptos_channel::Receiver<PeerId, ConnectionRequest>,
     connection_notifs_tx: conn_notifs_channel::Sender,
@@ -85,6 +90,10 @@ impl TestHarness {
         let (connection_notifs_tx, connection_notifs_rx) = conn_notifs_channel::new();
         let (conn_mgr_reqs_tx, conn_mgr_reqs_rx) = channel::new_test(0);
         let trusted_peers = Arc::new(RwLock::new(HashMap::new()));
+        let peer_reputations = Arc::new(PeerReputations::in_memory(
+            network_context,
+            time_service.clone(),
+        ));
 
         let conn_mgr = ConnectivityManager::new(
             network_context,
@@ -99,9 +108,11 @@ impl TestHarness {
             MAX_CONNECTION_DELAY,
             Some(MAX_TEST_CONNECTIONS),
             true, /* mutual_authentication */
+            peer_reputations.clone(),
         );
         let mock = Self {
             trusted_peers,
+            peer_reputations,
             mock_time: time_service.into_mock(),
             connection_reqs_rx,
             connection_notifs_tx,
@@ -806,3 +817,211 @@ fn basic_update_discovered_peers() {
     conn_mgr.handle_update_discovered_peers(DiscoverySource::Config, peers_empty.clone());
     assert_eq!(*trusted_peers.read(), peers_empty);
 }
+
+#[test]
+fn dont_dial_banned_peers() {
+    let (other_peer_id, other_peer, _, other_addr) = test_peer(AccountAddress::ZERO);
+    let (mut mock, conn_mgr) = TestHarness::new(HashMap::new());
+
+    let test = async move {
+        // Discover the other peer through the config, so that it isn't protected
+        mock.peer_reputations.ban(other_peer_id, None).unwrap();
+        let peers = hashmap! {other_peer_id => other_peer};
+        mock.send_update_discovered_peers(DiscoverySource::Config, peers)
+            .await;
+
+        // The banned peer isn't dialed
+        mock.trigger_connectivity_check().await;
+        assert_eq!(0, mock.get_dial_queue_size().await);
+
+        // Once unbanned, the peer is dialed again
+        mock.peer_reputations.unban(other_peer_id).unwrap();
+        mock.trigger_connectivity_check().await;
+        mock.trigger_pending_dials().await;
+        mock.expect_one_dial_success(other_peer_id, other_addr)
+            .await;
+    };
+    block_on(future::join(conn_mgr.start(), test));
+}
+
+fn test_reputation_config() -> PeerReputationConfig {
+    let mut config = PeerReputationConfig::default();
+    config.enable_automatic_bans = true;
+    config.failure_threshold = 3;
+    config.failure_window_secs = 10;
+    config.initial_ban_secs = 60;
+    config.max_ban_secs = 100;
+    config.enable_persistence = false;
+    config
+}
+
+#[test]
+fn ban_misbehaving_peers_with_backoff() {
+    let time_service = TimeService::mock();
+    let mock_time = time_service.clone().into_mock();
+    let reputations = PeerReputations::new(
+        NetworkContext::mock(),
+        time_service,
+        test_reputation_config(),
+    );
+    let peer_id = PeerId::random();
+
+    // Misbehaviors outside of the failure window are forgotten
+    reputations.record_misbehavior(peer_id, Misbehavior::InvalidMessage);
+    reputations.record_misbehavior(peer_id, Misbehavior::InvalidMessage);
+    mock_time.advance_secs(11);
+    reputations.record_misbehavior(peer_id, Misbehavior::InvalidMessage);
+    assert!(!reputations.is_banned(&peer_id));
+
+    // The first ban lasts for the initial ban duration
+    reputations.record_misbehavior(peer_id, Misbehavior::RpcError);
+    reputations.record_misbehavior(peer_id, Misbehavior::RpcError);
+    assert!(reputations.is_banned(&peer_id));
+    assert_eq!(
+        reputations.banned_peers().get(&peer_id).unwrap().reason,
+        BanReason::Misbehavior(Misbehavior::RpcError)
+    );
+    mock_time.advance_secs(59);
+    assert!(reputations.is_banned(&peer_id));
+    mock_time.advance_secs(1);
+    assert!(!reputations.is_banned(&peer_id));
+
+    // The second ban is capped at the maximum ban duration
+    for _ in 0..3 {
+        reputations.record_misbehavior(peer_id, Misbehavior::HandshakeFailure);
+    }
+    mock_time.advance_secs(99);
+    assert!(reputations.is_banned(&peer_id));
+    mock_time.advance_secs(1);
+    assert!(!reputations.is_banned(&peer_id));
+    assert!(reputations.banned_peers().is_empty());
+}
+
+#[test]
+fn never_ban_validator_set_peers() {
+    let validator = PeerId::random();
+    let other_peer = PeerId::random();
+    let mut config = test_reputation_config();
+    config.banned_peers = hashset! {validator};
+    let reputations = PeerReputations::new(NetworkContext::mock(), TimeService::mock(), config);
+
+    // Bans are lifted once the peer joins the validator set
+    reputations.ban(other_peer, None).unwrap();
+    assert!(reputations.is_banned(&validator));
+    assert!(reputations.is_banned(&other_peer));
+    assert_eq!(
+        reputations.unban(validator),
+        Err(ReputationError::ConfiguredBan(validator))
+    );
+    reputations.set_protected_peers(hashset! {validator, other_peer});
+    assert!(!reputations.is_banned(&validator));
+    assert!(!reputations.is_banned(&other_peer));
+    assert!(reputations.banned_peers().is_empty());
+
+    // Validators can't be banned, manually or automatically
+    assert_eq!(
+        reputations.ban(other_peer, None),
+        Err(ReputationError::ProtectedPeer(other_peer))
+    );
+    for _ in 0..3 {
+        reputations.record_misbehavior(other_peer, Misbehavior::NoiseAuthFailure);
+    }
+    assert!(!reputations.is_banned(&other_peer));
+}
+
+#[test]
+fn persist_peer_reputations() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let mut config = test_reputation_config();
+    config.enable_persistence = true;
+    config.persistence_dir = dir.path().to_path_buf();
+    let time_service = TimeService::mock();
+    let banned_peer = PeerId::random();
+    let misbehaving_peer = PeerId::random();
+
+    let reputations =
+        PeerReputations::new(NetworkContext::mock(), time_service.clone(), config.clone());
+    reputations.ban(banned_peer, None).unwrap();
+    for _ in 0..3 {
+        reputations.record_misbehavior(misbehaving_peer, Misbehavior::InvalidMessage);
+    }
+    let banned_peers = reputations.banned_peers();
+    assert_eq!(banned_peers.len(), 2);
+    reputations.flush();
+
+    // The bans survive a restart
+    let reputations = PeerReputations::new(NetworkContext::mock(), time_service, config.clone());
+    assert_eq!(reputations.banned_peers(), banned_peers);
+
+    // And so do unbans
+    reputations.unban(banned_peer).unwrap();
+    reputations.flush();
+    let reputations = PeerReputations::new(NetworkContext::mock(), TimeService::mock(), config);
+    assert!(!reputations.is_banned(&banned_peer));
+    assert!(reputations.is_banned(&misbehaving_peer));
+}
+
+#[test]
+fn bound_tracked_peers() {
+    let time_service = TimeService::mock();
+    let mock_time = time_service.clone().into_mock();
+    let mut config = test_reputation_config();
+    config.failure_threshold = 1;
+    let reputations = PeerReputations::new(NetworkContext::mock(), time_service, config);
+    for _ in 0..MAX_TRACKED_PEERS {
+        reputations.record_misbehavior(PeerId::random(), Misbehavior::InvalidMessage);
+    }
+    assert_eq!(reputations.banned_peers().len(), MAX_TRACKED_PEERS);
+
+    // No more peers are tracked while all the reputations are relevant
+    let peer_id = PeerId::random();
+    reputations.record_misbehavior(peer_id, Misbehavior::InvalidMessage);
+    assert!(!reputations.is_banned(&peer_id));
+
+    // Eligible peers are tracked nonetheless
+    let trusted_peer = PeerId::random();
+    reputations.set_trusted_peers(hashset! {trusted_peer});
+    reputations.record_misbehavior(trusted_peer, Misbehavior::InvalidMessage);
+    assert!(reputations.is_banned(&trusted_peer));
+    assert_eq!(reputations.banned_peers().len(), MAX_TRACKED_PEERS + 1);
+
+    // Once the bans are long expired, the stale reputations make room for new ones
+    mock_time.advance_secs(160);
+    reputations.record_misbehavior(peer_id, Misbehavior::InvalidMessage);
+    assert!(reputations.is_banned(&peer_id));
+    assert_eq!(reputations.banned_peers().len(), 1);
+}
+
+#[test]
+fn wait_for_validator_set_after_restart() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let mut config = test_reputation_config();
+    config.enable_persistence = true;
+    config.persistence_dir = dir.path().to_path_buf();
+    let validator = PeerId::random();
+    let configured_validator = PeerId::random();
+    let other_peer = PeerId::random();
+    config.banned_peers = hashset! {configured_validator};
+
+    // The validator was banned before it joined the validator set
+    let reputations =
+        PeerReputations::new(NetworkContext::mock(), TimeService::mock(), config.clone());
+    reputations.ban(validator, None).unwrap();
+    reputations.ban(other_peer, None).unwrap();
+    reputations.flush();
+
+    // After a restart, no ban is enforced until the validator set is known
+    let reputations = PeerReputations::new(NetworkContext::mock(), TimeService::mock(), config);
+    reputations.wait_for_validator_set();
+    assert!(!reputations.is_banned(&validator));
+    assert!(!reputations.is_banned(&configured_validator));
+    assert!(!reputations.is_banned(&other_peer));
+
+    // Then only the validators are spared
+    reputations.set_protected_peers(hashset! {validator, configured_validator});
+    assert!(!reputations.is_banned(&validator));
+    assert!(!reputations.is_banned(&configured_validator));
+    assert!(reputations.is_banned(&other_peer));
+}
@@ -1,7 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::protocols::wire::handshake::v1::ProtocolId;
+use crate::{
+    connectivity_manager::reputation::{BanReason, Misbehavior},
+    peer::PriorityClass,
+    protocols::wire::handshake::v1::ProtocolId,
+};
 use aptos_config::network_id::NetworkContext;
 use aptos_metrics_core::{
     register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
@@ -63,6 +67,40 @@ pub fn connections_rejected(
     ])
 }
 
+pub static APTOS_NETWORK_PEER_MISBEHAVIORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_misbehaviors",
+        "Number of misbehaviors of remote peers counted against their reputation",
+        &["role_type", "network_id", "misbehavior"]
+    )
+    .unwrap()
+});
+
+pub fn peer_misbehaviors(network_context: &NetworkContext, misbehavior: Misbehavior) -> IntCounter {
+    APTOS_NETWORK_PEER_MISBEHAVIORS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        misbehavior.as_str(),
+    ])
+}
+
+pub static APTOS_NETWORK_PEER_BANS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_bans",
+        "Number of remote peers banned, by reason of the ban",
+        &["role_type", "network_id", "reason"]
+    )
+    .unwrap()
+});
+
+pub fn peer_bans(network_context: &NetworkContext, reason: BanReason) -> IntCounter {
+    APTOS_NETWORK_PEER_BANS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        reason.as_str(),
+    ])
+}
+
 pub static APTOS_NETWORK_PEER_CONNECTED: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_network_peer_connected",
@@ -394,24 +432,47 @@ pub static PENDING_WIRE_MESSAGES: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
-/// Counter of messages pending in queue to be sent out on the multiplex channel
-pub static PENDING_MULTIPLEX_MESSAGE: Lazy<IntGauge> = Lazy::new(|| {
-    register_int_gauge!(
-        "aptos_network_pending_multiplex_messages",
-        "Number of pending multiplex messages"
+/// Counter of frames pending in the outbound scheduler of the connections
+pub static PENDING_OUTBOUND_FRAMES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_network_pending_outbound_frames",
+        "Number of outbound frames pending in the scheduler by priority class",
+        &["priority_class"]
     )
     .unwrap()
 });
 
-/// Counter of stream messages pending in queue to be sent out on the multiplex channel
-pub static PENDING_MULTIPLEX_STREAM: Lazy<IntGauge> = Lazy::new(|| {
-    register_int_gauge!(
-        "aptos_network_pending_multiplex_stream",
-        "Number of pending multiplex stream messages"
+/// Counter of outbound messages dropped as the scheduler queue of their class was full
+pub static DROPPED_OUTBOUND_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_dropped_outbound_messages",
+        "Number of outbound messages dropped as their priority class queue was full",
+        &["priority_class"]
     )
     .unwrap()
 });
 
+pub static APTOS_NETWORK_OUTBOUND_QUEUEING_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_outbound_queueing_delay_seconds",
+        "Time from queueing an outbound message until its last frame is written, in seconds",
+        &["role_type", "network_id", "peer_id", "priority_class"]
+    )
+    .unwrap()
+});
+
+pub fn outbound_queueing_delay(
+    network_context: &NetworkContext,
+    priority_class: PriorityClass,
+) -> Histogram {
+    APTOS_NETWORK_OUTBOUND_QUEUEING_DELAY.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        priority_class.as_str(),
+    ])
+}
+
 /// Counter of pending requests in Direct Send
 pub static PENDING_DIRECT_SEND_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
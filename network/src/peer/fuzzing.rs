@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    connectivity_manager::reputation::PeerReputations,
     constants,
     peer::Peer,
     protocols::wire::{
@@ -20,7 +21,7 @@ use futures::{executor::block_on, future, io::AsyncReadExt, sink::SinkExt, strea
 use memsocket::MemorySocket;
 use netcore::transport::ConnectionOrigin;
 use proptest::{arbitrary::any, collection::vec};
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 /// Generate a sequence of `MultiplexMessage`, bcs serialize them, and write them
 /// out to a buffer using our length-prefixed message codec.
@@ -109,6 +110,10 @@ pub fn fuzz(data: &[u8]) {
         constants::MAX_MESSAGE_SIZE,
         None,
         None,
+        Arc::new(PeerReputations::in_memory(
+            network_context,
+            TimeService::mock(),
+        )),
     );
     executor.spawn(peer.start());
 
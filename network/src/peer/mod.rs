@@ -15,6 +15,7 @@
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
+    connectivity_manager::reputation::{Misbehavior, PeerReputations},
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
         RECEIVED_LABEL, SENT_LABEL,
@@ -23,8 +24,8 @@ use crate::{
     peer_manager::{PeerManagerError, TransportNotification},
     protocols::{
         direct_send::Message,
-        rpc::{InboundRpcRequest, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
-        stream::{InboundStreamBuffer, OutboundStream, StreamMessage},
+        rpc::{error::RpcError, InboundRpcRequest, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
+        stream::{InboundStreamBuffer, StreamMessage},
         wire::messaging::v1::{
             DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
             MultiplexMessageStream, NetworkMessage, Priority, ReadError, WriteError,
@@ -45,20 +46,23 @@ use futures::{
     channel::oneshot,
     io::{AsyncRead, AsyncWrite},
     stream::StreamExt,
-    SinkExt,
+    FutureExt, SinkExt,
 };
-use futures_util::stream::select;
+use scheduler::OutboundScheduler;
 use serde::Serialize;
 use short_hex_str::AsShortHexStr;
-use std::{fmt, panic, time::Duration};
+use std::{fmt, panic, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
 
+mod scheduler;
 #[cfg(test)]
 mod test;
 
+pub use scheduler::PriorityClass;
+
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing;
 
@@ -142,6 +146,8 @@ pub struct Peer<TSocket> {
     outbound_rate_limiter: Option<SharedBucket>,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// Reputations of the peers, misbehaviors of the remote peer count against it
+    peer_reputations: Arc<PeerReputations>,
 }
 
 impl<TSocket> Peer<TSocket>
@@ -164,6 +170,7 @@ where
         max_message_size: usize,
         inbound_rate_limiter: Option<SharedBucket>,
         outbound_rate_limiter: Option<SharedBucket>,
+        peer_reputations: Arc<PeerReputations>,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -199,6 +206,7 @@ where
             inbound_rate_limiter,
             outbound_rate_limiter,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
+            peer_reputations,
         }
     }
 
@@ -206,6 +214,11 @@ where
         self.connection_metadata.remote_peer_id
     }
 
+    fn record_misbehavior(&self, misbehavior: Misbehavior) {
+        self.peer_reputations
+            .record_misbehavior(self.remote_peer_id(), misbehavior);
+    }
+
     pub async fn start(mut self) {
         let remote_peer_id = self.remote_peer_id();
         trace!(
@@ -310,10 +323,13 @@ where
     // Start a new task on the given executor which is responsible for writing outbound messages on
     // the wire. The function returns two channels which can be used to send instructions to the
     // task:
-    // 1. The first channel is used to send outbound NetworkMessages to the task
+    // 1. The first channel is used to send outbound NetworkMessages, tagged with their priority
+    //    class, to the task. The task splits them into frames and picks the frame to write next
+    //    using the `OutboundScheduler`, so that small high priority messages aren't delayed by
+    //    large low priority ones. Messages of a class whose queue is full are dropped.
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
-    // them and immediately closes the connection.
+    // them, finishes writing the frames it already scheduled and closes the connection.
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
@@ -322,23 +338,105 @@ where
         mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
         max_frame_size: usize,
         max_message_size: usize,
-    ) -> (channel::Sender<NetworkMessage>, oneshot::Sender<()>) {
+    ) -> (
+        channel::Sender<(PriorityClass, NetworkMessage)>,
+        oneshot::Sender<()>,
+    ) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (channel::Sender<NetworkMessage>, _) =
-            channel::new(1024, &counters::PENDING_WIRE_MESSAGES);
+        let (write_reqs_tx, mut write_reqs_rx): (
+            channel::Sender<(PriorityClass, NetworkMessage)>,
+            _,
+        ) = channel::new(1024, &counters::PENDING_WIRE_MESSAGES);
         let (close_tx, mut close_rx) = oneshot::channel();
 
-        let (mut msg_tx, msg_rx) = channel::new(1024, &counters::PENDING_MULTIPLEX_MESSAGE);
-        let (stream_msg_tx, stream_msg_rx) =
-            channel::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
-
-        // this task ends when the multiplex task ends (by dropping the senders)
         let writer_task = async move {
-            let mut stream = select(msg_rx, stream_msg_rx);
+            let mut scheduler = OutboundScheduler::new(
+                network_context,
+                time_service.clone(),
+                max_frame_size,
+                max_message_size,
+            );
             let log_context =
                 NetworkSchema::new(&network_context).connection_metadata(&connection_metadata);
-            while let Some(message) = stream.next().await {
-                if let Err(err) = writer.send(&message).await {
+            let schedule = |scheduler: &mut OutboundScheduler,
+                            priority_class: PriorityClass,
+                            message: NetworkMessage| {
+                if let Err(err) = scheduler.push(priority_class, message) {
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(1)),
+                        warn!(
+                            log_context,
+                            error = %err,
+                            "{} Error in scheduling {} priority message to peer: {}",
+                            network_context,
+                            priority_class,
+                            remote_peer_id.short_str(),
+                        )
+                    );
+                }
+            };
+            loop {
+                // Hand the messages waiting to be written to the scheduler, so that they
+                // compete for the next frame according to their priority class. The queue is
+                // always drained, so that a full class never holds up the messages of the
+                // other classes behind its own.
+                while let Some(Some((priority_class, message))) =
+                    write_reqs_rx.next().now_or_never()
+                {
+                    schedule(&mut scheduler, priority_class, message)
+                }
+                if !matches!(close_rx.try_recv(), Ok(None)) {
+                    break;
+                }
+
+                match scheduler.pop() {
+                    Some(message) => {
+                        if let Err(err) = writer.send(&message).await {
+                            warn!(
+                                log_context,
+                                error = %err,
+                                "{} Error in sending message to peer: {}",
+                                network_context,
+                                remote_peer_id.short_str(),
+                            );
+                        }
+                    }
+                    None => {
+                        futures::select! {
+                            maybe_message = write_reqs_rx.next() => {
+                                match maybe_message {
+                                    Some((priority_class, message)) => schedule(&mut scheduler, priority_class, message),
+                                    None => break,
+                                }
+                            },
+                            _ = close_rx => {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            // Finish writing the stream in progress, if any, so that it isn't left incomplete.
+            // The other scheduled frames are dropped.
+            let finish_stream = async {
+                while let Some(message) = scheduler.pop_in_progress_stream() {
+                    writer.send(&message).await?;
+                }
+                Ok(()) as Result<(), WriteError>
+            };
+            match time_service
+                .timeout(transport::TRANSPORT_TIMEOUT, finish_stream)
+                .await
+            {
+                Err(_) => {
+                    info!(
+                        log_context,
+                        "{} Timeout in finishing the stream to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str()
+                    );
+                }
+                Ok(Err(err)) => {
                     warn!(
                         log_context,
                         error = %err,
@@ -347,7 +445,9 @@ where
                         remote_peer_id.short_str(),
                     );
                 }
+                Ok(Ok(())) => (),
             }
+            drop(scheduler);
             info!(
                 log_context,
                 "{} Closing connection to peer: {}",
@@ -391,35 +491,7 @@ where
                 }
             }
         };
-        let multiplex_task = async move {
-            let mut outbound_stream =
-                OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
-            loop {
-                futures::select! {
-                    message = write_reqs_rx.select_next_some() => {
-                        // either channel full would block the other one
-                        let result = if outbound_stream.should_stream(&message) {
-                            outbound_stream.stream_message(message).await
-                        } else {
-                            msg_tx.send(MultiplexMessage::Message(message)).await.map_err(|_| anyhow::anyhow!("Writer task ended"))
-                        };
-                        if let Err(err) = result {
-                            warn!(
-                                error = %err,
-                                "{} Error in sending message to peer: {}",
-                                network_context,
-                                remote_peer_id.short_str(),
-                            );
-                        }
-                    },
-                    _ = close_rx => {
-                        break;
-                    }
-                }
-            }
-        };
         executor.spawn(writer_task);
-        executor.spawn(multiplex_task);
         (write_reqs_tx, close_tx)
     }
 
@@ -445,6 +517,9 @@ where
                     .inbound_rpcs
                     .handle_inbound_request(&mut self.peer_notifs_tx, request)
                 {
+                    if let RpcError::TooManyPending(_) = err {
+                        self.record_misbehavior(Misbehavior::RpcError);
+                    }
                     warn!(
                         NetworkSchema::new(&self.network_context)
                             .connection_metadata(&self.connection_metadata),
@@ -466,23 +541,24 @@ where
         &mut self,
         message: StreamMessage,
     ) -> Result<(), PeerManagerError> {
-        match message {
-            StreamMessage::Header(header) => {
-                self.inbound_stream.new_stream(header)?;
-            }
-            StreamMessage::Fragment(fragment) => {
-                if let Some(message) = self.inbound_stream.append_fragment(fragment)? {
-                    self.handle_inbound_network_message(message).await?;
-                }
+        let maybe_message = match message {
+            StreamMessage::Header(header) => self.inbound_stream.new_stream(header).map(|_| None),
+            StreamMessage::Fragment(fragment) => self.inbound_stream.append_fragment(fragment),
+        };
+        match maybe_message {
+            Ok(Some(message)) => self.handle_inbound_network_message(message).await,
+            Ok(None) => Ok(()),
+            Err(err) => {
+                self.record_misbehavior(Misbehavior::InvalidMessage);
+                Err(err.into())
             }
         }
-        Ok(())
     }
 
     async fn handle_inbound_message(
         &mut self,
         message: Result<MultiplexMessage, ReadError>,
-        write_reqs_tx: &mut channel::Sender<NetworkMessage>,
+        write_reqs_tx: &mut channel::Sender<(PriorityClass, NetworkMessage)>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -503,8 +579,9 @@ where
                     let protocol_id = frame_prefix.as_ref().get(1).unwrap_or(&0);
                     let error_code = ErrorCode::parsing_error(*message_type, *protocol_id);
                     let message = NetworkMessage::Error(error_code);
+                    self.record_misbehavior(Misbehavior::InvalidMessage);
 
-                    write_reqs_tx.send(message).await?;
+                    write_reqs_tx.send((PriorityClass::High, message)).await?;
                     return Err(err.into());
                 }
                 ReadError::IoError(_) => {
@@ -563,7 +640,7 @@ where
     async fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut channel::Sender<NetworkMessage>,
+        write_reqs_tx: &mut channel::Sender<(PriorityClass, NetworkMessage)>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -587,7 +664,10 @@ where
                     raw_msg: Vec::from(message.mdata.as_ref()),
                 });
 
-                match write_reqs_tx.send(message).await {
+                match write_reqs_tx
+                    .send((PriorityClass::from(protocol_id), message))
+                    .await
+                {
                     Ok(_) => {
                         counters::direct_send_messages(&self.network_context, SENT_LABEL).inc();
                         counters::direct_send_bytes(&self.network_context, SENT_LABEL)
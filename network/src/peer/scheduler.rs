@@ -0,0 +1,331 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Weighted fair queueing of the outbound frames of a single connection.
+//!
+//! Every outbound message is assigned a [`PriorityClass`] by its [`ProtocolId`] and queued as
+//! one or more frames. Messages of the [`PriorityClass::Normal`] and [`PriorityClass::Low`]
+//! classes are fragmented with [`OutboundStream`] well below the maximum frame size. The
+//! writer picks the next frame to write using deficit round robin over the class queues, so a
+//! large storage service response is interleaved with, rather than written ahead of, small
+//! consensus messages.
+//!
+//! The remote peer reassembles a single stream at a time, so the frames of two streams are
+//! never interleaved: a class whose next frame starts a new stream waits until the stream in
+//! progress has been written.
+//!
+//! The queue of each class is bounded by [`MAX_QUEUED_BYTES_PER_CLASS`]. While a class is full
+//! its new messages are dropped, so that a peer that doesn't read from the connection can't
+//! grow the queues. The other classes keep taking in messages, so a backlog of state sync
+//! data never holds up consensus messages.
+
+use crate::{
+    counters,
+    protocols::{
+        stream::{OutboundStream, StreamMessage},
+        wire::messaging::v1::{MultiplexMessage, NetworkMessage},
+    },
+    ProtocolId,
+};
+use anyhow::bail;
+use aptos_config::network_id::NetworkContext;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{collections::VecDeque, fmt, time::Instant};
+
+/// The preferred size of the fragments of normal and low priority messages
+pub const PREFERRED_FRAGMENT_SIZE: usize = 64 * 1024; /* 64 KiB */
+
+/// The number of bytes queued in a class above which its new messages are dropped. A single
+/// message is always accepted, so a class may hold up to one maximum size message more.
+pub const MAX_QUEUED_BYTES_PER_CLASS: usize = 16 * 1024 * 1024; /* 16 MiB */
+
+/// The number of bytes a class of weight 1 may write per scheduling round
+const QUANTUM: usize = 16 * 1024; /* 16 KiB */
+
+/// The scheduling class of an outbound message.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PriorityClass {
+    /// Small, latency critical messages, e.g., consensus votes, proposals and health checks.
+    High,
+    /// Mempool, discovery and other service messages.
+    Normal,
+    /// Bulk state sync data, which can be as large as the maximum message size.
+    Low,
+}
+
+impl PriorityClass {
+    pub fn all() -> &'static [PriorityClass] {
+        &[
+            PriorityClass::High,
+            PriorityClass::Normal,
+            PriorityClass::Low,
+        ]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PriorityClass::High => "high",
+            PriorityClass::Normal => "normal",
+            PriorityClass::Low => "low",
+        }
+    }
+
+    /// The share of the connection bandwidth the class gets relative to the other classes
+    fn weight(self) -> usize {
+        match self {
+            PriorityClass::High => 16,
+            PriorityClass::Normal => 4,
+            PriorityClass::Low => 1,
+        }
+    }
+
+    /// The preferred fragment size of the messages in the class. Normal and low priority
+    /// messages are split well below the frame size, so that a high priority message never
+    /// waits for a large frame to be written. High priority messages are only split at the
+    /// frame size, as they must not wait for another stream to complete before starting their
+    /// own.
+    fn preferred_fragment_size(self) -> usize {
+        match self {
+            PriorityClass::Normal | PriorityClass::Low => PREFERRED_FRAGMENT_SIZE,
+            PriorityClass::High => usize::MAX,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl From<ProtocolId> for PriorityClass {
+    fn from(protocol_id: ProtocolId) -> Self {
+        use ProtocolId::*;
+        match protocol_id {
+            ConsensusRpcBcs
+            | ConsensusDirectSendBcs
+            | ConsensusDirectSendJson
+            | ConsensusRpcJson
+            | ConsensusRpcCompressed
+            | ConsensusDirectSendCompressed
+            | HealthCheckerRpc => PriorityClass::High,
+            MempoolDirectSend
+            | MempoolRpc
+            | DiscoveryDirectSend
+            | PeerMonitoringServiceRpc
+            | ConsensusObserver => PriorityClass::Normal,
+            StateSyncDirectSend | StorageServiceRpc => PriorityClass::Low,
+        }
+    }
+}
+
+impl fmt::Display for PriorityClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+struct Frame {
+    message: MultiplexMessage,
+    len: usize,
+    starts_stream: bool,
+    ends_stream: bool,
+    /// Set on the last frame of a message, as writing it completes the message
+    enqueued_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct ClassQueue {
+    frames: VecDeque<Frame>,
+    deficit: usize,
+    /// The total length of the queued frames
+    queued_bytes: usize,
+}
+
+/// Deficit round robin scheduler of the outbound frames of a connection.
+pub struct OutboundScheduler {
+    network_context: NetworkContext,
+    time_service: TimeService,
+    outbound_stream: OutboundStream,
+    queues: [ClassQueue; 3],
+    /// The class of the stream being written, if any
+    active_stream: Option<PriorityClass>,
+    /// The class the round robin currently visits
+    current: usize,
+    /// Whether the current class got its quantum during this visit
+    quantum_granted: bool,
+}
+
+impl OutboundScheduler {
+    pub fn new(
+        network_context: NetworkContext,
+        time_service: TimeService,
+        max_frame_size: usize,
+        max_message_size: usize,
+    ) -> Self {
+        Self {
+            network_context,
+            time_service,
+            outbound_stream: OutboundStream::new(max_frame_size, max_message_size),
+            queues: Default::default(),
+            active_stream: None,
+            current: 0,
+            quantum_granted: false,
+        }
+    }
+
+    /// Splits the message into frames and queues them in the given class. Fails if the class
+    /// is full, dropping the message.
+    pub fn push(
+        &mut self,
+        priority_class: PriorityClass,
+        message: NetworkMessage,
+    ) -> anyhow::Result<()> {
+        if self.is_full(priority_class) {
+            counters::DROPPED_OUTBOUND_MESSAGES
+                .with_label_values(&[priority_class.as_str()])
+                .inc();
+            bail!(
+                "The {} priority queue is full, dropping the message",
+                priority_class
+            );
+        }
+
+        let preferred_fragment_size = priority_class.preferred_fragment_size();
+        let mut frames: Vec<Frame> = if self
+            .outbound_stream
+            .should_stream(&message, preferred_fragment_size)
+        {
+            let stream = self
+                .outbound_stream
+                .stream_message(message, preferred_fragment_size)?;
+            let num_frames = stream.len();
+            stream
+                .into_iter()
+                .enumerate()
+                .map(|(index, message)| Frame {
+                    len: message.data_len(),
+                    message: MultiplexMessage::Stream(message),
+                    starts_stream: index == 0,
+                    ends_stream: index + 1 == num_frames,
+                    enqueued_at: None,
+                })
+                .collect()
+        } else {
+            vec![Frame {
+                len: message.data_len(),
+                message: MultiplexMessage::Message(message),
+                starts_stream: false,
+                ends_stream: false,
+                enqueued_at: None,
+            }]
+        };
+        if let Some(frame) = frames.last_mut() {
+            frame.enqueued_at = Some(self.time_service.now());
+        }
+
+        counters::PENDING_OUTBOUND_FRAMES
+            .with_label_values(&[priority_class.as_str()])
+            .add(frames.len() as i64);
+        let queue = &mut self.queues[priority_class.index()];
+        queue.queued_bytes += frames.iter().map(|frame| frame.len).sum::<usize>();
+        queue.frames.extend(frames);
+        Ok(())
+    }
+
+    /// Whether the class holds too many bytes to take in more messages.
+    pub fn is_full(&self, priority_class: PriorityClass) -> bool {
+        self.queues[priority_class.index()].queued_bytes >= MAX_QUEUED_BYTES_PER_CLASS
+    }
+
+    /// Returns the next frame to write, or `None` if there are no frames queued.
+    pub fn pop(&mut self) -> Option<MultiplexMessage> {
+        if !PriorityClass::all()
+            .iter()
+            .any(|priority_class| self.is_eligible(*priority_class))
+        {
+            return None;
+        }
+
+        loop {
+            let priority_class = PriorityClass::all()[self.current];
+            if !self.is_eligible(priority_class) {
+                self.next_class();
+                continue;
+            }
+
+            let queue = &mut self.queues[priority_class.index()];
+            if !self.quantum_granted {
+                queue.deficit += priority_class.weight() * QUANTUM;
+                self.quantum_granted = true;
+            }
+            let frame_len = queue.frames.front().map_or(0, |frame| frame.len);
+            if frame_len > queue.deficit {
+                self.next_class();
+                continue;
+            }
+
+            queue.deficit -= frame_len;
+            let message = self.take_frame(priority_class);
+            if self.queues[priority_class.index()].frames.is_empty() {
+                self.queues[priority_class.index()].deficit = 0;
+                self.next_class();
+            }
+            return message;
+        }
+    }
+
+    /// Returns the next frame of the stream being written, if any. Used when closing the
+    /// connection, to complete the stream in progress without writing anything else.
+    pub fn pop_in_progress_stream(&mut self) -> Option<MultiplexMessage> {
+        let priority_class = self.active_stream?;
+        self.take_frame(priority_class)
+    }
+
+    /// Removes the next frame of the class, keeping track of the stream in progress.
+    fn take_frame(&mut self, priority_class: PriorityClass) -> Option<MultiplexMessage> {
+        let queue = &mut self.queues[priority_class.index()];
+        let frame = queue.frames.pop_front()?;
+        queue.queued_bytes -= frame.len;
+        if frame.starts_stream {
+            self.active_stream = Some(priority_class);
+        }
+        if frame.ends_stream {
+            self.active_stream = None;
+        }
+
+        counters::PENDING_OUTBOUND_FRAMES
+            .with_label_values(&[priority_class.as_str()])
+            .dec();
+        if let Some(enqueued_at) = frame.enqueued_at {
+            counters::outbound_queueing_delay(&self.network_context, priority_class).observe(
+                self.time_service
+                    .now()
+                    .saturating_duration_since(enqueued_at)
+                    .as_secs_f64(),
+            );
+        }
+        Some(frame.message)
+    }
+
+    /// A class can send its next frame unless that frame would start a second stream.
+    fn is_eligible(&self, priority_class: PriorityClass) -> bool {
+        match self.queues[priority_class.index()].frames.front() {
+            Some(frame) => !frame.starts_stream || self.active_stream.is_none(),
+            None => false,
+        }
+    }
+
+    fn next_class(&mut self) {
+        self.current = (self.current + 1) % self.queues.len();
+        self.quantum_granted = false;
+    }
+}
+
+impl Drop for OutboundScheduler {
+    fn drop(&mut self) {
+        for priority_class in PriorityClass::all() {
+            counters::PENDING_OUTBOUND_FRAMES
+                .with_label_values(&[priority_class.as_str()])
+                .sub(self.queues[priority_class.index()].frames.len() as i64);
+        }
+    }
+}
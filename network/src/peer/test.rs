@@ -2,15 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    connectivity_manager::reputation::PeerReputations,
     constants::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
     },
-    peer::{DisconnectReason, Peer, PeerNotification, PeerRequest},
+    peer::{
+        scheduler::{OutboundScheduler, MAX_QUEUED_BYTES_PER_CLASS, PREFERRED_FRAGMENT_SIZE},
+        DisconnectReason, Peer, PeerNotification, PeerRequest, PriorityClass,
+    },
     peer_manager::TransportNotification,
     protocols::{
         direct_send::Message,
         rpc::{error::RpcError, InboundRpcRequest, OutboundRpcRequest},
+        stream::{InboundStreamBuffer, StreamMessage},
         wire::{
             handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
@@ -36,7 +41,7 @@ use futures::{
 };
 use memsocket::MemorySocket;
 use netcore::transport::ConnectionOrigin;
-use std::{collections::HashSet, str::FromStr, time::Duration};
+use std::{collections::HashSet, str::FromStr, sync::Arc, time::Duration};
 use tokio::runtime::{Handle, Runtime};
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
@@ -79,7 +84,7 @@ fn build_test_peer(
     let peer = Peer::new(
         NetworkContext::mock(),
         executor,
-        time_service,
+        time_service.clone(),
         connection,
         connection_notifs_tx,
         peer_reqs_rx,
@@ -91,6 +96,10 @@ fn build_test_peer(
         MAX_MESSAGE_SIZE,
         None,
         None,
+        Arc::new(PeerReputations::in_memory(
+            NetworkContext::mock(),
+            time_service,
+        )),
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
@@ -948,3 +957,217 @@ fn peers_send_multiplex() {
 
     rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
 }
+
+fn direct_send(protocol_id: ProtocolId, len: usize) -> NetworkMessage {
+    NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id,
+        priority: 0,
+        raw_msg: vec![0; len],
+    })
+}
+
+// A small high priority message queued behind a large low priority message should be
+// written after at most one fragment of the large message.
+#[test]
+fn scheduler_interleaves_high_priority_messages() {
+    let mut scheduler = OutboundScheduler::new(
+        NetworkContext::mock(),
+        TimeService::mock(),
+        MAX_FRAME_SIZE,
+        MAX_MESSAGE_SIZE,
+    );
+    let storage_response = direct_send(ProtocolId::StateSyncDirectSend, 4 * 1024 * 1024);
+    let vote = direct_send(ProtocolId::ConsensusDirectSendBcs, 200);
+
+    scheduler
+        .push(
+            PriorityClass::from(ProtocolId::StateSyncDirectSend),
+            storage_response,
+        )
+        .unwrap();
+    assert!(matches!(
+        scheduler.pop(),
+        Some(MultiplexMessage::Stream(StreamMessage::Header(_)))
+    ));
+
+    scheduler
+        .push(
+            PriorityClass::from(ProtocolId::ConsensusDirectSendBcs),
+            vote.clone(),
+        )
+        .unwrap();
+    assert_eq!(scheduler.pop(), Some(MultiplexMessage::Message(vote)));
+
+    // The rest of the storage response follows
+    let mut num_fragments = 0;
+    while let Some(message) = scheduler.pop() {
+        assert!(matches!(
+            message,
+            MultiplexMessage::Stream(StreamMessage::Fragment(_))
+        ));
+        num_fragments += 1;
+    }
+    assert!(num_fragments > 1);
+}
+
+// The frames of different streams are never interleaved, so the remote peer can reassemble
+// every message.
+#[test]
+fn scheduler_does_not_interleave_streams() {
+    let mut scheduler =
+        OutboundScheduler::new(NetworkContext::mock(), TimeService::mock(), 1024, 16 * 1024);
+    let messages = vec![
+        direct_send(ProtocolId::StorageServiceRpc, 8000),
+        direct_send(ProtocolId::MempoolDirectSend, 3000),
+        direct_send(ProtocolId::ConsensusDirectSendBcs, 5000),
+        direct_send(ProtocolId::ConsensusDirectSendBcs, 100),
+        direct_send(ProtocolId::MempoolDirectSend, 500),
+    ];
+    for message in &messages {
+        let protocol_id = match message {
+            NetworkMessage::DirectSendMsg(message) => message.protocol_id,
+            _ => unreachable!(),
+        };
+        scheduler
+            .push(PriorityClass::from(protocol_id), message.clone())
+            .unwrap();
+    }
+
+    let mut inbound_stream = InboundStreamBuffer::new(16);
+    let mut received = vec![];
+    while let Some(message) = scheduler.pop() {
+        match message {
+            MultiplexMessage::Message(message) => received.push(message),
+            MultiplexMessage::Stream(StreamMessage::Header(header)) => {
+                inbound_stream.new_stream(header).unwrap()
+            }
+            MultiplexMessage::Stream(StreamMessage::Fragment(fragment)) => {
+                if let Some(message) = inbound_stream.append_fragment(fragment).unwrap() {
+                    received.push(message);
+                }
+            }
+        }
+    }
+
+    assert_eq!(received.len(), messages.len());
+    for message in messages {
+        assert!(received.contains(&message));
+    }
+}
+
+// A class drops new messages once it holds too many bytes, and takes them in again as its
+// frames are written. The other classes keep taking in messages meanwhile.
+#[test]
+fn scheduler_bounds_queued_bytes() {
+    let mut scheduler = OutboundScheduler::new(
+        NetworkContext::mock(),
+        TimeService::mock(),
+        MAX_FRAME_SIZE,
+        MAX_MESSAGE_SIZE,
+    );
+    let num_messages = MAX_QUEUED_BYTES_PER_CLASS / (1024 * 1024);
+    for _ in 0..num_messages {
+        assert!(!scheduler.is_full(PriorityClass::Low));
+        scheduler
+            .push(
+                PriorityClass::Low,
+                direct_send(ProtocolId::StorageServiceRpc, 1024 * 1024),
+            )
+            .unwrap();
+    }
+    assert!(scheduler.is_full(PriorityClass::Low));
+    scheduler
+        .push(
+            PriorityClass::Low,
+            direct_send(ProtocolId::StorageServiceRpc, 1024 * 1024),
+        )
+        .unwrap_err();
+
+    let vote = direct_send(ProtocolId::ConsensusDirectSendBcs, 200);
+    assert!(!scheduler.is_full(PriorityClass::High));
+    scheduler.push(PriorityClass::High, vote.clone()).unwrap();
+
+    // The vote goes out ahead of the low priority backlog
+    assert_eq!(scheduler.pop(), Some(MultiplexMessage::Message(vote)));
+
+    scheduler.pop().unwrap();
+    assert!(!scheduler.is_full(PriorityClass::Low));
+}
+
+// Large normal priority messages are fragmented too, so they don't hold up high priority
+// messages for a whole frame.
+#[test]
+fn scheduler_fragments_normal_priority_messages() {
+    let mut scheduler = OutboundScheduler::new(
+        NetworkContext::mock(),
+        TimeService::mock(),
+        MAX_FRAME_SIZE,
+        MAX_MESSAGE_SIZE,
+    );
+    let message_len = 4 * 1024 * 1024;
+    scheduler
+        .push(
+            PriorityClass::Normal,
+            direct_send(ProtocolId::MempoolDirectSend, message_len),
+        )
+        .unwrap();
+
+    // The receiver takes at most MAX_MESSAGE_SIZE / MAX_FRAME_SIZE fragments per stream,
+    // which bounds how small the fragments get
+    let max_fragments = MAX_MESSAGE_SIZE / MAX_FRAME_SIZE;
+    let max_fragment_len = PREFERRED_FRAGMENT_SIZE.max(message_len / max_fragments);
+    let mut num_frames = 0;
+    while let Some(message) = scheduler.pop() {
+        match message {
+            MultiplexMessage::Stream(StreamMessage::Header(header)) => {
+                assert!(header.message.data_len() <= max_fragment_len)
+            }
+            MultiplexMessage::Stream(StreamMessage::Fragment(fragment)) => {
+                assert!(fragment.raw_data.len() <= max_fragment_len)
+            }
+            MultiplexMessage::Message(_) => panic!("Expected the message to be streamed"),
+        }
+        num_frames += 1;
+    }
+    assert!(num_frames > 1);
+}
+
+// On close, only the frames of the stream in progress are written.
+#[test]
+fn scheduler_finishes_stream_in_progress() {
+    let mut scheduler =
+        OutboundScheduler::new(NetworkContext::mock(), TimeService::mock(), 1024, 16 * 1024);
+    scheduler
+        .push(
+            PriorityClass::Low,
+            direct_send(ProtocolId::StorageServiceRpc, 8000),
+        )
+        .unwrap();
+    scheduler
+        .push(
+            PriorityClass::High,
+            direct_send(ProtocolId::ConsensusDirectSendBcs, 100),
+        )
+        .unwrap();
+
+    // Nothing is in progress until the stream starts
+    assert_eq!(scheduler.pop_in_progress_stream(), None);
+    let mut header = None;
+    while let Some(message) = scheduler.pop() {
+        if let MultiplexMessage::Stream(StreamMessage::Header(stream_header)) = message {
+            header = Some(stream_header);
+            break;
+        }
+    }
+    let header = header.unwrap();
+
+    let mut num_fragments = 0;
+    while let Some(message) = scheduler.pop_in_progress_stream() {
+        assert!(matches!(
+            message,
+            MultiplexMessage::Stream(StreamMessage::Fragment(_))
+        ));
+        num_fragments += 1;
+    }
+    assert_eq!(num_fragments, header.num_fragments as usize);
+}
@@ -3,6 +3,7 @@
 
 use crate::{
     application::storage::PeerMetadataStorage,
+    connectivity_manager::reputation::PeerReputations,
     counters,
     counters::NETWORK_RATE_LIMIT_METRICS,
     noise::{stream::NoiseStream, HandshakeAuthMode},
@@ -71,6 +72,7 @@ struct PeerManagerContext {
 
     peer_metadata_storage: Arc<PeerMetadataStorage>,
     trusted_peers: Arc<RwLock<PeerSet>>,
+    peer_reputations: Arc<PeerReputations>,
     upstream_handlers:
         HashMap<ProtocolId, aptos_channel::Sender<(PeerId, ProtocolId), PeerManagerNotification>>,
     connection_event_handlers: Vec<conn_notifs_channel::Sender>,
@@ -95,6 +97,7 @@ impl PeerManagerContext {
 
         peer_metadata_storage: Arc<PeerMetadataStorage>,
         trusted_peers: Arc<RwLock<PeerSet>>,
+        peer_reputations: Arc<PeerReputations>,
         upstream_handlers: HashMap<
             ProtocolId,
             aptos_channel::Sender<(PeerId, ProtocolId), PeerManagerNotification>,
@@ -118,6 +121,7 @@ impl PeerManagerContext {
 
             peer_metadata_storage,
             trusted_peers,
+            peer_reputations,
             upstream_handlers,
             connection_event_handlers,
 
@@ -180,6 +184,7 @@ impl PeerManagerBuilder {
         listen_address: NetworkAddress,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
         trusted_peers: Arc<RwLock<PeerSet>>,
+        peer_reputations: Arc<PeerReputations>,
         authentication_mode: AuthenticationMode,
         channel_size: usize,
         max_concurrent_network_reqs: usize,
@@ -218,6 +223,7 @@ impl PeerManagerBuilder {
                 connection_reqs_rx,
                 peer_metadata_storage,
                 trusted_peers,
+                peer_reputations,
                 HashMap::new(),
                 Vec::new(),
                 max_concurrent_network_reqs,
@@ -364,6 +370,7 @@ impl PeerManagerBuilder {
             self.listen_address.clone(),
             pm_context.peer_metadata_storage,
             pm_context.trusted_peers,
+            pm_context.peer_reputations,
             pm_context.pm_reqs_rx,
             pm_context.connection_reqs_rx,
             pm_context.upstream_handlers,
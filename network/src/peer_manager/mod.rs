@@ -56,6 +56,7 @@ mod types;
 pub use self::error::PeerManagerError;
 use crate::{
     application::storage::PeerMetadataStorage,
+    connectivity_manager::reputation::PeerReputations,
     peer_manager::transport::{TransportHandler, TransportRequest},
     protocols::network::SerializedRequest,
 };
@@ -93,6 +94,8 @@ where
     peer_metadata_storage: Arc<PeerMetadataStorage>,
     /// Known trusted peers from discovery
     trusted_peers: Arc<RwLock<PeerSet>>,
+    /// Reputations of the peers, connections of banned peers are rejected
+    peer_reputations: Arc<PeerReputations>,
     /// Channel to receive requests from other actors.
     requests_rx: aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
     /// Upstream handlers for RPC and DirectSend protocols. The handlers are promised fair delivery
@@ -145,6 +148,7 @@ where
         listen_addr: NetworkAddress,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
         trusted_peers: Arc<RwLock<PeerSet>>,
+        peer_reputations: Arc<PeerReputations>,
         requests_rx: aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
         connection_reqs_rx: aptos_channel::Receiver<PeerId, ConnectionRequest>,
         upstream_handlers: HashMap<
@@ -177,6 +181,7 @@ where
             listen_addr,
             transport_reqs_rx,
             transport_notifs_tx_clone,
+            peer_reputations.clone(),
         );
 
         Self {
@@ -188,6 +193,7 @@ where
             active_peers: HashMap::new(),
             peer_metadata_storage,
             trusted_peers,
+            peer_reputations,
             requests_rx,
             connection_reqs_rx,
             transport_reqs_tx,
@@ -290,6 +296,24 @@ where
         self.sample_connected_peers();
         match event {
             TransportNotification::NewConnection(mut conn) => {
+                // Reject connections of banned peers, whichever side initiated them
+                if self
+                    .peer_reputations
+                    .is_banned(&conn.metadata.remote_peer_id)
+                {
+                    info!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata_with_address(&conn.metadata),
+                        "{} Connection rejected as the peer is banned: {}",
+                        self.network_context,
+                        conn.metadata
+                    );
+                    counters::connections_rejected(&self.network_context, conn.metadata.origin)
+                        .inc();
+                    self.disconnect(conn);
+                    return;
+                }
+
                 match conn.metadata.origin {
                     ConnectionOrigin::Outbound => {
                         // TODO: This is right now a hack around having to feed trusted peers deeper in the outbound path.  Inbound ones are assigned at Noise handshake time.
@@ -691,6 +715,7 @@ where
             self.max_message_size,
             Some(inbound_rate_limiter),
             Some(outbound_rate_limiter),
+            self.peer_reputations.clone(),
         );
         self.executor.spawn(peer.start());
 
@@ -3,6 +3,7 @@
 
 use crate::{
     application::storage::PeerMetadataStorage,
+    connectivity_manager::reputation::PeerReputations,
     constants,
     peer::DisconnectReason,
     peer_manager::{
@@ -107,6 +108,10 @@ fn build_test_peer_manager(
         "/memory/0".parse().unwrap(),
         PeerMetadataStorage::test(),
         Arc::new(RwLock::new(HashMap::new())),
+        Arc::new(PeerReputations::in_memory(
+            NetworkContext::mock_with_peer_id(peer_id),
+            TimeService::mock(),
+        )),
         peer_manager_request_rx,
         connection_reqs_rx,
         [(ProtocolId::mock(), hello_tx)].iter().cloned().collect(),
@@ -1,10 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    connectivity_manager::reputation::{Misbehavior, PeerReputations},
     counters::{self, FAILED_LABEL, SUCCEEDED_LABEL},
     logging::*,
+    noise::error::NoiseHandshakeError,
     peer_manager::{PeerManagerError, TransportNotification},
-    transport::Connection,
+    transport::{Connection, HandshakeNegotiationError},
 };
 use anyhow::format_err;
 use aptos_config::network_id::NetworkContext;
@@ -21,7 +23,7 @@ use futures::{
 };
 use netcore::transport::{ConnectionOrigin, Transport};
 use short_hex_str::AsShortHexStr;
-use std::time::Instant;
+use std::{any::Any, io, sync::Arc, time::Instant};
 
 #[derive(Debug)]
 pub enum TransportRequest {
@@ -45,6 +47,8 @@ where
     listener: Fuse<TTransport::Listener>,
     transport_reqs_rx: channel::Receiver<TransportRequest>,
     transport_notifs_tx: channel::Sender<TransportNotification<TSocket>>,
+    /// Reputations of the peers, failed handshakes count against them
+    peer_reputations: Arc<PeerReputations>,
 }
 
 impl<TTransport, TSocket> TransportHandler<TTransport, TSocket>
@@ -62,6 +66,7 @@ where
         listen_addr: NetworkAddress,
         transport_reqs_rx: channel::Receiver<TransportRequest>,
         transport_notifs_tx: channel::Sender<TransportNotification<TSocket>>,
+        peer_reputations: Arc<PeerReputations>,
     ) -> (Self, NetworkAddress) {
         let addr_string = format!("{}", listen_addr);
         let (listener, listen_addr) = transport
@@ -82,6 +87,7 @@ where
                 listener: listener.fuse(),
                 transport_reqs_rx,
                 transport_notifs_tx,
+                peer_reputations,
             },
             listen_addr,
         )
@@ -246,7 +252,10 @@ where
                     )))
                 }
             }
-            Err(err) => Err(PeerManagerError::from_transport_error(err)),
+            Err(err) => {
+                self.record_upgrade_failure(&err);
+                Err(PeerManagerError::from_transport_error(err))
+            }
         };
 
         let response = match upgrade {
@@ -324,10 +333,22 @@ where
                     FAILED_LABEL,
                 )
                 .observe(elapsed_time);
+
+                self.record_upgrade_failure(&err);
             }
         }
     }
 
+    /// Records a failed connection upgrade against the reputation of the remote peer, if the
+    /// failure is the peer's fault rather than, e.g., the network's, and the handshake got far
+    /// enough to authenticate the peer.
+    fn record_upgrade_failure(&self, error: &TTransport::Error) {
+        if let Some((peer_id, misbehavior)) = upgrade_misbehavior(error) {
+            self.peer_reputations
+                .record_misbehavior(peer_id, misbehavior);
+        }
+    }
+
     /// Send a newly completed connection to `PeerManager`
     async fn send_connection_to_peer_manager(
         &mut self,
@@ -363,3 +384,22 @@ where
         }
     }
 }
+
+/// Classifies the error of a failed connection upgrade, returning the misbehavior of the remote
+/// peer if the error names an identity the handshake authenticated. Self-reported peer ids are
+/// never blamed, as anyone could claim them to get another peer banned.
+fn upgrade_misbehavior<E: std::error::Error + 'static>(error: &E) -> Option<(PeerId, Misbehavior)> {
+    let error = (error as &dyn Any).downcast_ref::<io::Error>()?.get_ref()?;
+    if let Some(error) = error.downcast_ref::<HandshakeNegotiationError>() {
+        // The negotiation happens after the Noise handshake authenticated the peer
+        return Some((error.peer_id, Misbehavior::HandshakeFailure));
+    }
+    match error.downcast_ref::<NoiseHandshakeError>()? {
+        // The client proved it holds the key, the peer id derived from it is blamed rather
+        // than the one it claimed
+        NoiseHandshakeError::ClientPeerIdMismatch(_, _, derived_peer_id) => {
+            Some((*derived_peer_id, Misbehavior::NoiseAuthFailure))
+        }
+        _ => None,
+    }
+}
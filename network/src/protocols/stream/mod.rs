@@ -1,11 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::protocols::wire::messaging::v1::{MultiplexMessage, NetworkMessage};
+use crate::protocols::wire::messaging::v1::NetworkMessage;
 use anyhow::{bail, ensure};
 use aptos_id_generator::{IdGenerator, U32IdGenerator};
-use channel::Sender;
-use futures_util::SinkExt;
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
@@ -18,6 +16,16 @@ pub enum StreamMessage {
     Fragment(StreamFragment),
 }
 
+impl StreamMessage {
+    /// Returns the length of the raw data carried by this stream message
+    pub fn data_len(&self) -> usize {
+        match self {
+            StreamMessage::Header(header) => header.message.data_len(),
+            StreamMessage::Fragment(fragment) => fragment.raw_data.len(),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
 pub struct StreamHeader {
@@ -151,15 +159,13 @@ pub struct OutboundStream {
     request_id_gen: U32IdGenerator,
     max_frame_size: usize,
     max_message_size: usize,
-    stream_tx: Sender<MultiplexMessage>,
+    max_fragments: usize,
 }
 
 impl OutboundStream {
-    pub fn new(
-        max_frame_size: usize,
-        max_message_size: usize,
-        stream_tx: Sender<MultiplexMessage>,
-    ) -> Self {
+    pub fn new(max_frame_size: usize, max_message_size: usize) -> Self {
+        // the receiver doesn't accept streams of more fragments, see `InboundStreamBuffer`
+        let max_fragments = (max_message_size / max_frame_size).min(u8::MAX as usize);
         // some buffer for headers
         let max_frame_size = max_frame_size - 64;
         assert!(
@@ -172,65 +178,73 @@ impl OutboundStream {
             request_id_gen: U32IdGenerator::new(),
             max_frame_size,
             max_message_size,
-            stream_tx,
+            max_fragments,
         }
     }
 
-    pub fn should_stream(&self, message: &NetworkMessage) -> bool {
-        message.data_len() > self.max_frame_size
+    /// Returns the size of the chunks a message of `data_len` bytes is split into. Chunks are
+    /// larger than `preferred_fragment_size` if the receiver wouldn't accept that many
+    /// fragments, but never larger than a frame.
+    fn fragment_size(&self, data_len: usize, preferred_fragment_size: usize) -> usize {
+        // the header carries the first chunk
+        let max_chunks = self.max_fragments + 1;
+        let min_fragment_size = (data_len + max_chunks - 1) / max_chunks;
+        preferred_fragment_size
+            .max(min_fragment_size)
+            .min(self.max_frame_size)
     }
 
-    pub async fn stream_message(&mut self, mut message: NetworkMessage) -> anyhow::Result<()> {
+    pub fn should_stream(&self, message: &NetworkMessage, preferred_fragment_size: usize) -> bool {
+        message.data_len() > preferred_fragment_size.min(self.max_frame_size)
+    }
+
+    /// Splits the message into a stream header followed by its fragments, preferring chunks of
+    /// `preferred_fragment_size` bytes. Pass `usize::MAX` to only split at the frame size.
+    pub fn stream_message(
+        &mut self,
+        mut message: NetworkMessage,
+        preferred_fragment_size: usize,
+    ) -> anyhow::Result<Vec<StreamMessage>> {
         ensure!(
             message.data_len() <= self.max_message_size,
             "Message length {} exceed size limit {}",
             message.data_len(),
             self.max_message_size,
         );
+        let fragment_size = self.fragment_size(message.data_len(), preferred_fragment_size);
         ensure!(
-            message.data_len() >= self.max_frame_size,
-            "Message length {} is smaller than frame size {}, should not go through stream",
+            message.data_len() > fragment_size,
+            "Message length {} is not larger than fragment size {}, should not go through stream",
             message.data_len(),
-            self.max_frame_size,
+            fragment_size,
         );
         let request_id = self.request_id_gen.next();
         let rest = match &mut message {
             NetworkMessage::Error(_) => {
                 unreachable!("NetworkMessage::Error should always fit in a single frame")
             }
-            NetworkMessage::RpcRequest(request) => {
-                request.raw_request.split_off(self.max_frame_size)
-            }
-            NetworkMessage::RpcResponse(response) => {
-                response.raw_response.split_off(self.max_frame_size)
-            }
-            NetworkMessage::DirectSendMsg(message) => {
-                message.raw_msg.split_off(self.max_frame_size)
-            }
+            NetworkMessage::RpcRequest(request) => request.raw_request.split_off(fragment_size),
+            NetworkMessage::RpcResponse(response) => response.raw_response.split_off(fragment_size),
+            NetworkMessage::DirectSendMsg(message) => message.raw_msg.split_off(fragment_size),
         };
-        let chunks = rest.chunks(self.max_frame_size);
+        let chunks = rest.chunks(fragment_size);
         ensure!(
             chunks.len() <= u8::MAX as usize,
             "Number of fragments overflowed"
         );
-        let header = StreamMessage::Header(StreamHeader {
+        let mut stream = Vec::with_capacity(chunks.len() + 1);
+        stream.push(StreamMessage::Header(StreamHeader {
             request_id,
             num_fragments: chunks.len() as u8,
             message,
-        });
-        self.stream_tx
-            .send(MultiplexMessage::Stream(header))
-            .await?;
+        }));
         for (index, chunk) in chunks.enumerate() {
-            let message = StreamMessage::Fragment(StreamFragment {
+            stream.push(StreamMessage::Fragment(StreamFragment {
                 request_id,
                 fragment_id: index as u8 + 1,
                 raw_data: Vec::from(chunk),
-            });
-            self.stream_tx
-                .send(MultiplexMessage::Stream(message))
-                .await?;
+            }));
         }
-        Ok(())
+        Ok(stream)
     }
 }
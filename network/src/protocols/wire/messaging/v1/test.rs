@@ -224,6 +224,7 @@ proptest! {
         messages in vec(arb_network_message(64 * 255), 1..20),
         fragmented_read in any::<bool>(),
         fragmented_write in any::<bool>(),
+        preferred_fragment_size in prop_oneof![Just(usize::MAX), 16..128usize],
     ) {
         let (mut socket_tx, mut socket_rx) = ReadWriteTestSocket::new_pair();
 
@@ -236,16 +237,21 @@ proptest! {
 
         let mut message_tx = MultiplexMessageSink::new(socket_tx, 128, None);
         let message_rx = MultiplexMessageStream::new(socket_rx, 128, None);
-        let (stream_tx, stream_rx) = channel::new_test(1024);
+        let (mut stream_tx, stream_rx) = channel::new_test(1024);
         let (mut msg_tx, msg_rx) = channel::new_test(1024);
-        let mut outbound_stream = OutboundStream::new(128, 64 * 255, stream_tx);
+        let mut outbound_stream = OutboundStream::new(128, 64 * 255);
         let mut inbound_stream = InboundStreamBuffer::new(255);
 
         let messages_clone = messages.clone();
         let f_stream_all = async move {
             for message in messages_clone {
-                if outbound_stream.should_stream(&message) {
-                    outbound_stream.stream_message(message).await.unwrap();
+                if outbound_stream.should_stream(&message, preferred_fragment_size) {
+                    let stream = outbound_stream
+                        .stream_message(message, preferred_fragment_size)
+                        .unwrap();
+                    for stream_message in stream {
+                        stream_tx.send(MultiplexMessage::Stream(stream_message)).await.unwrap();
+                    }
                 } else {
                     msg_tx.send(MultiplexMessage::Message(message)).await.unwrap();
                 }
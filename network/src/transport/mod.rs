@@ -6,7 +6,9 @@ use crate::{
     noise::{stream::NoiseStream, AntiReplayTimestamps, HandshakeAuthMode, NoiseUpgrader},
     protocols::{
         identity::exchange_handshake,
-        wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet},
+        wire::handshake::v1::{
+            HandshakeError, HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet,
+        },
     },
 };
 use aptos_config::{
@@ -31,6 +33,7 @@ use netcore::transport::{proxy_protocol, tcp, ConnectionOrigin, Transport};
 use serde::{Deserialize, Serialize};
 use short_hex_str::AsShortHexStr;
 use std::{collections::BTreeMap, convert::TryFrom, fmt, io, pin::Pin, sync::Arc, time::Duration};
+use thiserror::Error;
 
 // Re-exposed for aptos-network-checker
 pub use netcore::transport::tcp::{resolve_and_connect, TCPBufferCfg, TcpSocket};
@@ -45,6 +48,16 @@ pub const TRANSPORT_TIMEOUT: Duration = Duration::from_secs(30);
 /// TODO: Add ability to support more than one messaging protocol.
 pub const SUPPORTED_MESSAGING_PROTOCOL: MessagingProtocolVersion = MessagingProtocolVersion::V1;
 
+/// The protocol negotiation with an authenticated peer failed, e.g., because the peer is on
+/// another chain. Carried by the `io::Error` of the failed upgrade, so that the failure can be
+/// held against the peer.
+#[derive(Debug, Error)]
+#[error("handshake negotiation with peer {} failed: {}", .peer_id.short_str(), .error)]
+pub struct HandshakeNegotiationError {
+    pub peer_id: PeerId,
+    pub error: HandshakeError,
+}
+
 /// Global connection-id generator.
 static CONNECTION_ID_GENERATOR: ConnectionIdGenerator = ConnectionIdGenerator::new();
 
@@ -296,12 +309,11 @@ async fn upgrade_inbound<T: TSocket>(
     // try to negotiate common aptosnet version and supported application protocols
     let (messaging_protocol, application_protocols) = handshake_msg
         .perform_handshake(&remote_handshake)
-        .map_err(|err| {
-            let err = format!(
-                "handshake negotiation with peer {} failed: {}",
-                remote_peer_id.short_str(),
-                err
-            );
+        .map_err(|error| {
+            let err = HandshakeNegotiationError {
+                peer_id: remote_peer_id,
+                error,
+            };
             add_pp_addr(
                 proxy_protocol_enabled,
                 io::Error::new(io::ErrorKind::Other, err),
@@ -371,11 +383,11 @@ pub async fn upgrade_outbound<T: TSocket>(
     // try to negotiate common aptosnet version and supported application protocols
     let (messaging_protocol, application_protocols) = handshake_msg
         .perform_handshake(&remote_handshake)
-        .map_err(|e| {
-            let e = format!(
-                "handshake negotiation with peer {} failed: {}",
-                remote_peer_id, e
-            );
+        .map_err(|error| {
+            let e = HandshakeNegotiationError {
+                peer_id: remote_peer_id,
+                error,
+            };
             io::Error::new(io::ErrorKind::Other, e)
         })?;
 
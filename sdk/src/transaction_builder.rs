@@ -8,12 +8,16 @@ use crate::{
         transaction::{authenticator::AuthenticationKey, RawTransaction, TransactionPayload},
     },
 };
-use aptos_crypto::ed25519::Ed25519PublicKey;
+use anyhow::{bail, ensure, Result};
+use aptos_crypto::{ed25519::Ed25519PublicKey, traits::signing_message};
 use aptos_global_constants::{GAS_UNIT_PRICE, MAX_GAS_AMOUNT};
 use aptos_types::transaction::{
-    authenticator::AuthenticationKeyPreimage, EntryFunction, ModuleBundle, Script,
+    authenticator::{AccountAuthenticator, AuthenticationKeyPreimage},
+    EntryFunction, ModuleBundle, RawTransactionWithData, Script, SignedTransaction,
 };
 pub use cached_packages::aptos_stdlib;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 pub struct TransactionBuilder {
     sender: Option<AccountAddress>,
@@ -85,6 +89,174 @@ impl TransactionBuilder {
             self.chain_id,
         )
     }
+
+    /// Builds a multi-agent transaction, to be signed by the sender and the secondary signers
+    pub fn build_multi_agent(
+        self,
+        secondary_signer_addresses: Vec<AccountAddress>,
+    ) -> Result<MultiAgentSigningRequest> {
+        MultiAgentSigningRequest::new(self.build(), secondary_signer_addresses)
+    }
+}
+
+/// A multi-agent transaction, collecting the signatures of its sender and secondary signers
+///
+/// Every party signs the same message, so the request can be serialized and passed around for
+/// each party to sign on its own machine.  The message is the one returned by the
+/// `/transactions/encode_submission` API for the transaction and its secondary signers, so
+/// signatures can also be made by clients without BCS support.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "UncheckedMultiAgentSigningRequest")]
+pub struct MultiAgentSigningRequest {
+    raw_txn: RawTransaction,
+    secondary_signer_addresses: Vec<AccountAddress>,
+    sender_signature: Option<AccountAuthenticator>,
+    secondary_signatures: Vec<Option<AccountAuthenticator>>,
+}
+
+/// A deserialized [`MultiAgentSigningRequest`], which may have been edited by hand
+#[derive(Deserialize)]
+struct UncheckedMultiAgentSigningRequest {
+    raw_txn: RawTransaction,
+    secondary_signer_addresses: Vec<AccountAddress>,
+    sender_signature: Option<AccountAuthenticator>,
+    secondary_signatures: Vec<Option<AccountAuthenticator>>,
+}
+
+impl TryFrom<UncheckedMultiAgentSigningRequest> for MultiAgentSigningRequest {
+    type Error = anyhow::Error;
+
+    fn try_from(request: UncheckedMultiAgentSigningRequest) -> Result<Self> {
+        check_signers(&request.raw_txn, &request.secondary_signer_addresses)?;
+        ensure!(
+            request.secondary_signatures.len() == request.secondary_signer_addresses.len(),
+            "Multi-agent signing request has {} secondary signatures for {} secondary signers",
+            request.secondary_signatures.len(),
+            request.secondary_signer_addresses.len()
+        );
+        Ok(Self {
+            raw_txn: request.raw_txn,
+            secondary_signer_addresses: request.secondary_signer_addresses,
+            sender_signature: request.sender_signature,
+            secondary_signatures: request.secondary_signatures,
+        })
+    }
+}
+
+/// Fails if a signer appears twice, as every signer signs for a single account
+fn check_signers(
+    raw_txn: &RawTransaction,
+    secondary_signer_addresses: &[AccountAddress],
+) -> Result<()> {
+    let mut signers = HashSet::new();
+    signers.insert(raw_txn.sender());
+    for address in secondary_signer_addresses {
+        ensure!(
+            signers.insert(*address),
+            "{} is a signer of the transaction more than once",
+            address
+        );
+    }
+    Ok(())
+}
+
+impl MultiAgentSigningRequest {
+    /// Fails if the sender is a secondary signer, or a secondary signer is listed more than once
+    pub fn new(
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+    ) -> Result<Self> {
+        check_signers(&raw_txn, &secondary_signer_addresses)?;
+        let secondary_signatures = vec![None; secondary_signer_addresses.len()];
+        Ok(Self {
+            raw_txn,
+            secondary_signer_addresses,
+            sender_signature: None,
+            secondary_signatures,
+        })
+    }
+
+    pub fn raw_transaction(&self) -> &RawTransaction {
+        &self.raw_txn
+    }
+
+    pub fn secondary_signer_addresses(&self) -> &[AccountAddress] {
+        &self.secondary_signer_addresses
+    }
+
+    /// The message signed by every party
+    pub fn message(&self) -> RawTransactionWithData {
+        RawTransactionWithData::new_multi_agent(
+            self.raw_txn.clone(),
+            self.secondary_signer_addresses.clone(),
+        )
+    }
+
+    /// The bytes to sign, as returned by `/transactions/encode_submission`
+    pub fn signing_message(&self) -> Result<Vec<u8>> {
+        Ok(signing_message(&self.message())?)
+    }
+
+    /// Adds the signature of `signer`, which must be the sender or one of the secondary signers
+    ///
+    /// The signature is checked against its public key, but whether the public key is the one of
+    /// the account can only be checked on chain.
+    pub fn add_signature(
+        &mut self,
+        signer: AccountAddress,
+        authenticator: AccountAuthenticator,
+    ) -> Result<()> {
+        authenticator.verify(&self.message())?;
+        if signer == self.raw_txn.sender() {
+            self.sender_signature = Some(authenticator);
+        } else if let Some(index) = self
+            .secondary_signer_addresses
+            .iter()
+            .position(|address| address == &signer)
+        {
+            match self.secondary_signatures.get_mut(index) {
+                Some(signature) => *signature = Some(authenticator),
+                None => bail!("Missing the signature slot of secondary signer {}", signer),
+            }
+        } else {
+            bail!("{} is not a signer of the transaction", signer);
+        }
+        Ok(())
+    }
+
+    /// Signers which haven't signed yet, the sender first
+    pub fn missing_signers(&self) -> Vec<AccountAddress> {
+        let sender = self
+            .sender_signature
+            .is_none()
+            .then(|| self.raw_txn.sender());
+        sender
+            .into_iter()
+            .chain(
+                self.secondary_signer_addresses
+                    .iter()
+                    .zip(&self.secondary_signatures)
+                    .filter(|(_, signature)| signature.is_none())
+                    .map(|(address, _)| *address),
+            )
+            .collect()
+    }
+
+    /// Builds the transaction, once every party has signed
+    pub fn into_signed_transaction(self) -> Result<SignedTransaction> {
+        let missing_signers = self.missing_signers();
+        ensure!(
+            missing_signers.is_empty(),
+            "Transaction is missing signatures from {:?}",
+            missing_signers
+        );
+        Ok(SignedTransaction::new_multi_agent(
+            self.raw_txn,
+            self.sender_signature.expect("Sender must have signed"),
+            self.secondary_signer_addresses,
+            self.secondary_signatures.into_iter().flatten().collect(),
+        ))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -220,3 +392,95 @@ impl DualAttestationMessage {
         &self.message
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LocalAccount;
+
+    fn make_request(num_secondary_signers: usize) -> (MultiAgentSigningRequest, Vec<LocalAccount>) {
+        let mut rng = rand::rngs::OsRng;
+        let sender = LocalAccount::generate(&mut rng);
+        let secondary_signers: Vec<_> = (0..num_secondary_signers)
+            .map(|_| LocalAccount::generate(&mut rng))
+            .collect();
+        let raw_txn = TransactionFactory::new(ChainId::test())
+            .payload(aptos_stdlib::aptos_coin_transfer(
+                secondary_signers[0].address(),
+                100,
+            ))
+            .sender(sender.address())
+            .sequence_number(0)
+            .build();
+        let request = MultiAgentSigningRequest::new(
+            raw_txn,
+            secondary_signers
+                .iter()
+                .map(|account| account.address())
+                .collect(),
+        )
+        .unwrap();
+        let mut signers = vec![sender];
+        signers.extend(secondary_signers);
+        (request, signers)
+    }
+
+    #[test]
+    fn test_multi_agent_signing_request() {
+        let (mut request, signers) = make_request(2);
+        let addresses: Vec<_> = signers.iter().map(|signer| signer.address()).collect();
+        assert_eq!(request.missing_signers(), addresses);
+
+        // Signatures can be added in any order, and survive serialization
+        signers[2].sign_multi_agent_request(&mut request).unwrap();
+        signers[0].sign_multi_agent_request(&mut request).unwrap();
+        let mut request: MultiAgentSigningRequest =
+            bcs::from_bytes(&bcs::to_bytes(&request).unwrap()).unwrap();
+        assert_eq!(request.missing_signers(), vec![addresses[1]]);
+        request.clone().into_signed_transaction().unwrap_err();
+
+        // Strangers can't sign
+        let stranger = LocalAccount::generate(&mut rand::rngs::OsRng);
+        stranger.sign_multi_agent_request(&mut request).unwrap_err();
+
+        signers[1].sign_multi_agent_request(&mut request).unwrap();
+        assert!(request.missing_signers().is_empty());
+        let signed_txn = request.into_signed_transaction().unwrap();
+        signed_txn.check_signature().unwrap();
+    }
+
+    #[test]
+    fn test_multi_agent_signing_request_with_missing_signature_slots() {
+        let (mut request, signers) = make_request(2);
+
+        // Drop the slot of a secondary signature, as if the request was edited by hand
+        request.secondary_signatures.pop();
+        bcs::from_bytes::<MultiAgentSigningRequest>(&bcs::to_bytes(&request).unwrap()).unwrap_err();
+
+        // Signing a request with inconsistent slots fails rather than panics
+        signers[2]
+            .sign_multi_agent_request(&mut request)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_multi_agent_signing_request_with_duplicate_signers() {
+        let (mut request, signers) = make_request(2);
+        let raw_txn = request.raw_transaction().clone();
+
+        // The sender can't also be a secondary signer
+        MultiAgentSigningRequest::new(
+            raw_txn.clone(),
+            vec![signers[1].address(), signers[0].address()],
+        )
+        .unwrap_err();
+
+        // A secondary signer can't be listed twice
+        MultiAgentSigningRequest::new(raw_txn, vec![signers[1].address(), signers[1].address()])
+            .unwrap_err();
+
+        // Neither in a request edited by hand
+        request.secondary_signer_addresses[1] = signers[1].address();
+        bcs::from_bytes::<MultiAgentSigningRequest>(&bcs::to_bytes(&request).unwrap()).unwrap_err();
+    }
+}
@@ -4,12 +4,15 @@
 use crate::{
     crypto::{
         ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-        traits::Uniform,
+        traits::{SigningKey, Uniform},
     },
-    transaction_builder::TransactionBuilder,
+    transaction_builder::{MultiAgentSigningRequest, TransactionBuilder},
     types::{
         account_address::AccountAddress,
-        transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+        transaction::{
+            authenticator::{AccountAuthenticator, AuthenticationKey},
+            RawTransaction, SignedTransaction,
+        },
     },
 };
 
@@ -101,6 +104,18 @@ impl LocalAccount {
             .into_inner()
     }
 
+    /// Adds the signature of this account to a multi-agent transaction
+    pub fn sign_multi_agent_request(
+        &self,
+        request: &mut MultiAgentSigningRequest,
+    ) -> anyhow::Result<()> {
+        let signature = self.private_key().sign(&request.message())?;
+        request.add_signature(
+            self.address(),
+            AccountAuthenticator::ed25519(self.public_key().clone(), signature),
+        )
+    }
+
     pub fn address(&self) -> AccountAddress {
         self.address
     }
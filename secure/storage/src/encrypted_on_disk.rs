@@ -0,0 +1,289 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{CryptoKVStorage, Error, GetResponse, KVStorage, OnDiskStorage};
+use aptos_temppath::TempPath;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+/// The version of the file format, bumped whenever the envelope or the key derivation changes
+const FORMAT_VERSION: u32 = 1;
+/// PBKDF2-HMAC-SHA256 iterations used for newly created files
+const KDF_ITERATIONS: u32 = 100_000;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+/// EncryptedOnDiskStorage is a key value store persisted to a single file on the local
+/// filesystem, like OnDiskStorage, except that the file contents are encrypted with AES-256-GCM.
+/// The encryption key is derived from a secret (a passphrase or the contents of a key file) and a
+/// random salt stored alongside the ciphertext. Every write uses a fresh nonce and atomically
+/// replaces the file, so a crash never leaves a partially written store behind.
+///
+/// Like OnDiskStorage, this is intended for single threads (or must be wrapped by a
+/// Arc<RwLock<>>) and provides no permission checks. It protects key material at rest, but the
+/// secret has to be available to the process, and decrypted data lives in memory while in use.
+pub struct EncryptedOnDiskStorage {
+    file_path: PathBuf,
+    temp_path: TempPath,
+    time_service: TimeService,
+    key: [u8; KEY_LEN],
+    kdf_iterations: u32,
+    salt: Vec<u8>,
+}
+
+/// The contents of the file, the ciphertext is the JSON encoded key value map
+#[derive(Deserialize, Serialize)]
+struct Envelope {
+    version: u32,
+    kdf_iterations: u32,
+    #[serde(
+        serialize_with = "crate::to_base64",
+        deserialize_with = "crate::from_base64"
+    )]
+    salt: Vec<u8>,
+    #[serde(
+        serialize_with = "crate::to_base64",
+        deserialize_with = "crate::from_base64"
+    )]
+    nonce: Vec<u8>,
+    #[serde(
+        serialize_with = "crate::to_base64",
+        deserialize_with = "crate::from_base64"
+    )]
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedOnDiskStorage {
+    /// Opens the store at `file_path`, creating it if it does not exist. `secret` is the
+    /// passphrase or key file contents the encryption key is derived from.
+    pub fn new(file_path: PathBuf, secret: &[u8]) -> Self {
+        Self::new_with_time_service(file_path, secret, TimeService::real())
+    }
+
+    fn new_with_time_service(file_path: PathBuf, secret: &[u8], time_service: TimeService) -> Self {
+        if !file_path.exists() {
+            create_user_only_file(&file_path)
+                .unwrap_or_else(|_| panic!("Unable to create storage at path: {:?}", file_path));
+        }
+
+        // Reuse the salt of an existing store so its key can be derived, otherwise start a new one
+        let envelope = read_envelope(&file_path)
+            .unwrap_or_else(|e| panic!("Unable to read storage at path {:?}: {}", file_path, e));
+        let (kdf_iterations, salt) = match envelope {
+            Some(envelope) => {
+                if envelope.version != FORMAT_VERSION {
+                    panic!(
+                        "Unsupported storage format version {} at path: {:?}",
+                        envelope.version, file_path
+                    );
+                }
+                (envelope.kdf_iterations, envelope.salt)
+            }
+            None => {
+                let mut salt = vec![0; SALT_LEN];
+                SystemRandom::new()
+                    .fill(&mut salt)
+                    .expect("Unable to generate salt");
+                (KDF_ITERATIONS, salt)
+            }
+        };
+        let key = derive_key(secret, &salt, kdf_iterations)
+            .unwrap_or_else(|e| panic!("Unable to derive storage key: {}", e));
+
+        // The parent will be one when only a filename is supplied. Therefore use the current
+        // working directory provided by PathBuf::new().
+        let file_dir = file_path
+            .parent()
+            .map_or(PathBuf::new(), |p| p.to_path_buf());
+
+        Self {
+            file_path,
+            temp_path: TempPath::new_with_temp_dir(file_dir),
+            time_service,
+            key,
+            kdf_iterations,
+            salt,
+        }
+    }
+
+    /// Encrypts the contents of the OnDiskStorage at `on_disk_path` into a new store at
+    /// `file_path`, keeping all values and their last update times. Returns the number of keys
+    /// migrated. Fails if a store already exists at `file_path`.
+    pub fn migrate_from_on_disk(
+        on_disk_path: &Path,
+        file_path: PathBuf,
+        secret: &[u8],
+    ) -> Result<usize, Error> {
+        if !on_disk_path.exists() {
+            return Err(Error::InternalError(format!(
+                "No storage found at path: {:?}",
+                on_disk_path
+            )));
+        }
+        if file_path.exists() {
+            return Err(Error::InternalError(format!(
+                "Storage already exists at path: {:?}",
+                file_path
+            )));
+        }
+
+        let data = OnDiskStorage::new(on_disk_path.to_path_buf()).read()?;
+        let storage = Self::new(file_path, secret);
+        storage.write(&data)?;
+        Ok(data.len())
+    }
+
+    fn read(&self) -> Result<HashMap<String, Value>, Error> {
+        let envelope = match read_envelope(&self.file_path)? {
+            Some(envelope) => envelope,
+            None => return Ok(HashMap::new()),
+        };
+        if envelope.version != FORMAT_VERSION
+            || envelope.kdf_iterations != self.kdf_iterations
+            || envelope.salt != self.salt
+        {
+            return Err(Error::InternalError(format!(
+                "Storage at path {:?} was replaced, reopen it",
+                self.file_path
+            )));
+        }
+
+        let nonce = Nonce::try_assume_unique_for_key(&envelope.nonce)
+            .map_err(|_| Error::SerializationError("Invalid nonce length".into()))?;
+        let mut in_out = envelope.ciphertext;
+        let plaintext = self
+            .aead_key()?
+            .open_in_place(nonce, Aad::from(aad(self.kdf_iterations)), &mut in_out)
+            .map_err(|_| {
+                Error::InternalError(
+                    "Unable to decrypt storage, the secret is wrong or the file is corrupted"
+                        .into(),
+                )
+            })?;
+        Ok(serde_json::from_slice(plaintext)?)
+    }
+
+    fn write(&self, data: &HashMap<String, Value>) -> Result<(), Error> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| Error::EntropyError("Unable to generate nonce".into()))?;
+
+        let mut ciphertext = serde_json::to_vec(data)?;
+        self.aead_key()?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad(self.kdf_iterations)),
+                &mut ciphertext,
+            )
+            .map_err(|_| Error::InternalError("Unable to encrypt storage".into()))?;
+        let envelope = Envelope {
+            version: FORMAT_VERSION,
+            kdf_iterations: self.kdf_iterations,
+            salt: self.salt.clone(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        };
+        let contents = serde_json::to_vec(&envelope)?;
+
+        // Write to a temporary file in the same directory and move it into place, so readers
+        // either see the old or the new contents, but never a partial write.
+        let mut file = create_user_only_file(self.temp_path.path())?;
+        file.write_all(&contents)?;
+        file.sync_all()?;
+        fs::rename(&self.temp_path, &self.file_path)?;
+        Ok(())
+    }
+
+    fn aead_key(&self) -> Result<LessSafeKey, Error> {
+        let key = UnboundKey::new(&AES_256_GCM, &self.key)
+            .map_err(|_| Error::InternalError("Invalid storage key".into()))?;
+        Ok(LessSafeKey::new(key))
+    }
+}
+
+impl KVStorage for EncryptedOnDiskStorage {
+    fn available(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get<V: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<V>, Error> {
+        let mut data = self.read()?;
+        data.remove(key)
+            .ok_or_else(|| Error::KeyNotSet(key.to_string()))
+            .and_then(|value| serde_json::from_value(value).map_err(|e| e.into()))
+    }
+
+    fn set<V: Serialize>(&mut self, key: &str, value: V) -> Result<(), Error> {
+        let now = self.time_service.now_secs();
+        let mut data = self.read()?;
+        data.insert(
+            key.to_string(),
+            serde_json::to_value(&GetResponse::new(value, now))?,
+        );
+        self.write(&data)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        self.write(&HashMap::new())
+    }
+}
+
+impl CryptoKVStorage for EncryptedOnDiskStorage {}
+
+fn read_envelope(file_path: &Path) -> Result<Option<Envelope>, Error> {
+    let mut file = File::open(file_path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    if contents.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+fn derive_key(secret: &[u8], salt: &[u8], iterations: u32) -> Result<[u8; KEY_LEN], Error> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| Error::SerializationError("KDF iterations must not be zero".into()))?;
+    let mut key = [0; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        secret,
+        &mut key,
+    );
+    Ok(key)
+}
+
+/// Binds the ciphertext to the format and key derivation parameters it was written with
+fn aad(kdf_iterations: u32) -> [u8; 8] {
+    let mut aad = [0; 8];
+    aad[..4].copy_from_slice(&FORMAT_VERSION.to_be_bytes());
+    aad[4..].copy_from_slice(&kdf_iterations.to_be_bytes());
+    aad
+}
+
+fn create_user_only_file(path: &Path) -> Result<File, Error> {
+    let mut opts = OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    opts.mode(0o600);
+    Ok(opts.open(path)?)
+}
@@ -50,7 +50,7 @@ impl OnDiskStorage {
         }
     }
 
-    fn read(&self) -> Result<HashMap<String, Value>, Error> {
+    pub(crate) fn read(&self) -> Result<HashMap<String, Value>, Error> {
         let mut file = File::open(&self.file_path)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
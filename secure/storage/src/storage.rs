@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::rocks_db::RocksDbStorage;
 use crate::{
-    CryptoStorage, Error, GetResponse, GitHubStorage, InMemoryStorage, KVStorage, Namespaced,
-    OnDiskStorage, PublicKeyResponse, VaultStorage,
+    CryptoStorage, EncryptedOnDiskStorage, Error, GetResponse, GitHubStorage, InMemoryStorage,
+    KVStorage, Namespaced, OnDiskStorage, PublicKeyResponse, VaultStorage,
 };
 use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
 use enum_dispatch::enum_dispatch;
@@ -22,6 +22,7 @@ pub enum Storage {
     InMemoryStorage(InMemoryStorage),
     NamespacedStorage(Namespaced<Box<Storage>>),
     OnDiskStorage(OnDiskStorage),
+    EncryptedOnDiskStorage(EncryptedOnDiskStorage),
     RocksDbStorage(RocksDbStorage),
 }
 
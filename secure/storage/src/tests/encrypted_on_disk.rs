@@ -0,0 +1,58 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{tests::suite, EncryptedOnDiskStorage, Error, KVStorage, OnDiskStorage, Storage};
+use aptos_temppath::TempPath;
+use std::fs;
+
+const SECRET: &[u8] = b"correct horse battery staple";
+
+#[test]
+fn encrypted_on_disk() {
+    let path_buf = TempPath::new().path().to_path_buf();
+    let mut storage = Storage::from(EncryptedOnDiskStorage::new(path_buf, SECRET));
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+#[test]
+fn encrypted_on_disk_persists_across_reopen() {
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+
+    let mut storage = EncryptedOnDiskStorage::new(path_buf.clone(), SECRET);
+    storage.set("key", "value".to_string()).unwrap();
+    assert!(!fs::read_to_string(&path_buf).unwrap().contains("value"));
+
+    let storage = EncryptedOnDiskStorage::new(path_buf.clone(), SECRET);
+    assert_eq!(storage.get::<String>("key").unwrap().value, "value");
+
+    let storage = EncryptedOnDiskStorage::new(path_buf, b"wrong secret");
+    assert!(matches!(
+        storage.get::<String>("key"),
+        Err(Error::InternalError(_))
+    ));
+}
+
+#[test]
+fn encrypted_on_disk_migration() {
+    let on_disk_path = TempPath::new();
+    let mut on_disk = OnDiskStorage::new(on_disk_path.path().to_path_buf());
+    on_disk.set("key", "value".to_string()).unwrap();
+    let last_update = on_disk.get::<String>("key").unwrap().last_update;
+
+    let temp_path = TempPath::new();
+    let path_buf = temp_path.path().to_path_buf();
+    let migrated =
+        EncryptedOnDiskStorage::migrate_from_on_disk(on_disk_path.path(), path_buf.clone(), SECRET)
+            .unwrap();
+    assert_eq!(migrated, 1);
+
+    let storage = EncryptedOnDiskStorage::new(path_buf.clone(), SECRET);
+    let response = storage.get::<String>("key").unwrap();
+    assert_eq!(response.value, "value");
+    assert_eq!(response.last_update, last_update);
+
+    // Never overwrite an existing store
+    EncryptedOnDiskStorage::migrate_from_on_disk(on_disk_path.path(), path_buf, SECRET)
+        .unwrap_err();
+}
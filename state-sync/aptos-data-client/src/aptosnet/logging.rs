@@ -41,6 +41,8 @@ impl<'a> LogSchema<'a> {
 #[serde(rename_all = "snake_case")]
 pub enum LogEntry {
     DataSummaryPoller,
+    PeerMonitoringPoller,
+    PeerMonitoringRequest,
     PeerStates,
     StorageServiceRequest,
     StorageServiceResponse,
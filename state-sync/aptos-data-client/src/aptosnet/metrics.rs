@@ -4,15 +4,30 @@
 use aptos_config::network_id::PeerNetworkId;
 use aptos_crypto::_once_cell::sync::Lazy;
 use aptos_metrics_core::{
-    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramTimer,
-    HistogramVec, IntCounterVec, IntGaugeVec,
+    register_gauge_vec, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec,
+    GaugeVec, HistogramTimer, HistogramVec, IntCounterVec, IntGaugeVec,
 };
+use short_hex_str::AsShortHexStr;
 
 /// The special label TOTAL_COUNT stores the sum of all values in the counter.
 pub const TOTAL_COUNT_LABEL: &str = "TOTAL_COUNT";
 pub const PRIORITIZED_PEER: &str = "prioritized_peer";
 pub const REGULAR_PEER: &str = "regular_peer";
 
+/// Peer score labels
+pub const SCORE_LABEL: &str = "score";
+pub const LATENCY_SECS_LABEL: &str = "latency_secs";
+pub const THROUGHPUT_LABEL: &str = "throughput_bytes_per_sec";
+pub const DISTANCE_FROM_VALIDATORS_LABEL: &str = "distance_from_validators";
+pub const SELECTION_WEIGHT_LABEL: &str = "selection_weight";
+pub const PEER_SCORE_LABELS: [&str; 5] = [
+    SCORE_LABEL,
+    LATENCY_SECS_LABEL,
+    THROUGHPUT_LABEL,
+    DISTANCE_FROM_VALIDATORS_LABEL,
+    SELECTION_WEIGHT_LABEL,
+];
+
 // TOOD(joshlind): add peer priorities back to the requests
 
 /// Counter for tracking sent requests
@@ -105,6 +120,16 @@ pub static OPTIMAL_CHUNK_SIZES: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge for the (decayed) scores and measurements of each connected peer
+pub static PEER_SCORES: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "aptos_data_client_peer_scores",
+        "Gauge related to the scores and measurements of each peer",
+        &["network", "remote_peer_id", "score_type"]
+    )
+    .unwrap()
+});
+
 /// An enum representing the various types of data that can be
 /// fetched via the data client.
 pub enum DataType {
@@ -152,6 +177,31 @@ pub fn set_gauge(counter: &Lazy<IntGaugeVec>, label: &str, value: u64) {
     counter.with_label_values(&[label]).set(value as i64);
 }
 
+/// Sets the peer score gauge for the given peer and score type
+pub fn set_peer_score_gauge(peer_network_id: &PeerNetworkId, score_type: &str, value: f64) {
+    let network = peer_network_id.network_id();
+    PEER_SCORES
+        .with_label_values(&[
+            network.as_str(),
+            peer_network_id.peer_id().short_str().as_str(),
+            score_type,
+        ])
+        .set(value);
+}
+
+/// Removes the peer score gauges of the given peer, e.g., once it disconnects
+pub fn remove_peer_score_gauges(peer_network_id: &PeerNetworkId) {
+    let network = peer_network_id.network_id();
+    for score_type in PEER_SCORE_LABELS {
+        // The gauge may not have been set yet
+        let _ = PEER_SCORES.remove_label_values(&[
+            network.as_str(),
+            peer_network_id.peer_id().short_str().as_str(),
+            score_type,
+        ]);
+    }
+}
+
 /// Starts the timer for the provided histogram and label values.
 pub fn start_request_timer(
     histogram: &Lazy<HistogramVec>,
@@ -28,11 +28,16 @@ use aptos_types::{
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{future::join_all, StreamExt};
 use network::{
     application::interface::NetworkInterface,
     protocols::{rpc::error::RpcError, wire::handshake::v1::ProtocolId},
 };
+use peer_monitoring_service_client::PeerMonitoringServiceClient;
+use peer_monitoring_service_types::{
+    DepthFromValidatorsResponse, PeerMonitoringServiceRequest, PeerMonitoringServiceResponse,
+    PingResponse, UnexpectedResponseError,
+};
 use rand::seq::SliceRandom;
 use std::{convert::TryFrom, fmt, sync::Arc, time::Duration};
 use storage_service_client::StorageServiceClient;
@@ -92,6 +97,8 @@ pub struct AptosNetDataClient {
     global_summary_cache: Arc<RwLock<GlobalDataSummary>>,
     /// Used for generating the next request/response id.
     response_id_generator: Arc<U64IdGenerator>,
+    /// The service used to measure request latencies.
+    time_service: TimeService,
 }
 
 impl AptosNetDataClient {
@@ -110,9 +117,11 @@ impl AptosNetDataClient {
                 base_config,
                 storage_service_config,
                 network_client.get_peer_metadata_storage(),
+                time_service.clone(),
             ))),
             global_summary_cache: Arc::new(RwLock::new(GlobalDataSummary::empty())),
             response_id_generator: Arc::new(U64IdGenerator::new()),
+            time_service: time_service.clone(),
         };
         let poller = DataSummaryPoller::new(
             client.clone(),
@@ -138,6 +147,29 @@ impl AptosNetDataClient {
         self.peer_states.write().update_summary(peer, summary)
     }
 
+    /// Updates the latency of the given peer using the round-trip time of a
+    /// peer monitoring service ping (i.e., `PeerMonitoringServiceRequest::Ping`).
+    pub fn update_peer_ping_latency(&self, peer: PeerNetworkId, latency: Duration) {
+        self.peer_states.write().update_ping_latency(peer, latency)
+    }
+
+    /// Updates the distance of the given peer from the validators, as reported by
+    /// the peer monitoring service (i.e., `PeerMonitoringServiceRequest::GetDepthFromValidators`).
+    pub fn update_peer_distance_from_validators(&self, peer: PeerNetworkId, distance: u64) {
+        self.peer_states
+            .write()
+            .update_distance_from_validators(peer, distance)
+    }
+
+    /// Updates the score metrics of all connected peers
+    fn update_peer_score_metrics(&self) {
+        // No peers are connected if this fails
+        let connected_peers = self.get_all_connected_peers().unwrap_or_default();
+        self.peer_states
+            .write()
+            .update_peer_score_metrics(&connected_peers)
+    }
+
     /// Recompute and update the global data summary cache.
     fn update_global_summary_cache(&self) {
         let aggregate = self.peer_states.read().calculate_aggregate_summary();
@@ -160,9 +192,15 @@ impl AptosNetDataClient {
             self.identify_serviceable(regular_peers, request)
         };
 
-        // Randomly select a peer to handle the request
+        // Select a peer to handle the request, weighting each peer by its score,
+        // latency, throughput and distance from the validators. If no peer has a
+        // usable weight, fall back to a uniformly random selection.
+        let mut rng = rand::thread_rng();
+        let peer_states = self.peer_states.read();
         serviceable_peers
-            .choose(&mut rand::thread_rng())
+            .choose_weighted(&mut rng, |peer| peer_states.selection_weight(peer))
+            .ok()
+            .or_else(|| serviceable_peers.choose(&mut rng))
             .copied()
             .ok_or_else(|| {
                 Error::DataIsUnavailable(
@@ -265,6 +303,24 @@ impl AptosNetDataClient {
         Ok(connected_peers)
     }
 
+    /// Returns all connected peers that can be monitored using the
+    /// peer monitoring service.
+    fn get_peers_to_monitor(&self) -> Vec<PeerNetworkId> {
+        let network_peer_metadata = self.network_client.peer_metadata_storage();
+        network_peer_metadata
+            .networks()
+            .flat_map(|network_id| {
+                network_peer_metadata
+                    .read_filtered(network_id, |(_, peer_metadata)| {
+                        peer_metadata.is_connected()
+                            && peer_metadata.supports_protocol(ProtocolId::StorageServiceRpc)
+                            && peer_metadata.supports_protocol(ProtocolId::PeerMonitoringServiceRpc)
+                    })
+                    .into_keys()
+            })
+            .collect()
+    }
+
     /// Returns all priority and regular peers
     fn get_priority_and_regular_peers(
         &self,
@@ -373,6 +429,7 @@ impl AptosNetDataClient {
 
         increment_request_counter(&metrics::SENT_REQUESTS, &request.get_label(), peer);
 
+        let request_start_time = self.time_service.now();
         let result = self
             .network_client
             .send_request(
@@ -394,6 +451,18 @@ impl AptosNetDataClient {
 
                 increment_request_counter(&metrics::SUCCESS_RESPONSES, &request.get_label(), peer);
 
+                // Update the latency or throughput measurements of the peer
+                let request_duration = self
+                    .time_service
+                    .now()
+                    .saturating_duration_since(request_start_time);
+                let response_bytes = bcs::serialized_size(&response).unwrap_or_default() as u64;
+                self.peer_states.write().update_response_measurements(
+                    peer,
+                    response_bytes,
+                    request_duration,
+                );
+
                 // For now, record all responses that at least pass the data
                 // client layer successfully. An alternative might also have the
                 // consumer notify both success and failure via the callback.
@@ -639,6 +708,12 @@ impl DataSummaryPoller {
             // Update the global storage summary
             self.data_client.update_global_summary_cache();
 
+            // Update the peer score metrics, periodically
+            sample!(
+                SampleRate::Duration(Duration::from_secs(GLOBAL_DATA_METRIC_FREQ_SECS)),
+                self.data_client.update_peer_score_metrics();
+            );
+
             // Fetch the prioritized and regular peers to poll (if any)
             let prioritized_peer = self.try_fetch_peer(true);
             let regular_peer = self.fetch_regular_peer(prioritized_peer.is_none());
@@ -697,6 +772,143 @@ impl DataSummaryPoller {
     }
 }
 
+/// A poller that periodically measures the ping latency and the distance from
+/// the validators of all connected peers, using the peer monitoring service.
+pub struct PeerMonitoringPoller {
+    data_client: AptosNetDataClient, // The data client to update with the measurements
+    peer_monitoring_client: PeerMonitoringServiceClient, // The client through which to monitor peers
+    poll_interval: Duration,                             // The interval between polling rounds
+    time_service: TimeService,                           // The service to monitor elapsed time
+}
+
+impl PeerMonitoringPoller {
+    pub fn new(
+        data_client: AptosNetDataClient,
+        peer_monitoring_client: PeerMonitoringServiceClient,
+        time_service: TimeService,
+    ) -> Self {
+        let poll_interval = Duration::from_millis(
+            data_client
+                .data_client_config
+                .peer_monitoring_poll_interval_ms,
+        );
+        Self {
+            data_client,
+            peer_monitoring_client,
+            poll_interval,
+            time_service,
+        }
+    }
+
+    /// Runs the poller that continuously monitors the connected peers
+    pub async fn start_poller(self) {
+        info!(
+            (LogSchema::new(LogEntry::PeerMonitoringPoller)
+                .message("Starting the peer monitoring poller!"))
+        );
+        let ticker = self.time_service.interval(self.poll_interval);
+        futures::pin_mut!(ticker);
+
+        loop {
+            // Wait for next round before polling
+            ticker.next().await;
+
+            // Monitor all peers concurrently, and wait for the round to complete
+            // so that each peer has at most one in-flight monitoring request.
+            let peers = self.data_client.get_peers_to_monitor();
+            join_all(peers.into_iter().map(|peer| {
+                monitor_peer(
+                    self.data_client.clone(),
+                    self.peer_monitoring_client.clone(),
+                    peer,
+                )
+            }))
+            .await;
+        }
+    }
+}
+
+/// Pings the given peer and fetches its depth from the validators, and
+/// updates the ping latency and distance of the peer in the data client.
+pub(crate) async fn monitor_peer(
+    data_client: AptosNetDataClient,
+    peer_monitoring_client: PeerMonitoringServiceClient,
+    peer: PeerNetworkId,
+) {
+    // Measure the round-trip time of a ping
+    let ping_start_time = data_client.time_service.now();
+    let result: Result<PingResponse> = send_peer_monitoring_request(
+        &data_client,
+        &peer_monitoring_client,
+        peer,
+        PeerMonitoringServiceRequest::Ping,
+    )
+    .await;
+    if result.is_ok() {
+        let latency = data_client
+            .time_service
+            .now()
+            .saturating_duration_since(ping_start_time);
+        data_client.update_peer_ping_latency(peer, latency);
+    }
+
+    // Fetch the depth of the peer from the validators
+    let result: Result<DepthFromValidatorsResponse> = send_peer_monitoring_request(
+        &data_client,
+        &peer_monitoring_client,
+        peer,
+        PeerMonitoringServiceRequest::GetDepthFromValidators,
+    )
+    .await;
+    if let Ok(response) = result {
+        data_client.update_peer_distance_from_validators(peer, response.depth);
+    }
+}
+
+/// Sends a peer monitoring request to the given peer and decodes the response.
+/// Errors are logged before being returned.
+async fn send_peer_monitoring_request<T>(
+    data_client: &AptosNetDataClient,
+    peer_monitoring_client: &PeerMonitoringServiceClient,
+    peer: PeerNetworkId,
+    request: PeerMonitoringServiceRequest,
+) -> Result<T>
+where
+    T: TryFrom<PeerMonitoringServiceResponse, Error = UnexpectedResponseError>,
+{
+    let result = peer_monitoring_client
+        .send_request(
+            peer,
+            request.clone(),
+            Duration::from_millis(data_client.data_client_config.response_timeout_ms),
+        )
+        .await
+        .map_err(|error| match error {
+            peer_monitoring_service_client::Error::RpcError(RpcError::TimedOut) => {
+                Error::TimeoutWaitingForResponse(error.to_string())
+            }
+            error => Error::UnexpectedErrorEncountered(error.to_string()),
+        })
+        .and_then(|response| {
+            T::try_from(response).map_err(|error| Error::InvalidResponse(error.to_string()))
+        });
+
+    if let Err(error) = &result {
+        sample!(
+            SampleRate::Duration(Duration::from_secs(POLLER_LOG_FREQ_SECS)),
+            warn!(
+                (LogSchema::new(LogEntry::PeerMonitoringRequest)
+                    .event(LogEvent::PeerPollingError)
+                    .message("Error encountered when monitoring peer!")
+                    .request_type(request.get_label())
+                    .error(error)
+                    .peer(&peer))
+            );
+        );
+    }
+    result
+}
+
 /// Logs the given poller error based on the logging frequency
 fn log_poller_error(error: Error) {
     sample!(
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    aptosnet::logging::{LogEntry, LogEvent, LogSchema},
+    aptosnet::{
+        logging::{LogEntry, LogEvent, LogSchema},
+        metrics,
+    },
     AdvertisedData, GlobalDataSummary, OptimalChunkSizes, ResponseError,
 };
 use aptos_config::{
@@ -10,6 +13,7 @@ use aptos_config::{
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use itertools::Itertools;
 use netcore::transport::ConnectionOrigin;
 use network::application::storage::PeerMetadataStorage;
@@ -17,6 +21,7 @@ use std::{
     cmp::min,
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::{Duration, Instant},
 };
 use storage_service_types::requests::StorageServiceRequest;
 use storage_service_types::responses::StorageServerSummary;
@@ -34,6 +39,24 @@ const MALICIOUS_MULTIPLIER: f64 = 0.8;
 /// Ignore a peer when their score dips below this threshold.
 const IGNORE_PEER_THRESHOLD: f64 = 25.0;
 
+/// The half-life (in seconds) with which all peer scores and measurements
+/// decay towards their starting (i.e., unmeasured) values.
+const SCORE_DECAY_HALF_LIFE_SECS: f64 = 300.0;
+/// The weight given to each new sample in the latency and throughput averages.
+const NEW_SAMPLE_WEIGHT: f64 = 0.2;
+/// Responses of at least this size are used to measure throughput (instead of latency).
+const MIN_THROUGHPUT_SAMPLE_BYTES: u64 = 64 * 1024;
+/// The latency assumed for unmeasured peers (and the latency at which
+/// the selection weight of a peer is halved).
+const REFERENCE_LATENCY_SECS: f64 = 0.5;
+/// The throughput assumed for unmeasured peers (and the throughput at
+/// which the selection weight of a peer is halved).
+const REFERENCE_THROUGHPUT_BYTES_PER_SEC: f64 = 1_000_000.0;
+/// The distance from the validators assumed for peers that haven't reported one.
+const DEFAULT_DISTANCE_FROM_VALIDATORS: u64 = 1;
+/// The selection weight of a peer is divided by (1 + distance * penalty).
+const DISTANCE_FROM_VALIDATORS_PENALTY: f64 = 0.5;
+
 pub(crate) enum ErrorType {
     /// A response or error that's not actively malicious but also doesn't help
     /// us make progress, e.g., timeouts, remote errors, invalid data, etc...
@@ -61,6 +84,17 @@ struct PeerState {
     storage_summary: Option<StorageServerSummary>,
     /// For now, a simplified port of the original state-sync v1 scoring system.
     score: f64,
+    /// A moving average of the request latency (in seconds), or `None` if we
+    /// haven't measured it yet.
+    latency_secs: Option<f64>,
+    /// A moving average of the response throughput (in bytes per second), or
+    /// `None` if we haven't measured it yet.
+    throughput: Option<f64>,
+    /// The distance of the peer from the validators, or `None` if the peer
+    /// hasn't reported it yet.
+    distance_from_validators: Option<u64>,
+    /// The time at which the score and measurements were last decayed.
+    last_decay_time: Option<Instant>,
 }
 
 impl Default for PeerState {
@@ -68,6 +102,10 @@ impl Default for PeerState {
         Self {
             storage_summary: None,
             score: STARTING_SCORE,
+            latency_secs: None,
+            throughput: None,
+            distance_from_validators: None,
+            last_decay_time: None,
         }
     }
 }
@@ -100,6 +138,85 @@ impl PeerState {
         };
         self.score = f64::max(self.score * multiplier, MIN_SCORE);
     }
+
+    /// Decays the score and measurements of the peer according to the time
+    /// elapsed since they were last decayed.
+    fn decay_scores(&mut self, now: Instant) {
+        let elapsed = self.elapsed_since_decay(now);
+        self.score = decay_towards(self.score, STARTING_SCORE, elapsed);
+        self.latency_secs = self
+            .latency_secs
+            .map(|latency| decay_towards(latency, REFERENCE_LATENCY_SECS, elapsed));
+        self.throughput = self.throughput.map(|throughput| {
+            decay_towards(throughput, REFERENCE_THROUGHPUT_BYTES_PER_SEC, elapsed)
+        });
+        self.last_decay_time = Some(now);
+    }
+
+    /// Returns the time elapsed since the peer was last decayed
+    fn elapsed_since_decay(&self, now: Instant) -> Duration {
+        self.last_decay_time
+            .map(|last_decay_time| now.saturating_duration_since(last_decay_time))
+            .unwrap_or_default()
+    }
+
+    /// Adds a new latency sample to the latency average of the peer
+    fn update_latency(&mut self, latency: Duration) {
+        self.latency_secs = Some(add_sample(self.latency_secs, latency.as_secs_f64()));
+    }
+
+    /// Adds a new throughput sample to the throughput average of the peer
+    fn update_throughput(&mut self, num_bytes: u64, duration: Duration) {
+        let throughput = num_bytes as f64 / duration.as_secs_f64().max(f64::EPSILON);
+        self.throughput = Some(add_sample(self.throughput, throughput));
+    }
+
+    /// Returns the (decayed) score, latency, throughput and distance of the
+    /// peer, using the starting values for everything we haven't measured yet.
+    fn current_scores(&self, now: Instant) -> (f64, f64, f64, u64) {
+        let elapsed = self.elapsed_since_decay(now);
+        let score = decay_towards(self.score, STARTING_SCORE, elapsed);
+        let latency_secs = self
+            .latency_secs
+            .map(|latency| decay_towards(latency, REFERENCE_LATENCY_SECS, elapsed))
+            .unwrap_or(REFERENCE_LATENCY_SECS);
+        let throughput = self
+            .throughput
+            .map(|throughput| {
+                decay_towards(throughput, REFERENCE_THROUGHPUT_BYTES_PER_SEC, elapsed)
+            })
+            .unwrap_or(REFERENCE_THROUGHPUT_BYTES_PER_SEC);
+        let distance = self
+            .distance_from_validators
+            .unwrap_or(DEFAULT_DISTANCE_FROM_VALIDATORS);
+        (score, latency_secs, throughput, distance)
+    }
+
+    /// Returns the relative weight with which the peer should be selected
+    /// to service a request. Reliable, fast peers that are close to the
+    /// validators have higher weights.
+    fn selection_weight(&self, now: Instant) -> f64 {
+        let (score, latency_secs, throughput, distance) = self.current_scores(now);
+        let score_factor = score / MAX_SCORE;
+        let latency_factor = REFERENCE_LATENCY_SECS / (REFERENCE_LATENCY_SECS + latency_secs);
+        let throughput_factor = throughput / (throughput + REFERENCE_THROUGHPUT_BYTES_PER_SEC);
+        let distance_factor = 1.0 / (1.0 + DISTANCE_FROM_VALIDATORS_PENALTY * distance as f64);
+        score_factor * latency_factor * throughput_factor * distance_factor
+    }
+}
+
+/// Decays the given value towards the target value, according to the elapsed time
+fn decay_towards(value: f64, target: f64, elapsed: Duration) -> f64 {
+    let decay_factor = 0.5_f64.powf(elapsed.as_secs_f64() / SCORE_DECAY_HALF_LIFE_SECS);
+    target + (value - target) * decay_factor
+}
+
+/// Adds a new sample to the given moving average (if one exists)
+fn add_sample(average: Option<f64>, sample: f64) -> f64 {
+    match average {
+        Some(average) => average + NEW_SAMPLE_WEIGHT * (sample - average),
+        None => sample,
+    }
 }
 
 /// Contains all of the unbanned peers' most recent [`StorageServerSummary`] data
@@ -112,7 +229,9 @@ pub(crate) struct PeerStates {
     peer_to_state: HashMap<PeerNetworkId, PeerState>,
     in_flight_priority_polls: HashSet<PeerNetworkId>, // The priority peers with in-flight polls
     in_flight_regular_polls: HashSet<PeerNetworkId>,  // The regular peers with in-flight polls
+    peers_with_score_metrics: HashSet<PeerNetworkId>, // The peers with score metrics set
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    time_service: TimeService, // The service used to decay the peer scores
 }
 
 impl PeerStates {
@@ -120,6 +239,7 @@ impl PeerStates {
         base_config: BaseConfig,
         storage_service_config: StorageServiceConfig,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
+        time_service: TimeService,
     ) -> Self {
         Self {
             base_config,
@@ -127,7 +247,9 @@ impl PeerStates {
             peer_to_state: HashMap::new(),
             in_flight_priority_polls: HashSet::new(),
             in_flight_regular_polls: HashSet::new(),
+            peers_with_score_metrics: HashSet::new(),
             peer_metadata_storage,
+            time_service,
         }
     }
 
@@ -156,12 +278,10 @@ impl PeerStates {
 
     /// Updates the score of the peer according to a successful operation
     pub fn update_score_success(&mut self, peer: PeerNetworkId) {
-        let old_score = self.peer_to_state.entry(peer).or_default().score;
-        self.peer_to_state
-            .entry(peer)
-            .or_default()
-            .update_score_success();
-        let new_score = self.peer_to_state.entry(peer).or_default().score;
+        let peer_state = self.decayed_peer_state(peer);
+        let old_score = peer_state.score;
+        peer_state.update_score_success();
+        let new_score = peer_state.score;
         if old_score <= IGNORE_PEER_THRESHOLD && new_score > IGNORE_PEER_THRESHOLD {
             info!(
                 (LogSchema::new(LogEntry::PeerStates)
@@ -174,12 +294,10 @@ impl PeerStates {
 
     /// Updates the score of the peer according to an error
     pub fn update_score_error(&mut self, peer: PeerNetworkId, error: ErrorType) {
-        let old_score = self.peer_to_state.entry(peer).or_default().score;
-        self.peer_to_state
-            .entry(peer)
-            .or_default()
-            .update_score_error(error);
-        let new_score = self.peer_to_state.entry(peer).or_default().score;
+        let peer_state = self.decayed_peer_state(peer);
+        let old_score = peer_state.score;
+        peer_state.update_score_error(error);
+        let new_score = peer_state.score;
         if old_score > IGNORE_PEER_THRESHOLD && new_score <= IGNORE_PEER_THRESHOLD {
             info!(
                 (LogSchema::new(LogEntry::PeerStates)
@@ -190,6 +308,86 @@ impl PeerStates {
         }
     }
 
+    /// Updates the latency or throughput of the peer using a successful
+    /// response of the given size, received after the given duration.
+    pub fn update_response_measurements(
+        &mut self,
+        peer: PeerNetworkId,
+        response_bytes: u64,
+        duration: Duration,
+    ) {
+        let peer_state = self.decayed_peer_state(peer);
+        if response_bytes >= MIN_THROUGHPUT_SAMPLE_BYTES {
+            peer_state.update_throughput(response_bytes, duration);
+        } else {
+            peer_state.update_latency(duration);
+        }
+    }
+
+    /// Updates the latency of the peer using a ping round-trip time
+    pub fn update_ping_latency(&mut self, peer: PeerNetworkId, latency: Duration) {
+        self.decayed_peer_state(peer).update_latency(latency);
+    }
+
+    /// Updates the distance of the peer from the validators
+    pub fn update_distance_from_validators(&mut self, peer: PeerNetworkId, distance: u64) {
+        self.peer_to_state
+            .entry(peer)
+            .or_default()
+            .distance_from_validators = Some(distance);
+    }
+
+    /// Returns the relative weight with which the peer should be selected
+    pub fn selection_weight(&self, peer: &PeerNetworkId) -> f64 {
+        let now = self.time_service.now();
+        match self.peer_to_state.get(peer) {
+            Some(peer_state) => peer_state.selection_weight(now),
+            None => PeerState::default().selection_weight(now),
+        }
+    }
+
+    /// Returns the state of the given peer, after decaying its score and measurements
+    fn decayed_peer_state(&mut self, peer: PeerNetworkId) -> &mut PeerState {
+        let now = self.time_service.now();
+        let peer_state = self.peer_to_state.entry(peer).or_default();
+        peer_state.decay_scores(now);
+        peer_state
+    }
+
+    /// Updates the score metrics of the connected peers, and removes the
+    /// metrics of the peers that disconnected since the last update (so that
+    /// the number of metric labels stays bounded).
+    pub fn update_peer_score_metrics(&mut self, connected_peers: &[PeerNetworkId]) {
+        let now = self.time_service.now();
+        let mut peers_with_score_metrics = HashSet::new();
+        for peer in connected_peers {
+            let peer_state = match self.peer_to_state.get(peer) {
+                Some(peer_state) => peer_state,
+                None => continue,
+            };
+            let (score, latency_secs, throughput, distance) = peer_state.current_scores(now);
+            let selection_weight = peer_state.selection_weight(now);
+            for (score_type, value) in [
+                (metrics::SCORE_LABEL, score),
+                (metrics::LATENCY_SECS_LABEL, latency_secs),
+                (metrics::THROUGHPUT_LABEL, throughput),
+                (metrics::DISTANCE_FROM_VALIDATORS_LABEL, distance as f64),
+                (metrics::SELECTION_WEIGHT_LABEL, selection_weight),
+            ] {
+                metrics::set_peer_score_gauge(peer, score_type, value);
+            }
+            peers_with_score_metrics.insert(*peer);
+        }
+
+        for peer in self
+            .peers_with_score_metrics
+            .difference(&peers_with_score_metrics)
+        {
+            metrics::remove_peer_score_gauges(peer);
+        }
+        self.peers_with_score_metrics = peers_with_score_metrics;
+    }
+
     /// Returns the number of in-flight priority polls
     pub fn num_in_flight_priority_polls(&self) -> u64 {
         self.in_flight_priority_polls.len() as u64
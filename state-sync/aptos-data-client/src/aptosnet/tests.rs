@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{AptosDataClient, AptosNetDataClient, DataSummaryPoller, Error};
-use crate::aptosnet::{poll_peer, state::calculate_optimal_chunk_sizes};
+use crate::aptosnet::{metrics, monitor_peer, poll_peer, state::calculate_optimal_chunk_sizes};
 use aptos_config::{
     config::{AptosDataClientConfig, BaseConfig, RoleType, StorageServiceConfig},
     network_id::{NetworkId, PeerNetworkId},
@@ -27,6 +27,14 @@ use network::{
     protocols::{network::NewNetworkSender, wire::handshake::v1::ProtocolId},
     transport::ConnectionMetadata,
 };
+use peer_monitoring_service_client::{
+    PeerMonitoringServiceClient, PeerMonitoringServiceNetworkSender,
+};
+use peer_monitoring_service_types::{
+    DepthFromValidatorsResponse, PeerMonitoringServiceMessage, PeerMonitoringServiceRequest,
+    PeerMonitoringServiceResponse, PingResponse,
+};
+use short_hex_str::AsShortHexStr;
 use std::{collections::hash_map::Entry, sync::Arc, time::Duration};
 use storage_service_client::{StorageServiceClient, StorageServiceNetworkSender};
 use storage_service_server::network::{NetworkRequest, ResponseSender};
@@ -116,6 +124,29 @@ impl MockNetwork {
         (mock_network, mock_time.into_mock(), client, poller)
     }
 
+    /// Creates a peer monitoring client (on the validator network), and
+    /// returns it with the receiver of the requests it sends.
+    fn create_peer_monitoring_client(
+        &self,
+    ) -> (
+        PeerMonitoringServiceClient,
+        aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+    ) {
+        let queue_cfg = aptos_channel::Config::new(10).queue_style(QueueStyle::FIFO);
+        let (peer_mgr_reqs_tx, peer_mgr_reqs_rx) = queue_cfg.build();
+        let (connection_reqs_tx, _connection_reqs_rx) = queue_cfg.build();
+
+        let network_sender = MultiNetworkSender::new(hashmap! {
+            NetworkId::Validator => PeerMonitoringServiceNetworkSender::new(
+                PeerManagerRequestSender::new(peer_mgr_reqs_tx),
+                ConnectionRequestSender::new(connection_reqs_tx),
+            )
+        });
+        let peer_monitoring_client =
+            PeerMonitoringServiceClient::new(network_sender, self.peer_infos.clone());
+        (peer_monitoring_client, peer_mgr_reqs_rx)
+    }
+
     /// Add a new peer to the network peer DB
     fn add_peer(&mut self, priority: bool) -> PeerNetworkId {
         // Get the network id
@@ -1345,6 +1376,190 @@ async fn bad_peer_is_eventually_added_back() {
         .contains(&CompleteDataRange::new(0, 200).unwrap()));
 }
 
+#[tokio::test]
+async fn weighted_peer_request_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+
+    // Add a nearby, fast peer and a distant, slow peer
+    let nearby_peer = mock_network.add_peer(true);
+    let distant_peer = mock_network.add_peer(true);
+    client.update_summary(nearby_peer, mock_storage_summary(100));
+    client.update_summary(distant_peer, mock_storage_summary(100));
+    client.update_peer_ping_latency(nearby_peer, Duration::from_millis(20));
+    client.update_peer_distance_from_validators(nearby_peer, 1);
+    client.update_peer_ping_latency(distant_peer, Duration::from_millis(800));
+    client.update_peer_distance_from_validators(distant_peer, 4);
+
+    // Verify the nearby peer is selected most of the time
+    let storage_request = StorageServiceRequest::new(
+        DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version: 100,
+            start_version: 0,
+            end_version: 100,
+            include_events: false,
+        }),
+        true,
+    );
+    let num_selections = 1000;
+    let mut num_nearby_selections = 0;
+    for _ in 0..num_selections {
+        let selected_peer = client.choose_peer_for_request(&storage_request).unwrap();
+        if selected_peer == nearby_peer {
+            num_nearby_selections += 1;
+        } else {
+            assert_eq!(selected_peer, distant_peer);
+        }
+    }
+    assert!(num_nearby_selections > num_selections * 3 / 4);
+
+    // Verify the distant peer is still selected if it's the only one serviceable
+    client.update_summary(distant_peer, mock_storage_summary(200));
+    let storage_request = StorageServiceRequest::new(
+        DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version: 200,
+            start_version: 150,
+            end_version: 200,
+            include_events: false,
+        }),
+        true,
+    );
+    assert_eq!(
+        client.choose_peer_for_request(&storage_request),
+        Ok(distant_peer)
+    );
+}
+
+#[tokio::test]
+async fn peer_scores_decay() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, mock_time, client, _) = MockNetwork::new(None, None, None);
+
+    // Add two peers and measure a high latency for one of them
+    let fast_peer = mock_network.add_peer(true);
+    let slow_peer = mock_network.add_peer(true);
+    client.update_peer_ping_latency(fast_peer, Duration::from_millis(500));
+    client.update_peer_ping_latency(slow_peer, Duration::from_secs(10));
+    let fast_weight = client.peer_states.read().selection_weight(&fast_peer);
+    let slow_weight = client.peer_states.read().selection_weight(&slow_peer);
+    assert!(slow_weight < fast_weight / 5.0);
+
+    // Elapse a long time and verify the measurements have decayed
+    mock_time.advance_secs(24 * 60 * 60);
+    let fast_weight = client.peer_states.read().selection_weight(&fast_peer);
+    let slow_weight = client.peer_states.read().selection_weight(&slow_peer);
+    assert!((fast_weight - slow_weight).abs() < 1e-6);
+
+    // Verify a new measurement takes effect again
+    client.update_peer_ping_latency(slow_peer, Duration::from_secs(10));
+    let slow_weight = client.peer_states.read().selection_weight(&slow_peer);
+    assert!(slow_weight < fast_weight);
+}
+
+#[tokio::test]
+async fn peer_score_metrics_of_disconnected_peers() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+
+    // Add two peers and update their score metrics
+    let connected_peer = mock_network.add_peer(true);
+    let disconnected_peer = mock_network.add_peer(true);
+    for peer in [connected_peer, disconnected_peer] {
+        client.update_peer_ping_latency(peer, Duration::from_millis(100));
+    }
+    client.update_peer_score_metrics();
+
+    // Disconnect one of the peers and verify its metrics are removed
+    mock_network.disconnect_peer(disconnected_peer);
+    client.update_peer_score_metrics();
+    let has_score_metric = |peer: PeerNetworkId| {
+        metrics::PEER_SCORES
+            .remove_label_values(&[
+                peer.network_id().as_str(),
+                peer.peer_id().short_str().as_str(),
+                metrics::SCORE_LABEL,
+            ])
+            .is_ok()
+    };
+    assert!(!has_score_metric(disconnected_peer));
+    assert!(has_score_metric(connected_peer));
+}
+
+#[tokio::test]
+async fn peer_monitoring_measurements() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, mock_time, client, _) = MockNetwork::new(None, None, None);
+    let (peer_monitoring_client, mut peer_monitoring_requests) =
+        mock_network.create_peer_monitoring_client();
+
+    // Add a nearby, fast peer and a distant, slow peer
+    let nearby_peer = mock_network.add_peer(true);
+    let distant_peer = mock_network.add_peer(true);
+
+    // Monitor both peers and respond to the pings and depth requests
+    for (peer, ping_latency, depth) in [
+        (nearby_peer, Duration::from_millis(10), 1),
+        (distant_peer, Duration::from_secs(5), 4),
+    ] {
+        let monitor = tokio::spawn(monitor_peer(
+            client.clone(),
+            peer_monitoring_client.clone(),
+            peer,
+        ));
+        respond_to_peer_monitoring_request(
+            &mut peer_monitoring_requests,
+            &mock_time,
+            PeerMonitoringServiceRequest::Ping,
+            ping_latency,
+            PeerMonitoringServiceResponse::Ping(PingResponse {}),
+        )
+        .await;
+        respond_to_peer_monitoring_request(
+            &mut peer_monitoring_requests,
+            &mock_time,
+            PeerMonitoringServiceRequest::GetDepthFromValidators,
+            Duration::from_millis(0),
+            PeerMonitoringServiceResponse::DepthFromValidators(DepthFromValidatorsResponse {
+                depth,
+            }),
+        )
+        .await;
+        monitor.await.unwrap();
+    }
+
+    // Verify the measurements were used to weight the peers
+    let nearby_weight = client.peer_states.read().selection_weight(&nearby_peer);
+    let distant_weight = client.peer_states.read().selection_weight(&distant_peer);
+    assert!(distant_weight < nearby_weight / 5.0);
+
+    // Verify an unexpected response doesn't update the measurements
+    let monitor = tokio::spawn(monitor_peer(
+        client.clone(),
+        peer_monitoring_client.clone(),
+        distant_peer,
+    ));
+    for request in [
+        PeerMonitoringServiceRequest::Ping,
+        PeerMonitoringServiceRequest::GetDepthFromValidators,
+    ] {
+        respond_to_peer_monitoring_request(
+            &mut peer_monitoring_requests,
+            &mock_time,
+            request,
+            Duration::from_millis(0),
+            PeerMonitoringServiceResponse::DepthFromValidators(DepthFromValidatorsResponse {
+                depth: 0,
+            }),
+        )
+        .await;
+    }
+    monitor.await.unwrap();
+    assert_eq!(
+        client.peer_states.read().selection_weight(&distant_peer),
+        distant_weight
+    );
+}
+
 #[tokio::test]
 async fn optimal_chunk_size_calculations() {
     // Create a test storage service config
@@ -1430,6 +1645,40 @@ fn fetch_peer_to_poll(
 }
 
 /// Fetches the number of in flight requests for peers depending on priority
+/// Waits for the next peer monitoring request, verifies it is the expected
+/// request, and responds with the given response after the given delay.
+async fn respond_to_peer_monitoring_request(
+    peer_monitoring_requests: &mut aptos_channel::Receiver<
+        (PeerId, ProtocolId),
+        PeerManagerRequest,
+    >,
+    mock_time: &MockTimeService,
+    expected_request: PeerMonitoringServiceRequest,
+    delay: Duration,
+    response: PeerMonitoringServiceResponse,
+) {
+    match peer_monitoring_requests.next().await {
+        Some(PeerManagerRequest::SendRpc(_, network_request)) => {
+            let message: PeerMonitoringServiceMessage =
+                bcs::from_bytes(network_request.data.as_ref()).unwrap();
+            match message {
+                PeerMonitoringServiceMessage::Request(request) => {
+                    assert_eq!(request, expected_request)
+                }
+                _ => panic!("unexpected: {:?}", message),
+            }
+
+            mock_time.advance(delay);
+            let response = PeerMonitoringServiceMessage::Response(Ok(response));
+            network_request
+                .res_tx
+                .send(Ok(bcs::to_bytes(&response).unwrap().into()))
+                .unwrap();
+        }
+        request => panic!("Unexpected peer monitoring request: {:?}", request),
+    }
+}
+
 fn get_num_in_flight_polls(client: AptosNetDataClient, is_priority_peer: bool) -> u64 {
     if is_priority_peer {
         client.peer_states.read().num_in_flight_priority_polls()
@@ -67,6 +67,17 @@ impl<
         }
     }
 
+    /// Stops the continuous syncer from writing to storage, so that the
+    /// consensus observer can take over execution. This terminates the
+    /// active stream, waits for any pending data to be stored and then
+    /// finishes the chunk executor.
+    pub async fn stop_for_consensus_observer(&mut self) -> Result<(), Error> {
+        self.reset_active_stream(None).await?;
+        utils::wait_for_storage_synchronizer_to_drain(&self.storage_synchronizer).await;
+        self.storage_synchronizer.finish_chunk_executor();
+        Ok(())
+    }
+
     /// Checks if the continuous syncer is able to make progress
     pub async fn drive_progress(
         &mut self,
@@ -17,9 +17,8 @@ use crate::{
     },
     storage_synchronizer::StorageSynchronizerInterface,
     utils,
-    utils::PENDING_DATA_LOG_FREQ_SECS,
 };
-use aptos_config::config::{RoleType, StateSyncDriverConfig};
+use aptos_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
 use aptos_data_client::AptosDataClient;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -33,9 +32,11 @@ use data_streaming_service::streaming_client::{
 use event_notifications::EventSubscriptionService;
 use futures::StreamExt;
 use mempool_notifications::MempoolNotificationSender;
-use std::{sync::Arc, time::SystemTime};
+use std::{
+    sync::Arc,
+    time::{Instant, SystemTime},
+};
 use storage_interface::DbReader;
-use tokio::task::yield_now;
 use tokio::time::{interval, Duration};
 use tokio_stream::wrappers::IntervalStream;
 
@@ -53,18 +54,75 @@ pub struct DriverConfiguration {
 
     // The trusted waypoint for the node
     pub waypoint: Waypoint,
+
+    // The config of the consensus observer (used by validator full nodes)
+    pub consensus_observer_config: ConsensusObserverConfig,
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        role: RoleType,
+        waypoint: Waypoint,
+        consensus_observer_config: ConsensusObserverConfig,
+    ) -> Self {
         Self {
             config,
             role,
             waypoint,
+            consensus_observer_config,
         }
     }
 }
 
+/// Tracks whether the consensus observer of a full node is executing, i.e.,
+/// whether it has notified state sync within the fallback duration.
+pub struct ConsensusObserverTracker {
+    // Whether this node is a full node running the consensus observer
+    enabled: bool,
+
+    // The time without notifications after which state sync takes over
+    fallback_duration: Duration,
+
+    // The time at which the last notification was handled for the consensus observer
+    last_notification_time: Option<Instant>,
+}
+
+impl ConsensusObserverTracker {
+    pub fn new(driver_configuration: &DriverConfiguration) -> Self {
+        let consensus_observer_config = driver_configuration.consensus_observer_config;
+        Self {
+            enabled: driver_configuration.role != RoleType::Validator
+                && consensus_observer_config.observer_enabled,
+            fallback_duration: Duration::from_millis(
+                consensus_observer_config.observer_fallback_duration_ms,
+            ),
+            last_notification_time: None,
+        }
+    }
+
+    /// Returns true iff this node is a full node running the consensus observer
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Records that the consensus observer has just notified state sync
+    pub fn notify_progress(&mut self) {
+        if self.enabled {
+            self.last_notification_time = Some(Instant::now());
+        }
+    }
+
+    /// Returns true iff the consensus observer has recently notified state sync,
+    /// i.e., it hasn't fallen back to state sync.
+    pub fn is_executing(&self) -> bool {
+        self.enabled
+            && self
+                .last_notification_time
+                .map_or(false, |time| time.elapsed() < self.fallback_duration)
+    }
+}
+
 /// The state sync driver that drives synchronization progress
 pub struct StateSyncDriver<
     DataClient,
@@ -103,6 +161,9 @@ pub struct StateSyncDriver<
     // The handler for notifications to mempool
     mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
 
+    // The tracker of the consensus observer's progress (on full nodes)
+    consensus_observer_tracker: ConsensusObserverTracker,
+
     // The timestamp at which the driver started executing
     start_time: Option<SystemTime>,
 
@@ -149,6 +210,7 @@ impl<
             storage.clone(),
             storage_synchronizer.clone(),
         );
+        let consensus_observer_tracker = ConsensusObserverTracker::new(&driver_configuration);
 
         Self {
             bootstrapper,
@@ -161,6 +223,7 @@ impl<
             error_notification_listener,
             event_subscription_service,
             mempool_notification_handler,
+            consensus_observer_tracker,
             start_time: None,
             storage,
             storage_synchronizer,
@@ -200,9 +263,12 @@ impl<
 
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
-        // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // Verify the notification: full nodes (that don't run the consensus observer)
+        // shouldn't receive notifications and consensus should only send notifications
+        // after bootstrapping!
+        let result = if self.driver_configuration.role == RoleType::FullNode
+            && !self.is_consensus_observer()
+        {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
@@ -239,6 +305,7 @@ impl<
         }
 
         // Handle the notification
+        self.consensus_observer_tracker.notify_progress();
         let result = match notification {
             ConsensusNotification::NotifyCommit(commit_notification) => {
                 self.handle_consensus_commit_notification(commit_notification)
@@ -348,6 +415,12 @@ impl<
             metrics::DRIVER_CONSENSUS_SYNC_NOTIFICATION,
         );
 
+        // The consensus observer takes over from the continuous syncer, so make
+        // sure the continuous syncer no longer writes to storage.
+        if self.is_consensus_observer() {
+            self.continuous_syncer.stop_for_consensus_observer().await?;
+        }
+
         // Initialize a new sync request
         let latest_synced_ledger_info =
             utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
@@ -462,15 +535,7 @@ impl<
 
         // Wait for the storage synchronizer to drain (if it hasn't already).
         // This prevents notifying consensus prematurely.
-        while self.storage_synchronizer.pending_storage_data() {
-            sample!(
-                SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
-                info!("Waiting for the storage synchronizer to handle pending data!")
-            );
-
-            // Yield to avoid starving the storage synchronizer threads.
-            yield_now().await;
-        }
+        utils::wait_for_storage_synchronizer_to_drain(&self.storage_synchronizer).await;
 
         // Refresh the latest synced ledger info and handle the sync request
         let latest_synced_ledger_info =
@@ -484,6 +549,7 @@ impl<
         if !self.active_sync_request() {
             self.continuous_syncer.reset_active_stream(None).await?;
             self.storage_synchronizer.finish_chunk_executor(); // Consensus is now in control
+            self.consensus_observer_tracker.notify_progress();
         }
         Ok(())
     }
@@ -498,9 +564,16 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
-    /// Returns true iff consensus is currently executing
+    /// Returns true iff this node is a full node running the consensus observer
+    fn is_consensus_observer(&self) -> bool {
+        self.consensus_observer_tracker.is_enabled()
+    }
+
+    /// Returns true iff consensus (or the consensus observer) is currently executing
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator() && self.bootstrapper.is_bootstrapped() && !self.active_sync_request()
+        (self.is_validator() || self.consensus_observer_tracker.is_executing())
+            && self.bootstrapper.is_bootstrapped()
+            && !self.active_sync_request()
     }
 
     /// Checks if the connection deadline has passed. If so, validators with
@@ -117,6 +117,7 @@ impl DriverFactory {
             node_config.state_sync.state_sync_driver,
             node_config.base.role,
             waypoint,
+            node_config.consensus_observer,
         );
 
         // Create the state sync driver
@@ -283,6 +283,76 @@ async fn test_data_stream_transaction_outputs() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_stop_for_consensus_observer() {
+    // Create test data
+    let current_synced_epoch = 7;
+    let current_synced_version = 1005;
+
+    // Create a driver configuration
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode =
+        ContinuousSyncingMode::ApplyTransactionOutputs;
+
+    // Create the mock streaming client
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let mut expectation_sequence = Sequence::new();
+    let (_notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let (_notification_sender_2, data_stream_listener_2) = create_data_stream_listener();
+    let data_stream_id_1 = data_stream_listener_1.data_stream_id;
+    for data_stream_listener in [data_stream_listener_1, data_stream_listener_2] {
+        mock_streaming_client
+            .expect_continuously_stream_transaction_outputs()
+            .times(1)
+            .with(
+                eq(current_synced_version),
+                eq(current_synced_epoch),
+                eq(None),
+            )
+            .return_once(move |_, _, _| Ok(data_stream_listener))
+            .in_sequence(&mut expectation_sequence);
+    }
+    mock_streaming_client
+        .expect_terminate_stream_with_feedback()
+        .times(1)
+        .with(eq(data_stream_id_1), eq(None))
+        .return_const(Ok(()));
+
+    // Create the continuous syncer
+    let mut continuous_syncer = create_continuous_syncer(
+        driver_configuration,
+        mock_streaming_client,
+        true,
+        current_synced_version,
+        current_synced_epoch,
+    );
+
+    // Drive progress to initialize the transaction output stream
+    let no_sync_request = Arc::new(Mutex::new(None));
+    continuous_syncer
+        .drive_progress(no_sync_request.clone())
+        .await
+        .unwrap();
+
+    // Stop the continuous syncer and verify the active stream is terminated
+    continuous_syncer
+        .stop_for_consensus_observer()
+        .await
+        .unwrap();
+
+    // Stopping again shouldn't terminate any more streams
+    continuous_syncer
+        .stop_for_consensus_observer()
+        .await
+        .unwrap();
+
+    // Drive progress and verify a new stream is initialized
+    continuous_syncer
+        .drive_progress(no_sync_request)
+        .await
+        .unwrap();
+}
+
 /// Creates a continuous syncer for testing
 fn create_continuous_syncer(
     driver_configuration: DriverConfiguration,
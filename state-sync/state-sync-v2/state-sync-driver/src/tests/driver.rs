@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    driver::ConsensusObserverTracker,
     driver_factory::DriverFactory,
     metadata_storage::PersistentMetadataStorage,
     tests::utils::{
-        create_event, create_ledger_info_at_version, create_transaction,
-        verify_mempool_and_event_notification,
+        create_event, create_full_node_driver_configuration, create_ledger_info_at_version,
+        create_transaction, verify_mempool_and_event_notification,
     },
 };
 use aptos_config::config::{NodeConfig, RoleType};
@@ -32,7 +33,7 @@ use executor_test_helpers::bootstrap_genesis;
 use futures::{FutureExt, StreamExt};
 use mempool_notifications::MempoolNotificationListener;
 use network::application::{interface::MultiNetworkSender, storage::PeerMetadataStorage};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, thread, time::Duration};
 use storage_interface::DbReaderWriter;
 use storage_service_client::StorageServiceClient;
 
@@ -181,6 +182,72 @@ async fn test_consensus_sync_request() {
     assert_err!(result);
 }
 
+#[tokio::test]
+async fn test_consensus_observer_notifications() {
+    // Create a driver for a full node running the consensus observer
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::FullNode;
+    node_config.consensus_observer.observer_enabled = true;
+    let (_full_node_driver, consensus_notifier, _, _, _) =
+        create_driver_for_tests(node_config, Waypoint::default(), None).await;
+
+    // Verify that the notifications are accepted, but fail because the node isn't bootstrapped
+    let error = consensus_notifier
+        .notify_new_commit(vec![create_transaction()], vec![])
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("BootstrapNotComplete"));
+    let error = consensus_notifier
+        .sync_to_target(create_ledger_info_at_version(0))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("BootstrapNotComplete"));
+}
+
+#[test]
+fn test_consensus_observer_tracker() {
+    // Create a driver configuration for a full node running the consensus observer
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration
+        .consensus_observer_config
+        .observer_enabled = true;
+    driver_configuration
+        .consensus_observer_config
+        .observer_fallback_duration_ms = 100;
+
+    // Verify the observer isn't executing until it notifies state sync
+    let mut tracker = ConsensusObserverTracker::new(&driver_configuration);
+    assert!(tracker.is_enabled());
+    assert!(!tracker.is_executing());
+    tracker.notify_progress();
+    assert!(tracker.is_executing());
+
+    // Verify the observer is no longer executing once the fallback duration has passed
+    thread::sleep(Duration::from_millis(200));
+    assert!(!tracker.is_executing());
+    tracker.notify_progress();
+    assert!(tracker.is_executing());
+
+    // Verify full nodes without the consensus observer never execute
+    driver_configuration
+        .consensus_observer_config
+        .observer_enabled = false;
+    let mut tracker = ConsensusObserverTracker::new(&driver_configuration);
+    tracker.notify_progress();
+    assert!(!tracker.is_enabled());
+    assert!(!tracker.is_executing());
+
+    // Verify validators never run the consensus observer
+    driver_configuration
+        .consensus_observer_config
+        .observer_enabled = true;
+    driver_configuration.role = RoleType::Validator;
+    let mut tracker = ConsensusObserverTracker::new(&driver_configuration);
+    tracker.notify_progress();
+    assert!(!tracker.is_enabled());
+    assert!(!tracker.is_executing());
+}
+
 /// Creates a state sync driver for a validator node
 async fn create_validator_driver(
     event_key_subscriptions: Option<Vec<EventKey>>,
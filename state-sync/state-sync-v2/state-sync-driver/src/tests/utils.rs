@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::driver::DriverConfiguration;
-use aptos_config::config::{RoleType, StateSyncDriverConfig};
+use aptos_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519Signature},
     HashValue, PrivateKey, Uniform,
@@ -72,6 +72,7 @@ pub fn create_full_node_driver_configuration() -> DriverConfiguration {
         config,
         role,
         waypoint,
+        consensus_observer_config: ConsensusObserverConfig::default(),
     }
 }
 
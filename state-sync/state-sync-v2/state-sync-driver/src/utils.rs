@@ -8,6 +8,7 @@ use crate::{
     notification_handlers::{
         CommitNotification, CommittedTransactions, MempoolNotificationHandler,
     },
+    storage_synchronizer::StorageSynchronizerInterface,
 };
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -26,7 +27,7 @@ use futures::StreamExt;
 use mempool_notifications::MempoolNotificationSender;
 use std::{sync::Arc, time::Duration};
 use storage_interface::DbReader;
-use tokio::time::timeout;
+use tokio::{task::yield_now, time::timeout};
 
 pub const PENDING_DATA_LOG_FREQ_SECS: u64 = 3;
 
@@ -157,6 +158,21 @@ pub async fn terminate_stream_with_feedback<StreamingClient: DataStreamingClient
         .map_err(|error| error.into())
 }
 
+/// Waits until the storage synchronizer has no more pending data
+pub async fn wait_for_storage_synchronizer_to_drain<StorageSyncer: StorageSynchronizerInterface>(
+    storage_synchronizer: &StorageSyncer,
+) {
+    while storage_synchronizer.pending_storage_data() {
+        sample!(
+            SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+            info!("Waiting for the storage synchronizer to handle pending data!")
+        );
+
+        // Yield to avoid starving the storage synchronizer threads.
+        yield_now().await;
+    }
+}
+
 /// Fetches the latest epoch state from the specified storage
 pub fn fetch_latest_epoch_state(storage: Arc<dyn DbReader>) -> Result<EpochState, Error> {
     storage.get_latest_epoch_state().map_err(|error| {
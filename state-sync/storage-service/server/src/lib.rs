@@ -18,7 +18,10 @@ use aptos_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValueChunkWithProof, StateValuesWithBatchProof},
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use bounded_executor::BoundedExecutor;
@@ -33,8 +36,9 @@ use std::{
 };
 use storage_interface::DbReader;
 use storage_service_types::requests::{
-    DataRequest, EpochEndingLedgerInfoRequest, StateValuesWithProofRequest, StorageServiceRequest,
-    TransactionOutputsWithProofRequest, TransactionsWithProofRequest,
+    DataRequest, EpochEndingLedgerInfoRequest, StateValuesWithBatchProofRequest,
+    StateValuesWithProofRequest, StorageServiceRequest, TransactionOutputsWithProofRequest,
+    TransactionsWithProofRequest,
 };
 use storage_service_types::responses::{
     CompleteDataRange, DataResponse, DataSummary, ProtocolMetadata, ServerProtocolVersion,
@@ -813,6 +817,9 @@ impl<T: StorageReaderInterface> Handler<T> {
             DataRequest::GetTransactionsWithProof(request) => {
                 self.get_transactions_with_proof(request)
             }
+            DataRequest::GetStateValuesWithBatchProof(request) => {
+                self.get_state_values_with_batch_proof(request)
+            }
             _ => unreachable!("Received an unexpected request: {:?}", request),
         }?;
         let storage_response = StorageServiceResponse::new(data_response, request.use_compression)?;
@@ -841,6 +848,21 @@ impl<T: StorageReaderInterface> Handler<T> {
         ))
     }
 
+    fn get_state_values_with_batch_proof(
+        &self,
+        request: &StateValuesWithBatchProofRequest,
+    ) -> Result<DataResponse, Error> {
+        let state_values_with_batch_proof = self.storage.get_state_values_with_batch_proof(
+            request.version,
+            request.proof_version,
+            &request.state_keys,
+        )?;
+
+        Ok(DataResponse::StateValuesWithBatchProof(
+            state_values_with_batch_proof,
+        ))
+    }
+
     fn get_epoch_ending_ledger_infos(
         &self,
         request: &EpochEndingLedgerInfoRequest,
@@ -956,6 +978,18 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         start_index: u64,
         end_index: u64,
     ) -> Result<StateValueChunkWithProof, Error>;
+
+    /// Returns the values of the given `state_keys` at the specified
+    /// `version`, ordered by the hash of the keys, with a single proof
+    /// relative to the `proof_version`. Unlike chunks, the values are
+    /// never split up, so the request fails if they don't fit into a
+    /// single network frame.
+    fn get_state_values_with_batch_proof(
+        &self,
+        version: u64,
+        proof_version: u64,
+        state_keys: &[StateKey],
+    ) -> Result<StateValuesWithBatchProof, Error>;
 }
 
 /// The underlying implementation of the StorageReaderInterface, used by the
@@ -1302,6 +1336,59 @@ impl StorageReaderInterface for StorageReader {
             version, start_index, end_index
         )))
     }
+
+    fn get_state_values_with_batch_proof(
+        &self,
+        version: u64,
+        proof_version: u64,
+        state_keys: &[StateKey],
+    ) -> Result<StateValuesWithBatchProof, Error> {
+        // Verify the request is within bounds
+        let num_state_keys = state_keys.len() as u64;
+        if num_state_keys == 0 || num_state_keys > self.config.max_state_chunk_size {
+            return Err(Error::InvalidRequest(format!(
+                "The number of state keys ({}) must be between 1 and {}!",
+                num_state_keys, self.config.max_state_chunk_size
+            )));
+        }
+        if version > proof_version {
+            return Err(Error::InvalidRequest(format!(
+                "The version ({}) must be <= the proof version ({})!",
+                version, proof_version
+            )));
+        }
+
+        // Fetch the values and the proofs
+        let (values, proof) = self
+            .storage
+            .get_state_values_with_batch_proof_by_version(state_keys, version)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let transaction_info_with_proof = self
+            .storage
+            .get_transaction_by_version(version, proof_version, false)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?
+            .proof;
+        let state_values_with_batch_proof =
+            StateValuesWithBatchProof::new(version, values, proof, transaction_info_with_proof);
+
+        // The values can't be divided up without losing the benefit of the batch proof
+        let (overflow_frame, num_bytes) = check_overflow_network_frame(
+            &state_values_with_batch_proof,
+            self.config.max_network_chunk_bytes,
+        )?;
+        if overflow_frame {
+            increment_network_frame_overflow(
+                DataResponse::StateValuesWithBatchProof(state_values_with_batch_proof).get_label(),
+            );
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Unable to serve the get_state_values_with_batch_proof request! Version: {:?}, \
+                number of state keys: {:?}, num bytes: {:?}. The data cannot fit into a single \
+                network frame!",
+                version, num_state_keys, num_bytes
+            )));
+        }
+        Ok(state_values_with_batch_proof)
+    }
 }
 
 /// Serializes the given data and returns true iff the data will overflow
@@ -20,13 +20,14 @@ use aptos_types::{
     event::EventKey,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proof::{
-        AccumulatorConsistencyProof, SparseMerkleProof, SparseMerkleRangeProof,
-        TransactionAccumulatorSummary,
+        AccumulatorConsistencyProof, SparseMerkleBatchProof, SparseMerkleProof,
+        SparseMerkleRangeProof, TransactionAccumulatorProof, TransactionAccumulatorSummary,
+        TransactionInfoWithProof,
     },
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
-        state_value::{StateValue, StateValueChunkWithProof},
+        state_value::{StateValue, StateValueChunkWithProof, StateValuesWithBatchProof},
     },
     transaction::{
         AccountTransactionsWithProof, ExecutionStatus, RawTransaction, Script, SignedTransaction,
@@ -57,8 +58,9 @@ use storage_interface::{DbReader, ExecutedTrees, Order};
 use storage_service_types::{
     requests::{
         DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
-        NewTransactionsWithProofRequest, StateValuesWithProofRequest, StorageServiceRequest,
-        TransactionOutputsWithProofRequest, TransactionsWithProofRequest,
+        NewTransactionsWithProofRequest, StateValuesWithBatchProofRequest,
+        StateValuesWithProofRequest, StorageServiceRequest, TransactionOutputsWithProofRequest,
+        TransactionsWithProofRequest,
     },
     responses::{
         CompleteDataRange, DataResponse, DataSummary, ProtocolMetadata, ServerProtocolVersion,
@@ -452,6 +454,110 @@ async fn test_get_states_with_proof_invalid() {
     }
 }
 
+#[tokio::test]
+async fn test_get_state_values_with_batch_proof() {
+    // Create test data
+    let version = 101;
+    let proof_version = 200;
+    let values: Vec<_> = (0..10u64)
+        .map(|index| {
+            let state_key = StateKey::Raw(index.to_le_bytes().to_vec());
+            (state_key, Some(StateValue::new(vec![index as u8])))
+        })
+        .collect();
+    let state_keys: Vec<_> = values
+        .iter()
+        .map(|(state_key, _)| state_key.clone())
+        .collect();
+    let proof = SparseMerkleBatchProof::new(vec![], vec![HashValue::random()]);
+    let transaction_with_proof = TransactionWithProof::new(
+        version,
+        create_test_transaction(0, vec![]),
+        None,
+        TransactionInfoWithProof::new(
+            TransactionAccumulatorProof::new(vec![]),
+            TransactionInfo::new(
+                HashValue::random(),
+                HashValue::random(),
+                HashValue::random(),
+                Some(HashValue::random()),
+                0,
+                ExecutionStatus::Success,
+            ),
+        ),
+    );
+
+    // Create the mock db reader
+    let mut db_reader = create_mock_db_reader();
+    let values_clone = values.clone();
+    let proof_clone = proof.clone();
+    db_reader
+        .expect_get_state_values_with_batch_proof_by_version()
+        .times(1)
+        .with(always(), eq(version))
+        .returning(move |_, _| Ok((values_clone.clone(), proof_clone.clone())));
+    let transaction_with_proof_clone = transaction_with_proof.clone();
+    db_reader
+        .expect_get_transaction_by_version()
+        .times(1)
+        .with(eq(version), eq(proof_version), eq(false))
+        .returning(move |_, _, _| Ok(transaction_with_proof_clone.clone()));
+
+    // Create the storage client and server
+    let (mut mock_client, service, _) = MockClient::new(Some(db_reader), None);
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the state values with a batch proof
+    let data_request =
+        DataRequest::GetStateValuesWithBatchProof(StateValuesWithBatchProofRequest {
+            version,
+            proof_version,
+            state_keys,
+        });
+    let storage_request = StorageServiceRequest::new(data_request, false);
+    let response = mock_client.process_request(storage_request).await.unwrap();
+
+    // Verify the response is correct
+    assert_matches!(response, StorageServiceResponse::RawResponse(_));
+    assert_eq!(
+        response.get_data_response().unwrap(),
+        DataResponse::StateValuesWithBatchProof(StateValuesWithBatchProof::new(
+            version,
+            values,
+            proof,
+            transaction_with_proof.proof
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_get_state_values_with_batch_proof_invalid() {
+    // Create the storage client and server
+    let (mut mock_client, service, _) = MockClient::new(None, None);
+    tokio::spawn(service.start());
+
+    // Test no state keys, too many state keys and a version above the proof version
+    let max_state_chunk_size = StorageServiceConfig::default().max_state_chunk_size;
+    for (version, num_state_keys) in [(0, 0), (0, max_state_chunk_size + 1), (201, 1)] {
+        let data_request =
+            DataRequest::GetStateValuesWithBatchProof(StateValuesWithBatchProofRequest {
+                version,
+                proof_version: 200,
+                state_keys: (0..num_state_keys)
+                    .map(|index| StateKey::Raw(index.to_le_bytes().to_vec()))
+                    .collect(),
+            });
+        let storage_request = StorageServiceRequest::new(data_request, false);
+
+        // Process and verify the response
+        let response = mock_client
+            .process_request(storage_request)
+            .await
+            .unwrap_err();
+        assert_matches!(response, StorageServiceError::InvalidRequest(_));
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_get_new_transactions() {
     // Test small and large chunk sizes
@@ -2295,6 +2401,12 @@ mock! {
             chunk_size: usize,
         ) -> Result<StateValueChunkWithProof>;
 
+        fn get_state_values_with_batch_proof_by_version(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> Result<(Vec<(StateKey, Option<StateValue>)>, SparseMerkleBatchProof)>;
+
         fn get_epoch_snapshot_prune_window(&self) -> Result<usize>;
 
         fn is_state_pruner_enabled(&self) -> Result<bool>;
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::COMPRESSION_SUFFIX_LABEL;
-use aptos_types::transaction::Version;
+use aptos_types::{state_store::state_key::StateKey, transaction::Version};
 use serde::{Deserialize, Serialize};
 
 /// A storage service request.
@@ -42,6 +42,7 @@ pub enum DataRequest {
     GetStorageServerSummary,             // Fetches a summary of the storage server state
     GetTransactionOutputsWithProof(TransactionOutputsWithProofRequest), // Fetches a list of transaction outputs with a proof
     GetTransactionsWithProof(TransactionsWithProofRequest), // Fetches a list of transactions with a proof
+    GetStateValuesWithBatchProof(StateValuesWithBatchProofRequest), // Fetches the values of a list of state keys with a single proof
 }
 
 impl DataRequest {
@@ -57,6 +58,7 @@ impl DataRequest {
             Self::GetStorageServerSummary => "get_storage_server_summary",
             Self::GetTransactionOutputsWithProof(_) => "get_transaction_outputs_with_proof",
             Self::GetTransactionsWithProof(_) => "get_transactions_with_proof",
+            Self::GetStateValuesWithBatchProof(_) => "get_state_values_with_batch_proof",
         }
     }
 
@@ -107,6 +109,15 @@ pub struct StateValuesWithProofRequest {
     pub end_index: u64,   // The index to stop fetching state values (inclusive)
 }
 
+/// A storage service request for fetching the values of a list of state keys
+/// at a specified version, with a single proof for all of them.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct StateValuesWithBatchProofRequest {
+    pub version: u64,              // The version to fetch the state values at
+    pub proof_version: u64,        // The version the proof should be relative to
+    pub state_keys: Vec<StateKey>, // The state keys to fetch the values of
+}
+
 /// A storage service request for fetching a transaction output list with a
 /// corresponding proof.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
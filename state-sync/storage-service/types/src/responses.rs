@@ -3,8 +3,9 @@
 
 use crate::requests::DataRequest::{
     GetEpochEndingLedgerInfos, GetNewTransactionOutputsWithProof, GetNewTransactionsWithProof,
-    GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValuesWithProof,
-    GetStorageServerSummary, GetTransactionOutputsWithProof, GetTransactionsWithProof,
+    GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValuesWithBatchProof,
+    GetStateValuesWithProof, GetStorageServerSummary, GetTransactionOutputsWithProof,
+    GetTransactionsWithProof,
 };
 use crate::responses::Error::DegenerateRangeError;
 use crate::{Epoch, StorageServiceRequest, COMPRESSION_SUFFIX_LABEL};
@@ -13,7 +14,7 @@ use aptos_compression::{CompressedData, CompressionError};
 use aptos_config::config::{StorageServiceConfig, MAX_APPLICATION_MESSAGE_SIZE};
 use aptos_types::epoch_change::EpochChangeProof;
 use aptos_types::ledger_info::LedgerInfoWithSignatures;
-use aptos_types::state_store::state_value::StateValueChunkWithProof;
+use aptos_types::state_store::state_value::{StateValueChunkWithProof, StateValuesWithBatchProof};
 use aptos_types::transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version};
 use num_traits::{PrimInt, Zero};
 #[cfg(test)]
@@ -119,6 +120,7 @@ pub enum DataResponse {
     StorageServerSummary(StorageServerSummary),
     TransactionOutputsWithProof(TransactionOutputListWithProof),
     TransactionsWithProof(TransactionListWithProof),
+    StateValuesWithBatchProof(StateValuesWithBatchProof),
 }
 
 impl DataResponse {
@@ -134,6 +136,7 @@ impl DataResponse {
             Self::StorageServerSummary(_) => "storage_server_summary",
             Self::TransactionOutputsWithProof(_) => "transaction_outputs_with_proof",
             Self::TransactionsWithProof(_) => "transactions_with_proof",
+            Self::StateValuesWithBatchProof(_) => "state_values_with_batch_proof",
         }
     }
 }
@@ -170,6 +173,20 @@ impl TryFrom<StorageServiceResponse> for StateValueChunkWithProof {
     }
 }
 
+impl TryFrom<StorageServiceResponse> for StateValuesWithBatchProof {
+    type Error = crate::responses::Error;
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::StateValuesWithBatchProof(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected state_values_with_batch_proof, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
 impl TryFrom<StorageServiceResponse> for EpochChangeProof {
     type Error = crate::responses::Error;
     fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
@@ -334,6 +351,10 @@ impl ProtocolMetadata {
                     .len()
                     .map_or(false, |chunk_size| self.max_state_chunk_size >= chunk_size)
             }),
+            GetStateValuesWithBatchProof(request) => {
+                !request.state_keys.is_empty()
+                    && self.max_state_chunk_size >= request.state_keys.len() as u64
+            }
             GetEpochEndingLedgerInfos(request) => CompleteDataRange::new(
                 request.start_epoch,
                 request.expected_end_epoch,
@@ -441,6 +462,21 @@ impl DataSummary {
 
                 can_serve_states && can_create_proof
             }
+            GetStateValuesWithBatchProof(request) => {
+                let can_serve_states = self
+                    .states
+                    .map(|range| range.contains(request.version))
+                    .unwrap_or(false);
+
+                let can_create_proof = request.version <= request.proof_version
+                    && self
+                        .synced_ledger_info
+                        .as_ref()
+                        .map(|li| li.ledger_info().version() >= request.proof_version)
+                        .unwrap_or(false);
+
+                can_serve_states && can_create_proof
+            }
             GetTransactionOutputsWithProof(request) => {
                 let desired_range =
                     match CompleteDataRange::new(request.start_version, request.end_version) {
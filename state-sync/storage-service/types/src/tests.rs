@@ -3,8 +3,9 @@
 
 use crate::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, StateValuesWithProofRequest,
-        TransactionOutputsWithProofRequest, TransactionsWithProofRequest,
+        DataRequest, EpochEndingLedgerInfoRequest, StateValuesWithBatchProofRequest,
+        StateValuesWithProofRequest, TransactionOutputsWithProofRequest,
+        TransactionsWithProofRequest,
     },
     responses::{CompleteDataRange, DataSummary, ProtocolMetadata},
     Epoch, StorageServiceRequest,
@@ -14,6 +15,7 @@ use aptos_types::{
     aggregate_signature::AggregateSignature,
     block_info::BlockInfo,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    state_store::state_key::StateKey,
     transaction::Version,
 };
 use claims::{assert_err, assert_ok};
@@ -160,6 +162,28 @@ fn test_data_summary_can_service_state_chunk_request() {
     }
 }
 
+#[test]
+fn test_data_summary_can_service_state_batch_request() {
+    let summary = DataSummary {
+        synced_ledger_info: Some(create_mock_ledger_info(250)),
+        states: Some(create_range(100, 300)),
+        ..Default::default()
+    };
+
+    for compression in [true, false] {
+        // in range and can provide proof => can service
+        assert!(summary.can_service(&state_batch_request(100, 250, 10, compression)));
+        assert!(summary.can_service(&state_batch_request(250, 250, 10, compression)));
+
+        // in range, but cannot provide proof => cannot service
+        assert!(!summary.can_service(&state_batch_request(200, 251, 10, compression)));
+        assert!(!summary.can_service(&state_batch_request(200, 150, 10, compression)));
+
+        // can provide proof, but out of range ==> cannot service
+        assert!(!summary.can_service(&state_batch_request(99, 250, 10, compression)));
+    }
+}
+
 #[test]
 fn test_protocol_metadata_can_service() {
     let metadata = ProtocolMetadata {
@@ -181,6 +205,10 @@ fn test_protocol_metadata_can_service() {
 
         assert!(metadata.can_service(&state_values_request(200, 100, 199, compression)));
         assert!(!metadata.can_service(&state_values_request(200, 100, 200, compression)));
+
+        assert!(metadata.can_service(&state_batch_request(200, 200, 100, compression)));
+        assert!(!metadata.can_service(&state_batch_request(200, 200, 101, compression)));
+        assert!(!metadata.can_service(&state_batch_request(200, 200, 0, compression)));
     }
 }
 
@@ -260,6 +288,23 @@ fn state_values_request(
     StorageServiceRequest::new(data_request, use_compression)
 }
 
+fn state_batch_request(
+    version: Version,
+    proof_version: Version,
+    num_state_keys: usize,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request =
+        DataRequest::GetStateValuesWithBatchProof(StateValuesWithBatchProofRequest {
+            version,
+            proof_version,
+            state_keys: (0..num_state_keys)
+                .map(|index| StateKey::Raw(index.to_le_bytes().to_vec()))
+                .collect(),
+        });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
 fn states_request(version: Version, use_compression: bool) -> StorageServiceRequest {
     state_values_request(version, 0, 1000, use_compression)
 }
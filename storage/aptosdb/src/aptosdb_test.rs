@@ -7,7 +7,9 @@ use crate::{
         ledger_pruner_manager::LedgerPrunerManager, state_pruner_manager::StatePrunerManager,
     },
     test_helper,
-    test_helper::{arb_blocks_to_commit, put_as_state_root, put_transaction_info},
+    test_helper::{
+        arb_blocks_to_commit, put_as_state_root, put_transaction_info, update_in_memory_state,
+    },
     AptosDB, PrunerManager, StaleNodeIndexSchema,
 };
 use aptos_config::config::{
@@ -22,13 +24,16 @@ use aptos_types::state_store::state_storage_usage::StateStorageUsage;
 use aptos_types::transaction::{TransactionToCommit, Version};
 use aptos_types::{
     proof::SparseMerkleLeafNode,
-    state_store::{state_key::StateKey, state_value::StateValue},
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChange},
+    },
     transaction::{ExecutionStatus, TransactionInfo},
 };
 use proptest::prelude::*;
 use std::collections::HashSet;
 use std::sync::Arc;
-use storage_interface::{DbReader, DbWriter, ExecutedTrees, Order};
+use storage_interface::{DbReader, DbWriter, Error as StorageError, ExecutedTrees, Order};
 use test_helper::{test_save_blocks_impl, test_sync_transactions_impl};
 
 proptest! {
@@ -45,6 +50,164 @@ proptest! {
     }
 }
 
+fn test_get_state_value_changes_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_buffered_state_target_items(&tmp_dir, 10);
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit);
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = cur_ver - 1;
+    let state_keys: HashSet<StateKey> = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit)
+        .flat_map(|txn_to_commit| txn_to_commit.state_updates().keys().cloned())
+        .collect();
+
+    for (from_version, to_version) in [
+        (0, latest_version),
+        (latest_version / 2, latest_version),
+        (latest_version, latest_version),
+    ] {
+        let mut expected: Vec<_> = state_keys
+            .iter()
+            .filter_map(|state_key| {
+                let old_value = db
+                    .get_state_value_by_version(state_key, from_version)
+                    .unwrap();
+                let new_value = db
+                    .get_state_value_by_version(state_key, to_version)
+                    .unwrap();
+                (old_value != new_value).then(|| StateValueChange {
+                    state_key: state_key.clone(),
+                    old_value,
+                    new_value,
+                })
+            })
+            .collect();
+        expected.sort_by_key(|change| change.state_key.hash());
+
+        // Read every write set, then diff the state snapshots wherever possible. Ranges that
+        // are too long to read and can't be diffed are refused.
+        'diff: for max_write_sets_to_diff in [u64::MAX, 0] {
+            let mut changes = vec![];
+            let mut start = None;
+            loop {
+                let result = db.get_state_value_changes_impl(
+                    from_version,
+                    to_version,
+                    start.as_ref(),
+                    3, /* limit */
+                    max_write_sets_to_diff,
+                );
+                let (page, next_key) = match result {
+                    Ok(page) => page,
+                    Err(err) => {
+                        assert!(matches!(
+                            err.downcast_ref(),
+                            Some(StorageError::VersionRangeTooLong(..))
+                        ));
+                        assert!(start.is_none() && to_version > from_version);
+                        continue 'diff;
+                    }
+                };
+                assert!(page.len() <= 3);
+                changes.extend(page);
+                match next_key {
+                    Some(next_key) => start = Some(next_key),
+                    None => break,
+                }
+            }
+            assert_eq!(changes, expected);
+        }
+    }
+}
+
+fn test_get_state_values_with_batch_proof_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_buffered_state_target_items(&tmp_dir, 10);
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit);
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let (snapshot_version, root_hash) = db.get_state_snapshot_before(cur_ver).unwrap().unwrap();
+
+    // Keys written in any transaction, some of which may not exist at the snapshot.
+    let state_keys: Vec<StateKey> = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit)
+        .flat_map(|txn_to_commit| txn_to_commit.state_updates().keys().cloned())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let (values, proof) = db
+        .get_state_values_with_batch_proof_by_version(&state_keys, snapshot_version)
+        .unwrap();
+    assert_eq!(values.len(), state_keys.len());
+    for (state_key, value) in &values {
+        assert_eq!(
+            *value,
+            db.get_state_value_by_version(state_key, snapshot_version)
+                .unwrap()
+        );
+    }
+    let elements: Vec<_> = values
+        .iter()
+        .map(|(state_key, value)| (state_key.hash(), value.as_ref()))
+        .collect();
+    proof.verify(root_hash, &elements).unwrap();
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_get_state_value_changes(input in arb_blocks_to_commit()) {
+        test_get_state_value_changes_impl(input);
+    }
+
+    #[test]
+    fn test_get_state_values_with_batch_proof(input in arb_blocks_to_commit()) {
+        test_get_state_values_with_batch_proof_impl(input);
+    }
+}
+
 #[test]
 fn test_get_first_seq_num_and_limit() {
     assert!(get_first_seq_num_and_limit(Order::Ascending, 0, 0).is_err());
@@ -0,0 +1,467 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module rolls an AptosDB back to a target version, deleting everything committed after it.
+//!
+//! The state merkle DB is truncated before the ledger DB, and the ledger DB is truncated from the
+//! latest version downwards, so that a truncation can be interrupted at any point and rerun with
+//! the same target. Until it completes, the target is recorded in the ledger DB and
+//! [`AptosDB::open`](crate::AptosDB::open) refuses to open the DB for writing.
+//!
+//! If there's an indexer DB, it is rolled back to the target version as well, so that the
+//! transactions committed again after the target version are indexed again.
+
+use crate::{
+    db_options::{gen_ledger_cfds, gen_state_merkle_cfds},
+    event_store::event_type_hash,
+    pruner::state_store::generics::StaleNodeIndexSchemaTrait,
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        epoch_by_version::EpochByVersionSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema,
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        ledger_info::LedgerInfoSchema,
+        stale_node_index::StaleNodeIndexSchema,
+        stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
+        stale_state_value_index::StaleStateValueIndexSchema,
+        state_value::StateValueSchema,
+        transaction::TransactionSchema,
+        transaction_accumulator::TransactionAccumulatorSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+        transaction_info::TransactionInfoSchema,
+        version_data::VersionDataSchema,
+        write_set::WriteSetSchema,
+    },
+    LEDGER_DB_NAME, STATE_MERKLE_DB_NAME,
+};
+use anyhow::{bail, ensure, Context, Result};
+use aptos_config::config::{RocksdbConfig, RocksdbConfigs};
+use aptos_crypto::hash::CryptoHash;
+use aptos_jellyfish_merkle::{node_type::NodeKey, StaleNodeIndex};
+use aptos_logger::info;
+use aptos_rocksdb_options::gen_rocksdb_options;
+use aptos_types::{
+    proof::position::Position,
+    transaction::{Transaction, Version},
+};
+use aptosdb_indexer::{Indexer, INDEX_DB_NAME};
+use schemadb::{schema::KeyCodec, Options, ReadOptions, SchemaBatch, DB};
+use std::{path::Path, sync::Arc};
+
+/// Generates the options to open an existing DB with, the truncater must not create a DB at a
+/// wrong path.
+fn gen_existing_db_options(config: &RocksdbConfig) -> Options {
+    let mut db_opts = gen_rocksdb_options(config, false);
+    db_opts.create_if_missing(false);
+    db_opts
+}
+
+/// Fails if a truncation of the DB was started but didn't complete.
+pub(crate) fn ensure_no_pending_truncation(ledger_db: &DB) -> Result<()> {
+    if let Some(target_version) = get_pending_truncation(ledger_db)? {
+        bail!(
+            "Truncation of the DB to version {} didn't complete, rerun it before opening the DB.",
+            target_version
+        );
+    }
+    Ok(())
+}
+
+fn get_pending_truncation(ledger_db: &DB) -> Result<Option<Version>> {
+    Ok(ledger_db
+        .get::<DbMetadataSchema>(&DbMetadataKey::TruncationTarget)?
+        .map(|v| v.expect_version()))
+}
+
+/// Returns the positions of the frozen accumulator nodes whose rightmost leaf is `leaf_index`,
+/// i.e. the nodes added to the accumulator together with that leaf.
+fn positions_frozen_by_leaf(leaf_index: u64) -> impl Iterator<Item = Position> {
+    let leaf = Position::from_leaf_index(leaf_index);
+    std::iter::once(leaf).chain(
+        leaf.iter_ancestor()
+            .skip(1)
+            .zip(leaf.iter_ancestor())
+            .take_while(|(_parent, child)| child.is_right_child())
+            .map(|(parent, _child)| parent),
+    )
+}
+
+/// `DbTruncater` deletes the data committed after a target version.
+pub struct DbTruncater {
+    ledger_db: Arc<DB>,
+    state_merkle_db: Arc<DB>,
+    indexer: Option<Indexer>,
+}
+
+impl DbTruncater {
+    /// Opens the existing DB exclusively. This fails while a node is running on the DB, as RocksDB
+    /// allows a single process to open a DB for writing.
+    pub fn open<P: AsRef<Path>>(db_root_path: P, rocksdb_configs: RocksdbConfigs) -> Result<Self> {
+        let ledger_db_path = db_root_path.as_ref().join(LEDGER_DB_NAME);
+        let state_merkle_db_path = db_root_path.as_ref().join(STATE_MERKLE_DB_NAME);
+        // RocksDB creates the directory of a DB it opens, even if it doesn't create the DB.
+        for db_path in [&ledger_db_path, &state_merkle_db_path] {
+            ensure!(db_path.exists(), "DB not found at {:?}.", db_path);
+        }
+
+        let ledger_db = DB::open_cf(
+            &gen_existing_db_options(&rocksdb_configs.ledger_db_config),
+            ledger_db_path,
+            LEDGER_DB_NAME,
+            gen_ledger_cfds(&rocksdb_configs.ledger_db_config),
+        )
+        .context("Failed to open the ledger DB, make sure no node is running on it")?;
+        let state_merkle_db = DB::open_cf(
+            &gen_existing_db_options(&rocksdb_configs.state_merkle_db_config),
+            state_merkle_db_path,
+            STATE_MERKLE_DB_NAME,
+            gen_state_merkle_cfds(&rocksdb_configs.state_merkle_db_config),
+        )
+        .context("Failed to open the state merkle DB, make sure no node is running on it")?;
+        let indexer = if db_root_path.as_ref().join(INDEX_DB_NAME).exists() {
+            Some(
+                Indexer::open(&db_root_path, rocksdb_configs.index_db_config)
+                    .context("Failed to open the indexer DB")?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            ledger_db: Arc::new(ledger_db),
+            state_merkle_db: Arc::new(state_merkle_db),
+            indexer,
+        })
+    }
+
+    /// Deletes everything committed after `target_version`, writing at most `batch_size` versions
+    /// of ledger data or `batch_size` state merkle nodes at a time.
+    ///
+    /// Fails without changing anything if the DB can't be rolled back to `target_version`, e.g.
+    /// because the ledger or the state snapshot the node restarts from is pruned.
+    pub fn truncate(&self, target_version: Version, batch_size: usize) -> Result<()> {
+        ensure!(batch_size > 0, "Batch size must be positive.");
+        if let Some(pending_version) = get_pending_truncation(&self.ledger_db)? {
+            ensure!(
+                target_version <= pending_version,
+                "Truncation to version {} didn't complete, data above it may be deleted already. \
+                 Rerun it with a target version no greater than {}.",
+                pending_version,
+                pending_version,
+            );
+        }
+        self.ensure_truncatable(target_version)?;
+
+        self.ledger_db.put::<DbMetadataSchema>(
+            &DbMetadataKey::TruncationTarget,
+            &DbMetadataValue::Version(target_version),
+        )?;
+        self.truncate_state_merkle_db(target_version, batch_size)?;
+        self.truncate_ledger_db(target_version, batch_size as u64)?;
+        if let Some(indexer) = &self.indexer {
+            indexer.truncate(target_version)?;
+        }
+        self.reset_metadata(target_version)?;
+        info!(target_version = target_version, "Truncated AptosDB.");
+        Ok(())
+    }
+
+    fn ensure_truncatable(&self, target_version: Version) -> Result<()> {
+        let ledger_min_readable_version =
+            self.get_progress(&self.ledger_db, DbMetadataKey::LedgerPrunerProgress)?;
+        ensure!(
+            target_version >= ledger_min_readable_version,
+            "Version {} is pruned, min readable version is {}.",
+            target_version,
+            ledger_min_readable_version,
+        );
+
+        // After the truncation, the node replays the write sets after the latest state snapshot
+        // at or before the target version, which must both be intact.
+        let state_min_readable_version = self.get_progress(
+            &self.state_merkle_db,
+            DbMetadataKey::StateMerklePrunerProgress,
+        )?;
+        let epoch_snapshot_min_readable_version = self.get_progress(
+            &self.state_merkle_db,
+            DbMetadataKey::EpochEndingStateMerklePrunerProgress,
+        )?;
+        match self.get_state_snapshot_version_at_or_before(target_version)? {
+            Some(snapshot_version) => {
+                ensure!(
+                    snapshot_version >= state_min_readable_version
+                        || (snapshot_version >= epoch_snapshot_min_readable_version
+                            && self
+                                .ledger_db
+                                .get::<EpochByVersionSchema>(&snapshot_version)?
+                                .is_some()),
+                    "State snapshot at version {}, the latest one at or before version {}, is \
+                     pruned.",
+                    snapshot_version,
+                    target_version,
+                );
+                ensure!(
+                    snapshot_version + 1 >= ledger_min_readable_version,
+                    "Write sets after the state snapshot at version {} are pruned, min readable \
+                     version is {}.",
+                    snapshot_version,
+                    ledger_min_readable_version,
+                );
+            }
+            None => ensure!(
+                state_min_readable_version == 0 && ledger_min_readable_version == 0,
+                "No state snapshot at or before version {}.",
+                target_version,
+            ),
+        }
+        Ok(())
+    }
+
+    fn get_progress(&self, db: &DB, key: DbMetadataKey) -> Result<Version> {
+        Ok(db
+            .get::<DbMetadataSchema>(&key)?
+            .map_or(0, |v| v.expect_version()))
+    }
+
+    fn get_state_snapshot_version_at_or_before(&self, version: Version) -> Result<Option<Version>> {
+        let mut iter = self
+            .state_merkle_db
+            .rev_iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+        iter.seek_for_prev(&NodeKey::new_empty_path(version))?;
+        Ok(iter
+            .next()
+            .transpose()?
+            .map(|(node_key, _node)| node_key.version()))
+    }
+
+    fn truncate_state_merkle_db(&self, target_version: Version, batch_size: usize) -> Result<()> {
+        // Nodes that became stale after the target version are alive at it, they must not be
+        // pruned.
+        self.delete_stale_node_indices::<StaleNodeIndexSchema>(target_version, batch_size)?;
+        self.delete_stale_node_indices::<StaleNodeIndexCrossEpochSchema>(
+            target_version,
+            batch_size,
+        )?;
+
+        let mut iter = self
+            .state_merkle_db
+            .rev_iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        let mut batch = SchemaBatch::new();
+        let mut num_nodes_in_batch = 0;
+        let mut current_version = None;
+        for item in iter {
+            let (node_key, _node) = item?;
+            let version = node_key.version();
+            if version <= target_version {
+                break;
+            }
+            if current_version != Some(version) {
+                // The root is deleted on its own first, so that an interrupted truncation doesn't
+                // leave a root behind whose descendants are partially deleted.
+                let root_batch = SchemaBatch::new();
+                root_batch
+                    .delete::<JellyfishMerkleNodeSchema>(&NodeKey::new_empty_path(version))?;
+                self.state_merkle_db.write_schemas(root_batch)?;
+                current_version = Some(version);
+                info!(version = version, "Deleting state merkle nodes.");
+            }
+            batch.delete::<JellyfishMerkleNodeSchema>(&node_key)?;
+            num_nodes_in_batch += 1;
+            if num_nodes_in_batch == batch_size {
+                self.state_merkle_db
+                    .write_schemas(std::mem::take(&mut batch))?;
+                num_nodes_in_batch = 0;
+            }
+        }
+        self.state_merkle_db.write_schemas(batch)
+    }
+
+    fn delete_stale_node_indices<S: StaleNodeIndexSchemaTrait>(
+        &self,
+        target_version: Version,
+        batch_size: usize,
+    ) -> Result<()>
+    where
+        StaleNodeIndex: KeyCodec<S>,
+    {
+        let mut iter = self.state_merkle_db.iter::<S>(ReadOptions::default())?;
+        iter.seek(&StaleNodeIndex {
+            stale_since_version: target_version + 1,
+            node_key: NodeKey::new_empty_path(0),
+        })?;
+        let mut batch = SchemaBatch::new();
+        let mut num_indices_in_batch = 0;
+        for item in iter {
+            let (index, _) = item?;
+            batch.delete::<S>(&index)?;
+            num_indices_in_batch += 1;
+            if num_indices_in_batch == batch_size {
+                self.state_merkle_db
+                    .write_schemas(std::mem::take(&mut batch))?;
+                num_indices_in_batch = 0;
+            }
+        }
+        self.state_merkle_db.write_schemas(batch)
+    }
+
+    fn truncate_ledger_db(&self, target_version: Version, batch_size: u64) -> Result<()> {
+        let mut iter = self
+            .ledger_db
+            .iter::<TransactionInfoSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        let latest_version = match iter.next().transpose()? {
+            Some((version, _txn_info)) => version,
+            None => return Ok(()),
+        };
+
+        let mut end_version = latest_version;
+        while end_version > target_version {
+            let start_version = std::cmp::max(
+                target_version + 1,
+                end_version.saturating_sub(batch_size - 1),
+            );
+            self.delete_ledger_versions(start_version, end_version)?;
+            info!(
+                start_version = start_version,
+                end_version = end_version,
+                "Deleted ledger data."
+            );
+            end_version = start_version - 1;
+        }
+        Ok(())
+    }
+
+    /// Deletes the ledger data of versions in `[start_version, end_version]` in one batch. All
+    /// versions after `end_version` must be deleted already.
+    fn delete_ledger_versions(&self, start_version: Version, end_version: Version) -> Result<()> {
+        let batch = SchemaBatch::new();
+        for version in start_version..=end_version {
+            if let Some(txn_info) = self.ledger_db.get::<TransactionInfoSchema>(&version)? {
+                batch.delete::<TransactionByHashSchema>(&txn_info.transaction_hash())?;
+            }
+            if let Some(txn) = self.ledger_db.get::<TransactionSchema>(&version)? {
+                if let Transaction::UserTransaction(signed_txn) = &txn {
+                    batch.delete::<TransactionByAccountSchema>(&(
+                        signed_txn.sender(),
+                        signed_txn.sequence_number(),
+                    ))?;
+                }
+                batch.delete::<TransactionByHashSchema>(&txn.hash())?;
+            }
+            if let Some(write_set) = self.ledger_db.get::<WriteSetSchema>(&version)? {
+                for (state_key, _write_op) in write_set.iter() {
+                    batch.delete::<StateValueSchema>(&(state_key.clone(), version))?;
+                }
+            }
+            self.delete_events(version, &batch)?;
+            for position in positions_frozen_by_leaf(version) {
+                batch.delete::<TransactionAccumulatorSchema>(&position)?;
+            }
+            batch.delete::<TransactionSchema>(&version)?;
+            batch.delete::<TransactionInfoSchema>(&version)?;
+            batch.delete::<WriteSetSchema>(&version)?;
+            batch.delete::<EpochByVersionSchema>(&version)?;
+            batch.delete::<VersionDataSchema>(&version)?;
+        }
+
+        // Values that became stale after the truncated versions are current again.
+        let mut iter = self
+            .ledger_db
+            .iter::<StaleStateValueIndexSchema>(ReadOptions::default())?;
+        iter.seek(&start_version)?;
+        for item in iter {
+            let (index, _) = item?;
+            batch.delete::<StaleStateValueIndexSchema>(&index)?;
+        }
+
+        // Only the latest ledger info of each epoch is kept, so the ledger infos of the epochs
+        // that went past the truncated versions are deleted altogether.
+        let mut iter = self
+            .ledger_db
+            .rev_iter::<LedgerInfoSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        for item in iter {
+            let (epoch, ledger_info_with_sigs) = item?;
+            if ledger_info_with_sigs.ledger_info().version() < start_version {
+                break;
+            }
+            batch.delete::<LedgerInfoSchema>(&epoch)?;
+        }
+
+        self.ledger_db.write_schemas(batch)
+    }
+
+    fn delete_events(&self, version: Version, batch: &SchemaBatch) -> Result<()> {
+        let mut iter = self.ledger_db.iter::<EventSchema>(ReadOptions::default())?;
+        iter.seek(&version)?;
+        let mut num_events = 0;
+        for item in iter {
+            let ((event_version, index), event) = item?;
+            if event_version != version {
+                break;
+            }
+            batch.delete::<EventSchema>(&(version, index))?;
+            batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+            batch.delete::<EventByVersionSchema>(&(
+                *event.key(),
+                version,
+                event.sequence_number(),
+            ))?;
+            batch.delete::<EventByTypeSchema>(&(
+                event_type_hash(event.type_tag())?,
+                version,
+                index,
+            ))?;
+            num_events += 1;
+        }
+        for leaf_index in 0..num_events {
+            for position in positions_frozen_by_leaf(leaf_index) {
+                batch.delete::<EventAccumulatorSchema>(&(version, position))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn reset_metadata(&self, target_version: Version) -> Result<()> {
+        // The state merkle pruners may be past the target version when the node restarts from an
+        // epoch ending snapshot. Trees committed again after the target version must be readable
+        // and have their stale nodes pruned.
+        let state_merkle_batch = SchemaBatch::new();
+        for key in [
+            DbMetadataKey::StateMerklePrunerProgress,
+            DbMetadataKey::EpochEndingStateMerklePrunerProgress,
+        ] {
+            if self.get_progress(&self.state_merkle_db, key.clone())? > target_version + 1 {
+                state_merkle_batch
+                    .put::<DbMetadataSchema>(&key, &DbMetadataValue::Version(target_version + 1))?;
+            }
+        }
+        self.state_merkle_db.write_schemas(state_merkle_batch)?;
+
+        let ledger_batch = SchemaBatch::new();
+        let mut iter = self
+            .ledger_db
+            .iter::<DbMetadataSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        for item in iter {
+            if let (DbMetadataKey::StateSnapshotRestoreProgress(version), _) = item? {
+                if version > target_version {
+                    ledger_batch.delete::<DbMetadataSchema>(
+                        &DbMetadataKey::StateSnapshotRestoreProgress(version),
+                    )?;
+                }
+            }
+        }
+        ledger_batch.delete::<DbMetadataSchema>(&DbMetadataKey::TruncationTarget)?;
+        self.ledger_db.write_schemas(ledger_batch)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,150 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_truncater::DbTruncater,
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        transaction_by_hash::TransactionByHashSchema,
+    },
+    test_helper::{arb_blocks_to_commit, save_blocks},
+    AptosDB,
+};
+use aptos_config::config::{
+    RocksdbConfig, RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::hash::CryptoHash;
+use aptos_temppath::TempPath;
+use aptos_types::{transaction::Version, write_set::WriteSet};
+use aptosdb_indexer::Indexer;
+use proptest::{prelude::*, sample::Index};
+use schemadb::ReadOptions;
+use std::sync::Arc;
+
+fn open_db(tmp_dir: &TempPath) -> anyhow::Result<AptosDB> {
+    AptosDB::open(
+        tmp_dir,
+        false, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        false, /* enable_indexer */
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_truncate(input in arb_blocks_to_commit(), target_index in any::<Index>()) {
+        let tmp_dir = TempPath::new();
+        let last_version = {
+            let db = AptosDB::new_for_test_with_buffered_state_target_items(&tmp_dir, 10);
+            save_blocks(&db, &input)
+        };
+        let target_version = target_index.index(last_version as usize + 1) as Version;
+        let txns_to_commit: Vec<_> = input
+            .iter()
+            .flat_map(|(txns_to_commit, _ledger_info_with_sigs)| txns_to_commit)
+            .collect();
+
+        DbTruncater::open(&tmp_dir, RocksdbConfigs::default())
+            .unwrap()
+            .truncate(target_version, 3 /* batch_size */)
+            .unwrap();
+
+        let db = open_db(&tmp_dir).unwrap();
+        let (latest_version, _txn_info) = db.ledger_store.get_latest_transaction_info().unwrap();
+        prop_assert_eq!(latest_version, target_version);
+        for (version, txn_to_commit) in txns_to_commit.iter().enumerate() {
+            let version = version as Version;
+            let txn_hash = txn_to_commit.transaction().hash();
+            let indexed_version = db.ledger_db.get::<TransactionByHashSchema>(&txn_hash).unwrap();
+            if version <= target_version {
+                prop_assert_eq!(
+                    &db.ledger_store.get_transaction_info(version).unwrap(),
+                    txn_to_commit.transaction_info()
+                );
+                prop_assert_eq!(indexed_version, Some(version));
+            } else {
+                prop_assert!(db.ledger_store.get_transaction_info(version).is_err());
+                prop_assert!(db.transaction_store.get_transaction(version).is_err());
+                prop_assert_eq!(indexed_version, None);
+            }
+        }
+
+        let mut iter = db
+            .state_merkle_db
+            .rev_iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())
+            .unwrap();
+        iter.seek_to_last();
+        if let Some((node_key, _node)) = iter.next().transpose().unwrap() {
+            prop_assert!(node_key.version() <= target_version);
+        }
+
+        if let Some(ledger_info_with_sigs) = db.ledger_store.get_latest_ledger_info_option() {
+            prop_assert!(ledger_info_with_sigs.ledger_info().version() <= target_version);
+            let report = db.get_db_verifier().verify(0, Version::MAX).unwrap();
+            prop_assert_eq!(report.corrupt_ranges, vec![]);
+        }
+    }
+}
+
+#[test]
+fn test_pending_truncation() {
+    let tmp_dir = TempPath::new();
+    {
+        let db = AptosDB::new_for_test(&tmp_dir);
+        db.ledger_db
+            .put::<DbMetadataSchema>(
+                &DbMetadataKey::TruncationTarget,
+                &DbMetadataValue::Version(10),
+            )
+            .unwrap();
+    }
+    assert!(open_db(&tmp_dir).is_err());
+
+    {
+        let truncater = DbTruncater::open(&tmp_dir, RocksdbConfigs::default()).unwrap();
+        // The data above the pending target may be gone already.
+        assert!(truncater.truncate(11, 100).is_err());
+        truncater.truncate(10, 100).unwrap();
+    }
+    open_db(&tmp_dir).unwrap();
+}
+
+#[test]
+fn test_truncate_while_open() {
+    let tmp_dir = TempPath::new();
+    let _db = AptosDB::new_for_test(&tmp_dir);
+    assert!(DbTruncater::open(&tmp_dir, RocksdbConfigs::default()).is_err());
+}
+
+#[test]
+fn test_truncate_missing_db() {
+    let tmp_dir = TempPath::new();
+    tmp_dir.create_as_dir().unwrap();
+    assert!(DbTruncater::open(&tmp_dir, RocksdbConfigs::default()).is_err());
+    // Nothing is created at a wrong path.
+    assert_eq!(std::fs::read_dir(tmp_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_truncate_indexer() {
+    let tmp_dir = TempPath::new();
+    {
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let indexer = Indexer::open(&tmp_dir, RocksdbConfig::default()).unwrap();
+        let write_set = WriteSet::default();
+        indexer.index(Arc::new(db), 0, &[&write_set; 10]).unwrap();
+        assert_eq!(indexer.next_version(), 10);
+    }
+
+    let truncater = DbTruncater::open(&tmp_dir, RocksdbConfigs::default()).unwrap();
+    truncater.truncate(5, 100).unwrap();
+    // The transactions after the target version are indexed again when they're committed again.
+    assert_eq!(truncater.indexer.as_ref().unwrap().next_version(), 6);
+}
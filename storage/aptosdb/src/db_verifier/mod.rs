@@ -0,0 +1,656 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module checks the data in an [`AptosDB`](crate::AptosDB) against the hashes committed in
+//! its `TransactionInfo`s and the latest ledger info, and checks that the secondary indices agree
+//! with the data they index.
+
+use crate::{
+    event_store::EventStore,
+    ledger_store::LedgerStore,
+    pruner::pruner_manager::PrunerManager,
+    schema::{
+        event_by_key::EventByKeySchema, jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        state_value::StateValueSchema, transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+    },
+    state_merkle_db::Node,
+    state_store::StateStore,
+    transaction_store::TransactionStore,
+};
+use accumulator::HashReader;
+use anyhow::{ensure, format_err, Context, Result};
+use aptos_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher},
+    HashValue,
+};
+use aptos_jellyfish_merkle::node_type::NodeKey;
+use aptos_logger::info;
+use aptos_types::{
+    proof::{accumulator::InMemoryAccumulator, position::Position},
+    transaction::{Transaction, TransactionInfo, Version},
+};
+use schemadb::{
+    schema::{Schema, SeekKeyCodec},
+    ReadOptions, DB,
+};
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+/// Number of versions between two progress logs.
+const PROGRESS_LOG_INTERVAL: u64 = 100_000;
+
+/// A kind of consistency check performed by the [`DbVerifier`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ConsistencyCheck {
+    /// The `TransactionInfo` exists and can be decoded.
+    TransactionInfo,
+    /// The transaction accumulator contains the `TransactionInfo` and is proven by the latest
+    /// ledger info.
+    TransactionAccumulator,
+    /// The transaction hashes to its `TransactionInfo::transaction_hash`.
+    Transaction,
+    /// The events hash to their `TransactionInfo::event_root_hash`.
+    Events,
+    /// The write set hashes to its `TransactionInfo::state_change_hash`.
+    WriteSet,
+    /// The state merkle tree committed at a state checkpoint is complete and hashes to the
+    /// `TransactionInfo::state_checkpoint_hash`.
+    StateMerkleTree,
+    /// The transaction by hash index agrees with the transactions.
+    TransactionByHashIndex,
+    /// The transaction by account index agrees with the user transactions.
+    TransactionByAccountIndex,
+    /// The event by key index agrees with the events.
+    EventByKeyIndex,
+}
+
+impl fmt::Display for ConsistencyCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ConsistencyCheck::TransactionInfo => "transaction_info",
+            ConsistencyCheck::TransactionAccumulator => "transaction_accumulator",
+            ConsistencyCheck::Transaction => "transaction",
+            ConsistencyCheck::Events => "events",
+            ConsistencyCheck::WriteSet => "write_set",
+            ConsistencyCheck::StateMerkleTree => "state_merkle_tree",
+            ConsistencyCheck::TransactionByHashIndex => "transaction_by_hash_index",
+            ConsistencyCheck::TransactionByAccountIndex => "transaction_by_account_index",
+            ConsistencyCheck::EventByKeyIndex => "event_by_key_index",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Consecutive versions failing the same check for the same reason.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CorruptRange {
+    pub check: ConsistencyCheck,
+    pub first_version: Version,
+    pub last_version: Version,
+    pub reason: String,
+}
+
+impl fmt::Display for CorruptRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[{}] versions {}..={}: {}",
+            self.check, self.first_version, self.last_version, self.reason
+        )
+    }
+}
+
+/// The outcome of verifying the versions in `[first_version, last_version]`.
+#[derive(Clone, Debug)]
+pub struct VerificationReport {
+    pub first_version: Version,
+    pub last_version: Version,
+    pub corrupt_ranges: Vec<CorruptRange>,
+}
+
+impl VerificationReport {
+    pub fn is_consistent(&self) -> bool {
+        self.corrupt_ranges.is_empty()
+    }
+}
+
+/// `DbVerifier` checks the consistency of the data in AptosDB. It only reads from the DB, so it
+/// can run against a DB opened read only or as a secondary instance of a running node.
+pub struct DbVerifier {
+    ledger_db: Arc<DB>,
+    ledger_store: Arc<LedgerStore>,
+    transaction_store: Arc<TransactionStore>,
+    state_store: Arc<StateStore>,
+    event_store: Arc<EventStore>,
+    ledger_min_readable_version: Version,
+    state_min_readable_version: Version,
+    epoch_snapshot_min_readable_version: Version,
+}
+
+impl DbVerifier {
+    pub(crate) fn new(
+        ledger_db: Arc<DB>,
+        ledger_store: Arc<LedgerStore>,
+        transaction_store: Arc<TransactionStore>,
+        state_store: Arc<StateStore>,
+        event_store: Arc<EventStore>,
+        ledger_min_readable_version: Version,
+    ) -> Self {
+        let state_min_readable_version = state_store.state_pruner.get_min_readable_version();
+        let epoch_snapshot_min_readable_version =
+            state_store.epoch_snapshot_pruner.get_min_readable_version();
+        Self {
+            ledger_db,
+            ledger_store,
+            transaction_store,
+            state_store,
+            event_store,
+            ledger_min_readable_version,
+            state_min_readable_version,
+            epoch_snapshot_min_readable_version,
+        }
+    }
+
+    /// Verifies the versions in `[start_version, end_version]`, narrowed down to the versions that
+    /// are neither pruned nor newer than the latest ledger info.
+    ///
+    /// Inconsistencies in the data are reported in the returned report, while an error is only
+    /// returned when the verification itself can't proceed.
+    pub fn verify(
+        &self,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<VerificationReport> {
+        let ledger_info_with_sigs = self.ledger_store.get_latest_ledger_info()?;
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let ledger_version = ledger_info.version();
+        let first_version = std::cmp::max(start_version, self.ledger_min_readable_version);
+        let last_version = std::cmp::min(end_version, ledger_version);
+        ensure!(
+            first_version <= last_version,
+            "No versions to verify in [{}, {}], min readable version is {}, ledger version is {}.",
+            start_version,
+            end_version,
+            self.ledger_min_readable_version,
+            ledger_version,
+        );
+
+        let mut corruptions = Corruptions::default();
+        // The first state merkle tree is walked completely, the following ones from the nodes
+        // written after it.
+        let mut walked_root_version = None;
+        for version in first_version..=last_version {
+            self.verify_version(
+                version,
+                ledger_version,
+                ledger_info.transaction_accumulator_hash(),
+                &mut walked_root_version,
+                &mut corruptions,
+            );
+            if (version - first_version + 1) % PROGRESS_LOG_INTERVAL == 0 {
+                info!(
+                    version = version,
+                    last_version = last_version,
+                    "Verified transactions."
+                );
+            }
+        }
+        self.verify_index_entries(first_version, last_version, &mut corruptions)?;
+
+        Ok(VerificationReport {
+            first_version,
+            last_version,
+            corrupt_ranges: corruptions.into_ranges(),
+        })
+    }
+
+    fn verify_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        accumulator_root_hash: HashValue,
+        walked_root_version: &mut Option<Version>,
+        corruptions: &mut Corruptions,
+    ) {
+        let txn_info = match self.ledger_store.get_transaction_info(version) {
+            Ok(txn_info) => txn_info,
+            Err(_) => {
+                corruptions.add(
+                    ConsistencyCheck::TransactionInfo,
+                    version,
+                    "transaction info is missing or unreadable".to_string(),
+                );
+                return;
+            }
+        };
+
+        corruptions.record(
+            ConsistencyCheck::TransactionAccumulator,
+            version,
+            self.verify_accumulator(version, ledger_version, accumulator_root_hash, &txn_info),
+        );
+        corruptions.record(
+            ConsistencyCheck::Transaction,
+            version,
+            self.verify_transaction(version, &txn_info),
+        );
+        corruptions.record(
+            ConsistencyCheck::Events,
+            version,
+            self.verify_events(version, &txn_info),
+        );
+        corruptions.record(
+            ConsistencyCheck::WriteSet,
+            version,
+            self.verify_write_set(version, &txn_info),
+        );
+        if self.is_state_merkle_readable(version) {
+            corruptions.record(
+                ConsistencyCheck::StateMerkleTree,
+                version,
+                self.verify_state_merkle_tree(version, &txn_info, walked_root_version),
+            );
+        }
+        corruptions.record(
+            ConsistencyCheck::TransactionByHashIndex,
+            version,
+            self.verify_transaction_by_hash(version, &txn_info),
+        );
+        corruptions.record(
+            ConsistencyCheck::TransactionByAccountIndex,
+            version,
+            self.verify_transaction_by_account(version),
+        );
+        corruptions.record(
+            ConsistencyCheck::EventByKeyIndex,
+            version,
+            self.verify_event_by_key(version),
+        );
+    }
+
+    fn verify_accumulator(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        accumulator_root_hash: HashValue,
+        txn_info: &TransactionInfo,
+    ) -> Result<()> {
+        let leaf_hash = self
+            .ledger_store
+            .get(Position::from_leaf_index(version))
+            .context("transaction accumulator leaf is missing or unreadable")?;
+        ensure!(
+            leaf_hash == txn_info.hash(),
+            "transaction accumulator leaf doesn't match the transaction info"
+        );
+        self.ledger_store
+            .get_transaction_proof(version, ledger_version)
+            .context("transaction accumulator nodes are missing or unreadable")?
+            .verify(accumulator_root_hash, leaf_hash, version)
+            .context("transaction accumulator doesn't hash to the root in the latest ledger info")
+    }
+
+    fn verify_transaction(&self, version: Version, txn_info: &TransactionInfo) -> Result<()> {
+        let txn = self
+            .transaction_store
+            .get_transaction(version)
+            .context("transaction is missing or unreadable")?;
+        ensure!(
+            txn.hash() == txn_info.transaction_hash(),
+            "transaction hash doesn't match the transaction info"
+        );
+        Ok(())
+    }
+
+    fn verify_events(&self, version: Version, txn_info: &TransactionInfo) -> Result<()> {
+        let event_hashes: Vec<_> = self
+            .event_store
+            .get_events_by_version(version)
+            .context("events are unreadable")?
+            .iter()
+            .map(CryptoHash::hash)
+            .collect();
+        let event_root_hash =
+            InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes).root_hash();
+        ensure!(
+            event_root_hash == txn_info.event_root_hash(),
+            "event root hash doesn't match the transaction info"
+        );
+        Ok(())
+    }
+
+    fn verify_write_set(&self, version: Version, txn_info: &TransactionInfo) -> Result<()> {
+        let write_set = self
+            .transaction_store
+            .get_write_set(version)
+            .context("write set is missing or unreadable")?;
+        ensure!(
+            CryptoHash::hash(&write_set) == txn_info.state_change_hash(),
+            "write set hash doesn't match the transaction info"
+        );
+        Ok(())
+    }
+
+    fn is_state_merkle_readable(&self, version: Version) -> bool {
+        version >= self.state_min_readable_version
+            || (version >= self.epoch_snapshot_min_readable_version
+                && self.ledger_store.ensure_epoch_ending(version).is_ok())
+    }
+
+    /// Walks the state merkle tree whose root was committed at `version`, if any. Only the nodes
+    /// written after `walked_root_version` are visited, the older ones were walked from that root
+    /// already. The whole tree is walked if no tree was walked yet. `walked_root_version` is
+    /// advanced to `version` once the tree is verified.
+    fn verify_state_merkle_tree(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        walked_root_version: &mut Option<Version>,
+    ) -> Result<()> {
+        // Only some of the state checkpoints are persisted as a tree, the rest live in memory
+        // until the next snapshot is committed.
+        let state_checkpoint_hash = match txn_info.state_checkpoint_hash() {
+            Some(hash) => hash,
+            None => return Ok(()),
+        };
+        let root_key = NodeKey::new_empty_path(version);
+        let root = match self
+            .state_store
+            .state_merkle_db
+            .get::<JellyfishMerkleNodeSchema>(&root_key)
+            .context("state merkle root is unreadable")?
+        {
+            Some(root) => root,
+            None => return Ok(()),
+        };
+        ensure!(
+            root.hash() == state_checkpoint_hash,
+            "state merkle root hash doesn't match the state checkpoint hash"
+        );
+
+        let mut to_visit = vec![(root_key, root)];
+        while let Some((node_key, node)) = to_visit.pop() {
+            match node {
+                Node::Internal(internal_node) => {
+                    for (nibble, child) in internal_node.children_sorted() {
+                        if walked_root_version.map_or(false, |walked| child.version <= walked) {
+                            continue;
+                        }
+                        let child_key = node_key.gen_child_node_key(child.version, *nibble);
+                        let child_node = self
+                            .state_store
+                            .state_merkle_db
+                            .get::<JellyfishMerkleNodeSchema>(&child_key)
+                            .context("state merkle node is unreadable")?
+                            .ok_or_else(|| format_err!("state merkle node is missing"))?;
+                        ensure!(
+                            child_node.hash() == child.hash,
+                            "state merkle node hash doesn't match its parent"
+                        );
+                        to_visit.push((child_key, child_node));
+                    }
+                }
+                Node::Leaf(leaf_node) => {
+                    let (state_key, value_version) = leaf_node.value_index();
+                    ensure!(
+                        state_key.hash() == leaf_node.account_key(),
+                        "state merkle leaf key doesn't match its state key"
+                    );
+                    let state_value = self
+                        .ledger_db
+                        .get::<StateValueSchema>(&(state_key.clone(), *value_version))
+                        .context("state value is unreadable")?
+                        .flatten()
+                        .ok_or_else(|| {
+                            format_err!("state value of a state merkle leaf is missing")
+                        })?;
+                    ensure!(
+                        state_value.hash() == leaf_node.value_hash(),
+                        "state value hash doesn't match its state merkle leaf"
+                    );
+                }
+                Node::Null => (),
+            }
+        }
+        *walked_root_version = Some(version);
+        Ok(())
+    }
+
+    fn verify_transaction_by_hash(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+    ) -> Result<()> {
+        let indexed_version = self
+            .ledger_db
+            .get::<TransactionByHashSchema>(&txn_info.transaction_hash())
+            .context("transaction by hash entry is unreadable")?
+            .ok_or_else(|| format_err!("transaction by hash entry is missing"))?;
+        ensure!(
+            indexed_version == version,
+            "transaction by hash entry points to another version"
+        );
+        Ok(())
+    }
+
+    fn verify_transaction_by_account(&self, version: Version) -> Result<()> {
+        let txn = match self.transaction_store.get_transaction(version) {
+            Ok(Transaction::UserTransaction(txn)) => txn,
+            // Only user transactions are indexed, and a missing transaction is reported by the
+            // transaction check already.
+            _ => return Ok(()),
+        };
+        let indexed_version = self
+            .ledger_db
+            .get::<TransactionByAccountSchema>(&(txn.sender(), txn.sequence_number()))
+            .context("transaction by account entry is unreadable")?
+            .ok_or_else(|| format_err!("transaction by account entry is missing"))?;
+        ensure!(
+            indexed_version == version,
+            "transaction by account entry points to another version"
+        );
+        Ok(())
+    }
+
+    fn verify_event_by_key(&self, version: Version) -> Result<()> {
+        let events = match self.event_store.get_events_by_version(version) {
+            Ok(events) => events,
+            // Reported by the events check already.
+            Err(_) => return Ok(()),
+        };
+        for (index, event) in events.iter().enumerate() {
+            let indexed = self
+                .ledger_db
+                .get::<EventByKeySchema>(&(*event.key(), event.sequence_number()))
+                .context("event by key entry is unreadable")?
+                .ok_or_else(|| format_err!("event by key entry is missing"))?;
+            ensure!(
+                indexed == (version, index as u64),
+                "event by key entry points to another event"
+            );
+        }
+        Ok(())
+    }
+
+    /// Scans the indices for entries pointing into `[first_version, last_version]` that don't
+    /// match the data they point to, which the per version checks can't find.
+    ///
+    /// The transaction by hash index isn't ordered by version, so it can't be scanned without
+    /// reading all of it: only its entries for the verified transactions are checked, by the per
+    /// version checks.
+    fn verify_index_entries(
+        &self,
+        first_version: Version,
+        last_version: Version,
+        corruptions: &mut Corruptions,
+    ) -> Result<()> {
+        self.for_each_index_entry_in_range::<TransactionByAccountSchema, _>(
+            first_version,
+            last_version,
+            |version| *version,
+            |(address, sequence_number), version| {
+                corruptions.record(
+                    ConsistencyCheck::TransactionByAccountIndex,
+                    version,
+                    self.transaction_store
+                        .get_transaction(version)
+                        .context("transaction by account entry points to a missing transaction")
+                        .and_then(|txn| {
+                            ensure!(
+                                matches!(
+                                    txn,
+                                    Transaction::UserTransaction(ref txn)
+                                        if txn.sender() == address
+                                            && txn.sequence_number() == sequence_number
+                                ),
+                                "transaction by account entry points to another transaction"
+                            );
+                            Ok(())
+                        }),
+                );
+            },
+        )?;
+
+        self.for_each_index_entry_in_range::<EventByKeySchema, _>(
+            first_version,
+            last_version,
+            |(version, _index)| *version,
+            |(event_key, sequence_number), (version, index)| {
+                corruptions.record(
+                    ConsistencyCheck::EventByKeyIndex,
+                    version,
+                    self.event_store
+                        .get_event_by_version_and_index(version, index)
+                        .context("event by key entry points to a missing event")
+                        .and_then(|event| {
+                            ensure!(
+                                *event.key() == event_key
+                                    && event.sequence_number() == sequence_number,
+                                "event by key entry points to another event"
+                            );
+                            Ok(())
+                        }),
+                );
+            },
+        )
+    }
+
+    /// Calls `f` on the entries of an index keyed by `(prefix, sequence_number)` which point into
+    /// `[first_version, last_version]`. The versions of the entries of a prefix grow with their
+    /// sequence numbers, so the entries of a prefix before the range are skipped with a galloping
+    /// search, and the ones after the range by seeking to the next prefix.
+    fn for_each_index_entry_in_range<S, P>(
+        &self,
+        first_version: Version,
+        last_version: Version,
+        version_of: impl Fn(&S::Value) -> Version,
+        mut f: impl FnMut(S::Key, S::Value),
+    ) -> Result<()>
+    where
+        S: Schema<Key = (P, u64)>,
+        P: Clone + Eq,
+        (P, u64): SeekKeyCodec<S>,
+    {
+        let mut iter = self.ledger_db.iter::<S>(ReadOptions::default())?;
+        iter.seek_to_first();
+        let mut entry = iter.next().transpose()?;
+        while let Some(((prefix, sequence_number), value)) = entry {
+            let version = version_of(&value);
+            if version < first_version {
+                // Find the last entry of the prefix before the range: `low` is before the range,
+                // and the entries from `high` on are in or after it, or of another prefix.
+                let mut low = sequence_number;
+                let mut high = None;
+                let mut step = 1u64;
+                loop {
+                    let target = match high {
+                        Some(high) if high - low <= 1 => break,
+                        Some(high) => low + (high - low) / 2,
+                        None if low == u64::MAX => break,
+                        None => low.saturating_add(step),
+                    };
+                    iter.seek(&(prefix.clone(), target))?;
+                    match iter.next().transpose()? {
+                        Some(((next_prefix, next_sequence_number), next_value))
+                            if next_prefix == prefix && version_of(&next_value) < first_version =>
+                        {
+                            low = next_sequence_number;
+                            step = step.saturating_mul(2);
+                        }
+                        _ => high = Some(target),
+                    }
+                }
+                iter.seek(&(prefix, low))?;
+                iter.next().transpose()?;
+                entry = iter.next().transpose()?;
+            } else if version > last_version {
+                iter.seek(&(prefix.clone(), u64::MAX))?;
+                entry = iter.next().transpose()?;
+                while matches!(&entry, Some(((next_prefix, _), _)) if *next_prefix == prefix) {
+                    entry = iter.next().transpose()?;
+                }
+            } else {
+                f((prefix, sequence_number), value);
+                entry = iter.next().transpose()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Failed checks, merged into ranges of consecutive versions as they are added.
+#[derive(Default)]
+struct Corruptions(BTreeMap<(ConsistencyCheck, String), Vec<(Version, Version)>>);
+
+impl Corruptions {
+    fn add(&mut self, check: ConsistencyCheck, version: Version, reason: String) {
+        let ranges = self.0.entry((check, reason)).or_default();
+        match ranges.last_mut() {
+            Some((first_version, last_version))
+                if (*first_version..=*last_version + 1).contains(&version) =>
+            {
+                *last_version = std::cmp::max(*last_version, version);
+            }
+            _ => ranges.push((version, version)),
+        }
+    }
+
+    /// Records `result` if it's an error. Only the outermost context of the error is kept, so that
+    /// the reasons of consecutive versions are the same and can be merged.
+    fn record(&mut self, check: ConsistencyCheck, version: Version, result: Result<()>) {
+        if let Err(err) = result {
+            self.add(check, version, err.to_string());
+        }
+    }
+
+    /// Returns the corrupt ranges ordered by check and first version. The versions of a check are
+    /// mostly added in order, the ranges of the ones that weren't are merged here.
+    fn into_ranges(self) -> Vec<CorruptRange> {
+        let mut corrupt_ranges = vec![];
+        for ((check, reason), mut ranges) in self.0 {
+            ranges.sort_unstable();
+            let mut merged: Vec<(Version, Version)> = vec![];
+            for (first_version, last_version) in ranges {
+                match merged.last_mut() {
+                    Some((_, merged_last_version)) if first_version <= *merged_last_version + 1 => {
+                        *merged_last_version = std::cmp::max(*merged_last_version, last_version);
+                    }
+                    _ => merged.push((first_version, last_version)),
+                }
+            }
+            corrupt_ranges.extend(merged.into_iter().map(|(first_version, last_version)| {
+                CorruptRange {
+                    check,
+                    first_version,
+                    last_version,
+                    reason: reason.clone(),
+                }
+            }));
+        }
+        corrupt_ranges.sort_by_key(|range| (range.check, range.first_version));
+        corrupt_ranges
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,122 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_verifier::{ConsistencyCheck, CorruptRange, Corruptions},
+    schema::{transaction_by_hash::TransactionByHashSchema, write_set::WriteSetSchema},
+    test_helper::{arb_blocks_to_commit, save_blocks},
+    AptosDB,
+};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    state_store::state_key::StateKey,
+    transaction::Version,
+    write_set::{WriteOp, WriteSetMut},
+};
+use proptest::prelude::*;
+use schemadb::SchemaBatch;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_verify_consistent_db(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test_with_buffered_state_target_items(&tmp_dir, 10);
+        let last_version = save_blocks(&db, &input);
+
+        let report = db.get_db_verifier().verify(0, Version::MAX).unwrap();
+        prop_assert_eq!(report.first_version, 0);
+        prop_assert_eq!(report.last_version, last_version);
+        prop_assert_eq!(report.corrupt_ranges, vec![]);
+    }
+
+    #[test]
+    fn test_verify_consistent_db_ranges(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test_with_buffered_state_target_items(&tmp_dir, 10);
+        let last_version = save_blocks(&db, &input);
+
+        for version in 0..=last_version {
+            let report = db.get_db_verifier().verify(version, version).unwrap();
+            prop_assert_eq!(report.first_version, version);
+            prop_assert_eq!(report.last_version, version);
+            prop_assert_eq!(report.corrupt_ranges, vec![]);
+        }
+    }
+
+    #[test]
+    fn test_verify_corrupt_db(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test_with_buffered_state_target_items(&tmp_dir, 10);
+        let last_version = save_blocks(&db, &input);
+
+        let batch = SchemaBatch::new();
+        let tampered_write_set = WriteSetMut::new(vec![(
+            StateKey::Raw(b"tampered".to_vec()),
+            WriteOp::Deletion,
+        )])
+        .freeze()
+        .unwrap();
+        batch.put::<WriteSetSchema>(&0, &tampered_write_set).unwrap();
+        let txn_info = db.ledger_store.get_transaction_info(last_version).unwrap();
+        batch
+            .delete::<TransactionByHashSchema>(&txn_info.transaction_hash())
+            .unwrap();
+        db.ledger_db.write_schemas(batch).unwrap();
+
+        let report = db.get_db_verifier().verify(0, last_version).unwrap();
+        prop_assert!(!report.is_consistent());
+        prop_assert_eq!(
+            report.corrupt_ranges,
+            vec![
+                CorruptRange {
+                    check: ConsistencyCheck::WriteSet,
+                    first_version: 0,
+                    last_version: 0,
+                    reason: "write set hash doesn't match the transaction info".to_string(),
+                },
+                CorruptRange {
+                    check: ConsistencyCheck::TransactionByHashIndex,
+                    first_version: last_version,
+                    last_version,
+                    reason: "transaction by hash entry is missing".to_string(),
+                },
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_verify_empty_db() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    assert!(db.get_db_verifier().verify(0, Version::MAX).is_err());
+}
+
+#[test]
+fn test_corruptions_into_ranges() {
+    let mut corruptions = Corruptions::default();
+    for version in [3, 4, 5, 5, 9, 7, 8, 1] {
+        corruptions.add(ConsistencyCheck::WriteSet, version, "bad".to_string());
+    }
+    corruptions.add(ConsistencyCheck::Events, 4, "bad".to_string());
+    corruptions.add(ConsistencyCheck::Events, 5, "worse".to_string());
+
+    let range = |check, first_version, last_version, reason: &str| CorruptRange {
+        check,
+        first_version,
+        last_version,
+        reason: reason.to_string(),
+    };
+    assert_eq!(
+        corruptions.into_ranges(),
+        vec![
+            range(ConsistencyCheck::Events, 4, 4, "bad"),
+            range(ConsistencyCheck::Events, 5, 5, "worse"),
+            range(ConsistencyCheck::WriteSet, 1, 1, "bad"),
+            range(ConsistencyCheck::WriteSet, 3, 5, "bad"),
+            range(ConsistencyCheck::WriteSet, 7, 9, "bad"),
+        ]
+    );
+}
@@ -11,7 +11,8 @@ use crate::{
     errors::AptosDbError,
     schema::{
         event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema,
     },
 };
 use accumulator::{HashReader, MerkleAccumulator};
@@ -28,6 +29,7 @@ use aptos_types::{
     proof::position::Position,
     transaction::Version,
 };
+use move_core_types::language_storage::TypeTag;
 use schemadb::iterator::SchemaIterator;
 use schemadb::{schema::ValueCodec, ReadOptions, SchemaBatch, DB};
 use std::{
@@ -188,6 +190,44 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Given the type of events and `start_version`, returns events identified by transaction
+    /// version and index among all events emitted by the same transaction. Result won't contain
+    /// records with a transaction version > `ledger_version` and is in ascending order.
+    ///
+    /// Once `limit` records are collected, the remaining events of the last transaction are still
+    /// included, so that the caller can continue from the version after the last one returned
+    /// without missing any event.
+    pub fn lookup_events_by_type(
+        &self,
+        event_type: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        let type_hash = event_type_hash(event_type)?;
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_hash, start_version, 0))?;
+
+        let mut result: Vec<(Version, u64)> = Vec::new();
+        for res in iter {
+            let ((hash, ver, idx), ()) = res?;
+            if hash != type_hash || ver > ledger_version {
+                break;
+            }
+            if result.len() as u64 >= limit && result.last().map(|(v, _)| *v) != Some(ver) {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
     fn lookup_event_by_key(
         &self,
         event_key: &EventKey,
@@ -322,6 +362,10 @@ impl EventStore {
                 batch.put::<EventByVersionSchema>(
                     &(*event.key(), version, event.sequence_number()),
                     &(idx as u64),
+                )?;
+                batch.put::<EventByTypeSchema>(
+                    &(event_type_hash(event.type_tag())?, version, idx as u64),
+                    &(),
                 )
             })?;
 
@@ -469,6 +513,11 @@ impl EventStore {
                     event.sequence_number(),
                 ))?;
                 db_batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+                db_batch.delete::<EventByTypeSchema>(&(
+                    event_type_hash(event.type_tag())?,
+                    current_version as u64,
+                    current_index as u64,
+                ))?;
                 db_batch.delete::<EventSchema>(&(current_version as u64, current_index as u64))?;
             }
             current_version += 1;
@@ -478,6 +527,11 @@ impl EventStore {
     }
 }
 
+/// Key prefix of the events of type `event_type` in `EventByTypeSchema`.
+pub(crate) fn event_type_hash(event_type: &TypeTag) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(event_type)?))
+}
+
 struct EventHashReader<'a> {
     store: &'a EventStore,
     version: Version,
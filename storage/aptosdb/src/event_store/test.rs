@@ -150,6 +150,40 @@ fn traverse_events_by_key(
         .collect()
 }
 
+fn traverse_events_by_type(
+    store: &EventStore,
+    event_type: &TypeTag,
+    ledger_version: Version,
+) -> Vec<(Version, ContractEvent)> {
+    const LIMIT: u64 = 3;
+
+    let mut start_version = 0;
+
+    let mut indices: Vec<(Version, u64)> = Vec::new();
+    loop {
+        let batch = store
+            .lookup_events_by_type(event_type, start_version, LIMIT, ledger_version)
+            .unwrap();
+        if batch.is_empty() {
+            break;
+        }
+
+        let first_ver = batch.first().unwrap().0;
+        let last_ver = batch.last().unwrap().0;
+        assert!(first_ver >= start_version);
+        // Only the events of the last version are allowed to go beyond the limit.
+        assert!(batch.iter().filter(|(ver, _)| *ver != last_ver).count() < LIMIT as usize);
+
+        indices.extend(batch.iter());
+        start_version = last_ver + 1;
+    }
+
+    indices
+        .into_iter()
+        .map(|(ver, idx)| (ver, store.get_event_by_version_and_index(ver, idx).unwrap()))
+        .collect()
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -193,6 +227,23 @@ fn test_index_get_impl(event_batches: Vec<Vec<ContractEvent>>) {
         event_batches,
     );
 
+    // Calculate expected events per type, and fetch them by type.
+    let mut events_by_type = HashMap::new();
+    event_batches.iter().enumerate().for_each(|(ver, batch)| {
+        batch.iter().for_each(|e| {
+            events_by_type
+                .entry(e.type_tag().clone())
+                .or_insert_with(Vec::new)
+                .push((ver as Version, e.clone()));
+        })
+    });
+    events_by_type
+        .into_iter()
+        .for_each(|(event_type, versions_and_events)| {
+            let traversed = traverse_events_by_type(store, &event_type, ledger_version_plus_one);
+            assert_eq!(versions_and_events, traversed);
+        });
+
     // Calculate expected event sequence per access_path.
     let mut events_by_event_key = HashMap::new();
     event_batches
@@ -14,6 +14,8 @@
 pub mod test_helper;
 
 pub mod backup;
+pub mod db_truncater;
+pub mod db_verifier;
 pub mod errors;
 pub mod metrics;
 pub mod schema;
@@ -41,6 +43,7 @@ use crate::{
         gen_ledger_cfds, gen_state_merkle_cfds, ledger_db_column_families,
         state_merkle_db_column_families,
     },
+    db_verifier::DbVerifier,
     errors::AptosDbError,
     event_store::EventStore,
     ledger_store::LedgerStore,
@@ -61,7 +64,7 @@ use aptos_config::config::{
     NO_OP_STORAGE_PRUNER_CONFIG,
 };
 
-use aptos_crypto::hash::HashValue;
+use aptos_crypto::hash::{CryptoHash, HashValue};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_rocksdb_options::gen_rocksdb_options;
@@ -76,14 +79,14 @@ use aptos_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
-        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleProofExt,
-        TransactionInfoListWithProof,
+        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleBatchProof,
+        SparseMerkleProofExt, TransactionInfoListWithProof,
     },
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
         state_key_prefix::StateKeyPrefix,
-        state_value::{StateValue, StateValueChunkWithProof},
+        state_value::{StateValue, StateValueChange, StateValueChunkWithProof},
         table::{TableHandle, TableInfo},
     },
     transaction::{
@@ -95,11 +98,12 @@ use aptos_types::{
 use aptos_vm::data_cache::AsMoveResolver;
 use aptosdb_indexer::Indexer;
 use itertools::zip_eq;
+use move_core_types::language_storage::TypeTag;
 use move_resource_viewer::MoveValueAnnotator;
 use once_cell::sync::Lazy;
 use schemadb::{SchemaBatch, DB};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     iter::Iterator,
     path::Path,
     sync::{mpsc, Arc},
@@ -114,6 +118,7 @@ use crate::pruner::{
 };
 use crate::stale_node_index::StaleNodeIndexSchema;
 use crate::stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema;
+use storage_interface::Error as StorageError;
 use storage_interface::{
     state_delta::StateDelta, state_view::DbStateView, DbReader, DbWriter, ExecutedTrees, Order,
     StateSnapshotReceiver, MAX_REQUEST_LIMIT,
@@ -125,6 +130,8 @@ pub const STATE_MERKLE_DB_NAME: &str = "state_merkle_db";
 // TODO: Either implement an iteration API to allow a very old client to loop through a long history
 // or guarantee that there is always a recent enough waypoint and client knows to boot from there.
 const MAX_NUM_EPOCH_ENDING_LEDGER_INFO: usize = 100;
+// State diffs over more versions than this are computed by diffing state snapshots.
+const MAX_WRITE_SETS_TO_DIFF: u64 = 10_000;
 static ROCKSDB_PROPERTY_MAP: Lazy<HashMap<&str, String>> = Lazy::new(|| {
     [
         "rocksdb.num-immutable-mem-table",
@@ -354,6 +361,9 @@ impl AptosDB {
                 )?,
             )
         };
+        if !readonly {
+            db_truncater::ensure_no_pending_truncation(&ledger_db)?;
+        }
 
         let mut myself = Self::new_with_dbs(
             ledger_db,
@@ -650,6 +660,18 @@ impl AptosDB {
         )
     }
 
+    /// Gets an instance of `DbVerifier` for checking the consistency of the data.
+    pub fn get_db_verifier(&self) -> DbVerifier {
+        DbVerifier::new(
+            Arc::clone(&self.ledger_db),
+            Arc::clone(&self.ledger_store),
+            Arc::clone(&self.transaction_store),
+            Arc::clone(&self.state_store),
+            Arc::clone(&self.event_store),
+            self.ledger_pruner.get_min_readable_version(),
+        )
+    }
+
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let start = Instant::now();
@@ -733,6 +755,151 @@ impl AptosDB {
         Ok(events_with_version)
     }
 
+    fn get_events_by_type_impl(
+        &self,
+        event_type: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+        self.error_if_ledger_pruned("Event", start_version)?;
+
+        self.event_store
+            .lookup_events_by_type(event_type, start_version, limit, ledger_version)?
+            .into_iter()
+            .map(|(ver, idx)| {
+                let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+                ensure!(
+                    event.type_tag() == event_type,
+                    "Index broken, expected type: {}, actual: {}",
+                    event_type,
+                    event.type_tag()
+                );
+                Ok(EventWithVersion::new(ver, event))
+            })
+            .collect()
+    }
+
+    fn get_state_value_changes_impl(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        start: Option<&StateKey>,
+        limit: u64,
+        max_write_sets_to_diff: u64,
+    ) -> Result<(Vec<StateValueChange>, Option<StateKey>)> {
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+        ensure!(limit > 0, "limit must be positive.");
+        ensure!(
+            from_version <= to_version,
+            "from_version {} is after to_version {}.",
+            from_version,
+            to_version
+        );
+        self.error_if_ledger_pruned("State", from_version)?;
+        let starting_key = start.map_or_else(HashValue::zero, CryptoHash::hash);
+
+        // Keys which may have changed in the range, by hash. For long ranges, the changes between
+        // the state snapshots around the range come from diffing their Jellyfish Merkle trees,
+        // and only the write sets after each snapshot are read.
+        let mut candidates = BTreeMap::new();
+        let mut next_leaf_key = None;
+        let mut write_set_ranges = vec![(from_version + 1, to_version + 1)];
+        if to_version - from_version > max_write_sets_to_diff {
+            let old_snapshot = self
+                .state_store
+                .state_merkle_db
+                .get_state_snapshot_version_before(from_version + 1)?;
+            let new_snapshot = self
+                .state_store
+                .state_merkle_db
+                .get_state_snapshot_version_before(to_version + 1)?;
+            if let (Some(old_snapshot), Some(new_snapshot)) = (old_snapshot, new_snapshot) {
+                if old_snapshot < new_snapshot
+                    && self
+                        .error_if_ledger_pruned("Write set", old_snapshot + 1)
+                        .is_ok()
+                    && self
+                        .error_if_state_merkle_pruned("State merkle", old_snapshot)
+                        .is_ok()
+                {
+                    let mut changed_leaves = self.state_store.state_merkle_db.get_changed_leaves(
+                        old_snapshot,
+                        new_snapshot,
+                        starting_key,
+                        limit as usize + 1,
+                    )?;
+                    // Keys after the last leaf may not have been diffed yet, so they are left
+                    // for the next page.
+                    if changed_leaves.len() > limit as usize {
+                        next_leaf_key = changed_leaves.pop().and_then(|(old, new)| new.or(old));
+                    }
+                    for leaf in changed_leaves
+                        .into_iter()
+                        .filter_map(|(old, new)| new.or(old))
+                    {
+                        candidates.insert(leaf.account_key(), leaf.value_index().0.clone());
+                    }
+                    write_set_ranges = vec![
+                        (old_snapshot + 1, from_version + 1),
+                        (new_snapshot + 1, to_version + 1),
+                    ];
+                }
+            }
+            // Without a diff, every write set in the range would have to be read on every page.
+            if write_set_ranges.len() == 1 {
+                return Err(StorageError::VersionRangeTooLong(
+                    to_version - from_version,
+                    max_write_sets_to_diff,
+                )
+                .into());
+            }
+        }
+        for (begin, end) in write_set_ranges {
+            for write_set in self
+                .transaction_store
+                .get_write_set_iter(begin, (end - begin) as usize)?
+            {
+                for (state_key, _) in write_set?.iter() {
+                    let key_hash = state_key.hash();
+                    if key_hash >= starting_key
+                        && next_leaf_key
+                            .as_ref()
+                            .map_or(true, |leaf| key_hash < leaf.account_key())
+                    {
+                        candidates.insert(key_hash, state_key.clone());
+                    }
+                }
+            }
+        }
+
+        let mut changes = vec![];
+        let mut candidates = candidates.into_values();
+        for state_key in candidates.by_ref() {
+            let old_value = self
+                .state_store
+                .get_state_value_by_version(&state_key, from_version)?;
+            let new_value = self
+                .state_store
+                .get_state_value_by_version(&state_key, to_version)?;
+            if old_value != new_value {
+                changes.push(StateValueChange {
+                    state_key,
+                    old_value,
+                    new_value,
+                });
+                if changes.len() as u64 == limit {
+                    break;
+                }
+            }
+        }
+        let next_key = candidates
+            .next()
+            .or_else(|| next_leaf_key.map(|leaf| leaf.value_index().0.clone()));
+        Ok((changes, next_key))
+    }
+
     fn save_transactions_impl(
         &self,
         txns_to_commit: &[TransactionToCommit],
@@ -1146,6 +1313,24 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Returns events of type `event_type` emitted by transactions with versions in
+    /// [`start_version`, `ledger_version`], in ascending order of version and index.
+    ///
+    /// At most `limit` events are returned, except that the events of the last transaction are
+    /// never split across pages: to continue, query again from the version after the last
+    /// event's. Only events committed since the index was introduced can be found.
+    fn get_events_by_type(
+        &self,
+        event_type: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        gauged_api("get_events_by_type", || {
+            self.get_events_by_type_impl(event_type, start_version, limit, ledger_version)
+        })
+    }
+
     /// Gets ledger info at specified version and ensures it's an epoch ending.
     fn get_epoch_ending_ledger_info(&self, version: u64) -> Result<LedgerInfoWithSignatures> {
         gauged_api("get_epoch_ending_ledger_info", || {
@@ -1227,6 +1412,43 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_values_with_batch_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<(StateKey, Option<StateValue>)>, SparseMerkleBatchProof)> {
+        gauged_api("get_state_values_with_batch_proof_by_version", || {
+            error_if_too_many_requested(state_keys.len() as u64, MAX_REQUEST_LIMIT)?;
+            self.error_if_state_merkle_pruned("State merkle", version)?;
+
+            self.state_store
+                .get_state_values_with_batch_proof_by_version(state_keys, version)
+        })
+    }
+
+    /// Returns the state keys whose values differ between `from_version` and `to_version`, with
+    /// their values at both versions, ordered by the hash of the key.
+    ///
+    /// At most `limit` keys are returned, from `start` onwards. The key to continue from is
+    /// returned if there may be more, and a page can be shorter than `limit` even then.
+    fn get_state_value_changes(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        start: Option<&StateKey>,
+        limit: u64,
+    ) -> Result<(Vec<StateValueChange>, Option<StateKey>)> {
+        gauged_api("get_state_value_changes", || {
+            self.get_state_value_changes_impl(
+                from_version,
+                to_version,
+                start,
+                limit,
+                MAX_WRITE_SETS_TO_DIFF,
+            )
+        })
+    }
+
     fn get_latest_epoch_state(&self) -> Result<EpochState> {
         gauged_api("get_latest_epoch_state", || {
             let latest_ledger_info = self.ledger_store.get_latest_ledger_info()?;
@@ -87,12 +87,14 @@ fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
             verify_events_not_in_store(j as u64, event_store);
             verify_event_by_key_not_in_store(&events, j as u64, event_store);
             verify_event_by_version_not_in_store(&events, j as u64, event_store);
+            verify_event_by_type_not_in_store(&events, j as u64, event_store);
         }
         // ensure all other events are valid in DB
         for j in i..num_versions {
             verify_events_in_store(&events, j as u64, event_store);
             verify_event_by_key_in_store(&events, j as u64, event_store);
             verify_event_by_version_in_store(&events, j as u64, event_store);
+            verify_event_by_type_in_store(&events, j as u64, event_store);
         }
     }
 }
@@ -119,6 +121,7 @@ fn verify_event_store_pruner_disabled(events: Vec<Vec<ContractEvent>>) {
             verify_events_in_store(&events, version as u64, event_store);
             verify_event_by_key_in_store(&events, version as u64, event_store);
             verify_event_by_version_in_store(&events, version as u64, event_store);
+            verify_event_by_type_in_store(&events, version as u64, event_store);
         }
     }
 }
@@ -176,6 +179,42 @@ fn verify_event_by_version_in_store(
     }
 }
 
+fn lookup_events_by_type_at_version(
+    event: &ContractEvent,
+    version: Version,
+    event_store: &Arc<EventStore>,
+) -> Vec<(Version, u64)> {
+    event_store
+        .lookup_events_by_type(event.type_tag(), version, 1, version)
+        .unwrap()
+        .into_iter()
+        .filter(|(ver, _idx)| *ver == version)
+        .collect()
+}
+
+fn verify_event_by_type_not_in_store(
+    events: &[Vec<ContractEvent>],
+    version: Version,
+    event_store: &Arc<EventStore>,
+) {
+    for event in events.get(version as usize).unwrap() {
+        assert!(lookup_events_by_type_at_version(event, version, event_store).is_empty());
+    }
+}
+
+fn verify_event_by_type_in_store(
+    events: &[Vec<ContractEvent>],
+    version: Version,
+    event_store: &Arc<EventStore>,
+) {
+    for (idx, event) in events.get(version as usize).unwrap().iter().enumerate() {
+        assert!(
+            lookup_events_by_type_at_version(event, version, event_store)
+                .contains(&(version, idx as u64))
+        );
+    }
+}
+
 fn verify_events_not_in_store(version: Version, event_store: &Arc<EventStore>) {
     assert!(event_store
         .get_events_by_version(version)
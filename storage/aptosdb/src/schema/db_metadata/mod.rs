@@ -49,6 +49,7 @@ pub enum DbMetadataKey {
     StateMerklePrunerProgress,
     EpochEndingStateMerklePrunerProgress,
     StateSnapshotRestoreProgress(Version),
+    TruncationTarget,
 }
 
 define_schema!(
@@ -0,0 +1,69 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which a ContractEvent (
+//! represented by a <txn_version, event_idx> tuple so that it can be fetched from `EventSchema`)
+//! can be found by the type of the event.
+//!
+//! ```text
+//! |<-----------key------------>|
+//! | type_hash | txn_ver | idx |
+//! ```
+//!
+//! `type_hash` is the SHA3-256 hash of the BCS encoded `TypeTag` of the event, so that keys are of
+//! fixed length no matter how long the type is. `txn_ver` and `idx` are serialized in big endian
+//! so that events of the same type are ordered by their position in the ledger.
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (HashValue, Version, Index);
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref type_hash, version, index) = *self;
+
+        let mut encoded = type_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        const HASH_LEN: usize = HashValue::LENGTH;
+        const VERSION_SIZE: usize = size_of::<Version>();
+        let type_hash = HashValue::from_slice(&data[..HASH_LEN])?;
+        let version = (&data[HASH_LEN..HASH_LEN + VERSION_SIZE]).read_u64::<BigEndian>()?;
+        let index = (&data[HASH_LEN + VERSION_SIZE..]).read_u64::<BigEndian>()?;
+
+        Ok((type_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
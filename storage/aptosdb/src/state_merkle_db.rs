@@ -16,7 +16,7 @@ use aptos_jellyfish_merkle::{
 };
 use aptos_types::{
     nibble::{nibble_path::NibblePath, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{SparseMerkleBatchProof, SparseMerkleProofExt, SparseMerkleRangeProof},
     state_store::state_key::StateKey,
     transaction::Version,
 };
@@ -66,6 +66,17 @@ impl StateMerkleDb {
         JellyfishMerkleTree::new(self).get_with_proof_ext(state_key.hash(), version)
     }
 
+    pub fn get_with_batch_proof(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<(
+        Vec<Option<(HashValue, (StateKey, Version))>>,
+        SparseMerkleBatchProof,
+    )> {
+        JellyfishMerkleTree::new(self).get_with_batch_proof(keys, version)
+    }
+
     pub fn get_range_proof(
         &self,
         rightmost_key: HashValue,
@@ -78,6 +89,21 @@ impl StateMerkleDb {
         JellyfishMerkleTree::new(self).get_root_hash(version)
     }
 
+    pub fn get_changed_leaves(
+        &self,
+        old_version: Version,
+        new_version: Version,
+        starting_key: HashValue,
+        limit: usize,
+    ) -> Result<Vec<(Option<LeafNode>, Option<LeafNode>)>> {
+        JellyfishMerkleTree::new(self).get_changed_leaves(
+            old_version,
+            new_version,
+            starting_key,
+            limit,
+        )
+    }
+
     pub fn get_leaf_count(&self, version: Version) -> Result<usize> {
         JellyfishMerkleTree::new(self).get_leaf_count(version)
     }
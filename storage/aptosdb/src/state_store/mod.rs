@@ -27,7 +27,9 @@ use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
 use aptos_logger::info;
 use aptos_state_view::StateViewId;
 use aptos_types::{
-    proof::{definition::LeafCount, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        definition::LeafCount, SparseMerkleBatchProof, SparseMerkleProofExt, SparseMerkleRangeProof,
+    },
     state_store::{
         state_key::StateKey,
         state_key_prefix::StateKeyPrefix,
@@ -40,7 +42,7 @@ use executor_types::in_memory_state_calculator::InMemoryStateCalculator;
 use once_cell::sync::Lazy;
 use schemadb::{ReadOptions, SchemaBatch, DB};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     ops::Deref,
     sync::Arc,
 };
@@ -156,6 +158,32 @@ impl DbReader for StateDb {
         ))
     }
 
+    /// Get the state values of the given keys with a single proof, ordered by the hash of the key
+    fn get_state_values_with_batch_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<(StateKey, Option<StateValue>)>, SparseMerkleBatchProof)> {
+        let state_keys_by_hash: BTreeMap<_, _> = state_keys
+            .iter()
+            .map(|state_key| (state_key.hash(), state_key))
+            .collect();
+        let keys: Vec<_> = state_keys_by_hash.keys().copied().collect();
+        let (leaf_data, proof) = self.state_merkle_db.get_with_batch_proof(&keys, version)?;
+        let values = state_keys_by_hash
+            .into_values()
+            .zip(leaf_data)
+            .map(|(state_key, leaf_data)| -> Result<_> {
+                let value = match leaf_data {
+                    Some((_, (key, version))) => Some(self.expect_value_by_version(&key, version)?),
+                    None => None,
+                };
+                Ok((state_key.clone(), value))
+            })
+            .collect::<Result<_>>()?;
+        Ok((values, proof))
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         version.map_or(Ok(StateStorageUsage::zero()), |version| {
             Ok(self
@@ -242,6 +270,16 @@ impl DbReader for StateStore {
         self.deref()
             .get_state_value_with_proof_by_version_ext(state_key, version)
     }
+
+    /// Get the state values of the given keys with a single proof, ordered by the hash of the key
+    fn get_state_values_with_batch_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<(StateKey, Option<StateValue>)>, SparseMerkleBatchProof)> {
+        self.deref()
+            .get_state_values_with_batch_proof_by_version(state_keys, version)
+    }
 }
 
 impl StateDb {
@@ -803,6 +803,34 @@ pub fn put_as_state_root(db: &AptosDB, version: Version, key: StateKey, value: S
         .unwrap();
 }
 
+/// Saves the blocks in order and returns the last version.
+pub fn save_blocks(
+    db: &AptosDB,
+    input: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)],
+) -> Version {
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit);
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    cur_ver - 1
+}
+
 pub fn test_sync_transactions_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
     snapshot_size_threshold: usize,
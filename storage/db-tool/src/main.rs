@@ -0,0 +1,37 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+mod truncate;
+mod verify;
+
+use anyhow::Result;
+use aptos_logger::{prelude::*, Level, Logger};
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(
+    name = "aptos-db-tool",
+    about = "Tool for inspecting and maintaining AptosDB"
+)]
+enum Cmd {
+    /// Checks the consistency of the data in the DB and reports the corrupt version ranges
+    Verify(verify::Opt),
+    /// Deletes everything committed after a version. The node using the DB must be stopped, and
+    /// an interrupted truncation must be rerun before the node can start again
+    Truncate(truncate::Opt),
+}
+
+fn main() -> Result<()> {
+    Logger::new().level(Level::Info).read_env().init();
+
+    let result = match Cmd::parse() {
+        Cmd::Verify(opt) => verify::run(opt),
+        Cmd::Truncate(opt) => truncate::run(opt),
+    };
+    result.map_err(|e| {
+        error!("aptos-db-tool failed: {}", e);
+        e
+    })
+}
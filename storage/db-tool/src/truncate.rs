@@ -0,0 +1,31 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_config::config::RocksdbConfigs;
+use aptos_types::transaction::Version;
+use aptosdb::db_truncater::DbTruncater;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+pub struct Opt {
+    /// Root directory of the DB, containing the ledger and state merkle DBs
+    #[clap(long, parse(from_os_str))]
+    db_dir: PathBuf,
+
+    /// Version to roll the DB back to, everything committed after it is deleted
+    #[clap(long)]
+    target_version: Version,
+
+    /// Number of versions, or of state merkle nodes, deleted in one write
+    #[clap(long, default_value = "10000")]
+    batch_size: usize,
+}
+
+pub fn run(opt: Opt) -> Result<()> {
+    DbTruncater::open(&opt.db_dir, RocksdbConfigs::default())?
+        .truncate(opt.target_version, opt.batch_size)?;
+    println!("Truncated the DB to version {}.", opt.target_version);
+    Ok(())
+}
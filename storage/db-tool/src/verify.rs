@@ -0,0 +1,69 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use aptos_config::config::{
+    RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_types::transaction::Version;
+use aptosdb::AptosDB;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+pub struct Opt {
+    /// Root directory of the DB, containing the ledger and state merkle DBs
+    #[clap(long, parse(from_os_str))]
+    db_dir: PathBuf,
+
+    /// Opens the DB as a secondary instance keeping its own files in this directory, which allows
+    /// verifying the DB of a running node. Otherwise the DB is opened read only.
+    #[clap(long, parse(from_os_str))]
+    secondary_db_dir: Option<PathBuf>,
+
+    /// First version to verify, versions that are already pruned are skipped
+    #[clap(long, default_value = "0")]
+    start_version: Version,
+
+    /// Last version to verify, defaults to the version of the latest ledger info
+    #[clap(long)]
+    end_version: Option<Version>,
+}
+
+pub fn run(opt: Opt) -> Result<()> {
+    let db = match opt.secondary_db_dir {
+        Some(secondary_db_dir) => {
+            AptosDB::open_as_secondary(opt.db_dir, secondary_db_dir, RocksdbConfigs::default())?
+        }
+        None => AptosDB::open(
+            opt.db_dir,
+            true, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs::default(),
+            false, /* enable_indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        )?,
+    };
+
+    let report = db
+        .get_db_verifier()
+        .verify(opt.start_version, opt.end_version.unwrap_or(Version::MAX))?;
+    for corrupt_range in &report.corrupt_ranges {
+        println!("{}", corrupt_range);
+    }
+    if !report.is_consistent() {
+        bail!(
+            "Found {} corrupt ranges in versions {}..={}.",
+            report.corrupt_ranges.len(),
+            report.first_version,
+            report.last_version,
+        );
+    }
+    println!(
+        "Versions {}..={} are consistent.",
+        report.first_version, report.last_version
+    );
+    Ok(())
+}
@@ -5,8 +5,9 @@ mod db;
 mod metadata;
 mod schema;
 
+pub use crate::db::INDEX_DB_NAME;
+
 use crate::{
-    db::INDEX_DB_NAME,
     metadata::{MetadataKey, MetadataValue},
     schema::{
         column_families, indexer_metadata::IndexerMetadataSchema, table_info::TableInfoSchema,
@@ -129,6 +130,24 @@ impl Indexer {
         Ok(())
     }
 
+    /// Rolls the indexer back to `target_version`, so that the transactions after it are indexed
+    /// again when they are committed again.
+    pub fn truncate(&self, target_version: Version) -> Result<()> {
+        let latest_version = self
+            .db
+            .get::<IndexerMetadataSchema>(&MetadataKey::LatestVersion)?
+            .map(|v| v.expect_version());
+        if latest_version.map_or(false, |v| v > target_version) {
+            self.db.put::<IndexerMetadataSchema>(
+                &MetadataKey::LatestVersion,
+                &MetadataValue::Version(target_version),
+            )?;
+            self.next_version
+                .store(target_version + 1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
     pub fn next_version(&self) -> Version {
         self.next_version.load(Ordering::Relaxed)
     }
@@ -6,8 +6,9 @@ use crate::{
     node_type::NodeType,
     test_helper::{
         arb_existent_kvs_and_nonexistent_keys, arb_kv_pair_with_distinct_last_nibble,
-        arb_tree_with_index, gen_value, test_get_leaf_count, test_get_range_proof,
-        test_get_with_proof, test_get_with_proof_with_distinct_last_nibble, ValueBlob,
+        arb_tree_with_index, gen_value, plus_one, test_get_leaf_count, test_get_range_proof,
+        test_get_with_batch_proof, test_get_with_proof,
+        test_get_with_proof_with_distinct_last_nibble, ValueBlob,
     },
 };
 use aptos_crypto::HashValue;
@@ -778,6 +779,82 @@ fn test_1000_versions() {
     many_versions_get_proof_and_verify_tree_root(seed, 1000);
 }
 
+#[test]
+fn test_get_changed_leaves() {
+    let mut rng: StdRng = StdRng::from_seed([1u8; 32]);
+    let db = MockTreeStore::default();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let keys: Vec<_> = (0..1000)
+        .map(|_| HashValue::random_with_rng(&mut rng))
+        .collect();
+    let values: Vec<_> = (0..1000).map(|_| gen_value()).collect();
+    let (_root, batch) = tree
+        .put_value_set_test(
+            keys.iter().cloned().zip(values.iter().map(Some)).collect(),
+            0,
+        )
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    // Update 50 keys, delete 20 and create 30.
+    let new_keys: Vec<_> = (0..30)
+        .map(|_| HashValue::random_with_rng(&mut rng))
+        .collect();
+    let new_values: Vec<_> = (0..80).map(|_| gen_value()).collect();
+    let mut value_set: Vec<_> = keys[..50]
+        .iter()
+        .chain(new_keys.iter())
+        .cloned()
+        .zip(new_values.iter().map(Some))
+        .collect();
+    value_set.extend(keys[50..70].iter().map(|key| (*key, None)));
+    // Writing the same value isn't a change.
+    value_set.push((keys[70], Some(&values[70])));
+    let (_root, batch) = tree.put_value_set_test(value_set, 1).unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    let mut expected: Vec<_> = keys[..70].iter().chain(new_keys.iter()).cloned().collect();
+    expected.sort();
+    let changed_key = |(old, new): &(Option<LeafNode<ValueBlob>>, Option<LeafNode<ValueBlob>>)| {
+        old.as_ref().or(new.as_ref()).unwrap().account_key()
+    };
+
+    let changes = tree
+        .get_changed_leaves(0, 1, HashValue::zero(), usize::MAX)
+        .unwrap();
+    assert_eq!(
+        changes.iter().map(changed_key).collect::<Vec<_>>(),
+        expected
+    );
+    for change in &changes {
+        let key = changed_key(change);
+        let (old, new) = change;
+        assert_eq!(old.is_some(), keys.contains(&key));
+        assert_eq!(new.is_some(), !keys[50..70].contains(&key));
+    }
+
+    // Page through the changes.
+    let mut paged = vec![];
+    let mut starting_key = HashValue::zero();
+    loop {
+        let page = tree.get_changed_leaves(0, 1, starting_key, 7).unwrap();
+        assert!(page.len() <= 7);
+        paged.extend(page.iter().map(changed_key));
+        if page.len() < 7 {
+            break;
+        }
+        starting_key = plus_one(*paged.last().unwrap());
+    }
+    assert_eq!(paged, expected);
+
+    // Identical trees have no changes.
+    assert!(tree
+        .get_changed_leaves(1, 1, HashValue::zero(), usize::MAX)
+        .unwrap()
+        .is_empty());
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -786,6 +863,11 @@ proptest! {
         test_get_with_proof((existent_kvs, nonexistent_keys))
     }
 
+    #[test]
+    fn proptest_get_with_batch_proof((existent_kvs, nonexistent_keys) in arb_existent_kvs_and_nonexistent_keys::<ValueBlob>(1000, 100)) {
+        test_get_with_batch_proof((existent_kvs, nonexistent_keys))
+    }
+
     #[test]
     fn proptest_get_with_proof_with_distinct_last_nibble((kv1, kv2) in arb_kv_pair_with_distinct_last_nibble::<ValueBlob>()) {
         test_get_with_proof_with_distinct_last_nibble((kv1, kv2))
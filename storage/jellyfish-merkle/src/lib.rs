@@ -87,7 +87,9 @@ use aptos_crypto::{
 };
 use aptos_types::{
     nibble::{nibble_path::NibblePath, Nibble, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        SparseMerkleBatchProof, SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof,
+    },
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::Version,
 };
@@ -797,6 +799,25 @@ where
             .map(|(value, proof_ext)| (value, proof_ext.into()))
     }
 
+    /// Returns the value of each key, which must be in ascending order, along with a proof
+    /// authenticating all of them.
+    pub fn get_with_batch_proof(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<(
+        Vec<Option<(HashValue, (K, Version))>>,
+        SparseMerkleBatchProof,
+    )> {
+        let (values, proofs): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .map(|key| self.get_with_proof(*key, version))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        Ok((values, SparseMerkleBatchProof::from_proofs(keys, &proofs)?))
+    }
+
     pub fn get_with_proof_ext(
         &self,
         key: HashValue,
@@ -891,6 +912,155 @@ where
         Ok(SparseMerkleRangeProof::new(siblings))
     }
 
+    /// Returns the leaves whose values differ between the trees at `old_version` and
+    /// `new_version`, ordered by key and starting from `starting_key`, with at most `limit`
+    /// entries. Each entry holds the leaf of the old tree and the leaf of the new tree, either of
+    /// which is `None` if the key doesn't exist at that version.
+    ///
+    /// Subtrees with the same hash in both trees are skipped, so the cost is proportional to the
+    /// number of changes rather than the size of the trees.
+    pub fn get_changed_leaves(
+        &self,
+        old_version: Version,
+        new_version: Version,
+        starting_key: HashValue,
+        limit: usize,
+    ) -> Result<Vec<(Option<LeafNode<K>>, Option<LeafNode<K>>)>> {
+        let old_root_key = NodeKey::new_empty_path(old_version);
+        let old_root = self
+            .reader
+            .get_node_option(&old_root_key)?
+            .ok_or(MissingRootError {
+                version: old_version,
+            })?;
+        let new_root_key = NodeKey::new_empty_path(new_version);
+        let new_root = self
+            .reader
+            .get_node_option(&new_root_key)?
+            .ok_or(MissingRootError {
+                version: new_version,
+            })?;
+
+        let mut changes = vec![];
+        self.get_changed_leaves_impl(
+            Some((old_root_key, old_root)),
+            Some((new_root_key, new_root)),
+            0,
+            Some(starting_key),
+            limit,
+            &mut changes,
+        )?;
+        Ok(changes)
+    }
+
+    /// Diffs two subtrees at the same position. `starting_key` is only set while the position is
+    /// on the path to the starting key, since everything to the right of it is included.
+    fn get_changed_leaves_impl(
+        &self,
+        old: Option<(NodeKey, Node<K>)>,
+        new: Option<(NodeKey, Node<K>)>,
+        nibble_depth: usize,
+        starting_key: Option<HashValue>,
+        limit: usize,
+        changes: &mut Vec<(Option<LeafNode<K>>, Option<LeafNode<K>>)>,
+    ) -> Result<()> {
+        ensure!(
+            nibble_depth <= ROOT_NIBBLE_HEIGHT,
+            "Jellyfish Merkle tree has cyclic graph inside."
+        );
+        if changes.len() >= limit {
+            return Ok(());
+        }
+
+        match (old, new) {
+            (
+                Some((old_node_key, Node::Internal(old_node))),
+                Some((new_node_key, Node::Internal(new_node))),
+            ) => {
+                let first_nibble =
+                    starting_key.map_or(0, |key| u8::from(key.get_nibble(nibble_depth)));
+                for index in first_nibble..16 {
+                    let nibble = Nibble::from(index);
+                    let (old_child, new_child) = (old_node.child(nibble), new_node.child(nibble));
+                    if let (Some(old_child), Some(new_child)) = (old_child, new_child) {
+                        if old_child.hash == new_child.hash {
+                            continue;
+                        }
+                    }
+                    let old_child = self.get_child_node(&old_node_key, nibble, old_child)?;
+                    let new_child = self.get_child_node(&new_node_key, nibble, new_child)?;
+                    self.get_changed_leaves_impl(
+                        old_child,
+                        new_child,
+                        nibble_depth + 1,
+                        starting_key.filter(|_| index == first_nibble),
+                        limit,
+                        changes,
+                    )?;
+                    if changes.len() >= limit {
+                        break;
+                    }
+                }
+            }
+            (old, new) => {
+                // One side is a leaf or empty, so compare the leaves one by one.
+                let mut leaves: BTreeMap<HashValue, (Option<LeafNode<K>>, Option<LeafNode<K>>)> =
+                    BTreeMap::new();
+                for leaf in self.get_all_leaves(old)? {
+                    leaves.entry(leaf.account_key()).or_default().0 = Some(leaf);
+                }
+                for leaf in self.get_all_leaves(new)? {
+                    leaves.entry(leaf.account_key()).or_default().1 = Some(leaf);
+                }
+                for (key, (old_leaf, new_leaf)) in leaves {
+                    if starting_key.map_or(false, |starting_key| key < starting_key)
+                        || old_leaf.as_ref().map(LeafNode::value_hash)
+                            == new_leaf.as_ref().map(LeafNode::value_hash)
+                    {
+                        continue;
+                    }
+                    changes.push((old_leaf, new_leaf));
+                    if changes.len() >= limit {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_child_node(
+        &self,
+        node_key: &NodeKey,
+        nibble: Nibble,
+        child: Option<&Child>,
+    ) -> Result<Option<(NodeKey, Node<K>)>> {
+        child
+            .map(|child| {
+                let child_node_key = node_key.gen_child_node_key(child.version, nibble);
+                let child_node = self.reader.get_node(&child_node_key)?;
+                Ok((child_node_key, child_node))
+            })
+            .transpose()
+    }
+
+    fn get_all_leaves(&self, node: Option<(NodeKey, Node<K>)>) -> Result<Vec<LeafNode<K>>> {
+        let mut leaves = vec![];
+        let mut stack: Vec<_> = node.into_iter().collect();
+        while let Some((node_key, node)) = stack.pop() {
+            match node {
+                Node::Internal(internal_node) => {
+                    for (nibble, child) in internal_node.children_sorted() {
+                        stack.extend(self.get_child_node(&node_key, *nibble, Some(child))?);
+                    }
+                }
+                Node::Leaf(leaf_node) => leaves.push(leaf_node),
+                Node::Null => {}
+            }
+        }
+        Ok(leaves)
+    }
+
     #[cfg(test)]
     pub fn get(&self, key: HashValue, version: Version) -> Result<Option<HashValue>> {
         Ok(self.get_with_proof(key, version)?.0.map(|x| x.0))
@@ -18,7 +18,7 @@ use proptest::{
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ops::Bound,
 };
 use storage_interface::jmt_update_refs;
@@ -136,6 +136,45 @@ pub fn test_get_with_proof<V: TestKey>(
     test_nonexistent_keys_impl(&tree, version, &nonexistent_keys);
 }
 
+pub fn test_get_with_batch_proof<V: TestKey>(
+    (existent_kvs, nonexistent_keys): (HashMap<HashValue, (HashValue, V)>, Vec<HashValue>),
+) {
+    let (db, version) = init_mock_db(&existent_kvs);
+    let tree = JellyfishMerkleTree::new(&db);
+    let root_hash = tree.get_root_hash(version).unwrap();
+
+    let keys: Vec<_> = existent_kvs
+        .keys()
+        .chain(&nonexistent_keys)
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let (values, proof) = tree.get_with_batch_proof(&keys, version).unwrap();
+    let elements: Vec<_> = keys
+        .iter()
+        .zip(&values)
+        .map(|(key, value)| (*key, value.as_ref().map(|v| v.0)))
+        .collect();
+    assert!(proof.verify_by_hash(root_hash, &elements).is_ok());
+    for (key, value) in keys.iter().zip(values) {
+        assert_eq!(value.map(|v| (v.0, v.1 .0)), existent_kvs.get(key).cloned());
+    }
+
+    // The batch proof is never larger than the individual proofs of the same keys.
+    let individual_siblings: usize = keys
+        .iter()
+        .map(|key| {
+            tree.get_with_proof(*key, version)
+                .unwrap()
+                .1
+                .siblings()
+                .len()
+        })
+        .sum();
+    assert!(proof.siblings().len() <= individual_siblings);
+}
+
 pub fn arb_kv_pair_with_distinct_last_nibble<V: TestKey>(
 ) -> impl Strategy<Value = ((HashValue, (HashValue, V)), (HashValue, (HashValue, V)))> {
     (
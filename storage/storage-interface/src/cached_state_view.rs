@@ -83,6 +83,7 @@ pub struct CachedStateView {
     /// in JMT node.
     state_cache: RwLock<HashMap<StateKey, Option<StateValue>>>,
     proof_fetcher: Arc<dyn ProofFetcher>,
+    reader: Arc<dyn DbReader>,
 }
 
 impl CachedStateView {
@@ -108,6 +109,7 @@ impl CachedStateView {
             speculative_state,
             state_cache: RwLock::new(HashMap::new()),
             proof_fetcher,
+            reader,
         })
     }
 
@@ -168,13 +170,18 @@ impl CachedStateView {
                                 )
                                 })?;
                         }
-                        value
+                        match value {
+                            Some(value) => Some(value),
+                            // A key absent from both the speculative and the persisted state
+                            // may still have a value in the chain this DB forks. Keys deleted
+                            // speculatively are `DoesNotExist` above and never get here.
+                            None => self.reader.get_forked_state_value(state_key)?,
+                        }
                     }
                     None => None,
                 }
             }
         };
-
         Ok(state_value_option)
     }
 }
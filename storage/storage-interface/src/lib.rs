@@ -18,20 +18,21 @@ use aptos_types::{
     move_resource::MoveStorage,
     on_chain_config::{access_path_for_config, ConfigID},
     proof::{
-        AccumulatorConsistencyProof, SparseMerkleProof, SparseMerkleProofExt,
-        SparseMerkleRangeProof, TransactionAccumulatorSummary,
+        AccumulatorConsistencyProof, SparseMerkleBatchProof, SparseMerkleProof,
+        SparseMerkleProofExt, SparseMerkleRangeProof, TransactionAccumulatorSummary,
     },
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
         state_key_prefix::StateKeyPrefix,
-        state_value::{StateValue, StateValueChunkWithProof},
+        state_value::{StateValue, StateValueChange, StateValueChunkWithProof},
     },
     transaction::{
         AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
 };
+use move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -69,6 +70,9 @@ pub enum Error {
 
     #[error("Serialization error: {0}")]
     SerializationError(String),
+
+    #[error("Version range too long to compare without state snapshots: {0} versions, max is {1}")]
+    VersionRangeTooLong(u64, u64),
 }
 
 impl From<anyhow::Error> for Error {
@@ -206,6 +210,20 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// See [AptosDB::get_events_by_type].
+    ///
+    /// [AptosDB::get_events_by_type]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_events_by_type
+    fn get_events_by_type(
+        &self,
+        event_type: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_block_timestamp].
     ///
     /// [AptosDB::get_block_timestamp]:
@@ -386,6 +404,35 @@ pub trait DbReader: Send + Sync {
             .map(|(value, proof_ext)| (value, proof_ext.into()))
     }
 
+    /// Gets the state values of several state keys along with a single proof of all of them, out
+    /// of the ledger state indicated by the state Merkle tree root at `version`. The values are
+    /// ordered by the hash of their state key, which is the order the proof verifies them in.
+    /// See [AptosDB::get_state_values_with_batch_proof_by_version].
+    ///
+    /// [AptosDB::get_state_values_with_batch_proof_by_version]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_state_values_with_batch_proof_by_version
+    fn get_state_values_with_batch_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<(StateKey, Option<StateValue>)>, SparseMerkleBatchProof)> {
+        unimplemented!()
+    }
+
+    /// See [AptosDB::get_state_value_changes].
+    ///
+    /// [AptosDB::get_state_value_changes]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_state_value_changes
+    fn get_state_value_changes(
+        &self,
+        from_version: Version,
+        to_version: Version,
+        start: Option<&StateKey>,
+        limit: u64,
+    ) -> Result<(Vec<StateValueChange>, Option<StateKey>)> {
+        unimplemented!()
+    }
+
     /// Gets the latest ExecutedTrees no matter if db has been bootstrapped.
     /// Used by the Db-bootstrapper.
     fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
@@ -492,6 +539,13 @@ pub trait DbReader: Send + Sync {
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         unimplemented!()
     }
+
+    /// Returns the value of a state key which doesn't exist locally, for a DB which forks the
+    /// state of another chain. State views fall back to it for keys that were never written
+    /// locally. Not part of the local state tree, so it comes without a proof.
+    fn get_forked_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        Ok(None)
+    }
 }
 
 impl MoveStorage for &dyn DbReader {
@@ -16,9 +16,11 @@ pub struct DbStateView {
 impl DbStateView {
     fn get(&self, key: &StateKey) -> Result<Option<Vec<u8>>> {
         Ok(if let Some(version) = self.version {
-            self.db
-                .get_state_value_by_version(key, version)?
-                .map(|value| value.into_bytes())
+            match self.db.get_state_value_by_version(key, version)? {
+                Some(value) => Some(value),
+                None => self.db.get_forked_state_value(key)?,
+            }
+            .map(|value| value.into_bytes())
         } else {
             None
         })
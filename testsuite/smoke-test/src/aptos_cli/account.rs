@@ -3,9 +3,10 @@
 
 use crate::smoke_test_environment::SwarmBuilder;
 use aptos::account::create::DEFAULT_FUNDED_COINS;
-use aptos::common::types::GasOptions;
+use aptos::common::types::{GasOptions, SubmitResult};
 use aptos_crypto::{PrivateKey, ValidCryptoMaterialStringExt};
 use aptos_keygen::KeyGen;
+use aptos_temppath::TempPath;
 
 #[tokio::test]
 async fn test_account_flow() {
@@ -131,3 +132,47 @@ async fn test_account_key_rotation() {
         .await
         .expect("New key should be able to transfer");
 }
+
+#[tokio::test]
+async fn test_offline_signing() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let unsigned_file = dir.path().join("transfer.unsigned");
+    let signed_file = dir.path().join("transfer.signed");
+
+    let transfer_amount = 100;
+    let unsigned_summary = match cli
+        .transfer_coins_unsigned(0, 1, transfer_amount, unsigned_file.clone())
+        .await
+        .unwrap()
+    {
+        SubmitResult::Unsigned(summary) => summary,
+        _ => panic!("Transaction should have been written unsigned"),
+    };
+    assert_eq!(cli.account_id(0), unsigned_summary.sender);
+    // Nothing should have been submitted
+    cli.assert_account_balance_now(1, DEFAULT_FUNDED_COINS)
+        .await;
+
+    let signed_summary = cli
+        .sign_transaction(0, unsigned_file, signed_file.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        Some(unsigned_summary.sequence_number),
+        signed_summary.sequence_number
+    );
+
+    let submitted_summary = cli.submit_transaction(signed_file).await.unwrap();
+    assert_eq!(
+        signed_summary.transaction_hash,
+        submitted_summary.transaction_hash
+    );
+    assert_eq!(Some(true), submitted_summary.success);
+    cli.assert_account_balance_now(1, DEFAULT_FUNDED_COINS + transfer_amount)
+        .await;
+}
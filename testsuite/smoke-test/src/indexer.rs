@@ -2,23 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_indexer::{
-    database::{new_db_pool, PgDbPool, PgPoolConnection},
+    database::{new_db_pool, DbConnection, DbPool},
     models::transactions::TransactionQuery,
 };
 use aptos_sdk::types::LocalAccount;
 use cached_packages::aptos_stdlib::aptos_token_stdlib;
-use diesel::RunQueryDsl;
+use diesel::connection::SimpleConnection;
 use forge::{AptosPublicInfo, Result, Swarm};
 use std::sync::Arc;
 
-pub fn wipe_database(conn: &mut PgPoolConnection) {
+pub fn wipe_database(conn: &mut DbConnection) {
     for command in [
         "DROP SCHEMA public CASCADE",
         "CREATE SCHEMA public",
         "GRANT ALL ON SCHEMA public TO postgres",
         "GRANT ALL ON SCHEMA public TO public",
     ] {
-        diesel::sql_query(command).execute(conn).unwrap();
+        conn.batch_execute(command).unwrap();
     }
 }
 
@@ -26,7 +26,7 @@ pub fn get_database_url() -> String {
     std::env::var("INDEXER_DATABASE_URL").expect("must set 'INDEXER_DATABASE_URL' to run tests!")
 }
 
-pub fn setup_indexer() -> anyhow::Result<PgDbPool> {
+pub fn setup_indexer() -> anyhow::Result<DbPool> {
     let conn_pool = new_db_pool(get_database_url().as_str())?;
     wipe_database(&mut conn_pool.get()?);
     Ok(conn_pool)
@@ -15,12 +15,15 @@ use aptos_rest_client::aptos_api_types::{TransactionOnChainData, UserTransaction
 use aptos_rest_client::{Response, Transaction};
 use aptos_rosetta::common::BlockHash;
 use aptos_rosetta::types::{
-    AccountIdentifier, BlockResponse, Operation, OperationStatusType, OperationType,
-    TransactionType, STAKING_CONTRACT_MODULE, SWITCH_OPERATOR_WITH_SAME_COMMISSION_FUNCTION,
+    AccountIdentifier, BlockEvent, BlockEventType, BlockResponse, BlockTransactionRequest,
+    ConstructionSubmitRequest, EventsBlocksRequest, MempoolRequest, MempoolTransactionRequest,
+    Operation, OperationStatusType, OperationType, SearchTransactionsRequest,
+    TransactionIdentifier, TransactionType, STAKING_CONTRACT_MODULE,
+    SWITCH_OPERATOR_WITH_SAME_COMMISSION_FUNCTION,
 };
 use aptos_rosetta::{
     client::RosettaClient,
-    common::{native_coin, BLOCKCHAIN, Y2K_MS},
+    common::{encode_bcs, native_coin, BLOCKCHAIN, Y2K_MS},
     types::{
         AccountBalanceRequest, AccountBalanceResponse, BlockIdentifier, BlockRequest,
         NetworkIdentifier, NetworkRequest, PartialBlockIdentifier,
@@ -660,6 +663,224 @@ async fn test_transfer() {
     */
 }
 
+/// Tests looking up single transactions, pending or committed, and the block events
+#[tokio::test]
+async fn test_transaction_lookups() {
+    let (mut swarm, cli, _faucet, rosetta_client) = setup_test(1, 1).await;
+    let chain_id = swarm.chain_id();
+    let transaction_factory = swarm.aptos_public_info().transaction_factory();
+    let validator = swarm.validators().next().unwrap();
+    let rest_client = validator.rest_client();
+    let network = NetworkIdentifier::from(chain_id);
+    let sender = cli.account_id(0);
+    let sender_key = cli.private_key(0);
+    let receiver = AccountAddress::from_hex_literal("0xBEEF").unwrap();
+
+    // Wait until the Rosetta service is ready
+    let request = NetworkRequest {
+        network_identifier: network.clone(),
+    };
+    loop {
+        let status = try_until_ok_default(|| rosetta_client.network_status(&request))
+            .await
+            .unwrap();
+        if status.current_block_identifier.index >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // Nothing has been submitted through Rosetta yet
+    let mempool_request = MempoolRequest {
+        network_identifier: network.clone(),
+    };
+    assert!(rosetta_client
+        .mempool(&mempool_request)
+        .await
+        .unwrap()
+        .transaction_identifiers
+        .is_empty());
+
+    // A transaction after a sequence number gap stays in mempool until the gap is filled
+    let sequence_number = rest_client
+        .get_account(sender)
+        .await
+        .unwrap()
+        .into_inner()
+        .sequence_number;
+    let parked_txn = transaction_factory
+        .payload(aptos_stdlib::aptos_account_transfer(receiver, 100))
+        .sender(sender)
+        .sequence_number(sequence_number + 1)
+        .build()
+        .sign(sender_key, sender_key.public_key())
+        .unwrap()
+        .into_inner();
+    let parked_hash = rosetta_client
+        .submit(&ConstructionSubmitRequest {
+            network_identifier: network.clone(),
+            signed_transaction: encode_bcs(&parked_txn).unwrap(),
+        })
+        .await
+        .unwrap()
+        .transaction_identifier;
+
+    assert_eq!(
+        rosetta_client
+            .mempool(&mempool_request)
+            .await
+            .unwrap()
+            .transaction_identifiers,
+        vec![parked_hash.clone()]
+    );
+    let mempool_transaction_request = MempoolTransactionRequest {
+        network_identifier: network.clone(),
+        transaction_identifier: parked_hash.clone(),
+    };
+    let response = rosetta_client
+        .mempool_transaction(&mempool_transaction_request)
+        .await
+        .unwrap();
+    assert_eq!(response.transaction.transaction_identifier, parked_hash);
+
+    // Pending transactions aren't searchable until they're committed
+    let search_by_hash = SearchTransactionsRequest::by_hash(chain_id, parked_hash.clone());
+    let response = rosetta_client
+        .search_transactions(&search_by_hash)
+        .await
+        .unwrap();
+    assert!(response.transactions.is_empty());
+    assert_eq!(response.total_count, 0);
+
+    // Filling the gap commits both transactions
+    let filler_txn = transfer_and_wait(
+        &rosetta_client,
+        &rest_client,
+        &network,
+        sender_key,
+        receiver,
+        100,
+        Duration::from_secs(60),
+        Some(sequence_number),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let parked_txn = wait_for_transaction(
+        &rest_client,
+        Duration::from_secs(parked_txn.expiration_timestamp_secs()),
+        parked_hash.hash.clone(),
+    )
+    .await
+    .unwrap();
+
+    // Committed transactions leave mempool
+    assert!(rosetta_client
+        .mempool(&mempool_request)
+        .await
+        .unwrap()
+        .transaction_identifiers
+        .is_empty());
+    rosetta_client
+        .mempool_transaction(&mempool_transaction_request)
+        .await
+        .expect_err("Committed transactions aren't in mempool");
+
+    // The transaction can be retrieved from its block, and only from its block
+    let block_height = rest_client
+        .get_block_by_version_bcs(parked_txn.info.version.0, false)
+        .await
+        .unwrap()
+        .into_inner()
+        .block_height;
+    let block_identifier = BlockIdentifier {
+        index: block_height,
+        hash: BlockHash::new(chain_id, block_height).to_string(),
+    };
+    let response = rosetta_client
+        .block_transaction(&BlockTransactionRequest {
+            network_identifier: network.clone(),
+            block_identifier: block_identifier.clone(),
+            transaction_identifier: parked_hash.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.transaction.transaction_identifier, parked_hash);
+    rosetta_client
+        .block_transaction(&BlockTransactionRequest {
+            network_identifier: network.clone(),
+            block_identifier: BlockIdentifier {
+                index: block_height + 1,
+                hash: BlockHash::new(chain_id, block_height + 1).to_string(),
+            },
+            transaction_identifier: parked_hash.clone(),
+        })
+        .await
+        .expect_err("Transaction isn't in the next block");
+    rosetta_client
+        .block_transaction(&BlockTransactionRequest {
+            network_identifier: network.clone(),
+            block_identifier: BlockIdentifier {
+                index: block_height,
+                hash: BlockHash::new(chain_id, block_height + 1).to_string(),
+            },
+            transaction_identifier: parked_hash.clone(),
+        })
+        .await
+        .expect_err("Block hash doesn't match the block index");
+
+    // Search by hash returns the transaction and its block
+    let response = rosetta_client
+        .search_transactions(&search_by_hash)
+        .await
+        .unwrap();
+    assert_eq!(response.total_count, 1);
+    assert_eq!(response.transactions.len(), 1);
+    assert_eq!(response.transactions[0].block_identifier, block_identifier);
+    assert_eq!(
+        response.transactions[0].transaction.transaction_identifier,
+        parked_hash
+    );
+
+    // Search by account pages through the sender's transactions by sequence number
+    let response = rosetta_client
+        .search_transactions(&SearchTransactionsRequest::by_account(
+            chain_id,
+            AccountIdentifier::base_account(sender),
+            Some(sequence_number),
+            Some(1),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.total_count, sequence_number + 2);
+    assert_eq!(response.next_offset, Some(sequence_number + 1));
+    assert_eq!(response.transactions.len(), 1);
+    assert_eq!(
+        response.transactions[0].transaction.transaction_identifier,
+        TransactionIdentifier::from(filler_txn.info.hash.0)
+    );
+
+    // Every block has a single block added event
+    let response = rosetta_client
+        .events_blocks(&EventsBlocksRequest {
+            network_identifier: network.clone(),
+            offset: Some(block_height),
+            limit: Some(1),
+        })
+        .await
+        .unwrap();
+    assert!(response.max_sequence >= block_height);
+    assert_eq!(
+        response.events,
+        vec![BlockEvent {
+            sequence: block_height,
+            block_identifier,
+            event_type: BlockEventType::BlockAdded,
+        }]
+    );
+}
+
 /// This test tests all of Rosetta's functionality from the read side in one go.  Since
 /// it's block based and it needs time to run, we do all the checks in a single test.
 #[tokio::test]
@@ -1044,7 +1265,7 @@ async fn parse_block_transactions(
 ) {
     let mut txn_hashes = HashSet::new();
     for transaction in block.transactions.iter() {
-        let txn_metadata = &transaction.metadata;
+        let txn_metadata = transaction.metadata.as_ref().unwrap();
         let txn_version = txn_metadata.version.0;
         let cur_version = *current_version;
         assert!(
@@ -1469,7 +1690,7 @@ async fn parse_operations(
 
     assert!(
         has_gas_op
-            || transaction.metadata.transaction_type == TransactionType::Genesis
+            || transaction.metadata.as_ref().unwrap().transaction_type == TransactionType::Genesis
             || transaction.operations.is_empty(),
         "Must have a gas operation at least in a transaction except for Genesis",
     );
@@ -1578,7 +1799,7 @@ async fn test_invalid_transaction_gas_charged() {
     let rosetta_txn = block_with_transfer
         .transactions
         .iter()
-        .find(|txn| txn.metadata.version.0 == txn_version)
+        .find(|txn| txn.metadata.as_ref().unwrap().version.0 == txn_version)
         .unwrap();
 
     assert_failed_transfer_transaction(
@@ -1603,7 +1824,7 @@ fn assert_failed_transfer_transaction(
         rosetta_txn.transaction_identifier.hash
     );
 
-    let rosetta_txn_metadata = &rosetta_txn.metadata;
+    let rosetta_txn_metadata = rosetta_txn.metadata.as_ref().unwrap();
     assert_eq!(TransactionType::User, rosetta_txn_metadata.transaction_type);
     assert_eq!(actual_txn.info.version.0, rosetta_txn_metadata.version.0);
     // This should have 3, the deposit, withdraw, and fee
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::smoke_test_environment::SwarmBuilder;
+use crate::smoke_test_environment::{new_local_swarm_with_aptos, SwarmBuilder};
 use crate::{
     test_utils::{
         assert_balance, create_and_fund_account, swarm_utils::insert_waypoint,
@@ -11,17 +11,25 @@ use crate::{
     workspace_builder::workspace_root,
 };
 use anyhow::{bail, Result};
+use aptos_config::config::ForkConfig;
 use aptos_logger::info;
 use aptos_temppath::TempPath;
-use aptos_types::{transaction::Version, waypoint::Waypoint};
+use aptos_types::{
+    access_path::AccessPath, account_address::AccountAddress, account_config::CoinStoreResource,
+    state_store::state_key::StateKey, transaction::Version, waypoint::Waypoint,
+};
+use aptos_validator_interface::ForkedDb;
+use aptosdb::AptosDB;
 use backup_cli::metadata::view::BackupStorageState;
 use forge::{reconfig, NodeExt, Swarm, SwarmExt};
+use move_core_types::move_resource::MoveStructType;
 use std::{
     fs,
     path::Path,
     process::Command,
     time::{Duration, Instant},
 };
+use storage_interface::{DbReader, DbReaderWriter};
 
 const MAX_WAIT_SECS: u64 = 180;
 
@@ -344,3 +352,58 @@ pub(crate) fn db_restore(backup_path: &Path, db_path: &Path, trusted_waypoints:
     }
     info!("Backup restored in {} seconds.", now.elapsed().as_secs());
 }
+
+#[tokio::test]
+async fn test_forked_db_reads_remote_state() {
+    let mut swarm = new_local_swarm_with_aptos(1).await;
+    let mut info = swarm.aptos_public_info();
+    let account = info.random_account();
+    info.create_user_account(account.public_key())
+        .await
+        .unwrap();
+    info.mint(account.address(), 1_000_000).await.unwrap();
+    let version = info
+        .client()
+        .get_ledger_information()
+        .await
+        .unwrap()
+        .into_inner()
+        .version;
+
+    // The local swarm is the forked chain
+    let db_dir = TempPath::new();
+    db_dir.create_as_dir().unwrap();
+    let forked_db = ForkedDb::new(
+        DbReaderWriter::new(AptosDB::new_for_test(&db_dir)),
+        &ForkConfig {
+            url: info.url().parse().unwrap(),
+            version,
+        },
+        db_dir.path(),
+    )
+    .unwrap();
+
+    // State which was never written locally is read from the forked chain, at the forked version
+    info.mint(account.address(), 1_000_000).await.unwrap();
+    let coin_store_key = |address| {
+        StateKey::AccessPath(AccessPath::new(
+            address,
+            AccessPath::resource_path_vec(CoinStoreResource::struct_tag()),
+        ))
+    };
+    let coin_store: CoinStoreResource = bcs::from_bytes(
+        forked_db
+            .get_forked_state_value(&coin_store_key(account.address()))
+            .unwrap()
+            .unwrap()
+            .bytes(),
+    )
+    .unwrap();
+    assert_eq!(coin_store.coin(), 1_000_000);
+
+    // State which doesn't exist on the forked chain either has no value
+    assert!(forked_db
+        .get_forked_state_value(&coin_store_key(AccountAddress::random()))
+        .unwrap()
+        .is_none());
+}
@@ -769,6 +769,269 @@ impl SparseMerkleRangeProof {
     }
 }
 
+/// A proof that can be used to authenticate many elements at once in a Sparse Merkle Tree given
+/// trusted root hash. It is equivalent to one `SparseMerkleProof` per key, except that siblings
+/// shared by the paths of several keys, or computable from the other keys, are only included once
+/// or not at all.
+///
+/// The keys are proven in ascending order. For example, given the following sparse Merkle tree:
+///
+/// ```text
+///                   root
+///                  /     \
+///                 /       \
+///                /         \
+///               o           o
+///              / \         / \
+///             a   o       o   h
+///                / \     / \
+///               o   d   e   X
+///              / \         / \
+///             b   c       f   g
+/// ```
+///
+/// proving `b` and `e` needs the siblings `a`, `c`, `d`, `X` and `h`, in this order, instead of
+/// the seven siblings of the two individual proofs.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SparseMerkleBatchProof {
+    /// For each key, the leaf at the end of its path if there is one, along with the depth of that
+    /// end, i.e. the number of siblings the individual proof of the key would have.
+    paths: Vec<(Option<SparseMerkleLeafNode>, u16)>,
+    /// The siblings which can't be computed from the paths, in the order a depth first walk from
+    /// the left of the tree reaches them.
+    siblings: Vec<HashValue>,
+}
+
+impl SparseMerkleBatchProof {
+    /// Constructs a new `SparseMerkleBatchProof` using the paths of the keys and the siblings.
+    pub fn new(paths: Vec<(Option<SparseMerkleLeafNode>, u16)>, siblings: Vec<HashValue>) -> Self {
+        Self { paths, siblings }
+    }
+
+    /// Combines the individual proofs of `keys`, which must be in ascending order and proven
+    /// against the same root hash.
+    pub fn from_proofs(keys: &[HashValue], proofs: &[SparseMerkleProof]) -> Result<Self> {
+        ensure!(
+            keys.len() == proofs.len(),
+            "The number of keys ({}) does not match the number of proofs ({}).",
+            keys.len(),
+            proofs.len(),
+        );
+        ensure_strictly_ascending(keys)?;
+
+        let mut siblings = vec![];
+        if !keys.is_empty() {
+            Self::collect_siblings(keys, proofs, 0, &mut siblings)?;
+        }
+        Ok(Self {
+            paths: proofs
+                .iter()
+                .map(|proof| (proof.leaf(), proof.siblings().len() as u16))
+                .collect(),
+            siblings,
+        })
+    }
+
+    fn collect_siblings(
+        keys: &[HashValue],
+        proofs: &[SparseMerkleProof],
+        depth: usize,
+        siblings: &mut Vec<HashValue>,
+    ) -> Result<()> {
+        if proofs.iter().all(|proof| proof.siblings().len() == depth) {
+            return Ok(());
+        }
+        ensure!(
+            proofs.iter().all(|proof| proof.siblings().len() > depth),
+            "Proofs of keys in the same subtree at depth {} end at different depths.",
+            depth,
+        );
+        // The sibling at `depth` of a key in one half of the subtree is the root of the other half.
+        let sibling_at_depth =
+            |proof: &SparseMerkleProof| proof.siblings()[proof.siblings().len() - 1 - depth];
+        let split = keys.partition_point(|key| !key.bit(depth));
+        if split == 0 {
+            siblings.push(sibling_at_depth(&proofs[0]));
+        } else {
+            Self::collect_siblings(&keys[..split], &proofs[..split], depth + 1, siblings)?;
+        }
+        if split == keys.len() {
+            siblings.push(sibling_at_depth(&proofs[0]));
+        } else {
+            Self::collect_siblings(&keys[split..], &proofs[split..], depth + 1, siblings)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the leaf and depth at the end of the path of each key.
+    pub fn paths(&self) -> &[(Option<SparseMerkleLeafNode>, u16)] {
+        &self.paths
+    }
+
+    /// Returns the list of siblings in this proof.
+    pub fn siblings(&self) -> &[HashValue] {
+        &self.siblings
+    }
+
+    pub fn verify<V: CryptoHash>(
+        &self,
+        expected_root_hash: HashValue,
+        elements: &[(HashValue, Option<&V>)],
+    ) -> Result<()> {
+        let elements: Vec<_> = elements
+            .iter()
+            .map(|(key, value)| (*key, value.map(|v| v.hash())))
+            .collect();
+        self.verify_by_hash(expected_root_hash, &elements)
+    }
+
+    /// Verifies every element like `SparseMerkleProof::verify_by_hash` would: elements with a hash
+    /// exist in the tree with that value hash, and elements without one don't exist. Elements
+    /// must be in ascending order of their keys.
+    pub fn verify_by_hash(
+        &self,
+        expected_root_hash: HashValue,
+        elements: &[(HashValue, Option<HashValue>)],
+    ) -> Result<()> {
+        ensure!(
+            !elements.is_empty(),
+            "Sparse Merkle Tree batch proof must prove at least one element."
+        );
+        ensure!(
+            elements.len() == self.paths.len(),
+            "The number of elements ({}) does not match the number of paths in proof ({}).",
+            elements.len(),
+            self.paths.len(),
+        );
+        let keys: Vec<_> = elements.iter().map(|(key, _)| *key).collect();
+        ensure_strictly_ascending(&keys)?;
+
+        for ((element_key, element_hash), (leaf, depth)) in elements.iter().zip(&self.paths) {
+            // Each path is checked like the proof of a single element, with `depth` siblings.
+            let depth = *depth as usize;
+            ensure!(
+                depth <= HashValue::LENGTH_IN_BITS,
+                "Sparse Merkle Tree proof has more than {} ({}) siblings.",
+                HashValue::LENGTH_IN_BITS,
+                depth,
+            );
+            match (element_hash, leaf) {
+                (Some(hash), Some(leaf)) => {
+                    ensure!(
+                        *element_key == leaf.key,
+                        "Keys do not match. Key in proof: {:x}. Expected key: {:x}.",
+                        leaf.key,
+                        element_key,
+                    );
+                    ensure!(
+                        *hash == leaf.value_hash,
+                        "Value hashes do not match for key {:x}. Value hash in proof: {:x}. \
+                         Expected value hash: {:x}.",
+                        element_key,
+                        leaf.value_hash,
+                        hash
+                    );
+                }
+                (Some(hash), None) => {
+                    bail!(
+                        "Expected inclusion proof for key {:x}, value hash: {:x}. Found \
+                         non-inclusion proof.",
+                        element_key,
+                        hash
+                    )
+                }
+                (None, Some(leaf)) => {
+                    ensure!(
+                        *element_key != leaf.key,
+                        "Expected non-inclusion proof, but key exists in proof. Key: {:x}.",
+                        element_key,
+                    );
+                    ensure!(
+                        element_key.common_prefix_bits_len(leaf.key) >= depth,
+                        "Key would not have ended up in the subtree where the provided key in \
+                         proof is the only existing key, if it existed. So this is not a valid \
+                         non-inclusion proof. Key: {:x}. Key in proof: {:x}.",
+                        element_key,
+                        leaf.key
+                    );
+                }
+                (None, None) => {}
+            }
+        }
+
+        let mut siblings = self.siblings.iter();
+        let actual_root_hash = Self::compute_root_hash(&keys, &self.paths, 0, &mut siblings)?;
+        ensure!(
+            siblings.next().is_none(),
+            "Sparse Merkle Tree batch proof has unused siblings."
+        );
+        ensure!(
+            actual_root_hash == expected_root_hash,
+            "{}: Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            type_name::<Self>(),
+            actual_root_hash,
+            expected_root_hash,
+        );
+
+        Ok(())
+    }
+
+    /// Computes the hash of the subtree at `depth` containing `keys`, all of which share their
+    /// first `depth` bits.
+    fn compute_root_hash<'a>(
+        keys: &[HashValue],
+        paths: &[(Option<SparseMerkleLeafNode>, u16)],
+        depth: usize,
+        siblings: &mut impl Iterator<Item = &'a HashValue>,
+    ) -> Result<HashValue> {
+        if paths.iter().all(|(_, end)| *end as usize == depth) {
+            let leaf = paths[0].0;
+            ensure!(
+                paths.iter().all(|(other, _)| *other == leaf),
+                "Paths ending at the same position at depth {} have different leaves.",
+                depth,
+            );
+            return Ok(leaf.map_or(*SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash()));
+        }
+        ensure!(
+            paths.iter().all(|(_, end)| *end as usize > depth),
+            "Paths of keys in the same subtree at depth {} end at different depths.",
+            depth,
+        );
+
+        let split = keys.partition_point(|key| !key.bit(depth));
+        let left_hash = if split == 0 {
+            next_sibling(siblings, depth)?
+        } else {
+            Self::compute_root_hash(&keys[..split], &paths[..split], depth + 1, siblings)?
+        };
+        let right_hash = if split == keys.len() {
+            next_sibling(siblings, depth)?
+        } else {
+            Self::compute_root_hash(&keys[split..], &paths[split..], depth + 1, siblings)?
+        };
+        Ok(SparseMerkleInternalNode::new(left_hash, right_hash).hash())
+    }
+}
+
+fn next_sibling<'a>(
+    siblings: &mut impl Iterator<Item = &'a HashValue>,
+    depth: usize,
+) -> Result<HashValue> {
+    siblings
+        .next()
+        .copied()
+        .ok_or_else(|| format_err!("Missing sibling at depth {}.", depth))
+}
+
+fn ensure_strictly_ascending(keys: &[HashValue]) -> Result<()> {
+    ensure!(
+        keys.windows(2).all(|pair| pair[0] < pair[1]),
+        "Keys are not in strictly ascending order."
+    );
+    Ok(())
+}
+
 /// `TransactionInfo` and a `TransactionAccumulatorProof` connecting it to the ledger root.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
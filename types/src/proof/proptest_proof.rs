@@ -6,8 +6,8 @@
 
 use crate::proof::{
     definition::MAX_ACCUMULATOR_PROOF_DEPTH, AccumulatorConsistencyProof, AccumulatorProof,
-    AccumulatorRangeProof, SparseMerkleLeafNode, SparseMerkleProof, SparseMerkleRangeProof,
-    TransactionAccumulatorSummary,
+    AccumulatorRangeProof, SparseMerkleBatchProof, SparseMerkleLeafNode, SparseMerkleProof,
+    SparseMerkleRangeProof, TransactionAccumulatorSummary,
 };
 use aptos_crypto::{
     hash::{CryptoHasher, ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH},
@@ -145,6 +145,20 @@ impl Arbitrary for SparseMerkleRangeProof {
     }
 }
 
+impl Arbitrary for SparseMerkleBatchProof {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (
+            vec((any::<Option<SparseMerkleLeafNode>>(), 0..=256u16), 0..10),
+            vec(arb_sparse_merkle_sibling(), 0..=256),
+        )
+            .prop_map(|(paths, siblings)| Self::new(paths, siblings))
+            .boxed()
+    }
+}
+
 impl Arbitrary for TransactionAccumulatorSummary {
     type Parameters = ();
     type Strategy = BoxedStrategy<Self>;
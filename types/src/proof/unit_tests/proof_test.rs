@@ -10,9 +10,9 @@ use crate::{
     ledger_info::LedgerInfo,
     proof::{
         definition::MAX_ACCUMULATOR_PROOF_DEPTH, AccumulatorExtensionProof, AccumulatorRangeProof,
-        SparseMerkleInternalNode, SparseMerkleLeafNode, TestAccumulatorInternalNode,
-        TestAccumulatorProof, TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
-        TransactionInfoListWithProof, TransactionInfoWithProof,
+        SparseMerkleBatchProof, SparseMerkleInternalNode, SparseMerkleLeafNode,
+        TestAccumulatorInternalNode, TestAccumulatorProof, TransactionAccumulatorInternalNode,
+        TransactionAccumulatorProof, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
     state_store::state_value::StateValue,
     transaction::{
@@ -265,6 +265,116 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_verify_sparse_merkle_batch_proof() {
+    // Same tree as in `test_verify_three_element_sparse_merkle`.
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+    let non_existing_key1 = b"abc".test_only_hash();
+    let non_existing_key2 = b"def".test_only_hash();
+
+    let blob1 = StateValue::from(b"1".to_vec());
+    let blob2 = StateValue::from(b"2".to_vec());
+    let blob3 = StateValue::from(b"3".to_vec());
+
+    let leaf1 = SparseMerkleLeafNode::new(key1, blob1.hash());
+    let leaf2 = SparseMerkleLeafNode::new(key2, blob2.hash());
+    let leaf3 = SparseMerkleLeafNode::new(key3, blob3.hash());
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2.hash(), leaf3.hash()).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1.hash(), internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    let keys = [key1, non_existing_key1, key2, key3, non_existing_key2];
+    let proofs = [
+        SparseMerkleProof::new(
+            Some(leaf1),
+            vec![internal_b_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH],
+        ),
+        SparseMerkleProof::new(
+            Some(leaf1),
+            vec![internal_b_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH],
+        ),
+        SparseMerkleProof::new(
+            Some(leaf2),
+            vec![leaf3.hash(), leaf1.hash(), *SPARSE_MERKLE_PLACEHOLDER_HASH],
+        ),
+        SparseMerkleProof::new(
+            Some(leaf3),
+            vec![leaf2.hash(), leaf1.hash(), *SPARSE_MERKLE_PLACEHOLDER_HASH],
+        ),
+        SparseMerkleProof::new(None, vec![internal_a_hash]),
+    ];
+
+    {
+        // Every sibling can be computed from the other keys.
+        let proof = SparseMerkleBatchProof::from_proofs(&keys, &proofs).unwrap();
+        assert!(proof.siblings().is_empty());
+
+        let elements = [
+            (key1, Some(&blob1)),
+            (non_existing_key1, None),
+            (key2, Some(&blob2)),
+            (key3, Some(&blob3)),
+            (non_existing_key2, None),
+        ];
+        assert!(proof.verify(root_hash, &elements).is_ok());
+        // Trying to show that a key has another value.
+        let mut wrong_value = elements;
+        wrong_value[2] = (key2, Some(&blob1));
+        assert!(proof.verify(root_hash, &wrong_value).is_err());
+        // Trying to show that a key doesn't exist.
+        let mut wrong_existence = elements;
+        wrong_existence[3] = (key3, None);
+        assert!(proof.verify(root_hash, &wrong_existence).is_err());
+        // Elements must be in the order of their keys.
+        let mut unordered = elements;
+        unordered.swap(0, 1);
+        assert!(proof.verify(root_hash, &unordered).is_err());
+        // Every key of the proof must be verified.
+        assert!(proof.verify(root_hash, &elements[..4]).is_err());
+        assert!(proof
+            .verify(*SPARSE_MERKLE_PLACEHOLDER_HASH, &elements)
+            .is_err());
+    }
+
+    {
+        // A single key needs the same siblings as its own proof.
+        let proof = SparseMerkleBatchProof::from_proofs(&keys[2..3], &proofs[2..3]).unwrap();
+        assert_eq!(
+            proof.siblings(),
+            &[leaf1.hash(), leaf3.hash(), *SPARSE_MERKLE_PLACEHOLDER_HASH,]
+        );
+        assert!(proof.verify(root_hash, &[(key2, Some(&blob2))]).is_ok());
+    }
+
+    {
+        // Keys in both halves of the tree only share the root.
+        let proof = SparseMerkleBatchProof::from_proofs(
+            &[key1, non_existing_key2],
+            &[proofs[0].clone(), proofs[4].clone()],
+        )
+        .unwrap();
+        assert_eq!(proof.siblings(), &[internal_b_hash]);
+        assert!(proof
+            .verify(
+                root_hash,
+                &[(key1, Some(&blob1)), (non_existing_key2, None)]
+            )
+            .is_ok());
+
+        // A proof missing a sibling doesn't verify.
+        let truncated = SparseMerkleBatchProof::new(proof.paths().to_vec(), vec![]);
+        assert!(truncated
+            .verify(
+                root_hash,
+                &[(key1, Some(&blob1)), (non_existing_key2, None)]
+            )
+            .is_err());
+    }
+}
+
 #[test]
 fn test_verify_transaction() {
     //            root
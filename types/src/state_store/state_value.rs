@@ -2,7 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::transaction::Version;
-use crate::{proof::SparseMerkleRangeProof, state_store::state_key::StateKey};
+use crate::{
+    ledger_info::LedgerInfo,
+    proof::{
+        SparseMerkleBatchProof, SparseMerkleProof, SparseMerkleRangeProof, TransactionInfoWithProof,
+    },
+    state_store::state_key::StateKey,
+};
+use anyhow::Result;
 use aptos_crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
@@ -135,6 +142,115 @@ impl StateValueChunkWithProof {
     }
 }
 
+/// A state key whose value differs between two versions, along with its value at both.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+pub struct StateValueChange {
+    pub state_key: StateKey,
+    /// The value at the older version, `None` if the state key didn't exist then.
+    pub old_value: Option<StateValue>,
+    /// The value at the newer version, `None` if the state key doesn't exist anymore.
+    pub new_value: Option<StateValue>,
+}
+
+/// A state value read from the state snapshot at `version`, along with the proofs that connect it
+/// to a ledger info.
+///
+/// Note: state snapshots are not taken at every version, so `version` may be lower than the
+/// version the value was requested at.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+pub struct StateValueWithProof {
+    /// The version of the state snapshot the value is read from.
+    pub version: Version,
+    /// The state value, `None` if the state key doesn't exist at `version`.
+    pub value: Option<StateValue>,
+    /// The proof from the state root hash at `version` to the value.
+    pub proof: SparseMerkleProof,
+    /// The transaction info at `version`, which holds the state root hash, and the proof from the
+    /// ledger info to it.
+    pub transaction_info_with_proof: TransactionInfoWithProof,
+}
+
+impl StateValueWithProof {
+    pub fn new(
+        version: Version,
+        value: Option<StateValue>,
+        proof: SparseMerkleProof,
+        transaction_info_with_proof: TransactionInfoWithProof,
+    ) -> Self {
+        Self {
+            version,
+            value,
+            proof,
+            transaction_info_with_proof,
+        }
+    }
+
+    /// Verifies that `state_key` maps to `self.value` in the state snapshot at `self.version` of
+    /// the ledger represented by `ledger_info`.
+    pub fn verify(&self, ledger_info: &LedgerInfo, state_key: &StateKey) -> Result<()> {
+        self.transaction_info_with_proof
+            .verify(ledger_info, self.version)?;
+        let state_root_hash = self
+            .transaction_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        self.proof
+            .verify(state_root_hash, state_key.hash(), self.value.as_ref())
+    }
+}
+
+/// Several state values read from the state snapshot at `version`, along with a single proof
+/// connecting all of them to a ledger info.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+pub struct StateValuesWithBatchProof {
+    /// The version of the state snapshot the values are read from.
+    pub version: Version,
+    /// The state keys in ascending order of their hashes, along with their values, `None` for the
+    /// state keys which don't exist at `version`.
+    pub values: Vec<(StateKey, Option<StateValue>)>,
+    /// The proof from the state root hash at `version` to all of the values.
+    pub proof: SparseMerkleBatchProof,
+    /// The transaction info at `version`, which holds the state root hash, and the proof from the
+    /// ledger info to it.
+    pub transaction_info_with_proof: TransactionInfoWithProof,
+}
+
+impl StateValuesWithBatchProof {
+    pub fn new(
+        version: Version,
+        values: Vec<(StateKey, Option<StateValue>)>,
+        proof: SparseMerkleBatchProof,
+        transaction_info_with_proof: TransactionInfoWithProof,
+    ) -> Self {
+        Self {
+            version,
+            values,
+            proof,
+            transaction_info_with_proof,
+        }
+    }
+
+    /// Verifies that each state key maps to its value in the state snapshot at `self.version` of
+    /// the ledger represented by `ledger_info`.
+    pub fn verify(&self, ledger_info: &LedgerInfo) -> Result<()> {
+        self.transaction_info_with_proof
+            .verify(ledger_info, self.version)?;
+        let state_root_hash = self
+            .transaction_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        let elements: Vec<_> = self
+            .values
+            .iter()
+            .map(|(state_key, value)| (state_key.hash(), value.as_ref()))
+            .collect();
+        self.proof.verify(state_root_hash, &elements)
+    }
+}
+
 /// Indicates a state value becomes stale since `stale_since_version`.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
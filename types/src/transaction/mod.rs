@@ -327,6 +327,30 @@ impl RawTransaction {
         self.sender
     }
 
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    pub fn payload(&self) -> &TransactionPayload {
+        &self.payload
+    }
+
+    pub fn max_gas_amount(&self) -> u64 {
+        self.max_gas_amount
+    }
+
+    pub fn gas_unit_price(&self) -> u64 {
+        self.gas_unit_price
+    }
+
+    pub fn expiration_timestamp_secs(&self) -> u64 {
+        self.expiration_timestamp_secs
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
     /// Return the signing message for creating transaction signature.
     pub fn signing_message(&self) -> Result<Vec<u8>, CryptoMaterialError> {
         signing_message(self)
@@ -670,6 +694,12 @@ impl TransactionWithProof {
             sequence_number,
         );
 
+        self.verify(ledger_info)
+    }
+
+    /// Verifies that this transaction, and its events if present, exist at `self.version` in the
+    /// ledger represented by `ledger_info`.
+    pub fn verify(&self, ledger_info: &LedgerInfo) -> Result<()> {
         let txn_hash = self.transaction.hash();
         ensure!(
             txn_hash == self.proof.transaction_info().transaction_hash(),
@@ -691,7 +721,7 @@ impl TransactionWithProof {
             );
         }
 
-        self.proof.verify(ledger_info, version)
+        self.proof.verify(ledger_info, self.version)
     }
 }
 